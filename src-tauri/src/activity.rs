@@ -0,0 +1,234 @@
+//! Workspace activity log.
+//!
+//! Every save is recorded in `.vmark/activity.json` with a timestamp and the
+//! change in word count, so the frontend can answer "what did I work on this
+//! week" across windows and restarts. Consecutive saves of the same file are
+//! merged and old entries are dropped to keep the file small.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter};
+
+use crate::workspace;
+
+const ACTIVITY_FILE: &str = "activity.json";
+
+/// Entries older than this are dropped when the log is written
+const RETENTION_DAYS: i64 = 90;
+
+/// Hard cap on stored entries
+const MAX_ENTRIES: usize = 5000;
+
+/// Saves of the same file within this window are merged into one entry
+const MERGE_WINDOW_MS: i64 = 10 * 60 * 1000;
+
+const DAY_MS: i64 = 24 * 60 * 60 * 1000;
+
+/// Serializes read-modify-write of activity files across windows
+static ACTIVITY_LOCK: Mutex<()> = Mutex::new(());
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ActivityEntry {
+    /// Workspace-relative path of the saved file
+    pub path: String,
+    /// Unix timestamp in milliseconds of the last save in this entry
+    pub timestamp: i64,
+    /// Change in word count since the previous save
+    #[serde(rename = "wordDelta")]
+    pub word_delta: i64,
+    /// Number of saves merged into this entry
+    #[serde(default = "default_saves")]
+    pub saves: u32,
+}
+
+fn default_saves() -> u32 {
+    1
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct ActivityLog {
+    #[serde(default)]
+    entries: Vec<ActivityEntry>,
+}
+
+/// Activity for a single file, aggregated over the requested period
+#[derive(Debug, Clone, Serialize)]
+pub struct FileActivity {
+    pub path: String,
+    #[serde(rename = "absolutePath")]
+    pub absolute_path: String,
+    /// Unix timestamp in milliseconds of the most recent save
+    #[serde(rename = "lastEdited")]
+    pub last_edited: i64,
+    pub saves: u32,
+    #[serde(rename = "wordDelta")]
+    pub word_delta: i64,
+}
+
+fn activity_file_path(root: &Path) -> PathBuf {
+    root.join(".vmark").join(ACTIVITY_FILE)
+}
+
+fn read_log(root: &Path) -> ActivityLog {
+    fs::read_to_string(activity_file_path(root))
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+fn write_log(root: &Path, log: &ActivityLog) -> Result<(), String> {
+    let path = activity_file_path(root);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create .vmark directory: {e}"))?;
+    }
+    let content =
+        serde_json::to_string(log).map_err(|e| format!("Failed to serialize activity log: {e}"))?;
+    fs::write(&path, content).map_err(|e| format!("Failed to write activity log: {e}"))
+}
+
+/// Append a save to the log, merging with the latest entry for the same file
+/// and dropping expired entries.
+fn append_entry(log: &mut ActivityLog, path: String, word_delta: i64, now: i64) {
+    let recent = log
+        .entries
+        .iter_mut()
+        .rev()
+        .find(|e| e.path == path)
+        .filter(|e| now - e.timestamp < MERGE_WINDOW_MS);
+
+    match recent {
+        Some(entry) => {
+            entry.timestamp = now;
+            entry.word_delta += word_delta;
+            entry.saves += 1;
+        }
+        None => log.entries.push(ActivityEntry {
+            path,
+            timestamp: now,
+            word_delta,
+            saves: 1,
+        }),
+    }
+
+    let cutoff = now - RETENTION_DAYS * DAY_MS;
+    log.entries.retain(|e| e.timestamp >= cutoff);
+    log.entries.sort_by_key(|e| e.timestamp);
+    if log.entries.len() > MAX_ENTRIES {
+        let excess = log.entries.len() - MAX_ENTRIES;
+        log.entries.drain(..excess);
+    }
+}
+
+/// Aggregate entries newer than `since` per file, most recent first.
+fn summarize(root: &Path, entries: &[ActivityEntry], since: i64) -> Vec<FileActivity> {
+    let mut by_path: HashMap<&str, FileActivity> = HashMap::new();
+
+    for entry in entries.iter().filter(|e| e.timestamp >= since) {
+        let item = by_path.entry(&entry.path).or_insert_with(|| FileActivity {
+            path: entry.path.clone(),
+            absolute_path: workspace::resolve_workspace_path(root, &entry.path)
+                .to_string_lossy()
+                .to_string(),
+            last_edited: 0,
+            saves: 0,
+            word_delta: 0,
+        });
+        item.last_edited = item.last_edited.max(entry.timestamp);
+        item.saves += entry.saves;
+        item.word_delta += entry.word_delta;
+    }
+
+    let mut result: Vec<FileActivity> = by_path.into_values().collect();
+    result.sort_by_key(|a| std::cmp::Reverse(a.last_edited));
+    result
+}
+
+/// Record a save in the workspace activity log.
+/// Emits `workspace:activity-changed` with the workspace root.
+#[tauri::command]
+pub fn record_activity(
+    app: AppHandle,
+    root_path: String,
+    path: String,
+    word_delta: i64,
+) -> Result<(), String> {
+    let root = Path::new(&root_path);
+    let relative =
+        workspace::to_workspace_relative(root, &workspace::resolve_workspace_path(root, &path));
+    let now = chrono::Utc::now().timestamp_millis();
+
+    {
+        let _guard = ACTIVITY_LOCK.lock().map_err(|_| "Activity lock poisoned")?;
+        let mut log = read_log(root);
+        append_entry(&mut log, relative, word_delta, now);
+        write_log(root, &log)?;
+    }
+
+    let _ = app.emit("workspace:activity-changed", &root_path);
+    Ok(())
+}
+
+/// Files edited in the last `days` days, most recent first.
+#[tauri::command]
+pub fn recent_activity(root_path: String, days: u32) -> Vec<FileActivity> {
+    let root = Path::new(&root_path);
+    let since = chrono::Utc::now().timestamp_millis() - i64::from(days) * DAY_MS;
+    summarize(root, &read_log(root).entries, since)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_append_merges_consecutive_saves() {
+        let mut log = ActivityLog::default();
+        append_entry(&mut log, "a.md".into(), 10, 1_000);
+        append_entry(&mut log, "a.md".into(), 5, 2_000);
+        append_entry(&mut log, "b.md".into(), -3, 3_000);
+        append_entry(&mut log, "a.md".into(), 1, 3_000 + MERGE_WINDOW_MS);
+
+        assert_eq!(log.entries.len(), 3);
+        assert_eq!(log.entries[0].word_delta, 15);
+        assert_eq!(log.entries[0].saves, 2);
+    }
+
+    #[test]
+    fn test_append_drops_expired_entries() {
+        let mut log = ActivityLog::default();
+        append_entry(&mut log, "old.md".into(), 1, 0);
+        append_entry(&mut log, "new.md".into(), 1, (RETENTION_DAYS + 1) * DAY_MS);
+
+        assert_eq!(log.entries.len(), 1);
+        assert_eq!(log.entries[0].path, "new.md");
+    }
+
+    #[test]
+    fn test_summarize_and_persist() {
+        let dir = tempdir().unwrap();
+        let root = dir.path();
+
+        let mut log = ActivityLog::default();
+        append_entry(&mut log, "a.md".into(), 10, 1_000);
+        append_entry(&mut log, "b.md".into(), 7, 2_000);
+        append_entry(&mut log, "a.md".into(), -2, 1_000 + MERGE_WINDOW_MS);
+        write_log(root, &log).unwrap();
+
+        let entries = read_log(root).entries;
+        let summary = summarize(root, &entries, 1_500);
+        assert_eq!(summary.len(), 2);
+        assert_eq!(summary[0].path, "a.md");
+        assert_eq!(summary[0].saves, 1);
+        assert_eq!(summary[0].word_delta, -2);
+        assert_eq!(summary[1].path, "b.md");
+
+        let all = summarize(root, &entries, 0);
+        assert_eq!(all[0].word_delta, 8);
+        assert_eq!(all[0].saves, 2);
+    }
+}
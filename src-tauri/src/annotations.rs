@@ -0,0 +1,499 @@
+//! Bookmarks and highlight annotations.
+//!
+//! Annotations of a document are stored in
+//! `~/.vmark/annotations/<document path hash>.json`, and follow the document
+//! when it is renamed or moved (`move_annotations`). Each one keeps
+//! the marked text, the line and column it was last found at, and a little
+//! text on either side of it. When the document changes (on save, or
+//! whenever annotations are read after an outside edit) every annotation
+//! is re-anchored: to the occurrence of its text whose surroundings match
+//! best, the nearest one to its old position on ties. Reads don't write the
+//! new positions back; the next change to the annotations does. Annotations
+//! whose text is gone are kept and marked `orphaned`.
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use crate::document_io::{self, SaveOptions};
+
+/// Characters of surrounding text kept on each side of an anchor
+const CONTEXT_CHARS: usize = 32;
+
+/// Serializes read-modify-write cycles of annotation files
+static LOCK: Mutex<()> = Mutex::new(());
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum AnnotationKind {
+    Bookmark,
+    Highlight,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Annotation {
+    pub id: String,
+    pub kind: AnnotationKind,
+    /// Bookmark name
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// The marked text; empty for a bookmark on a whole line
+    #[serde(default)]
+    pub anchor: String,
+    /// 1-based line where the anchor starts
+    pub line: usize,
+    /// 1-based column, in characters
+    pub column: usize,
+    #[serde(default)]
+    pub prefix: String,
+    #[serde(default)]
+    pub suffix: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
+    /// CSS color or palette name
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub color: Option<String>,
+    /// Unix timestamp in milliseconds
+    #[serde(rename = "createdAt")]
+    pub created_at: i64,
+    #[serde(rename = "updatedAt")]
+    pub updated_at: i64,
+    /// The anchor text is no longer in the document
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub orphaned: bool,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct NewAnnotation {
+    pub kind: AnnotationKind,
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub anchor: String,
+    pub line: usize,
+    #[serde(default = "first_column")]
+    pub column: usize,
+    #[serde(default)]
+    pub note: Option<String>,
+    #[serde(default)]
+    pub color: Option<String>,
+}
+
+fn first_column() -> usize {
+    1
+}
+
+/// Fields of an annotation that can be changed; `None` keeps the value.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct AnnotationPatch {
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub note: Option<String>,
+    #[serde(default)]
+    pub color: Option<String>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct AnnotationFile {
+    /// `document_io::checksum` of the content the annotations were anchored in
+    #[serde(default)]
+    checksum: String,
+    #[serde(default)]
+    annotations: Vec<Annotation>,
+}
+
+fn annotations_root() -> Result<PathBuf, String> {
+    dirs::home_dir()
+        .map(|home| home.join(".vmark").join("annotations"))
+        .ok_or_else(|| "Failed to locate home directory".to_string())
+}
+
+/// Annotation file of a document under `root`, named by a hash of its
+/// canonical path.
+fn annotations_path(root: &Path, doc_path: &Path) -> Result<PathBuf, String> {
+    if doc_path.file_name().is_none() {
+        return Err(format!("Invalid document path: {}", doc_path.display()));
+    }
+    Ok(root.join(format!("{}.json", document_io::path_key(doc_path))))
+}
+
+fn read_file(path: &Path) -> AnnotationFile {
+    fs::read_to_string(path)
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+fn write_file(path: &Path, file: &AnnotationFile) -> Result<(), String> {
+    if file.annotations.is_empty() {
+        return match fs::remove_file(path) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+                Err(format!("Failed to delete annotations: {e}"))
+            }
+            _ => Ok(()),
+        };
+    }
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create annotations directory: {e}"))?;
+    }
+    let content = serde_json::to_string_pretty(file)
+        .map_err(|e| format!("Failed to serialize annotations: {e}"))?;
+    document_io::save_atomic(path, content.as_bytes(), &SaveOptions::default())?;
+    Ok(())
+}
+
+/// Byte offset of a 1-based line and character column, clamped to the
+/// content.
+fn byte_offset(content: &str, line: usize, column: usize) -> usize {
+    let line_start = if line <= 1 {
+        0
+    } else {
+        content
+            .match_indices('\n')
+            .nth(line - 2)
+            .map_or(content.len(), |(i, _)| i + 1)
+    };
+    let rest = &content[line_start..];
+    let line_end = rest.find('\n').unwrap_or(rest.len());
+    line_start
+        + rest[..line_end]
+            .char_indices()
+            .nth(column.saturating_sub(1))
+            .map_or(line_end, |(i, _)| i)
+}
+
+/// 1-based line and character column of a byte offset.
+fn line_column(content: &str, offset: usize) -> (usize, usize) {
+    let before = &content[..offset];
+    let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+    (
+        before.matches('\n').count() + 1,
+        before[line_start..].chars().count() + 1,
+    )
+}
+
+fn context(content: &str, start: usize, end: usize) -> (String, String) {
+    let before: Vec<char> = content[..start].chars().rev().take(CONTEXT_CHARS).collect();
+    (
+        before.into_iter().rev().collect(),
+        content[end..].chars().take(CONTEXT_CHARS).collect(),
+    )
+}
+
+/// How well the text around an occurrence matches the stored context.
+fn context_score(content: &str, start: usize, end: usize, annotation: &Annotation) -> usize {
+    let (prefix, suffix) = context(content, start, end);
+    let before = prefix
+        .chars()
+        .rev()
+        .zip(annotation.prefix.chars().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let after = suffix
+        .chars()
+        .zip(annotation.suffix.chars())
+        .take_while(|(a, b)| a == b)
+        .count();
+    before + after
+}
+
+/// Find `annotation` in `content` and update its position and context.
+fn anchor(annotation: &mut Annotation, content: &str) {
+    let hint = byte_offset(content, annotation.line, annotation.column);
+    if annotation.anchor.is_empty() {
+        let (line, _) = line_column(content, hint);
+        annotation.line = line;
+        annotation.column = 1;
+        annotation.orphaned = false;
+        return;
+    }
+    let found = if content[hint..].starts_with(&annotation.anchor) {
+        Some(hint)
+    } else {
+        content
+            .match_indices(&annotation.anchor)
+            .map(|(start, _)| start)
+            .max_by_key(|&start| {
+                let end = start + annotation.anchor.len();
+                (
+                    context_score(content, start, end, annotation),
+                    std::cmp::Reverse(start.abs_diff(hint)),
+                )
+            })
+    };
+    let Some(start) = found else {
+        annotation.orphaned = true;
+        return;
+    };
+    let (line, column) = line_column(content, start);
+    let (prefix, suffix) = context(content, start, start + annotation.anchor.len());
+    annotation.line = line;
+    annotation.column = column;
+    annotation.prefix = prefix;
+    annotation.suffix = suffix;
+    annotation.orphaned = false;
+}
+
+/// Annotations of `doc_path`, re-anchored in memory if the document
+/// changed since they were last placed.
+fn load(root: &Path, doc_path: &Path) -> Result<(PathBuf, AnnotationFile), String> {
+    let path = annotations_path(root, doc_path)?;
+    let mut file = read_file(&path);
+    if file.annotations.is_empty() {
+        return Ok((path, file));
+    }
+    if let Ok(content) = fs::read_to_string(doc_path) {
+        let checksum = document_io::checksum(content.as_bytes());
+        if checksum != file.checksum {
+            for annotation in &mut file.annotations {
+                anchor(annotation, &content);
+            }
+            file.checksum = checksum;
+        }
+    }
+    Ok((path, file))
+}
+
+fn reanchor_in(root: &Path, doc_path: &Path, content: &str) -> Result<(), String> {
+    let path = annotations_path(root, doc_path)?;
+    if !path.is_file() {
+        return Ok(());
+    }
+    let _guard = LOCK.lock();
+    let mut file = read_file(&path);
+    for annotation in &mut file.annotations {
+        anchor(annotation, content);
+    }
+    file.checksum = document_io::checksum(content.as_bytes());
+    write_file(&path, &file)
+}
+
+/// Re-anchor the annotations of a document just saved with `content`.
+pub fn reanchor(doc_path: &Path, content: &str) -> Result<(), String> {
+    reanchor_in(&annotations_root()?, doc_path, content)
+}
+
+fn move_in(root: &Path, from: &Path, to: &Path) -> Result<(), String> {
+    let source = annotations_path(root, from)?;
+    if !source.is_file() {
+        return Ok(());
+    }
+    let destination = annotations_path(root, to)?;
+    let _guard = LOCK.lock();
+    fs::rename(&source, &destination).map_err(|e| format!("Failed to move annotations: {e}"))
+}
+
+/// Carry the annotations of a document moved from `from` to `to` along.
+/// Call after the move, with the paths used for it.
+pub fn move_annotations(from: &Path, to: &Path) -> Result<(), String> {
+    move_in(&annotations_root()?, from, to)
+}
+
+fn list_in(root: &Path, doc_path: &Path) -> Result<Vec<Annotation>, String> {
+    let _guard = LOCK.lock();
+    let (_, file) = load(root, doc_path)?;
+    let mut annotations = file.annotations;
+    annotations.sort_by_key(|a| (a.orphaned, a.line, a.column));
+    Ok(annotations)
+}
+
+/// Bookmarks and highlights of a document, in document order.
+#[tauri::command]
+pub fn list_annotations(path: String) -> Result<Vec<Annotation>, String> {
+    list_in(&annotations_root()?, Path::new(&path))
+}
+
+fn add_in(root: &Path, doc_path: &Path, annotation: NewAnnotation) -> Result<Annotation, String> {
+    let _guard = LOCK.lock();
+    let (file_path, mut file) = load(root, doc_path)?;
+    let now = chrono::Utc::now().timestamp_millis();
+    let mut created = Annotation {
+        id: uuid::Uuid::new_v4().to_string(),
+        kind: annotation.kind,
+        name: annotation.name,
+        anchor: annotation.anchor,
+        line: annotation.line,
+        column: annotation.column,
+        prefix: String::new(),
+        suffix: String::new(),
+        note: annotation.note,
+        color: annotation.color,
+        created_at: now,
+        updated_at: now,
+        orphaned: false,
+    };
+    let content = fs::read_to_string(doc_path)
+        .map_err(|e| format!("Failed to read {}: {e}", doc_path.display()))?;
+    anchor(&mut created, &content);
+    if created.orphaned {
+        return Err("The marked text is not in the document".to_string());
+    }
+    file.checksum = document_io::checksum(content.as_bytes());
+    file.annotations.push(created.clone());
+    write_file(&file_path, &file)?;
+    Ok(created)
+}
+
+#[tauri::command]
+pub fn add_annotation(path: String, annotation: NewAnnotation) -> Result<Annotation, String> {
+    add_in(&annotations_root()?, Path::new(&path), annotation)
+}
+
+fn update_in(
+    root: &Path,
+    doc_path: &Path,
+    id: &str,
+    patch: AnnotationPatch,
+) -> Result<Annotation, String> {
+    let _guard = LOCK.lock();
+    let (file_path, mut file) = load(root, doc_path)?;
+    let annotation = file
+        .annotations
+        .iter_mut()
+        .find(|a| a.id == id)
+        .ok_or_else(|| format!("Annotation not found: {id}"))?;
+    if let Some(name) = patch.name {
+        annotation.name = Some(name).filter(|n| !n.is_empty());
+    }
+    if let Some(note) = patch.note {
+        annotation.note = Some(note).filter(|n| !n.is_empty());
+    }
+    if let Some(color) = patch.color {
+        annotation.color = Some(color).filter(|c| !c.is_empty());
+    }
+    annotation.updated_at = chrono::Utc::now().timestamp_millis();
+    let updated = annotation.clone();
+    write_file(&file_path, &file)?;
+    Ok(updated)
+}
+
+#[tauri::command]
+pub fn update_annotation(
+    path: String,
+    id: String,
+    patch: AnnotationPatch,
+) -> Result<Annotation, String> {
+    update_in(&annotations_root()?, Path::new(&path), &id, patch)
+}
+
+fn delete_in(root: &Path, doc_path: &Path, id: &str) -> Result<(), String> {
+    let _guard = LOCK.lock();
+    let (file_path, mut file) = load(root, doc_path)?;
+    let count = file.annotations.len();
+    file.annotations.retain(|a| a.id != id);
+    if file.annotations.len() == count {
+        return Err(format!("Annotation not found: {id}"));
+    }
+    write_file(&file_path, &file)
+}
+
+#[tauri::command]
+pub fn delete_annotation(path: String, id: String) -> Result<(), String> {
+    delete_in(&annotations_root()?, Path::new(&path), &id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn highlight(anchor: &str, line: usize, column: usize) -> NewAnnotation {
+        NewAnnotation {
+            kind: AnnotationKind::Highlight,
+            name: None,
+            anchor: anchor.to_string(),
+            line,
+            column,
+            note: Some("check".to_string()),
+            color: None,
+        }
+    }
+
+    #[test]
+    fn test_annotations_follow_edits() {
+        let dir = tempdir().unwrap();
+        let root = dir.path().join("annotations");
+        let doc = dir.path().join("draft.md");
+        fs::write(&doc, "# Title\n\nThe cat sat. The cat ran.\n").unwrap();
+
+        let ran = add_in(&root, &doc, highlight("The cat", 3, 14)).unwrap();
+        let sat = add_in(&root, &doc, highlight("The cat", 3, 1)).unwrap();
+        let gone = add_in(&root, &doc, highlight("sat", 3, 9)).unwrap();
+        assert_eq!((ran.line, ran.column), (3, 14));
+        assert!(add_in(&root, &doc, highlight("dog", 1, 1)).is_err());
+        assert!(!dir.path().join(".vmark").exists());
+
+        // Edited outside the app
+        fs::write(&doc, "# Title\n\nIntro.\n\nThe cat stood. The cat ran.\n").unwrap();
+        let stored = fs::read(annotations_path(&root, &doc).unwrap()).unwrap();
+        let listed = list_in(&root, &doc).unwrap();
+        let find = |id: &str| listed.iter().find(|a| a.id == id).unwrap();
+        assert_eq!((find(&sat.id).line, find(&sat.id).column), (5, 1));
+        assert_eq!((find(&ran.id).line, find(&ran.id).column), (5, 16));
+        assert!(find(&gone.id).orphaned);
+        // Reading doesn't write
+        assert_eq!(
+            fs::read(annotations_path(&root, &doc).unwrap()).unwrap(),
+            stored
+        );
+
+        // Saved from the app
+        fs::write(&doc, "Top.\nThe cat ran.\n").unwrap();
+        reanchor_in(&root, &doc, "Top.\nThe cat ran.\n").unwrap();
+        let listed = list_in(&root, &doc).unwrap();
+        assert_eq!(
+            (listed[0].id.as_str(), listed[0].line),
+            (ran.id.as_str(), 2)
+        );
+    }
+
+    #[test]
+    fn test_update_and_delete() {
+        let dir = tempdir().unwrap();
+        let root = dir.path().join("annotations");
+        let doc = dir.path().join("a.md");
+        fs::write(&doc, "one\ntwo\n").unwrap();
+        let bookmark = NewAnnotation {
+            kind: AnnotationKind::Bookmark,
+            name: Some("Start".to_string()),
+            ..highlight("", 2, 1)
+        };
+        let created = add_in(&root, &doc, bookmark).unwrap();
+
+        let patch = AnnotationPatch {
+            note: Some(String::new()),
+            color: Some("yellow".to_string()),
+            ..Default::default()
+        };
+        let updated = update_in(&root, &doc, &created.id, patch).unwrap();
+        assert_eq!(updated.name.as_deref(), Some("Start"));
+        assert_eq!(updated.note, None);
+        assert_eq!(updated.color.as_deref(), Some("yellow"));
+
+        delete_in(&root, &doc, &created.id).unwrap();
+        assert!(list_in(&root, &doc).unwrap().is_empty());
+        assert!(!annotations_path(&root, &doc).unwrap().exists());
+    }
+
+    #[test]
+    fn test_annotations_follow_moves() {
+        let dir = tempdir().unwrap();
+        let root = dir.path().join("annotations");
+        let folder = dir.path().join("notes");
+        fs::create_dir(&folder).unwrap();
+        let doc = folder.join("a.md");
+        fs::write(&doc, "one\ntwo\n").unwrap();
+        let created = add_in(&root, &doc, highlight("two", 2, 1)).unwrap();
+
+        let moved_folder = dir.path().join("archive");
+        fs::rename(&folder, &moved_folder).unwrap();
+        let moved = moved_folder.join("a.md");
+        move_in(&root, &doc, &moved).unwrap();
+
+        assert!(list_in(&root, &doc).unwrap().is_empty());
+        assert_eq!(list_in(&root, &moved).unwrap()[0].id, created.id);
+    }
+}
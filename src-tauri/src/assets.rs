@@ -0,0 +1,302 @@
+//! Orphaned asset detection and cleanup.
+//!
+//! Scans asset folders in a workspace (`<name>.assets/`, `.assets/`,
+//! `assets/images/` next to documents, plus user-configured folders),
+//! cross-references them against links in every markdown file, and
+//! reports images that nothing references anymore.
+
+use serde::Serialize;
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::exclude::ExcludeMatcher;
+use crate::links::{self, LinkKind};
+
+/// Image extensions considered assets (matches frontend IMAGE_EXTENSIONS).
+pub const IMAGE_EXTENSIONS: &[&str] = &["png", "jpg", "jpeg", "gif", "webp", "svg", "bmp"];
+
+/// Default image folder relative to a document (matches frontend ASSETS_FOLDER).
+const DEFAULT_ASSETS_FOLDER: &str = "assets/images";
+
+#[derive(Debug, Clone, Serialize)]
+pub struct OrphanedAsset {
+    pub path: String,
+    pub size: u64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct OrphanScanResult {
+    pub orphans: Vec<OrphanedAsset>,
+    /// Number of asset files that are referenced somewhere
+    #[serde(rename = "referencedCount")]
+    pub referenced_count: usize,
+    /// Total number of asset files found in asset folders
+    #[serde(rename = "totalAssets")]
+    pub total_assets: usize,
+    /// Combined size of all orphaned assets in bytes
+    #[serde(rename = "orphanedBytes")]
+    pub orphaned_bytes: u64,
+}
+
+/// Check whether a path has an image extension.
+pub fn is_image_path(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .map(|ext| IMAGE_EXTENSIONS.contains(&ext.to_ascii_lowercase().as_str()))
+        .unwrap_or(false)
+}
+
+/// Check whether a directory is an asset folder.
+fn is_asset_dir(dir: &Path, extra_dirs: &[PathBuf]) -> bool {
+    let name = dir
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+    name == ".assets"
+        || name.ends_with(".assets")
+        || dir.ends_with(DEFAULT_ASSETS_FOLDER)
+        || extra_dirs.iter().any(|extra| dir == extra)
+}
+
+/// Recursively collect image files inside a directory.
+fn collect_images(dir: &Path, out: &mut Vec<PathBuf>) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        match entry.file_type() {
+            Ok(ft) if ft.is_dir() => collect_images(&path, out),
+            Ok(ft) if ft.is_file() && is_image_path(&path) => out.push(path),
+            _ => {}
+        }
+    }
+}
+
+/// Find all asset files under `root` that live in asset folders.
+fn collect_asset_files(root: &Path, excludes: &ExcludeMatcher, extra_dirs: &[PathBuf]) -> Vec<PathBuf> {
+    let mut assets = Vec::new();
+    let mut stack = vec![root.to_path_buf()];
+
+    while let Some(dir) = stack.pop() {
+        if is_asset_dir(&dir, extra_dirs) {
+            collect_images(&dir, &mut assets);
+            continue;
+        }
+
+        let Ok(entries) = fs::read_dir(&dir) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let name = entry.file_name().to_string_lossy().to_string();
+            let is_dir = entry.file_type().map(|ft| ft.is_dir()).unwrap_or(false);
+            if !is_dir || excludes.is_excluded(&path, true) {
+                continue;
+            }
+            // Hidden folders are skipped unless they are asset folders themselves
+            if name.starts_with('.') && !is_asset_dir(&path, extra_dirs) {
+                continue;
+            }
+            stack.push(path);
+        }
+    }
+
+    assets.sort();
+    assets
+}
+
+/// Collected references from all markdown files in a workspace.
+#[derive(Default)]
+struct References {
+    /// Resolved absolute paths of linked files
+    paths: HashSet<PathBuf>,
+    /// File names referenced by wiki embeds (`![[pic.png]]`), lowercased
+    names: HashSet<String>,
+}
+
+impl References {
+    fn contains(&self, asset: &Path) -> bool {
+        if self.paths.contains(asset) {
+            return true;
+        }
+        asset
+            .file_name()
+            .map(|n| self.names.contains(&n.to_string_lossy().to_lowercase()))
+            .unwrap_or(false)
+    }
+}
+
+fn collect_references(root: &Path, excludes: &ExcludeMatcher) -> References {
+    let mut refs = References::default();
+
+    for file in crate::file_tree::collect_markdown_files(root, excludes) {
+        let Ok(content) = fs::read_to_string(&file) else {
+            continue;
+        };
+        let Some(doc_dir) = file.parent() else {
+            continue;
+        };
+
+        for link in links::scan_links(&content) {
+            if !links::is_local_target(&link.target) {
+                continue;
+            }
+            let (path_part, _) = links::split_fragment(&link.target);
+            if path_part.is_empty() {
+                continue;
+            }
+            if link.kind == LinkKind::WikiLink {
+                let name = Path::new(path_part.trim())
+                    .file_name()
+                    .map(|n| n.to_string_lossy().to_lowercase())
+                    .unwrap_or_default();
+                refs.names.insert(name);
+            }
+            refs.paths.insert(links::resolve_link_path(doc_dir, path_part));
+        }
+    }
+
+    refs
+}
+
+/// Scan a workspace and return orphaned assets.
+fn scan_orphans(root: &Path, extra_dirs: &[String]) -> OrphanScanResult {
+    let root = links::normalize_path(root);
+    let excludes = ExcludeMatcher::for_root(&root);
+    let extra: Vec<PathBuf> = extra_dirs
+        .iter()
+        .map(|d| links::normalize_path(&root.join(d)))
+        .collect();
+
+    let assets = collect_asset_files(&root, &excludes, &extra);
+    let refs = collect_references(&root, &excludes);
+
+    let mut orphans = Vec::new();
+    let mut orphaned_bytes = 0;
+    for asset in &assets {
+        if refs.contains(asset) {
+            continue;
+        }
+        let size = fs::metadata(asset).map(|m| m.len()).unwrap_or(0);
+        orphaned_bytes += size;
+        orphans.push(OrphanedAsset {
+            path: asset.to_string_lossy().to_string(),
+            size,
+        });
+    }
+
+    OrphanScanResult {
+        referenced_count: assets.len() - orphans.len(),
+        total_assets: assets.len(),
+        orphans,
+        orphaned_bytes,
+    }
+}
+
+/// Find assets in the workspace that are not referenced by any markdown file.
+///
+/// # Arguments
+/// * `root_path` - Workspace root to scan
+/// * `asset_dirs` - Extra asset folders (relative to root) beyond the built-in conventions
+#[tauri::command]
+pub fn find_orphaned_assets(
+    root_path: String,
+    asset_dirs: Option<Vec<String>>,
+) -> Result<OrphanScanResult, String> {
+    let root = Path::new(&root_path);
+    if !root.is_dir() {
+        return Err(format!("Not a directory: {root_path}"));
+    }
+    Ok(scan_orphans(root, &asset_dirs.unwrap_or_default()))
+}
+
+/// Move orphaned assets to the system trash.
+///
+/// The workspace is rescanned first and only paths that are still orphaned
+/// are removed, so a stale selection can never delete a referenced image.
+/// Returns the paths that were trashed.
+#[tauri::command]
+pub fn trash_orphaned_assets(
+    root_path: String,
+    paths: Vec<String>,
+    asset_dirs: Option<Vec<String>>,
+) -> Result<Vec<String>, String> {
+    let root = Path::new(&root_path);
+    if !root.is_dir() {
+        return Err(format!("Not a directory: {root_path}"));
+    }
+
+    let scan = scan_orphans(root, &asset_dirs.unwrap_or_default());
+    let orphaned: HashSet<&str> = scan.orphans.iter().map(|o| o.path.as_str()).collect();
+
+    let mut trashed = Vec::new();
+    for path in paths {
+        if !orphaned.contains(path.as_str()) {
+            continue;
+        }
+        trash::delete(&path).map_err(|e| format!("Failed to move {path} to trash: {e}"))?;
+        trashed.push(path);
+    }
+
+    Ok(trashed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_is_image_path() {
+        assert!(is_image_path(Path::new("a/b.PNG")));
+        assert!(!is_image_path(Path::new("a/b.md")));
+    }
+
+    #[test]
+    fn test_scan_orphans_across_asset_folder_conventions() {
+        let dir = tempdir().unwrap();
+        let root = dir.path();
+        fs::create_dir_all(root.join("notes/assets/images")).unwrap();
+        fs::create_dir_all(root.join("notes/a.assets")).unwrap();
+        fs::create_dir_all(root.join(".assets")).unwrap();
+        fs::create_dir_all(root.join("media")).unwrap();
+
+        fs::write(root.join("notes/assets/images/used.png"), "x").unwrap();
+        fs::write(root.join("notes/assets/images/dead.png"), "xx").unwrap();
+        fs::write(root.join("notes/a.assets/embedded.jpg"), "x").unwrap();
+        fs::write(root.join(".assets/html.gif"), "x").unwrap();
+        fs::write(root.join(".assets/dead.gif"), "xxx").unwrap();
+        fs::write(root.join("media/dead.webp"), "x").unwrap();
+        fs::write(root.join("unrelated.png"), "x").unwrap();
+
+        fs::write(
+            root.join("notes/a.md"),
+            "![used](assets/images/used.png)\n![[embedded.jpg]]\n<img src=\"../.assets/html.gif\">\n",
+        )
+        .unwrap();
+
+        let result = scan_orphans(root, &["media".to_string()]);
+        let mut orphans: Vec<String> = result
+            .orphans
+            .iter()
+            .map(|o| {
+                Path::new(&o.path)
+                    .strip_prefix(root)
+                    .unwrap()
+                    .to_string_lossy()
+                    .to_string()
+            })
+            .collect();
+        orphans.sort();
+
+        assert_eq!(
+            orphans,
+            vec![".assets/dead.gif", "media/dead.webp", "notes/assets/images/dead.png"]
+        );
+        assert_eq!(result.total_assets, 6);
+        assert_eq!(result.referenced_count, 3);
+        assert_eq!(result.orphaned_bytes, 6);
+    }
+}
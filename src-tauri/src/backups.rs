@@ -0,0 +1,273 @@
+//! Rolling backups of documents with unsaved changes.
+//!
+//! While a document is dirty, the frontend sends its content on a timer
+//! (`autosave_backup`). Each distinct content becomes a snapshot at
+//! `~/.vmark/backups/<document path hash>/<unix ms>.md`, so document folders
+//! stay clean. Old snapshots are pruned by count and age
+//! (`set_backup_retention`).
+
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use crate::document_io::{self, SaveOptions};
+
+/// Retention used until the frontend sets its own
+const DEFAULT_RETENTION: Retention = Retention {
+    max_count: 20,
+    max_age_days: 7,
+};
+
+static RETENTION: Mutex<Retention> = Mutex::new(DEFAULT_RETENTION);
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Retention {
+    /// Snapshots kept per document; 0 = no limit
+    max_count: usize,
+    /// Snapshots older than this are deleted; 0 = no limit
+    max_age_days: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Backup {
+    pub id: String,
+    /// Unix timestamp in milliseconds
+    #[serde(rename = "createdAt")]
+    pub created_at: i64,
+    pub size: u64,
+}
+
+fn backups_root() -> Result<PathBuf, String> {
+    dirs::home_dir()
+        .map(|home| home.join(".vmark").join("backups"))
+        .ok_or_else(|| "Failed to locate home directory".to_string())
+}
+
+/// Backup folder of a document under `root`, named by a hash of its
+/// canonical path.
+fn backups_dir(root: &Path, doc_path: &Path) -> Result<PathBuf, String> {
+    if doc_path.file_name().is_none() {
+        return Err(format!("Invalid document path: {}", doc_path.display()));
+    }
+    Ok(root.join(document_io::path_key(doc_path)))
+}
+
+/// Backup ids are the timestamps written here; anything else could name
+/// a file outside the backup folder.
+fn is_backup_id(id: &str) -> bool {
+    !id.is_empty() && id.chars().all(|c| c.is_ascii_digit())
+}
+
+fn backup_path(dir: &Path, id: &str) -> Result<PathBuf, String> {
+    if !is_backup_id(id) {
+        return Err(format!("Invalid backup id: {id}"));
+    }
+    Ok(dir.join(format!("{id}.md")))
+}
+
+/// Snapshots in `dir`, newest first.
+fn list_in(dir: &Path) -> Vec<Backup> {
+    let mut backups: Vec<Backup> = fs::read_dir(dir)
+        .ok()
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter_map(|entry| {
+            let path = entry.path();
+            if path.extension().is_none_or(|ext| ext != "md") {
+                return None;
+            }
+            let id = path.file_stem()?.to_str()?.to_string();
+            let created_at = id.parse().ok()?;
+            let size = entry.metadata().ok()?.len();
+            Some(Backup {
+                id,
+                created_at,
+                size,
+            })
+        })
+        .collect();
+    backups.sort_by_key(|backup| std::cmp::Reverse(backup.created_at));
+    backups
+}
+
+fn prune(dir: &Path, retention: Retention, now: i64) {
+    let max_age = Duration::from_secs(retention.max_age_days.saturating_mul(24 * 60 * 60));
+    let max_age_ms = i64::try_from(max_age.as_millis()).unwrap_or(i64::MAX);
+    for (index, backup) in list_in(dir).iter().enumerate() {
+        let too_many = retention.max_count > 0 && index >= retention.max_count;
+        let too_old =
+            retention.max_age_days > 0 && now.saturating_sub(backup.created_at) > max_age_ms;
+        if too_many || too_old {
+            let _ = fs::remove_file(dir.join(format!("{}.md", backup.id)));
+        }
+    }
+}
+
+/// Write a snapshot unless it matches the newest one. Returns the new
+/// snapshot, if any.
+fn write_backup(
+    root: &Path,
+    doc_path: &Path,
+    content: &str,
+    retention: Retention,
+    now: i64,
+) -> Result<Option<Backup>, String> {
+    let dir = backups_dir(root, doc_path)?;
+    if let Some(newest) = list_in(&dir).first() {
+        if fs::read_to_string(dir.join(format!("{}.md", newest.id))).is_ok_and(|c| c == content) {
+            return Ok(None);
+        }
+    }
+
+    fs::create_dir_all(&dir).map_err(|e| format!("Failed to create backup directory: {e}"))?;
+    // Two snapshots within a millisecond must not overwrite each other
+    let mut created_at = now;
+    while dir.join(format!("{created_at}.md")).exists() {
+        created_at += 1;
+    }
+    let id = created_at.to_string();
+    fs::write(dir.join(format!("{id}.md")), content)
+        .map_err(|e| format!("Failed to write backup: {e}"))?;
+    prune(&dir, retention, now);
+    Ok(Some(Backup {
+        id,
+        created_at,
+        size: content.len() as u64,
+    }))
+}
+
+fn current_retention() -> Retention {
+    RETENTION.lock().map(|r| *r).unwrap_or(DEFAULT_RETENTION)
+}
+
+/// Snapshot the unsaved content of the document at `path`.
+#[tauri::command]
+pub fn autosave_backup(path: String, content: String) -> Result<Option<Backup>, String> {
+    write_backup(
+        &backups_root()?,
+        Path::new(&path),
+        &content,
+        current_retention(),
+        chrono::Utc::now().timestamp_millis(),
+    )
+}
+
+/// Keep at most `max_count` snapshots per document, none older than
+/// `max_age_days` (0 = no limit).
+#[tauri::command]
+pub fn set_backup_retention(max_count: usize, max_age_days: u64) {
+    if let Ok(mut retention) = RETENTION.lock() {
+        *retention = Retention {
+            max_count,
+            max_age_days,
+        };
+    }
+}
+
+/// Snapshots of a document, newest first.
+#[tauri::command]
+pub fn list_backups(path: String) -> Result<Vec<Backup>, String> {
+    Ok(list_in(&backups_dir(&backups_root()?, Path::new(&path))?))
+}
+
+/// Replace the document with a snapshot and return the restored content.
+/// The document's current content is backed up first, so a restore can be
+/// undone.
+#[tauri::command]
+pub fn restore_backup(path: String, id: String) -> Result<String, String> {
+    let root = backups_root()?;
+    let doc_path = Path::new(&path);
+    let dir = backups_dir(&root, doc_path)?;
+    let content = fs::read_to_string(backup_path(&dir, &id)?)
+        .map_err(|e| format!("Failed to read backup: {e}"))?;
+    if let Ok(current) = fs::read_to_string(doc_path) {
+        write_backup(
+            &root,
+            doc_path,
+            &current,
+            current_retention(),
+            chrono::Utc::now().timestamp_millis(),
+        )?;
+    }
+    document_io::save_atomic(doc_path, content.as_bytes(), &SaveOptions::default())?;
+    Ok(content)
+}
+
+#[tauri::command]
+pub fn delete_backup(path: String, id: String) -> Result<(), String> {
+    let dir = backups_dir(&backups_root()?, Path::new(&path))?;
+    fs::remove_file(backup_path(&dir, &id)?).map_err(|e| format!("Failed to delete backup: {e}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    const DAY_MS: i64 = 24 * 60 * 60 * 1000;
+
+    #[test]
+    fn test_write_backup_skips_unchanged_content() {
+        let dir = tempdir().unwrap();
+        let root = dir.path().join("backups");
+        let doc = dir.path().join("note.md");
+
+        let first = write_backup(&root, &doc, "one", DEFAULT_RETENTION, 1000).unwrap();
+        assert_eq!(first.unwrap().id, "1000");
+        assert!(write_backup(&root, &doc, "one", DEFAULT_RETENTION, 2000)
+            .unwrap()
+            .is_none());
+        write_backup(&root, &doc, "two", DEFAULT_RETENTION, 2000).unwrap();
+
+        let backup_dir = backups_dir(&root, &doc).unwrap();
+        let ids: Vec<_> = list_in(&backup_dir).iter().map(|b| b.id.clone()).collect();
+        assert_eq!(ids, vec!["2000", "1000"]);
+        assert!(backup_dir.join("1000.md").is_file());
+        assert!(!dir.path().join(".vmark").exists());
+        assert_ne!(
+            backup_dir,
+            backups_dir(&root, &dir.path().join("other.md")).unwrap()
+        );
+        assert!(backup_path(dir.path(), "../note").is_err());
+    }
+
+    #[test]
+    fn test_retention() {
+        let dir = tempdir().unwrap();
+        let root = dir.path().join("backups");
+        let doc = dir.path().join("note.md");
+        let retention = Retention {
+            max_count: 2,
+            max_age_days: 1,
+        };
+        let now = 10 * DAY_MS;
+
+        write_backup(&root, &doc, "old", retention, now - 2 * DAY_MS).unwrap();
+        write_backup(&root, &doc, "a", retention, now - 3).unwrap();
+        write_backup(&root, &doc, "b", retention, now - 2).unwrap();
+        write_backup(&root, &doc, "c", retention, now).unwrap();
+
+        let backups = list_in(&backups_dir(&root, &doc).unwrap());
+        let times: Vec<_> = backups.iter().map(|b| b.created_at).collect();
+        assert_eq!(times, vec![now, now - 2]);
+    }
+
+    #[test]
+    fn test_retention_without_age_limit_overflow() {
+        let dir = tempdir().unwrap();
+        let root = dir.path().join("backups");
+        let doc = dir.path().join("note.md");
+        let retention = Retention {
+            max_count: 0,
+            max_age_days: u64::MAX,
+        };
+
+        write_backup(&root, &doc, "a", retention, 0).unwrap();
+        write_backup(&root, &doc, "b", retention, i64::MAX).unwrap();
+
+        assert_eq!(list_in(&backups_dir(&root, &doc).unwrap()).len(), 2);
+    }
+}
@@ -0,0 +1,257 @@
+//! Export a document with its local assets as a zip for sharing.
+//!
+//! The archive holds the document at its root and every local file it
+//! links to (images, PDFs, ...) under `assets/`, with the links rewritten
+//! to point there. Links to other markdown documents and URLs are left
+//! alone. Progress is reported to the calling window with `bundle:progress`
+//! events.
+
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fs::{self, File};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use tauri::{Emitter, WebviewWindow};
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipWriter};
+
+use crate::links::{self, LinkKind};
+use crate::{file_tree, frontmatter};
+
+/// Folder of the archive that holds the assets
+const ASSETS_DIR: &str = "assets";
+
+#[derive(Debug, Default, Deserialize)]
+pub struct BundleOptions {
+    /// Leave the document's frontmatter out of the exported copy
+    #[serde(rename = "stripFrontmatter", default)]
+    pub strip_frontmatter: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct BundleResult {
+    pub path: String,
+    /// Number of asset files packaged
+    pub assets: usize,
+    /// Linked files that don't exist and were left out
+    pub missing: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct BundleProgress {
+    done: usize,
+    total: usize,
+}
+
+/// The document rewritten for the archive and the files to package with it.
+#[derive(Debug, Default)]
+struct BundlePlan {
+    content: String,
+    /// Source file -> path inside the archive
+    assets: Vec<(PathBuf, String)>,
+    missing: Vec<String>,
+}
+
+/// Content with a leading frontmatter block (and the blank lines after it)
+/// removed.
+fn strip_frontmatter(content: &str) -> &str {
+    content[frontmatter::body_start(content)..].trim_start_matches(['\r', '\n'])
+}
+
+/// Archive name for `file`, numbered when another asset took its name.
+/// Names are compared case-insensitively, and every name handed out is
+/// taken, so `pic.png`, `pic.png` and `pic-2.png` can't collide.
+fn archive_name(file: &Path, taken: &mut HashSet<String>) -> String {
+    let name = file
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let stem = file.file_stem().unwrap_or_default().to_string_lossy();
+    let mut candidate = name;
+    let mut count = 1;
+    while !taken.insert(candidate.to_lowercase()) {
+        count += 1;
+        candidate = match file.extension() {
+            Some(ext) => format!("{stem}-{count}.{}", ext.to_string_lossy()),
+            None => format!("{stem}-{count}"),
+        };
+    }
+    format!("{ASSETS_DIR}/{candidate}")
+}
+
+fn plan_bundle(doc_dir: &Path, content: &str) -> BundlePlan {
+    let mut plan = BundlePlan::default();
+    let mut names: HashMap<PathBuf, String> = HashMap::new();
+    let mut taken = HashSet::new();
+
+    let (content, _) = links::rewrite_links(content, |link| {
+        if link.kind == LinkKind::WikiLink || !links::is_local_target(&link.target) {
+            return None;
+        }
+        let (path_part, fragment) = links::split_fragment(&link.target);
+        if path_part.is_empty() {
+            return None;
+        }
+        let file = links::resolve_link_path(doc_dir, path_part);
+        if file_tree::is_markdown_path(&file) {
+            return None;
+        }
+        if !file.is_file() {
+            plan.missing.push(file.to_string_lossy().to_string());
+            return None;
+        }
+        let name = names.entry(file.clone()).or_insert_with(|| {
+            let name = archive_name(&file, &mut taken);
+            plan.assets.push((file.clone(), name.clone()));
+            name
+        });
+        Some(links::format_link_path(
+            &format!("{name}{fragment}"),
+            link.bracketed,
+        ))
+    });
+    plan.content = content;
+    plan
+}
+
+fn write_archive(
+    dest: &Path,
+    doc_name: &str,
+    plan: &BundlePlan,
+    mut on_progress: impl FnMut(usize),
+) -> Result<(), String> {
+    let file = File::create(dest).map_err(|e| format!("Failed to create archive: {e}"))?;
+    let mut zip = ZipWriter::new(file);
+    let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+
+    zip.start_file(doc_name, options)
+        .map_err(|e| format!("Failed to write archive: {e}"))?;
+    zip.write_all(plan.content.as_bytes())
+        .map_err(|e| format!("Failed to write archive: {e}"))?;
+
+    for (index, (source, name)) in plan.assets.iter().enumerate() {
+        let mut input =
+            File::open(source).map_err(|e| format!("Failed to read {}: {e}", source.display()))?;
+        zip.start_file(name.as_str(), options)
+            .map_err(|e| format!("Failed to write archive: {e}"))?;
+        io::copy(&mut input, &mut zip)
+            .map_err(|e| format!("Failed to add {}: {e}", source.display()))?;
+        on_progress(index + 1);
+    }
+    zip.finish()
+        .map_err(|e| format!("Failed to write archive: {e}"))?;
+    Ok(())
+}
+
+/// Package a document and the local files it links to into a zip.
+///
+/// # Arguments
+/// * `path` - Document to export
+/// * `dest_zip` - Archive to create (replaced if it exists)
+/// * `options` - Export options
+#[tauri::command]
+pub fn export_bundle(
+    window: WebviewWindow,
+    path: String,
+    dest_zip: String,
+    options: Option<BundleOptions>,
+) -> Result<BundleResult, String> {
+    let doc_path = links::normalize_path(Path::new(&path));
+    let doc_dir = doc_path
+        .parent()
+        .ok_or_else(|| format!("Invalid document path: {path}"))?;
+    let doc_name = doc_path
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .ok_or_else(|| format!("Invalid document path: {path}"))?;
+    let content =
+        fs::read_to_string(&doc_path).map_err(|e| format!("Failed to read document: {e}"))?;
+    let content = if options.unwrap_or_default().strip_frontmatter {
+        strip_frontmatter(&content)
+    } else {
+        &content
+    };
+
+    let plan = plan_bundle(doc_dir, content);
+    let total = plan.assets.len();
+    let dest = Path::new(&dest_zip);
+    let written = write_archive(dest, &doc_name, &plan, |done| {
+        let _ = window.emit_to(
+            window.label(),
+            "bundle:progress",
+            BundleProgress { done, total },
+        );
+    });
+    if let Err(e) = written {
+        let _ = fs::remove_file(dest);
+        return Err(e);
+    }
+
+    Ok(BundleResult {
+        path: dest_zip,
+        assets: total,
+        missing: plan.missing,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_plan_bundle_rewrites_local_assets() {
+        let dir = tempdir().unwrap();
+        fs::create_dir_all(dir.path().join("a.assets")).unwrap();
+        fs::create_dir_all(dir.path().join("shared")).unwrap();
+        fs::write(dir.path().join("a.assets/pic.png"), "1").unwrap();
+        fs::write(dir.path().join("shared/pic.png"), "2").unwrap();
+        fs::write(dir.path().join("shared/spec file.pdf"), "3").unwrap();
+
+        let content = "![](a.assets/pic.png) ![](shared/pic.png) ![again](a.assets/pic.png)\n\
+            [spec](<shared/spec file.pdf#page=2>) [note](other.md) [web](https://x.io/a.png)\n\
+            ![](gone.png)\n";
+        let plan = plan_bundle(dir.path(), content);
+
+        assert_eq!(
+            plan.content,
+            "![](assets/pic.png) ![](assets/pic-2.png) ![again](assets/pic.png)\n\
+            [spec](<assets/spec file.pdf#page=2>) [note](other.md) [web](https://x.io/a.png)\n\
+            ![](gone.png)\n"
+        );
+        let names: Vec<&str> = plan.assets.iter().map(|(_, n)| n.as_str()).collect();
+        assert_eq!(
+            names,
+            vec!["assets/pic.png", "assets/pic-2.png", "assets/spec file.pdf"]
+        );
+        assert_eq!(plan.missing.len(), 1);
+    }
+
+    #[test]
+    fn test_archive_name_skips_taken_names() {
+        let mut taken = HashSet::new();
+        let names: Vec<String> = ["a/pic-2.png", "b/pic.png", "c/PIC.png", "d/pic"]
+            .iter()
+            .map(|file| archive_name(Path::new(file), &mut taken))
+            .collect();
+        assert_eq!(
+            names,
+            vec![
+                "assets/pic-2.png",
+                "assets/pic.png",
+                "assets/PIC-3.png",
+                "assets/pic"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_strip_frontmatter() {
+        assert_eq!(strip_frontmatter("---\ntitle: A\n---\n\n# A\n"), "# A\n");
+        assert_eq!(
+            strip_frontmatter("# No frontmatter\n"),
+            "# No frontmatter\n"
+        );
+        assert_eq!(strip_frontmatter("+++\ntitle = \"A\"\n+++\n# A\n"), "# A\n");
+    }
+}
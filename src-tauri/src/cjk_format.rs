@@ -0,0 +1,1176 @@
+//! CJK typography ("Format CJK Text"): spacing between CJK and Latin
+//! text, full-width punctuation, units and currencies, dashes and quotes.
+//!
+//! Markdown is formatted line by line. Frontmatter, fenced and indented
+//! code, math blocks and syntax lines are copied as they are; within a
+//! line, code spans, math, links, HTML, URLs, wiki links, footnote markers
+//! and table pipes are protected and the text between them is formatted.
+//! `format_cjk_stream` runs the same pass in the background and sends the
+//! result in pieces, so very long documents never block the editor;
+//! `cancel_cjk_format` stops it.
+
+use regex::{Captures, Regex};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::ops::Range;
+use std::sync::{LazyLock, Mutex};
+use tauri::{Emitter, WebviewWindow};
+
+use crate::{formatter, frontmatter, links, typography};
+
+/// Lines formatted between two `cjk-format:chunk` events
+const STREAM_LINES: usize = 2000;
+
+/// Ids of the `format_cjk_stream` runs that are still wanted
+static RUNNING: LazyLock<Mutex<HashSet<String>>> = LazyLock::new(Default::default);
+
+/// Han, kana, bopomofo and hangul, as a regex class body
+const CJK: &str = r"\p{Han}\p{Hiragana}\p{Katakana}\p{Bopomofo}\p{Hangul}";
+
+/// CJK letters and full-width punctuation, as a regex class body
+const CJK_CHARS: &str = r"\p{Han}\p{Hiragana}\p{Katakana}《》「」『』【】（）〈〉，。！？；：、";
+
+/// CJK letters without hangul, as a regex class body
+const CJK_NO_KOREAN: &str = r"\p{Han}\p{Hiragana}\p{Katakana}\p{Bopomofo}";
+
+/// A number with an optional currency prefix or unit suffix
+const ALNUM: &str = r"(?:[$¥€£₹] ?)?[A-Za-z0-9]+(?:[%‰℃℉]|°[CcFf]?| ?(?:USD|CNY|EUR|GBP|RMB))?";
+
+const TERMINAL_PUNCTUATION: &str = "，。！？；：、";
+const CLOSING_BRACKETS: &str = "》」』】）〉";
+const OPENING_BRACKETS: &str = "《「『【（〈";
+
+static ELLIPSIS_SPACED: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\s*\.\s+\.\s+\.(?:\s+\.)*").expect("valid ellipsis regex"));
+static ELLIPSIS: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\.\.\.\s*").expect("valid ellipsis regex"));
+static CJK_PARENTHESES: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(&format!(r"\(([{CJK_NO_KOREAN}][^()]*)\)")).expect("valid parentheses regex")
+});
+static CJK_BRACKETS: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(&format!(r"\[([{CJK_NO_KOREAN}][^\[\]]*)\]")).expect("valid brackets regex")
+});
+static CJK_THEN_ALNUM: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(&format!("([{CJK}])({ALNUM})")).expect("valid spacing regex"));
+static ALNUM_THEN_CJK: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(&format!("({ALNUM})([{CJK}])")).expect("valid spacing regex"));
+static CJK_OPEN_PAREN: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(&format!(r"([{CJK}])\(")).expect("valid spacing regex"));
+static CLOSE_PAREN_CJK: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(&format!(r"\)([{CJK}])")).expect("valid spacing regex"));
+static CURRENCY_SYMBOL: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"([$¥€£₹])\s+(\d)").expect("valid currency regex"));
+static CURRENCY_PREFIX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(USD|CNY|EUR|GBP|RMB|JPY)\s+(\d)").expect("valid currency regex")
+});
+static UNIT: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(\d)\s+(%|‰|℃|℉|°[CcFf]?)").expect("valid unit regex"));
+static CURRENCY_POSTFIX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(\d)(USD|CNY|EUR|GBP|RMB|JPY)\b").expect("valid currency regex"));
+static SPACES: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(\S) {2,}").expect("valid spaces regex"));
+static DASH_BOTH: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(&format!(r"([{CJK_CHARS}])\s*-{{2,}}\s*([{CJK_CHARS}])")).expect("valid dash regex")
+});
+static DASH_LEFT: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(&format!(r"([{CJK_CHARS}])\s*-{{2,}}\s*([A-Za-z0-9])")).expect("valid dash regex")
+});
+static DASH_RIGHT: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(&format!(r"([A-Za-z0-9])\s*-{{2,}}\s*([{CJK_CHARS}])")).expect("valid dash regex")
+});
+static EMDASH: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(\S)\s*——\s*(\S)").expect("valid dash regex"));
+static SINGLE_QUOTED: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(^|[\s(\[{「『《【〈])'([^']*?)'").expect("valid quote regex"));
+static CJK_SINGLE_QUOTED: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(&format!("([{CJK}])'([^']*?)'")).expect("valid quote regex"));
+static CORNER_QUOTED: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new("「([^」]*)」").expect("valid quote regex"));
+static CURLY_SINGLE_QUOTED: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new("‘([^’]*)’").expect("valid quote regex"));
+static DOUBLE_QUOTE_SPACING: LazyLock<QuoteSpacing> = LazyLock::new(|| QuoteSpacing::new('“', '”'));
+static SINGLE_QUOTE_SPACING: LazyLock<QuoteSpacing> = LazyLock::new(|| QuoteSpacing::new('‘', '’'));
+static IMAGE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"!\[[^\]]*\]\([^)]+\)").expect("valid image regex"));
+static WIKI_LINK: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\[\[[^\]|]+(?:\|[^\]]+)?\]\]").expect("valid wiki link regex"));
+static FOOTNOTE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\[\^[^\]]+\]:?").expect("valid footnote regex"));
+
+/// URLs, emails, versions, times, numbers and domains whose punctuation
+/// stays half-width, most specific first
+static TECHNICAL: LazyLock<Vec<Regex>> = LazyLock::new(|| {
+    [
+        r"https?://\S+",
+        r"[a-zA-Z0-9._%+-]+@[a-zA-Z0-9.-]+\.[a-zA-Z]{2,}",
+        r"\b(?:v\d+(?:\.\d+)+|\d+(?:\.\d+){2,})\b",
+        r"\b\d{1,2}:\d{2}(?::\d{2})?\b",
+        r"\b\d{1,3}(?:,\d{3})+\b",
+        r"\b[a-zA-Z][a-zA-Z0-9-]*\.[a-zA-Z0-9.-]+[a-zA-Z]\b",
+        r"\b\d+\.\d+\b",
+    ]
+    .iter()
+    .map(|pattern| Regex::new(pattern).expect("valid technical regex"))
+    .collect()
+});
+
+fn enabled() -> bool {
+    true
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum QuoteStyle {
+    #[default]
+    Curly,
+    Corner,
+    Guillemets,
+}
+
+/// The CJK formatting settings; every rule can be turned off.
+#[derive(Debug, Clone, Deserialize)]
+pub struct CjkFormatOptions {
+    /// `. . .` → `...`, with one space after
+    #[serde(rename = "ellipsisNormalization", default = "enabled")]
+    pub ellipsis_normalization: bool,
+    /// At most one blank line in a row; drops `<br />` paragraphs
+    #[serde(rename = "newlineCollapsing", default = "enabled")]
+    pub newline_collapsing: bool,
+    /// `１２３Ａ` → `123A`
+    #[serde(rename = "fullwidthAlphanumeric", default = "enabled")]
+    pub fullwidth_alphanumeric: bool,
+    /// `,.!?;:` next to CJK text → `，。！？；：`
+    #[serde(rename = "fullwidthPunctuation", default = "enabled")]
+    pub fullwidth_punctuation: bool,
+    /// `(中文)` → `（中文）`
+    #[serde(rename = "fullwidthParentheses", default = "enabled")]
+    pub fullwidth_parentheses: bool,
+    /// `[中文]` → `【中文】`
+    #[serde(rename = "fullwidthBrackets", default)]
+    pub fullwidth_brackets: bool,
+    /// `中文English` → `中文 English`
+    #[serde(rename = "cjkEnglishSpacing", default = "enabled")]
+    pub cjk_english_spacing: bool,
+    /// `中文(note)` → `中文 (note)`
+    #[serde(rename = "cjkParenthesisSpacing", default = "enabled")]
+    pub cjk_parenthesis_spacing: bool,
+    /// `$ 100` → `$100`, `50 %` → `50%`, `100USD` → `100 USD`
+    #[serde(rename = "currencySpacing", default = "enabled")]
+    pub currency_spacing: bool,
+    /// `A / B` → `A/B`
+    #[serde(rename = "slashSpacing", default = "enabled")]
+    pub slash_spacing: bool,
+    /// Runs of spaces inside a line → one space
+    #[serde(rename = "spaceCollapsing", default = "enabled")]
+    pub space_collapsing: bool,
+    /// `中文--中文` → `中文 —— 中文`
+    #[serde(rename = "dashConversion", default = "enabled")]
+    pub dash_conversion: bool,
+    /// One space around `——`, none next to brackets
+    #[serde(rename = "emdashSpacing", default = "enabled")]
+    pub emdash_spacing: bool,
+    /// Straight quotes → smart quotes of `quote_style`
+    #[serde(rename = "smartQuoteConversion", default = "enabled")]
+    pub smart_quote_conversion: bool,
+    #[serde(rename = "quoteStyle", default)]
+    pub quote_style: QuoteStyle,
+    /// Convert only quotes touching CJK text; Latin quotes stay straight
+    #[serde(rename = "contextualQuotes", default = "enabled")]
+    pub contextual_quotes: bool,
+    /// Space between `“”` and surrounding Latin/CJK text
+    #[serde(rename = "quoteSpacing", default = "enabled")]
+    pub quote_spacing: bool,
+    /// Space between `‘’` and surrounding Latin/CJK text
+    #[serde(rename = "singleQuoteSpacing", default = "enabled")]
+    pub single_quote_spacing: bool,
+    /// Quotes touching CJK text become `「」` and `『』`
+    #[serde(rename = "cjkCornerQuotes", default)]
+    pub cjk_corner_quotes: bool,
+    /// `‘’` inside `「」` → `『』`
+    #[serde(rename = "cjkNestedQuotes", default)]
+    pub cjk_nested_quotes: bool,
+    /// Longest run of `！`, `？` or `。` (0 = no limit)
+    #[serde(rename = "consecutivePunctuationLimit", default)]
+    pub consecutive_punctuation_limit: usize,
+    #[serde(rename = "trailingSpaceRemoval", default = "enabled")]
+    pub trailing_space_removal: bool,
+    /// Keep two or more trailing spaces, which mark a hard line break
+    #[serde(rename = "preserveTwoSpaceHardBreaks", default)]
+    pub preserve_two_space_hard_breaks: bool,
+}
+
+impl Default for CjkFormatOptions {
+    fn default() -> Self {
+        CjkFormatOptions {
+            ellipsis_normalization: enabled(),
+            newline_collapsing: enabled(),
+            fullwidth_alphanumeric: enabled(),
+            fullwidth_punctuation: enabled(),
+            fullwidth_parentheses: enabled(),
+            fullwidth_brackets: false,
+            cjk_english_spacing: enabled(),
+            cjk_parenthesis_spacing: enabled(),
+            currency_spacing: enabled(),
+            slash_spacing: enabled(),
+            space_collapsing: enabled(),
+            dash_conversion: enabled(),
+            emdash_spacing: enabled(),
+            smart_quote_conversion: enabled(),
+            quote_style: QuoteStyle::default(),
+            contextual_quotes: enabled(),
+            quote_spacing: enabled(),
+            single_quote_spacing: enabled(),
+            cjk_corner_quotes: false,
+            cjk_nested_quotes: false,
+            consecutive_punctuation_limit: 0,
+            trailing_space_removal: enabled(),
+            preserve_two_space_hard_breaks: false,
+        }
+    }
+}
+
+/// Han, kana and bopomofo (not hangul).
+fn is_cjk_letter(c: char) -> bool {
+    matches!(c as u32,
+        0x2E80..=0x2FDF // CJK and Kangxi radicals
+        | 0x3005 | 0x3007 | 0x3021..=0x3029 | 0x3038..=0x303B
+        | 0x3040..=0x30FF // Hiragana, Katakana
+        | 0x3100..=0x312F | 0x31A0..=0x31BF // Bopomofo
+        | 0x31F0..=0x31FF // Katakana extensions
+        | 0x3400..=0x4DBF // CJK Extension A
+        | 0x4E00..=0x9FFF // CJK Unified Ideographs
+        | 0xF900..=0xFAFF // CJK Compatibility Ideographs
+        | 0x20000..=0x3134F) // CJK Extensions B-G
+}
+
+/// Han, kana, bopomofo and hangul.
+pub fn is_cjk(c: char) -> bool {
+    is_cjk_letter(c)
+        || matches!(c as u32,
+            0x1100..=0x11FF // Hangul Jamo
+            | 0x3130..=0x318F // Hangul Compatibility Jamo
+            | 0xAC00..=0xD7AF) // Hangul syllables
+}
+
+/// `... ` with exactly one space when text follows.
+fn normalize_ellipsis(text: &str) -> String {
+    let text = ELLIPSIS_SPACED.replace_all(text, "...");
+    ELLIPSIS
+        .replace_all(&text, |caps: &Captures| {
+            let whole = caps.get(0).expect("match");
+            if whole.end() < text.len() {
+                "... ".to_string()
+            } else {
+                whole.as_str().to_string()
+            }
+        })
+        .into_owned()
+}
+
+fn fullwidth_alphanumeric(text: &str) -> String {
+    text.chars()
+        .map(|c| match c {
+            '０'..='９' | 'Ａ'..='Ｚ' | 'ａ'..='ｚ' => {
+                char::from_u32(c as u32 - 0xFEE0).unwrap_or(c)
+            }
+            _ => c,
+        })
+        .collect()
+}
+
+/// Byte ranges of URLs, versions and the like inside runs of Latin text.
+fn technical_ranges(text: &str) -> Vec<Range<usize>> {
+    let is_latin = |c: char| {
+        c.is_ascii_alphanumeric()
+            || c == ' '
+            || c == '\t'
+            || ".,!?;:'\"()[]{}<>/-_@#&=+*%$\\|~`^".contains(c)
+    };
+    let mut ranges: Vec<Range<usize>> = Vec::new();
+    let mut start = 0;
+    for span in text.split(|c: char| !is_latin(c)) {
+        let base = start;
+        start += span.len();
+        start += text[start..].chars().next().map_or(0, char::len_utf8);
+        if span.trim().is_empty() {
+            continue;
+        }
+        let first = ranges.len();
+        for pattern in TECHNICAL.iter() {
+            for found in pattern.find_iter(span) {
+                let range = base + found.start()..base + found.end();
+                let overlaps = ranges[first..]
+                    .iter()
+                    .any(|r| range.start < r.end && r.start < range.end);
+                if !overlaps {
+                    ranges.push(range);
+                }
+            }
+        }
+    }
+    ranges
+}
+
+/// Nearest character after `chars[at]`, skipping spaces and tabs.
+fn right_neighbor(chars: &[(usize, char)], at: usize) -> Option<char> {
+    chars[at + 1..]
+        .iter()
+        .map(|(_, c)| *c)
+        .find(|c| *c != ' ' && *c != '\t')
+}
+
+/// `,.!?;:` next to CJK text become full-width, except when escaped, part
+/// of an ellipsis or inside a URL, version, time or number. The left
+/// neighbor is taken from the output, so `中文!!!` converts as a whole.
+fn fullwidth_punctuation(text: &str) -> String {
+    let technical = technical_ranges(text);
+    let chars: Vec<(usize, char)> = text.char_indices().collect();
+    let mut out = String::with_capacity(text.len());
+    for (i, &(at, c)) in chars.iter().enumerate() {
+        let fullwidth = match c {
+            ',' => '，',
+            '.' => '。',
+            '!' => '！',
+            '?' => '？',
+            ';' => '；',
+            ':' => '：',
+            _ => {
+                out.push(c);
+                continue;
+            }
+        };
+        let prev = i.checked_sub(1).map(|p| chars[p].1);
+        let next = chars.get(i + 1).map(|n| n.1);
+        let ellipsis = c == '.' && (prev == Some('.') || next == Some('.'));
+        if prev == Some('\\') || ellipsis || technical.iter().any(|r| r.contains(&at)) {
+            out.push(c);
+            continue;
+        }
+        let left_cjk = out
+            .chars()
+            .rev()
+            .find(|c| *c != ' ' && *c != '\t')
+            .is_some_and(|l| {
+                is_cjk_letter(l) || CLOSING_BRACKETS.contains(l) || TERMINAL_PUNCTUATION.contains(l)
+            });
+        let right_cjk = right_neighbor(&chars, i)
+            .is_some_and(|r| is_cjk_letter(r) || OPENING_BRACKETS.contains(r));
+        out.push(if left_cjk || right_cjk { fullwidth } else { c });
+    }
+    out
+}
+
+fn currency_spacing(text: &str) -> String {
+    let text = CURRENCY_SYMBOL.replace_all(text, "${1}${2}");
+    let text = CURRENCY_PREFIX.replace_all(&text, "${1}${2}");
+    // Units stick to the number when they end a word
+    let text = UNIT
+        .replace_all(&text, |caps: &Captures| {
+            let end = caps.get(0).expect("match").end();
+            let ends_word = text[end..]
+                .chars()
+                .next()
+                .is_none_or(|c| c.is_whitespace() || ",;.。，；、！？!?)]」』】〉》）".contains(c));
+            if ends_word {
+                format!("{}{}", &caps[1], &caps[2])
+            } else {
+                caps[0].to_string()
+            }
+        })
+        .into_owned();
+    CURRENCY_POSTFIX
+        .replace_all(&text, "${1} ${2}")
+        .into_owned()
+}
+
+/// Drop spaces around `/`, leaving `//` and `://` alone.
+fn slash_spacing(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(slash) = rest.find('/') {
+        let before = rest[..slash].trim_end_matches([' ', '\t']);
+        let after = rest[slash + 1..].trim_start_matches([' ', '\t']);
+        let prev = before
+            .chars()
+            .next_back()
+            .or_else(|| out.chars().next_back());
+        if prev.is_some_and(|c| c == '/' || c == ':') || after.starts_with('/') {
+            out.push_str(&rest[..=slash]);
+            rest = &rest[slash + 1..];
+            continue;
+        }
+        out.push_str(before);
+        out.push('/');
+        rest = after;
+    }
+    out.push_str(rest);
+    out
+}
+
+/// `——` spaced from its neighbors, except brackets that face it.
+fn space_dash(caps: &Captures) -> String {
+    let before = &caps[1];
+    let after = &caps[2];
+    let left = if CLOSING_BRACKETS.contains(before) {
+        ""
+    } else {
+        " "
+    };
+    let right = if OPENING_BRACKETS.contains(after) {
+        ""
+    } else {
+        " "
+    };
+    format!("{before}{left}——{right}{after}")
+}
+
+fn convert_dashes(text: &str) -> String {
+    let text = DASH_BOTH.replace_all(text, space_dash);
+    let text = DASH_LEFT.replace_all(&text, space_dash);
+    DASH_RIGHT.replace_all(&text, space_dash).into_owned()
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum QuoteMode {
+    CurlyEverywhere,
+    /// Curly quotes where CJK is involved, straight ones elsewhere
+    Contextual,
+    /// Corner brackets where CJK is involved, straight quotes elsewhere
+    CornerForCjk,
+}
+
+struct QuotePair {
+    open: usize,
+    close: usize,
+    double: bool,
+    cjk: bool,
+}
+
+fn is_double_quote(c: char) -> bool {
+    matches!(c, '"' | '“' | '”')
+}
+
+fn is_single_quote(c: char) -> bool {
+    matches!(c, '\'' | '‘' | '’')
+}
+
+/// don't, l'amour, Xiaolai's, '90s
+fn is_apostrophe(chars: &[char], i: usize) -> bool {
+    let before = i.checked_sub(1).map(|p| chars[p]);
+    let after = chars.get(i + 1).copied();
+    let after2 = chars.get(i + 2).copied();
+    let letter = |c: Option<char>| c.is_some_and(|c| c.is_ascii_alphabetic());
+    let digit = |c: Option<char>| c.is_some_and(|c| c.is_ascii_digit());
+    (letter(before) && letter(after))
+        || (letter(before) && matches!(after, Some('s' | 'S')) && !letter(after2))
+        || (chars[i] != '’' && !digit(before) && digit(after) && digit(after2))
+}
+
+/// Feet and inches: 5'10", 12"
+fn is_prime(chars: &[char], i: usize) -> bool {
+    i > 0 && chars[i - 1].is_ascii_digit() && chars[i] != '‘' && chars[i] != '“'
+}
+
+/// Whether the quote at `i` opens or closes, from its surroundings and the
+/// quotes still open.
+fn opens(chars: &[char], i: usize, open_quotes: usize) -> bool {
+    let left = chars[..i].iter().rev().find(|c| **c != ' ' && **c != '\t');
+    let right = chars[i + 1..].iter().find(|c| **c != ' ' && **c != '\t');
+    let left_space = i == 0 || chars[i - 1].is_whitespace();
+    let right_space = chars.get(i + 1).is_none_or(|c| c.is_whitespace());
+    if left_space || left.is_some_and(|c| "([{（【《〈「『".contains(*c)) {
+        return true;
+    }
+    if right_space || right.is_some_and(|c| ")]}）】》〉」』，。！？；：、.,!?;:".contains(*c))
+    {
+        return false;
+    }
+    open_quotes == 0
+}
+
+/// Pair up quotes, skipping apostrophes and primes. Quotes opened inside
+/// a pair and never closed are left as they are.
+fn pair_quotes(chars: &[char]) -> Vec<QuotePair> {
+    let mut pairs = Vec::new();
+    let mut doubles: Vec<usize> = Vec::new();
+    let mut singles: Vec<usize> = Vec::new();
+    for i in 0..chars.len() {
+        let double = is_double_quote(chars[i]);
+        if !double && !is_single_quote(chars[i]) {
+            continue;
+        }
+        if (!double && is_apostrophe(chars, i)) || is_prime(chars, i) {
+            continue;
+        }
+        let (stack, other) = if double {
+            (&mut doubles, &mut singles)
+        } else {
+            (&mut singles, &mut doubles)
+        };
+        if opens(chars, i, stack.len()) {
+            stack.push(i);
+            continue;
+        }
+        let Some(open) = stack.pop() else {
+            continue;
+        };
+        while other.last().is_some_and(|inner| *inner > open) {
+            other.pop();
+        }
+        let touches = |at: Option<usize>| {
+            at.and_then(|at| chars.get(at))
+                .is_some_and(|c| is_cjk_letter(*c))
+        };
+        let cjk = chars[open + 1..i].iter().any(|c| is_cjk_letter(*c))
+            || touches(open.checked_sub(1))
+            || touches(Some(i + 1));
+        pairs.push(QuotePair {
+            open,
+            close: i,
+            double,
+            cjk,
+        });
+    }
+    pairs
+}
+
+fn contextual_quotes(text: &str, mode: QuoteMode) -> String {
+    let mut chars: Vec<char> = text.chars().collect();
+    for pair in pair_quotes(&chars) {
+        let marks = match (mode, pair.cjk, pair.double) {
+            (QuoteMode::CornerForCjk, true, true) => ('「', '」'),
+            (QuoteMode::CornerForCjk, true, false) => ('『', '』'),
+            (QuoteMode::CurlyEverywhere, _, true) | (QuoteMode::Contextual, true, true) => {
+                ('“', '”')
+            }
+            (QuoteMode::CurlyEverywhere, _, false) | (QuoteMode::Contextual, true, false) => {
+                ('‘', '’')
+            }
+            (_, false, true) => ('"', '"'),
+            (_, false, false) => ('\'', '\''),
+        };
+        chars[pair.open] = marks.0;
+        chars[pair.close] = marks.1;
+    }
+    chars.into_iter().collect()
+}
+
+/// Guillemets: `"` opens after a space or bracket and alternates after CJK
+/// text; `'` only converts in pairs so apostrophes survive.
+fn guillemet_quotes(text: &str) -> String {
+    let chars: Vec<char> = text.chars().collect();
+    let mut out = String::with_capacity(text.len());
+    let mut cjk_quotes = 0;
+    for (i, &c) in chars.iter().enumerate() {
+        if c != '"' {
+            out.push(c);
+            continue;
+        }
+        let before = i.checked_sub(1).map(|p| chars[p]);
+        let after = chars.get(i + 1).copied();
+        let open = match before {
+            None => true,
+            Some(b) if b.is_whitespace() || "([{「『《【〈".contains(b) => true,
+            Some(b) if is_cjk(b) => {
+                cjk_quotes += 1;
+                let continues = after.is_some_and(|a| {
+                    a.is_whitespace() || a.is_ascii_alphanumeric() || a == '_' || is_cjk(a)
+                });
+                continues && cjk_quotes % 2 == 1
+            }
+            Some(_) => false,
+        };
+        out.push(if open { '«' } else { '»' });
+    }
+    let out = SINGLE_QUOTED.replace_all(&out, "${1}‹${2}›");
+    CJK_SINGLE_QUOTED
+        .replace_all(&out, "${1}‹${2}›")
+        .into_owned()
+}
+
+fn nested_corner_quotes(text: &str) -> String {
+    CORNER_QUOTED
+        .replace_all(text, |caps: &Captures| {
+            let inner = CURLY_SINGLE_QUOTED.replace_all(&caps[1], "『${1}』");
+            format!("「{inner}」")
+        })
+        .into_owned()
+}
+
+/// Spacing between a pair of quote marks and the text around them.
+struct QuoteSpacing {
+    before_open: Regex,
+    after_close: Regex,
+}
+
+impl QuoteSpacing {
+    fn new(open: char, close: char) -> Self {
+        let before = format!("[A-Za-z0-9{CJK}{CLOSING_BRACKETS}{TERMINAL_PUNCTUATION}]|——");
+        let after = format!("[A-Za-z0-9{CJK}{OPENING_BRACKETS}{TERMINAL_PUNCTUATION}]|——");
+        QuoteSpacing {
+            before_open: Regex::new(&format!("({before}){open}")).expect("valid quote regex"),
+            after_close: Regex::new(&format!("{close}({after})")).expect("valid quote regex"),
+        }
+    }
+
+    fn apply(&self, text: &str) -> String {
+        let text = self.before_open.replace_all(text, |caps: &Captures| {
+            let open = caps[0][caps[1].len()..].to_string();
+            let tight =
+                CLOSING_BRACKETS.contains(&caps[1]) || TERMINAL_PUNCTUATION.contains(&caps[1]);
+            format!("{}{}{open}", &caps[1], if tight { "" } else { " " })
+        });
+        self.after_close
+            .replace_all(&text, |caps: &Captures| {
+                let close = &caps[0][..caps[0].len() - caps[1].len()];
+                let tight =
+                    OPENING_BRACKETS.contains(&caps[1]) || TERMINAL_PUNCTUATION.contains(&caps[1]);
+                format!("{close}{}{}", if tight { "" } else { " " }, &caps[1])
+            })
+            .into_owned()
+    }
+}
+
+/// Cut runs of `！`, `？` and `。` down to `limit`.
+fn limit_punctuation(text: &str, limit: usize) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut run = 0;
+    let mut last = None;
+    for c in text.chars() {
+        run = if Some(c) == last { run + 1 } else { 1 };
+        last = Some(c);
+        if matches!(c, '！' | '？' | '。') && run > limit {
+            continue;
+        }
+        out.push(c);
+    }
+    out
+}
+
+/// Apply the enabled rules to text with no markdown syntax in it. Most
+/// rules only run when the text contains CJK characters.
+fn apply_rules(text: &str, options: &CjkFormatOptions) -> String {
+    let mut text = text.to_string();
+    if options.ellipsis_normalization {
+        text = normalize_ellipsis(&text);
+    }
+    if text.chars().any(is_cjk) {
+        if options.fullwidth_alphanumeric {
+            text = fullwidth_alphanumeric(&text);
+        }
+        if options.fullwidth_punctuation {
+            text = fullwidth_punctuation(&text);
+        }
+        if options.fullwidth_brackets {
+            text = CJK_BRACKETS.replace_all(&text, "【${1}】").into_owned();
+        }
+        if options.dash_conversion {
+            text = convert_dashes(&text);
+        }
+        if options.emdash_spacing {
+            text = EMDASH.replace_all(&text, space_dash).into_owned();
+        }
+        if options.smart_quote_conversion {
+            text = match options.quote_style {
+                QuoteStyle::Guillemets => guillemet_quotes(&text),
+                _ if options.cjk_corner_quotes => contextual_quotes(&text, QuoteMode::CornerForCjk),
+                _ if options.contextual_quotes => contextual_quotes(&text, QuoteMode::Contextual),
+                _ => contextual_quotes(&text, QuoteMode::CurlyEverywhere),
+            };
+        }
+        if options.cjk_nested_quotes {
+            text = nested_corner_quotes(&text);
+        }
+        if options.quote_spacing {
+            text = DOUBLE_QUOTE_SPACING.apply(&text);
+        }
+        if options.single_quote_spacing {
+            text = SINGLE_QUOTE_SPACING.apply(&text);
+        }
+        if options.cjk_english_spacing {
+            text = CJK_THEN_ALNUM.replace_all(&text, "${1} ${2}").into_owned();
+            text = ALNUM_THEN_CJK.replace_all(&text, "${1} ${2}").into_owned();
+        }
+        // Before full-width parentheses, which only catch what is left
+        if options.cjk_parenthesis_spacing {
+            text = CJK_OPEN_PAREN.replace_all(&text, "${1} (").into_owned();
+            text = CLOSE_PAREN_CJK.replace_all(&text, ") ${1}").into_owned();
+        }
+        if options.fullwidth_parentheses {
+            text = CJK_PARENTHESES.replace_all(&text, "（${1}）").into_owned();
+        }
+        if options.currency_spacing {
+            text = currency_spacing(&text);
+        }
+        if options.slash_spacing {
+            text = slash_spacing(&text);
+        }
+        if options.consecutive_punctuation_limit > 0 {
+            text = limit_punctuation(&text, options.consecutive_punctuation_limit);
+        }
+    }
+    if options.space_collapsing {
+        text = SPACES.replace_all(&text, "${1} ").into_owned();
+    }
+    text
+}
+
+/// Byte ranges of a line that are copied as they are. In table rows the
+/// pipes and the padding around them are protected too.
+fn protected(line: &str) -> Vec<Range<usize>> {
+    let mut ranges = typography::protected(line);
+    for pattern in [&*IMAGE, &*WIKI_LINK, &*FOOTNOTE] {
+        ranges.extend(pattern.find_iter(line).map(|m| m.range()));
+    }
+    let (prefix, _) = formatter::split_prefix(line);
+    ranges.push(0..prefix.len());
+
+    let code = links::code_spans(line);
+    let bytes = line.as_bytes();
+    for (i, _) in line.match_indices('|') {
+        if (i > 0 && bytes[i - 1] == b'\\') || code.iter().any(|r| r.contains(&i)) {
+            continue;
+        }
+        let start = line[..i].trim_end().len();
+        let end = line.len() - line[i + 1..].trim_start().len();
+        ranges.push(start..end);
+    }
+    ranges.sort_by_key(|r| r.start);
+    ranges
+}
+
+fn format_line(line: &str, options: &CjkFormatOptions) -> String {
+    let mut out = String::with_capacity(line.len() + 16);
+    let mut from = 0;
+    for range in protected(line) {
+        if range.start > from {
+            out.push_str(&apply_rules(&line[from..range.start], options));
+        }
+        if range.end > from {
+            let kept = &line[from.max(range.start)..range.end];
+            // Bare URLs are protected whole but still spaced from CJK text
+            let url = kept.starts_with("http://")
+                || kept.starts_with("https://")
+                || kept.starts_with("www.");
+            if url && options.cjk_english_spacing && out.chars().next_back().is_some_and(is_cjk) {
+                out.push(' ');
+            }
+            out.push_str(kept);
+            from = range.end;
+        }
+    }
+    if from < line.len() {
+        out.push_str(&apply_rules(&line[from..], options));
+    }
+    out
+}
+
+/// Line-by-line formatting state. Blank lines are held back until the
+/// next text line, so runs of them can be collapsed and trailing ones
+/// dropped.
+struct Formatter<'a> {
+    options: &'a CjkFormatOptions,
+    fence: Option<(u8, usize)>,
+    math: bool,
+    list: bool,
+    blank_lines: Vec<&'a str>,
+    /// A `<br />` line after a blank line, dropped if a blank line follows
+    held_break: Option<&'a str>,
+}
+
+impl<'a> Formatter<'a> {
+    fn new(options: &'a CjkFormatOptions) -> Self {
+        Formatter {
+            options,
+            fence: None,
+            math: false,
+            list: false,
+            blank_lines: Vec::new(),
+            held_break: None,
+        }
+    }
+
+    fn flush(&mut self, out: &mut String) {
+        let keep = if self.options.newline_collapsing {
+            1
+        } else {
+            usize::MAX
+        };
+        for blank in self.blank_lines.drain(..).take(keep) {
+            out.push_str(blank);
+        }
+        if let Some(line_break) = self.held_break.take() {
+            out.push_str(line_break);
+        }
+    }
+
+    fn line(&mut self, line: &'a str, out: &mut String) {
+        let text = line.trim_end_matches(['\n', '\r']);
+        let ending = &line[text.len()..];
+        let trimmed = text.trim();
+
+        if self.fence.is_none() && !self.math && trimmed.is_empty() {
+            self.held_break = None;
+            self.blank_lines
+                .push(if self.options.trailing_space_removal {
+                    ending
+                } else {
+                    line
+                });
+            return;
+        }
+        let is_break = trimmed
+            .strip_prefix("<br")
+            .is_some_and(|rest| matches!(rest.trim_start(), ">" | "/>"));
+        if self.options.newline_collapsing
+            && is_break
+            && !self.blank_lines.is_empty()
+            && self.held_break.is_none()
+        {
+            self.held_break = Some(line);
+            return;
+        }
+        self.flush(out);
+
+        if links::update_fence(&mut self.fence, text) || self.fence.is_some() {
+            out.push_str(line);
+            return;
+        }
+        if self.math || trimmed.starts_with("$$") {
+            let closes_inline = !self.math && trimmed.len() > 2 && trimmed[2..].contains("$$");
+            if !closes_inline && (!self.math || trimmed.ends_with("$$")) {
+                self.math = !self.math;
+            }
+            out.push_str(line);
+            return;
+        }
+        let indented = text.starts_with("    ") || text.starts_with('\t');
+        if !indented {
+            let (_, rest) = formatter::split_prefix(text);
+            self.list = formatter::is_list_item(rest);
+        }
+        if (indented && !self.list) || typography::is_syntax_line(text) {
+            out.push_str(line);
+            return;
+        }
+
+        let body = text.trim_end_matches(' ');
+        let trailing = &text[body.len()..];
+        out.push_str(&format_line(body, self.options));
+        let hard_break = self.options.preserve_two_space_hard_breaks && trailing.len() >= 2;
+        if !self.options.trailing_space_removal || hard_break {
+            out.push_str(trailing);
+        }
+        out.push_str(ending);
+    }
+
+    /// Trailing blank lines are dropped.
+    fn finish(mut self, out: &mut String) {
+        if self.held_break.is_some() {
+            self.flush(out);
+        }
+    }
+}
+
+/// Format markdown `content` with the CJK typography rules.
+pub fn format(content: &str, options: &CjkFormatOptions) -> String {
+    let body_start = frontmatter::body_start(content);
+    let mut out = String::with_capacity(content.len() + content.len() / 8);
+    out.push_str(&content[..body_start]);
+    let mut formatter = Formatter::new(options);
+    for line in content[body_start..].split_inclusive('\n') {
+        formatter.line(line, &mut out);
+    }
+    formatter.finish(&mut out);
+    out
+}
+
+/// Apply CJK typography rules to markdown `content`.
+#[tauri::command]
+pub fn format_cjk(content: String, options: Option<CjkFormatOptions>) -> String {
+    format(&content, &options.unwrap_or_default())
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct FormatChunk {
+    /// Formatted text following the previous chunk
+    pub text: String,
+    /// Share of the input formatted so far, from 0 to 1
+    pub progress: f64,
+    pub done: bool,
+}
+
+fn is_running(id: &str) -> bool {
+    RUNNING.lock().is_ok_and(|running| running.contains(id))
+}
+
+/// Format `content` like `format` and hand the result to `send` in pieces
+/// of `STREAM_LINES` lines, stopping once run `id` is cancelled.
+fn format_chunks<F>(id: &str, content: &str, options: &CjkFormatOptions, mut send: F)
+where
+    F: FnMut(FormatChunk),
+{
+    let body_start = frontmatter::body_start(content);
+    let mut text = content[..body_start].to_string();
+    let mut formatter = Formatter::new(options);
+    let mut consumed = body_start;
+    for (n, line) in content[body_start..].split_inclusive('\n').enumerate() {
+        formatter.line(line, &mut text);
+        consumed += line.len();
+        if (n + 1).is_multiple_of(STREAM_LINES) && !text.is_empty() {
+            if !is_running(id) {
+                return;
+            }
+            send(FormatChunk {
+                text: std::mem::take(&mut text),
+                progress: consumed as f64 / content.len() as f64,
+                done: false,
+            });
+        }
+    }
+    formatter.finish(&mut text);
+    // Removing the id also tells whether the run was cancelled meanwhile
+    if RUNNING.lock().is_ok_and(|mut running| running.remove(id)) {
+        send(FormatChunk {
+            text,
+            progress: 1.0,
+            done: true,
+        });
+    }
+}
+
+/// Format `content` in the background and send the result to the calling
+/// window as `cjk-format:chunk` events (payload: id, chunk). Joining the
+/// chunks gives what `format_cjk` returns.
+#[tauri::command]
+pub fn format_cjk_stream(
+    window: WebviewWindow,
+    id: String,
+    content: String,
+    options: Option<CjkFormatOptions>,
+) {
+    if let Ok(mut running) = RUNNING.lock() {
+        running.insert(id.clone());
+    }
+    std::thread::spawn(move || {
+        let label = window.label().to_string();
+        let options = options.unwrap_or_default();
+        format_chunks(&id, &content, &options, |chunk| {
+            let _ = window.emit_to(label.as_str(), "cjk-format:chunk", (&id, chunk));
+        });
+    });
+}
+
+/// Stop a `format_cjk_stream` run; no more chunks are sent for `id`.
+#[tauri::command]
+pub fn cancel_cjk_format(id: String) {
+    if let Ok(mut running) = RUNNING.lock() {
+        running.remove(&id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_apply_rules() {
+        let options = CjkFormatOptions::default();
+        let cases = [
+            ("中文English混排", "中文 English 混排"),
+            ("价格是100USD,折扣50 %。", "价格是 100 USD，折扣 50%。"),
+            ("他说\"你好\",然后走了", "他说 “你好”，然后走了"),
+            ("版本v1.2.3发布于12:30", "版本 v1.2.3 发布于 12:30"),
+            ("中文--中文", "中文 —— 中文"),
+            ("中文(注释)", "中文 （注释）"),
+            (
+                "访问https://example.com/a 吧",
+                "访问 https://example.com/a 吧",
+            ),
+            ("Ｗｉｎｄｏｗｓ１０系统", "Windows10 系统"),
+            ("中文!!!真的?", "中文！！！真的？"),
+            (
+                "plain \"Latin\" text.  Stays",
+                "plain \"Latin\" text. Stays",
+            ),
+        ];
+        for (input, expected) in cases {
+            assert_eq!(format_line(input, &options), expected, "{input}");
+        }
+    }
+
+    #[test]
+    fn test_format_markdown() {
+        let content = "---\ntitle: 中文abc\n---\n# 标题Title\n\n\n\n使用`code`和[链接](http://a.com/b,c)。  \n\n```\n中文abc\n```\n\n| 名称 | 值abc |\n| --- | --- |\n\n    缩进abc\n\n";
+        let expected = "---\ntitle: 中文abc\n---\n# 标题 Title\n\n使用`code`和[链接](http://a.com/b,c)。\n\n```\n中文abc\n```\n\n| 名称 | 值 abc |\n| --- | --- |\n\n    缩进abc\n";
+        let options = CjkFormatOptions::default();
+        assert_eq!(format(content, &options), expected);
+
+        let options = CjkFormatOptions {
+            preserve_two_space_hard_breaks: true,
+            ..CjkFormatOptions::default()
+        };
+        assert_eq!(
+            format("中文abc  \n下一行\n", &options),
+            "中文 abc  \n下一行\n"
+        );
+    }
+
+    #[test]
+    fn test_quote_styles() {
+        let guillemets = CjkFormatOptions {
+            quote_style: QuoteStyle::Guillemets,
+            ..CjkFormatOptions::default()
+        };
+        assert_eq!(
+            format_line("他说\"你好\"，她说'再见'。", &guillemets),
+            "他说«你好»，她说‹再见›。"
+        );
+
+        let corner = CjkFormatOptions {
+            cjk_corner_quotes: true,
+            ..CjkFormatOptions::default()
+        };
+        assert_eq!(
+            format_line("他说\"你好\"，又说'好'。Latin \"quote\" here.", &corner),
+            "他说「你好」，又说『好』。Latin \"quote\" here."
+        );
+
+        let nested = CjkFormatOptions {
+            cjk_nested_quotes: true,
+            ..corner
+        };
+        assert_eq!(
+            format_line("他说\"她说'好'\"。", &nested),
+            "他说「她说『好』」。"
+        );
+        let nested = CjkFormatOptions {
+            cjk_nested_quotes: true,
+            ..CjkFormatOptions::default()
+        };
+        assert_eq!(
+            format_line("他说「她说‘好’」。", &nested),
+            "他说「她说『好』」。"
+        );
+
+        let curly = CjkFormatOptions {
+            contextual_quotes: false,
+            ..CjkFormatOptions::default()
+        };
+        assert_eq!(
+            format_line("中文 \"Latin\" 和'x'", &curly),
+            "中文 “Latin” 和 ‘x’"
+        );
+
+        let straight = CjkFormatOptions {
+            smart_quote_conversion: false,
+            ..CjkFormatOptions::default()
+        };
+        assert_eq!(format_line("他说\"你好\"", &straight), "他说\"你好\"");
+    }
+
+    #[test]
+    fn test_apostrophes_and_primes_are_not_quotes() {
+        let options = CjkFormatOptions::default();
+        assert_eq!(
+            format_line("他说\"don't do it\"，身高5'10\"，'90s的歌。", &options),
+            "他说 “don't do it”，身高 5'10\"，'90s 的歌。"
+        );
+        assert_eq!(
+            format_line("Xiaolai's书和l'amour的\"故事\"", &options),
+            "Xiaolai's 书和 l'amour 的 “故事”"
+        );
+    }
+
+    #[test]
+    fn test_punctuation_and_bracket_options() {
+        let limit = |n| CjkFormatOptions {
+            consecutive_punctuation_limit: n,
+            ..CjkFormatOptions::default()
+        };
+        assert_eq!(
+            format_line("真的!!!好吗???", &limit(0)),
+            "真的！！！好吗？？？"
+        );
+        assert_eq!(format_line("真的!!!好吗???", &limit(1)), "真的！好吗？");
+        assert_eq!(format_line("真的！！！！好吗", &limit(2)), "真的！！好吗");
+
+        let brackets = CjkFormatOptions {
+            fullwidth_brackets: true,
+            ..CjkFormatOptions::default()
+        };
+        assert_eq!(format_line("中文[注释]结尾", &brackets), "中文【注释】结尾");
+        let options = CjkFormatOptions::default();
+        assert_eq!(format_line("中文[注释]结尾", &options), "中文[注释]结尾");
+    }
+
+    #[test]
+    fn test_math_blocks_are_kept() {
+        let options = CjkFormatOptions::default();
+        assert_eq!(
+            format("中文abc\n$$\nx中文abc\n$$\n$$a中文b$$\n后面abc\n", &options),
+            "中文 abc\n$$\nx中文abc\n$$\n$$a中文b$$\n后面 abc\n"
+        );
+    }
+
+    #[test]
+    fn test_line_break_paragraphs() {
+        let options = CjkFormatOptions::default();
+        // A `<br />` paragraph between blank lines goes with them
+        assert_eq!(
+            format("中文abc\n\n<br />\n\n下一段abc\n", &options),
+            "中文 abc\n\n下一段 abc\n"
+        );
+        // Followed directly by text it stays
+        assert_eq!(
+            format("中文abc\n\n<br />\n下一段abc\n", &options),
+            "中文 abc\n\n<br />\n下一段 abc\n"
+        );
+        let keep = CjkFormatOptions {
+            newline_collapsing: false,
+            ..CjkFormatOptions::default()
+        };
+        assert_eq!(
+            format("中文abc\n\n\n<br />\n\n下一段abc\n", &keep),
+            "中文 abc\n\n\n<br />\n\n下一段 abc\n"
+        );
+    }
+
+    fn start(id: &str) {
+        RUNNING.lock().unwrap().insert(id.to_string());
+    }
+
+    #[test]
+    fn test_stream_chunks_join_to_format() {
+        let options = CjkFormatOptions::default();
+        let content = format!(
+            "---\ntitle: 测试\n---\n{}",
+            "中文abc\n\n\n```\n代码abc\n```\n".repeat(STREAM_LINES / 2)
+        );
+
+        start("join");
+        let mut chunks = Vec::new();
+        format_chunks("join", &content, &options, |chunk| chunks.push(chunk));
+        assert!(chunks.len() > 2);
+        assert!(chunks.last().unwrap().done);
+        assert!(chunks[..chunks.len() - 1].iter().all(|c| !c.done));
+        let joined: String = chunks.iter().map(|c| c.text.as_str()).collect();
+        assert_eq!(joined, format(&content, &options));
+        assert!(!is_running("join"));
+    }
+
+    #[test]
+    fn test_stream_stops_when_cancelled() {
+        let options = CjkFormatOptions::default();
+        let content = "中文abc\n".repeat(STREAM_LINES * 3);
+
+        start("cancel");
+        let mut chunks = Vec::new();
+        format_chunks("cancel", &content, &options, |chunk| {
+            chunks.push(chunk);
+            cancel_cjk_format("cancel".to_string());
+        });
+        assert_eq!(chunks.len(), 1);
+        assert!(!chunks[0].done);
+    }
+}
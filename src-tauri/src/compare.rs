@@ -0,0 +1,299 @@
+//! Compare two documents.
+//!
+//! Differences are grouped into hunks with a few lines of unchanged
+//! context, like a unified diff. In word mode, changed lines also carry
+//! segments marking which words changed, so the compare view can
+//! highlight edits inside a line. Each side reports when it was last
+//! modified to help tell which of two drafts is newer.
+
+use serde::{Deserialize, Serialize};
+use similar::{Algorithm, ChangeTag, DiffTag, TextDiff};
+use std::fs;
+use std::ops::Range;
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+use crate::{history, text_encoding};
+
+/// Unchanged lines shown around each change
+const CONTEXT_LINES: usize = 3;
+
+/// Time after which the diff settles for a coarser result
+const DIFF_TIMEOUT: Duration = Duration::from_secs(2);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum DiffKind {
+    Equal,
+    Insert,
+    Delete,
+}
+
+impl From<ChangeTag> for DiffKind {
+    fn from(tag: ChangeTag) -> Self {
+        match tag {
+            ChangeTag::Equal => DiffKind::Equal,
+            ChangeTag::Insert => DiffKind::Insert,
+            ChangeTag::Delete => DiffKind::Delete,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum DiffMode {
+    #[default]
+    Lines,
+    Words,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Segment {
+    pub text: String,
+    /// Part of the edit rather than unchanged text of the line
+    pub changed: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct HunkLine {
+    pub kind: DiffKind,
+    pub text: String,
+    /// 1-based line in the old text (absent for inserted lines)
+    #[serde(rename = "oldLine")]
+    pub old_line: Option<usize>,
+    /// 1-based line in the new text (absent for deleted lines)
+    #[serde(rename = "newLine")]
+    pub new_line: Option<usize>,
+    /// Word-level changes of a changed line (word mode only)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub segments: Option<Vec<Segment>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DiffHunk {
+    /// 1-based first line of the hunk in the old text
+    #[serde(rename = "oldStart")]
+    pub old_start: usize,
+    #[serde(rename = "oldLines")]
+    pub old_lines: usize,
+    #[serde(rename = "newStart")]
+    pub new_start: usize,
+    #[serde(rename = "newLines")]
+    pub new_lines: usize,
+    pub lines: Vec<HunkLine>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct DocumentDiff {
+    pub hunks: Vec<DiffHunk>,
+    pub insertions: usize,
+    pub deletions: usize,
+    /// Unix timestamps in milliseconds, when known
+    #[serde(rename = "oldModified")]
+    pub old_modified: Option<i64>,
+    #[serde(rename = "newModified")]
+    pub new_modified: Option<i64>,
+}
+
+/// Diff `old` against `new`.
+pub fn diff(old: &str, new: &str, mode: DiffMode) -> DocumentDiff {
+    let text_diff = TextDiff::configure()
+        .algorithm(Algorithm::Patience)
+        .timeout(DIFF_TIMEOUT)
+        .diff_lines(old, new);
+    let deadline = Instant::now() + DIFF_TIMEOUT;
+    let mut result = DocumentDiff::default();
+
+    for group in text_diff.grouped_ops(CONTEXT_LINES) {
+        let (Some(first), Some(last)) = (group.first(), group.last()) else {
+            continue;
+        };
+        let old_range = first.old_range().start..last.old_range().end;
+        let new_range = first.new_range().start..last.new_range().end;
+        let mut lines = Vec::new();
+        for op in &group {
+            // Word segments are only computed when asked for
+            if mode == DiffMode::Lines {
+                for change in text_diff.iter_changes(op) {
+                    lines.push(HunkLine {
+                        kind: change.tag().into(),
+                        text: change.value().trim_end_matches(['\n', '\r']).to_string(),
+                        old_line: change.old_index().map(|i| i + 1),
+                        new_line: change.new_index().map(|i| i + 1),
+                        segments: None,
+                    });
+                }
+                continue;
+            }
+            for change in text_diff.iter_inline_changes_deadline(op, Some(deadline)) {
+                let kind = DiffKind::from(change.tag());
+                let segments: Vec<Segment> = change
+                    .iter_strings_lossy()
+                    .map(|(changed, text)| Segment {
+                        text: text.trim_end_matches(['\n', '\r']).to_string(),
+                        changed,
+                    })
+                    .filter(|segment| !segment.text.is_empty())
+                    .collect();
+                let text: String = segments.iter().map(|s| s.text.as_str()).collect();
+                lines.push(HunkLine {
+                    kind,
+                    text,
+                    old_line: change.old_index().map(|i| i + 1),
+                    new_line: change.new_index().map(|i| i + 1),
+                    segments: (kind != DiffKind::Equal).then_some(segments),
+                });
+            }
+        }
+        for line in &lines {
+            match line.kind {
+                DiffKind::Equal => {}
+                DiffKind::Insert => result.insertions += 1,
+                DiffKind::Delete => result.deletions += 1,
+            }
+        }
+        result.hunks.push(DiffHunk {
+            old_start: old_range.start + 1,
+            old_lines: old_range.len(),
+            new_start: new_range.start + 1,
+            new_lines: new_range.len(),
+            lines,
+        });
+    }
+    result
+}
+
+/// Changed regions of `new` against `old` as pairs of 0-based line ranges
+/// (old lines replaced, new lines replacing them). Lines are compared
+/// without their line endings.
+pub fn changed_line_ranges(old: &str, new: &str) -> Vec<(Range<usize>, Range<usize>)> {
+    let old: Vec<&str> = old.lines().collect();
+    let new: Vec<&str> = new.lines().collect();
+    let deadline = Instant::now() + DIFF_TIMEOUT;
+    similar::capture_diff_slices_deadline(Algorithm::Patience, &old, &new, Some(deadline))
+        .into_iter()
+        .filter(|op| op.tag() != DiffTag::Equal)
+        .map(|op| (op.old_range(), op.new_range()))
+        .collect()
+}
+
+fn read(path: &Path) -> Result<(String, Option<i64>), String> {
+    let bytes = fs::read(path).map_err(|e| format!("Failed to read {}: {e}", path.display()))?;
+    let modified = fs::metadata(path)
+        .and_then(|m| m.modified())
+        .ok()
+        .map(|time| chrono::DateTime::<chrono::Utc>::from(time).timestamp_millis());
+    Ok((text_encoding::decode(&bytes).content, modified))
+}
+
+/// Compare two files.
+#[tauri::command]
+pub fn diff_files(
+    path_a: String,
+    path_b: String,
+    mode: Option<DiffMode>,
+) -> Result<DocumentDiff, String> {
+    let (old, old_modified) = read(Path::new(&path_a))?;
+    let (new, new_modified) = read(Path::new(&path_b))?;
+    Ok(DocumentDiff {
+        old_modified,
+        new_modified,
+        ..diff(&old, &new, mode.unwrap_or_default())
+    })
+}
+
+/// Compare a revision from the document's history with the document on
+/// disk.
+#[tauri::command]
+pub fn diff_against_history(
+    path: String,
+    revision: String,
+    mode: Option<DiffMode>,
+) -> Result<DocumentDiff, String> {
+    let old = history::get_revision(path.clone(), revision.clone())?;
+    let (new, new_modified) = read(Path::new(&path))?;
+    Ok(DocumentDiff {
+        // Revision ids are their creation time
+        old_modified: revision.parse().ok(),
+        new_modified,
+        ..diff(&old, &new, mode.unwrap_or_default())
+    })
+}
+
+/// Compare the document on disk with `content`, e.g. unsaved editor text.
+#[tauri::command]
+pub fn diff_against_disk(
+    path: String,
+    content: String,
+    mode: Option<DiffMode>,
+) -> Result<DocumentDiff, String> {
+    let (old, old_modified) = read(Path::new(&path))?;
+    Ok(DocumentDiff {
+        old_modified,
+        ..diff(&old, &content, mode.unwrap_or_default())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_diff_hunks() {
+        let old: String = (1..=20).map(|i| format!("line {i}\n")).collect();
+        let new = old
+            .replace("line 5\n", "line five\n")
+            .replace("line 18\n", "");
+        let result = diff(&old, &new, DiffMode::Lines);
+        assert_eq!((result.insertions, result.deletions), (1, 2));
+        assert_eq!(result.hunks.len(), 2);
+
+        let first = &result.hunks[0];
+        assert_eq!((first.old_start, first.old_lines), (2, 7));
+        assert_eq!((first.new_start, first.new_lines), (2, 7));
+        let changed: Vec<_> = first
+            .lines
+            .iter()
+            .filter(|l| l.kind != DiffKind::Equal)
+            .map(|l| (l.kind, l.text.as_str(), l.old_line, l.new_line))
+            .collect();
+        assert_eq!(
+            changed,
+            vec![
+                (DiffKind::Delete, "line 5", Some(5), None),
+                (DiffKind::Insert, "line five", None, Some(5)),
+            ]
+        );
+        assert!(first.lines.iter().all(|l| l.segments.is_none()));
+    }
+
+    #[test]
+    fn test_diff_words() {
+        let result = diff(
+            "The quick brown fox.\n",
+            "The slow brown fox.\n",
+            DiffMode::Words,
+        );
+        let lines = &result.hunks[0].lines;
+        let changed: Vec<_> = lines[1]
+            .segments
+            .as_ref()
+            .unwrap()
+            .iter()
+            .filter(|s| s.changed)
+            .map(|s| s.text.as_str())
+            .collect();
+        assert_eq!(changed, vec!["slow"]);
+        assert_eq!(lines[1].text, "The slow brown fox.");
+    }
+
+    #[test]
+    fn test_changed_line_ranges() {
+        assert_eq!(
+            changed_line_ranges("a\nb\nc\nd\n", "a\nx\nc\nd\ne\n"),
+            vec![(1..2, 1..2), (4..4, 4..5)]
+        );
+        assert!(changed_line_ranges("same\n", "same").is_empty());
+        assert_eq!(changed_line_ranges("", "new"), vec![(0..0, 0..1)]);
+    }
+}
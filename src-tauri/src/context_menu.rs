@@ -0,0 +1,249 @@
+//! Native context menus built from a frontend template.
+//!
+//! The frontend describes the menu as JSON and calls `show_context_menu` on
+//! right-click. Item ids are namespaced with `ctx:` so clicks can be told
+//! apart from the menu bar in `menu_events::handle_menu_event`; the window
+//! that opened the menu receives `context-menu:clicked` with the item's id.
+//! `revealInFileManager` entries are handled here instead.
+
+use serde::Deserialize;
+use std::sync::Mutex;
+use tauri::menu::{CheckMenuItem, IsMenuItem, Menu, MenuItem, PredefinedMenuItem, Submenu};
+use tauri::{AppHandle, Emitter, Manager};
+
+/// Id prefix of context menu items
+pub const CONTEXT_ITEM_PREFIX: &str = "ctx:";
+
+/// Id of the built-in reveal item
+const REVEAL_ITEM_ID: &str = "ctx:reveal-in-file-manager";
+
+/// Label of the window that opened the current context menu
+static POPUP_LABEL: Mutex<Option<String>> = Mutex::new(None);
+
+/// Path revealed by the current context menu's reveal item
+static REVEAL_PATH: Mutex<Option<String>> = Mutex::new(None);
+
+/// One entry of a context menu template.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum ContextMenuEntry {
+    Item {
+        id: String,
+        text: String,
+        #[serde(default = "enabled_by_default")]
+        enabled: bool,
+        /// Shown as a check item when set
+        #[serde(default)]
+        checked: Option<bool>,
+        /// Displayed shortcut hint, e.g. `CmdOrCtrl+C`
+        #[serde(default)]
+        accelerator: Option<String>,
+    },
+    Separator,
+    Submenu {
+        text: String,
+        #[serde(default = "enabled_by_default")]
+        enabled: bool,
+        items: Vec<ContextMenuEntry>,
+    },
+    /// Platform item handled natively: `cut`, `copy`, `paste`, `selectAll`,
+    /// `undo`, `redo` or `services` (macOS)
+    Predefined {
+        name: String,
+        #[serde(default)]
+        text: Option<String>,
+    },
+    /// "Reveal in Finder" (or the platform's equivalent) for `path`
+    RevealInFileManager {
+        path: String,
+        #[serde(default)]
+        text: Option<String>,
+    },
+}
+
+fn enabled_by_default() -> bool {
+    true
+}
+
+/// Build a native item for a template entry. Unknown predefined names are
+/// skipped.
+fn build_entry(
+    app: &AppHandle,
+    entry: &ContextMenuEntry,
+) -> tauri::Result<Option<Box<dyn IsMenuItem<tauri::Wry>>>> {
+    let item: Box<dyn IsMenuItem<tauri::Wry>> = match entry {
+        ContextMenuEntry::Item {
+            id,
+            text,
+            enabled,
+            checked,
+            accelerator,
+        } => {
+            let id = format!("{}{}", CONTEXT_ITEM_PREFIX, id);
+            match checked {
+                Some(checked) => Box::new(CheckMenuItem::with_id(
+                    app,
+                    id,
+                    text,
+                    *enabled,
+                    *checked,
+                    accelerator.as_deref(),
+                )?),
+                None => Box::new(MenuItem::with_id(
+                    app,
+                    id,
+                    text,
+                    *enabled,
+                    accelerator.as_deref(),
+                )?),
+            }
+        }
+        ContextMenuEntry::Separator => Box::new(PredefinedMenuItem::separator(app)?),
+        ContextMenuEntry::Submenu {
+            text,
+            enabled,
+            items,
+        } => {
+            let submenu = Submenu::new(app, text, *enabled)?;
+            for child in items {
+                if let Some(child) = build_entry(app, child)? {
+                    submenu.append(child.as_ref())?;
+                }
+            }
+            Box::new(submenu)
+        }
+        ContextMenuEntry::Predefined { name, text } => {
+            let text = text.as_deref();
+            match name.as_str() {
+                "cut" => Box::new(PredefinedMenuItem::cut(app, text)?),
+                "copy" => Box::new(PredefinedMenuItem::copy(app, text)?),
+                "paste" => Box::new(PredefinedMenuItem::paste(app, text)?),
+                "selectAll" => Box::new(PredefinedMenuItem::select_all(app, text)?),
+                "undo" => Box::new(PredefinedMenuItem::undo(app, text)?),
+                "redo" => Box::new(PredefinedMenuItem::redo(app, text)?),
+                #[cfg(target_os = "macos")]
+                "services" => Box::new(PredefinedMenuItem::services(app, text)?),
+                _ => return Ok(None),
+            }
+        }
+        ContextMenuEntry::RevealInFileManager { path, text } => {
+            if let Ok(mut reveal) = REVEAL_PATH.lock() {
+                *reveal = Some(path.clone());
+            }
+            let text = text
+                .clone()
+                .unwrap_or_else(|| crate::menu_i18n::tr(crate::reveal::REVEAL_LABEL));
+            Box::new(MenuItem::with_id(
+                app,
+                REVEAL_ITEM_ID,
+                text,
+                true,
+                None::<&str>,
+            )?)
+        }
+    };
+    Ok(Some(item))
+}
+
+/// Forward a context menu click to the window that opened the menu.
+pub fn handle_click(app: &AppHandle, item_id: &str) {
+    if item_id == REVEAL_ITEM_ID {
+        let path = REVEAL_PATH.lock().ok().and_then(|p| p.clone());
+        if let Some(path) = path {
+            if let Err(_e) = crate::reveal::reveal(std::path::Path::new(&path)) {
+                #[cfg(debug_assertions)]
+                eprintln!("[context_menu] {}", _e);
+            }
+        }
+        return;
+    }
+    let label = POPUP_LABEL.lock().ok().and_then(|l| l.clone());
+    if let Some(label) = label {
+        let _ = app.emit_to(label.as_str(), "context-menu:clicked", (&label, item_id));
+    }
+}
+
+/// Show a native context menu at `(x, y)` (logical pixels relative to the
+/// window). The clicked item's id is sent back as `context-menu:clicked`.
+#[tauri::command]
+pub fn show_context_menu(
+    app: AppHandle,
+    label: String,
+    template: Vec<ContextMenuEntry>,
+    x: f64,
+    y: f64,
+) -> Result<(), String> {
+    let window = app
+        .get_webview_window(&label)
+        .ok_or_else(|| format!("Window '{}' not found", label))?;
+
+    let menu = Menu::new(&app).map_err(|e| format!("Failed to create context menu: {e}"))?;
+    for entry in &template {
+        let item =
+            build_entry(&app, entry).map_err(|e| format!("Failed to create context menu: {e}"))?;
+        if let Some(item) = item {
+            menu.append(item.as_ref())
+                .map_err(|e| format!("Failed to create context menu: {e}"))?;
+        }
+    }
+
+    if let Ok(mut popup) = POPUP_LABEL.lock() {
+        *popup = Some(label);
+    }
+    window
+        .popup_menu_at(&menu, tauri::LogicalPosition { x, y })
+        .map_err(|e| format!("Failed to show context menu: {e}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_template() {
+        let template: Vec<ContextMenuEntry> = serde_json::from_str(
+            r#"[
+                { "type": "item", "id": "reveal", "text": "Reveal in Finder" },
+                { "type": "item", "id": "wrap", "text": "Word Wrap", "checked": true },
+                { "type": "separator" },
+                { "type": "submenu", "text": "Insert", "items": [
+                    { "type": "item", "id": "table", "text": "Table", "enabled": false }
+                ] },
+                { "type": "predefined", "name": "services" },
+                { "type": "revealInFileManager", "path": "/notes/a.md" }
+            ]"#,
+        )
+        .unwrap();
+
+        assert_eq!(template.len(), 6);
+        match &template[0] {
+            ContextMenuEntry::Item {
+                enabled, checked, ..
+            } => {
+                assert!(*enabled);
+                assert_eq!(*checked, None);
+            }
+            other => panic!("unexpected entry: {:?}", other),
+        }
+        match &template[3] {
+            ContextMenuEntry::Submenu { items, .. } => assert_eq!(
+                items[0],
+                ContextMenuEntry::Item {
+                    id: "table".into(),
+                    text: "Table".into(),
+                    enabled: false,
+                    checked: None,
+                    accelerator: None,
+                }
+            ),
+            other => panic!("unexpected entry: {:?}", other),
+        }
+        assert_eq!(
+            template[5],
+            ContextMenuEntry::RevealInFileManager {
+                path: "/notes/a.md".into(),
+                text: None,
+            }
+        );
+    }
+}
@@ -0,0 +1,344 @@
+//! Crash reports.
+//!
+//! A panic hook writes a report (message, location, backtrace) to
+//! `~/.vmark/crashes/<id>.json`. Crashes that never reach Rust (a killed
+//! process, a native crash in the webview) are caught on the next launch: a
+//! `running` marker is written at startup and removed on a clean exit, so a
+//! marker left behind produces an "unclean exit" report. Reports also carry
+//! the tail of the newest log file. Nothing leaves the machine unless the
+//! user submits a report, which opens a prefilled GitHub issue.
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// Log lines included in a report
+const LOG_TAIL_LINES: usize = 50;
+
+/// Longest report body put into an issue URL
+const MAX_ISSUE_BODY: usize = 6000;
+
+const ISSUE_URL: &str = "https://github.com/xiaolai/vmark/issues/new";
+
+/// Directory of the app log files, known once the app is set up
+static LOG_DIR: Mutex<Option<PathBuf>> = Mutex::new(None);
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum CrashKind {
+    Panic,
+    /// The previous run ended without a clean exit
+    UncleanExit,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CrashReport {
+    pub id: String,
+    pub kind: CrashKind,
+    pub message: String,
+    #[serde(default)]
+    pub location: Option<String>,
+    #[serde(default)]
+    pub thread: Option<String>,
+    #[serde(default)]
+    pub backtrace: Option<String>,
+    pub version: String,
+    pub os: String,
+    pub arch: String,
+    /// Unix timestamp in milliseconds
+    #[serde(rename = "createdAt")]
+    pub created_at: i64,
+    #[serde(rename = "logTail", default)]
+    pub log_tail: Vec<String>,
+    #[serde(default)]
+    pub submitted: bool,
+}
+
+/// What the crash list shows.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CrashReportSummary {
+    pub id: String,
+    pub kind: CrashKind,
+    pub message: String,
+    #[serde(rename = "createdAt")]
+    pub created_at: i64,
+    pub submitted: bool,
+}
+
+fn crashes_dir() -> Option<PathBuf> {
+    dirs::home_dir().map(|home| home.join(".vmark").join("crashes"))
+}
+
+fn marker_path(dir: &Path) -> PathBuf {
+    dir.join("running")
+}
+
+/// Report ids are generated here; anything else can't name a report file.
+fn is_report_id(id: &str) -> bool {
+    !id.is_empty() && id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+}
+
+fn new_report(kind: CrashKind, message: String, created_at: i64) -> CrashReport {
+    let suffix = match kind {
+        CrashKind::Panic => "panic",
+        CrashKind::UncleanExit => "unclean-exit",
+    };
+    CrashReport {
+        id: format!("{created_at}-{suffix}"),
+        kind,
+        message,
+        location: None,
+        thread: None,
+        backtrace: None,
+        version: env!("CARGO_PKG_VERSION").to_string(),
+        os: std::env::consts::OS.to_string(),
+        arch: std::env::consts::ARCH.to_string(),
+        created_at,
+        log_tail: Vec::new(),
+        submitted: false,
+    }
+}
+
+/// Last lines of the most recently modified `.log` file in `dir`.
+fn log_tail(dir: &Path, lines: usize) -> Vec<String> {
+    let newest = fs::read_dir(dir)
+        .ok()
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter(|entry| entry.path().extension().is_some_and(|ext| ext == "log"))
+        .max_by_key(|entry| entry.metadata().and_then(|m| m.modified()).ok());
+    let Some(content) = newest.and_then(|entry| fs::read_to_string(entry.path()).ok()) else {
+        return Vec::new();
+    };
+    let all: Vec<&str> = content.lines().collect();
+    all[all.len().saturating_sub(lines)..]
+        .iter()
+        .map(|line| line.to_string())
+        .collect()
+}
+
+fn write_report(dir: &Path, report: &CrashReport) -> Result<(), String> {
+    fs::create_dir_all(dir).map_err(|e| format!("Failed to create crashes directory: {e}"))?;
+    let content = serde_json::to_string_pretty(report)
+        .map_err(|e| format!("Failed to serialize crash report: {e}"))?;
+    fs::write(dir.join(format!("{}.json", report.id)), content)
+        .map_err(|e| format!("Failed to write crash report: {e}"))
+}
+
+fn read_report(dir: &Path, id: &str) -> Result<CrashReport, String> {
+    if !is_report_id(id) {
+        return Err(format!("Invalid crash report id: {id}"));
+    }
+    let content = fs::read_to_string(dir.join(format!("{id}.json")))
+        .map_err(|e| format!("Failed to read crash report: {e}"))?;
+    serde_json::from_str(&content).map_err(|e| format!("Failed to parse crash report: {e}"))
+}
+
+/// Reports in `dir`, newest first.
+fn list_reports(dir: &Path) -> Vec<CrashReportSummary> {
+    let mut reports: Vec<CrashReportSummary> = fs::read_dir(dir)
+        .ok()
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter_map(|entry| {
+            let content = fs::read_to_string(entry.path()).ok()?;
+            let report: CrashReport = serde_json::from_str(&content).ok()?;
+            Some(CrashReportSummary {
+                id: report.id,
+                kind: report.kind,
+                message: report.message,
+                created_at: report.created_at,
+                submitted: report.submitted,
+            })
+        })
+        .collect();
+    reports.sort_by_key(|report| std::cmp::Reverse(report.created_at));
+    reports
+}
+
+fn current_log_tail() -> Vec<String> {
+    LOG_DIR
+        .lock()
+        .ok()
+        .and_then(|dir| dir.clone())
+        .map(|dir| log_tail(&dir, LOG_TAIL_LINES))
+        .unwrap_or_default()
+}
+
+/// Record an unclean exit if the previous run left its marker, then mark
+/// this run as running.
+fn check_previous_run(dir: &Path, now: i64) {
+    let marker = marker_path(dir);
+    if let Ok(started) = fs::read_to_string(&marker) {
+        let mut report = new_report(
+            CrashKind::UncleanExit,
+            format!(
+                "VMark quit unexpectedly (run started at {})",
+                started.trim()
+            ),
+            now,
+        );
+        report.log_tail = current_log_tail();
+        let _ = write_report(dir, &report);
+    }
+    let _ = fs::create_dir_all(dir);
+    let _ = fs::write(&marker, now.to_string());
+}
+
+/// Install the panic hook and check how the previous run ended. Called
+/// at the start of `setup`.
+pub fn install() {
+    let Some(dir) = crashes_dir() else {
+        return;
+    };
+    check_previous_run(&dir, chrono::Utc::now().timestamp_millis());
+
+    let default_hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        let message = info
+            .payload()
+            .downcast_ref::<&str>()
+            .map(|s| s.to_string())
+            .or_else(|| info.payload().downcast_ref::<String>().cloned())
+            .unwrap_or_else(|| "Unknown panic".to_string());
+        let mut report = new_report(
+            CrashKind::Panic,
+            message,
+            chrono::Utc::now().timestamp_millis(),
+        );
+        report.location = info
+            .location()
+            .map(|l| format!("{}:{}:{}", l.file(), l.line(), l.column()));
+        report.thread = std::thread::current().name().map(str::to_string);
+        report.backtrace = Some(std::backtrace::Backtrace::force_capture().to_string());
+        report.log_tail = current_log_tail();
+        let _ = write_report(&dir, &report);
+        default_hook(info);
+    }));
+}
+
+/// Remember where the log files are for the log tail.
+pub fn set_log_dir(dir: PathBuf) {
+    if let Ok(mut log_dir) = LOG_DIR.lock() {
+        *log_dir = Some(dir);
+    }
+}
+
+/// The app is exiting normally; the next launch must not report a crash.
+pub fn mark_clean_exit() {
+    if let Some(dir) = crashes_dir() {
+        let _ = fs::remove_file(marker_path(&dir));
+    }
+}
+
+/// Issue body for a report, cut to fit into a URL.
+fn issue_body(report: &CrashReport) -> String {
+    let mut body = format!(
+        "**VMark {}** on {} ({})\n\n**{:?}**: {}\n",
+        report.version, report.os, report.arch, report.kind, report.message
+    );
+    if let Some(location) = &report.location {
+        body.push_str(&format!("at `{location}`\n"));
+    }
+    if let Some(backtrace) = &report.backtrace {
+        body.push_str(&format!(
+            "\n<details><summary>Backtrace</summary>\n\n```\n{backtrace}\n```\n</details>\n"
+        ));
+    }
+    if !report.log_tail.is_empty() {
+        body.push_str(&format!(
+            "\n<details><summary>Log</summary>\n\n```\n{}\n```\n</details>\n",
+            report.log_tail.join("\n")
+        ));
+    }
+    if body.len() > MAX_ISSUE_BODY {
+        let mut end = MAX_ISSUE_BODY;
+        while !body.is_char_boundary(end) {
+            end -= 1;
+        }
+        body.truncate(end);
+        body.push_str("\n\n(truncated)");
+    }
+    body
+}
+
+#[tauri::command]
+pub fn list_crash_reports() -> Vec<CrashReportSummary> {
+    crashes_dir()
+        .map(|dir| list_reports(&dir))
+        .unwrap_or_default()
+}
+
+/// Full report, for previewing before submitting.
+#[tauri::command]
+pub fn read_crash_report(id: String) -> Result<CrashReport, String> {
+    let dir = crashes_dir().ok_or("Cannot find home directory")?;
+    read_report(&dir, &id)
+}
+
+/// Open a prefilled GitHub issue for a report and mark it submitted. The
+/// user reviews and sends the issue in the browser.
+#[tauri::command]
+pub fn submit_crash_report(id: String) -> Result<(), String> {
+    let dir = crashes_dir().ok_or("Cannot find home directory")?;
+    let mut report = read_report(&dir, &id)?;
+    let title = format!("Crash: {}", report.message.lines().next().unwrap_or(""));
+    let url = format!(
+        "{ISSUE_URL}?title={}&body={}",
+        urlencoding::encode(&title),
+        urlencoding::encode(&issue_body(&report))
+    );
+    tauri_plugin_opener::open_url(url, None::<&str>)
+        .map_err(|e| format!("Failed to open issue page: {e}"))?;
+    report.submitted = true;
+    write_report(&dir, &report)
+}
+
+#[tauri::command]
+pub fn delete_crash_report(id: String) -> Result<(), String> {
+    if !is_report_id(&id) {
+        return Err(format!("Invalid crash report id: {id}"));
+    }
+    let dir = crashes_dir().ok_or("Cannot find home directory")?;
+    fs::remove_file(dir.join(format!("{id}.json")))
+        .map_err(|e| format!("Failed to delete crash report: {e}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_unclean_exit_detection() {
+        let dir = tempdir().unwrap();
+
+        check_previous_run(dir.path(), 1000);
+        assert!(list_reports(dir.path()).is_empty());
+
+        // The marker is still there on the next launch: the run crashed
+        check_previous_run(dir.path(), 2000);
+        let reports = list_reports(dir.path());
+        assert_eq!(reports.len(), 1);
+        assert_eq!(reports[0].kind, CrashKind::UncleanExit);
+        assert!(reports[0].message.contains("1000"));
+
+        let report = read_report(dir.path(), &reports[0].id).unwrap();
+        assert_eq!(report.created_at, 2000);
+        assert!(read_report(dir.path(), "../running").is_err());
+    }
+
+    #[test]
+    fn test_log_tail() {
+        let dir = tempdir().unwrap();
+        let lines: Vec<String> = (1..=80).map(|i| format!("line {i}")).collect();
+        fs::write(dir.path().join("vmark.log"), lines.join("\n")).unwrap();
+        fs::write(dir.path().join("notes.txt"), "not a log").unwrap();
+
+        let tail = log_tail(dir.path(), 3);
+        assert_eq!(tail, vec!["line 78", "line 79", "line 80"]);
+    }
+}
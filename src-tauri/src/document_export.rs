@@ -0,0 +1,293 @@
+//! Export rendered documents as Word (DOCX) and EPUB files.
+//!
+//! The frontend renders the markdown (the same way HTML export does), with
+//! local images embedded as data URIs and the markup serialized as XHTML.
+//! Both formats are zip containers around that markup:
+//!
+//! - EPUB 3 with a single content document and a one-entry table of contents.
+//! - DOCX whose body is an `altChunk` holding the HTML, which Word converts
+//!   to native paragraphs, tables and images when the file is opened.
+
+use serde::Deserialize;
+use std::io::{Cursor, Write};
+use std::path::Path;
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipWriter};
+
+use crate::document_io::{self, SaveOptions};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    Docx,
+    Epub,
+}
+
+/// Escape text for XML content and attribute values.
+fn escape_xml(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&apos;"),
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+/// Complete XHTML document around a rendered body.
+fn xhtml_document(title: &str, css: &str, body: &str) -> String {
+    format!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+         <!DOCTYPE html>\n\
+         <html xmlns=\"http://www.w3.org/1999/xhtml\" xmlns:epub=\"http://www.idpf.org/2007/ops\">\n\
+         <head>\n<meta charset=\"UTF-8\"/>\n<title>{}</title>\n<style>\n{}\n</style>\n</head>\n\
+         <body>\n{}\n</body>\n</html>\n",
+        escape_xml(title),
+        escape_xml(css),
+        body
+    )
+}
+
+struct Archive {
+    zip: ZipWriter<Cursor<Vec<u8>>>,
+}
+
+impl Archive {
+    fn new() -> Self {
+        Self {
+            zip: ZipWriter::new(Cursor::new(Vec::new())),
+        }
+    }
+
+    fn add(&mut self, name: &str, content: &str, method: CompressionMethod) -> Result<(), String> {
+        let options = SimpleFileOptions::default().compression_method(method);
+        self.zip
+            .start_file(name, options)
+            .map_err(|e| format!("Failed to write archive: {e}"))?;
+        self.zip
+            .write_all(content.as_bytes())
+            .map_err(|e| format!("Failed to write archive: {e}"))
+    }
+
+    fn finish(self) -> Result<Vec<u8>, String> {
+        self.zip
+            .finish()
+            .map(Cursor::into_inner)
+            .map_err(|e| format!("Failed to write archive: {e}"))
+    }
+}
+
+fn build_epub(title: &str, language: &str, css: &str, body: &str) -> Result<Vec<u8>, String> {
+    let content = xhtml_document(title, css, body);
+    let title = escape_xml(title);
+    let language = escape_xml(language);
+    let id = uuid::Uuid::new_v4();
+    let modified = chrono::Utc::now().format("%Y-%m-%dT%H:%M:%SZ");
+
+    let mut archive = Archive::new();
+    // The mimetype must come first and be stored uncompressed
+    archive.add(
+        "mimetype",
+        "application/epub+zip",
+        CompressionMethod::Stored,
+    )?;
+    archive.add(
+        "META-INF/container.xml",
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+         <container version=\"1.0\" xmlns=\"urn:oasis:names:tc:opendocument:xmlns:container\">\n\
+         <rootfiles>\n\
+         <rootfile full-path=\"OEBPS/content.opf\" media-type=\"application/oebps-package+xml\"/>\n\
+         </rootfiles>\n\
+         </container>\n",
+        CompressionMethod::Deflated,
+    )?;
+    archive.add(
+        "OEBPS/content.opf",
+        &format!(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+             <package xmlns=\"http://www.idpf.org/2007/opf\" version=\"3.0\" unique-identifier=\"book-id\">\n\
+             <metadata xmlns:dc=\"http://purl.org/dc/elements/1.1/\">\n\
+             <dc:identifier id=\"book-id\">urn:uuid:{id}</dc:identifier>\n\
+             <dc:title>{title}</dc:title>\n\
+             <dc:language>{language}</dc:language>\n\
+             <meta property=\"dcterms:modified\">{modified}</meta>\n\
+             </metadata>\n\
+             <manifest>\n\
+             <item id=\"nav\" href=\"nav.xhtml\" media-type=\"application/xhtml+xml\" properties=\"nav\"/>\n\
+             <item id=\"content\" href=\"content.xhtml\" media-type=\"application/xhtml+xml\"/>\n\
+             </manifest>\n\
+             <spine>\n<itemref idref=\"content\"/>\n</spine>\n\
+             </package>\n"
+        ),
+        CompressionMethod::Deflated,
+    )?;
+    archive.add(
+        "OEBPS/nav.xhtml",
+        &format!(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+             <!DOCTYPE html>\n\
+             <html xmlns=\"http://www.w3.org/1999/xhtml\" xmlns:epub=\"http://www.idpf.org/2007/ops\">\n\
+             <head><title>{title}</title></head>\n\
+             <body>\n<nav epub:type=\"toc\">\n<ol><li><a href=\"content.xhtml\">{title}</a></li></ol>\n</nav>\n</body>\n\
+             </html>\n"
+        ),
+        CompressionMethod::Deflated,
+    )?;
+    archive.add("OEBPS/content.xhtml", &content, CompressionMethod::Deflated)?;
+    archive.finish()
+}
+
+fn build_docx(title: &str, css: &str, body: &str) -> Result<Vec<u8>, String> {
+    let mut archive = Archive::new();
+    archive.add(
+        "[Content_Types].xml",
+        "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\n\
+         <Types xmlns=\"http://schemas.openxmlformats.org/package/2006/content-types\">\n\
+         <Default Extension=\"rels\" ContentType=\"application/vnd.openxmlformats-package.relationships+xml\"/>\n\
+         <Default Extension=\"xml\" ContentType=\"application/xml\"/>\n\
+         <Default Extension=\"xhtml\" ContentType=\"application/xhtml+xml\"/>\n\
+         <Override PartName=\"/word/document.xml\" ContentType=\"application/vnd.openxmlformats-officedocument.wordprocessingml.document.main+xml\"/>\n\
+         </Types>\n",
+        CompressionMethod::Deflated,
+    )?;
+    archive.add(
+        "_rels/.rels",
+        "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\n\
+         <Relationships xmlns=\"http://schemas.openxmlformats.org/package/2006/relationships\">\n\
+         <Relationship Id=\"rId1\" Type=\"http://schemas.openxmlformats.org/officeDocument/2006/relationships/officeDocument\" Target=\"word/document.xml\"/>\n\
+         </Relationships>\n",
+        CompressionMethod::Deflated,
+    )?;
+    archive.add(
+        "word/_rels/document.xml.rels",
+        "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\n\
+         <Relationships xmlns=\"http://schemas.openxmlformats.org/package/2006/relationships\">\n\
+         <Relationship Id=\"htmlChunk\" Type=\"http://schemas.openxmlformats.org/officeDocument/2006/relationships/aFChunk\" Target=\"content.xhtml\"/>\n\
+         </Relationships>\n",
+        CompressionMethod::Deflated,
+    )?;
+    archive.add(
+        "word/document.xml",
+        "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\n\
+         <w:document xmlns:w=\"http://schemas.openxmlformats.org/wordprocessingml/2006/main\" \
+         xmlns:r=\"http://schemas.openxmlformats.org/officeDocument/2006/relationships\">\n\
+         <w:body>\n<w:altChunk r:id=\"htmlChunk\"/>\n<w:sectPr/>\n</w:body>\n\
+         </w:document>\n",
+        CompressionMethod::Deflated,
+    )?;
+    archive.add(
+        "word/content.xhtml",
+        &xhtml_document(title, css, body),
+        CompressionMethod::Deflated,
+    )?;
+    archive.finish()
+}
+
+/// Write a rendered document as DOCX or EPUB.
+///
+/// # Arguments
+/// * `body` - Rendered document body as XHTML
+/// * `css` - Stylesheet to embed
+/// * `title` - Document title
+/// * `language` - BCP 47 language tag for EPUB metadata (default `en`)
+/// * `format` - `docx` or `epub`
+/// * `path` - File to create (replaced if it exists)
+#[tauri::command]
+pub fn export_document(
+    body: String,
+    css: String,
+    title: String,
+    language: Option<String>,
+    format: ExportFormat,
+    path: String,
+) -> Result<(), String> {
+    let bytes = match format {
+        ExportFormat::Docx => build_docx(&title, &css, &body)?,
+        ExportFormat::Epub => {
+            let language = language.filter(|l| !l.trim().is_empty());
+            build_epub(&title, language.as_deref().unwrap_or("en"), &css, &body)?
+        }
+    };
+    document_io::save_atomic(Path::new(&path), &bytes, &SaveOptions::default())?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Read;
+    use zip::ZipArchive;
+
+    fn entries(bytes: Vec<u8>) -> Vec<(String, String)> {
+        let mut zip = ZipArchive::new(Cursor::new(bytes)).unwrap();
+        (0..zip.len())
+            .map(|i| {
+                let mut file = zip.by_index(i).unwrap();
+                let mut content = String::new();
+                file.read_to_string(&mut content).unwrap();
+                (file.name().to_string(), content)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_epub_layout() {
+        let bytes = build_epub("A & B", "de", "p{}", "<p>Hi</p>").unwrap();
+        let mut zip = ZipArchive::new(Cursor::new(bytes.clone())).unwrap();
+        assert_eq!(
+            zip.by_index(0).unwrap().compression(),
+            CompressionMethod::Stored
+        );
+
+        let entries = entries(bytes);
+        assert_eq!(
+            entries[0],
+            ("mimetype".into(), "application/epub+zip".into())
+        );
+        let opf = &entries
+            .iter()
+            .find(|(n, _)| n == "OEBPS/content.opf")
+            .unwrap()
+            .1;
+        assert!(opf.contains("<dc:title>A &amp; B</dc:title>"));
+        assert!(opf.contains("<dc:language>de</dc:language>"));
+        let content = &entries
+            .iter()
+            .find(|(n, _)| n == "OEBPS/content.xhtml")
+            .unwrap()
+            .1;
+        assert!(content.contains("<title>A &amp; B</title>"));
+        assert!(content.contains("<p>Hi</p>"));
+    }
+
+    #[test]
+    fn test_docx_wraps_html_chunk() {
+        let entries = entries(build_docx("Doc", "", "<p>Hi</p>").unwrap());
+        let names: Vec<_> = entries.iter().map(|(n, _)| n.as_str()).collect();
+        assert!(names.contains(&"[Content_Types].xml"));
+        assert!(names.contains(&"word/document.xml"));
+        let document = &entries
+            .iter()
+            .find(|(n, _)| n == "word/document.xml")
+            .unwrap()
+            .1;
+        assert!(document.contains("<w:altChunk r:id=\"htmlChunk\"/>"));
+        let chunk = &entries
+            .iter()
+            .find(|(n, _)| n == "word/content.xhtml")
+            .unwrap()
+            .1;
+        assert!(chunk.contains("<p>Hi</p>"));
+    }
+
+    #[test]
+    fn test_stylesheet_is_escaped() {
+        let doc = xhtml_document("t", "ul > li {} </style><script>", "");
+        assert!(doc.contains("ul &gt; li {} &lt;/style&gt;&lt;script&gt;"));
+    }
+}
@@ -0,0 +1,418 @@
+//! Reading and writing documents on disk.
+//!
+//! `save_document` never writes into the document itself: the content goes
+//! to a temp file next to it, which is flushed to disk, given the original
+//! file's permissions and extended attributes, and then renamed over the
+//! original. A crash at any point leaves either the old or the new
+//! document, never a truncated one.
+//!
+//! Very large documents are loaded in chunks (`read_file_range`, or
+//! `stream_file` which pushes `file:chunk` events) instead of one huge IPC
+//! string. Every chunk carries a checksum of its bytes.
+
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File, OpenOptions};
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use tauri::{Emitter, WebviewWindow};
+
+use crate::formatter::{self, FormatConfig};
+use crate::line_endings::{self, LineEnding};
+use crate::normalization::{self, NormalizationForm};
+use crate::write_access::SaveError;
+use crate::{annotations, file_locks, history, text_encoding, typography, watcher};
+
+/// Makes temp file names unique within the process
+static TEMP_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+/// Chunk size of `stream_file` unless the caller picks one
+const DEFAULT_CHUNK_SIZE: usize = 1024 * 1024;
+
+/// Smallest chunk that always fits a whole UTF-8 character
+const MIN_CHUNK_SIZE: usize = 4;
+
+#[derive(Debug, Default, Deserialize)]
+pub struct SaveOptions {
+    /// Keep the replaced version as `<name>.bak` next to the document
+    #[serde(rename = "keepPrevious", default)]
+    pub keep_previous: bool,
+    /// Encoding to write, as reported by `read_text_smart` (default UTF-8)
+    #[serde(default)]
+    pub encoding: Option<String>,
+    /// Start the file with a byte order mark
+    #[serde(default)]
+    pub bom: bool,
+    /// Line endings to write; the content is saved as is when absent
+    #[serde(rename = "lineEnding", default)]
+    pub line_ending: Option<LineEnding>,
+    /// Run `format_markdown` with this style before saving
+    #[serde(default)]
+    pub format: Option<FormatConfig>,
+    /// Run `smarten_typography` for this locale before saving
+    #[serde(default)]
+    pub typography: Option<String>,
+    /// Unicode normalization form to write; the content is saved as is
+    /// when absent
+    #[serde(default)]
+    pub normalization: Option<NormalizationForm>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SaveResult {
+    /// Where the previous version was kept, if requested and there was one
+    #[serde(rename = "previousPath")]
+    pub previous_path: Option<String>,
+    /// The saved content, if the on-save transforms changed it
+    pub formatted: Option<String>,
+}
+
+/// Path of the kept previous version (`notes/a.md` -> `notes/a.md.bak`).
+fn previous_version_path(path: &Path) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(".bak");
+    path.with_file_name(name)
+}
+
+fn temp_path(path: &Path) -> PathBuf {
+    let name = path.file_name().unwrap_or_default().to_string_lossy();
+    let n = TEMP_COUNTER.fetch_add(1, Ordering::Relaxed);
+    path.with_file_name(format!(".{name}.{}-{n}.tmp", std::process::id()))
+}
+
+#[cfg(unix)]
+fn copy_xattrs(from: &Path, to: &Path) {
+    let Ok(names) = xattr::list(from) else {
+        return;
+    };
+    for name in names {
+        if let Ok(Some(value)) = xattr::get(from, &name) {
+            let _ = xattr::set(to, &name, &value);
+        }
+    }
+}
+
+/// Flush a rename to disk; only possible (and needed) on Unix.
+fn sync_dir(_dir: &Path) {
+    #[cfg(unix)]
+    if let Ok(dir) = File::open(_dir) {
+        let _ = dir.sync_all();
+    }
+}
+
+fn write_temp(temp: &Path, content: &[u8], target: &Path, existing: bool) -> Result<(), SaveError> {
+    let mut file = OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(temp)
+        .map_err(|e| SaveError::io(target, "Failed to create temp file", &e))?;
+    file.write_all(content)
+        .map_err(|e| SaveError::io(target, "Failed to write document", &e))?;
+    file.sync_all()
+        .map_err(|e| SaveError::io(target, "Failed to flush document", &e))?;
+
+    if existing {
+        if let Ok(meta) = fs::metadata(target) {
+            fs::set_permissions(temp, meta.permissions())
+                .map_err(|e| SaveError::io(target, "Failed to keep file permissions", &e))?;
+        }
+        #[cfg(unix)]
+        copy_xattrs(target, temp);
+    }
+    Ok(())
+}
+
+/// Atomically replace (or create) the file at `path` with `content`.
+pub fn save_atomic(
+    path: &Path,
+    content: &[u8],
+    options: &SaveOptions,
+) -> Result<SaveResult, SaveError> {
+    // Write through symlinks so the link itself stays in place
+    let target = fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+    let existing = target.is_file();
+    let dir = target
+        .parent()
+        .ok_or_else(|| format!("Invalid document path: {}", path.display()))?;
+
+    // Renaming over a file only needs a writable folder on Unix, so the
+    // read-only flag has to be honored here
+    #[cfg(unix)]
+    if existing
+        && fs::metadata(&target)
+            .map_err(|e| SaveError::io(&target, "Failed to read metadata", &e))?
+            .permissions()
+            .readonly()
+    {
+        return Err(SaveError::read_only(&target));
+    }
+
+    let temp = temp_path(&target);
+    // Registered up front: watchers also see the temp file being created
+    watcher::expect_write(path, &target, &temp, content);
+    if let Err(e) = write_temp(&temp, content, &target, existing) {
+        let _ = fs::remove_file(&temp);
+        return Err(e);
+    }
+
+    let mut previous_path = None;
+    if options.keep_previous && existing {
+        let previous = previous_version_path(&target);
+        if let Err(e) = fs::copy(&target, &previous) {
+            let _ = fs::remove_file(&temp);
+            return Err(SaveError::io(
+                &target,
+                "Failed to keep previous version",
+                &e,
+            ));
+        }
+        previous_path = Some(previous.to_string_lossy().to_string());
+    }
+
+    if let Err(e) = fs::rename(&temp, &target) {
+        let _ = fs::remove_file(&temp);
+        return Err(SaveError::io(&target, "Failed to replace document", &e));
+    }
+    sync_dir(dir);
+    Ok(SaveResult {
+        previous_path,
+        formatted: None,
+    })
+}
+
+/// Save a document atomically (see the module docs) and record the saved
+/// content in its version history. Refuses files another window holds
+/// (see `file_locks`). Failures say why and how to recover (see
+/// `write_access`).
+#[tauri::command]
+pub fn save_document(
+    window: WebviewWindow,
+    path: String,
+    content: String,
+    options: Option<SaveOptions>,
+) -> Result<SaveResult, SaveError> {
+    let path = Path::new(&path);
+    if let Some(owner) = file_locks::other_owner(&path.to_string_lossy(), window.label()) {
+        return Err(SaveError::owned_by_window(path, &owner.label));
+    }
+    let options = options.unwrap_or_default();
+    let mut transformed = match &options.format {
+        Some(config) => formatter::format(&content, config)?,
+        None => content.clone(),
+    };
+    if let Some(locale) = &options.typography {
+        transformed = typography::smarten(&transformed, locale);
+    }
+    if let Some(form) = options.normalization {
+        transformed = normalization::normalize(&transformed, form);
+    }
+    let formatted = (transformed != content).then(|| transformed.clone());
+    let content = transformed;
+    let content = match options.line_ending {
+        Some(ending) => line_endings::apply(&content, ending),
+        None => content,
+    };
+    let bytes = text_encoding::encode(
+        &content,
+        options.encoding.as_deref().unwrap_or("UTF-8"),
+        options.bom,
+    )?;
+    let mut result = save_atomic(path, &bytes, &options)?;
+    result.formatted = formatted;
+    // The save itself succeeded; a missing revision is not worth failing it
+    if let Err(_e) = history::record(path, &content) {
+        #[cfg(debug_assertions)]
+        eprintln!("[document_io] {}", _e);
+    }
+    if let Err(_e) = annotations::reanchor(path, &content) {
+        #[cfg(debug_assertions)]
+        eprintln!("[document_io] {}", _e);
+    }
+    Ok(result)
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct FileChunk {
+    pub data: String,
+    /// Byte offset of `data` in the file
+    pub offset: u64,
+    /// Where the next chunk starts; chunks end on character boundaries,
+    /// so this can be less than `offset + len`
+    #[serde(rename = "nextOffset")]
+    pub next_offset: u64,
+    #[serde(rename = "totalSize")]
+    pub total_size: u64,
+    /// FNV-1a hash (hex) of the chunk's bytes
+    pub checksum: String,
+    pub eof: bool,
+}
+
+/// FNV-1a hash of `bytes` as 16 hex digits.
+pub fn checksum(bytes: &[u8]) -> String {
+    let hash = bytes.iter().fold(0xcbf2_9ce4_8422_2325u64, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(0x0100_0000_01b3)
+    });
+    format!("{hash:016x}")
+}
+
+/// Stable key for per-document data kept outside the document's folder:
+/// the checksum of its canonical path.
+pub fn path_key(path: &Path) -> String {
+    checksum(canonical_path(path).to_string_lossy().as_bytes())
+}
+
+/// `path` with its deepest existing ancestor canonicalized, so a path that
+/// was just moved away (or isn't created yet) resolves like it would exist.
+pub fn canonical_path(path: &Path) -> PathBuf {
+    let mut names = Vec::new();
+    let mut current = path;
+    loop {
+        if let Ok(canonical) = fs::canonicalize(current) {
+            return names
+                .iter()
+                .rev()
+                .fold(canonical, |acc, name| acc.join(name));
+        }
+        match (current.parent(), current.file_name()) {
+            (Some(parent), Some(name)) => {
+                names.push(name);
+                current = parent;
+            }
+            _ => return path.to_path_buf(),
+        }
+    }
+}
+
+fn read_range(path: &Path, offset: u64, len: usize) -> Result<FileChunk, String> {
+    let mut file = File::open(path).map_err(|e| format!("Failed to open file: {e}"))?;
+    let total_size = file
+        .metadata()
+        .map_err(|e| format!("Failed to read file metadata: {e}"))?
+        .len();
+    file.seek(SeekFrom::Start(offset))
+        .map_err(|e| format!("Failed to seek in file: {e}"))?;
+    let mut bytes = Vec::with_capacity(len.max(MIN_CHUNK_SIZE));
+    file.take(len.max(MIN_CHUNK_SIZE) as u64)
+        .read_to_end(&mut bytes)
+        .map_err(|e| format!("Failed to read file: {e}"))?;
+
+    // Leave a character cut off at the end for the next chunk
+    let end = match std::str::from_utf8(&bytes) {
+        Err(e) if e.error_len().is_none() => e.valid_up_to(),
+        _ => bytes.len(),
+    };
+    bytes.truncate(end);
+    let next_offset = offset + bytes.len() as u64;
+    Ok(FileChunk {
+        checksum: checksum(&bytes),
+        data: String::from_utf8_lossy(&bytes).into_owned(),
+        offset,
+        next_offset,
+        total_size,
+        eof: next_offset >= total_size,
+    })
+}
+
+/// Read about `len` bytes of a UTF-8 file starting at byte `offset`.
+#[tauri::command]
+pub fn read_file_range(path: String, offset: u64, len: usize) -> Result<FileChunk, String> {
+    read_range(Path::new(&path), offset, len)
+}
+
+/// Send a file to the calling window as `file:chunk` events (payload: path,
+/// chunk), in the background. A failed read ends the stream with
+/// `file:chunk-error` (path, error).
+#[tauri::command]
+pub fn stream_file(window: WebviewWindow, path: String, chunk_size: Option<usize>) {
+    let chunk_size = chunk_size.unwrap_or(DEFAULT_CHUNK_SIZE);
+    std::thread::spawn(move || {
+        let label = window.label().to_string();
+        let mut offset = 0;
+        loop {
+            match read_range(Path::new(&path), offset, chunk_size) {
+                Ok(chunk) => {
+                    let eof = chunk.eof;
+                    offset = chunk.next_offset;
+                    let _ = window.emit_to(label.as_str(), "file:chunk", (&path, chunk));
+                    if eof {
+                        return;
+                    }
+                }
+                Err(e) => {
+                    let _ = window.emit_to(label.as_str(), "file:chunk-error", (&path, e));
+                    return;
+                }
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_save_atomic() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("note.md");
+
+        let result = save_atomic(&path, b"first", &SaveOptions::default()).unwrap();
+        assert!(result.previous_path.is_none());
+        assert_eq!(fs::read_to_string(&path).unwrap(), "first");
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            fs::set_permissions(&path, fs::Permissions::from_mode(0o600)).unwrap();
+        }
+
+        let options = SaveOptions {
+            keep_previous: true,
+            ..Default::default()
+        };
+        let result = save_atomic(&path, b"second", &options).unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "second");
+        let previous = result.previous_path.unwrap();
+        assert_eq!(fs::read_to_string(previous).unwrap(), "first");
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = fs::metadata(&path).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+
+        // Only the document and its previous version, no temp files
+        assert_eq!(fs::read_dir(dir.path()).unwrap().count(), 2);
+
+        #[cfg(unix)]
+        {
+            let mut permissions = fs::metadata(&path).unwrap().permissions();
+            permissions.set_readonly(true);
+            fs::set_permissions(&path, permissions).unwrap();
+            let error = save_atomic(&path, b"third", &SaveOptions::default()).unwrap_err();
+            assert_eq!(error.kind, crate::write_access::SaveErrorKind::ReadOnly);
+            assert_eq!(fs::read_to_string(&path).unwrap(), "second");
+        }
+    }
+
+    #[test]
+    fn test_read_range_keeps_characters_whole() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("big.md");
+        fs::write(&path, "ab文字").unwrap();
+
+        // "文" takes bytes 2..5, so a 4-byte chunk stops before it
+        let first = read_range(&path, 0, 4).unwrap();
+        assert_eq!(first.data, "ab");
+        assert_eq!(first.next_offset, 2);
+        assert_eq!(first.total_size, 8);
+        assert!(!first.eof);
+
+        let second = read_range(&path, first.next_offset, 16).unwrap();
+        assert_eq!(second.data, "文字");
+        assert!(second.eof);
+        assert_eq!(second.checksum, checksum("文字".as_bytes()));
+    }
+}
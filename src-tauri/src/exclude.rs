@@ -0,0 +1,182 @@
+//! Workspace exclude rules.
+//!
+//! Combines the literal `vmark.excludeFolders` names with the
+//! `vmark.excludeGlobs` patterns into one matcher shared by the watcher,
+//! the file tree listing, and workspace-wide scans.
+//!
+//! Glob patterns follow gitignore conventions:
+//! - a pattern without `/` matches at any depth (`*.tmp.md`, `drafts-archive`)
+//! - a leading `/` anchors the pattern to the workspace root
+//! - `dir/**` also excludes `dir` itself
+
+use globset::{GlobBuilder, GlobSet, GlobSetBuilder};
+use std::path::{Component, Path, PathBuf};
+
+use crate::workspace::{self, WorkspaceConfig};
+
+pub struct ExcludeMatcher {
+    root: PathBuf,
+    folders: Vec<String>,
+    globs: GlobSet,
+}
+
+/// Expand a user pattern into the globs that implement the conventions above.
+fn expand_pattern(pattern: &str) -> Vec<String> {
+    let pattern = pattern.trim().trim_end_matches('/');
+    if pattern.is_empty() {
+        return vec![];
+    }
+
+    let base = match pattern.strip_prefix('/') {
+        Some(anchored) => anchored.to_string(),
+        None if !pattern.contains('/') => format!("**/{pattern}"),
+        None => pattern.to_string(),
+    };
+
+    let mut expanded = vec![base.clone()];
+    if let Some(dir) = base.strip_suffix("/**") {
+        if !dir.is_empty() {
+            expanded.push(dir.to_string());
+        }
+    }
+    expanded
+}
+
+/// Compile glob patterns, skipping invalid ones.
+fn build_globset(patterns: &[String]) -> GlobSet {
+    let mut builder = GlobSetBuilder::new();
+    for pattern in patterns {
+        for expanded in expand_pattern(pattern) {
+            match GlobBuilder::new(&expanded).literal_separator(true).build() {
+                Ok(glob) => {
+                    builder.add(glob);
+                }
+                Err(_e) => {
+                    #[cfg(debug_assertions)]
+                    eprintln!("[exclude] Invalid glob {:?}: {}", pattern, _e);
+                }
+            }
+        }
+    }
+    builder.build().unwrap_or_else(|_| GlobSet::empty())
+}
+
+impl ExcludeMatcher {
+    pub fn new(root: &Path, folders: Vec<String>, globs: &[String]) -> Self {
+        Self {
+            root: root.to_path_buf(),
+            folders,
+            globs: build_globset(globs),
+        }
+    }
+
+    pub fn from_config(root: &Path, config: &WorkspaceConfig) -> Self {
+        Self::new(root, config.exclude_folders.clone(), &config.exclude_globs)
+    }
+
+    /// Load rules from the workspace config at `root` (defaults if none).
+    pub fn for_root(root: &Path) -> Self {
+        let config = root
+            .to_str()
+            .and_then(|r| workspace::read_workspace_config(r).ok().flatten())
+            .unwrap_or_default();
+        Self::from_config(root, &config)
+    }
+
+    /// Workspace-relative path with `/` separators, or None if outside the root.
+    fn relative(&self, path: &Path) -> Option<String> {
+        let rest = path.strip_prefix(&self.root).ok()?;
+        let parts: Vec<String> = rest
+            .components()
+            .filter_map(|c| match c {
+                Component::Normal(part) => Some(part.to_string_lossy().to_string()),
+                _ => None,
+            })
+            .collect();
+        (!parts.is_empty()).then(|| parts.join("/"))
+    }
+
+    /// Check a single entry, assuming its ancestors were already checked
+    /// (as during a directory walk).
+    pub fn is_excluded(&self, path: &Path, is_dir: bool) -> bool {
+        if is_dir {
+            if let Some(name) = path.file_name() {
+                if self.folders.iter().any(|f| f.as_str() == name.to_string_lossy()) {
+                    return true;
+                }
+            }
+        }
+        self.relative(path)
+            .map(|rel| self.globs.is_match(&rel))
+            .unwrap_or(false)
+    }
+
+    /// Check a path and every ancestor up to the root (for arbitrary paths
+    /// such as watcher events). The leaf is treated as a file.
+    pub fn is_excluded_path(&self, path: &Path) -> bool {
+        let Ok(rest) = path.strip_prefix(&self.root) else {
+            return false;
+        };
+        let mut current = self.root.clone();
+        let count = rest.components().count();
+        for (i, component) in rest.components().enumerate() {
+            current.push(component);
+            if self.is_excluded(&current, i + 1 < count) {
+                return true;
+            }
+        }
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn matcher(folders: &[&str], globs: &[&str]) -> ExcludeMatcher {
+        ExcludeMatcher::new(
+            Path::new("/ws"),
+            folders.iter().map(|s| s.to_string()).collect(),
+            &globs.iter().map(|s| s.to_string()).collect::<Vec<_>>(),
+        )
+    }
+
+    #[test]
+    fn test_expand_pattern() {
+        assert_eq!(expand_pattern("*.tmp.md"), vec!["**/*.tmp.md"]);
+        assert_eq!(expand_pattern("/build/**"), vec!["build/**", "build"]);
+        assert_eq!(expand_pattern("docs/old/"), vec!["docs/old"]);
+        assert!(expand_pattern("  ").is_empty());
+    }
+
+    #[test]
+    fn test_glob_and_folder_rules() {
+        let m = matcher(&["node_modules"], &["**/drafts-archive", "*.tmp.md", "build/**"]);
+
+        assert!(m.is_excluded(Path::new("/ws/notes/drafts-archive"), true));
+        assert!(m.is_excluded(Path::new("/ws/a/b/c.tmp.md"), false));
+        assert!(m.is_excluded(Path::new("/ws/build"), true));
+        assert!(m.is_excluded(Path::new("/ws/build/out.md"), false));
+        assert!(m.is_excluded(Path::new("/ws/x/node_modules"), true));
+
+        assert!(!m.is_excluded(Path::new("/ws/node_modules"), false));
+        assert!(!m.is_excluded(Path::new("/ws/notes/build/a.md"), false));
+        assert!(!m.is_excluded(Path::new("/ws/notes/a.md"), false));
+    }
+
+    #[test]
+    fn test_is_excluded_path_checks_ancestors() {
+        let m = matcher(&["node_modules"], &["*-archive"]);
+
+        assert!(m.is_excluded_path(Path::new("/ws/node_modules/pkg/readme.md")));
+        assert!(m.is_excluded_path(Path::new("/ws/notes/old-archive/a.md")));
+        assert!(!m.is_excluded_path(Path::new("/ws/notes/a.md")));
+        assert!(!m.is_excluded_path(Path::new("/elsewhere/node_modules/a.md")));
+    }
+
+    #[test]
+    fn test_invalid_glob_is_skipped() {
+        let m = matcher(&[], &["[", "*.bak"]);
+        assert!(m.is_excluded(Path::new("/ws/a.bak"), false));
+    }
+}
@@ -0,0 +1,175 @@
+//! Exit hooks: cleanup a window must finish before it is destroyed during
+//! a coordinated quit.
+//!
+//! A window registers named hooks (`register_exit_hook`, e.g. "autosave",
+//! "scroll-positions"). When it is closed during a quit, the window first
+//! gets `app:exit-hooks` (payload: label, hook names) and is destroyed once
+//! every hook is acknowledged with `ack_exit_hook`, or after `ACK_TIMEOUT`
+//! so a stuck hook can't block the quit.
+
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{LazyLock, Mutex};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager, WebviewWindow};
+
+/// Longest wait for a window's hooks
+const ACK_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// Registered hook names per window label
+static REGISTERED: LazyLock<Mutex<HashMap<String, BTreeSet<String>>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// Windows waiting for hook acknowledgments
+static PENDING: LazyLock<Mutex<HashMap<String, PendingHooks>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// Tells a timeout apart from a later wait of the same window.
+static NEXT_TOKEN: AtomicU64 = AtomicU64::new(1);
+
+struct PendingHooks {
+    token: u64,
+    remaining: HashSet<String>,
+    on_done: Box<dyn FnOnce() + Send>,
+}
+
+/// Remove an acknowledged hook; returns the completion once none is left.
+fn acknowledge(
+    pending: &mut HashMap<String, PendingHooks>,
+    label: &str,
+    hook: &str,
+) -> Option<Box<dyn FnOnce() + Send>> {
+    let entry = pending.get_mut(label)?;
+    entry.remaining.remove(hook);
+    if !entry.remaining.is_empty() {
+        return None;
+    }
+    pending.remove(label).map(|entry| entry.on_done)
+}
+
+/// Run the exit hooks of window `label`, then `on_done` (once all hooks
+/// acknowledged or on timeout). Without hooks `on_done` runs right away.
+pub fn run(app: &AppHandle, label: &str, on_done: impl FnOnce() + Send + 'static) {
+    let hooks: BTreeSet<String> = REGISTERED
+        .lock()
+        .ok()
+        .and_then(|registered| registered.get(label).cloned())
+        .unwrap_or_default();
+    let Some(window) = app.get_webview_window(label).filter(|_| !hooks.is_empty()) else {
+        on_done();
+        return;
+    };
+
+    let token = NEXT_TOKEN.fetch_add(1, Ordering::SeqCst);
+    if let Ok(mut pending) = PENDING.lock() {
+        // A second close while waiting replaces the first one's completion
+        pending.insert(
+            label.to_string(),
+            PendingHooks {
+                token,
+                remaining: hooks.iter().cloned().collect(),
+                on_done: Box::new(on_done),
+            },
+        );
+    }
+    let _ = window.emit("app:exit-hooks", (label, &hooks));
+
+    let label = label.to_string();
+    std::thread::spawn(move || {
+        std::thread::sleep(ACK_TIMEOUT);
+        let timed_out = PENDING.lock().ok().and_then(|mut pending| {
+            if pending
+                .get(&label)
+                .is_some_and(|entry| entry.token == token)
+            {
+                pending.remove(&label)
+            } else {
+                None
+            }
+        });
+        if let Some(entry) = timed_out {
+            #[cfg(debug_assertions)]
+            eprintln!(
+                "[exit_hooks] '{}' timed out waiting for {:?}",
+                label, entry.remaining
+            );
+            (entry.on_done)();
+        }
+    });
+}
+
+/// Forget a destroyed window's hooks.
+pub fn remove_window(label: &str) {
+    if let Ok(mut registered) = REGISTERED.lock() {
+        registered.remove(label);
+    }
+    if let Ok(mut pending) = PENDING.lock() {
+        pending.remove(label);
+    }
+}
+
+/// Declare cleanup the calling window must finish before it is destroyed.
+#[tauri::command]
+pub fn register_exit_hook(window: WebviewWindow, hook: String) {
+    if let Ok(mut registered) = REGISTERED.lock() {
+        registered
+            .entry(window.label().to_string())
+            .or_default()
+            .insert(hook);
+    }
+}
+
+#[tauri::command]
+pub fn unregister_exit_hook(window: WebviewWindow, hook: String) {
+    if let Ok(mut registered) = REGISTERED.lock() {
+        if let Some(hooks) = registered.get_mut(window.label()) {
+            hooks.remove(&hook);
+        }
+    }
+}
+
+/// Report that a hook of the calling window finished (successfully or
+/// not; either way the window may now close).
+#[tauri::command]
+pub fn ack_exit_hook(window: WebviewWindow, hook: String) {
+    let on_done = PENDING
+        .lock()
+        .ok()
+        .and_then(|mut pending| acknowledge(&mut pending, window.label(), &hook));
+    if let Some(on_done) = on_done {
+        on_done();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::AtomicBool;
+    use std::sync::Arc;
+
+    #[test]
+    fn test_acknowledge() {
+        let done = Arc::new(AtomicBool::new(false));
+        let done_flag = done.clone();
+        let mut pending = HashMap::new();
+        pending.insert(
+            "doc-1".to_string(),
+            PendingHooks {
+                token: 1,
+                remaining: ["autosave", "scroll"]
+                    .iter()
+                    .map(|h| h.to_string())
+                    .collect(),
+                on_done: Box::new(move || done_flag.store(true, Ordering::SeqCst)),
+            },
+        );
+
+        assert!(acknowledge(&mut pending, "doc-1", "unknown").is_none());
+        assert!(acknowledge(&mut pending, "doc-1", "autosave").is_none());
+        assert!(acknowledge(&mut pending, "doc-2", "scroll").is_none());
+        let on_done = acknowledge(&mut pending, "doc-1", "scroll").unwrap();
+        on_done();
+        assert!(done.load(Ordering::SeqCst));
+        assert!(pending.is_empty());
+    }
+}
@@ -0,0 +1,207 @@
+//! Which window owns each open file.
+//!
+//! A window acquires a file before opening it for editing. If another
+//! window already owns the file, acquiring fails and reports the owner, so
+//! the frontend can focus that window or open the file read-only instead
+//! of letting two windows overwrite each other's saves; `save_document`
+//! refuses to write a file another window holds. Locks move with a tab
+//! transferred to another window and are released when the file is closed
+//! or the window is destroyed.
+
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use std::sync::{LazyLock, Mutex};
+use tauri::{AppHandle, Manager, WebviewWindow};
+
+use crate::links;
+
+/// Lock owner keyed by canonical path
+static LOCKS: LazyLock<Mutex<HashMap<String, FileOwner>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FileOwner {
+    pub label: String,
+    #[serde(rename = "tabId")]
+    pub tab_id: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct AcquireResult {
+    pub acquired: bool,
+    /// The window holding the file when it could not be acquired
+    pub owner: Option<FileOwner>,
+}
+
+/// Same key for every spelling of a path (relative parts, symlinks, case
+/// on case-insensitive file systems).
+fn lock_key(path: &str) -> String {
+    fs::canonicalize(path)
+        .unwrap_or_else(|_| links::normalize_path(Path::new(path)))
+        .to_string_lossy()
+        .to_string()
+}
+
+/// Give `key` to `owner` unless another window holds it. Re-acquiring
+/// within the same window (e.g. by another tab) moves the lock.
+fn try_acquire(
+    locks: &mut HashMap<String, FileOwner>,
+    key: String,
+    owner: FileOwner,
+) -> Result<(), FileOwner> {
+    match locks.get(&key) {
+        Some(current) if current.label != owner.label => Err(current.clone()),
+        _ => {
+            locks.insert(key, owner);
+            Ok(())
+        }
+    }
+}
+
+/// Move `key` from window `from` to `owner`. Does nothing unless `from`
+/// holds it.
+fn move_lock(locks: &mut HashMap<String, FileOwner>, key: &str, from: &str, owner: FileOwner) {
+    if let Some(current) = locks.get_mut(key) {
+        if current.label == from {
+            *current = owner;
+        }
+    }
+}
+
+/// Acquire `path` for the calling window. When another window owns it and
+/// `focus_owner` is set, that window is brought to the front.
+#[tauri::command]
+pub fn acquire_file(
+    app: AppHandle,
+    window: WebviewWindow,
+    path: String,
+    tab_id: Option<String>,
+    focus_owner: Option<bool>,
+) -> Result<AcquireResult, String> {
+    let owner = FileOwner {
+        label: window.label().to_string(),
+        tab_id,
+    };
+    let result = LOCKS
+        .lock()
+        .map_err(|e| format!("Failed to lock file registry: {e}"))
+        .map(|mut locks| try_acquire(&mut locks, lock_key(&path), owner))?;
+    match result {
+        Ok(()) => Ok(AcquireResult {
+            acquired: true,
+            owner: None,
+        }),
+        Err(owner) => {
+            if focus_owner.unwrap_or(false) {
+                if let Some(window) = app.get_webview_window(&owner.label) {
+                    let _ = window.unminimize();
+                    let _ = window.show();
+                    let _ = window.set_focus();
+                }
+            }
+            Ok(AcquireResult {
+                acquired: false,
+                owner: Some(owner),
+            })
+        }
+    }
+}
+
+/// Release `path`. Only its owner window can release it.
+#[tauri::command]
+pub fn release_file(window: WebviewWindow, path: String) {
+    let key = lock_key(&path);
+    if let Ok(mut locks) = LOCKS.lock() {
+        if locks
+            .get(&key)
+            .is_some_and(|owner| owner.label == window.label())
+        {
+            locks.remove(&key);
+        }
+    }
+}
+
+/// Owner of `path`, if any window holds it.
+#[tauri::command]
+pub fn get_file_owner(path: String) -> Option<FileOwner> {
+    LOCKS
+        .lock()
+        .ok()
+        .and_then(|locks| locks.get(&lock_key(&path)).cloned())
+}
+
+/// The window holding `path`, if it is not `label`.
+pub fn other_owner(path: &str, label: &str) -> Option<FileOwner> {
+    let locks = LOCKS.lock().ok()?;
+    locks
+        .get(&lock_key(path))
+        .filter(|owner| owner.label != label)
+        .cloned()
+}
+
+/// Hand `path` from window `from` to window `to`, after its tab moved there.
+pub fn transfer(path: &str, from: &str, to: &str) {
+    if let Ok(mut locks) = LOCKS.lock() {
+        let owner = FileOwner {
+            label: to.to_string(),
+            tab_id: None,
+        };
+        move_lock(&mut locks, &lock_key(path), from, owner);
+    }
+}
+
+/// Release every file of a destroyed window.
+pub fn remove_window(label: &str) {
+    if let Ok(mut locks) = LOCKS.lock() {
+        locks.retain(|_, owner| owner.label != label);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn owner(label: &str, tab: &str) -> FileOwner {
+        FileOwner {
+            label: label.to_string(),
+            tab_id: Some(tab.to_string()),
+        }
+    }
+
+    #[test]
+    fn test_try_acquire() {
+        let mut locks = HashMap::new();
+        let key = "/notes/a.md".to_string();
+
+        assert!(try_acquire(&mut locks, key.clone(), owner("main", "t1")).is_ok());
+        let err = try_acquire(&mut locks, key.clone(), owner("doc-1", "t2")).unwrap_err();
+        assert_eq!(err, owner("main", "t1"));
+
+        // Another tab of the owning window takes the lock over
+        assert!(try_acquire(&mut locks, key.clone(), owner("main", "t3")).is_ok());
+        assert_eq!(locks[&key], owner("main", "t3"));
+    }
+
+    #[test]
+    fn test_move_lock() {
+        let mut locks = HashMap::new();
+        let key = "/notes/a.md".to_string();
+        locks.insert(key.clone(), owner("main", "t1"));
+
+        move_lock(&mut locks, &key, "doc-2", owner("doc-1", "t2"));
+        assert_eq!(locks[&key], owner("main", "t1"));
+
+        move_lock(&mut locks, &key, "main", owner("doc-1", "t2"));
+        assert_eq!(locks[&key], owner("doc-1", "t2"));
+    }
+
+    #[test]
+    fn test_lock_key_normalizes() {
+        assert_eq!(
+            lock_key("/no/such/dir/./notes/../a.md"),
+            lock_key("/no/such/dir/a.md")
+        );
+    }
+}
@@ -0,0 +1,1217 @@
+//! File operations that keep workspace references intact.
+//!
+//! Renaming or moving a document also moves its sidecar asset folder
+//! (`<name>.assets/`) and rewrites relative links and wiki links that
+//! point at the moved paths. Duplicating a document can copy its sidecar
+//! folder the same way, and batch renames go through the same rewriting.
+
+use chrono::{DateTime, Local};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+use tauri::WebviewWindow;
+
+use crate::annotations;
+use crate::document_io::{self, SaveOptions};
+use crate::exclude::ExcludeMatcher;
+use crate::file_tree;
+use crate::links::{self, LinkKind, LinkRef};
+use crate::templates;
+use crate::text_encoding;
+use crate::wikilinks;
+use crate::workspace;
+
+/// Result of a rename/move operation
+#[derive(Debug, Serialize)]
+pub struct RenameResult {
+    /// Final path of the renamed item
+    #[serde(rename = "newPath")]
+    pub new_path: String,
+    /// Paths that were moved on disk (old -> new), including sidecar asset folders
+    pub moved: Vec<(String, String)>,
+    /// Markdown files whose links were rewritten
+    #[serde(rename = "updatedFiles")]
+    pub updated_files: Vec<String>,
+}
+
+/// What `duplicate_file` does with the sidecar asset folder
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum DuplicateAssets {
+    /// Copy the folder and point the copy's links at it
+    #[default]
+    Copy,
+    /// Keep linking to the original's assets
+    Share,
+}
+
+/// Result of duplicating a document
+#[derive(Debug, Serialize)]
+pub struct DuplicateResult {
+    #[serde(rename = "newPath")]
+    pub new_path: String,
+    /// Sidecar asset folder created for the copy, if any
+    #[serde(rename = "copiedAssets")]
+    pub copied_assets: Option<String>,
+}
+
+/// One rename of a batch
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PlannedRename {
+    pub from: String,
+    pub to: String,
+}
+
+/// Result of a batch rename (or of its dry run)
+#[derive(Debug, Serialize)]
+pub struct BatchRenameResult {
+    pub renames: Vec<PlannedRename>,
+    /// Why the batch can't run; nothing is renamed when this is not empty
+    pub conflicts: Vec<String>,
+    /// Whether the renames were carried out
+    pub applied: bool,
+    #[serde(rename = "updatedFiles")]
+    pub updated_files: Vec<String>,
+}
+
+/// File metadata for the file info popover and MCP tools
+#[derive(Debug, Serialize)]
+pub struct PathStat {
+    pub path: String,
+    #[serde(rename = "isDir")]
+    pub is_dir: bool,
+    pub size: u64,
+    /// Unix milliseconds; None where the platform doesn't record it
+    #[serde(rename = "createdAt")]
+    pub created_at: Option<i64>,
+    #[serde(rename = "modifiedAt")]
+    pub modified_at: Option<i64>,
+    pub readonly: bool,
+    /// User name of the owner (unix only)
+    pub owner: Option<String>,
+    /// Whether the path is inside the workspace root or one of its folders
+    #[serde(rename = "inWorkspace")]
+    pub in_workspace: bool,
+}
+
+/// Get the sidecar asset folder for a document (`notes/a.md` -> `notes/a.assets`).
+pub fn sidecar_assets_dir(doc_path: &Path) -> Option<PathBuf> {
+    let stem = doc_path.file_stem()?.to_str()?;
+    Some(doc_path.with_file_name(format!("{stem}.assets")))
+}
+
+/// Map a path through a set of moves (exact match or directory prefix).
+fn map_moved_path(path: &Path, moves: &[(PathBuf, PathBuf)]) -> PathBuf {
+    for (from, to) in moves {
+        if let Ok(rest) = path.strip_prefix(from) {
+            return if rest.as_os_str().is_empty() {
+                to.clone()
+            } else {
+                to.join(rest)
+            };
+        }
+    }
+    path.to_path_buf()
+}
+
+/// Compute the new destination for a path-style link, or None if unchanged.
+fn rewrite_path_link(
+    link: &LinkRef,
+    old_dir: &Path,
+    new_dir: &Path,
+    moves: &[(PathBuf, PathBuf)],
+) -> Option<String> {
+    if !links::is_local_target(&link.target) {
+        return None;
+    }
+    let (path_part, fragment) = links::split_fragment(&link.target);
+    if path_part.is_empty() {
+        return None;
+    }
+
+    let resolved = links::resolve_link_path(old_dir, path_part);
+    let mapped = map_moved_path(&resolved, moves);
+    if mapped == resolved && old_dir == new_dir {
+        return None;
+    }
+
+    let new_path = if Path::new(path_part).is_absolute() {
+        mapped.to_string_lossy().to_string()
+    } else {
+        links::relative_link(new_dir, &mapped)
+    };
+    Some(format!(
+        "{}{}",
+        links::format_link_path(&new_path, link.bracketed),
+        fragment
+    ))
+}
+
+/// Compute the new target for a wiki link, or None if unchanged.
+///
+/// A note link (`[[Note]]`, `[[folder/Note]]`, extension optional) is
+/// resolved against the notes as they were before the moves, so only links
+/// to the moved note itself change and not links to another note with the
+/// same name. The rewritten link keeps as many path components as the
+/// original, falling back to the full workspace path when that would now
+/// point somewhere else. Other wiki links are workspace-relative paths.
+fn rewrite_wikilink(
+    link: &LinkRef,
+    root: &Path,
+    dirs: (&Path, &Path),
+    notes: (&[PathBuf], &[PathBuf]),
+    moves: &[(PathBuf, PathBuf)],
+) -> Option<String> {
+    let (name, fragment) = links::split_fragment(&link.target);
+    let name = name.trim();
+    if name.is_empty() || name.starts_with("./") || name.starts_with("../") {
+        return None;
+    }
+
+    if !file_tree::is_markdown_path(Path::new(name)) && Path::new(name).extension().is_some() {
+        let resolved = links::normalize_path(&root.join(name.trim_start_matches('/')));
+        let mapped = map_moved_path(&resolved, moves);
+        if mapped == resolved || !name.contains('/') {
+            return None;
+        }
+        let relative = links::relative_link(root, &mapped);
+        return Some(format!("{relative}{fragment}"));
+    }
+
+    let (old_dir, new_dir) = dirs;
+    let (notes_before, notes_after) = notes;
+    let extension = Path::new(name).extension().and_then(|e| e.to_str());
+    let stem = match extension {
+        Some(ext) => &name[..name.len() - ext.len() - 1],
+        None => name,
+    };
+    let target = wikilinks::resolve_among(root, notes_before, stem, old_dir)?;
+    let moved = map_moved_path(&target, moves);
+    if moved == target {
+        return None;
+    }
+    let without_extension = moved.with_extension("");
+    let relative = without_extension.strip_prefix(root).ok()?;
+    let parts: Vec<String> = relative
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy().to_string())
+        .collect();
+    let depth = stem.trim_start_matches('/').split('/').count();
+    let mut new_stem = parts[parts.len().saturating_sub(depth)..].join("/");
+    if stem.starts_with('/')
+        || wikilinks::resolve_among(root, notes_after, &new_stem, new_dir).as_ref() != Some(&moved)
+    {
+        new_stem = format!("/{}", parts.join("/"));
+    }
+    let new_name = match (extension, moved.extension().and_then(|e| e.to_str())) {
+        (Some(_), Some(ext)) => format!("{new_stem}.{ext}"),
+        _ => new_stem,
+    };
+    Some(format!("{new_name}{fragment}"))
+}
+
+/// Rewrite links in the markdown files of a workspace after `moves` were
+/// applied. `notes` are the workspace's markdown files as collected before
+/// the moves. Returns the files that changed.
+fn rewrite_workspace_links(
+    root: &Path,
+    notes: &[PathBuf],
+    moves: &[(PathBuf, PathBuf)],
+) -> Result<Vec<String>, String> {
+    let mut updated = Vec::new();
+    let moved_notes: Vec<PathBuf> = notes.iter().map(|n| map_moved_path(n, moves)).collect();
+
+    for (old_location, file) in notes.iter().zip(&moved_notes) {
+        if !file.starts_with(root) {
+            continue;
+        }
+        let (Some(old_dir), Some(new_dir)) = (old_location.parent(), file.parent()) else {
+            continue;
+        };
+
+        let content = match fs::read_to_string(file) {
+            Ok(content) => content,
+            Err(_) => continue,
+        };
+
+        let (rewritten, count) = links::rewrite_links(&content, |link| match link.kind {
+            LinkKind::WikiLink => {
+                rewrite_wikilink(link, root, (old_dir, new_dir), (notes, &moved_notes), moves)
+            }
+            _ => rewrite_path_link(link, old_dir, new_dir, moves),
+        });
+
+        if count > 0 {
+            write_links(file, &rewritten)?;
+            updated.push(file.to_string_lossy().to_string());
+        }
+    }
+
+    Ok(updated)
+}
+
+/// Rewrite the path links of a moved document so they resolve from its new
+/// location (including links into its renamed sidecar asset folder).
+/// Returns whether the document changed.
+fn rewrite_moved_document(
+    old: &Path,
+    new: &Path,
+    moves: &[(PathBuf, PathBuf)],
+) -> Result<bool, String> {
+    let (Some(old_dir), Some(new_dir)) = (old.parent(), new.parent()) else {
+        return Ok(false);
+    };
+    let content =
+        fs::read_to_string(new).map_err(|e| format!("Failed to read {}: {e}", new.display()))?;
+    let (rewritten, count) = links::rewrite_links(&content, |link| match link.kind {
+        LinkKind::WikiLink => None,
+        _ => rewrite_path_link(link, old_dir, new_dir, moves),
+    });
+    if count == 0 {
+        return Ok(false);
+    }
+    write_links(new, &rewritten)?;
+    Ok(true)
+}
+
+/// Save a document whose links were rewritten.
+fn write_links(path: &Path, content: &str) -> Result<(), String> {
+    document_io::save_atomic(path, content.as_bytes(), &SaveOptions::default())
+        .map(|_| ())
+        .map_err(|e| {
+            format!(
+                "Failed to update links in {}: {}",
+                path.display(),
+                String::from(e)
+            )
+        })
+}
+
+/// Move a path on disk, creating the destination's parent directory if needed.
+fn move_path(from: &Path, to: &Path) -> Result<(), String> {
+    if let Some(parent) = to.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create directory {}: {e}", parent.display()))?;
+    }
+    fs::rename(from, to).map_err(|e| format!("Failed to move {}: {e}", from.display()))
+}
+
+/// Carry the annotations of moved documents along, including documents
+/// inside moved folders.
+fn move_annotations(moves: &[(PathBuf, PathBuf)]) {
+    for (from, to) in moves {
+        let documents = if to.is_dir() {
+            file_tree::collect_markdown_files(to, &ExcludeMatcher::new(to, Vec::new(), &[]))
+        } else {
+            vec![to.clone()]
+        };
+        for document in documents {
+            let old = map_moved_path(&document, &[(to.clone(), from.clone())]);
+            if let Err(_e) = annotations::move_annotations(&old, &document) {
+                #[cfg(debug_assertions)]
+                eprintln!("[file_ops] {}", _e);
+            }
+        }
+    }
+}
+
+/// Whether `new` differs from `old` only in the case of its name and
+/// resolves to the same file, as it does on case-insensitive file systems.
+fn is_case_only_rename(old: &Path, new: &Path) -> bool {
+    let (Some(old_name), Some(new_name)) = (old.file_name(), new.file_name()) else {
+        return false;
+    };
+    old_name != new_name
+        && old_name.to_string_lossy().to_lowercase() == new_name.to_string_lossy().to_lowercase()
+        && matches!(
+            (fs::canonicalize(old), fs::canonicalize(new)),
+            (Ok(a), Ok(b)) if a == b
+        )
+}
+
+/// Rename or move a file or folder, carrying its sidecar asset folder and
+/// annotations along.
+///
+/// # Arguments
+/// * `old_path` - Existing file or folder
+/// * `new_path` - Destination path (must not exist)
+/// * `update_links` - Rewrite relative links and wiki links across the workspace
+///   and in the moved document itself
+/// * `workspace_root` - Root to scan for links (defaults to the parent of `old_path`)
+#[tauri::command]
+pub fn rename_path(
+    old_path: String,
+    new_path: String,
+    update_links: bool,
+    workspace_root: Option<String>,
+) -> Result<RenameResult, String> {
+    let old = links::normalize_path(Path::new(&old_path));
+    let new = links::normalize_path(Path::new(&new_path));
+
+    if !old.exists() {
+        return Err(format!("Path does not exist: {old_path}"));
+    }
+    if new.exists() && !is_case_only_rename(&old, &new) {
+        return Err(format!("Destination already exists: {new_path}"));
+    }
+
+    let mut moves = vec![(old.clone(), new.clone())];
+    if old.is_file() {
+        if let (Some(old_assets), Some(new_assets)) =
+            (sidecar_assets_dir(&old), sidecar_assets_dir(&new))
+        {
+            if old_assets.is_dir()
+                && old_assets != new_assets
+                && (!new_assets.exists() || is_case_only_rename(&old_assets, &new_assets))
+            {
+                moves.push((old_assets, new_assets));
+            }
+        }
+    }
+
+    // Wiki links are resolved against the workspace as it was before the move
+    let link_scope = if update_links {
+        let root = workspace_root
+            .map(PathBuf::from)
+            .or_else(|| old.parent().map(Path::to_path_buf))
+            .ok_or("Cannot determine workspace root")?;
+        let notes = file_tree::collect_markdown_files(&root, &ExcludeMatcher::for_root(&root));
+        Some((root, notes))
+    } else {
+        None
+    };
+
+    apply_moves(&moves)?;
+    move_annotations(&moves);
+
+    let Some((root, notes)) = link_scope else {
+        return Ok(RenameResult {
+            new_path: new.to_string_lossy().to_string(),
+            moved: display_moves(&moves),
+            updated_files: Vec::new(),
+        });
+    };
+
+    // Keep pinned entries pointing at the moved files
+    let _ = workspace::remap_pinned_files(&root, |p| map_moved_path(p, &moves));
+    let mut updated_files = rewrite_workspace_links(&root, &notes, &moves)?;
+
+    // The document's own links must follow it even when it left the
+    // workspace
+    let new_display = new.to_string_lossy().to_string();
+    if file_tree::is_markdown_path(&new)
+        && new.is_file()
+        && !updated_files.contains(&new_display)
+        && rewrite_moved_document(&old, &new, &moves)?
+    {
+        updated_files.push(new_display);
+    }
+
+    Ok(RenameResult {
+        new_path: new.to_string_lossy().to_string(),
+        moved: display_moves(&moves),
+        updated_files,
+    })
+}
+
+fn display_moves(moves: &[(PathBuf, PathBuf)]) -> Vec<(String, String)> {
+    moves
+        .iter()
+        .map(|(from, to)| {
+            (
+                from.to_string_lossy().to_string(),
+                to.to_string_lossy().to_string(),
+            )
+        })
+        .collect()
+}
+
+/// Move a file or folder to the system trash, together with the sidecar
+/// asset folder of a document. Returns the paths that were trashed.
+#[tauri::command]
+pub fn trash_path(path: String) -> Result<Vec<String>, String> {
+    let target = links::normalize_path(Path::new(&path));
+    if !target.exists() {
+        return Err(format!("Path does not exist: {path}"));
+    }
+
+    let mut paths = vec![target.clone()];
+    if target.is_file() {
+        if let Some(assets) = sidecar_assets_dir(&target).filter(|dir| dir.is_dir()) {
+            paths.push(assets);
+        }
+    }
+
+    let mut trashed = Vec::new();
+    for path in paths {
+        trash::delete(&path)
+            .map_err(|e| format!("Failed to move {} to trash: {e}", path.display()))?;
+        trashed.push(path.to_string_lossy().to_string());
+    }
+    Ok(trashed)
+}
+
+/// First free `name copy.md`, `name copy 2.md`, ... next to `path`.
+/// Create `name copy.md` (or `name copy 2.md`, ...) next to `path`. The
+/// name is taken with `create_new`, so a file that appears meanwhile is
+/// never replaced.
+fn create_copy_destination(path: &Path) -> Result<(PathBuf, File), String> {
+    let stem = path
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .ok_or_else(|| format!("Invalid file name: {}", path.display()))?;
+    let extension = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(|e| format!(".{e}"))
+        .unwrap_or_default();
+    let mut n = 1;
+    loop {
+        let suffix = if n == 1 {
+            " copy".to_string()
+        } else {
+            format!(" copy {n}")
+        };
+        let candidate = path.with_file_name(format!("{stem}{suffix}{extension}"));
+        match OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(&candidate)
+        {
+            Ok(file) => return Ok((candidate, file)),
+            Err(e) if e.kind() == io::ErrorKind::AlreadyExists => n += 1,
+            Err(e) => return Err(format!("Failed to create copy: {e}")),
+        }
+    }
+}
+
+fn copy_dir(from: &Path, to: &Path) -> Result<(), String> {
+    fs::create_dir_all(to)
+        .map_err(|e| format!("Failed to create directory {}: {e}", to.display()))?;
+    let entries =
+        fs::read_dir(from).map_err(|e| format!("Failed to read {}: {e}", from.display()))?;
+    for entry in entries.flatten() {
+        let target = to.join(entry.file_name());
+        if entry.path().is_dir() {
+            copy_dir(&entry.path(), &target)?;
+        } else {
+            fs::copy(entry.path(), &target)
+                .map_err(|e| format!("Failed to copy {}: {e}", entry.path().display()))?;
+        }
+    }
+    Ok(())
+}
+
+/// Duplicate a document as `name copy.md` next to it.
+///
+/// # Arguments
+/// * `path` - Document to duplicate
+/// * `assets` - Copy the sidecar asset folder (default) or share it
+#[tauri::command]
+pub fn duplicate_file(
+    path: String,
+    assets: Option<DuplicateAssets>,
+) -> Result<DuplicateResult, String> {
+    let source = links::normalize_path(Path::new(&path));
+    if !source.is_file() {
+        return Err(format!("Not a file: {path}"));
+    }
+    let (dest, file) = create_copy_destination(&source)?;
+    let copy_assets = assets.unwrap_or_default() == DuplicateAssets::Copy;
+    match write_copy(&source, &dest, file, copy_assets) {
+        Ok(copied_assets) => Ok(DuplicateResult {
+            new_path: dest.to_string_lossy().to_string(),
+            copied_assets: copied_assets.map(|dir| dir.to_string_lossy().to_string()),
+        }),
+        Err(e) => {
+            let _ = fs::remove_file(&dest);
+            Err(e)
+        }
+    }
+}
+
+/// Fill `dest`, the new copy of `source`. With `copy_assets`, the sidecar
+/// asset folder is copied too and the copy's links are pointed at it; the
+/// document keeps its encoding. Returns the copied asset folder.
+fn write_copy(
+    source: &Path,
+    dest: &Path,
+    mut file: File,
+    copy_assets: bool,
+) -> Result<Option<PathBuf>, String> {
+    let assets = sidecar_assets_dir(source)
+        .zip(sidecar_assets_dir(dest))
+        .filter(|(old, new)| copy_assets && old.is_dir() && !new.exists());
+    let Some((old_assets, new_assets)) = assets else {
+        drop(file);
+        fs::copy(source, dest).map_err(|e| format!("Failed to write copy: {e}"))?;
+        return Ok(None);
+    };
+
+    let bytes =
+        fs::read(source).map_err(|e| format!("Failed to read {}: {e}", source.display()))?;
+    let moves = [(old_assets.clone(), new_assets.clone())];
+    copy_dir(&old_assets, &new_assets)
+        .and_then(|()| {
+            let text = text_encoding::decode(&bytes);
+            // Rewriting text that didn't decode cleanly would damage it
+            let content = if text.had_errors {
+                bytes
+            } else {
+                let dir = source.parent().unwrap_or(Path::new(""));
+                let rewritten = links::rewrite_links(&text.content, |link| match link.kind {
+                    LinkKind::WikiLink => None,
+                    _ => rewrite_path_link(link, dir, dir, &moves),
+                })
+                .0;
+                text_encoding::encode(&rewritten, &text.encoding, text.bom)?
+            };
+            file.write_all(&content)
+                .map_err(|e| format!("Failed to write copy: {e}"))
+        })
+        .inspect_err(|_| {
+            let _ = fs::remove_dir_all(&new_assets);
+        })?;
+    Ok(Some(new_assets))
+}
+
+/// Default format of date tokens
+const DATE_TOKEN_FORMAT: &str = "%Y-%m-%d";
+
+/// Replace `{{created}}`, `{{modified}}` and `{{date}}` (today), each with
+/// an optional strftime format (`{{modified:%Y%m%d}}`), with the file's
+/// dates. Same syntax as template variables.
+fn expand_date_tokens(name: &str, path: &Path) -> Result<String, String> {
+    let meta = fs::metadata(path).ok();
+    let modified = meta.as_ref().and_then(|m| m.modified().ok());
+    // Not every file system records creation times
+    let created = meta.as_ref().and_then(|m| m.created().ok()).or(modified);
+
+    let mut error = None;
+    let expanded = templates::substitute(name, |key, format| {
+        let time: Option<SystemTime> = match key {
+            "created" => created,
+            "modified" => modified,
+            "date" => Some(SystemTime::now()),
+            // Not a date token; keep it as written
+            _ => return None,
+        };
+        let Some(time) = time else {
+            error = Some(format!("Cannot read dates of {}", path.display()));
+            return None;
+        };
+        let format = format.unwrap_or(DATE_TOKEN_FORMAT);
+        let formatted = templates::format_time(&DateTime::<Local>::from(time), format);
+        if formatted.is_none() {
+            error = Some(format!("Invalid date format: {format}"));
+        }
+        formatted
+    });
+    error.map_or(Ok(expanded), Err)
+}
+
+/// Renames of a batch and what prevents them
+struct RenamePlan {
+    moves: Vec<(PathBuf, PathBuf)>,
+    conflicts: Vec<String>,
+}
+
+/// Markdown files under `root` whose names match `pattern`, with their new
+/// paths.
+fn plan_batch_rename(
+    root: &Path,
+    excludes: &ExcludeMatcher,
+    pattern: &Regex,
+    replacement: &str,
+) -> Result<RenamePlan, String> {
+    let mut files = file_tree::collect_markdown_files(root, excludes);
+    files.sort();
+
+    let mut plan = Vec::new();
+    let mut conflicts = Vec::new();
+    for file in files {
+        let Some(name) = file.file_name().and_then(|n| n.to_str()) else {
+            continue;
+        };
+        if !pattern.is_match(name) {
+            continue;
+        }
+        let new_name = expand_date_tokens(&pattern.replace(name, replacement), &file)?;
+        if new_name == name {
+            continue;
+        }
+        if new_name.is_empty() || new_name.contains(['/', '\\']) {
+            conflicts.push(format!("Invalid new name for {name}: {new_name:?}"));
+            continue;
+        }
+        let dest = file.with_file_name(&new_name);
+        plan.push((file, dest));
+    }
+
+    let sources: HashSet<&PathBuf> = plan.iter().map(|(from, _)| from).collect();
+    let mut targets = HashSet::new();
+    for (from, to) in &plan {
+        if !targets.insert(to) {
+            conflicts.push(format!(
+                "More than one file would be renamed to {}",
+                to.display()
+            ));
+        } else if to.exists() && !sources.contains(to) {
+            conflicts.push(format!(
+                "{} would replace existing {}",
+                from.display(),
+                to.display()
+            ));
+        }
+    }
+    Ok(RenamePlan {
+        moves: plan,
+        conflicts,
+    })
+}
+
+/// Carry out `moves` all or nothing. Every source goes to a temporary name
+/// first, so renames within the batch (`a -> b`, `b -> c`) can't collide.
+fn apply_moves(moves: &[(PathBuf, PathBuf)]) -> Result<(), String> {
+    let temps: Vec<PathBuf> = moves
+        .iter()
+        .enumerate()
+        .map(|(i, (from, _))| {
+            from.with_file_name(format!(".vmark-rename-{}-{i}", std::process::id()))
+        })
+        .collect();
+
+    // Undo the first `done` steps (first phase: to temp, second: to target)
+    let rollback = |to_temp: usize, to_target: usize| {
+        for i in (0..to_target).rev() {
+            let _ = fs::rename(&moves[i].1, &temps[i]);
+        }
+        for i in (0..to_temp).rev() {
+            let _ = fs::rename(&temps[i], &moves[i].0);
+        }
+    };
+
+    for (i, (from, _)) in moves.iter().enumerate() {
+        if let Err(e) = fs::rename(from, &temps[i]) {
+            rollback(i, 0);
+            return Err(format!("Failed to move {}: {e}", from.display()));
+        }
+    }
+    for (i, (_, to)) in moves.iter().enumerate() {
+        if let Err(e) = move_path(&temps[i], to) {
+            rollback(moves.len(), i);
+            return Err(e);
+        }
+    }
+    Ok(())
+}
+
+/// Rename markdown files under `root` whose names match a regex.
+///
+/// # Arguments
+/// * `root` - Folder to search (recursively, honoring workspace excludes)
+/// * `pattern` - Regex matched against file names
+/// * `replacement` - Replacement for the first match; `$1`/`${name}` refer
+///   to capture groups and `{{created}}`, `{{modified}}`, `{{date}}`
+///   (optionally `{{modified:%Y%m%d}}`) to dates
+/// * `dry_run` - Only return the planned renames
+/// * `workspace_root` - Root to rewrite links in (defaults to `root`)
+#[tauri::command]
+pub fn batch_rename(
+    root: String,
+    pattern: String,
+    replacement: String,
+    dry_run: bool,
+    workspace_root: Option<String>,
+) -> Result<BatchRenameResult, String> {
+    let root = links::normalize_path(Path::new(&root));
+    if !root.is_dir() {
+        return Err(format!("Not a directory: {}", root.display()));
+    }
+    let regex = Regex::new(&pattern).map_err(|e| format!("Invalid pattern: {e}"))?;
+    let link_root = workspace_root
+        .map(|r| links::normalize_path(Path::new(&r)))
+        .unwrap_or_else(|| root.clone());
+    let excludes = ExcludeMatcher::for_root(&link_root);
+
+    let RenamePlan {
+        moves: plan,
+        conflicts,
+    } = plan_batch_rename(&root, &excludes, &regex, &replacement)?;
+    let renames = plan
+        .iter()
+        .map(|(from, to)| PlannedRename {
+            from: from.to_string_lossy().to_string(),
+            to: to.to_string_lossy().to_string(),
+        })
+        .collect();
+    if dry_run || !conflicts.is_empty() || plan.is_empty() {
+        return Ok(BatchRenameResult {
+            renames,
+            conflicts,
+            applied: false,
+            updated_files: Vec::new(),
+        });
+    }
+
+    let mut moves = plan.clone();
+    for (from, to) in &plan {
+        if let (Some(old_assets), Some(new_assets)) =
+            (sidecar_assets_dir(from), sidecar_assets_dir(to))
+        {
+            if old_assets.is_dir() && !new_assets.exists() {
+                moves.push((old_assets, new_assets));
+            }
+        }
+    }
+    let notes = file_tree::collect_markdown_files(&link_root, &excludes);
+    apply_moves(&moves)?;
+    move_annotations(&moves);
+
+    let _ = workspace::remap_pinned_files(&link_root, |p| map_moved_path(p, &moves));
+    let updated_files = rewrite_workspace_links(&link_root, &notes, &moves)?;
+    Ok(BatchRenameResult {
+        renames,
+        conflicts,
+        applied: true,
+        updated_files,
+    })
+}
+
+fn unix_millis(time: std::io::Result<SystemTime>) -> Option<i64> {
+    let elapsed = time.ok()?.duration_since(SystemTime::UNIX_EPOCH).ok()?;
+    i64::try_from(elapsed.as_millis()).ok()
+}
+
+/// User name for `uid`: from /etc/passwd, else the current user when the
+/// uid is theirs (macOS keeps users out of /etc/passwd), else the number.
+#[cfg(unix)]
+fn owner_name(uid: u32) -> String {
+    use std::os::unix::fs::MetadataExt;
+
+    let from_passwd = fs::read_to_string("/etc/passwd").ok().and_then(|passwd| {
+        passwd.lines().find_map(|line| {
+            let mut fields = line.split(':');
+            let name = fields.next()?;
+            (fields.nth(1)? == uid.to_string()).then(|| name.to_string())
+        })
+    });
+    from_passwd
+        .or_else(|| {
+            let home = dirs::home_dir()?;
+            (fs::metadata(home).ok()?.uid() == uid)
+                .then(|| std::env::var("USER").ok())
+                .flatten()
+        })
+        .unwrap_or_else(|| uid.to_string())
+}
+
+fn is_in_workspace(path: &Path, root: &str) -> bool {
+    let root_path = links::normalize_path(Path::new(root));
+    if path.starts_with(&root_path) {
+        return true;
+    }
+    workspace::resolve_workspace_folders(root)
+        .map(|folders| {
+            folders
+                .iter()
+                .any(|folder| path.starts_with(&folder.absolute_path))
+        })
+        .unwrap_or(false)
+}
+
+/// Metadata of a file or folder.
+///
+/// # Arguments
+/// * `path` - File or folder
+/// * `workspace_root` - Workspace to test against; defaults to the one open
+///   in the calling window
+#[tauri::command]
+pub fn stat_path(
+    window: WebviewWindow,
+    path: String,
+    workspace_root: Option<String>,
+) -> Result<PathStat, String> {
+    let target = links::normalize_path(Path::new(&path));
+    let metadata = fs::metadata(&target).map_err(|e| format!("Failed to read metadata: {e}"))?;
+
+    #[cfg(unix)]
+    let owner = {
+        use std::os::unix::fs::MetadataExt;
+        Some(owner_name(metadata.uid()))
+    };
+    #[cfg(not(unix))]
+    let owner = None;
+
+    let in_workspace = workspace_root
+        .or_else(|| crate::window_registry::workspace_root_of(window.label()))
+        .is_some_and(|root| is_in_workspace(&target, &root));
+
+    Ok(PathStat {
+        path: target.to_string_lossy().to_string(),
+        is_dir: metadata.is_dir(),
+        size: metadata.len(),
+        created_at: unix_millis(metadata.created()),
+        modified_at: unix_millis(metadata.modified()),
+        readonly: metadata.permissions().readonly(),
+        owner,
+        in_workspace,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_sidecar_assets_dir() {
+        assert_eq!(
+            sidecar_assets_dir(Path::new("/ws/notes/a.md")),
+            Some(PathBuf::from("/ws/notes/a.assets"))
+        );
+    }
+
+    #[test]
+    fn test_rename_rewrites_links_and_moves_assets() {
+        let dir = tempdir().unwrap();
+        let root = dir.path();
+        fs::create_dir_all(root.join("notes/a.assets")).unwrap();
+        fs::create_dir_all(root.join("archive")).unwrap();
+        fs::write(root.join("notes/a.assets/pic.png"), "png").unwrap();
+        fs::write(
+            root.join("notes/a.md"),
+            "![pic](a.assets/pic.png) [index](../index.md)",
+        )
+        .unwrap();
+        fs::write(
+            root.join("index.md"),
+            "[A](notes/a.md#intro) [[a]] [[notes/a|Alias]] [web](https://x.com)",
+        )
+        .unwrap();
+
+        let result = rename_path(
+            root.join("notes/a.md").to_string_lossy().to_string(),
+            root.join("archive/b.md").to_string_lossy().to_string(),
+            true,
+            Some(root.to_string_lossy().to_string()),
+        )
+        .unwrap();
+
+        assert!(root.join("archive/b.md").exists());
+        assert!(root.join("archive/b.assets/pic.png").exists());
+        assert!(!root.join("notes/a.assets").exists());
+        assert_eq!(result.moved.len(), 2);
+        assert_eq!(result.updated_files.len(), 2);
+
+        let index = fs::read_to_string(root.join("index.md")).unwrap();
+        assert_eq!(
+            index,
+            "[A](archive/b.md#intro) [[b]] [[archive/b|Alias]] [web](https://x.com)"
+        );
+
+        let moved = fs::read_to_string(root.join("archive/b.md")).unwrap();
+        assert_eq!(moved, "![pic](b.assets/pic.png) [index](../index.md)");
+    }
+
+    #[test]
+    fn test_rename_only_rewrites_wikilinks_to_the_moved_note() {
+        let dir = tempdir().unwrap();
+        let root = dir.path();
+        fs::create_dir_all(root.join("x")).unwrap();
+        fs::create_dir_all(root.join("y")).unwrap();
+        fs::write(root.join("x/a.md"), "").unwrap();
+        fs::write(root.join("y/a.md"), "").unwrap();
+        fs::write(root.join("y/index.md"), "[[a]] [[x/a]] [[/x/a.md]]").unwrap();
+        fs::write(root.join("index.md"), "[[a#Top]]").unwrap();
+
+        rename_path(
+            root.join("x/a.md").to_string_lossy().to_string(),
+            root.join("x/b.md").to_string_lossy().to_string(),
+            true,
+            Some(root.to_string_lossy().to_string()),
+        )
+        .unwrap();
+
+        // [[a]] next to y/a.md means that note, not the renamed one
+        assert_eq!(
+            fs::read_to_string(root.join("y/index.md")).unwrap(),
+            "[[a]] [[x/b]] [[/x/b.md]]"
+        );
+        assert_eq!(
+            fs::read_to_string(root.join("index.md")).unwrap(),
+            "[[b#Top]]"
+        );
+    }
+
+    #[test]
+    fn test_rename_falls_back_to_full_wikilink_path_when_ambiguous() {
+        let dir = tempdir().unwrap();
+        let root = dir.path();
+        fs::create_dir_all(root.join("x")).unwrap();
+        fs::create_dir_all(root.join("y")).unwrap();
+        fs::write(root.join("x/a.md"), "").unwrap();
+        fs::write(root.join("y/b.md"), "").unwrap();
+        fs::write(root.join("y/index.md"), "[[a]]").unwrap();
+
+        rename_path(
+            root.join("x/a.md").to_string_lossy().to_string(),
+            root.join("x/b.md").to_string_lossy().to_string(),
+            true,
+            Some(root.to_string_lossy().to_string()),
+        )
+        .unwrap();
+
+        // [[b]] would mean y/b.md from here
+        assert_eq!(
+            fs::read_to_string(root.join("y/index.md")).unwrap(),
+            "[[/x/b]]"
+        );
+    }
+
+    #[test]
+    fn test_rename_leaves_links_without_update() {
+        let dir = tempdir().unwrap();
+        let root = dir.path();
+        fs::create_dir_all(root.join("a.assets")).unwrap();
+        fs::write(root.join("a.assets/pic.png"), "png").unwrap();
+        fs::write(
+            root.join("a.md"),
+            "![pic](a.assets/pic.png) ![logo](<img/logo one.png>) [[a]]",
+        )
+        .unwrap();
+
+        let result = rename_path(
+            root.join("a.md").to_string_lossy().to_string(),
+            root.join("sub/b.md").to_string_lossy().to_string(),
+            false,
+            None,
+        )
+        .unwrap();
+
+        assert!(root.join("sub/b.assets/pic.png").exists());
+        assert!(result.updated_files.is_empty());
+        let moved = fs::read_to_string(root.join("sub/b.md")).unwrap();
+        assert_eq!(
+            moved,
+            "![pic](a.assets/pic.png) ![logo](<img/logo one.png>) [[a]]"
+        );
+    }
+
+    #[test]
+    fn test_rename_outside_workspace_keeps_own_links() {
+        let dir = tempdir().unwrap();
+        let root = dir.path().join("ws");
+        fs::create_dir_all(root.join("img")).unwrap();
+        fs::write(root.join("a.md"), "![logo](img/logo.png)").unwrap();
+
+        let result = rename_path(
+            root.join("a.md").to_string_lossy().to_string(),
+            dir.path().join("out/a.md").to_string_lossy().to_string(),
+            true,
+            Some(root.to_string_lossy().to_string()),
+        )
+        .unwrap();
+
+        assert_eq!(result.updated_files.len(), 1);
+        let moved = fs::read_to_string(dir.path().join("out/a.md")).unwrap();
+        assert_eq!(moved, "![logo](../ws/img/logo.png)");
+    }
+
+    #[test]
+    fn test_rename_rejects_existing_destination() {
+        let dir = tempdir().unwrap();
+        let root = dir.path();
+        fs::write(root.join("a.md"), "").unwrap();
+        fs::write(root.join("b.md"), "").unwrap();
+
+        let result = rename_path(
+            root.join("a.md").to_string_lossy().to_string(),
+            root.join("b.md").to_string_lossy().to_string(),
+            false,
+            None,
+        );
+        assert!(result.is_err());
+    }
+
+    /// A link from `Note.md` to `note.md` stands in for a case-insensitive
+    /// file system, where both names resolve to the same file.
+    #[cfg(unix)]
+    #[test]
+    fn test_rename_allows_case_only_change() {
+        let dir = tempdir().unwrap();
+        let root = dir.path();
+        fs::write(root.join("note.md"), "hello").unwrap();
+        std::os::unix::fs::symlink(root.join("note.md"), root.join("Note.md")).unwrap();
+
+        let result = rename_path(
+            root.join("note.md").to_string_lossy().to_string(),
+            root.join("Note.md").to_string_lossy().to_string(),
+            false,
+            None,
+        )
+        .unwrap();
+        assert!(result.new_path.ends_with("Note.md"));
+        assert!(!root.join("note.md").exists());
+        assert!(!fs::symlink_metadata(root.join("Note.md"))
+            .unwrap()
+            .file_type()
+            .is_symlink());
+        assert_eq!(fs::read_to_string(root.join("Note.md")).unwrap(), "hello");
+
+        // A different file under another case is still refused
+        fs::write(root.join("other.md"), "").unwrap();
+        fs::write(root.join("Other.md"), "").unwrap();
+        assert!(!is_case_only_rename(
+            &root.join("other.md"),
+            &root.join("Other.md")
+        ));
+    }
+
+    #[test]
+    fn test_duplicate_copies_sidecar_assets() {
+        let dir = tempdir().unwrap();
+        let root = dir.path();
+        fs::create_dir_all(root.join("a.assets")).unwrap();
+        fs::write(root.join("a.assets/pic.png"), "png").unwrap();
+        fs::write(
+            root.join("a.md"),
+            "![pic](a.assets/pic.png) ![logo](shared/logo.png)",
+        )
+        .unwrap();
+
+        let path = root.join("a.md").to_string_lossy().to_string();
+        let first = duplicate_file(path.clone(), None).unwrap();
+        assert!(first.new_path.ends_with("a copy.md"));
+        assert!(root.join("a copy.assets/pic.png").exists());
+        assert_eq!(
+            fs::read_to_string(root.join("a copy.md")).unwrap(),
+            "![pic](a%20copy.assets/pic.png) ![logo](shared/logo.png)"
+        );
+
+        let second = duplicate_file(path, Some(DuplicateAssets::Share)).unwrap();
+        assert!(second.new_path.ends_with("a copy 2.md"));
+        assert!(second.copied_assets.is_none());
+        assert!(!root.join("a copy 2.assets").exists());
+    }
+
+    #[test]
+    fn test_duplicate_keeps_encoding() {
+        let dir = tempdir().unwrap();
+        let root = dir.path();
+        fs::create_dir_all(root.join("a.assets")).unwrap();
+        let content = "日本語のメモです。図を見てください ![図](a.assets/pic.png)";
+        let bytes = text_encoding::encode(content, "Shift_JIS", false).unwrap();
+        fs::write(root.join("a.md"), bytes).unwrap();
+        // Taken names are skipped, not replaced
+        fs::write(root.join("a copy.md"), "keep").unwrap();
+
+        let result = duplicate_file(root.join("a.md").to_string_lossy().to_string(), None).unwrap();
+        assert!(result.new_path.ends_with("a copy 2.md"));
+        assert_eq!(fs::read_to_string(root.join("a copy.md")).unwrap(), "keep");
+        let copy = text_encoding::decode(&fs::read(root.join("a copy 2.md")).unwrap());
+        assert_eq!(copy.encoding, "Shift_JIS");
+        assert_eq!(
+            copy.content,
+            "日本語のメモです。図を見てください ![図](a%20copy%202.assets/pic.png)"
+        );
+    }
+
+    #[test]
+    fn test_batch_rename() {
+        let dir = tempdir().unwrap();
+        let root = dir.path();
+        fs::create_dir_all(root.join("daily/2024-01-02.assets")).unwrap();
+        fs::write(root.join("daily/2024-01-02.assets/pic.png"), "png").unwrap();
+        fs::write(
+            root.join("daily/2024-01-02.md"),
+            "![pic](2024-01-02.assets/pic.png)",
+        )
+        .unwrap();
+        fs::write(root.join("daily/2024-01-03.md"), "").unwrap();
+        fs::write(
+            root.join("index.md"),
+            "[day](daily/2024-01-02.md) [[2024-01-03]]",
+        )
+        .unwrap();
+
+        let rename = |dry_run| {
+            batch_rename(
+                root.join("daily").to_string_lossy().to_string(),
+                r"^(\d{4})-(\d{2})-(\d{2})".to_string(),
+                "$3.$2.$1".to_string(),
+                dry_run,
+                Some(root.to_string_lossy().to_string()),
+            )
+            .unwrap()
+        };
+
+        let planned = rename(true);
+        assert!(!planned.applied);
+        assert_eq!(planned.renames.len(), 2);
+        assert!(planned.renames[0].to.ends_with("02.01.2024.md"));
+        assert!(root.join("daily/2024-01-02.md").exists());
+
+        let result = rename(false);
+        assert!(result.applied);
+        assert!(root.join("daily/03.01.2024.md").exists());
+        assert!(root.join("daily/02.01.2024.assets/pic.png").exists());
+        assert_eq!(
+            fs::read_to_string(root.join("index.md")).unwrap(),
+            "[day](daily/02.01.2024.md) [[03.01.2024]]"
+        );
+        assert_eq!(
+            fs::read_to_string(root.join("daily/02.01.2024.md")).unwrap(),
+            "![pic](02.01.2024.assets/pic.png)"
+        );
+    }
+
+    #[test]
+    fn test_batch_rename_reports_conflicts() {
+        let dir = tempdir().unwrap();
+        let root = dir.path();
+        fs::write(root.join("a-1.md"), "").unwrap();
+        fs::write(root.join("a-2.md"), "").unwrap();
+
+        let result = batch_rename(
+            root.to_string_lossy().to_string(),
+            r"-\d".to_string(),
+            String::new(),
+            false,
+            None,
+        )
+        .unwrap();
+        assert!(!result.applied);
+        assert_eq!(result.conflicts.len(), 1);
+        assert!(root.join("a-1.md").exists());
+    }
+
+    #[test]
+    fn test_expand_date_tokens() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("note.md");
+        fs::write(&path, "").unwrap();
+
+        let today = Local::now().format("%Y").to_string();
+        assert_eq!(
+            expand_date_tokens("{{date:%Y}}-note {x}.md", &path).unwrap(),
+            format!("{today}-note {{x}}.md")
+        );
+        assert!(expand_date_tokens("{{modified:%Q}}", &path).is_err());
+    }
+
+    #[test]
+    fn test_is_in_workspace() {
+        let dir = tempdir().unwrap();
+        let root = dir.path().join("ws");
+        fs::create_dir_all(&root).unwrap();
+        let root = root.to_string_lossy().to_string();
+
+        assert!(is_in_workspace(&dir.path().join("ws/notes/a.md"), &root));
+        assert!(!is_in_workspace(&dir.path().join("other/a.md"), &root));
+    }
+}
@@ -1,5 +1,11 @@
 use serde::Serialize;
 use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::exclude::ExcludeMatcher;
+
+/// File extensions treated as markdown documents (matches bundle file associations).
+pub const MARKDOWN_EXTENSIONS: &[&str] = &["md", "markdown", "mdown", "mkd"];
 
 #[derive(Debug, Serialize)]
 pub struct DirectoryEntry {
@@ -29,9 +35,17 @@ fn is_hidden_by_metadata(_: &fs::Metadata) -> bool {
     false
 }
 
+/// List the entries of a directory.
+///
+/// When `workspace_root` is given, entries matching the workspace's exclude
+/// folders and exclude globs are left out.
 #[tauri::command]
-pub fn list_directory_entries(path: &str) -> Result<Vec<DirectoryEntry>, String> {
+pub fn list_directory_entries(
+    path: &str,
+    workspace_root: Option<String>,
+) -> Result<Vec<DirectoryEntry>, String> {
     let entries = fs::read_dir(path).map_err(|e| format!("Failed to read dir: {e}"))?;
+    let matcher = workspace_root.map(|root| ExcludeMatcher::for_root(Path::new(&root)));
     let mut results = Vec::new();
 
     for entry in entries {
@@ -48,6 +62,12 @@ pub fn list_directory_entries(path: &str) -> Result<Vec<DirectoryEntry>, String>
             .map(|file_type| file_type.is_dir())
             .unwrap_or(false);
 
+        if let Some(matcher) = &matcher {
+            if matcher.is_excluded(&entry.path(), is_directory) {
+                continue;
+            }
+        }
+
         let is_hidden = entry
             .metadata()
             .map(|metadata| is_hidden_by_metadata(&metadata) || is_hidden_by_name(&name))
@@ -64,6 +84,49 @@ pub fn list_directory_entries(path: &str) -> Result<Vec<DirectoryEntry>, String>
     Ok(results)
 }
 
+/// Check whether a path has a markdown file extension.
+pub fn is_markdown_path(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .map(|ext| MARKDOWN_EXTENSIONS.contains(&ext.to_ascii_lowercase().as_str()))
+        .unwrap_or(false)
+}
+
+/// Recursively collect markdown files under `root`.
+/// Hidden directories and anything matched by `excludes` are skipped.
+pub fn collect_markdown_files(root: &Path, excludes: &ExcludeMatcher) -> Vec<PathBuf> {
+    let mut results = Vec::new();
+    let mut stack = vec![root.to_path_buf()];
+
+    while let Some(dir) = stack.pop() {
+        let Ok(entries) = fs::read_dir(&dir) else {
+            continue;
+        };
+
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let name = entry.file_name().to_string_lossy().to_string();
+            let Ok(file_type) = entry.file_type() else {
+                continue;
+            };
+
+            if file_type.is_dir() {
+                if !is_hidden_by_name(&name) && !excludes.is_excluded(&path, true) {
+                    stack.push(path);
+                }
+            } else if file_type.is_file()
+                && is_markdown_path(&path)
+                && !excludes.is_excluded(&path, false)
+            {
+                results.push(path);
+            }
+        }
+    }
+
+    results.sort();
+    results
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -78,7 +141,7 @@ mod tests {
         fs::write(root.join(".hidden.md"), "secret").unwrap();
         fs::write(root.join("visible.md"), "hello").unwrap();
 
-        let entries = list_directory_entries(root.to_str().unwrap()).unwrap();
+        let entries = list_directory_entries(root.to_str().unwrap(), None).unwrap();
 
         let hidden = entries.iter().find(|entry| entry.name == ".hidden.md");
         let visible = entries.iter().find(|entry| entry.name == "visible.md");
@@ -88,4 +151,55 @@ mod tests {
         assert!(hidden.unwrap().is_hidden);
         assert!(!visible.unwrap().is_hidden);
     }
+
+    #[test]
+    fn collect_markdown_files_skips_excluded_and_hidden() {
+        let dir = tempdir().unwrap();
+        let root = dir.path();
+
+        fs::create_dir_all(root.join("notes")).unwrap();
+        fs::create_dir_all(root.join("node_modules")).unwrap();
+        fs::create_dir_all(root.join(".git")).unwrap();
+        fs::write(root.join("a.md"), "").unwrap();
+        fs::write(root.join("notes").join("b.markdown"), "").unwrap();
+        fs::write(root.join("notes").join("c.txt"), "").unwrap();
+        fs::write(root.join("node_modules").join("d.md"), "").unwrap();
+        fs::write(root.join(".git").join("e.md"), "").unwrap();
+        fs::write(root.join("notes").join("f.tmp.md"), "").unwrap();
+
+        let excludes = ExcludeMatcher::new(
+            root,
+            vec!["node_modules".to_string()],
+            &["*.tmp.md".to_string()],
+        );
+        let files = collect_markdown_files(root, &excludes);
+
+        assert_eq!(
+            files,
+            vec![root.join("a.md"), root.join("notes").join("b.markdown")]
+        );
+    }
+
+    #[test]
+    fn list_directory_entries_applies_workspace_excludes() {
+        let dir = tempdir().unwrap();
+        let root = dir.path();
+
+        fs::create_dir_all(root.join("build")).unwrap();
+        fs::create_dir_all(root.join("node_modules")).unwrap();
+        fs::write(root.join("a.md"), "").unwrap();
+        fs::create_dir_all(root.join(".vmark")).unwrap();
+        fs::write(
+            root.join(".vmark").join("vmark.code-workspace"),
+            r#"{ "settings": { "vmark.excludeFolders": ["node_modules"], "vmark.excludeGlobs": ["/build/**"] } }"#,
+        )
+        .unwrap();
+
+        let workspace_root = Some(root.to_string_lossy().to_string());
+        let entries = list_directory_entries(root.to_str().unwrap(), workspace_root).unwrap();
+        let mut names: Vec<&str> = entries.iter().map(|e| e.name.as_str()).collect();
+        names.sort();
+
+        assert_eq!(names, vec![".vmark", "a.md"]);
+    }
 }
@@ -0,0 +1,256 @@
+//! Per-folder settings overrides within a workspace.
+//!
+//! Any folder inside a workspace may contain `.vmark/folder.json` (JSONC allowed)
+//! overriding a subset of workspace settings for everything beneath it.
+//!
+//! Precedence (lowest to highest):
+//! 1. Built-in defaults
+//! 2. Workspace settings (`<root>/.vmark/vmark.code-workspace`)
+//! 3. `folder.json` files from the workspace root down to the target's folder;
+//!    the folder closest to the target wins.
+//!
+//! Each field set in a `folder.json` replaces the inherited value entirely
+//! (lists are not merged).
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::workspace::{self, WorkspaceConfig};
+
+/// Default folder (relative to the document) where pasted images are saved.
+pub const DEFAULT_IMAGE_SAVE_FOLDER: &str = "assets/images";
+
+/// File name of a folder override inside a folder's `.vmark` directory.
+const FOLDER_SETTINGS_FILE: &str = "folder.json";
+
+/// Overrides declared in a `.vmark/folder.json` file.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FolderOverrides {
+    #[serde(rename = "excludeFolders", default, skip_serializing_if = "Option::is_none")]
+    pub exclude_folders: Option<Vec<String>>,
+    #[serde(rename = "showHiddenFiles", default, skip_serializing_if = "Option::is_none")]
+    pub show_hidden_files: Option<bool>,
+    #[serde(rename = "imageSaveFolder", default, skip_serializing_if = "Option::is_none")]
+    pub image_save_folder: Option<String>,
+}
+
+/// Settings in effect for a specific file or folder.
+#[derive(Debug, Clone, Serialize)]
+pub struct EffectiveSettings {
+    #[serde(rename = "workspaceRoot")]
+    pub workspace_root: Option<String>,
+    #[serde(rename = "excludeFolders")]
+    pub exclude_folders: Vec<String>,
+    /// Exclude globs from the workspace (not overridable per folder)
+    #[serde(rename = "excludeGlobs")]
+    pub exclude_globs: Vec<String>,
+    #[serde(rename = "showHiddenFiles")]
+    pub show_hidden_files: bool,
+    #[serde(rename = "imageSaveFolder")]
+    pub image_save_folder: String,
+    /// Override files that were applied, in precedence order (lowest first)
+    pub sources: Vec<String>,
+}
+
+impl EffectiveSettings {
+    fn from_config(root: Option<&Path>, config: WorkspaceConfig) -> Self {
+        Self {
+            workspace_root: root.map(|r| r.to_string_lossy().to_string()),
+            exclude_folders: config.exclude_folders,
+            exclude_globs: config.exclude_globs,
+            show_hidden_files: config.show_hidden_files,
+            image_save_folder: DEFAULT_IMAGE_SAVE_FOLDER.to_string(),
+            sources: Vec::new(),
+        }
+    }
+
+    fn apply(&mut self, overrides: FolderOverrides) {
+        if let Some(exclude_folders) = overrides.exclude_folders {
+            self.exclude_folders = exclude_folders;
+        }
+        if let Some(show_hidden_files) = overrides.show_hidden_files {
+            self.show_hidden_files = show_hidden_files;
+        }
+        if let Some(image_save_folder) = overrides.image_save_folder {
+            self.image_save_folder = image_save_folder;
+        }
+    }
+}
+
+/// Get the path of a folder's override file.
+fn get_folder_settings_path(folder: &Path) -> PathBuf {
+    folder.join(".vmark").join(FOLDER_SETTINGS_FILE)
+}
+
+/// Read a folder's overrides, if it has any.
+fn read_folder_overrides(folder: &Path) -> Result<Option<FolderOverrides>, String> {
+    let path = get_folder_settings_path(folder);
+    if !path.is_file() {
+        return Ok(None);
+    }
+
+    let content = fs::read_to_string(&path)
+        .map_err(|e| format!("Failed to read {}: {e}", path.display()))?;
+    let overrides = serde_json::from_str(&workspace::strip_jsonc(&content))
+        .map_err(|e| format!("Failed to parse {}: {e}", path.display()))?;
+
+    Ok(Some(overrides))
+}
+
+/// Find the nearest ancestor folder containing a workspace file.
+pub fn find_workspace_root(path: &Path) -> Option<PathBuf> {
+    path.ancestors()
+        .find(|dir| workspace::get_workspace_file_path(dir).is_file())
+        .map(Path::to_path_buf)
+}
+
+/// Resolve the effective settings for a path.
+pub fn resolve_effective_settings(
+    path: &Path,
+    workspace_root: Option<&Path>,
+) -> Result<EffectiveSettings, String> {
+    let root = workspace_root
+        .map(Path::to_path_buf)
+        .or_else(|| find_workspace_root(path));
+
+    let config = match root.as_deref().and_then(|r| r.to_str()) {
+        Some(root_str) => workspace::read_workspace_config(root_str)?.unwrap_or_default(),
+        None => WorkspaceConfig::default(),
+    };
+    let mut settings = EffectiveSettings::from_config(root.as_deref(), config);
+
+    // Folder chain from the target's folder up to the workspace root (inclusive)
+    let start = if path.is_dir() { Some(path) } else { path.parent() };
+    let mut chain: Vec<&Path> = Vec::new();
+    if let Some(start) = start {
+        for dir in start.ancestors() {
+            chain.push(dir);
+            if root.as_deref() == Some(dir) {
+                break;
+            }
+        }
+        // Outside any workspace only the target's own folder applies
+        if root.is_none() {
+            chain.truncate(1);
+        }
+    }
+
+    // Apply from the root down so the closest folder wins
+    for dir in chain.into_iter().rev() {
+        if let Some(overrides) = read_folder_overrides(dir)? {
+            settings.apply(overrides);
+            settings
+                .sources
+                .push(get_folder_settings_path(dir).to_string_lossy().to_string());
+        }
+    }
+
+    Ok(settings)
+}
+
+/// Get the settings in effect for a file or folder, applying per-folder overrides.
+#[tauri::command]
+pub fn effective_settings(
+    path: String,
+    workspace_root: Option<String>,
+) -> Result<EffectiveSettings, String> {
+    resolve_effective_settings(Path::new(&path), workspace_root.as_deref().map(Path::new))
+}
+
+/// Read the overrides declared directly in a folder (not inherited).
+#[tauri::command]
+pub fn read_folder_settings(folder_path: String) -> Result<Option<FolderOverrides>, String> {
+    read_folder_overrides(Path::new(&folder_path))
+}
+
+/// Write (or remove, when empty) a folder's overrides.
+#[tauri::command]
+pub fn write_folder_settings(folder_path: String, overrides: FolderOverrides) -> Result<(), String> {
+    let path = get_folder_settings_path(Path::new(&folder_path));
+
+    let is_empty = overrides.exclude_folders.is_none()
+        && overrides.show_hidden_files.is_none()
+        && overrides.image_save_folder.is_none();
+    if is_empty {
+        if path.exists() {
+            fs::remove_file(&path).map_err(|e| format!("Failed to remove folder settings: {e}"))?;
+        }
+        return Ok(());
+    }
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create .vmark directory: {e}"))?;
+    }
+    let content = serde_json::to_string_pretty(&overrides)
+        .map_err(|e| format!("Failed to serialize folder settings: {e}"))?;
+    fs::write(&path, content).map_err(|e| format!("Failed to write folder settings: {e}"))?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn write_overrides(folder: &Path, json: &str) {
+        fs::create_dir_all(folder.join(".vmark")).unwrap();
+        fs::write(get_folder_settings_path(folder), json).unwrap();
+    }
+
+    #[test]
+    fn test_defaults_without_workspace() {
+        let dir = tempdir().unwrap();
+        let settings = resolve_effective_settings(&dir.path().join("a.md"), None).unwrap();
+        assert!(settings.workspace_root.is_none());
+        assert_eq!(settings.image_save_folder, DEFAULT_IMAGE_SAVE_FOLDER);
+        assert!(settings.sources.is_empty());
+    }
+
+    #[test]
+    fn test_closest_folder_wins() {
+        let dir = tempdir().unwrap();
+        let root = dir.path();
+        let config = WorkspaceConfig {
+            exclude_folders: vec!["root-excluded".to_string()],
+            show_hidden_files: true,
+            ..WorkspaceConfig::default()
+        };
+        workspace::write_workspace_config(root.to_str().unwrap(), config).unwrap();
+
+        let blog = root.join("blog");
+        let posts = blog.join("posts");
+        fs::create_dir_all(&posts).unwrap();
+        write_overrides(&blog, r#"{ "imageSaveFolder": "img", "excludeFolders": ["drafts"], }"#);
+        write_overrides(&posts, r#"{ "imageSaveFolder": "../shared-img" }"#);
+
+        let settings = resolve_effective_settings(&posts.join("hello.md"), None).unwrap();
+        assert_eq!(settings.workspace_root.as_deref(), root.to_str());
+        assert_eq!(settings.image_save_folder, "../shared-img");
+        assert_eq!(settings.exclude_folders, vec!["drafts".to_string()]);
+        assert!(settings.show_hidden_files);
+        assert_eq!(settings.sources.len(), 2);
+
+        let notes = resolve_effective_settings(&root.join("notes.md"), None).unwrap();
+        assert_eq!(notes.exclude_folders, vec!["root-excluded".to_string()]);
+        assert_eq!(notes.image_save_folder, DEFAULT_IMAGE_SAVE_FOLDER);
+    }
+
+    #[test]
+    fn test_write_empty_overrides_removes_file() {
+        let dir = tempdir().unwrap();
+        let folder = dir.path().to_string_lossy().to_string();
+
+        let overrides = FolderOverrides {
+            show_hidden_files: Some(true),
+            ..FolderOverrides::default()
+        };
+        write_folder_settings(folder.clone(), overrides).unwrap();
+        assert!(read_folder_settings(folder.clone()).unwrap().is_some());
+
+        write_folder_settings(folder.clone(), FolderOverrides::default()).unwrap();
+        assert!(read_folder_settings(folder).unwrap().is_none());
+    }
+}
@@ -0,0 +1,376 @@
+//! Footnote renumbering.
+//!
+//! `renumber_footnotes` numbers footnotes 1, 2, 3, ... in the order they
+//! are first referenced and gathers the definitions either at the end of
+//! the document or right after the paragraph that first cites them.
+//! Definitions nothing refers to are kept (after the others, numbered after
+//! them when their label is a number) and reported, as are references
+//! without a definition and labels defined twice.
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::ops::Range;
+use std::sync::LazyLock;
+
+use crate::line_endings::{self, LineEnding};
+use crate::links;
+
+/// `[^label]`
+static REFERENCE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\[\^([^\]\s]+)\]").expect("valid footnote regex"));
+
+/// `[^label]:` at the start of a line
+static DEFINITION: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^ {0,3}\[\^([^\]\s]+)\]:").expect("valid definition regex"));
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum DefinitionPlacement {
+    /// All definitions at the end of the document
+    #[default]
+    End,
+    /// Each definition after the paragraph of its first reference
+    Adjacent,
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct FootnoteOptions {
+    #[serde(default)]
+    pub placement: DefinitionPlacement,
+}
+
+#[derive(Debug, Serialize)]
+pub struct FootnoteResult {
+    pub content: String,
+    /// Number of referenced footnotes
+    pub count: usize,
+    /// Labels defined but never referenced
+    pub orphaned: Vec<String>,
+    /// Labels referenced but never defined
+    pub missing: Vec<String>,
+    /// Labels defined more than once
+    pub duplicates: Vec<String>,
+}
+
+/// A definition: its label and lines (the first starts with `[^label]:`).
+struct Definition {
+    label: String,
+    lines: Vec<String>,
+}
+
+/// References in a line outside code spans: (byte range, label).
+fn references(line: &str) -> Vec<(Range<usize>, String)> {
+    let spans = links::code_spans(line);
+    let definition_end = DEFINITION.find(line).map(|m| m.end());
+    REFERENCE
+        .captures_iter(line)
+        .filter_map(|caps| {
+            let whole = caps.get(0)?;
+            let in_code = spans.iter().any(|s| s.contains(&whole.start()));
+            let is_definition = definition_end == Some(whole.end() + 1);
+            (!in_code && !is_definition).then(|| (whole.range(), caps[1].to_string()))
+        })
+        .collect()
+}
+
+fn is_continuation(line: &str) -> bool {
+    line.starts_with("    ") || line.starts_with('\t')
+}
+
+/// Split lines into body lines and definitions, skipping fenced code. Also
+/// returns the body indices definitions were taken out before.
+fn split_definitions(lines: &[&str]) -> (Vec<String>, Vec<Definition>, HashSet<usize>) {
+    let mut body = Vec::new();
+    let mut definitions: Vec<Definition> = Vec::new();
+    let mut cuts = HashSet::new();
+    let mut fence = None;
+    let mut i = 0;
+    while i < lines.len() {
+        let line = lines[i];
+        if links::update_fence(&mut fence, line) || fence.is_some() {
+            body.push(line.to_string());
+            i += 1;
+            continue;
+        }
+        let Some(caps) = DEFINITION.captures(line) else {
+            body.push(line.to_string());
+            i += 1;
+            continue;
+        };
+        let mut end = i + 1;
+        // Indented lines (and blank lines between them) continue it
+        let mut j = end;
+        while j < lines.len() && (is_continuation(lines[j]) || lines[j].trim().is_empty()) {
+            j += 1;
+            if is_continuation(lines[j - 1]) {
+                end = j;
+            }
+        }
+        definitions.push(Definition {
+            label: caps[1].to_string(),
+            lines: lines[i..end].iter().map(|l| l.to_string()).collect(),
+        });
+        cuts.insert(body.len());
+        i = end;
+    }
+    (body, definitions, cuts)
+}
+
+/// Replace every reference label in `line`, and the label of a definition
+/// it starts, through `numbers`.
+fn renumber_line(line: &str, numbers: &HashMap<String, usize>) -> String {
+    let mut out = String::with_capacity(line.len());
+    let mut last = 0;
+    if let Some(label) = DEFINITION.captures(line).and_then(|caps| caps.get(1)) {
+        if let Some(number) = numbers.get(label.as_str()) {
+            out.push_str(&line[..label.start()]);
+            out.push_str(&number.to_string());
+            last = label.end();
+        }
+    }
+    for (range, label) in references(line) {
+        if let Some(number) = numbers.get(&label) {
+            out.push_str(&line[last..range.start]);
+            out.push_str(&format!("[^{number}]"));
+            last = range.end;
+        }
+    }
+    out.push_str(&line[last..]);
+    out
+}
+
+/// Remove blank lines that repeat or trail where definitions were taken out
+/// (before the body indices in `cuts`). Blank lines elsewhere are kept.
+fn collapse_blank_lines(lines: Vec<String>, cuts: &HashSet<usize>) -> Vec<String> {
+    let len = lines.len();
+    let mut out: Vec<String> = Vec::with_capacity(len);
+    // Inside a run of blank lines that starts at a cut
+    let mut collapsing = false;
+    for (index, line) in lines.into_iter().enumerate() {
+        collapsing = (collapsing || cuts.contains(&index)) && line.trim().is_empty();
+        if collapsing && out.last().is_some_and(|l| l.trim().is_empty()) {
+            continue;
+        }
+        out.push(line);
+    }
+    if collapsing || cuts.contains(&len) {
+        while out.last().is_some_and(|l| l.trim().is_empty()) {
+            out.pop();
+        }
+    }
+    out
+}
+
+fn renumber(content: &str, placement: DefinitionPlacement) -> FootnoteResult {
+    let crlf = line_endings::detect(content) == Some(LineEnding::Crlf);
+    let lines: Vec<&str> = content.lines().collect();
+    let (body, definitions, cuts) = split_definitions(&lines);
+    let body = collapse_blank_lines(body, &cuts);
+
+    // Number labels by first reference: body first, then inside definitions
+    let mut order: Vec<String> = Vec::new();
+    let mut first_line: HashMap<String, usize> = HashMap::new();
+    let mut fence = None;
+    for (index, line) in body.iter().enumerate() {
+        if links::update_fence(&mut fence, line) || fence.is_some() {
+            continue;
+        }
+        for (_, label) in references(line) {
+            if !order.contains(&label) {
+                first_line.insert(label.clone(), index);
+                order.push(label);
+            }
+        }
+    }
+    for definition in &definitions {
+        for line in &definition.lines {
+            for (_, label) in references(line) {
+                if !order.contains(&label) {
+                    order.push(label);
+                }
+            }
+        }
+    }
+    let mut numbers: HashMap<String, usize> = order
+        .iter()
+        .enumerate()
+        .map(|(i, label)| (label.clone(), i + 1))
+        .collect();
+
+    let mut seen: Vec<&str> = Vec::new();
+    let mut duplicates = Vec::new();
+    for definition in &definitions {
+        if seen.contains(&definition.label.as_str()) {
+            if !duplicates.contains(&definition.label) {
+                duplicates.push(definition.label.clone());
+            }
+        } else {
+            seen.push(&definition.label);
+        }
+    }
+    let missing: Vec<String> = order
+        .iter()
+        .filter(|label| !seen.contains(&label.as_str()))
+        .cloned()
+        .collect();
+    let orphaned: Vec<String> = seen
+        .iter()
+        .filter(|label| !numbers.contains_key(**label))
+        .map(|label| label.to_string())
+        .collect();
+    // A numeric orphan would clash with the new numbering
+    let numeric_orphans = orphaned
+        .iter()
+        .filter(|label| label.chars().all(|c| c.is_ascii_digit()));
+    for (index, label) in numeric_orphans.enumerate() {
+        numbers.insert(label.clone(), order.len() + 1 + index);
+    }
+
+    // Rewritten definitions: referenced ones by number, then orphans
+    let mut sorted: Vec<(usize, Vec<String>, &str)> = definitions
+        .iter()
+        .enumerate()
+        .map(|(index, definition)| {
+            let key = numbers
+                .get(&definition.label)
+                .copied()
+                .unwrap_or(order.len() + 1 + index);
+            let lines = definition
+                .lines
+                .iter()
+                .map(|line| renumber_line(line, &numbers))
+                .collect();
+            (key, lines, definition.label.as_str())
+        })
+        .collect();
+    sorted.sort_by_key(|(key, _, _)| *key);
+
+    let mut fence = None;
+    let mut body: Vec<String> = body
+        .iter()
+        .map(|line| {
+            if links::update_fence(&mut fence, line) || fence.is_some() {
+                line.clone()
+            } else {
+                renumber_line(line, &numbers)
+            }
+        })
+        .collect();
+
+    let mut trailing: Vec<String> = Vec::new();
+    match placement {
+        DefinitionPlacement::End => {
+            for (_, lines, _) in sorted {
+                trailing.extend(lines);
+            }
+        }
+        DefinitionPlacement::Adjacent => {
+            // Insert from the bottom so earlier positions stay valid
+            let mut placed: Vec<(usize, Vec<String>)> = Vec::new();
+            for (_, lines, label) in sorted {
+                match first_line.get(label) {
+                    Some(&line) => {
+                        let end = (line..body.len())
+                            .find(|&i| body[i].trim().is_empty())
+                            .unwrap_or(body.len());
+                        match placed.iter_mut().find(|(at, _)| *at == end) {
+                            Some((_, group)) => group.extend(lines),
+                            None => placed.push((end, lines)),
+                        }
+                    }
+                    None => trailing.extend(lines),
+                }
+            }
+            placed.sort_by_key(|(at, _)| std::cmp::Reverse(*at));
+            for (at, group) in placed {
+                let mut block = vec![String::new()];
+                block.extend(group);
+                body.splice(at..at, block);
+            }
+        }
+    }
+
+    let mut lines = body;
+    if !trailing.is_empty() {
+        while lines.last().is_some_and(|l| l.trim().is_empty()) {
+            lines.pop();
+        }
+        lines.push(String::new());
+        lines.extend(trailing);
+    }
+    let mut result = lines.join("\n");
+    if content.ends_with('\n') {
+        result.push('\n');
+    }
+    if crlf {
+        result = line_endings::apply(&result, LineEnding::Crlf);
+    }
+
+    FootnoteResult {
+        content: result,
+        count: order.len(),
+        orphaned,
+        missing,
+        duplicates,
+    }
+}
+
+/// Renumber the footnotes of `content` and reorganize their definitions.
+#[tauri::command]
+pub fn renumber_footnotes(content: String, options: Option<FootnoteOptions>) -> FootnoteResult {
+    renumber(&content, options.unwrap_or_default().placement)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ESSAY: &str = "Intro[^b] and `[^code]`.\n\n[^b]: Second.\n\nMore[^a][^b].\nStill here.\n\n[^a]: First,\n    continued.\n[^old]: Unused.\n\nEnd[^gone].\n";
+
+    #[test]
+    fn test_renumber_to_end() {
+        let result = renumber(ESSAY, DefinitionPlacement::End);
+        assert_eq!(
+            result.content,
+            "Intro[^1] and `[^code]`.\n\nMore[^2][^1].\nStill here.\n\nEnd[^3].\n\n\
+            [^1]: Second.\n[^2]: First,\n    continued.\n[^old]: Unused.\n"
+        );
+        assert_eq!(result.count, 3);
+        assert_eq!(result.orphaned, vec!["old"]);
+        assert_eq!(result.missing, vec!["gone"]);
+        assert!(result.duplicates.is_empty());
+    }
+
+    #[test]
+    fn test_renumber_adjacent() {
+        let result = renumber(ESSAY, DefinitionPlacement::Adjacent);
+        assert_eq!(
+            result.content,
+            "Intro[^1] and `[^code]`.\n\n[^1]: Second.\n\nMore[^2][^1].\nStill here.\n\n\
+            [^2]: First,\n    continued.\n\nEnd[^3].\n\n[^old]: Unused.\n"
+        );
+    }
+
+    #[test]
+    fn test_numeric_orphans_follow_the_numbering() {
+        let result = renumber(
+            "B[^x] A[^y]\n\n[^y]: Why.\n[^x]: Ex.\n[^1]: Old.\n",
+            DefinitionPlacement::End,
+        );
+        assert_eq!(
+            result.content,
+            "B[^1] A[^2]\n\n[^1]: Ex.\n[^2]: Why.\n[^3]: Old.\n"
+        );
+        assert_eq!(result.orphaned, vec!["1"]);
+    }
+
+    #[test]
+    fn test_blank_lines_kept_away_from_definitions() {
+        let result = renumber(
+            "One.\n\n\nTwo[^a].\n\n[^a]: Note.\n",
+            DefinitionPlacement::End,
+        );
+        assert_eq!(result.content, "One.\n\n\nTwo[^1].\n\n[^1]: Note.\n");
+    }
+}
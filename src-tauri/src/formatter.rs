@@ -0,0 +1,583 @@
+//! Markdown style normalization (`format_markdown`).
+//!
+//! Works line by line instead of parsing and re-rendering the document, so
+//! anything the style doesn't cover (HTML, math, unusual constructs) comes
+//! out exactly as it went in. Frontmatter, fenced and indented code are
+//! never touched. Emphasis is only rewritten when both delimiters are on
+//! the same line.
+
+use serde::Deserialize;
+use std::ops::Range;
+
+use crate::line_endings::{self, LineEnding};
+use crate::{frontmatter, links, tables, toc};
+
+fn default_bullet() -> char {
+    '-'
+}
+
+fn default_emphasis() -> char {
+    '*'
+}
+
+fn enabled() -> bool {
+    true
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct FormatConfig {
+    /// Bullet list marker: `-`, `*` or `+`
+    #[serde(rename = "bulletMarker", default = "default_bullet")]
+    pub bullet_marker: char,
+    /// Emphasis delimiter: `*` or `_`
+    #[serde(rename = "emphasisMarker", default = "default_emphasis")]
+    pub emphasis_marker: char,
+    /// Strong emphasis delimiter (doubled): `*` or `_`
+    #[serde(rename = "strongMarker", default = "default_emphasis")]
+    pub strong_marker: char,
+    /// ATX headings with one space after the `#`s, no closing `#`s and a
+    /// blank line before and after
+    #[serde(rename = "headingSpacing", default = "enabled")]
+    pub heading_spacing: bool,
+    /// Pad table cells so the columns line up
+    #[serde(rename = "alignTables", default = "enabled")]
+    pub align_tables: bool,
+    /// Remove trailing whitespace (two-space hard breaks are kept) and
+    /// trailing blank lines
+    #[serde(rename = "trimTrailingWhitespace", default = "enabled")]
+    pub trim_trailing_whitespace: bool,
+}
+
+impl Default for FormatConfig {
+    fn default() -> Self {
+        FormatConfig {
+            bullet_marker: default_bullet(),
+            emphasis_marker: default_emphasis(),
+            strong_marker: default_emphasis(),
+            heading_spacing: enabled(),
+            align_tables: enabled(),
+            trim_trailing_whitespace: enabled(),
+        }
+    }
+}
+
+impl FormatConfig {
+    fn validate(&self) -> Result<(), String> {
+        if !matches!(self.bullet_marker, '-' | '*' | '+') {
+            return Err(format!("Invalid bullet marker: {}", self.bullet_marker));
+        }
+        for marker in [self.emphasis_marker, self.strong_marker] {
+            if !matches!(marker, '*' | '_') {
+                return Err(format!("Invalid emphasis marker: {marker}"));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Leading blockquote markers and indentation, and the rest of the line.
+pub fn split_prefix(line: &str) -> (&str, &str) {
+    let end = line
+        .bytes()
+        .position(|b| b != b' ' && b != b'>')
+        .unwrap_or(line.len());
+    line.split_at(end)
+}
+
+fn indent_width(line: &str) -> usize {
+    line.chars()
+        .take_while(|c| matches!(c, ' ' | '\t'))
+        .map(|c| if c == '\t' { 4 } else { 1 })
+        .sum()
+}
+
+/// `***`, `- - -`, `___` and the like.
+pub fn is_thematic_break(text: &str) -> bool {
+    let marks: Vec<char> = text.chars().filter(|c| !c.is_whitespace()).collect();
+    marks.len() >= 3 && matches!(marks[0], '-' | '*' | '_') && marks.iter().all(|c| *c == marks[0])
+}
+
+/// Whether `text` (a line after `split_prefix`) starts a bullet list item.
+pub fn is_bullet_item(text: &str) -> bool {
+    let mut chars = text.chars();
+    matches!(chars.next(), Some('-' | '*' | '+'))
+        && chars.next().is_none_or(|c| c == ' ' || c == '\t')
+        && !is_thematic_break(text)
+}
+
+/// Whether `text` starts a bullet or ordered list item.
+pub fn is_list_item(text: &str) -> bool {
+    let digits = text.bytes().take_while(u8::is_ascii_digit).count();
+    let ordered = (1..=9).contains(&digits)
+        && text[digits..]
+            .strip_prefix(['.', ')'])
+            .is_some_and(|rest| rest.is_empty() || rest.starts_with([' ', '\t']));
+    ordered || is_bullet_item(text)
+}
+
+/// `[label]: destination`
+pub fn is_reference_definition(text: &str) -> bool {
+    text.starts_with('[') && !text.starts_with("[^") && text.contains("]:")
+}
+
+/// End of the inline math span (`$x$` or `$$x$$`) opening at `start`. A
+/// single `$` must hug its content and the closing one can't be followed
+/// by a digit, so prices like `$5 and $10` stay text.
+fn math_span_end(text: &str, start: usize) -> Option<usize> {
+    let bytes = text.as_bytes();
+    if start > 0 && bytes[start - 1] == b'\\' {
+        return None;
+    }
+    let run = bytes[start..].iter().take_while(|b| **b == b'$').count();
+    if run > 2
+        || bytes
+            .get(start + run)
+            .is_none_or(|b| b.is_ascii_whitespace())
+    {
+        return None;
+    }
+    let mut i = start + run;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'\\' => i += 2,
+            b'$' => {
+                let len = bytes[i..].iter().take_while(|b| **b == b'$').count();
+                let hugs = run == 2 || !bytes[i - 1].is_ascii_whitespace();
+                let digit_after = bytes.get(i + len).is_some_and(u8::is_ascii_digit);
+                if len == run && hugs && !(run == 1 && digit_after) {
+                    return Some(i + len);
+                }
+                i += len;
+            }
+            _ => i += 1,
+        }
+    }
+    None
+}
+
+/// Ranges where `*` and `_` are literal: code spans, inline math, autolinks
+/// and HTML tags, link destinations and bare URLs.
+pub fn protected_ranges(text: &str) -> Vec<Range<usize>> {
+    let mut ranges = links::code_spans(text);
+    let bytes = text.as_bytes();
+    let mut i = 0;
+    while i < bytes.len() {
+        let rest = &text[i..];
+        let end = if bytes[i] == b'<'
+            && rest[1..].starts_with(|c: char| c.is_ascii_alphabetic() || c == '/')
+        {
+            rest.find('>').map(|end| i + end + 1)
+        } else if bytes[i] == b'$' && !ranges.iter().any(|r| r.contains(&i)) {
+            math_span_end(text, i)
+        } else if rest.starts_with("](") {
+            rest.find(')').map(|end| i + end + 1)
+        } else if rest.starts_with("http://")
+            || rest.starts_with("https://")
+            || rest.starts_with("www.")
+        {
+            Some(
+                rest.find(char::is_whitespace)
+                    .map_or(text.len(), |end| i + end),
+            )
+        } else {
+            None
+        };
+        match end {
+            Some(end) => {
+                ranges.push(i..end);
+                i = end;
+            }
+            None => i += rest.chars().next().map_or(1, char::len_utf8),
+        }
+    }
+    ranges
+}
+
+fn is_punctuation(c: Option<char>) -> bool {
+    c.is_some_and(|c| !c.is_alphanumeric() && !c.is_whitespace())
+}
+
+fn is_space(c: Option<char>) -> bool {
+    c.is_none_or(char::is_whitespace)
+}
+
+/// A run of `*` or `_`. Its unmatched delimiters are `start + lo..start + hi`:
+/// closers use them up from the left, openers from the right.
+struct DelimiterRun {
+    start: usize,
+    len: usize,
+    ch: u8,
+    can_open: bool,
+    can_close: bool,
+    lo: usize,
+    hi: usize,
+}
+
+fn delimiter_runs(text: &str) -> Vec<DelimiterRun> {
+    let protected = protected_ranges(text);
+    let bytes = text.as_bytes();
+    let mut runs = Vec::new();
+    let mut i = 0;
+    while i < bytes.len() {
+        let ch = bytes[i];
+        if ch == b'\\' {
+            i += 2;
+            continue;
+        }
+        if !matches!(ch, b'*' | b'_') || protected.iter().any(|r| r.contains(&i)) {
+            i += 1;
+            continue;
+        }
+        let len = bytes[i..].iter().take_while(|b| **b == ch).count();
+        let before = text[..i].chars().next_back();
+        let after = text[i + len..].chars().next();
+        let left = !is_space(after)
+            && (!is_punctuation(after) || is_space(before) || is_punctuation(before));
+        let right = !is_space(before)
+            && (!is_punctuation(before) || is_space(after) || is_punctuation(after));
+        let (can_open, can_close) = if ch == b'*' {
+            (left, right)
+        } else {
+            (
+                left && (!right || is_punctuation(before)),
+                right && (!left || is_punctuation(after)),
+            )
+        };
+        runs.push(DelimiterRun {
+            start: i,
+            len,
+            ch,
+            can_open,
+            can_close,
+            lo: 0,
+            hi: len,
+        });
+        i += len;
+    }
+    runs
+}
+
+/// Byte ranges of matched emphasis delimiters (opener, closer), innermost
+/// first, following CommonMark's delimiter matching.
+fn emphasis_pairs(runs: &mut [DelimiterRun]) -> Vec<(Range<usize>, Range<usize>)> {
+    let mut pairs = Vec::new();
+    let mut openers: Vec<usize> = Vec::new();
+    for closer in 0..runs.len() {
+        while runs[closer].can_close && runs[closer].lo < runs[closer].hi {
+            let c = &runs[closer];
+            // The "rule of three" for runs that can both open and close
+            let found = openers.iter().rposition(|&o| {
+                let o = &runs[o];
+                let both = o.can_close || c.can_open;
+                let sum_of_three = (o.len + c.len).is_multiple_of(3);
+                let both_of_three = o.len.is_multiple_of(3) && c.len.is_multiple_of(3);
+                o.ch == c.ch && !(both && sum_of_three && !both_of_three)
+            });
+            let Some(position) = found else {
+                break;
+            };
+            let opener = openers[position];
+            let open_left = runs[opener].hi - runs[opener].lo;
+            let close_left = runs[closer].hi - runs[closer].lo;
+            let used = if open_left >= 2 && close_left >= 2 {
+                2
+            } else {
+                1
+            };
+
+            let o = &mut runs[opener];
+            o.hi -= used;
+            let open_range = o.start + o.hi..o.start + o.hi + used;
+            let o_done = o.lo == o.hi;
+            let c = &mut runs[closer];
+            let close_range = c.start + c.lo..c.start + c.lo + used;
+            c.lo += used;
+            pairs.push((open_range, close_range));
+
+            openers.truncate(position + 1);
+            if o_done {
+                openers.pop();
+            }
+        }
+        if runs[closer].can_open && runs[closer].lo < runs[closer].hi {
+            openers.push(closer);
+        }
+    }
+    pairs
+}
+
+/// Rewrite emphasis and strong delimiters in `text` to the configured
+/// markers. A pair is left alone if the new marker would merge with a
+/// neighboring delimiter or (for `_`) sit inside a word.
+fn convert_emphasis(text: &str, config: &FormatConfig) -> String {
+    let mut runs = delimiter_runs(text);
+    let pairs = emphasis_pairs(&mut runs);
+    let mut bytes = text.as_bytes().to_vec();
+    for (open, close) in pairs {
+        let target = if open.len() == 2 {
+            config.strong_marker as u8
+        } else {
+            config.emphasis_marker as u8
+        };
+        if bytes[open.start] == target {
+            continue;
+        }
+        let neighbors = [
+            open.start.checked_sub(1).map(|i| bytes[i]),
+            bytes.get(open.end).copied(),
+            close.start.checked_sub(1).map(|i| bytes[i]),
+            bytes.get(close.end).copied(),
+        ];
+        if neighbors.contains(&Some(target)) {
+            continue;
+        }
+        let intraword = target == b'_'
+            && (text[..open.start]
+                .chars()
+                .next_back()
+                .is_some_and(char::is_alphanumeric)
+                || text[close.end..]
+                    .chars()
+                    .next()
+                    .is_some_and(char::is_alphanumeric));
+        if intraword {
+            continue;
+        }
+        bytes[open].fill(target);
+        bytes[close].fill(target);
+    }
+    // Only ASCII delimiters were replaced with ASCII delimiters
+    String::from_utf8(bytes).unwrap_or_else(|_| text.to_string())
+}
+
+/// A line of text other than a heading: bullet marker and emphasis.
+fn format_text(prefix: &str, rest: &str, config: &FormatConfig) -> String {
+    let mut line = prefix.to_string();
+    let mut content = rest;
+    if is_bullet_item(rest) {
+        line.push(config.bullet_marker);
+        content = &rest[1..];
+    }
+    if is_thematic_break(rest) || is_reference_definition(rest) || rest.starts_with('<') {
+        line.push_str(content);
+    } else {
+        line.push_str(&convert_emphasis(content, config));
+    }
+    line
+}
+
+/// Tracks fenced code and `$$` display math line by line, for passes that
+/// must leave both untouched.
+#[derive(Debug, Default)]
+pub struct VerbatimBlocks {
+    fence: Option<(u8, usize)>,
+    math: bool,
+}
+
+impl VerbatimBlocks {
+    /// Whether `line` is part of a fenced code or display math block,
+    /// delimiters included.
+    pub fn update(&mut self, line: &str) -> bool {
+        if links::update_fence(&mut self.fence, line) || self.fence.is_some() {
+            return true;
+        }
+        if line.trim() == "$$" || self.math {
+            self.math ^= line.trim() == "$$";
+            return true;
+        }
+        false
+    }
+}
+
+fn format_body(body: &str, config: &FormatConfig) -> String {
+    let lines: Vec<&str> = body.lines().collect();
+    let mut out: Vec<String> = Vec::with_capacity(lines.len());
+    let mut verbatim = VerbatimBlocks::default();
+    let mut indented_code = false;
+    let mut list_context = false;
+    // The previous line was a heading that wants a blank line after it
+    let mut blank_after = false;
+
+    for (index, &line) in lines.iter().enumerate() {
+        let blank = line.trim().is_empty();
+        if blank_after && !blank {
+            out.push(String::new());
+        }
+        blank_after = false;
+
+        if verbatim.update(line) {
+            out.push(line.to_string());
+            continue;
+        }
+        let previous_blank = index == 0 || lines[index - 1].trim().is_empty();
+        let indent = indent_width(line);
+        if !(indented_code && (blank || indent >= 4)) {
+            indented_code = !blank && indent >= 4 && previous_blank && !list_context;
+        }
+        if indented_code {
+            out.push(line.to_string());
+            continue;
+        }
+        if blank {
+            let kept = if config.trim_trailing_whitespace {
+                ""
+            } else {
+                line
+            };
+            out.push(kept.to_string());
+            continue;
+        }
+
+        let (prefix, rest) = split_prefix(line);
+        let quoted = prefix.contains('>');
+        if is_list_item(rest) {
+            list_context = true;
+        } else if indent == 0 && previous_blank {
+            list_context = false;
+        }
+
+        let heading = toc::atx_heading(rest).filter(|_| config.heading_spacing);
+        if let Some((level, title)) = heading {
+            let title = convert_emphasis(title, config);
+            let marks = "#".repeat(level as usize);
+            // Blockquote markers and list item indentation are structure
+            let nested = quoted || (list_context && indent > 0);
+            let prefix = if nested { prefix } else { "" };
+            if title.is_empty() {
+                out.push(format!("{prefix}{marks}"));
+            } else {
+                out.push(format!("{prefix}{marks} {title}"));
+            }
+            if !nested {
+                let last = out.len() - 1;
+                if last > 0 && !out[last - 1].trim().is_empty() {
+                    out.insert(last, String::new());
+                }
+                blank_after = true;
+            }
+            continue;
+        }
+
+        let mut formatted = format_text(prefix, rest, config);
+        if config.trim_trailing_whitespace {
+            // A break before a blank line, list item or heading does nothing
+            let next_is_text = lines.get(index + 1).is_some_and(|next| {
+                let (_, next) = split_prefix(next);
+                !next.trim().is_empty() && !is_list_item(next) && toc::atx_heading(next).is_none()
+            });
+            let hard_break = line.ends_with("  ") && next_is_text;
+            formatted.truncate(formatted.trim_end().len());
+            if hard_break {
+                formatted.push_str("  ");
+            }
+        }
+        out.push(formatted);
+    }
+
+    if config.align_tables {
+        let refs: Vec<&str> = out.iter().map(String::as_str).collect();
+        let aligned: Vec<(Range<usize>, Vec<String>)> = tables::find_tables(&refs)
+            .into_iter()
+            .filter_map(|range| {
+                let table = tables::parse(&refs[range.clone()])?;
+                Some((range, tables::format(&table)))
+            })
+            .collect();
+        for (range, lines) in aligned.into_iter().rev() {
+            out.splice(range, lines);
+        }
+    }
+
+    if config.trim_trailing_whitespace {
+        while out.last().is_some_and(|l| l.is_empty()) {
+            out.pop();
+        }
+    }
+    let mut result = out.join("\n");
+    let final_newline = if config.trim_trailing_whitespace {
+        !result.is_empty()
+    } else {
+        body.ends_with('\n')
+    };
+    if final_newline {
+        result.push('\n');
+    }
+    result
+}
+
+/// Normalize `content` to the style in `config`, keeping its line endings.
+pub fn format(content: &str, config: &FormatConfig) -> Result<String, String> {
+    config.validate()?;
+    let crlf = line_endings::detect(content) == Some(LineEnding::Crlf);
+    let body_start = frontmatter::body_start(content);
+    let body = format_body(&content[body_start..], config);
+    let result = format!("{}{body}", &content[..body_start]);
+    Ok(if crlf {
+        line_endings::apply(&result, LineEnding::Crlf)
+    } else {
+        result
+    })
+}
+
+/// Format markdown content (the Format menu's "Format Markdown" and
+/// format-on-save).
+#[tauri::command]
+pub fn format_markdown(content: String, config: Option<FormatConfig>) -> Result<String, String> {
+    format(&content, &config.unwrap_or_default())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_markdown() {
+        let content = "---\ntitle: x\n---\n##Not heading\n#  Title ##\nText with __strong__ and _em_ and snake_case_name.   \nnext line   \n* one\n    + two\n* * *\n\n```\n* code *\n```\n\n| a | b |\n|:-|-:|\n| long cell | `x\\|` |\n\n\n";
+        let formatted = format(content, &FormatConfig::default()).unwrap();
+        assert_eq!(
+            formatted,
+            "---\ntitle: x\n---\n##Not heading\n\n# Title\n\nText with **strong** and *em* and snake_case_name.  \nnext line\n- one\n    - two\n* * *\n\n```\n* code *\n```\n\n| a         |     b |\n| :-------- | ----: |\n| long cell | `x\\|` |\n"
+        );
+    }
+
+    #[test]
+    fn test_emphasis_to_underscore() {
+        let config = FormatConfig {
+            emphasis_marker: '_',
+            strong_marker: '_',
+            ..Default::default()
+        };
+        let convert = |text: &str| convert_emphasis(text, &config);
+        assert_eq!(
+            convert("*a* and **b** and ***c***"),
+            "_a_ and __b__ and *__c__*"
+        );
+        assert_eq!(convert("un*frigging*believable"), "un*frigging*believable");
+        assert_eq!(
+            convert("`*code*` [*x*](a*b*c.md) 2 * 3 * 4"),
+            "`*code*` [_x_](a*b*c.md) 2 * 3 * 4"
+        );
+
+        let invalid = FormatConfig {
+            bullet_marker: '#',
+            ..Default::default()
+        };
+        assert!(format("- a\n", &invalid).is_err());
+    }
+
+    #[test]
+    fn test_inline_math_is_protected() {
+        let convert = |text: &str| convert_emphasis(text, &FormatConfig::default());
+        assert_eq!(convert("$a_1 + b_2$ and _x_"), "$a_1 + b_2$ and *x*");
+        assert_eq!(convert("$$x_i _y_$$ _z_"), "$$x_i _y_$$ *z*");
+        assert_eq!(convert("$5 and _x_ $10"), "$5 and *x* $10");
+        assert_eq!(convert("\\$a _b_ c$"), "\\$a *b* c$");
+    }
+
+    #[test]
+    fn test_heading_in_list_item_keeps_indent() {
+        let content = "- item\n\n  ##  Sub\n  text\n\nPara\n #  Top\n";
+        let formatted = format(content, &FormatConfig::default()).unwrap();
+        assert_eq!(formatted, "- item\n\n  ## Sub\n  text\n\nPara\n\n# Top\n");
+    }
+}
@@ -0,0 +1,490 @@
+//! Reading and patching document frontmatter.
+//!
+//! Supports YAML (`---`) and TOML (`+++`) blocks at the top of a document.
+//! Patches are applied per top-level key by replacing only that key's
+//! lines, so field order, comments and formatting of the other fields
+//! survive, as does a comment at the end of the replaced key's line. New
+//! keys are appended at the end of the block.
+
+use serde::Serialize;
+use serde_json::{Map, Number, Value};
+use std::fs;
+use std::path::Path;
+use yaml_rust2::{Yaml, YamlEmitter, YamlLoader};
+
+use crate::document_io::{self, SaveOptions};
+use crate::line_endings::{self, LineEnding};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum FrontmatterFormat {
+    Yaml,
+    Toml,
+}
+
+impl FrontmatterFormat {
+    fn delimiter(self) -> &'static str {
+        match self {
+            FrontmatterFormat::Yaml => "---",
+            FrontmatterFormat::Toml => "+++",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Frontmatter {
+    pub format: FrontmatterFormat,
+    /// The parsed block as a JSON object
+    pub data: Value,
+    /// Top-level keys in document order (`data` is sorted by key)
+    pub keys: Vec<String>,
+}
+
+/// Location of the frontmatter block: `body` is the text between the
+/// delimiter lines.
+#[derive(Debug, Clone, Copy)]
+struct Block {
+    format: FrontmatterFormat,
+    body_start: usize,
+    body_end: usize,
+}
+
+/// A top-level key and the byte range of its lines within the body.
+#[derive(Debug, Clone)]
+struct Entry {
+    key: String,
+    start: usize,
+    end: usize,
+}
+
+fn find_block(content: &str) -> Option<Block> {
+    let first = content.split_inclusive('\n').next()?;
+    let format = match first.trim_end() {
+        "---" => FrontmatterFormat::Yaml,
+        "+++" => FrontmatterFormat::Toml,
+        _ => return None,
+    };
+    let body_start = first.len();
+    let mut offset = body_start;
+    for line in content[body_start..].split_inclusive('\n') {
+        if line.trim_end() == format.delimiter() {
+            return Some(Block {
+                format,
+                body_start,
+                body_end: offset,
+            });
+        }
+        offset += line.len();
+    }
+    None
+}
+
+/// Byte offset where the document body starts, after any frontmatter.
+pub fn body_start(content: &str) -> usize {
+    find_block(content)
+        .map(|block| {
+            let closing = &content[block.body_end..];
+            block.body_end + closing.find('\n').map_or(closing.len(), |i| i + 1)
+        })
+        .unwrap_or(0)
+}
+
+fn unquote(key: &str) -> String {
+    let key = key.trim();
+    let quoted = key.len() >= 2
+        && ((key.starts_with('"') && key.ends_with('"'))
+            || (key.starts_with('\'') && key.ends_with('\'')));
+    if quoted {
+        key[1..key.len() - 1].to_string()
+    } else {
+        key.to_string()
+    }
+}
+
+/// Key defined on `line` if it starts a top-level entry.
+fn entry_key(format: FrontmatterFormat, line: &str) -> Option<String> {
+    if line.starts_with([' ', '\t', '#', '-', '[']) || line.trim().is_empty() {
+        return None;
+    }
+    match format {
+        FrontmatterFormat::Yaml => {
+            let line = line.trim_end();
+            let colon = line
+                .match_indices(':')
+                .map(|(i, _)| i)
+                .find(|&i| line[i + 1..].is_empty() || line[i + 1..].starts_with([' ', '\t']))?;
+            Some(unquote(&line[..colon]))
+        }
+        FrontmatterFormat::Toml => line.split_once('=').map(|(key, _)| unquote(key)),
+    }
+}
+
+/// Top-level entries of a frontmatter body. An entry runs until the next
+/// key, comment or (for TOML) table header; trailing blank lines are left
+/// outside it.
+fn entries(format: FrontmatterFormat, body: &str) -> Vec<Entry> {
+    let mut entries: Vec<Entry> = Vec::new();
+    let mut open = false;
+    let mut offset = 0;
+    for line in body.split_inclusive('\n') {
+        let start = offset;
+        offset += line.len();
+        if let Some(key) = entry_key(format, line) {
+            entries.push(Entry {
+                key,
+                start,
+                end: offset,
+            });
+            open = true;
+        } else if line.trim().is_empty() {
+            // Belongs to the entry only if an indented line follows
+        } else if open && !line.starts_with(['#', '[']) {
+            if let Some(entry) = entries.last_mut() {
+                entry.end = offset;
+            }
+        } else {
+            open = false;
+        }
+        if format == FrontmatterFormat::Toml && line.starts_with('[') {
+            // Keys of tables are not top-level
+            break;
+        }
+    }
+    entries
+}
+
+fn toml_to_json(value: toml::Value) -> Value {
+    match value {
+        toml::Value::String(s) => Value::String(s),
+        toml::Value::Integer(i) => Value::from(i),
+        toml::Value::Float(f) => Value::from(f),
+        toml::Value::Boolean(b) => Value::Bool(b),
+        toml::Value::Datetime(d) => Value::String(d.to_string()),
+        toml::Value::Array(items) => Value::Array(items.into_iter().map(toml_to_json).collect()),
+        toml::Value::Table(table) => Value::Object(
+            table
+                .into_iter()
+                .map(|(k, v)| (k, toml_to_json(v)))
+                .collect(),
+        ),
+    }
+}
+
+fn yaml_to_json(value: Yaml) -> Value {
+    match value {
+        Yaml::String(s) => Value::String(s),
+        Yaml::Integer(i) => Value::from(i),
+        Yaml::Real(r) => r
+            .parse::<f64>()
+            .ok()
+            .and_then(Number::from_f64)
+            .map_or(Value::String(r), Value::Number),
+        Yaml::Boolean(b) => Value::Bool(b),
+        Yaml::Array(items) => Value::Array(items.into_iter().map(yaml_to_json).collect()),
+        Yaml::Hash(hash) => Value::Object(
+            hash.into_iter()
+                .map(|(k, v)| {
+                    let key = match yaml_to_json(k) {
+                        Value::String(s) => s,
+                        other => other.to_string(),
+                    };
+                    (key, yaml_to_json(v))
+                })
+                .collect(),
+        ),
+        Yaml::Null | Yaml::Alias(_) | Yaml::BadValue => Value::Null,
+    }
+}
+
+fn json_to_yaml(value: &Value) -> Yaml {
+    match value {
+        Value::Null => Yaml::Null,
+        Value::Bool(b) => Yaml::Boolean(*b),
+        Value::Number(n) => n
+            .as_i64()
+            .map_or_else(|| Yaml::Real(n.to_string()), Yaml::Integer),
+        Value::String(s) => Yaml::String(s.clone()),
+        Value::Array(items) => Yaml::Array(items.iter().map(json_to_yaml).collect()),
+        Value::Object(map) => Yaml::Hash(
+            map.iter()
+                .map(|(k, v)| (Yaml::String(k.clone()), json_to_yaml(v)))
+                .collect(),
+        ),
+    }
+}
+
+fn parse_body(format: FrontmatterFormat, body: &str) -> Result<Value, String> {
+    let data = match format {
+        FrontmatterFormat::Yaml => YamlLoader::load_from_str(body)
+            .map(|docs| docs.into_iter().next().map_or(Value::Null, yaml_to_json))
+            .map_err(|e| format!("Invalid YAML frontmatter: {e}"))?,
+        FrontmatterFormat::Toml => toml::from_str::<toml::Table>(body)
+            .map(|table| toml_to_json(toml::Value::Table(table)))
+            .map_err(|e| format!("Invalid TOML frontmatter: {e}"))?,
+    };
+    match data {
+        Value::Null => Ok(Value::Object(Map::new())),
+        Value::Object(_) => Ok(data),
+        _ => Err("Frontmatter is not a set of key/value pairs".to_string()),
+    }
+}
+
+/// The frontmatter of `content`, if it has a block.
+pub fn parse(content: &str) -> Result<Option<Frontmatter>, String> {
+    let Some(block) = find_block(content) else {
+        return Ok(None);
+    };
+    let body = &content[block.body_start..block.body_end];
+    Ok(Some(Frontmatter {
+        format: block.format,
+        data: parse_body(block.format, body)?,
+        keys: entries(block.format, body)
+            .into_iter()
+            .map(|e| e.key)
+            .collect(),
+    }))
+}
+
+fn toml_key(key: &str) -> String {
+    let bare = !key.is_empty()
+        && key
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+    if bare {
+        key.to_string()
+    } else {
+        toml::Value::String(key.to_string()).to_string()
+    }
+}
+
+/// `key` and `value` written as a top-level entry, ending with a newline.
+fn format_entry(format: FrontmatterFormat, key: &str, value: &Value) -> Result<String, String> {
+    match format {
+        FrontmatterFormat::Yaml => {
+            let entry = Value::Object(Map::from_iter([(key.to_string(), value.clone())]));
+            let mut out = String::new();
+            YamlEmitter::new(&mut out)
+                .dump(&json_to_yaml(&entry))
+                .map_err(|e| format!("Failed to format {key}: {e}"))?;
+            Ok(format!("{}\n", out.trim_start_matches("---\n")))
+        }
+        FrontmatterFormat::Toml => {
+            let value =
+                toml::Value::try_from(value).map_err(|e| format!("Failed to format {key}: {e}"))?;
+            Ok(format!("{} = {value}\n", toml_key(key)))
+        }
+    }
+}
+
+/// Byte offset of a trailing `# comment` on `line`, including the
+/// whitespace before it. Quotes only open at the start of a value, so an
+/// apostrophe in a plain YAML scalar doesn't hide the comment.
+fn comment_start(line: &str) -> Option<usize> {
+    let mut quote = None;
+    let mut escaped = false;
+    let mut previous = ' ';
+    for (i, c) in line.char_indices() {
+        match quote {
+            Some(q) => {
+                if escaped {
+                    escaped = false;
+                } else if c == '\\' && q == '"' {
+                    escaped = true;
+                } else if c == q {
+                    quote = None;
+                }
+            }
+            None if (c == '"' || c == '\'')
+                && matches!(previous, ' ' | '\t' | ':' | '=' | '[' | '{' | ',') =>
+            {
+                quote = Some(c);
+            }
+            None if c == '#' && matches!(previous, ' ' | '\t') && i > 0 => {
+                return Some(line[..i].trim_end_matches([' ', '\t']).len());
+            }
+            None => {}
+        }
+        previous = c;
+    }
+    None
+}
+
+/// `replacement` with the inline comment of the entry's first line carried
+/// over to its own first line.
+fn keep_comment(entry_text: &str, replacement: String) -> String {
+    let first = entry_text.lines().next().unwrap_or("");
+    let Some(start) = comment_start(first) else {
+        return replacement;
+    };
+    let comment = first[start..].trim_end_matches('\r');
+    let end = replacement.find('\n').unwrap_or(replacement.len());
+    format!("{}{comment}{}", &replacement[..end], &replacement[end..])
+}
+
+/// Apply `patch` to the frontmatter of `content`: keys set to null are
+/// removed, others are replaced in place or appended. Content without
+/// frontmatter gets a new YAML block.
+fn apply_patch(content: &str, patch: &Map<String, Value>) -> Result<String, String> {
+    let crlf = line_endings::detect(content) == Some(LineEnding::Crlf);
+    let Some(block) = find_block(content) else {
+        let mut body = String::new();
+        for (key, value) in patch.iter().filter(|(_, v)| !v.is_null()) {
+            body.push_str(&format_entry(FrontmatterFormat::Yaml, key, value)?);
+        }
+        if body.is_empty() {
+            return Ok(content.to_string());
+        }
+        let block = format!("---\n{body}---\n");
+        let block = if crlf {
+            line_endings::apply(&block, LineEnding::Crlf)
+        } else {
+            block
+        };
+        return Ok(format!("{block}{content}"));
+    };
+
+    let format = block.format;
+    let mut body = content[block.body_start..block.body_end].to_string();
+    let existing = entries(format, &body);
+
+    // Appended before the first TOML table, since keys after it belong to it
+    let mut appended = String::new();
+    for (key, value) in patch {
+        if !value.is_null() && !existing.iter().any(|e| &e.key == key) {
+            appended.push_str(&format_entry(format, key, value)?);
+        }
+    }
+    if !appended.is_empty() {
+        let at = match format {
+            FrontmatterFormat::Toml => body
+                .split_inclusive('\n')
+                .scan(0, |offset, line| {
+                    let start = *offset;
+                    *offset += line.len();
+                    Some((start, line))
+                })
+                .find(|(_, line)| line.starts_with('['))
+                .map(|(start, _)| start)
+                .unwrap_or(body.len()),
+            FrontmatterFormat::Yaml => body.len(),
+        };
+        body.insert_str(at, &appended);
+    }
+
+    // Replace from the end so earlier offsets stay valid
+    for entry in existing.iter().rev() {
+        let Some(value) = patch.get(&entry.key) else {
+            continue;
+        };
+        let replacement = if value.is_null() {
+            String::new()
+        } else {
+            keep_comment(
+                &body[entry.start..entry.end],
+                format_entry(format, &entry.key, value)?,
+            )
+        };
+        body.replace_range(entry.start..entry.end, &replacement);
+    }
+
+    if crlf {
+        body = line_endings::apply(&body, LineEnding::Crlf);
+    }
+    parse_body(format, &body)?;
+    Ok(format!(
+        "{}{body}{}",
+        &content[..block.body_start],
+        &content[block.body_end..]
+    ))
+}
+
+/// Parsed frontmatter of a document, or None if it has none.
+#[tauri::command]
+pub fn get_frontmatter(path: String) -> Result<Option<Frontmatter>, String> {
+    let content = fs::read_to_string(&path).map_err(|e| format!("Failed to read file: {e}"))?;
+    parse(&content)
+}
+
+/// Set or remove (with `null`) top-level frontmatter keys of a document.
+/// Returns the updated frontmatter.
+#[tauri::command]
+pub fn set_frontmatter(path: String, patch: Map<String, Value>) -> Result<Frontmatter, String> {
+    let content = fs::read_to_string(&path).map_err(|e| format!("Failed to read file: {e}"))?;
+    let updated = apply_patch(&content, &patch)?;
+    if updated != content {
+        document_io::save_atomic(
+            Path::new(&path),
+            updated.as_bytes(),
+            &SaveOptions::default(),
+        )?;
+    }
+    parse(&updated)?.ok_or_else(|| "Document has no frontmatter".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn patch(value: Value) -> Map<String, Value> {
+        value.as_object().unwrap().clone()
+    }
+
+    #[test]
+    fn test_yaml_patch_keeps_order_and_comments() {
+        let content = "---\n# Post metadata\ntitle: Old\ntags:\n  - a\n  - b\ndate: 2024-01-02\n\ndraft: true\n---\n# Body\n";
+        let patched = apply_patch(
+            content,
+            &patch(json!({"title": "New", "tags": ["x"], "date": null, "author": "Ann"})),
+        )
+        .unwrap();
+        assert_eq!(
+            patched,
+            "---\n# Post metadata\ntitle: New\ntags:\n  - x\n\ndraft: true\nauthor: Ann\n---\n# Body\n"
+        );
+
+        assert_eq!(&patched[body_start(&patched)..], "# Body\n");
+
+        let parsed = parse(&patched).unwrap().unwrap();
+        assert_eq!(parsed.format, FrontmatterFormat::Yaml);
+        assert_eq!(parsed.keys, vec!["title", "tags", "draft", "author"]);
+        assert_eq!(parsed.data["draft"], json!(true));
+    }
+
+    #[test]
+    fn test_toml_patch() {
+        let content =
+            "+++\ntitle = \"Old # not a comment\" # keep\ndate = 2024-01-02\n\n[extra]\nx = 1\n+++\nBody\n";
+        let parsed = parse(content).unwrap().unwrap();
+        assert_eq!(parsed.data["date"], json!("2024-01-02"));
+        assert_eq!(parsed.keys, vec!["title", "date"]);
+
+        let patched = apply_patch(content, &patch(json!({"title": "New", "tags": ["a"]}))).unwrap();
+        assert_eq!(
+            patched,
+            "+++\ntitle = \"New\" # keep\ndate = 2024-01-02\n\ntags = [\"a\"]\n[extra]\nx = 1\n+++\nBody\n"
+        );
+    }
+
+    #[test]
+    fn test_yaml_patch_keeps_inline_comments() {
+        let content = "---\ntitle: It's old # keep\ntags: [a] # list\nurl: a#b\n---\n";
+        let patched = apply_patch(
+            content,
+            &patch(json!({"title": "New", "tags": ["x", "z"], "url": "c"})),
+        )
+        .unwrap();
+        assert_eq!(
+            patched,
+            "---\ntitle: New # keep\ntags: # list\n  - x\n  - z\nurl: c\n---\n"
+        );
+        let parsed = parse(&patched).unwrap().unwrap();
+        assert_eq!(parsed.data["tags"], json!(["x", "z"]));
+    }
+
+    #[test]
+    fn test_patch_adds_block() {
+        let patched = apply_patch("# Body\n", &patch(json!({"title": "T"}))).unwrap();
+        assert_eq!(patched, "---\ntitle: T\n---\n# Body\n");
+    }
+}
@@ -0,0 +1,216 @@
+//! Local version history.
+//!
+//! Every explicit save (`save_document`) stores the saved content as a
+//! gzip-compressed revision at
+//! `~/.vmark/history/<document path hash>/<unix ms>.md.gz`, so document
+//! folders stay clean.
+//! Revisions can be listed, read back, and diffed against each other or
+//! the document on disk.
+
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use serde::Serialize;
+use std::fs;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+
+use crate::compare::{self, DiffMode, DocumentDiff};
+use crate::document_io;
+
+/// Oldest revisions beyond this many are deleted
+const MAX_REVISIONS: usize = 200;
+
+const REVISION_EXTENSION: &str = ".md.gz";
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Revision {
+    pub id: String,
+    /// Unix timestamp in milliseconds
+    #[serde(rename = "createdAt")]
+    pub created_at: i64,
+    /// Size of the compressed revision on disk
+    #[serde(rename = "storedSize")]
+    pub stored_size: u64,
+}
+
+fn history_root() -> Result<PathBuf, String> {
+    dirs::home_dir()
+        .map(|home| home.join(".vmark").join("history"))
+        .ok_or_else(|| "Failed to locate home directory".to_string())
+}
+
+/// History folder of a document under `root`, named by a hash of its
+/// canonical path.
+fn history_dir_in(root: &Path, doc_path: &Path) -> Result<PathBuf, String> {
+    if doc_path.file_name().is_none() {
+        return Err(format!("Invalid document path: {}", doc_path.display()));
+    }
+    Ok(root.join(document_io::path_key(doc_path)))
+}
+
+fn history_dir(doc_path: &Path) -> Result<PathBuf, String> {
+    history_dir_in(&history_root()?, doc_path)
+}
+
+fn revision_path(dir: &Path, id: &str) -> Result<PathBuf, String> {
+    // Ids are the timestamps written here; anything else could escape `dir`
+    if id.is_empty() || !id.chars().all(|c| c.is_ascii_digit()) {
+        return Err(format!("Invalid revision id: {id}"));
+    }
+    Ok(dir.join(format!("{id}{REVISION_EXTENSION}")))
+}
+
+/// Revisions in `dir`, newest first.
+fn list_in(dir: &Path) -> Vec<Revision> {
+    let mut revisions: Vec<Revision> = fs::read_dir(dir)
+        .ok()
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter_map(|entry| {
+            let name = entry.file_name().into_string().ok()?;
+            let id = name.strip_suffix(REVISION_EXTENSION)?.to_string();
+            let created_at = id.parse().ok()?;
+            Some(Revision {
+                id,
+                created_at,
+                stored_size: entry.metadata().ok()?.len(),
+            })
+        })
+        .collect();
+    revisions.sort_by_key(|revision| std::cmp::Reverse(revision.created_at));
+    revisions
+}
+
+fn read_in(dir: &Path, id: &str) -> Result<String, String> {
+    let file = fs::File::open(revision_path(dir, id)?)
+        .map_err(|e| format!("Failed to open revision: {e}"))?;
+    let mut content = String::new();
+    GzDecoder::new(file)
+        .read_to_string(&mut content)
+        .map_err(|e| format!("Failed to read revision: {e}"))?;
+    Ok(content)
+}
+
+/// Store `content` as a new revision unless it matches the newest one.
+fn record_in(dir: &Path, content: &str, now: i64) -> Result<Option<Revision>, String> {
+    let revisions = list_in(dir);
+    if let Some(newest) = revisions.first() {
+        if read_in(dir, &newest.id).is_ok_and(|c| c == content) {
+            return Ok(None);
+        }
+    }
+
+    fs::create_dir_all(dir).map_err(|e| format!("Failed to create history directory: {e}"))?;
+    let mut created_at = now;
+    while revision_path(dir, &created_at.to_string())?.exists() {
+        created_at += 1;
+    }
+    let id = created_at.to_string();
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder
+        .write_all(content.as_bytes())
+        .map_err(|e| format!("Failed to compress revision: {e}"))?;
+    let compressed = encoder
+        .finish()
+        .map_err(|e| format!("Failed to compress revision: {e}"))?;
+    fs::write(revision_path(dir, &id)?, &compressed)
+        .map_err(|e| format!("Failed to write revision: {e}"))?;
+
+    for old in revisions.iter().skip(MAX_REVISIONS - 1) {
+        let _ = fs::remove_file(dir.join(format!("{}{REVISION_EXTENSION}", old.id)));
+    }
+    Ok(Some(Revision {
+        id,
+        created_at,
+        stored_size: compressed.len() as u64,
+    }))
+}
+
+/// Record a revision of the document just saved at `doc_path`.
+pub fn record(doc_path: &Path, content: &str) -> Result<Option<Revision>, String> {
+    record_in(
+        &history_dir(doc_path)?,
+        content,
+        chrono::Utc::now().timestamp_millis(),
+    )
+}
+
+/// Revisions of a document, newest first.
+#[tauri::command]
+pub fn list_revisions(path: String) -> Result<Vec<Revision>, String> {
+    Ok(list_in(&history_dir(Path::new(&path))?))
+}
+
+#[tauri::command]
+pub fn get_revision(path: String, id: String) -> Result<String, String> {
+    read_in(&history_dir(Path::new(&path))?, &id)
+}
+
+/// Diff revision `from` against revision `to`, or against the document
+/// on disk when `to` is absent.
+#[tauri::command]
+pub fn diff_revisions(
+    path: String,
+    from: String,
+    to: Option<String>,
+    mode: Option<DiffMode>,
+) -> Result<DocumentDiff, String> {
+    let doc_path = Path::new(&path);
+    let dir = history_dir(doc_path)?;
+    let old = read_in(&dir, &from)?;
+    let new = match &to {
+        Some(to) => read_in(&dir, to)?,
+        None => fs::read_to_string(doc_path).map_err(|e| format!("Failed to read file: {e}"))?,
+    };
+    Ok(DocumentDiff {
+        // Revision ids are their creation time
+        old_modified: from.parse().ok(),
+        new_modified: to.and_then(|to| to.parse().ok()),
+        ..compare::diff(&old, &new, mode.unwrap_or_default())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_record_and_read_revisions() {
+        let dir = tempdir().unwrap();
+        let history = dir.path().join("history");
+
+        let first = record_in(&history, "# Title\n", 1000).unwrap().unwrap();
+        assert!(record_in(&history, "# Title\n", 2000).unwrap().is_none());
+        let second = record_in(&history, "# Title\n\nBody\n", 2000)
+            .unwrap()
+            .unwrap();
+
+        let ids: Vec<_> = list_in(&history).into_iter().map(|r| r.id).collect();
+        assert_eq!(ids, vec![second.id.clone(), first.id.clone()]);
+        assert_eq!(read_in(&history, &first.id).unwrap(), "# Title\n");
+        assert_eq!(read_in(&history, &second.id).unwrap(), "# Title\n\nBody\n");
+        assert!(read_in(&history, "../secret").is_err());
+    }
+
+    #[test]
+    fn test_history_dir_is_per_document() {
+        let dir = tempdir().unwrap();
+        let root = dir.path().join("history");
+        let doc = dir.path().join("note.md");
+        fs::write(&doc, "").unwrap();
+
+        let history = history_dir_in(&root, &doc).unwrap();
+        assert!(history.starts_with(&root));
+        assert_eq!(
+            history,
+            history_dir_in(&root, &dir.path().join(".").join("note.md")).unwrap()
+        );
+        assert_ne!(
+            history,
+            history_dir_in(&root, &dir.path().join("other.md")).unwrap()
+        );
+    }
+}
@@ -0,0 +1,398 @@
+//! User keymap for menu accelerators.
+//!
+//! `~/.vmark/keybindings.json` (JSONC allowed) maps menu item ids to
+//! accelerators, e.g. `{ "bold": "CmdOrCtrl+B", "source-mode": "" }`. An
+//! empty string removes the item's shortcut. The keymap is applied on top of
+//! the defaults and the shortcuts configured in the settings UI whenever the
+//! menu is built; `reload_keybindings` re-reads it and rebuilds the menu.
+//!
+//! Every menu build checks the applied accelerators for duplicates and for
+//! shortcuts the OS keeps for itself; `get_menu_diagnostics` returns the
+//! result so the settings UI can flag bindings that won't fire.
+
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tauri::AppHandle;
+
+use crate::{menu, workspace};
+
+/// Keymap loaded from disk (item id -> accelerator)
+static KEYMAP: Mutex<Option<HashMap<String, String>>> = Mutex::new(None);
+
+/// Diagnostics of the last menu build
+static DIAGNOSTICS: Mutex<Option<MenuDiagnostics>> = Mutex::new(None);
+
+/// Shortcuts taken by the OS before the menu sees them, with who takes them.
+#[cfg(target_os = "macos")]
+const RESERVED_SHORTCUTS: &[(&str, &str)] = &[
+    ("Cmd+Tab", "App Switcher"),
+    ("Cmd+Space", "Spotlight"),
+    ("Cmd+Alt+Space", "Finder search"),
+    ("Cmd+Shift+3", "Screenshot"),
+    ("Cmd+Shift+4", "Screenshot"),
+    ("Cmd+Shift+5", "Screenshot"),
+    ("Cmd+Alt+Escape", "Force Quit"),
+    ("Ctrl+Cmd+Q", "Lock Screen"),
+    ("Cmd+Shift+Q", "Log Out"),
+    ("Ctrl+Up", "Mission Control"),
+    ("Ctrl+Down", "App Exposé"),
+    ("Cmd+H", "Hide VMark"),
+    ("Cmd+Alt+H", "Hide Others"),
+    ("Cmd+M", "Minimize"),
+];
+#[cfg(target_os = "windows")]
+const RESERVED_SHORTCUTS: &[(&str, &str)] = &[
+    ("Alt+Tab", "Task switcher"),
+    ("Alt+F4", "Close window"),
+    ("Alt+Space", "Window menu"),
+    ("Ctrl+Alt+Delete", "Security screen"),
+    ("Ctrl+Shift+Escape", "Task Manager"),
+    ("Ctrl+Escape", "Start menu"),
+    ("Super+D", "Show desktop"),
+    ("Super+E", "File Explorer"),
+    ("Super+L", "Lock screen"),
+    ("Super+V", "Clipboard history"),
+];
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+const RESERVED_SHORTCUTS: &[(&str, &str)] = &[
+    ("Alt+Tab", "Window switcher"),
+    ("Alt+F4", "Close window"),
+    ("Ctrl+Alt+Delete", "Log out"),
+    ("Ctrl+Alt+T", "Terminal"),
+    ("Super+L", "Lock screen"),
+];
+
+/// Several menu items bound to the same accelerator.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct KeybindingConflict {
+    pub accelerator: String,
+    pub ids: Vec<String>,
+}
+
+/// A menu item bound to a shortcut reserved by the OS.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ReservedShortcut {
+    pub accelerator: String,
+    pub id: String,
+    /// What the OS uses the shortcut for
+    #[serde(rename = "reservedBy")]
+    pub reserved_by: String,
+}
+
+/// Problems with the accelerators of the current menu.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct MenuDiagnostics {
+    pub conflicts: Vec<KeybindingConflict>,
+    pub reserved: Vec<ReservedShortcut>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct KeybindingsReport {
+    /// Keymap file path, if the home directory is known
+    pub path: Option<String>,
+    /// Number of bindings loaded from the file
+    pub bindings: usize,
+    pub conflicts: Vec<KeybindingConflict>,
+    /// Set when the file exists but could not be read or parsed
+    pub error: Option<String>,
+}
+
+fn keybindings_file_path() -> Option<PathBuf> {
+    dirs::home_dir().map(|home| home.join(".vmark").join("keybindings.json"))
+}
+
+/// Read a keymap file. A missing file is an empty keymap.
+fn read_keymap(path: &Path) -> Result<HashMap<String, String>, String> {
+    if !path.is_file() {
+        return Ok(HashMap::new());
+    }
+    let content =
+        fs::read_to_string(path).map_err(|e| format!("Failed to read {}: {e}", path.display()))?;
+    serde_json::from_str(&workspace::strip_jsonc(&content))
+        .map_err(|e| format!("Failed to parse {}: {e}", path.display()))
+}
+
+/// Canonical form of an accelerator for comparison: lowercase, aliases
+/// resolved, modifiers sorted, key last. `CmdOrCtrl` resolves per platform.
+fn normalize_accelerator(accel: &str) -> String {
+    let mut modifiers = Vec::new();
+    let mut key = String::new();
+    for part in accel.split('+').map(|p| p.trim().to_lowercase()) {
+        let modifier = match part.as_str() {
+            "cmdorctrl" | "commandorcontrol" | "cmdorcontrol" | "commandorctrl" => {
+                if cfg!(target_os = "macos") {
+                    "super"
+                } else {
+                    "ctrl"
+                }
+            }
+            "cmd" | "command" | "super" | "meta" => "super",
+            "ctrl" | "control" => "ctrl",
+            "alt" | "option" => "alt",
+            "shift" => "shift",
+            _ => {
+                key = part;
+                continue;
+            }
+        };
+        modifiers.push(modifier);
+    }
+    modifiers.sort_unstable();
+    modifiers.dedup();
+    modifiers.push(&key);
+    modifiers.join("+")
+}
+
+/// Accelerators bound to more than one item, in a stable order.
+fn find_conflicts(bindings: &HashMap<String, String>) -> Vec<KeybindingConflict> {
+    let mut by_accel: BTreeMap<String, (String, Vec<String>)> = BTreeMap::new();
+    for (id, accel) in bindings {
+        if accel.trim().is_empty() {
+            continue;
+        }
+        by_accel
+            .entry(normalize_accelerator(accel))
+            .or_insert_with(|| (accel.clone(), Vec::new()))
+            .1
+            .push(id.clone());
+    }
+    by_accel
+        .into_values()
+        .filter(|(_, ids)| ids.len() > 1)
+        .map(|(accelerator, mut ids)| {
+            ids.sort();
+            KeybindingConflict { accelerator, ids }
+        })
+        .collect()
+}
+
+/// Bindings that use a shortcut in `reserved`, sorted by item id.
+fn find_reserved(
+    bindings: &HashMap<String, String>,
+    reserved: &[(&str, &str)],
+) -> Vec<ReservedShortcut> {
+    let reserved: HashMap<String, &str> = reserved
+        .iter()
+        .map(|(accel, owner)| (normalize_accelerator(accel), *owner))
+        .collect();
+    let mut found: Vec<ReservedShortcut> = bindings
+        .iter()
+        .filter_map(|(id, accel)| {
+            let owner = reserved.get(&normalize_accelerator(accel))?;
+            Some(ReservedShortcut {
+                accelerator: accel.clone(),
+                id: id.clone(),
+                reserved_by: owner.to_string(),
+            })
+        })
+        .collect();
+    found.sort_by(|a, b| a.id.cmp(&b.id));
+    found
+}
+
+/// Check the accelerators applied by a menu build (item id -> accelerator)
+/// and keep the result for `get_menu_diagnostics`.
+pub fn record_diagnostics(applied: &HashMap<String, String>) {
+    let diagnostics = MenuDiagnostics {
+        conflicts: find_conflicts(applied),
+        reserved: find_reserved(applied, RESERVED_SHORTCUTS),
+    };
+    #[cfg(debug_assertions)]
+    {
+        for conflict in &diagnostics.conflicts {
+            eprintln!(
+                "[keybindings] {} is bound to several items: {}",
+                conflict.accelerator,
+                conflict.ids.join(", ")
+            );
+        }
+        for reserved in &diagnostics.reserved {
+            eprintln!(
+                "[keybindings] {} ({}) is reserved by the system for {}",
+                reserved.accelerator, reserved.id, reserved.reserved_by
+            );
+        }
+    }
+    if let Ok(mut current) = DIAGNOSTICS.lock() {
+        *current = Some(diagnostics);
+    }
+}
+
+/// Duplicate and reserved accelerators of the current menu. Empty until the
+/// menu has been built with custom shortcuts.
+#[tauri::command]
+pub fn get_menu_diagnostics() -> MenuDiagnostics {
+    DIAGNOSTICS
+        .lock()
+        .ok()
+        .and_then(|d| d.clone())
+        .unwrap_or_default()
+}
+
+/// Load the keymap from disk into memory and report on it.
+fn load() -> KeybindingsReport {
+    let path = keybindings_file_path();
+    let mut report = KeybindingsReport {
+        path: path.as_ref().map(|p| p.to_string_lossy().to_string()),
+        ..Default::default()
+    };
+    let keymap = match path
+        .as_deref()
+        .map(read_keymap)
+        .unwrap_or(Ok(HashMap::new()))
+    {
+        Ok(keymap) => keymap,
+        Err(e) => {
+            report.error = Some(e);
+            HashMap::new()
+        }
+    };
+    report.bindings = keymap.len();
+    report.conflicts = find_conflicts(&keymap);
+    if let Ok(mut current) = KEYMAP.lock() {
+        *current = Some(keymap);
+    }
+    report
+}
+
+/// The user keymap, loading it on first use.
+pub fn keymap() -> HashMap<String, String> {
+    let loaded = KEYMAP.lock().ok().and_then(|k| k.clone());
+    match loaded {
+        Some(keymap) => keymap,
+        None => {
+            let _report = load();
+            #[cfg(debug_assertions)]
+            if _report.error.is_some() || !_report.conflicts.is_empty() {
+                eprintln!("[keybindings] {:?}", _report);
+            }
+            KEYMAP
+                .lock()
+                .ok()
+                .and_then(|k| k.clone())
+                .unwrap_or_default()
+        }
+    }
+}
+
+/// Shortcuts from the settings UI with the user keymap applied on top.
+pub fn apply_keymap(shortcuts: &HashMap<String, String>) -> HashMap<String, String> {
+    let mut merged = shortcuts.clone();
+    merged.extend(keymap());
+    merged
+}
+
+/// Re-read `~/.vmark/keybindings.json` and rebuild the menu with it.
+/// Conflicts are checked across the keymap and the settings UI shortcuts.
+#[tauri::command]
+pub fn reload_keybindings(app: AppHandle) -> Result<KeybindingsReport, String> {
+    let mut report = load();
+    let shortcuts = menu::last_shortcuts();
+    report.conflicts = find_conflicts(&apply_keymap(&shortcuts));
+    menu::rebuild_menu(app, shortcuts)?;
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_read_keymap() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("keybindings.json");
+        assert!(read_keymap(&path).unwrap().is_empty());
+
+        fs::write(
+            &path,
+            "{\n  // no backtick on my layout\n  \"source-mode\": \"\",\n  \"bold\": \"Alt+B\",\n}",
+        )
+        .unwrap();
+        let keymap = read_keymap(&path).unwrap();
+        assert_eq!(keymap["source-mode"], "");
+        assert_eq!(keymap["bold"], "Alt+B");
+
+        fs::write(&path, "{ \"bold\": 1 }").unwrap();
+        assert!(read_keymap(&path).is_err());
+    }
+
+    #[test]
+    fn test_normalize_accelerator() {
+        assert_eq!(
+            normalize_accelerator("Shift+Alt+K"),
+            normalize_accelerator("option+shift+k")
+        );
+        assert_eq!(normalize_accelerator("Cmd+Ctrl+F"), "ctrl+super+f");
+        assert_ne!(
+            normalize_accelerator("Alt+K"),
+            normalize_accelerator("Alt+Shift+K")
+        );
+    }
+
+    #[test]
+    fn test_find_conflicts() {
+        let bindings: HashMap<String, String> = [
+            ("bold", "CmdOrCtrl+B"),
+            ("sidebar", "cmdorctrl+b"),
+            ("italic", "CmdOrCtrl+I"),
+            ("outline", ""),
+            ("focus-mode", ""),
+        ]
+        .into_iter()
+        .map(|(id, accel)| (id.to_string(), accel.to_string()))
+        .collect();
+
+        let conflicts = find_conflicts(&bindings);
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(conflicts[0].ids, vec!["bold", "sidebar"]);
+    }
+
+    /// Default accelerators of the custom-shortcut menu, by menu item id.
+    fn default_menu_bindings() -> HashMap<String, String> {
+        let mut bindings = HashMap::new();
+        for line in include_str!("menu.rs").lines() {
+            let (Some((_, item)), Some((_, call))) = (
+                line.split_once("with_id(app, \""),
+                line.split_once("get_accel(\""),
+            ) else {
+                continue;
+            };
+            let id = item.split('"').next().unwrap_or_default();
+            let accel = call.split('"').nth(2).unwrap_or_default();
+            bindings.insert(id.to_string(), accel.to_string());
+        }
+        bindings
+    }
+
+    #[test]
+    fn test_default_menu_has_no_conflicts() {
+        let mut bindings = default_menu_bindings();
+        assert_eq!(bindings["unordered-list"], "Alt+CmdOrCtrl+U");
+        // Ctrl+Shift+T only overlaps Insert Table where CmdOrCtrl is Ctrl
+        bindings.remove("transform-title-case");
+        assert_eq!(find_conflicts(&bindings), vec![]);
+    }
+
+    #[test]
+    fn test_find_reserved() {
+        let bindings: HashMap<String, String> = [
+            ("screenshot", "Shift+Cmd+4"),
+            ("bold", "Cmd+B"),
+            ("hide", "command+h"),
+        ]
+        .into_iter()
+        .map(|(id, accel)| (id.to_string(), accel.to_string()))
+        .collect();
+        let reserved = [("Cmd+Shift+4", "Screenshot"), ("Cmd+H", "Hide")];
+
+        let found = find_reserved(&bindings, &reserved);
+        let ids: Vec<&str> = found.iter().map(|r| r.id.as_str()).collect();
+        assert_eq!(ids, vec!["hide", "screenshot"]);
+        assert_eq!(found[1].reserved_by, "Screenshot");
+        assert_eq!(found[1].accelerator, "Shift+Cmd+4");
+    }
+}
@@ -0,0 +1,128 @@
+//! Command-line arguments.
+//!
+//! File managers on Windows and Linux pass the files to open as arguments
+//! (`Exec=vmark %F` in the `.desktop` file, the Windows file association).
+//! The `.desktop` actions and the Windows Jump List use `--new-window` and
+//! `--quick-note`. macOS delivers opened files through `RunEvent::Opened`
+//! instead, but a second launch from a terminal passes arguments there too;
+//! the single-instance plugin forwards them to the running instance.
+
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Manager};
+
+use crate::{quick_note, window_manager};
+
+/// Argument of the "New Window" action
+pub const NEW_WINDOW_ARG: &str = "--new-window";
+/// Argument of the "Quick Note" action
+pub const QUICK_NOTE_ARG: &str = "--quick-note";
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum LaunchAction {
+    NewWindow,
+    QuickNote,
+    /// A file or folder to open
+    Open(PathBuf),
+}
+
+/// Parse arguments (without the program name). Relative paths are resolved
+/// against `cwd`; `file://` URIs are accepted; missing paths and unknown
+/// flags are ignored.
+pub fn parse<I: IntoIterator<Item = String>>(args: I, cwd: &Path) -> Vec<LaunchAction> {
+    args.into_iter()
+        .filter_map(|arg| match arg.as_str() {
+            NEW_WINDOW_ARG => Some(LaunchAction::NewWindow),
+            QUICK_NOTE_ARG => Some(LaunchAction::QuickNote),
+            flag if flag.starts_with('-') => None,
+            uri if uri.starts_with("file://") => tauri::Url::parse(uri)
+                .ok()
+                .and_then(|url| url.to_file_path().ok())
+                .filter(|path| path.exists())
+                .map(LaunchAction::Open),
+            path => {
+                let path = cwd.join(path);
+                path.exists().then_some(LaunchAction::Open(path))
+            }
+        })
+        .collect()
+}
+
+/// Run launch actions. On cold start the main window from `tauri.conf.json`
+/// already serves as the new window, so `NewWindow` only opens another one
+/// when the app was already running.
+pub fn handle(app: &AppHandle, actions: Vec<LaunchAction>, cold_start: bool) {
+    for action in actions {
+        match action {
+            LaunchAction::NewWindow if !cold_start => {
+                let _ = window_manager::create_document_window(app, None, None);
+            }
+            LaunchAction::NewWindow => {}
+            LaunchAction::QuickNote => {
+                if let Err(_e) = quick_note::open_quick_note(app.clone()) {
+                    #[cfg(debug_assertions)]
+                    eprintln!("[launch_args] {}", _e);
+                }
+            }
+            LaunchAction::Open(path) => crate::open_path(app, &path),
+        }
+    }
+}
+
+/// A second launch of the app exited and forwarded its arguments
+/// (including the program name) to this instance. Runs them like a warm
+/// start after bringing the app to the front; with nothing to open, a new
+/// window is created only if there is none.
+pub fn handle_second_instance(app: &AppHandle, args: Vec<String>, cwd: &str) {
+    let actions = parse(args.into_iter().skip(1), Path::new(cwd));
+    let window = app
+        .webview_windows()
+        .into_iter()
+        .find(|(label, _)| crate::quit::is_document_window_label(label))
+        .map(|(_, window)| window);
+    match window {
+        Some(window) => {
+            let _ = window.unminimize();
+            let _ = window.show();
+            let _ = window.set_focus();
+        }
+        None if actions.is_empty() => {
+            let _ = window_manager::create_document_window(app, None, None);
+        }
+        None => {}
+    }
+    handle(app, actions, false);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_parse() {
+        let dir = tempdir().unwrap();
+        let file = dir.path().join("My Notes.md");
+        fs::write(&file, "# Notes").unwrap();
+        let uri = tauri::Url::from_file_path(&file).unwrap().to_string();
+
+        let args = [
+            "--new-window",
+            "My Notes.md",
+            "missing.md",
+            "--unknown",
+            uri.as_str(),
+            "--quick-note",
+        ];
+        let actions = parse(args.iter().map(|a| a.to_string()), dir.path());
+        assert_eq!(
+            actions,
+            vec![
+                LaunchAction::NewWindow,
+                LaunchAction::Open(file.clone()),
+                LaunchAction::Open(file),
+                LaunchAction::QuickNote,
+            ]
+        );
+    }
+}
@@ -1,16 +1,82 @@
 mod mcp_bridge;
 mod mcp_config;
 mod mcp_server;
+mod context_menu;
+mod crash_reports;
+mod exit_hooks;
+mod keybindings;
 mod menu;
 mod menu_events;
+mod menu_i18n;
+mod menu_state;
+mod plugin_menu;
 mod quit;
 mod watcher;
 mod window_manager;
+mod window_geometry;
+mod window_registry;
+mod window_settings;
+mod tab_transfer;
+mod updater;
+mod templates;
+mod quick_note;
+mod quick_capture;
+mod session;
+mod startup_timings;
+mod recovery;
 mod workspace;
+mod workspace_migration;
 mod file_tree;
+mod file_ops;
+mod file_locks;
+mod document_io;
+mod backups;
+mod history;
+mod merge;
+mod text_encoding;
+mod line_endings;
+mod write_access;
+mod frontmatter;
+mod toc;
+mod footnotes;
+mod tables;
+mod formatter;
+mod lint;
+mod table_import;
+mod typography;
+mod cjk_format;
+mod normalization;
+mod link_check;
+mod wikilinks;
+mod positions;
+mod annotations;
+mod compare;
+mod reveal;
+mod share;
+mod bundle;
+mod document_export;
+mod terminal;
+mod links;
+mod assets;
+mod folder_settings;
+mod activity;
+mod exclude;
 
+#[cfg(target_os = "macos")]
+mod macos_dock;
 #[cfg(target_os = "macos")]
 mod macos_menu;
+#[cfg(target_os = "macos")]
+mod macos_services;
+#[cfg(target_os = "macos")]
+mod macos_system_events;
+#[cfg(target_os = "macos")]
+mod macos_touch_bar;
+#[cfg(target_os = "windows")]
+mod windows_jump_list;
+#[cfg(target_os = "windows")]
+mod windows_system_events;
+mod launch_args;
 
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Mutex;
@@ -39,6 +105,55 @@ fn get_pending_file_opens() -> Vec<PendingFileOpen> {
     pending.drain(..).collect()
 }
 
+/// Open a file or folder passed by the OS (Finder, command line, Jump List).
+/// Folders open as a workspace in a new window. Files go to the main window,
+/// or are queued for it during cold start before the frontend is ready.
+fn open_path(app: &tauri::AppHandle, path: &std::path::Path) {
+    let Some(path_str) = path.to_str() else {
+        return;
+    };
+
+    // Handle directories: open as workspace
+    if path.is_dir() {
+        let _ = window_manager::create_document_window(app, None, Some(path_str));
+        return;
+    }
+
+    // Compute workspace root from file's parent directory
+    let workspace_root = window_manager::get_workspace_root_for_file(path_str);
+
+    // Check if frontend is ready (has called get_pending_file_opens)
+    if FRONTEND_READY.load(Ordering::SeqCst) {
+        // Frontend is ready - check if we have a window to emit to
+        if let Some(main_window) = app.get_webview_window("main") {
+            // Emit event to main window
+            use tauri::Emitter;
+            let payload = PendingFileOpen {
+                path: path_str.to_string(),
+                workspace_root,
+            };
+            let _ = main_window.emit("app:open-file", payload);
+        } else {
+            // No main window but frontend was ready (reopen scenario)
+            // Create a new window with the file
+            let _ = window_manager::create_document_window(
+                app,
+                Some(path_str),
+                workspace_root.as_deref(),
+            );
+        }
+    } else {
+        // Cold start - queue for the main window
+        // The main window from tauri.conf.json will handle pending files
+        if let Ok(mut pending) = PENDING_FILE_OPENS.lock() {
+            pending.push(PendingFileOpen {
+                path: path_str.to_string(),
+                workspace_root,
+            });
+        }
+    }
+}
+
 /// Debug logging from frontend (logs to terminal, debug builds only)
 #[cfg(debug_assertions)]
 #[tauri::command]
@@ -54,8 +169,14 @@ fn print_webview(window: tauri::WebviewWindow) -> Result<(), String> {
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
+    startup_timings::mark_process_start();
     #[allow(unused_mut)]
     let mut builder = tauri::Builder::default()
+        // Must be registered first: a second launch exits before any other
+        // plugin starts, and hands its arguments to this instance.
+        .plugin(tauri_plugin_single_instance::init(|app, args, cwd| {
+            launch_args::handle_second_instance(app, args, &cwd);
+        }))
         .plugin(tauri_plugin_opener::init())
         .plugin(tauri_plugin_fs::init())
         .plugin(tauri_plugin_dialog::init())
@@ -63,9 +184,10 @@ pub fn run() {
         .plugin(tauri_plugin_shell::init())
         .plugin(tauri_plugin_updater::Builder::new().build())
         .plugin(tauri_plugin_process::init())
+        .plugin(tauri_plugin_global_shortcut::Builder::new().build())
         .plugin(
             tauri_plugin_window_state::Builder::new()
-                .with_denylist(&["settings"])
+                .with_denylist(&["settings", window_manager::PRINT_PREVIEW_LABEL])
                 // Exclude VISIBLE from state restoration to prevent flash.
                 // Windows start hidden (visible: false) and are shown only
                 // after frontend emits "ready" event in mark_window_ready().
@@ -79,24 +201,146 @@ pub fn run() {
             get_pending_file_opens,
             menu::update_recent_files,
             menu::update_recent_workspaces,
+            menu::update_spelling_languages,
+            menu_state::set_menu_item_states,
+            menu_state::sync_view_menu_state,
             menu::rebuild_menu,
+            keybindings::reload_keybindings,
+            keybindings::get_menu_diagnostics,
+            plugin_menu::register_menu_items,
+            plugin_menu::unregister_menu_items,
+            menu_i18n::set_menu_language,
+            context_menu::show_context_menu,
             window_manager::new_window,
+            window_manager::new_window_with_content,
+            window_manager::take_initial_content,
             window_manager::open_file_in_new_window,
             window_manager::open_workspace_in_new_window,
             window_manager::open_workspace_with_files_in_new_window,
+            window_manager::open_side_by_side,
+            window_manager::open_paths,
+            window_manager::open_print_preview,
+            window_manager::get_display_info,
+            window_manager::snap_window,
+            quick_note::open_quick_note,
+            quick_capture::get_quick_capture_settings,
+            quick_capture::update_quick_capture_settings,
+            tab_transfer::register_tab,
+            tab_transfer::unregister_tab,
+            tab_transfer::file_owner,
+            tab_transfer::transfer_tab,
+            tab_transfer::pending_tab_transfers,
+            tab_transfer::claim_tab_transfer,
+            window_manager::set_always_on_top,
+            window_manager::toggle_fullscreen,
+            window_manager::set_represented_file,
+            window_manager::set_document_edited,
+            window_manager::set_zoom,
+            window_manager::get_zoom,
+            window_settings::update_window_settings,
+            window_settings::get_window_settings,
             window_manager::close_window,
+            window_manager::set_window_context,
+            session::update_window_session,
+            session::get_last_session,
+            session::restore_session,
+            recovery::save_recovery_snapshot,
+            recovery::remove_recovery_snapshot,
+            recovery::list_recovery_files,
+            recovery::read_recovery_file,
+            recovery::list_recoverable_documents,
+            recovery::discard_recovery,
+            window_registry::register_window_info,
+            window_registry::list_windows,
+            startup_timings::get_startup_timings,
+            crash_reports::list_crash_reports,
+            crash_reports::read_crash_report,
+            crash_reports::submit_crash_report,
+            crash_reports::delete_crash_report,
             window_manager::force_quit,
             window_manager::request_quit,
             quit::cancel_quit,
+            quit::quit_ack,
+            exit_hooks::register_exit_hook,
+            exit_hooks::unregister_exit_hook,
+            exit_hooks::ack_exit_hook,
+            quit::save_all_result,
+            updater::check_for_updates,
+            updater::download_and_install,
+            updater::set_update_check_interval,
             watcher::start_watching,
             watcher::stop_watching,
             watcher::stop_all_watchers,
             watcher::list_watchers,
             file_tree::list_directory_entries,
+            file_ops::rename_path,
+            file_ops::trash_path,
+            file_ops::duplicate_file,
+            file_ops::batch_rename,
+            file_ops::stat_path,
+            bundle::export_bundle,
+            document_export::export_document,
+            templates::create_from_template,
+            file_locks::acquire_file,
+            file_locks::release_file,
+            file_locks::get_file_owner,
+            document_io::save_document,
+            document_io::read_file_range,
+            document_io::stream_file,
+            text_encoding::read_text_smart,
+            write_access::make_writable,
+            frontmatter::get_frontmatter,
+            frontmatter::set_frontmatter,
+            toc::generate_toc,
+            footnotes::renumber_footnotes,
+            formatter::format_markdown,
+            lint::lint_document,
+            tables::edit_table,
+            table_import::import_table,
+            typography::smarten_typography,
+            cjk_format::format_cjk,
+            cjk_format::format_cjk_stream,
+            cjk_format::cancel_cjk_format,
+            link_check::check_links,
+            wikilinks::resolve_wikilink,
+            positions::set_reading_position,
+            positions::get_reading_position,
+            annotations::list_annotations,
+            annotations::add_annotation,
+            annotations::update_annotation,
+            annotations::delete_annotation,
+            compare::diff_files,
+            compare::diff_against_history,
+            compare::diff_against_disk,
+            backups::autosave_backup,
+            backups::set_backup_retention,
+            backups::list_backups,
+            backups::restore_backup,
+            backups::delete_backup,
+            history::list_revisions,
+            history::get_revision,
+            history::diff_revisions,
+            merge::check_external_change,
+            reveal::reveal_in_file_manager,
+            share::share_document,
+            terminal::open_in_terminal,
+            assets::find_orphaned_assets,
+            assets::trash_orphaned_assets,
             workspace::open_folder_dialog,
+            workspace::migrate_workspace_config,
             workspace::read_workspace_config,
             workspace::write_workspace_config,
             workspace::has_workspace_config,
+            workspace::resolve_workspace_folders,
+            workspace::workspace_relative_path,
+            workspace::list_pinned_files,
+            workspace::pin_file,
+            workspace::unpin_file,
+            folder_settings::effective_settings,
+            folder_settings::read_folder_settings,
+            folder_settings::write_folder_settings,
+            activity::record_activity,
+            activity::recent_activity,
             mcp_server::mcp_bridge_start,
             mcp_server::mcp_bridge_stop,
             mcp_server::mcp_server_start,
@@ -115,12 +359,52 @@ pub fn run() {
             print_webview,
         ])
         .setup(|app| {
-            let menu = menu::create_menu(app.handle())?;
-            app.set_menu(menu)?;
+            if let Ok(log_dir) = app.path().app_log_dir() {
+                if let Err(_e) = startup_timings::init_logging(&log_dir) {
+                    #[cfg(debug_assertions)]
+                    eprintln!("[Tauri] {}", _e);
+                }
+                crash_reports::set_log_dir(log_dir);
+            }
+            startup_timings::record_since_start("plugins");
+            let _setup_phase = startup_timings::phase("setup");
+            // After the single-instance check, so a second launch that exits
+            // right away isn't mistaken for a crash of the running instance
+            crash_reports::install();
+            workspace_migration::init(app.handle().clone());
+
+            {
+                let _menu_phase = startup_timings::phase("menu");
+                let menu = menu::create_initial_menu(app.handle())?;
+                app.set_menu(menu)?;
+            }
+            menu::refresh_workspace_menus(app.handle());
+            quick_capture::init(app.handle());
 
             // Fix macOS Help/Window menus (workaround for muda bug)
             #[cfg(target_os = "macos")]
-            macos_menu::apply_menu_fixes();
+            macos_menu::apply_menu_fixes(app.handle());
+            #[cfg(target_os = "macos")]
+            macos_dock::install_dock_menu(app.handle());
+            #[cfg(target_os = "macos")]
+            macos_services::install_services_provider(app.handle());
+            #[cfg(target_os = "macos")]
+            macos_system_events::install(app.handle());
+            #[cfg(target_os = "macos")]
+            if let Some(main_window) = app.get_webview_window("main") {
+                macos_touch_bar::install(app.handle(), &main_window);
+            }
+            #[cfg(target_os = "windows")]
+            if let Some(main_window) = app.get_webview_window("main") {
+                windows_system_events::install(app.handle(), &main_window);
+            }
+
+            // Files and .desktop / Jump List actions passed on the command line
+            #[cfg(not(target_os = "macos"))]
+            if let Ok(cwd) = std::env::current_dir() {
+                let actions = launch_args::parse(std::env::args().skip(1), &cwd);
+                launch_args::handle(app.handle(), actions, true);
+            }
 
             // Listen for "ready" events from frontend windows
             // This is used by menu_events to know when it's safe to emit events
@@ -131,6 +415,7 @@ pub fn run() {
                 if let Ok(label) = serde_json::from_str::<String>(event.payload()) {
                     #[cfg(debug_assertions)]
                     eprintln!("[Tauri] Window '{}' is ready", label);
+                    startup_timings::record_since_start("first_window");
                     menu_events::mark_window_ready(&app_handle, &label);
                 }
             });
@@ -142,6 +427,19 @@ pub fn run() {
         // Non-document windows (settings) should close normally
         .on_window_event(|window, event| {
             use tauri::Emitter;
+            // Keep the Window menu's checkmark on the focused document window
+            if let tauri::WindowEvent::Focused(true) = event {
+                if quit::is_document_window_label(window.label()) {
+                    menu::refresh_window_list(window.app_handle());
+                    menu::refresh_workspace_menus(window.app_handle());
+                    menu_state::handle_document_window_focused(window.app_handle(), window.label());
+                }
+            }
+            if let tauri::WindowEvent::Resized(_) = event {
+                if quit::is_document_window_label(window.label()) {
+                    window_manager::handle_window_resized(window.app_handle(), window.label());
+                }
+            }
             if let tauri::WindowEvent::CloseRequested { api, .. } = event {
                 let label = window.label();
                 #[cfg(debug_assertions)]
@@ -149,12 +447,13 @@ pub fn run() {
                 // Only intercept close for document windows
                 if label == "main" || label.starts_with("doc-") {
                     api.prevent_close();
+                    window_geometry::save_window_geometry(window);
                     // Include target label in payload so frontend can filter
                     let _ = window.emit("window:close-requested", label);
                     #[cfg(debug_assertions)]
                     eprintln!("[Tauri] Emitted window:close-requested to '{}'", label);
                 }
-                // Settings and other non-document windows close normally
+                // Settings, quick-note and other non-document windows close normally
             }
         });
 
@@ -211,8 +510,24 @@ pub fn run() {
                     if let tauri::WindowEvent::Destroyed = event {
                         quit::handle_window_destroyed(&app, &label);
                         menu_events::clear_window_ready(&label);
+                        window_geometry::register_window(&label, None);
+                        session::remove_window(&label);
+                        exit_hooks::remove_window(&label);
+                        file_locks::remove_window(&label);
+                        tab_transfer::handle_window_destroyed(app, &label);
+                        menu_state::remove_window(&label);
+                        window_manager::remove_window(&label);
+                        menu::refresh_window_list(app);
+                        if !app
+                            .webview_windows()
+                            .keys()
+                            .any(|l| quit::is_document_window_label(l))
+                        {
+                            menu_state::handle_no_document_windows(app);
+                        }
                     }
                 }
+                tauri::RunEvent::Exit => crash_reports::mark_clean_exit(),
                 // macOS: Clicking dock icon when no windows visible -> create new window
                 #[cfg(target_os = "macos")]
                 tauri::RunEvent::Reopen {
@@ -230,52 +545,7 @@ pub fn run() {
                     for url in urls {
                         // Convert file:// URL to path
                         if let Ok(path) = url.to_file_path() {
-                            if let Some(path_str) = path.to_str() {
-                                // Handle directories: open as workspace
-                                if path.is_dir() {
-                                    let _ = window_manager::create_document_window(
-                                        app,
-                                        None,
-                                        Some(path_str),
-                                    );
-                                    continue;
-                                }
-
-                                // Compute workspace root from file's parent directory
-                                let workspace_root =
-                                    window_manager::get_workspace_root_for_file(path_str);
-
-                                // Check if frontend is ready (has called get_pending_file_opens)
-                                if FRONTEND_READY.load(Ordering::SeqCst) {
-                                    // Frontend is ready - check if we have a window to emit to
-                                    if let Some(main_window) = app.get_webview_window("main") {
-                                        // Emit event to main window
-                                        use tauri::Emitter;
-                                        let payload = PendingFileOpen {
-                                            path: path_str.to_string(),
-                                            workspace_root,
-                                        };
-                                        let _ = main_window.emit("app:open-file", payload);
-                                    } else {
-                                        // No main window but frontend was ready (reopen scenario)
-                                        // Create a new window with the file
-                                        let _ = window_manager::create_document_window(
-                                            app,
-                                            Some(path_str),
-                                            workspace_root.as_deref(),
-                                        );
-                                    }
-                                } else {
-                                    // Cold start - queue for the main window
-                                    // The main window from tauri.conf.json will handle pending files
-                                    if let Ok(mut pending) = PENDING_FILE_OPENS.lock() {
-                                        pending.push(PendingFileOpen {
-                                            path: path_str.to_string(),
-                                            workspace_root,
-                                        });
-                                    }
-                                }
-                            }
+                            open_path(app, &path);
                         }
                     }
                 }
@@ -0,0 +1,57 @@
+//! Line-ending detection and conversion.
+//!
+//! `read_text_smart` reports the line endings a file uses, and
+//! `save_document` converts the editor's content back to them (or to a
+//! configured style), so saving doesn't rewrite every line of a file.
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum LineEnding {
+    Lf,
+    Crlf,
+    /// Both styles occur; saving leaves line endings as they are
+    Mixed,
+}
+
+/// Line endings used in `text`, or None if it has no line breaks.
+pub fn detect(text: &str) -> Option<LineEnding> {
+    let crlf = text.matches("\r\n").count();
+    let lf = text.matches('\n').count() - crlf;
+    match (lf, crlf) {
+        (0, 0) => None,
+        (_, 0) => Some(LineEnding::Lf),
+        (0, _) => Some(LineEnding::Crlf),
+        _ => Some(LineEnding::Mixed),
+    }
+}
+
+/// Convert every line break in `text` to `ending`.
+pub fn apply(text: &str, ending: LineEnding) -> String {
+    match ending {
+        LineEnding::Lf => text.replace("\r\n", "\n"),
+        LineEnding::Crlf => text.replace("\r\n", "\n").replace('\n', "\r\n"),
+        LineEnding::Mixed => text.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect() {
+        assert_eq!(detect("one line"), None);
+        assert_eq!(detect("a\nb\n"), Some(LineEnding::Lf));
+        assert_eq!(detect("a\r\nb\r\n"), Some(LineEnding::Crlf));
+        assert_eq!(detect("a\r\nb\n"), Some(LineEnding::Mixed));
+    }
+
+    #[test]
+    fn test_apply() {
+        assert_eq!(apply("a\nb\r\n", LineEnding::Crlf), "a\r\nb\r\n");
+        assert_eq!(apply("a\r\nb\n", LineEnding::Lf), "a\nb\n");
+        assert_eq!(apply("a\r\nb\n", LineEnding::Mixed), "a\r\nb\n");
+    }
+}
@@ -0,0 +1,406 @@
+//! Dead link checking for one document or a whole workspace.
+//!
+//! Relative links and images are checked against the filesystem, including
+//! `#heading` fragments into markdown files, and wiki links must name a
+//! note in the workspace. External `http(s)` URLs are only requested when
+//! asked for: one HEAD request per unique URL (GET if HEAD is refused), a
+//! few in parallel, with a pause between requests to the same host.
+
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use crate::exclude::ExcludeMatcher;
+use crate::file_tree;
+use crate::links::{self, LinkKind, LinkRef};
+use crate::toc::{self, AnchorStyle};
+use crate::wikilinks;
+
+const DEFAULT_CONCURRENCY: usize = 8;
+const DEFAULT_TIMEOUT_MS: u64 = 10_000;
+const MAX_REDIRECTS: u32 = 5;
+
+/// Pause between two requests to the same host
+const HOST_INTERVAL: Duration = Duration::from_millis(500);
+
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum LinkScope {
+    /// One document; wiki links resolve within `workspace_root` if given,
+    /// otherwise within the document's folder
+    Document {
+        path: String,
+        #[serde(rename = "workspaceRoot", default)]
+        workspace_root: Option<String>,
+    },
+    /// Every markdown file under `root`, minus excluded folders
+    Workspace { root: String },
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct CheckOptions {
+    /// Request external URLs; otherwise they are reported as skipped
+    #[serde(default)]
+    pub external: bool,
+    /// Timeout of each request (default 10 s)
+    #[serde(rename = "timeoutMs", default)]
+    pub timeout_ms: Option<u64>,
+    /// Requests in flight at once (default 8)
+    #[serde(default)]
+    pub concurrency: Option<usize>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum LinkState {
+    Ok,
+    /// The file or wiki note does not exist
+    Missing,
+    /// The file exists but has no such heading
+    MissingAnchor,
+    /// The server answered with an error status
+    Broken,
+    /// No answer: DNS, connection or TLS failure, or timeout
+    Unreachable,
+    /// External link not requested
+    Skipped,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct LinkStatus {
+    pub file: String,
+    /// 1-based line of the link
+    pub line: usize,
+    pub target: String,
+    pub state: LinkState,
+    #[serde(rename = "httpStatus")]
+    pub http_status: Option<u16>,
+    /// What is wrong with the link, for anything but `Ok` and `Skipped`
+    pub message: Option<String>,
+}
+
+/// Outcome of checking one link: state, HTTP status, message.
+type Outcome = (LinkState, Option<u16>, Option<String>);
+
+fn ok() -> Outcome {
+    (LinkState::Ok, None, None)
+}
+
+fn is_web_url(target: &str) -> bool {
+    let lower = target.trim().to_ascii_lowercase();
+    lower.starts_with("http://") || lower.starts_with("https://")
+}
+
+fn host(url: &str) -> &str {
+    let rest = url.split_once("://").map_or(url, |(_, rest)| rest);
+    rest.split(['/', '?', '#']).next().unwrap_or_default()
+}
+
+/// Whether markdown `content` has a heading (by anchor or text) or an
+/// HTML `id`/`name` matching `fragment`.
+fn has_anchor(content: &str, fragment: &str) -> bool {
+    let fragment = urlencoding::decode(fragment)
+        .map(|s| s.into_owned())
+        .unwrap_or_else(|_| fragment.to_string());
+    toc::scan_headings(content, AnchorStyle::Github)
+        .iter()
+        .any(|h| h.anchor == fragment.to_lowercase() || h.text.eq_ignore_ascii_case(&fragment))
+        || content.contains(&format!("id=\"{fragment}\""))
+        || content.contains(&format!("name=\"{fragment}\""))
+}
+
+/// Documents whose contents are needed for anchor checks, read once.
+#[derive(Default)]
+struct ContentCache(HashMap<PathBuf, Option<String>>);
+
+impl ContentCache {
+    fn get(&mut self, path: &Path) -> Option<&str> {
+        self.0
+            .entry(path.to_path_buf())
+            .or_insert_with(|| fs::read_to_string(path).ok())
+            .as_deref()
+    }
+}
+
+fn check_fragment(path: &Path, fragment: &str, cache: &mut ContentCache) -> Outcome {
+    let fragment = fragment.trim_start_matches('#');
+    if fragment.is_empty() || !file_tree::is_markdown_path(path) {
+        return ok();
+    }
+    match cache.get(path) {
+        Some(content) if !has_anchor(content, fragment) => (
+            LinkState::MissingAnchor,
+            None,
+            Some(format!("No heading #{fragment}")),
+        ),
+        _ => ok(),
+    }
+}
+
+fn check_wikilink(link: &LinkRef, file: &Path, root: &Path, cache: &mut ContentCache) -> Outcome {
+    let (name, fragment) = links::split_fragment(&link.target);
+    match wikilinks::resolve(root, file, &link.target).path {
+        Some(path) => check_fragment(Path::new(&path), fragment, cache),
+        None => (
+            LinkState::Missing,
+            None,
+            Some(format!("No note named {}", name.trim())),
+        ),
+    }
+}
+
+fn check_local(link: &LinkRef, file: &Path, cache: &mut ContentCache) -> Outcome {
+    let (path, fragment) = links::split_fragment(link.target.trim());
+    if path.is_empty() {
+        return check_fragment(file, fragment, cache);
+    }
+    let dir = file.parent().unwrap_or(Path::new(""));
+    let resolved = links::resolve_link_path(dir, path);
+    if !resolved.exists() {
+        return (
+            LinkState::Missing,
+            None,
+            Some(format!("{} not found", resolved.display())),
+        );
+    }
+    check_fragment(&resolved, fragment, cache)
+}
+
+fn request(agent: &ureq::Agent, url: &str) -> Outcome {
+    let status = |result: Result<ureq::Response, ureq::Error>| match result {
+        Ok(response) => Ok(response.status()),
+        Err(ureq::Error::Status(code, _)) => Ok(code),
+        Err(ureq::Error::Transport(e)) => Err(e.to_string()),
+    };
+    let mut result = status(agent.head(url).call());
+    // Some servers refuse HEAD; ask again with GET
+    if matches!(result, Ok(403 | 405 | 501)) {
+        result = status(agent.get(url).call());
+    }
+    match result {
+        Ok(code) if code < 400 => (LinkState::Ok, Some(code), None),
+        Ok(code) => (LinkState::Broken, Some(code), Some(format!("HTTP {code}"))),
+        Err(e) => (LinkState::Unreachable, None, Some(e)),
+    }
+}
+
+/// Request every URL, `concurrency` at a time, keeping requests to the
+/// same host `HOST_INTERVAL` apart.
+fn check_urls(urls: Vec<String>, options: &CheckOptions) -> HashMap<String, Outcome> {
+    let agent = ureq::AgentBuilder::new()
+        .timeout(Duration::from_millis(
+            options.timeout_ms.unwrap_or(DEFAULT_TIMEOUT_MS),
+        ))
+        .redirects(MAX_REDIRECTS)
+        .user_agent(concat!(
+            "VMark/",
+            env!("CARGO_PKG_VERSION"),
+            " link checker"
+        ))
+        .build();
+    let workers = options
+        .concurrency
+        .unwrap_or(DEFAULT_CONCURRENCY)
+        .clamp(1, urls.len().max(1));
+    let queue = Mutex::new(VecDeque::from(urls));
+    let next_slot: Mutex<HashMap<String, Instant>> = Mutex::new(HashMap::new());
+    let results = Mutex::new(HashMap::new());
+
+    std::thread::scope(|scope| {
+        for _ in 0..workers {
+            scope.spawn(|| loop {
+                let Some(url) = queue.lock().ok().and_then(|mut q| q.pop_front()) else {
+                    return;
+                };
+                let wait = next_slot.lock().map_or(Duration::ZERO, |mut slots| {
+                    let now = Instant::now();
+                    let slot = slots
+                        .get(host(&url))
+                        .copied()
+                        .filter(|slot| *slot > now)
+                        .unwrap_or(now);
+                    slots.insert(host(&url).to_string(), slot + HOST_INTERVAL);
+                    slot - now
+                });
+                std::thread::sleep(wait);
+                let outcome = request(&agent, &url);
+                if let Ok(mut results) = results.lock() {
+                    results.insert(url, outcome);
+                }
+            });
+        }
+    });
+    results.into_inner().unwrap_or_default()
+}
+
+fn line_of(content: &str, offset: usize) -> usize {
+    content[..offset].matches('\n').count() + 1
+}
+
+fn check(scope: LinkScope, options: &CheckOptions) -> Result<Vec<LinkStatus>, String> {
+    let (files, root) = match scope {
+        LinkScope::Document {
+            path,
+            workspace_root,
+        } => {
+            let path = PathBuf::from(path);
+            let root = workspace_root
+                .map(PathBuf::from)
+                .or_else(|| path.parent().map(Path::to_path_buf))
+                .unwrap_or_default();
+            (vec![path], root)
+        }
+        LinkScope::Workspace { root } => {
+            let root = PathBuf::from(root);
+            let excludes = ExcludeMatcher::for_root(&root);
+            (file_tree::collect_markdown_files(&root, &excludes), root)
+        }
+    };
+    let mut cache = ContentCache::default();
+    let mut statuses = Vec::new();
+    let mut pending: Vec<(usize, String)> = Vec::new();
+
+    for file in &files {
+        let content = fs::read_to_string(file)
+            .map_err(|e| format!("Failed to read {}: {e}", file.display()))?;
+        for link in links::scan_links(&content) {
+            let target = link.target.trim();
+            let outcome = if link.kind == LinkKind::WikiLink {
+                check_wikilink(&link, file, &root, &mut cache)
+            } else if is_web_url(target) {
+                if options.external {
+                    pending.push((statuses.len(), target.to_string()));
+                }
+                (LinkState::Skipped, None, None)
+            } else if target.starts_with('#') || links::is_local_target(target) {
+                check_local(&link, file, &mut cache)
+            } else {
+                // mailto:, tel: and other schemes
+                continue;
+            };
+            statuses.push(LinkStatus {
+                file: file.to_string_lossy().to_string(),
+                line: line_of(&content, link.start),
+                target: link.target.clone(),
+                state: outcome.0,
+                http_status: outcome.1,
+                message: outcome.2,
+            });
+        }
+    }
+
+    if !pending.is_empty() {
+        let mut urls: Vec<String> = pending.iter().map(|(_, url)| url.clone()).collect();
+        urls.sort();
+        urls.dedup();
+        let results = check_urls(urls, options);
+        for (index, url) in pending {
+            if let Some((state, http_status, message)) = results.get(&url).cloned() {
+                let status = &mut statuses[index];
+                status.state = state;
+                status.http_status = http_status;
+                status.message = message;
+            }
+        }
+    }
+    Ok(statuses)
+}
+
+/// Check the links of a document or workspace; returns the status of
+/// every link, in document order.
+#[tauri::command]
+pub async fn check_links(
+    scope: LinkScope,
+    options: Option<CheckOptions>,
+) -> Result<Vec<LinkStatus>, String> {
+    let options = options.unwrap_or_default();
+    tauri::async_runtime::spawn_blocking(move || check(scope, &options))
+        .await
+        .map_err(|e| format!("Link check failed: {e}"))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_check_local_links() {
+        let dir = tempdir().unwrap();
+        let root = dir.path();
+        fs::create_dir(root.join("notes")).unwrap();
+        fs::write(root.join("notes/Other Note.md"), "# Setup Guide\n").unwrap();
+        fs::write(root.join("pic.png"), b"png").unwrap();
+        let doc = root.join("index.md");
+        fs::write(
+            &doc,
+            "# Intro\n[ok](notes/Other%20Note.md#setup-guide) [bad](notes/Other%20Note.md#nope)\n\
+             ![img](pic.png) [gone](missing.md) [top](#intro)\n\
+             [[Other Note#Setup Guide]] [[Nobody]] [web](https://example.com) <mailto:a@b.c>\n",
+        )
+        .unwrap();
+
+        let scope = LinkScope::Document {
+            path: doc.to_string_lossy().to_string(),
+            workspace_root: Some(root.to_string_lossy().to_string()),
+        };
+        let states: Vec<(usize, String, LinkState)> = check(scope, &CheckOptions::default())
+            .unwrap()
+            .into_iter()
+            .map(|s| (s.line, s.target, s.state))
+            .collect();
+        assert_eq!(
+            states,
+            vec![
+                (
+                    2,
+                    "notes/Other%20Note.md#setup-guide".to_string(),
+                    LinkState::Ok
+                ),
+                (
+                    2,
+                    "notes/Other%20Note.md#nope".to_string(),
+                    LinkState::MissingAnchor
+                ),
+                (3, "pic.png".to_string(), LinkState::Ok),
+                (3, "missing.md".to_string(), LinkState::Missing),
+                (3, "#intro".to_string(), LinkState::Ok),
+                (4, "Other Note#Setup Guide".to_string(), LinkState::Ok),
+                (4, "Nobody".to_string(), LinkState::Missing),
+                (4, "https://example.com".to_string(), LinkState::Skipped),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_check_urls() {
+        use std::io::{Read, Write};
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        std::thread::spawn(move || {
+            for mut stream in listener.incoming().flatten() {
+                let mut buf = [0; 1024];
+                let n = stream.read(&mut buf).unwrap_or(0);
+                let request = String::from_utf8_lossy(&buf[..n]);
+                let status = if request.contains("/gone") {
+                    "404 Not Found"
+                } else {
+                    "200 OK"
+                };
+                let _ = write!(stream, "HTTP/1.1 {status}\r\nContent-Length: 0\r\n\r\n");
+            }
+        });
+
+        let ok = format!("http://127.0.0.1:{port}/page");
+        let gone = format!("http://127.0.0.1:{port}/gone");
+        let results = check_urls(vec![ok.clone(), gone.clone()], &CheckOptions::default());
+        assert_eq!(results[&ok].0, LinkState::Ok);
+        assert_eq!(
+            results[&gone],
+            (LinkState::Broken, Some(404), Some("HTTP 404".to_string()))
+        );
+    }
+}
@@ -0,0 +1,563 @@
+//! Markdown link scanning and rewriting.
+//!
+//! A lightweight scanner (not a full CommonMark parser) that finds link
+//! destinations in markdown text so they can be resolved or rewritten:
+//! - Inline links and images: `[text](dest)`, `![alt](<dest with spaces>)`
+//! - Reference definitions: `[id]: dest`
+//! - HTML images: `<img src="dest">`
+//! - Wiki links: `[[Note]]`, `[[folder/Note#Heading|Alias]]`, `![[embed.png]]`
+//!
+//! Fenced code blocks and inline code spans are skipped.
+
+use std::ops::Range;
+use std::path::{Component, Path, PathBuf};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LinkKind {
+    Inline,
+    Image,
+    Reference,
+    Html,
+    WikiLink,
+}
+
+/// A link destination found in markdown content.
+#[derive(Debug, Clone)]
+pub struct LinkRef {
+    pub kind: LinkKind,
+    /// Raw destination text (without angle brackets or wiki alias)
+    pub target: String,
+    /// Byte range of `target` within the scanned content
+    pub start: usize,
+    pub end: usize,
+    /// Destination was written as `<...>` (spaces allowed unescaped)
+    pub bracketed: bool,
+}
+
+/// Scan markdown content and return every link destination in document order.
+pub fn scan_links(content: &str) -> Vec<LinkRef> {
+    let mut links = Vec::new();
+    let mut fence: Option<(u8, usize)> = None;
+    let mut offset = 0;
+
+    for line in content.split_inclusive('\n') {
+        let line_start = offset;
+        offset += line.len();
+
+        let trimmed = line.trim_start_matches(' ');
+        let indent = line.len() - trimmed.len();
+
+        if update_fence(&mut fence, line) || fence.is_some() {
+            continue;
+        }
+
+        if indent <= 3 {
+            if let Some(link) = scan_reference_definition(trimmed, line_start + indent) {
+                links.push(link);
+                continue;
+            }
+        }
+
+        scan_inline(line, line_start, &mut links);
+    }
+
+    links
+}
+
+/// Fence marker (character and run length) opening or closing a fenced
+/// code block: ``` or ~~~ with up to 3 spaces of indentation.
+pub fn fence_marker(line: &str) -> Option<(u8, usize)> {
+    let trimmed = line.trim_start_matches(' ');
+    if line.len() - trimmed.len() > 3 {
+        return None;
+    }
+    let ch = *trimmed.as_bytes().first()?;
+    if ch != b'`' && ch != b'~' {
+        return None;
+    }
+    let run = trimmed.bytes().take_while(|b| *b == ch).count();
+    (run >= 3).then_some((ch, run))
+}
+
+/// Track fenced code blocks line by line. Returns true if `line` opens or
+/// closes a fence; `fence` holds the open fence, if any.
+pub fn update_fence(fence: &mut Option<(u8, usize)>, line: &str) -> bool {
+    let Some((ch, run)) = fence_marker(line) else {
+        return false;
+    };
+    match *fence {
+        None => {
+            *fence = Some((ch, run));
+            true
+        }
+        Some((open_ch, open_run)) if open_ch == ch && run >= open_run => {
+            *fence = None;
+            true
+        }
+        _ => false,
+    }
+}
+
+/// Byte ranges of inline code spans in a line.
+pub fn code_spans(line: &str) -> Vec<Range<usize>> {
+    let bytes = line.as_bytes();
+    let mut spans = Vec::new();
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] != b'`' {
+            i += 1;
+            continue;
+        }
+        let run = bytes[i..].iter().take_while(|b| **b == b'`').count();
+        let start = i;
+        i += run;
+        // The span closes at the next run of the same length
+        let mut j = i;
+        while j < bytes.len() {
+            let close = bytes[j..].iter().take_while(|b| **b == b'`').count();
+            if close == run {
+                spans.push(start..j + close);
+                i = j + close;
+                break;
+            }
+            j += close.max(1);
+        }
+    }
+    spans
+}
+
+/// Parse `[label]: destination` at the start of a (trimmed) line.
+fn scan_reference_definition(line: &str, base: usize) -> Option<LinkRef> {
+    let bytes = line.as_bytes();
+    if bytes.first() != Some(&b'[') || bytes.get(1) == Some(&b'[') {
+        return None;
+    }
+    let close = find_unescaped(bytes, 1, b']')?;
+    if close == 1 || bytes.get(close + 1) != Some(&b':') {
+        return None;
+    }
+    let mut pos = close + 2;
+    while pos < bytes.len() && (bytes[pos] == b' ' || bytes[pos] == b'\t') {
+        pos += 1;
+    }
+    let (start, end, bracketed) = parse_destination(bytes, pos)?;
+    Some(LinkRef {
+        kind: LinkKind::Reference,
+        target: line[start..end].to_string(),
+        start: base + start,
+        end: base + end,
+        bracketed,
+    })
+}
+
+/// Scan a single line for inline links, images, wiki links and `<img>` tags.
+fn scan_inline(line: &str, base: usize, links: &mut Vec<LinkRef>) {
+    let bytes = line.as_bytes();
+    let mut i = 0;
+
+    while i < bytes.len() {
+        match bytes[i] {
+            b'\\' => {
+                i += 2;
+            }
+            b'`' => {
+                // Skip inline code span delimited by a backtick run of equal length
+                let run = bytes[i..].iter().take_while(|b| **b == b'`').count();
+                let mut j = i + run;
+                let mut closed = None;
+                while j < bytes.len() {
+                    if bytes[j] == b'`' {
+                        let other = bytes[j..].iter().take_while(|b| **b == b'`').count();
+                        if other == run {
+                            closed = Some(j + other);
+                            break;
+                        }
+                        j += other;
+                    } else {
+                        j += 1;
+                    }
+                }
+                i = closed.unwrap_or(i + run);
+            }
+            b'[' if bytes.get(i + 1) == Some(&b'[') => {
+                let Some(close) = find_subslice(bytes, i + 2, b"]]") else {
+                    i += 2;
+                    continue;
+                };
+                let inner = &line[i + 2..close];
+                let target_len = inner.find('|').unwrap_or(inner.len());
+                let target = &inner[..target_len];
+                if !target.trim().is_empty() {
+                    links.push(LinkRef {
+                        kind: LinkKind::WikiLink,
+                        target: target.to_string(),
+                        start: base + i + 2,
+                        end: base + i + 2 + target_len,
+                        bracketed: false,
+                    });
+                }
+                i = close + 2;
+            }
+            b'[' => {
+                let is_image = i > 0 && bytes[i - 1] == b'!';
+                if let Some(close) = find_matching_bracket(bytes, i) {
+                    if bytes.get(close + 1) == Some(&b'(') {
+                        let mut pos = close + 2;
+                        while pos < bytes.len() && bytes[pos] == b' ' {
+                            pos += 1;
+                        }
+                        if let Some((start, end, bracketed)) = parse_destination(bytes, pos) {
+                            links.push(LinkRef {
+                                kind: if is_image { LinkKind::Image } else { LinkKind::Inline },
+                                target: line[start..end].to_string(),
+                                start: base + start,
+                                end: base + end,
+                                bracketed,
+                            });
+                        }
+                    }
+                }
+                // Continue inside the link text so nested images are found
+                i += 1;
+            }
+            b'<' if starts_with_ignore_case(&bytes[i..], b"<img") => {
+                let tag_end = bytes[i..]
+                    .iter()
+                    .position(|b| *b == b'>')
+                    .map(|p| i + p)
+                    .unwrap_or(bytes.len());
+                if let Some((start, end)) = find_src_attribute(bytes, i, tag_end) {
+                    links.push(LinkRef {
+                        kind: LinkKind::Html,
+                        target: line[start..end].to_string(),
+                        start: base + start,
+                        end: base + end,
+                        bracketed: false,
+                    });
+                }
+                i = tag_end;
+            }
+            _ => {
+                i += 1;
+            }
+        }
+    }
+}
+
+/// Parse a link destination starting at `pos`. Returns (start, end, bracketed).
+fn parse_destination(bytes: &[u8], pos: usize) -> Option<(usize, usize, bool)> {
+    if bytes.get(pos) == Some(&b'<') {
+        let close = find_unescaped(bytes, pos + 1, b'>')?;
+        return Some((pos + 1, close, true));
+    }
+
+    let mut depth = 0usize;
+    let mut end = pos;
+    while end < bytes.len() {
+        match bytes[end] {
+            b'\\' => {
+                end += 2;
+                continue;
+            }
+            b'(' => depth += 1,
+            b')' if depth == 0 => break,
+            b')' => depth -= 1,
+            b' ' | b'\t' | b'\n' | b'\r' => break,
+            _ => {}
+        }
+        end += 1;
+    }
+    let end = end.min(bytes.len());
+    if end == pos {
+        None
+    } else {
+        Some((pos, end, false))
+    }
+}
+
+fn find_matching_bracket(bytes: &[u8], open: usize) -> Option<usize> {
+    let mut depth = 0usize;
+    let mut i = open;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'\\' => {
+                i += 2;
+                continue;
+            }
+            b'[' => depth += 1,
+            b']' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(i);
+                }
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+    None
+}
+
+fn find_unescaped(bytes: &[u8], from: usize, needle: u8) -> Option<usize> {
+    let mut i = from;
+    while i < bytes.len() {
+        if bytes[i] == b'\\' {
+            i += 2;
+            continue;
+        }
+        if bytes[i] == needle {
+            return Some(i);
+        }
+        i += 1;
+    }
+    None
+}
+
+fn find_subslice(bytes: &[u8], from: usize, needle: &[u8]) -> Option<usize> {
+    if from > bytes.len() {
+        return None;
+    }
+    bytes[from..]
+        .windows(needle.len())
+        .position(|w| w == needle)
+        .map(|p| from + p)
+}
+
+fn starts_with_ignore_case(bytes: &[u8], prefix: &[u8]) -> bool {
+    bytes.len() >= prefix.len() && bytes[..prefix.len()].eq_ignore_ascii_case(prefix)
+}
+
+/// Find the value of a `src="..."` attribute within an HTML tag.
+fn find_src_attribute(bytes: &[u8], tag_start: usize, tag_end: usize) -> Option<(usize, usize)> {
+    let mut i = tag_start;
+    while i + 4 <= tag_end {
+        if bytes[i..i + 3].eq_ignore_ascii_case(b"src") && bytes[i - 1].is_ascii_whitespace() {
+            let mut pos = i + 3;
+            while pos < tag_end && bytes[pos] == b' ' {
+                pos += 1;
+            }
+            if bytes.get(pos) != Some(&b'=') {
+                i += 1;
+                continue;
+            }
+            pos += 1;
+            while pos < tag_end && bytes[pos] == b' ' {
+                pos += 1;
+            }
+            let quote = *bytes.get(pos)?;
+            if quote != b'"' && quote != b'\'' {
+                return None;
+            }
+            let close = bytes[pos + 1..tag_end].iter().position(|b| *b == quote)?;
+            return Some((pos + 1, pos + 1 + close));
+        }
+        i += 1;
+    }
+    None
+}
+
+/// Rewrite link destinations. The callback returns the replacement target
+/// for a link, or None to leave it untouched.
+/// Returns the new content and the number of links rewritten.
+pub fn rewrite_links<F>(content: &str, mut replace: F) -> (String, usize)
+where
+    F: FnMut(&LinkRef) -> Option<String>,
+{
+    let mut result = String::with_capacity(content.len());
+    let mut last = 0;
+    let mut count = 0;
+
+    for link in scan_links(content) {
+        if let Some(replacement) = replace(&link) {
+            if replacement != link.target {
+                result.push_str(&content[last..link.start]);
+                result.push_str(&replacement);
+                last = link.end;
+                count += 1;
+            }
+        }
+    }
+
+    result.push_str(&content[last..]);
+    (result, count)
+}
+
+/// Whether a link target has a URL scheme (`https:`, `mailto:`, ...).
+/// Single-letter schemes are treated as Windows drive letters.
+pub fn has_url_scheme(target: &str) -> bool {
+    match target.find(':') {
+        Some(idx) if idx > 1 => target[..idx]
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.')),
+        _ => false,
+    }
+}
+
+/// Whether a link target refers to a local file (not a URL or in-page anchor).
+pub fn is_local_target(target: &str) -> bool {
+    let target = target.trim();
+    !target.is_empty()
+        && !target.starts_with('#')
+        && !target.starts_with("//")
+        && !has_url_scheme(target)
+}
+
+/// Split a target into its path part and fragment (fragment keeps the leading `#`).
+pub fn split_fragment(target: &str) -> (&str, &str) {
+    match target.find('#') {
+        Some(idx) => (&target[..idx], &target[idx..]),
+        None => (target, ""),
+    }
+}
+
+/// Lexically normalize a path, resolving `.` and `..` without touching the filesystem.
+pub fn normalize_path(path: &Path) -> PathBuf {
+    let mut result = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                if !result.pop() {
+                    result.push("..");
+                }
+            }
+            other => result.push(other.as_os_str()),
+        }
+    }
+    result
+}
+
+/// Resolve a link path (without fragment) relative to the linking document's directory.
+pub fn resolve_link_path(base_dir: &Path, link_path: &str) -> PathBuf {
+    let decoded = urlencoding::decode(link_path)
+        .map(|s| s.into_owned())
+        .unwrap_or_else(|_| link_path.to_string());
+    let path = Path::new(&decoded);
+    if path.is_absolute() {
+        normalize_path(path)
+    } else {
+        normalize_path(&base_dir.join(path))
+    }
+}
+
+/// Compute a `/`-separated relative link from `from_dir` to `to`.
+pub fn relative_link(from_dir: &Path, to: &Path) -> String {
+    let from: Vec<Component> = from_dir.components().collect();
+    let to_parts: Vec<Component> = to.components().collect();
+
+    let common = from
+        .iter()
+        .zip(to_parts.iter())
+        .take_while(|(a, b)| a == b)
+        .count();
+
+    let mut parts: Vec<String> = Vec::new();
+    for _ in common..from.len() {
+        parts.push("..".to_string());
+    }
+    for part in &to_parts[common..] {
+        parts.push(part.as_os_str().to_string_lossy().to_string());
+    }
+
+    if parts.is_empty() {
+        ".".to_string()
+    } else {
+        parts.join("/")
+    }
+}
+
+/// Format a path for use as a link destination, escaping spaces when the
+/// original destination was not wrapped in angle brackets.
+pub fn format_link_path(path: &str, bracketed: bool) -> String {
+    if bracketed {
+        path.to_string()
+    } else {
+        path.replace(' ', "%20")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn targets(content: &str) -> Vec<(LinkKind, String)> {
+        scan_links(content)
+            .into_iter()
+            .map(|l| (l.kind, l.target))
+            .collect()
+    }
+
+    #[test]
+    fn test_scan_inline_and_image_links() {
+        let found = targets("See [doc](notes/a.md) and ![img](<assets/my pic.png> \"t\").");
+        assert_eq!(
+            found,
+            vec![
+                (LinkKind::Inline, "notes/a.md".to_string()),
+                (LinkKind::Image, "assets/my pic.png".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_scan_nested_image_in_link() {
+        let found = targets("[![badge](b.svg)](https://example.com)");
+        assert_eq!(found.len(), 2);
+        assert!(found.contains(&(LinkKind::Image, "b.svg".to_string())));
+        assert!(found.contains(&(LinkKind::Inline, "https://example.com".to_string())));
+    }
+
+    #[test]
+    fn test_scan_wikilinks_reference_and_html() {
+        let content = "[[Note#Intro|Alias]] ![[pic.png]]\n[ref]: ./other.md\n<img alt=\"x\" src=\"a.png\">\n";
+        let found = targets(content);
+        assert_eq!(
+            found,
+            vec![
+                (LinkKind::WikiLink, "Note#Intro".to_string()),
+                (LinkKind::WikiLink, "pic.png".to_string()),
+                (LinkKind::Reference, "./other.md".to_string()),
+                (LinkKind::Html, "a.png".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_scan_skips_code() {
+        let content = "`[a](b.md)`\n```\n[c](d.md)\n```\n[e](f.md)\n";
+        assert_eq!(targets(content), vec![(LinkKind::Inline, "f.md".to_string())]);
+    }
+
+    #[test]
+    fn test_rewrite_links_preserves_surroundings() {
+        let content = "A [x](old.md#top) b ![y](old.md)";
+        let (result, count) = rewrite_links(content, |link| {
+            let (path, fragment) = split_fragment(&link.target);
+            (path == "old.md").then(|| format!("new.md{fragment}"))
+        });
+        assert_eq!(result, "A [x](new.md#top) b ![y](new.md)");
+        assert_eq!(count, 2);
+    }
+
+    #[test]
+    fn test_is_local_target() {
+        assert!(is_local_target("notes/a.md"));
+        assert!(is_local_target("C:/notes/a.md"));
+        assert!(!is_local_target("https://example.com"));
+        assert!(!is_local_target("mailto:me@example.com"));
+        assert!(!is_local_target("#heading"));
+    }
+
+    #[test]
+    fn test_relative_link() {
+        let from = Path::new("/ws/notes/daily");
+        assert_eq!(relative_link(from, Path::new("/ws/notes/daily/a.md")), "a.md");
+        assert_eq!(relative_link(from, Path::new("/ws/assets/b.png")), "../../assets/b.png");
+    }
+
+    #[test]
+    fn test_resolve_link_path_decodes_and_normalizes() {
+        let resolved = resolve_link_path(Path::new("/ws/notes"), "../assets/my%20pic.png");
+        assert_eq!(resolved, PathBuf::from("/ws/assets/my pic.png"));
+    }
+}
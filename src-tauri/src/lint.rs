@@ -0,0 +1,1119 @@
+//! Markdown linting with markdownlint's rules.
+//!
+//! `lint_document` implements a subset of markdownlint's rules under the
+//! same ids, aliases and parameters, so an existing `.markdownlint.json`
+//! works unchanged as the workspace's `.vmark/markdownlint.json`. Where a
+//! problem has a mechanical fix, the diagnostic carries it as an edit in
+//! markdownlint's `fixInfo` shape.
+
+use regex::Regex;
+use serde::Serialize;
+use serde_json::{Map, Value};
+use std::fs;
+use std::ops::Range;
+use std::path::Path;
+use std::sync::LazyLock;
+
+use crate::toc::{self, AnchorStyle, Heading};
+use crate::{formatter, frontmatter, links, tables};
+
+static BARE_URL: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"https?://[^\s<>()\[\]]+").expect("valid url regex"));
+
+static EMPTY_LINK: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\[[^\]]*\]\(\s*#?\s*\)").expect("valid link regex"));
+
+/// An edit that fixes a diagnostic.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Fix {
+    /// 1-based line
+    pub line: usize,
+    /// 1-based column, in characters
+    pub column: usize,
+    /// Characters to delete; -1 deletes the whole line
+    #[serde(rename = "deleteCount")]
+    pub delete_count: i64,
+    #[serde(rename = "insertText")]
+    pub insert_text: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Diagnostic {
+    /// 1-based line
+    pub line: usize,
+    /// 1-based column of the flagged range, in characters
+    pub column: usize,
+    /// Column just past the flagged range
+    #[serde(rename = "endColumn")]
+    pub end_column: usize,
+    #[serde(rename = "ruleId")]
+    pub rule_id: &'static str,
+    #[serde(rename = "ruleAlias")]
+    pub rule_alias: &'static str,
+    pub message: String,
+    pub fix: Option<Fix>,
+}
+
+/// A problem found by a rule, before the rule is attached.
+struct Finding {
+    line: usize,
+    column: usize,
+    end_column: usize,
+    message: String,
+    fix: Option<Fix>,
+}
+
+struct Document<'a> {
+    content: &'a str,
+    lines: Vec<&'a str>,
+    /// Index of the first line after frontmatter
+    start: usize,
+    /// Lines of fenced code blocks, fences included
+    code: Vec<bool>,
+    /// Fenced code blocks: opening line and closing line, if closed
+    fences: Vec<(usize, Option<usize>)>,
+    headings: Vec<Heading>,
+}
+
+impl<'a> Document<'a> {
+    fn new(content: &'a str) -> Self {
+        let body_start = frontmatter::body_start(content);
+        let start = content[..body_start].matches('\n').count();
+        let lines: Vec<&str> = content.lines().collect();
+        let mut code = vec![false; lines.len()];
+        let mut fences: Vec<(usize, Option<usize>)> = Vec::new();
+        let mut fence = None;
+        for (index, line) in lines.iter().enumerate().skip(start) {
+            let was_open = fence.is_some();
+            if links::update_fence(&mut fence, line) {
+                match fences.last_mut() {
+                    Some((_, close)) if was_open => *close = Some(index),
+                    _ => fences.push((index, None)),
+                }
+            }
+            code[index] = was_open || fence.is_some();
+        }
+        Document {
+            content,
+            lines,
+            start,
+            code,
+            fences,
+            headings: toc::scan_headings(content, AnchorStyle::Github),
+        }
+    }
+
+    /// Body lines with their indexes, optionally skipping fenced code.
+    fn body(&self, with_code: bool) -> impl Iterator<Item = (usize, &'a str)> + '_ {
+        (self.start..self.lines.len())
+            .filter(move |&i| with_code || !self.code[i])
+            .map(|i| (i, self.lines[i]))
+    }
+
+    /// Blank or past the end of the document.
+    fn is_blank(&self, index: usize) -> bool {
+        self.lines.get(index).is_none_or(|l| l.trim().is_empty())
+    }
+
+    fn column(&self, index: usize, byte: usize) -> usize {
+        self.lines[index][..byte].chars().count() + 1
+    }
+
+    fn finding(
+        &self,
+        index: usize,
+        range: Range<usize>,
+        message: String,
+        fix: Option<Fix>,
+    ) -> Finding {
+        Finding {
+            line: index + 1,
+            column: self.column(index, range.start),
+            end_column: self.column(index, range.end),
+            message,
+            fix,
+        }
+    }
+
+    fn replace(&self, index: usize, range: Range<usize>, insert: &str) -> Fix {
+        Fix {
+            line: index + 1,
+            column: self.column(index, range.start),
+            delete_count: self.lines[index][range].chars().count() as i64,
+            insert_text: insert.to_string(),
+        }
+    }
+
+    /// Fix inserting a blank line before line `index`.
+    fn insert_blank(&self, index: usize) -> Fix {
+        Fix {
+            line: index + 1,
+            column: 1,
+            delete_count: 0,
+            insert_text: "\n".to_string(),
+        }
+    }
+
+    fn whole(&self, index: usize) -> Range<usize> {
+        0..self.lines[index].len()
+    }
+}
+
+/// A rule's parameters from the config (`"MD013": { "line_length": 100 }`).
+struct Params<'a>(Option<&'a Map<String, Value>>);
+
+impl Params<'_> {
+    fn get(&self, name: &str) -> Option<&Value> {
+        self.0.and_then(|params| params.get(name))
+    }
+
+    fn usize(&self, name: &str, default: usize) -> usize {
+        self.get(name)
+            .and_then(Value::as_u64)
+            .map_or(default, |v| v as usize)
+    }
+
+    fn bool(&self, name: &str, default: bool) -> bool {
+        self.get(name).and_then(Value::as_bool).unwrap_or(default)
+    }
+
+    fn str<'s>(&'s self, name: &str, default: &'s str) -> &'s str {
+        self.get(name).and_then(Value::as_str).unwrap_or(default)
+    }
+}
+
+struct Rule {
+    id: &'static str,
+    alias: &'static str,
+    /// Whether the rule runs when the config doesn't mention it
+    on_by_default: bool,
+    check: fn(&Document, &Params) -> Vec<Finding>,
+}
+
+const RULES: &[Rule] = &[
+    Rule {
+        id: "MD001",
+        alias: "heading-increment",
+        on_by_default: true,
+        check: heading_increment,
+    },
+    Rule {
+        id: "MD004",
+        alias: "ul-style",
+        on_by_default: true,
+        check: ul_style,
+    },
+    Rule {
+        id: "MD009",
+        alias: "no-trailing-spaces",
+        on_by_default: true,
+        check: no_trailing_spaces,
+    },
+    Rule {
+        id: "MD010",
+        alias: "no-hard-tabs",
+        on_by_default: true,
+        check: no_hard_tabs,
+    },
+    Rule {
+        id: "MD012",
+        alias: "no-multiple-blanks",
+        on_by_default: true,
+        check: no_multiple_blanks,
+    },
+    // Off unless configured: paragraphs written in vmark are usually one
+    // long line
+    Rule {
+        id: "MD013",
+        alias: "line-length",
+        on_by_default: false,
+        check: line_length,
+    },
+    Rule {
+        id: "MD018",
+        alias: "no-missing-space-atx",
+        on_by_default: true,
+        check: no_missing_space_atx,
+    },
+    Rule {
+        id: "MD019",
+        alias: "no-multiple-space-atx",
+        on_by_default: true,
+        check: no_multiple_space_atx,
+    },
+    Rule {
+        id: "MD022",
+        alias: "blanks-around-headings",
+        on_by_default: true,
+        check: blanks_around_headings,
+    },
+    Rule {
+        id: "MD023",
+        alias: "heading-start-left",
+        on_by_default: true,
+        check: heading_start_left,
+    },
+    Rule {
+        id: "MD025",
+        alias: "single-h1",
+        on_by_default: true,
+        check: single_h1,
+    },
+    Rule {
+        id: "MD026",
+        alias: "no-trailing-punctuation",
+        on_by_default: true,
+        check: no_trailing_punctuation,
+    },
+    Rule {
+        id: "MD031",
+        alias: "blanks-around-fences",
+        on_by_default: true,
+        check: blanks_around_fences,
+    },
+    Rule {
+        id: "MD032",
+        alias: "blanks-around-lists",
+        on_by_default: true,
+        check: blanks_around_lists,
+    },
+    Rule {
+        id: "MD034",
+        alias: "no-bare-urls",
+        on_by_default: true,
+        check: no_bare_urls,
+    },
+    Rule {
+        id: "MD040",
+        alias: "fenced-code-language",
+        on_by_default: true,
+        check: fenced_code_language,
+    },
+    Rule {
+        id: "MD042",
+        alias: "no-empty-links",
+        on_by_default: true,
+        check: no_empty_links,
+    },
+    Rule {
+        id: "MD047",
+        alias: "single-trailing-newline",
+        on_by_default: true,
+        check: single_trailing_newline,
+    },
+    Rule {
+        id: "MD056",
+        alias: "table-column-count",
+        on_by_default: true,
+        check: table_column_count,
+    },
+];
+
+fn heading_increment(doc: &Document, _: &Params) -> Vec<Finding> {
+    let mut findings = Vec::new();
+    for pair in doc.headings.windows(2) {
+        let (previous, heading) = (&pair[0], &pair[1]);
+        if heading.level > previous.level + 1 {
+            let index = heading.line - 1;
+            findings.push(doc.finding(
+                index,
+                doc.whole(index),
+                format!("Expected h{}, found h{}", previous.level + 1, heading.level),
+                None,
+            ));
+        }
+    }
+    findings
+}
+
+fn ul_style(doc: &Document, params: &Params) -> Vec<Finding> {
+    let mut expected = match params.str("style", "consistent") {
+        "asterisk" => Some('*'),
+        "dash" => Some('-'),
+        "plus" => Some('+'),
+        _ => None,
+    };
+    let mut findings = Vec::new();
+    for (index, line) in doc.body(false) {
+        let (prefix, rest) = formatter::split_prefix(line);
+        if !formatter::is_bullet_item(rest) {
+            continue;
+        }
+        let marker = rest.chars().next().unwrap_or('-');
+        let want = *expected.get_or_insert(marker);
+        if marker != want {
+            let at = prefix.len()..prefix.len() + 1;
+            findings.push(doc.finding(
+                index,
+                at.clone(),
+                format!("Expected '{want}' list marker, found '{marker}'"),
+                Some(doc.replace(index, at, &want.to_string())),
+            ));
+        }
+    }
+    findings
+}
+
+fn no_trailing_spaces(doc: &Document, params: &Params) -> Vec<Finding> {
+    let br_spaces = params.usize("br_spaces", 2);
+    let mut findings = Vec::new();
+    for (index, line) in doc.body(false) {
+        let text_end = line.trim_end_matches(' ').len();
+        let count = line.len() - text_end;
+        let hard_break =
+            br_spaces >= 2 && count == br_spaces && text_end > 0 && !doc.is_blank(index + 1);
+        if count > 0 && !hard_break {
+            findings.push(doc.finding(
+                index,
+                text_end..line.len(),
+                format!("{count} trailing spaces"),
+                Some(doc.replace(index, text_end..line.len(), "")),
+            ));
+        }
+    }
+    findings
+}
+
+fn no_hard_tabs(doc: &Document, params: &Params) -> Vec<Finding> {
+    let spaces = " ".repeat(params.usize("spaces_per_tab", 1));
+    let mut findings = Vec::new();
+    for (index, line) in doc.body(params.bool("code_blocks", true)) {
+        for (at, _) in line.match_indices('\t') {
+            findings.push(doc.finding(
+                index,
+                at..at + 1,
+                "Hard tab".to_string(),
+                Some(doc.replace(index, at..at + 1, &spaces)),
+            ));
+        }
+    }
+    findings
+}
+
+fn no_multiple_blanks(doc: &Document, params: &Params) -> Vec<Finding> {
+    let maximum = params.usize("maximum", 1);
+    let mut findings = Vec::new();
+    let mut blanks = 0;
+    for (index, line) in doc.body(true) {
+        blanks = if line.trim().is_empty() && !doc.code[index] {
+            blanks + 1
+        } else {
+            0
+        };
+        if blanks > maximum {
+            let fix = Fix {
+                line: index + 1,
+                column: 1,
+                delete_count: -1,
+                insert_text: String::new(),
+            };
+            findings.push(doc.finding(
+                index,
+                doc.whole(index),
+                format!("{blanks} consecutive blank lines (at most {maximum})"),
+                Some(fix),
+            ));
+        }
+    }
+    findings
+}
+
+fn line_length(doc: &Document, params: &Params) -> Vec<Finding> {
+    let limit = params.usize("line_length", 80);
+    let headings = params.bool("headings", true);
+    let tables = params.bool("tables", true);
+    let mut findings = Vec::new();
+    for (index, line) in doc.body(params.bool("code_blocks", true)) {
+        let Some((at, _)) = line.char_indices().nth(limit) else {
+            continue;
+        };
+        let skipped = (!headings && toc::atx_heading(line).is_some())
+            || (!tables && line.trim_start().starts_with('|'));
+        // Like markdownlint's non-strict mode: a line that can't be wrapped
+        // (a long URL, say) is fine
+        if !skipped && line[at..].contains(char::is_whitespace) {
+            findings.push(doc.finding(
+                index,
+                at..line.len(),
+                format!(
+                    "Line is {} characters long (at most {limit})",
+                    line.chars().count()
+                ),
+                None,
+            ));
+        }
+    }
+    findings
+}
+
+/// Byte range of the `#`s starting an unindented (up to 3 spaces) line.
+fn hashes(line: &str) -> Range<usize> {
+    let start = line.len() - line.trim_start_matches(' ').len();
+    let count = line[start..].bytes().take_while(|b| *b == b'#').count();
+    start..start + count
+}
+
+fn no_missing_space_atx(doc: &Document, _: &Params) -> Vec<Finding> {
+    let mut findings = Vec::new();
+    for (index, line) in doc.body(false) {
+        let marks = hashes(line);
+        let after = line[marks.end..].chars().next();
+        let missing = marks.start <= 3
+            && (1..=6).contains(&marks.len())
+            && after.is_some_and(|c| !c.is_whitespace() && c != '#');
+        if missing {
+            findings.push(doc.finding(
+                index,
+                marks.clone(),
+                "No space after hash on heading".to_string(),
+                Some(doc.replace(index, marks.end..marks.end, " ")),
+            ));
+        }
+    }
+    findings
+}
+
+fn no_multiple_space_atx(doc: &Document, _: &Params) -> Vec<Finding> {
+    let mut findings = Vec::new();
+    for (index, line) in doc.body(false) {
+        if toc::atx_heading(line).is_none() {
+            continue;
+        }
+        let marks = hashes(line);
+        let rest = &line[marks.end..];
+        let spaces = rest.len() - rest.trim_start_matches([' ', '\t']).len();
+        if spaces > 1 && spaces < rest.len() {
+            let range = marks.end..marks.end + spaces;
+            findings.push(doc.finding(
+                index,
+                range.clone(),
+                "Multiple spaces after hash on heading".to_string(),
+                Some(doc.replace(index, range, " ")),
+            ));
+        }
+    }
+    findings
+}
+
+fn blanks_around_headings(doc: &Document, _: &Params) -> Vec<Finding> {
+    let mut findings = Vec::new();
+    for heading in &doc.headings {
+        let index = heading.line - 1;
+        // A setext heading ends at its underline
+        let end = if toc::atx_heading(doc.lines[index]).is_some() {
+            index
+        } else {
+            index + 1
+        };
+        if index > doc.start && !doc.is_blank(index - 1) {
+            findings.push(doc.finding(
+                index,
+                doc.whole(index),
+                "Expected a blank line above the heading".to_string(),
+                Some(doc.insert_blank(index)),
+            ));
+        }
+        if !doc.is_blank(end + 1) {
+            findings.push(doc.finding(
+                index,
+                doc.whole(index),
+                "Expected a blank line below the heading".to_string(),
+                Some(doc.insert_blank(end + 1)),
+            ));
+        }
+    }
+    findings
+}
+
+fn heading_start_left(doc: &Document, _: &Params) -> Vec<Finding> {
+    let mut findings = Vec::new();
+    for (index, line) in doc.body(false) {
+        let indent = line.len() - line.trim_start_matches(' ').len();
+        if indent > 0 && toc::atx_heading(line).is_some() {
+            findings.push(doc.finding(
+                index,
+                0..indent,
+                "Heading is indented".to_string(),
+                Some(doc.replace(index, 0..indent, "")),
+            ));
+        }
+    }
+    findings
+}
+
+fn single_h1(doc: &Document, params: &Params) -> Vec<Finding> {
+    let level = params.usize("level", 1) as u8;
+    let title_key = params.str("front_matter_title", "title");
+    // A frontmatter title counts as the first top-level heading
+    let frontmatter = doc
+        .lines
+        .get(1..doc.start.saturating_sub(1))
+        .unwrap_or_default();
+    let has_title = !title_key.is_empty()
+        && frontmatter.iter().any(|line| {
+            line.strip_prefix(title_key)
+                .is_some_and(|rest| rest.trim_start().starts_with([':', '=']))
+        });
+    let mut seen = has_title;
+    let mut findings = Vec::new();
+    for heading in doc.headings.iter().filter(|h| h.level == level) {
+        if seen {
+            let index = heading.line - 1;
+            findings.push(doc.finding(
+                index,
+                doc.whole(index),
+                format!("Multiple top-level headings (h{level})"),
+                None,
+            ));
+        }
+        seen = true;
+    }
+    findings
+}
+
+fn no_trailing_punctuation(doc: &Document, params: &Params) -> Vec<Finding> {
+    let punctuation = params.str("punctuation", ".,;:!。，；：！");
+    let mut findings = Vec::new();
+    for (index, line) in doc.body(false) {
+        let Some((_, text)) = toc::atx_heading(line) else {
+            continue;
+        };
+        let Some(last) = text
+            .chars()
+            .next_back()
+            .filter(|c| punctuation.contains(*c))
+        else {
+            continue;
+        };
+        let end = text.as_ptr() as usize - line.as_ptr() as usize + text.len();
+        let at = end - last.len_utf8()..end;
+        findings.push(doc.finding(
+            index,
+            at.clone(),
+            format!("Trailing punctuation in heading: '{last}'"),
+            Some(doc.replace(index, at, "")),
+        ));
+    }
+    findings
+}
+
+fn blanks_around_fences(doc: &Document, _: &Params) -> Vec<Finding> {
+    let mut findings = Vec::new();
+    for &(open, close) in &doc.fences {
+        if open > doc.start && !doc.is_blank(open - 1) {
+            findings.push(doc.finding(
+                open,
+                doc.whole(open),
+                "Expected a blank line above the code block".to_string(),
+                Some(doc.insert_blank(open)),
+            ));
+        }
+        if let Some(close) = close.filter(|&c| !doc.is_blank(c + 1)) {
+            findings.push(doc.finding(
+                close,
+                doc.whole(close),
+                "Expected a blank line below the code block".to_string(),
+                Some(doc.insert_blank(close + 1)),
+            ));
+        }
+    }
+    findings
+}
+
+/// A top-level list item (not quoted or indented).
+fn starts_list(line: &str) -> bool {
+    let (prefix, rest) = formatter::split_prefix(line);
+    !prefix.contains('>') && prefix.len() < 4 && formatter::is_list_item(rest)
+}
+
+fn blanks_around_lists(doc: &Document, _: &Params) -> Vec<Finding> {
+    // Lines that end a list without a blank line: headings, rules and
+    // unindented code fences
+    let ends_list = |index: usize| {
+        let line = doc.lines[index];
+        toc::atx_heading(line).is_some()
+            || (formatter::is_thematic_break(line) && !formatter::is_bullet_item(line.trim_start()))
+            || (doc.code[index] && !line.starts_with([' ', '\t']))
+    };
+    let mut findings = Vec::new();
+    let mut index = doc.start;
+    while index < doc.lines.len() {
+        if doc.code[index] || !starts_list(doc.lines[index]) {
+            index += 1;
+            continue;
+        }
+        if index > doc.start && !doc.is_blank(index - 1) {
+            findings.push(doc.finding(
+                index,
+                doc.whole(index),
+                "Expected a blank line above the list".to_string(),
+                Some(doc.insert_blank(index)),
+            ));
+        }
+        let mut end = index;
+        while !doc.is_blank(end + 1) && !ends_list(end + 1) {
+            end += 1;
+        }
+        if !doc.is_blank(end + 1) {
+            findings.push(doc.finding(
+                end,
+                doc.whole(end),
+                "Expected a blank line below the list".to_string(),
+                Some(doc.insert_blank(end + 1)),
+            ));
+        }
+        index = end + 1;
+    }
+    findings
+}
+
+fn no_bare_urls(doc: &Document, _: &Params) -> Vec<Finding> {
+    let mut findings = Vec::new();
+    for (index, line) in doc.body(false) {
+        let (_, rest) = formatter::split_prefix(line);
+        if rest.starts_with('[') && rest.contains("]:") {
+            continue;
+        }
+        let spans = links::code_spans(line);
+        for found in BARE_URL.find_iter(line) {
+            let before = line[..found.start()].chars().next_back();
+            if matches!(before, Some('<' | '(' | '[' | '"' | '\'' | '='))
+                || spans.iter().any(|s| s.contains(&found.start()))
+            {
+                continue;
+            }
+            // Trailing punctuation ends the sentence, not the URL
+            let url = found
+                .as_str()
+                .trim_end_matches(['.', ',', ';', ':', '!', '?', '*', '_']);
+            let range = found.start()..found.start() + url.len();
+            findings.push(doc.finding(
+                index,
+                range.clone(),
+                format!("Bare URL: {url}"),
+                Some(doc.replace(index, range, &format!("<{url}>"))),
+            ));
+        }
+    }
+    findings
+}
+
+fn fenced_code_language(doc: &Document, _: &Params) -> Vec<Finding> {
+    let mut findings = Vec::new();
+    for &(open, _) in &doc.fences {
+        let line = doc.lines[open];
+        let info = line.trim().trim_start_matches(['`', '~']).trim();
+        if info.is_empty() {
+            findings.push(doc.finding(
+                open,
+                doc.whole(open),
+                "Code block has no language".to_string(),
+                None,
+            ));
+        }
+    }
+    findings
+}
+
+fn no_empty_links(doc: &Document, _: &Params) -> Vec<Finding> {
+    let mut findings = Vec::new();
+    for (index, line) in doc.body(false) {
+        let spans = links::code_spans(line);
+        for found in EMPTY_LINK.find_iter(line) {
+            let image = line[..found.start()].ends_with('!');
+            if !image && !spans.iter().any(|s| s.contains(&found.start())) {
+                findings.push(doc.finding(
+                    index,
+                    found.range(),
+                    "Link has no destination".to_string(),
+                    None,
+                ));
+            }
+        }
+    }
+    findings
+}
+
+fn single_trailing_newline(doc: &Document, _: &Params) -> Vec<Finding> {
+    if doc.content.is_empty() || doc.content.ends_with('\n') {
+        return Vec::new();
+    }
+    let index = doc.lines.len() - 1;
+    let end = doc.lines[index].len();
+    vec![doc.finding(
+        index,
+        end..end,
+        "File should end with a single newline".to_string(),
+        Some(doc.replace(index, end..end, "\n")),
+    )]
+}
+
+fn table_column_count(doc: &Document, _: &Params) -> Vec<Finding> {
+    let mut findings = Vec::new();
+    for table in tables::find_tables(&doc.lines[doc.start..]) {
+        let first = doc.start + table.start;
+        let columns = tables::split_row(doc.lines[first]).len();
+        for index in first + 2..doc.start + table.end {
+            let cells = tables::split_row(doc.lines[index]).len();
+            if cells != columns {
+                findings.push(doc.finding(
+                    index,
+                    doc.whole(index),
+                    format!("Expected {columns} table cells, found {cells}"),
+                    None,
+                ));
+            }
+        }
+    }
+    findings
+}
+
+/// The config entry for a rule, by id or alias.
+fn setting<'a>(config: &'a Map<String, Value>, rule: &Rule) -> Option<&'a Value> {
+    config
+        .iter()
+        .find(|(key, _)| key.eq_ignore_ascii_case(rule.id) || key.eq_ignore_ascii_case(rule.alias))
+        .map(|(_, value)| value)
+}
+
+fn lint(content: &str, config: &Map<String, Value>) -> Vec<Diagnostic> {
+    let doc = Document::new(content);
+    let default = config
+        .get("default")
+        .and_then(Value::as_bool)
+        .unwrap_or(true);
+    let mut diagnostics = Vec::new();
+    for rule in RULES {
+        let setting = setting(config, rule);
+        let enabled = match setting {
+            Some(Value::Bool(enabled)) => *enabled,
+            Some(_) => true,
+            None => default && rule.on_by_default,
+        };
+        if !enabled {
+            continue;
+        }
+        let params = Params(setting.and_then(Value::as_object));
+        diagnostics.extend((rule.check)(&doc, &params).into_iter().map(|f| Diagnostic {
+            line: f.line,
+            column: f.column,
+            end_column: f.end_column,
+            rule_id: rule.id,
+            rule_alias: rule.alias,
+            message: f.message,
+            fix: f.fix,
+        }));
+    }
+    diagnostics.sort_by_key(|d| (d.line, d.column));
+    diagnostics
+}
+
+/// The workspace's lint config, or an empty one (all defaults).
+fn load_config(root: &Path) -> Result<Map<String, Value>, String> {
+    let path = root.join(".vmark").join("markdownlint.json");
+    if !path.is_file() {
+        return Ok(Map::new());
+    }
+    let text = fs::read_to_string(&path).map_err(|e| format!("Failed to read lint config: {e}"))?;
+    serde_json::from_str(&text).map_err(|e| format!("Invalid lint config: {e}"))
+}
+
+/// Lint markdown content. `ruleset` uses markdownlint's config format;
+/// without one, the workspace's `.vmark/markdownlint.json` is used.
+#[tauri::command]
+pub fn lint_document(
+    content: String,
+    ruleset: Option<Map<String, Value>>,
+    workspace_root: Option<String>,
+) -> Result<Vec<Diagnostic>, String> {
+    let config = match (ruleset, workspace_root) {
+        (Some(ruleset), _) => ruleset,
+        (None, Some(root)) => load_config(Path::new(&root))?,
+        (None, None) => Map::new(),
+    };
+    Ok(lint(&content, &config))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use tempfile::tempdir;
+
+    fn summary(diagnostics: &[Diagnostic]) -> Vec<(usize, &str)> {
+        diagnostics.iter().map(|d| (d.line, d.rule_id)).collect()
+    }
+
+    /// Lint with only `rule` enabled, with its `setting`.
+    fn only(content: &str, rule: &str, setting: Value) -> Vec<Diagnostic> {
+        let config = json!({"default": false, rule: setting});
+        lint(content, config.as_object().unwrap())
+    }
+
+    /// Apply the diagnostics' fixes, last first so earlier positions hold.
+    fn apply(content: &str, diagnostics: &[Diagnostic]) -> String {
+        let mut fixes: Vec<&Fix> = diagnostics.iter().filter_map(|d| d.fix.as_ref()).collect();
+        fixes.sort_by_key(|f| (f.line, f.column));
+        let mut text = content.to_string();
+        for fix in fixes.into_iter().rev() {
+            let line_start: usize = text
+                .split_inclusive('\n')
+                .take(fix.line - 1)
+                .map(str::len)
+                .sum();
+            let line = text[line_start..].split('\n').next().unwrap();
+            if fix.delete_count < 0 {
+                let end = (line_start + line.len() + 1).min(text.len());
+                text.replace_range(line_start..end, "");
+                continue;
+            }
+            let byte = |column: usize| {
+                line.char_indices()
+                    .nth(column - 1)
+                    .map_or(line.len(), |(at, _)| at)
+            };
+            let start = byte(fix.column);
+            let end = byte(fix.column + fix.delete_count as usize);
+            text.replace_range(line_start + start..line_start + end, &fix.insert_text);
+        }
+        text
+    }
+
+    #[test]
+    fn test_rule_fixes() {
+        let cases = [
+            ("MD004", "- a\n* b\n", "- a\n- b\n"),
+            ("MD009", "a   \nb  \nc\n", "a\nb  \nc\n"),
+            ("MD010", "a\tb\n", "a b\n"),
+            ("MD012", "a\n\n\n\nb\n", "a\n\nb\n"),
+            ("MD018", "#Title\n", "# Title\n"),
+            ("MD019", "##  Title\n", "## Title\n"),
+            ("MD022", "a\n# T\nb\n", "a\n\n# T\n\nb\n"),
+            ("MD022", "a\n\nT\n===\nb\n", "a\n\nT\n===\n\nb\n"),
+            ("MD023", "  # T\n", "# T\n"),
+            ("MD026", "# T.\n", "# T\n"),
+            ("MD026", "# 标题。\n", "# 标题\n"),
+            (
+                "MD031",
+                "a\n```js\nx\n```\nb\n",
+                "a\n\n```js\nx\n```\n\nb\n",
+            ),
+            ("MD032", "a\n- x\n# H\n", "a\n\n- x\n\n# H\n"),
+            ("MD034", "See https://x.io.\n", "See <https://x.io>.\n"),
+            ("MD047", "a", "a\n"),
+        ];
+        for (rule, content, expected) in cases {
+            let diagnostics = only(content, rule, json!(true));
+            assert!(
+                !diagnostics.is_empty(),
+                "{rule} found nothing in {content:?}"
+            );
+            assert_eq!(apply(content, &diagnostics), expected, "{rule}");
+            // Fixed content is clean
+            assert!(only(expected, rule, json!(true)).is_empty(), "{rule}");
+        }
+    }
+
+    #[test]
+    fn test_rules_without_fixes() {
+        let cases = [
+            ("MD001", "# a\n\n### b\n", 3),
+            ("MD013", &format!("{} b\n", "a".repeat(80)), 1),
+            ("MD025", "# a\n\n# b\n", 3),
+            ("MD040", "```\nx\n```\n", 1),
+            ("MD042", "a [x](#) b\n", 1),
+            ("MD056", "| a | b |\n|---|---|\n| 1 | 2 | 3 |\n", 3),
+        ];
+        for (rule, content, line) in cases {
+            let diagnostics = only(content, rule, json!(true));
+            assert_eq!(summary(&diagnostics), vec![(line, rule)]);
+            assert_eq!(diagnostics[0].fix, None, "{rule}");
+        }
+        // Images, code spans and reference definitions are not flagged
+        assert!(only("![x]() `[y]()`\n", "MD042", json!(true)).is_empty());
+        assert!(only(
+            "<https://x.io> `https://y.io`\n[r]: https://z.io\n",
+            "MD034",
+            json!(true)
+        )
+        .is_empty());
+    }
+
+    #[test]
+    fn test_rule_params() {
+        let found =
+            |content: &str, rule: &str, params: Value| summary(&only(content, rule, params)).len();
+
+        assert_eq!(found("* a\n", "MD004", json!({"style": "dash"})), 1);
+        assert_eq!(found("* a\n+ b\n", "MD004", json!({"style": "plus"})), 1);
+        assert_eq!(found("b  \nc\n", "MD009", json!({"br_spaces": 0})), 1);
+        assert_eq!(found("b   \nc\n", "MD009", json!({"br_spaces": 3})), 0);
+
+        let tabs = only("a\tb\n", "MD010", json!({"spaces_per_tab": 4}));
+        assert_eq!(tabs[0].fix.as_ref().unwrap().insert_text, "    ");
+        assert_eq!(
+            found("```\n\tx\n```\n", "MD010", json!({"code_blocks": false})),
+            0
+        );
+
+        assert_eq!(found("a\n\n\nb\n", "MD012", json!({"maximum": 2})), 0);
+        assert_eq!(found("a\n\n\n\nb\n", "MD012", json!({"maximum": 2})), 1);
+
+        let long = format!("# {} b\n\n| {} b |\n", "a".repeat(20), "a".repeat(20));
+        assert_eq!(found(&long, "MD013", json!({"line_length": 10})), 2);
+        let skipped = json!({"line_length": 10, "headings": false, "tables": false});
+        assert_eq!(found(&long, "MD013", skipped), 0);
+        // A line that can't be wrapped is fine
+        assert_eq!(
+            found(
+                "see https://example.com/long/path\n",
+                "MD013",
+                json!({"line_length": 10})
+            ),
+            0
+        );
+
+        assert_eq!(found("## a\n\n## b\n", "MD025", json!({"level": 2})), 1);
+        let titled = "---\ntitle: T\n---\n# a\n";
+        assert_eq!(found(titled, "MD025", json!(true)), 1);
+        assert_eq!(found(titled, "MD025", json!({"front_matter_title": ""})), 0);
+
+        assert_eq!(found("# a?\n", "MD026", json!(true)), 0);
+        assert_eq!(found("# a?\n", "MD026", json!({"punctuation": "?"})), 1);
+    }
+
+    #[test]
+    fn test_rule_names_in_config() {
+        let content = "a\tb\n";
+        for key in ["MD010", "md010", "no-hard-tabs", "No-Hard-Tabs"] {
+            let config = json!({ key: false });
+            assert!(
+                lint(content, config.as_object().unwrap()).is_empty(),
+                "{key}"
+            );
+        }
+        // A rule off by default runs once it's configured
+        let long = format!("{} b\n", "a".repeat(80));
+        assert!(lint(&long, &Map::new()).is_empty());
+        let config = json!({"line-length": {}});
+        assert_eq!(
+            summary(&lint(&long, config.as_object().unwrap())),
+            vec![(1, "MD013")]
+        );
+    }
+
+    #[test]
+    fn test_lint_edge_cases() {
+        // Trailing newline
+        assert!(lint("", &Map::new()).is_empty());
+        assert!(lint("a\n", &Map::new()).is_empty());
+        let missing = lint("a\nbc", &Map::new());
+        assert_eq!(summary(&missing), vec![(2, "MD047")]);
+        assert_eq!(missing[0].column, 3);
+
+        // Frontmatter keeps its line numbers and isn't linted
+        let content = "---\ntitle: x   \ntags:\n\t- a\n---\n#Title\n";
+        let diagnostics = lint(content, &Map::new());
+        assert_eq!(summary(&diagnostics), vec![(6, "MD018")]);
+        assert_eq!(
+            apply(content, &diagnostics),
+            content.replace("#Title", "# Title")
+        );
+
+        // An unclosed fence runs to the end of the document
+        let content = "a\n```\ncode   \n#x\n\n\n\n";
+        assert_eq!(
+            summary(&lint(content, &Map::new())),
+            vec![(2, "MD031"), (2, "MD040")]
+        );
+    }
+
+    #[test]
+    fn test_load_config() {
+        let dir = tempdir().unwrap();
+        assert!(load_config(dir.path()).unwrap().is_empty());
+
+        fs::create_dir(dir.path().join(".vmark")).unwrap();
+        fs::write(dir.path().join(".vmark/markdownlint.json"), "{ nope").unwrap();
+        let error = load_config(dir.path()).unwrap_err();
+        assert!(error.starts_with("Invalid lint config"), "{error}");
+
+        fs::write(
+            dir.path().join(".vmark/markdownlint.json"),
+            r#"{"MD047": false}"#,
+        )
+        .unwrap();
+        let root = dir.path().to_string_lossy().to_string();
+        let diagnostics = lint_document("a".to_string(), None, Some(root.clone())).unwrap();
+        assert!(diagnostics.is_empty());
+        // A ruleset from the caller wins over the workspace config
+        let ruleset = json!({}).as_object().unwrap().clone();
+        let diagnostics = lint_document("a".to_string(), Some(ruleset), Some(root)).unwrap();
+        assert_eq!(summary(&diagnostics), vec![(1, "MD047")]);
+    }
+
+    #[test]
+    fn test_lint_rules() {
+        let content = "---\ntitle: T\n---\n# Title.\nText   \n* a\n- b\n\n\n\n###  Deep\n```\ncode\t \n```\nSee https://example.com. and [x]()\n\n| a | b |\n|---|---|\n| 1 |\n\n#tag";
+        let diagnostics = lint(content, &Map::new());
+        assert_eq!(
+            summary(&diagnostics),
+            vec![
+                (4, "MD022"),
+                (4, "MD025"),
+                (4, "MD026"),
+                (5, "MD009"),
+                (6, "MD032"),
+                (7, "MD004"),
+                (9, "MD012"),
+                (10, "MD012"),
+                (11, "MD001"),
+                (11, "MD022"),
+                (11, "MD019"),
+                (12, "MD031"),
+                (12, "MD040"),
+                (13, "MD010"),
+                (14, "MD031"),
+                (15, "MD034"),
+                (15, "MD042"),
+                (19, "MD056"),
+                (21, "MD018"),
+                (21, "MD047"),
+            ]
+        );
+
+        let fix = |rule: &str| {
+            diagnostics
+                .iter()
+                .find(|d| d.rule_id == rule)
+                .unwrap()
+                .fix
+                .clone()
+                .unwrap()
+        };
+        assert_eq!(fix("MD009").column, 5);
+        assert_eq!(fix("MD009").delete_count, 3);
+        assert_eq!(fix("MD034").insert_text, "<https://example.com>");
+        assert_eq!(fix("MD034").delete_count, 19);
+        assert_eq!(fix("MD012").delete_count, -1);
+    }
+
+    #[test]
+    fn test_lint_config() {
+        let dir = tempdir().unwrap();
+        fs::create_dir(dir.path().join(".vmark")).unwrap();
+        fs::write(
+            dir.path().join(".vmark/markdownlint.json"),
+            r#"{"no-trailing-spaces": false, "MD013": {"line_length": 10}, "MD004": {"style": "asterisk"}}"#,
+        )
+        .unwrap();
+        let content = "# Title\n\ntrail   \nsome long line here\n\n- item\n";
+        let root = dir.path().to_string_lossy().to_string();
+        let diagnostics = lint_document(content.to_string(), None, Some(root)).unwrap();
+        assert_eq!(summary(&diagnostics), vec![(4, "MD013"), (6, "MD004")]);
+
+        let config = json!({"default": false, "MD047": true});
+        let diagnostics = lint("# a\n# b", config.as_object().unwrap());
+        assert_eq!(summary(&diagnostics), vec![(2, "MD047")]);
+    }
+}
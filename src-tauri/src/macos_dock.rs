@@ -0,0 +1,186 @@
+//! macOS Dock menu.
+//!
+//! AppKit asks the application delegate for the Dock menu through
+//! `applicationDockMenu:`. tao's delegate doesn't implement it, so the method
+//! is added to its class at startup. The menu is rebuilt from the recent
+//! files/workspaces snapshots in `menu.rs` every time the Dock asks, and
+//! clicks go through `menu_events::handle_menu_event` like the matching menu
+//! bar items, which also covers the case where no window is open.
+
+use std::cell::RefCell;
+use std::sync::OnceLock;
+
+use objc2::ffi::class_addMethod;
+use objc2::rc::Retained;
+use objc2::runtime::{AnyClass, AnyObject, Imp, Sel};
+use objc2::{sel, MainThreadMarker, MainThreadOnly};
+use objc2_app_kit::{NSApplication, NSMenu, NSMenuItem};
+use objc2_foundation::NSString;
+use tauri::menu::{MenuEvent, MenuId};
+use tauri::AppHandle;
+
+use crate::{menu, menu_events, menu_i18n};
+
+/// Tag of the "New Window" item
+const TAG_NEW_WINDOW: isize = 1;
+/// Recent file `i` is tagged `TAG_RECENT_FILE + i`
+const TAG_RECENT_FILE: isize = 1000;
+/// Recent workspace `i` is tagged `TAG_RECENT_WORKSPACE + i`
+const TAG_RECENT_WORKSPACE: isize = 2000;
+
+/// Most recent entries of each kind shown in the Dock menu
+const MAX_RECENT: usize = 10;
+
+static APP: OnceLock<AppHandle> = OnceLock::new();
+
+thread_local! {
+    /// The menu handed to AppKit; `applicationDockMenu:` doesn't transfer
+    /// ownership, so it is kept alive here until the next request.
+    static DOCK_MENU: RefCell<Option<Retained<NSMenu>>> = const { RefCell::new(None) };
+}
+
+fn add_item(menu: &NSMenu, target: &AnyObject, mtm: MainThreadMarker, title: &str, tag: isize) {
+    let item = unsafe {
+        NSMenuItem::initWithTitle_action_keyEquivalent(
+            NSMenuItem::alloc(mtm),
+            &NSString::from_str(title),
+            Some(sel!(vmarkDockMenuItem:)),
+            &NSString::from_str(""),
+        )
+    };
+    item.setTag(tag);
+    unsafe { item.setTarget(Some(target)) };
+    menu.addItem(&item);
+}
+
+fn display_name(path: &str) -> &str {
+    std::path::Path::new(path)
+        .file_name()
+        .and_then(|n| n.to_str())
+        .unwrap_or(path)
+}
+
+/// Build the Dock menu: New Window, then recent files and workspaces.
+fn build_dock_menu(target: &AnyObject, mtm: MainThreadMarker) -> Retained<NSMenu> {
+    let dock_menu = NSMenu::new(mtm);
+    add_item(
+        &dock_menu,
+        target,
+        mtm,
+        &menu_i18n::tr("New Window"),
+        TAG_NEW_WINDOW,
+    );
+
+    let files: Vec<String> = (0..MAX_RECENT)
+        .map_while(menu::get_recent_file_path)
+        .collect();
+    if !files.is_empty() {
+        dock_menu.addItem(&NSMenuItem::separatorItem(mtm));
+        for (index, path) in files.iter().enumerate() {
+            let tag = TAG_RECENT_FILE + index as isize;
+            add_item(&dock_menu, target, mtm, display_name(path), tag);
+        }
+    }
+
+    let workspaces: Vec<String> = (0..MAX_RECENT)
+        .map_while(menu::get_recent_workspace_path)
+        .collect();
+    if !workspaces.is_empty() {
+        dock_menu.addItem(&NSMenuItem::separatorItem(mtm));
+        for (index, path) in workspaces.iter().enumerate() {
+            let tag = TAG_RECENT_WORKSPACE + index as isize;
+            add_item(&dock_menu, target, mtm, display_name(path), tag);
+        }
+    }
+
+    dock_menu
+}
+
+/// `-[delegate applicationDockMenu:]`
+extern "C-unwind" fn application_dock_menu(
+    this: &AnyObject,
+    _cmd: Sel,
+    _sender: &AnyObject,
+) -> *mut NSMenu {
+    let Some(mtm) = MainThreadMarker::new() else {
+        return std::ptr::null_mut();
+    };
+    let dock_menu = build_dock_menu(this, mtm);
+    let ptr = Retained::as_ptr(&dock_menu) as *mut NSMenu;
+    DOCK_MENU.with(|current| *current.borrow_mut() = Some(dock_menu));
+    ptr
+}
+
+/// `-[delegate vmarkDockMenuItem:]`, the action of every Dock menu item.
+extern "C-unwind" fn dock_menu_item_clicked(_this: &AnyObject, _cmd: Sel, sender: &NSMenuItem) {
+    let Some(app) = APP.get() else {
+        return;
+    };
+    let id = match sender.tag() {
+        TAG_NEW_WINDOW => "new-window".to_string(),
+        tag if tag >= TAG_RECENT_WORKSPACE => {
+            format!("recent-workspace-{}", tag - TAG_RECENT_WORKSPACE)
+        }
+        tag if tag >= TAG_RECENT_FILE => format!("recent-file-{}", tag - TAG_RECENT_FILE),
+        _ => return,
+    };
+    menu_events::handle_menu_event(
+        app,
+        MenuEvent {
+            id: MenuId::new(id),
+        },
+    );
+}
+
+/// Install the Dock menu. Must be called on the main thread after the app
+/// has finished launching (i.e. from `setup`).
+pub fn install_dock_menu(app: &AppHandle) {
+    let _ = APP.set(app.clone());
+
+    let Some(mtm) = MainThreadMarker::new() else {
+        #[cfg(debug_assertions)]
+        eprintln!("[macos_dock] Not on main thread, cannot install Dock menu");
+        return;
+    };
+
+    let ns_app = NSApplication::sharedApplication(mtm);
+    let Some(delegate) = ns_app.delegate() else {
+        #[cfg(debug_assertions)]
+        eprintln!("[macos_dock] No application delegate");
+        return;
+    };
+    let delegate_object: &AnyObject = (*delegate).as_ref();
+    let class = delegate_object.class() as *const AnyClass as *mut AnyClass;
+
+    let added = unsafe {
+        let dock_menu: extern "C-unwind" fn(&AnyObject, Sel, &AnyObject) -> *mut NSMenu =
+            application_dock_menu;
+        let clicked: extern "C-unwind" fn(&AnyObject, Sel, &NSMenuItem) = dock_menu_item_clicked;
+        class_addMethod(
+            class,
+            sel!(applicationDockMenu:),
+            std::mem::transmute::<_, Imp>(dock_menu),
+            c"@@:@".as_ptr(),
+        )
+        .as_bool()
+            && class_addMethod(
+                class,
+                sel!(vmarkDockMenuItem:),
+                std::mem::transmute::<_, Imp>(clicked),
+                c"v@:@".as_ptr(),
+            )
+            .as_bool()
+    };
+    if !added {
+        #[cfg(debug_assertions)]
+        eprintln!("[macos_dock] Delegate already implements the Dock menu methods");
+        return;
+    }
+
+    // AppKit caches which optional methods the delegate responds to when it
+    // is set, so set it again for `applicationDockMenu:` to be picked up.
+    ns_app.setDelegate(Some(&delegate));
+
+    #[cfg(debug_assertions)]
+    eprintln!("[macos_dock] Dock menu installed");
+}
@@ -3,42 +3,52 @@
 //! Workaround for muda's broken `set_as_help_menu_for_nsapp()`.
 //! See: https://github.com/tauri-apps/muda/pull/322
 
+use objc2::rc::Retained;
 use objc2::MainThreadMarker;
-use objc2_app_kit::NSApplication;
+use objc2_app_kit::{NSApplication, NSMenu};
 use objc2_foundation::NSString;
+use tauri::menu::MenuItemKind;
+use tauri::AppHandle;
+
+use crate::menu::{HELP_MENU_ID, WINDOW_MENU_ID};
+
+/// Find the native submenu for one of our top-level submenus.
+///
+/// The top-level titles are localized, so the submenu is looked up by id and
+/// its current title is used to find the matching NSMenu item.
+fn find_submenu(app: &AppHandle, ns_app: &NSApplication, id: &str) -> Option<Retained<NSMenu>> {
+    let menu = app.menu()?;
+    let Some(MenuItemKind::Submenu(submenu)) = menu.get(id) else {
+        return None;
+    };
+    let title = submenu.text().ok()?;
+    let main_menu = ns_app.mainMenu()?;
+    let item = main_menu.itemWithTitle(&NSString::from_str(&title))?;
+    item.submenu()
+}
 
 /// Fix the Help menu on macOS.
 ///
-/// This finds the "Help" submenu in the app's main menu and properly registers it
+/// This finds the Help submenu in the app's main menu and properly registers it
 /// with NSApplication so macOS shows the native search field.
 ///
 /// Must be called after `app.set_menu()`.
-pub fn fix_help_menu() {
+pub fn fix_help_menu(app: &AppHandle) {
     let Some(mtm) = MainThreadMarker::new() else {
+        #[cfg(debug_assertions)]
         eprintln!("[macos_menu] Not on main thread, cannot fix Help menu");
         return;
     };
 
-    let app = NSApplication::sharedApplication(mtm);
-    let Some(main_menu) = app.mainMenu() else {
-        eprintln!("[macos_menu] No main menu found");
-        return;
-    };
-
-    // Find the Help menu by title
-    let help_title = NSString::from_str("Help");
-    let Some(help_item) = main_menu.itemWithTitle(&help_title) else {
-        eprintln!("[macos_menu] No 'Help' menu item found");
-        return;
-    };
-
-    let Some(help_submenu) = help_item.submenu() else {
-        eprintln!("[macos_menu] Help item has no submenu");
+    let ns_app = NSApplication::sharedApplication(mtm);
+    let Some(help_submenu) = find_submenu(app, &ns_app, HELP_MENU_ID) else {
+        #[cfg(debug_assertions)]
+        eprintln!("[macos_menu] No Help menu found");
         return;
     };
 
     // Register as the Help menu — this enables the native search field
-    app.setHelpMenu(Some(&help_submenu));
+    ns_app.setHelpMenu(Some(&help_submenu));
 
     #[cfg(debug_assertions)]
     eprintln!("[macos_menu] Help menu registered with search field");
@@ -46,36 +56,27 @@ pub fn fix_help_menu() {
 
 /// Fix the Window menu on macOS.
 ///
-/// This finds the "Window" submenu and registers it with NSApplication
+/// This finds the Window submenu and registers it with NSApplication
 /// so macOS adds native window management items.
-pub fn fix_window_menu() {
+pub fn fix_window_menu(app: &AppHandle) {
     let Some(mtm) = MainThreadMarker::new() else {
         return;
     };
 
-    let app = NSApplication::sharedApplication(mtm);
-    let Some(main_menu) = app.mainMenu() else {
-        return;
-    };
-
-    let window_title = NSString::from_str("Window");
-    let Some(window_item) = main_menu.itemWithTitle(&window_title) else {
+    let ns_app = NSApplication::sharedApplication(mtm);
+    let Some(window_submenu) = find_submenu(app, &ns_app, WINDOW_MENU_ID) else {
         // Window menu is optional
         return;
     };
 
-    let Some(window_submenu) = window_item.submenu() else {
-        return;
-    };
-
-    app.setWindowsMenu(Some(&window_submenu));
+    ns_app.setWindowsMenu(Some(&window_submenu));
 
     #[cfg(debug_assertions)]
     eprintln!("[macos_menu] Window menu registered");
 }
 
 /// Apply all macOS menu fixes.
-pub fn apply_menu_fixes() {
-    fix_help_menu();
-    fix_window_menu();
+pub fn apply_menu_fixes(app: &AppHandle) {
+    fix_help_menu(app);
+    fix_window_menu(app);
 }
@@ -0,0 +1,121 @@
+//! macOS Services ("New VMark Note with Selection", "Append to VMark Inbox").
+//!
+//! The services are declared under `NSServices` in `Info.plist`. AppKit
+//! delivers them to the services provider object by calling
+//! `<NSMessage>:userData:error:` with a pasteboard holding the selection.
+//! The two methods are added to tao's application delegate class, which is
+//! then registered as the provider.
+
+use std::sync::OnceLock;
+
+use objc2::ffi::class_addMethod;
+use objc2::runtime::{AnyClass, AnyObject, Imp, Sel};
+use objc2::{sel, MainThreadMarker};
+use objc2_app_kit::{NSApplication, NSPasteboard, NSPasteboardTypeString, NSUpdateDynamicServices};
+use objc2_foundation::NSString;
+use tauri::AppHandle;
+
+use crate::quick_capture;
+use crate::window_manager::{self, InitialContent};
+
+static APP: OnceLock<AppHandle> = OnceLock::new();
+
+/// Signature of a service method: `-(void)name:(NSPasteboard *)pboard
+/// userData:(NSString *)data error:(NSString **)error`
+type ServiceFn =
+    extern "C-unwind" fn(&AnyObject, Sel, &NSPasteboard, *mut NSString, *mut *mut NSString);
+
+fn selected_text(pboard: &NSPasteboard) -> Option<String> {
+    let text = unsafe { pboard.stringForType(NSPasteboardTypeString) }?.to_string();
+    (!text.trim().is_empty()).then_some(text)
+}
+
+/// `-[delegate newNoteWithSelection:userData:error:]`
+extern "C-unwind" fn new_note_with_selection(
+    _this: &AnyObject,
+    _cmd: Sel,
+    pboard: &NSPasteboard,
+    _user_data: *mut NSString,
+    _error: *mut *mut NSString,
+) {
+    let (Some(app), Some(content)) = (APP.get(), selected_text(pboard)) else {
+        return;
+    };
+    let initial = InitialContent {
+        content,
+        suggested_name: None,
+    };
+    if let Err(_e) = window_manager::create_document_window_with_content(app, initial) {
+        #[cfg(debug_assertions)]
+        eprintln!("[macos_services] Failed to create window: {}", _e);
+    }
+}
+
+/// `-[delegate appendToInbox:userData:error:]`
+extern "C-unwind" fn append_to_inbox(
+    _this: &AnyObject,
+    _cmd: Sel,
+    pboard: &NSPasteboard,
+    _user_data: *mut NSString,
+    _error: *mut *mut NSString,
+) {
+    let (Some(app), Some(text)) = (APP.get(), selected_text(pboard)) else {
+        return;
+    };
+    if let Err(_e) = quick_capture::append_text(app, &text) {
+        #[cfg(debug_assertions)]
+        eprintln!("[macos_services] {}", _e);
+    }
+}
+
+/// Register the app delegate as the services provider. Must be called on the
+/// main thread from `setup`.
+pub fn install_services_provider(app: &AppHandle) {
+    let _ = APP.set(app.clone());
+
+    let Some(mtm) = MainThreadMarker::new() else {
+        #[cfg(debug_assertions)]
+        eprintln!("[macos_services] Not on main thread, cannot register services");
+        return;
+    };
+
+    let ns_app = NSApplication::sharedApplication(mtm);
+    let Some(delegate) = ns_app.delegate() else {
+        #[cfg(debug_assertions)]
+        eprintln!("[macos_services] No application delegate");
+        return;
+    };
+    let delegate_object: &AnyObject = (*delegate).as_ref();
+    let class = delegate_object.class() as *const AnyClass as *mut AnyClass;
+
+    let added = unsafe {
+        let new_note: ServiceFn = new_note_with_selection;
+        let append: ServiceFn = append_to_inbox;
+        class_addMethod(
+            class,
+            sel!(newNoteWithSelection:userData:error:),
+            std::mem::transmute::<ServiceFn, Imp>(new_note),
+            c"v@:@@^@".as_ptr(),
+        )
+        .as_bool()
+            && class_addMethod(
+                class,
+                sel!(appendToInbox:userData:error:),
+                std::mem::transmute::<ServiceFn, Imp>(append),
+                c"v@:@@^@".as_ptr(),
+            )
+            .as_bool()
+    };
+    if !added {
+        #[cfg(debug_assertions)]
+        eprintln!("[macos_services] Delegate already implements the service methods");
+        return;
+    }
+
+    unsafe { ns_app.setServicesProvider(Some(delegate_object)) };
+    // Refresh the Services menus of other apps without a logout.
+    NSUpdateDynamicServices();
+
+    #[cfg(debug_assertions)]
+    eprintln!("[macos_services] Services provider registered");
+}
@@ -0,0 +1,98 @@
+//! macOS power-off and sleep notifications.
+//!
+//! NSWorkspace posts `NSWorkspaceWillPowerOffNotification` before a logout,
+//! restart or shutdown, and `NSWorkspaceWillSleepNotification` before the
+//! Mac sleeps. The observer methods are added to tao's application delegate
+//! class (like the Dock menu) and hand off to `quit`.
+
+use std::sync::OnceLock;
+
+use objc2::ffi::class_addMethod;
+use objc2::runtime::{AnyClass, AnyObject, Imp, Sel};
+use objc2::{sel, MainThreadMarker};
+use objc2_app_kit::{
+    NSApplication, NSWorkspace, NSWorkspaceWillPowerOffNotification,
+    NSWorkspaceWillSleepNotification,
+};
+use objc2_foundation::NSNotification;
+use tauri::AppHandle;
+
+use crate::quit;
+
+static APP: OnceLock<AppHandle> = OnceLock::new();
+
+/// `-[delegate vmarkWillPowerOff:]`
+extern "C-unwind" fn will_power_off(_this: &AnyObject, _cmd: Sel, _notification: &NSNotification) {
+    if let Some(app) = APP.get() {
+        quit::handle_session_end(app);
+    }
+}
+
+/// `-[delegate vmarkWillSleep:]`
+extern "C-unwind" fn will_sleep(_this: &AnyObject, _cmd: Sel, _notification: &NSNotification) {
+    if let Some(app) = APP.get() {
+        quit::handle_system_sleep(app);
+    }
+}
+
+/// Observe the workspace notifications. Must be called on the main thread
+/// (i.e. from `setup`).
+pub fn install(app: &AppHandle) {
+    let _ = APP.set(app.clone());
+
+    let Some(mtm) = MainThreadMarker::new() else {
+        #[cfg(debug_assertions)]
+        eprintln!("[macos_system_events] Not on main thread, cannot observe notifications");
+        return;
+    };
+    let ns_app = NSApplication::sharedApplication(mtm);
+    let Some(delegate) = ns_app.delegate() else {
+        #[cfg(debug_assertions)]
+        eprintln!("[macos_system_events] No application delegate");
+        return;
+    };
+    let delegate_object: &AnyObject = (*delegate).as_ref();
+    let class = delegate_object.class() as *const AnyClass as *mut AnyClass;
+
+    let added = unsafe {
+        let power_off: extern "C-unwind" fn(&AnyObject, Sel, &NSNotification) = will_power_off;
+        let sleep: extern "C-unwind" fn(&AnyObject, Sel, &NSNotification) = will_sleep;
+        class_addMethod(
+            class,
+            sel!(vmarkWillPowerOff:),
+            std::mem::transmute::<_, Imp>(power_off),
+            c"v@:@".as_ptr(),
+        )
+        .as_bool()
+            && class_addMethod(
+                class,
+                sel!(vmarkWillSleep:),
+                std::mem::transmute::<_, Imp>(sleep),
+                c"v@:@".as_ptr(),
+            )
+            .as_bool()
+    };
+    if !added {
+        #[cfg(debug_assertions)]
+        eprintln!("[macos_system_events] Delegate already implements the observer methods");
+        return;
+    }
+
+    // The delegate lives as long as the app, so it is never removed as an
+    // observer.
+    let center = NSWorkspace::sharedWorkspace().notificationCenter();
+    unsafe {
+        center.addObserver_selector_name_object(
+            delegate_object,
+            sel!(vmarkWillPowerOff:),
+            Some(NSWorkspaceWillPowerOffNotification),
+            None,
+        );
+        center.addObserver_selector_name_object(
+            delegate_object,
+            sel!(vmarkWillSleep:),
+            Some(NSWorkspaceWillSleepNotification),
+            None,
+        );
+    }
+}
@@ -0,0 +1,139 @@
+//! macOS Touch Bar for document windows.
+//!
+//! Each document window gets a Touch Bar with Bold, Italic, Heading 1-3 and
+//! Task List buttons. Buttons target an action method added to tao's
+//! application delegate class (like the Dock menu) and are dispatched through
+//! `menu_events::handle_menu_event`, so they emit the same `menu:*` events to
+//! the focused document window as the menu items.
+
+use std::sync::OnceLock;
+
+use objc2::ffi::class_addMethod;
+use objc2::rc::Retained;
+use objc2::runtime::{AnyClass, AnyObject, Imp, Sel};
+use objc2::{sel, MainThreadMarker, MainThreadOnly};
+use objc2_app_kit::{
+    NSApplication, NSButton, NSCustomTouchBarItem, NSTouchBar, NSTouchBarItem, NSWindow,
+};
+use objc2_foundation::{NSArray, NSSet, NSString};
+use tauri::menu::{MenuEvent, MenuId};
+use tauri::{AppHandle, WebviewWindow};
+
+use crate::menu_events;
+
+/// Menu item ids and button titles, in Touch Bar order. A button's tag is
+/// its index here.
+const ITEMS: &[(&str, &str)] = &[
+    ("bold", "B"),
+    ("italic", "I"),
+    ("heading-1", "H1"),
+    ("heading-2", "H2"),
+    ("heading-3", "H3"),
+    ("task-list", "☑"),
+];
+
+static APP: OnceLock<AppHandle> = OnceLock::new();
+
+/// Delegate object the buttons target, once the action method is added.
+/// Stored as an address since `AnyObject` isn't `Sync`.
+static TARGET: OnceLock<Option<usize>> = OnceLock::new();
+
+/// `-[delegate vmarkTouchBarItem:]`, the action of every Touch Bar button.
+extern "C-unwind" fn touch_bar_item_pressed(_this: &AnyObject, _cmd: Sel, sender: &NSButton) {
+    let Some(app) = APP.get() else {
+        return;
+    };
+    let Some((id, _)) = usize::try_from(sender.tag())
+        .ok()
+        .and_then(|i| ITEMS.get(i))
+    else {
+        return;
+    };
+    menu_events::handle_menu_event(
+        app,
+        MenuEvent {
+            id: MenuId::new(*id),
+        },
+    );
+}
+
+/// Add the action method to the delegate class and return the delegate.
+fn install_action(mtm: MainThreadMarker) -> Option<usize> {
+    let ns_app = NSApplication::sharedApplication(mtm);
+    let delegate = ns_app.delegate()?;
+    let delegate_object: &AnyObject = (*delegate).as_ref();
+    let class = delegate_object.class() as *const AnyClass as *mut AnyClass;
+
+    let added = unsafe {
+        let pressed: extern "C-unwind" fn(&AnyObject, Sel, &NSButton) = touch_bar_item_pressed;
+        class_addMethod(
+            class,
+            sel!(vmarkTouchBarItem:),
+            std::mem::transmute::<_, Imp>(pressed),
+            c"v@:@".as_ptr(),
+        )
+        .as_bool()
+    };
+    if !added {
+        #[cfg(debug_assertions)]
+        eprintln!("[macos_touch_bar] Delegate already implements vmarkTouchBarItem:");
+        return None;
+    }
+    // The delegate is owned by NSApplication for the lifetime of the app.
+    Some(delegate_object as *const AnyObject as usize)
+}
+
+fn build_touch_bar(target: &AnyObject, mtm: MainThreadMarker) -> Retained<NSTouchBar> {
+    let mut identifiers = Vec::new();
+    let mut items: Vec<Retained<NSTouchBarItem>> = Vec::new();
+    for (index, (id, title)) in ITEMS.iter().enumerate() {
+        let identifier = NSString::from_str(&format!("app.vmark.touchbar.{id}"));
+        let button = unsafe {
+            NSButton::buttonWithTitle_target_action(
+                &NSString::from_str(title),
+                Some(target),
+                Some(sel!(vmarkTouchBarItem:)),
+                mtm,
+            )
+        };
+        button.setTag(index as isize);
+        let item =
+            NSCustomTouchBarItem::initWithIdentifier(NSCustomTouchBarItem::alloc(mtm), &identifier);
+        item.setView(&button);
+        items.push(Retained::into_super(item));
+        identifiers.push(identifier);
+    }
+
+    let touch_bar = NSTouchBar::new(mtm);
+    touch_bar.setDefaultItemIdentifiers(&NSArray::from_retained_slice(&identifiers));
+    touch_bar.setTemplateItems(&NSSet::from_retained_slice(&items));
+    touch_bar
+}
+
+/// Give a document window its Touch Bar.
+pub fn install(app: &AppHandle, window: &WebviewWindow) {
+    let _ = APP.set(app.clone());
+    let Ok(ns_window) = window.ns_window() else {
+        return;
+    };
+    let ns_window = ns_window as usize;
+
+    let _ = window.run_on_main_thread(move || {
+        let Some(mtm) = MainThreadMarker::new() else {
+            return;
+        };
+        let Some(target) = *TARGET.get_or_init(|| install_action(mtm)) else {
+            return;
+        };
+        // SAFETY: both pointers are live AppKit objects used on the main
+        // thread: the window was just created, the delegate lives as long
+        // as the app.
+        let (ns_window, target) = unsafe {
+            (
+                &*(ns_window as *const NSWindow),
+                &*(target as *const AnyObject),
+            )
+        };
+        ns_window.setTouchBar(Some(&build_touch_bar(target, mtm)));
+    });
+}
@@ -18,8 +18,9 @@ use std::collections::HashMap;
 use std::fs;
 use std::net::SocketAddr;
 use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
-use std::time::Instant;
+use std::time::{Duration, Instant};
 use tauri::{AppHandle, Emitter};
 use tokio::net::{TcpListener, TcpStream};
 use tokio::sync::{mpsc, oneshot, Mutex, RwLock};
@@ -171,6 +172,11 @@ static SHUTDOWN_TX: std::sync::OnceLock<Arc<RwLock<Option<oneshot::Sender<()>>>>
 /// All clients can read simultaneously, but writes are serialized.
 static WRITE_LOCK: std::sync::OnceLock<Arc<tokio::sync::Mutex<()>>> = std::sync::OnceLock::new();
 
+/// Set while the app is quitting; new requests are refused.
+static SHUTTING_DOWN: AtomicBool = AtomicBool::new(false);
+
+const SHUTTING_DOWN_ERROR: &str = "VMark is shutting down";
+
 fn get_bridge_state() -> Arc<Mutex<BridgeState>> {
     BRIDGE_STATE
         .get_or_init(|| {
@@ -347,7 +353,11 @@ pub async fn stop_bridge() {
         let _ = pending.response_tx.send(McpResponse {
             success: false,
             data: None,
-            error: Some("Bridge stopped".to_string()),
+            error: Some(if SHUTTING_DOWN.load(Ordering::SeqCst) {
+                SHUTTING_DOWN_ERROR.to_string()
+            } else {
+                "Bridge stopped".to_string()
+            }),
         });
     }
 }
@@ -513,6 +523,28 @@ async fn handle_message(text: &str, client_id: u64, app: &AppHandle) -> Result<(
         return Ok(());
     }
 
+    if SHUTTING_DOWN.load(Ordering::SeqCst) {
+        let state = get_bridge_state();
+        let guard = state.lock().await;
+        let client = guard.clients.get(&client_id).ok_or("Client not found")?;
+        let response = WsMessage {
+            id: msg.id,
+            msg_type: "response".to_string(),
+            payload: serde_json::to_value(McpResponse {
+                success: false,
+                data: None,
+                error: Some(SHUTTING_DOWN_ERROR.to_string()),
+            })
+            .unwrap_or_default(),
+        };
+        let response_json =
+            serde_json::to_string(&response).map_err(|e| format!("Failed to serialize: {}", e))?;
+        return client
+            .tx
+            .send(response_json)
+            .map_err(|e| format!("Failed to send response: {}", e));
+    }
+
     let request = McpRequest::from_value(msg.payload.clone())?;
 
     // Debug: Log request args to trace markdown escaping issues
@@ -657,6 +689,51 @@ pub async fn mcp_bridge_respond(payload: McpResponsePayload) -> Result<(), Strin
     Ok(())
 }
 
+/// Send a status message to every connected client.
+async fn broadcast_status(msg_type: &str, payload: serde_json::Value) {
+    let msg = WsMessage {
+        id: "system".to_string(),
+        msg_type: msg_type.to_string(),
+        payload,
+    };
+    let Ok(msg_str) = serde_json::to_string(&msg) else {
+        return;
+    };
+    let state = get_bridge_state();
+    let guard = state.lock().await;
+    for client in guard.clients.values() {
+        let _ = client.tx.send(msg_str.clone());
+    }
+}
+
+/// Tell clients the app is quitting (`shutting_down`), refuse new requests,
+/// and wait up to `grace` for in-flight requests to be answered.
+pub async fn announce_shutdown(grace: Duration) {
+    if SHUTTING_DOWN.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    broadcast_status(
+        "shutting_down",
+        serde_json::json!({ "graceMs": grace.as_millis() as u64 }),
+    )
+    .await;
+
+    let deadline = Instant::now() + grace;
+    while Instant::now() < deadline {
+        if get_bridge_state().lock().await.pending.is_empty() {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(50)).await;
+    }
+}
+
+/// The quit was cancelled: accept requests again (`shutdown_cancelled`).
+pub async fn cancel_shutdown() {
+    if SHUTTING_DOWN.swap(false, Ordering::SeqCst) {
+        broadcast_status("shutdown_cancelled", serde_json::json!({})).await;
+    }
+}
+
 /// Check if the bridge has any connected clients.
 #[allow(dead_code)]
 pub async fn is_client_connected() -> bool {
@@ -69,7 +69,9 @@ pub async fn mcp_bridge_start(app: AppHandle, port: u16) -> Result<McpServerStat
     }
 
     // Start the bridge WebSocket server (returns actual port assigned by OS)
+    let started_at = std::time::Instant::now();
     let actual_port = mcp_bridge::start_bridge(app.clone(), port).await?;
+    crate::startup_timings::record("mcp_bridge", started_at, started_at.elapsed());
 
     // Mark bridge as running with actual port
     BRIDGE_RUNNING.store(true, Ordering::SeqCst);
@@ -272,6 +274,22 @@ pub async fn mcp_bridge_client_count() -> Result<usize, String> {
     Ok(mcp_bridge::client_count().await)
 }
 
+/// How long quitting waits for in-flight MCP requests
+const SHUTDOWN_GRACE: std::time::Duration = std::time::Duration::from_millis(1500);
+
+/// Notify connected MCP clients that the app is quitting and give in-flight
+/// requests a short grace period, so they aren't cut off by `cleanup`.
+pub async fn announce_shutdown() {
+    if BRIDGE_RUNNING.load(Ordering::SeqCst) {
+        mcp_bridge::announce_shutdown(SHUTDOWN_GRACE).await;
+    }
+}
+
+/// The quit was cancelled; MCP clients may send requests again.
+pub fn cancel_shutdown() {
+    tauri::async_runtime::spawn(mcp_bridge::cancel_shutdown());
+}
+
 /// Cleanup function to kill the MCP server on app exit.
 pub fn cleanup() {
     // Stop the bridge
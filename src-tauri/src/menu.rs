@@ -1,19 +1,91 @@
+use serde::Deserialize;
 use std::collections::HashMap;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Mutex;
-use tauri::menu::{Menu, MenuItem, MenuItemKind, PredefinedMenuItem, Submenu};
-use tauri::AppHandle;
+use tauri::menu::{CheckMenuItem, Menu, MenuItem, MenuItemKind, PredefinedMenuItem, Submenu};
+use tauri::{AppHandle, Emitter};
 
 pub const RECENT_FILES_SUBMENU_ID: &str = "recent-files-submenu";
 pub const RECENT_WORKSPACES_SUBMENU_ID: &str = "recent-workspaces-submenu";
+pub const WINDOW_MENU_ID: &str = "window-menu";
+pub const HELP_MENU_ID: &str = "help-menu";
+pub const EXPORT_SUBMENU_ID: &str = "export-submenu";
+pub const TEMPLATES_SUBMENU_ID: &str = "templates-submenu";
+pub const FAVORITES_SUBMENU_ID: &str = "favorites-submenu";
+pub const SPELLING_SUBMENU_ID: &str = "spelling-submenu";
+
+/// Id prefix of the New from Template entries
+pub const TEMPLATE_ITEM_PREFIX: &str = "template-";
+
+/// Id prefix of the Favorites entries
+pub const FAVORITE_ITEM_PREFIX: &str = "favorite-";
+
+/// Id prefix of the Spelling entries, followed by the language code
+pub const SPELLING_ITEM_PREFIX: &str = "spelling-lang-";
+
+/// Id prefix of plugin-contributed items (`plugin:<plugin>:<item>`)
+pub const PLUGIN_ITEM_PREFIX: &str = "plugin:";
+
+/// A spell-check dictionary listed in the Spelling submenu.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct SpellingLanguage {
+    /// Language code, e.g. `en-US`
+    pub code: String,
+    /// Display name, e.g. `English (US)`
+    pub name: String,
+}
+
+/// Id prefix of the Window menu entries for open document windows
+pub const WINDOW_ITEM_PREFIX: &str = "window-item-";
+
+/// Native fullscreen shortcut: Ctrl+Cmd+F on macOS, F11 elsewhere
+#[cfg(target_os = "macos")]
+const FULLSCREEN_ACCEL: &str = "Ctrl+Cmd+F";
+#[cfg(not(target_os = "macos"))]
+const FULLSCREEN_ACCEL: &str = "F11";
+
+/// Redo shortcut: Ctrl+Y is the Windows convention
+#[cfg(target_os = "windows")]
+const REDO_ACCEL: &str = "Ctrl+Y";
+#[cfg(not(target_os = "windows"))]
+const REDO_ACCEL: &str = "CmdOrCtrl+Shift+Z";
 
 /// Stores the recent files list snapshot at menu build time.
 /// This ensures that when a menu item is clicked, we can look up
 /// the correct path even if the store changed since menu creation.
 static RECENT_FILES_SNAPSHOT: Mutex<Vec<String>> = Mutex::new(Vec::new());
 
+/// Set once the recent files were checked for deleted entries this launch
+static RECENT_FILES_PRUNED: AtomicBool = AtomicBool::new(false);
+
 /// Stores the recent workspaces list snapshot at menu build time.
 static RECENT_WORKSPACES_SNAPSHOT: Mutex<Vec<String>> = Mutex::new(Vec::new());
 
+/// Template paths listed in the New from Template submenu.
+static TEMPLATES_SNAPSHOT: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+/// Pinned file paths listed in the Favorites submenu.
+static FAVORITES_SNAPSHOT: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+/// Dictionaries listed in the Spelling submenu.
+static SPELLING_SNAPSHOT: Mutex<Vec<SpellingLanguage>> = Mutex::new(Vec::new());
+
+/// Workspace whose templates and pinned files the File menu lists.
+static MENU_WORKSPACE: Mutex<Option<String>> = Mutex::new(None);
+
+/// Shortcuts from the settings UI used for the last menu rebuild.
+static LAST_SHORTCUTS: Mutex<Option<HashMap<String, String>>> = Mutex::new(None);
+
+/// Shortcuts from the settings UI used for the last menu rebuild.
+pub fn last_shortcuts() -> HashMap<String, String> {
+    LAST_SHORTCUTS
+        .lock()
+        .ok()
+        .and_then(|s| s.clone())
+        .unwrap_or_default()
+}
+
 /// Get the path for a recent file by its menu index.
 /// Returns None if index is out of bounds.
 pub fn get_recent_file_path(index: usize) -> Option<String> {
@@ -23,6 +95,22 @@ pub fn get_recent_file_path(index: usize) -> Option<String> {
         .and_then(|files| files.get(index).cloned())
 }
 
+/// Get the path for a template by its menu index.
+pub fn get_template_path(index: usize) -> Option<String> {
+    TEMPLATES_SNAPSHOT
+        .lock()
+        .ok()
+        .and_then(|templates| templates.get(index).cloned())
+}
+
+/// Get the path for a pinned file by its menu index.
+pub fn get_favorite_path(index: usize) -> Option<String> {
+    FAVORITES_SNAPSHOT
+        .lock()
+        .ok()
+        .and_then(|favorites| favorites.get(index).cloned())
+}
+
 /// Get the path for a recent workspace by its menu index.
 /// Returns None if index is out of bounds.
 pub fn get_recent_workspace_path(index: usize) -> Option<String> {
@@ -33,16 +121,18 @@ pub fn get_recent_workspace_path(index: usize) -> Option<String> {
 }
 
 // ============================================================================
-// Menu Structure (8 menus on macOS, 7 on Windows/Linux):
+// Menu Structure (8 menus on each platform):
 //
 // macOS:        VMark | File | Edit | Format | Insert | View | Window | Help
-// Windows/Linux:        File | Edit | Format | Insert | View | Window | Help
+// Windows/Linux:        File | Edit | Format | Insert | View | Tools | Window | Help
 //
 // Key changes from previous structure:
 // - Block menu merged into Format
-// - Tools menu removed (CJK/Cleanup → Format)
+// - Tools menu removed (CJK/Cleanup → Format); on Windows/Linux it now only
+//   holds Options (Settings in the app menu on macOS)
 // - Window menu added
 // - About/Updates moved to App menu (macOS) or Help menu (others)
+// - File ends with Exit on Windows/Linux; Redo is Ctrl+Y on Windows
 // ============================================================================
 
 pub fn create_menu(app: &tauri::AppHandle) -> tauri::Result<Menu<tauri::Wry>> {
@@ -56,9 +146,9 @@ pub fn create_menu(app: &tauri::AppHandle) -> tauri::Result<Menu<tauri::Wry>> {
         true,
         &[
             &PredefinedMenuItem::about(app, Some("About VMark"), None)?,
-            &MenuItem::with_id(app, "check-updates", "Check for Updates...", true, None::<&str>)?,
+            &MenuItem::with_id(app, "check-updates", "Check for Updates…", true, None::<&str>)?,
             &PredefinedMenuItem::separator(app)?,
-            &MenuItem::with_id(app, "preferences", "Settings...", true, Some("CmdOrCtrl+,"))?,
+            &MenuItem::with_id(app, "preferences", "Settings…", true, Some("CmdOrCtrl+,"))?,
             &PredefinedMenuItem::separator(app)?,
             &PredefinedMenuItem::services(app, Some("Services"))?,
             &PredefinedMenuItem::separator(app)?,
@@ -74,6 +164,22 @@ pub fn create_menu(app: &tauri::AppHandle) -> tauri::Result<Menu<tauri::Wry>> {
     // ========================================================================
     // File menu
     // ========================================================================
+    let templates_submenu = Submenu::with_id_and_items(
+        app,
+        TEMPLATES_SUBMENU_ID,
+        "New from Template",
+        true,
+        &[&MenuItem::with_id(app, "no-templates", "No Templates", false, None::<&str>)?],
+    )?;
+
+    let favorites_submenu = Submenu::with_id_and_items(
+        app,
+        FAVORITES_SUBMENU_ID,
+        "Favorites",
+        true,
+        &[&MenuItem::with_id(app, "no-favorites", "No Favorites", false, None::<&str>)?],
+    )?;
+
     let recent_submenu = Submenu::with_id_and_items(
         app,
         RECENT_FILES_SUBMENU_ID,
@@ -98,13 +204,18 @@ pub fn create_menu(app: &tauri::AppHandle) -> tauri::Result<Menu<tauri::Wry>> {
         ],
     )?;
 
-    let export_submenu = Submenu::with_items(
+    let export_submenu = Submenu::with_id_and_items(
         app,
+        EXPORT_SUBMENU_ID,
         "Export",
         true,
         &[
-            &MenuItem::with_id(app, "export-html", "HTML...", true, Some("Alt+CmdOrCtrl+E"))?,
-            &MenuItem::with_id(app, "export-pdf", "Print...", true, Some("CmdOrCtrl+P"))?,
+            &MenuItem::with_id(app, "save-pdf", "PDF…", true, None::<&str>)?,
+            &MenuItem::with_id(app, "export-html", "HTML…", true, Some("Alt+CmdOrCtrl+E"))?,
+            &MenuItem::with_id(app, "export-docx", "Word (DOCX)…", true, None::<&str>)?,
+            &MenuItem::with_id(app, "export-epub", "EPUB…", true, None::<&str>)?,
+            &PredefinedMenuItem::separator(app)?,
+            &MenuItem::with_id(app, "export-pdf", "Print…", true, Some("CmdOrCtrl+P"))?,
             &PredefinedMenuItem::separator(app)?,
             &MenuItem::with_id(app, "copy-html", "Copy as HTML", true, Some("CmdOrCtrl+Shift+C"))?,
         ],
@@ -115,9 +226,9 @@ pub fn create_menu(app: &tauri::AppHandle) -> tauri::Result<Menu<tauri::Wry>> {
         "Document History",
         true,
         &[
-            &MenuItem::with_id(app, "view-history", "View History...", true, Some("CmdOrCtrl+Shift+H"))?,
+            &MenuItem::with_id(app, "view-history", "View History…", true, Some("CmdOrCtrl+Shift+H"))?,
             &PredefinedMenuItem::separator(app)?,
-            &MenuItem::with_id(app, "clear-history", "Clear History...", true, None::<&str>)?,
+            &MenuItem::with_id(app, "clear-history", "Clear History…", true, None::<&str>)?,
         ],
     )?;
 
@@ -129,18 +240,26 @@ pub fn create_menu(app: &tauri::AppHandle) -> tauri::Result<Menu<tauri::Wry>> {
         &[
             &MenuItem::with_id(app, "new", "New", true, Some("CmdOrCtrl+N"))?,
             &MenuItem::with_id(app, "new-window", "New Window", true, Some("CmdOrCtrl+Shift+N"))?,
+            &templates_submenu,
             &PredefinedMenuItem::separator(app)?,
-            &MenuItem::with_id(app, "open", "Open...", true, Some("CmdOrCtrl+O"))?,
-            &MenuItem::with_id(app, "open-folder", "Open Folder...", true, Some("CmdOrCtrl+Shift+O"))?,
+            &MenuItem::with_id(app, "open", "Open…", true, Some("CmdOrCtrl+O"))?,
+            &MenuItem::with_id(app, "open-folder", "Open Folder…", true, Some("CmdOrCtrl+Shift+O"))?,
+            &MenuItem::with_id(app, "insert-file-contents", "Insert File Contents…", true, None::<&str>)?,
+            &MenuItem::with_id(app, "insert-table-from-csv", "Insert Table from CSV…", true, None::<&str>)?,
             &recent_submenu,
             &recent_workspaces_submenu,
+            &favorites_submenu,
             &PredefinedMenuItem::separator(app)?,
             &MenuItem::with_id(app, "close", "Close", true, Some("CmdOrCtrl+W"))?,
             &MenuItem::with_id(app, "close-workspace", "Close Workspace", true, None::<&str>)?,
             &PredefinedMenuItem::separator(app)?,
             &MenuItem::with_id(app, "save", "Save", true, Some("CmdOrCtrl+S"))?,
-            &MenuItem::with_id(app, "save-as", "Save As...", true, Some("CmdOrCtrl+Shift+S"))?,
-            &MenuItem::with_id(app, "move-to", "Move to...", true, None::<&str>)?,
+            &MenuItem::with_id(app, "save-as", "Save As…", true, Some("CmdOrCtrl+Shift+S"))?,
+            &MenuItem::with_id(app, "move-to", "Move to…", true, None::<&str>)?,
+            &MenuItem::with_id(app, "toggle-pin-file", "Pin to Workspace", true, None::<&str>)?,
+            &MenuItem::with_id(app, "reveal-in-file-manager", crate::reveal::REVEAL_LABEL, true, None::<&str>)?,
+            &MenuItem::with_id(app, "open-in-terminal", "Open in Terminal", true, None::<&str>)?,
+            &MenuItem::with_id(app, "share", "Share…", true, None::<&str>)?,
             &PredefinedMenuItem::separator(app)?,
             &export_submenu,
             &PredefinedMenuItem::separator(app)?,
@@ -156,25 +275,30 @@ pub fn create_menu(app: &tauri::AppHandle) -> tauri::Result<Menu<tauri::Wry>> {
         &[
             &MenuItem::with_id(app, "new", "New", true, Some("CmdOrCtrl+N"))?,
             &MenuItem::with_id(app, "new-window", "New Window", true, Some("CmdOrCtrl+Shift+N"))?,
+            &templates_submenu,
             &PredefinedMenuItem::separator(app)?,
-            &MenuItem::with_id(app, "open", "Open...", true, Some("CmdOrCtrl+O"))?,
-            &MenuItem::with_id(app, "open-folder", "Open Folder...", true, Some("CmdOrCtrl+Shift+O"))?,
+            &MenuItem::with_id(app, "open", "Open…", true, Some("CmdOrCtrl+O"))?,
+            &MenuItem::with_id(app, "open-folder", "Open Folder…", true, Some("CmdOrCtrl+Shift+O"))?,
+            &MenuItem::with_id(app, "insert-file-contents", "Insert File Contents…", true, None::<&str>)?,
+            &MenuItem::with_id(app, "insert-table-from-csv", "Insert Table from CSV…", true, None::<&str>)?,
             &recent_submenu,
             &recent_workspaces_submenu,
+            &favorites_submenu,
             &PredefinedMenuItem::separator(app)?,
             &MenuItem::with_id(app, "close", "Close", true, Some("CmdOrCtrl+W"))?,
             &MenuItem::with_id(app, "close-workspace", "Close Workspace", true, None::<&str>)?,
             &PredefinedMenuItem::separator(app)?,
             &MenuItem::with_id(app, "save", "Save", true, Some("CmdOrCtrl+S"))?,
-            &MenuItem::with_id(app, "save-as", "Save As...", true, Some("CmdOrCtrl+Shift+S"))?,
-            &MenuItem::with_id(app, "move-to", "Move to...", true, None::<&str>)?,
+            &MenuItem::with_id(app, "save-as", "Save As…", true, Some("CmdOrCtrl+Shift+S"))?,
+            &MenuItem::with_id(app, "move-to", "Move to…", true, None::<&str>)?,
+            &MenuItem::with_id(app, "toggle-pin-file", "Pin to Workspace", true, None::<&str>)?,
+            &MenuItem::with_id(app, "reveal-in-file-manager", crate::reveal::REVEAL_LABEL, true, None::<&str>)?,
+            &MenuItem::with_id(app, "open-in-terminal", "Open in Terminal", true, None::<&str>)?,
             &PredefinedMenuItem::separator(app)?,
             &export_submenu,
             &PredefinedMenuItem::separator(app)?,
             &history_submenu,
             &PredefinedMenuItem::separator(app)?,
-            &MenuItem::with_id(app, "preferences", "Settings...", true, Some("CmdOrCtrl+,"))?,
-            &PredefinedMenuItem::separator(app)?,
             &MenuItem::with_id(app, "save-all-quit", "Save All and Exit", true, Some("Alt+Shift+CmdOrCtrl+Q"))?,
             &MenuItem::with_id(app, "quit", "Exit", true, Some("CmdOrCtrl+Q"))?,
         ],
@@ -188,7 +312,7 @@ pub fn create_menu(app: &tauri::AppHandle) -> tauri::Result<Menu<tauri::Wry>> {
         "Find",
         true,
         &[
-            &MenuItem::with_id(app, "find-replace", "Find and Replace...", true, Some("CmdOrCtrl+F"))?,
+            &MenuItem::with_id(app, "find-replace", "Find and Replace…", true, Some("CmdOrCtrl+F"))?,
             &MenuItem::with_id(app, "find-next", "Find Next", true, Some("CmdOrCtrl+G"))?,
             &MenuItem::with_id(app, "find-prev", "Find Previous", true, Some("CmdOrCtrl+Shift+G"))?,
             &PredefinedMenuItem::separator(app)?,
@@ -227,6 +351,14 @@ pub fn create_menu(app: &tauri::AppHandle) -> tauri::Result<Menu<tauri::Wry>> {
         ],
     )?;
 
+    let spelling_submenu = Submenu::with_id_and_items(
+        app,
+        SPELLING_SUBMENU_ID,
+        "Spelling",
+        true,
+        &[&MenuItem::with_id(app, "no-dictionaries", "No Dictionaries", false, None::<&str>)?],
+    )?;
+
     let line_endings_submenu = Submenu::with_items(
         app,
         "Line Endings",
@@ -243,7 +375,7 @@ pub fn create_menu(app: &tauri::AppHandle) -> tauri::Result<Menu<tauri::Wry>> {
         true,
         &[
             &MenuItem::with_id(app, "undo", "Undo", true, Some("CmdOrCtrl+Z"))?,
-            &MenuItem::with_id(app, "redo", "Redo", true, Some("CmdOrCtrl+Shift+Z"))?,
+            &MenuItem::with_id(app, "redo", "Redo", true, Some(REDO_ACCEL))?,
             &PredefinedMenuItem::separator(app)?,
             &PredefinedMenuItem::cut(app, Some("Cut"))?,
             &PredefinedMenuItem::copy(app, Some("Copy"))?,
@@ -254,6 +386,8 @@ pub fn create_menu(app: &tauri::AppHandle) -> tauri::Result<Menu<tauri::Wry>> {
             &selection_submenu,
             &lines_submenu,
             &line_endings_submenu,
+            &PredefinedMenuItem::separator(app)?,
+            &spelling_submenu,
         ],
     )?;
 
@@ -360,6 +494,7 @@ pub fn create_menu(app: &tauri::AppHandle) -> tauri::Result<Menu<tauri::Wry>> {
             &transform_submenu,
             &cjk_submenu,
             &cleanup_submenu,
+            &MenuItem::with_id(app, "format-markdown", "Format Markdown", true, None::<&str>)?,
             &PredefinedMenuItem::separator(app)?,
             &MenuItem::with_id(app, "clear-format", "Clear Format", true, Some("CmdOrCtrl+\\"))?,
         ],
@@ -426,7 +561,7 @@ pub fn create_menu(app: &tauri::AppHandle) -> tauri::Result<Menu<tauri::Wry>> {
         true,
         &[
             &links_submenu,
-            &MenuItem::with_id(app, "image", "Image...", true, Some("Shift+CmdOrCtrl+I"))?,
+            &MenuItem::with_id(app, "image", "Image…", true, Some("Shift+CmdOrCtrl+I"))?,
             &PredefinedMenuItem::separator(app)?,
             &table_submenu,
             &MenuItem::with_id(app, "code-fences", "Code Block", true, Some("Alt+CmdOrCtrl+C"))?,
@@ -448,50 +583,86 @@ pub fn create_menu(app: &tauri::AppHandle) -> tauri::Result<Menu<tauri::Wry>> {
         "View",
         true,
         &[
-            &MenuItem::with_id(app, "source-mode", "Source Code Mode", true, Some("F6"))?,
+            &CheckMenuItem::with_id(app, "source-mode", "Source Code Mode", true, false, Some("F6"))?,
             &PredefinedMenuItem::separator(app)?,
-            &MenuItem::with_id(app, "focus-mode", "Focus Mode", true, Some("F8"))?,
-            &MenuItem::with_id(app, "typewriter-mode", "Typewriter Mode", true, Some("F9"))?,
+            &CheckMenuItem::with_id(app, "focus-mode", "Focus Mode", true, false, Some("F8"))?,
+            &CheckMenuItem::with_id(app, "typewriter-mode", "Typewriter Mode", true, false, Some("F9"))?,
             &PredefinedMenuItem::separator(app)?,
             &MenuItem::with_id(app, "zoom-actual", "Actual Size", true, Some("CmdOrCtrl+0"))?,
             &MenuItem::with_id(app, "zoom-in", "Zoom In", true, Some("CmdOrCtrl+="))?,
             &MenuItem::with_id(app, "zoom-out", "Zoom Out", true, Some("CmdOrCtrl+-"))?,
+            &MenuItem::with_id(app, "page-zoom-actual", "Actual Page Size", true, Some("Alt+CmdOrCtrl+0"))?,
+            &MenuItem::with_id(app, "page-zoom-in", "Zoom Page In", true, Some("Shift+CmdOrCtrl+="))?,
+            &MenuItem::with_id(app, "page-zoom-out", "Zoom Page Out", true, Some("Shift+CmdOrCtrl+-"))?,
             &PredefinedMenuItem::separator(app)?,
-            &MenuItem::with_id(app, "word-wrap", "Toggle Word Wrap", true, Some("Alt+Z"))?,
+            &CheckMenuItem::with_id(app, "word-wrap", "Word Wrap", true, false, Some("Alt+Z"))?,
             &MenuItem::with_id(app, "line-numbers", "Toggle Line Numbers", true, Some("Alt+CmdOrCtrl+L"))?,
             &MenuItem::with_id(app, "diagram-preview", "Toggle Diagram Preview", true, Some("Alt+CmdOrCtrl+P"))?,
             &PredefinedMenuItem::separator(app)?,
-            &MenuItem::with_id(app, "sidebar", "Toggle Sidebar", true, Some("CmdOrCtrl+Shift+B"))?,
-            &MenuItem::with_id(app, "outline", "Toggle Outline", true, Some("Alt+CmdOrCtrl+1"))?,
+            &CheckMenuItem::with_id(app, "sidebar", "Sidebar", true, false, Some("CmdOrCtrl+Shift+B"))?,
+            &CheckMenuItem::with_id(app, "outline", "Outline", true, false, Some("Alt+CmdOrCtrl+1"))?,
             &PredefinedMenuItem::separator(app)?,
-            &PredefinedMenuItem::fullscreen(app, Some("Enter Full Screen"))?,
+            &CheckMenuItem::with_id(app, "always-on-top", "Float on Top", true, false, None::<&str>)?,
+            &MenuItem::with_id(app, "toggle-fullscreen", "Toggle Full Screen", true, Some(FULLSCREEN_ACCEL))?,
         ],
     )?;
 
+    // ========================================================================
+    // Tools menu (Windows/Linux; settings live in the app menu on macOS)
+    // ========================================================================
+    #[cfg(not(target_os = "macos"))]
+    let tools_menu = Submenu::with_items(
+        app,
+        "Tools",
+        true,
+        &[&MenuItem::with_id(app, "preferences", "Options…", true, Some("CmdOrCtrl+,"))?],
+    )?;
+
     // ========================================================================
     // Window menu
     // ========================================================================
+    let arrange_submenu = Submenu::with_items(
+        app,
+        "Arrange",
+        true,
+        &[
+            &MenuItem::with_id(app, "snap-left-half", "Left Half", true, Some("Ctrl+Alt+Left"))?,
+            &MenuItem::with_id(app, "snap-right-half", "Right Half", true, Some("Ctrl+Alt+Right"))?,
+            &MenuItem::with_id(app, "snap-top", "Top Half", true, Some("Ctrl+Alt+Up"))?,
+            &MenuItem::with_id(app, "snap-bottom", "Bottom Half", true, Some("Ctrl+Alt+Down"))?,
+            &PredefinedMenuItem::separator(app)?,
+            &MenuItem::with_id(app, "snap-top-left", "Top Left", true, Some("Ctrl+Alt+Home"))?,
+            &MenuItem::with_id(app, "snap-top-right", "Top Right", true, Some("Ctrl+Alt+PageUp"))?,
+            &MenuItem::with_id(app, "snap-bottom-left", "Bottom Left", true, Some("Ctrl+Alt+End"))?,
+            &MenuItem::with_id(app, "snap-bottom-right", "Bottom Right", true, Some("Ctrl+Alt+PageDown"))?,
+        ],
+    )?;
+
     #[cfg(target_os = "macos")]
-    let window_menu = Submenu::with_items(
+    let window_menu = Submenu::with_id_and_items(
         app,
+        WINDOW_MENU_ID,
         "Window",
         true,
         &[
             &PredefinedMenuItem::minimize(app, Some("Minimize"))?,
             &PredefinedMenuItem::maximize(app, Some("Zoom"))?,
+            &arrange_submenu,
             &PredefinedMenuItem::separator(app)?,
             &MenuItem::with_id(app, "bring-all-to-front", "Bring All to Front", true, None::<&str>)?,
         ],
     )?;
 
     #[cfg(not(target_os = "macos"))]
-    let window_menu = Submenu::with_items(
+    let window_menu = Submenu::with_id_and_items(
         app,
+        WINDOW_MENU_ID,
         "Window",
         true,
         &[
             &PredefinedMenuItem::minimize(app, Some("Minimize"))?,
             &PredefinedMenuItem::maximize(app, Some("Maximize"))?,
+            &arrange_submenu,
         ],
     )?;
 
@@ -499,30 +670,34 @@ pub fn create_menu(app: &tauri::AppHandle) -> tauri::Result<Menu<tauri::Wry>> {
     // Help menu
     // ========================================================================
     #[cfg(target_os = "macos")]
-    let help_menu = Submenu::with_items(
+    let help_menu = Submenu::with_id_and_items(
         app,
+        HELP_MENU_ID,
         "Help",
         true,
         &[
             &MenuItem::with_id(app, "vmark-help", "VMark Help", true, None::<&str>)?,
             &MenuItem::with_id(app, "keyboard-shortcuts", "Keyboard Shortcuts", true, None::<&str>)?,
             &PredefinedMenuItem::separator(app)?,
-            &MenuItem::with_id(app, "report-issue", "Report an Issue...", true, None::<&str>)?,
+            &MenuItem::with_id(app, "report-issue", "Report an Issue…", true, None::<&str>)?,
+            &MenuItem::with_id(app, "open-logs-folder", "Open Logs Folder", true, None::<&str>)?,
         ],
     )?;
 
     #[cfg(not(target_os = "macos"))]
-    let help_menu = Submenu::with_items(
+    let help_menu = Submenu::with_id_and_items(
         app,
+        HELP_MENU_ID,
         "Help",
         true,
         &[
             &MenuItem::with_id(app, "vmark-help", "VMark Help", true, None::<&str>)?,
             &MenuItem::with_id(app, "keyboard-shortcuts", "Keyboard Shortcuts", true, None::<&str>)?,
             &PredefinedMenuItem::separator(app)?,
-            &MenuItem::with_id(app, "report-issue", "Report an Issue...", true, None::<&str>)?,
+            &MenuItem::with_id(app, "report-issue", "Report an Issue…", true, None::<&str>)?,
+            &MenuItem::with_id(app, "open-logs-folder", "Open Logs Folder", true, None::<&str>)?,
             &PredefinedMenuItem::separator(app)?,
-            &MenuItem::with_id(app, "check-updates", "Check for Updates...", true, None::<&str>)?,
+            &MenuItem::with_id(app, "check-updates", "Check for Updates…", true, None::<&str>)?,
             &PredefinedMenuItem::about(app, Some("About VMark"), None)?,
         ],
     )?;
@@ -554,37 +729,66 @@ pub fn create_menu(app: &tauri::AppHandle) -> tauri::Result<Menu<tauri::Wry>> {
             &format_menu,
             &insert_menu,
             &view_menu,
+            &tools_menu,
             &window_menu,
             &help_menu,
         ],
     )
 }
 
-/// Update the Open Recent submenu with the given list of file paths
-pub fn update_recent_files_menu(app: &AppHandle, files: Vec<String>) -> tauri::Result<()> {
-    // Store snapshot of files for lookup when menu items are clicked
-    if let Ok(mut snapshot) = RECENT_FILES_SNAPSHOT.lock() {
-        *snapshot = files.clone();
-    }
-
+/// Find a submenu nested in one of the top-level menus by its id.
+fn find_submenu(app: &AppHandle, id: &str) -> tauri::Result<Option<Submenu<tauri::Wry>>> {
     let Some(menu) = app.menu() else {
-        return Ok(());
+        return Ok(None);
     };
-
-    // Find the recent files submenu
-    let mut submenu_opt = None;
     for item in menu.items()? {
         if let MenuItemKind::Submenu(sub) = item {
-            if let Some(found) = sub.get(RECENT_FILES_SUBMENU_ID) {
-                if let MenuItemKind::Submenu(recent) = found {
-                    submenu_opt = Some(recent);
-                    break;
-                }
+            if let Some(MenuItemKind::Submenu(found)) = sub.get(id) {
+                return Ok(Some(found));
             }
         }
     }
+    Ok(None)
+}
+
+/// Fill the recent files/workspaces submenus of a freshly built menu from
+/// the last reported lists, so a rebuild doesn't blank them out.
+fn restore_recent_menus(app: &AppHandle) -> tauri::Result<()> {
+    let files = RECENT_FILES_SNAPSHOT
+        .lock()
+        .map(|f| f.clone())
+        .unwrap_or_default();
+    let workspaces = RECENT_WORKSPACES_SNAPSHOT
+        .lock()
+        .map(|w| w.clone())
+        .unwrap_or_default();
+    update_recent_files_menu(app, files)?;
+    update_recent_workspaces_menu(app, workspaces)
+}
+
+/// Menu for app startup: the default menu, or the user keymap applied to it
+/// when `~/.vmark/keybindings.json` has bindings, in the menu language.
+pub fn create_initial_menu(app: &AppHandle) -> tauri::Result<Menu<tauri::Wry>> {
+    let keymap = crate::keybindings::keymap();
+    let menu = if keymap.is_empty() {
+        create_menu(app)?
+    } else {
+        create_menu_with_shortcuts(app, &keymap)?
+    };
+    crate::menu_i18n::localize_menu(&menu)?;
+    Ok(menu)
+}
+
+/// Update the Open Recent submenu with the given list of file paths
+pub fn update_recent_files_menu(app: &AppHandle, files: Vec<String>) -> tauri::Result<()> {
+    // Store snapshot of files for lookup when menu items are clicked
+    if let Ok(mut snapshot) = RECENT_FILES_SNAPSHOT.lock() {
+        *snapshot = files.clone();
+    }
+    #[cfg(target_os = "windows")]
+    crate::windows_jump_list::update(files.clone());
 
-    let Some(submenu) = submenu_opt else {
+    let Some(submenu) = find_submenu(app, RECENT_FILES_SUBMENU_ID)? else {
         return Ok(());
     };
 
@@ -595,7 +799,7 @@ pub fn update_recent_files_menu(app: &AppHandle, files: Vec<String>) -> tauri::R
 
     // Add file items
     if files.is_empty() {
-        let no_recent = MenuItem::with_id(app, "no-recent", "No Recent Files", false, None::<&str>)?;
+        let no_recent = MenuItem::with_id(app, "no-recent", crate::menu_i18n::tr("No Recent Files"), false, None::<&str>)?;
         submenu.append(&no_recent)?;
     } else {
         for (index, path) in files.iter().enumerate() {
@@ -614,7 +818,7 @@ pub fn update_recent_files_menu(app: &AppHandle, files: Vec<String>) -> tauri::R
     let separator = PredefinedMenuItem::separator(app)?;
     submenu.append(&separator)?;
 
-    let clear_item = MenuItem::with_id(app, "clear-recent", "Clear Recent Files", !files.is_empty(), None::<&str>)?;
+    let clear_item = MenuItem::with_id(app, "clear-recent", crate::menu_i18n::tr("Clear Recent Files"), !files.is_empty(), None::<&str>)?;
     submenu.append(&clear_item)?;
 
     Ok(())
@@ -622,32 +826,75 @@ pub fn update_recent_files_menu(app: &AppHandle, files: Vec<String>) -> tauri::R
 
 #[tauri::command]
 pub fn update_recent_files(app: AppHandle, files: Vec<String>) -> Result<(), String> {
+    // The first list comes from the frontend at launch
+    if !RECENT_FILES_PRUNED.swap(true, Ordering::SeqCst) {
+        prune_recent_files(app.clone(), files.clone());
+    }
     update_recent_files_menu(&app, files).map_err(|e| e.to_string())
 }
 
+/// Mount points whose subfolders are removable or network volumes, with the
+/// number of path components that name a volume below them.
+#[cfg(not(target_os = "windows"))]
+const VOLUME_DIRS: &[(&str, usize)] = &[
+    ("/Volumes", 1),
+    ("/mnt", 1),
+    ("/media", 2),
+    ("/run/media", 2),
+];
+
+/// Whether `path` is on a volume that isn't mounted right now, in which case
+/// its files are unreachable rather than gone.
+fn is_volume_unmounted(path: &Path) -> bool {
+    #[cfg(target_os = "windows")]
+    {
+        match path.components().next() {
+            Some(std::path::Component::Prefix(prefix)) => {
+                !Path::new(prefix.as_os_str()).join("\\").exists()
+            }
+            _ => false,
+        }
+    }
+    #[cfg(not(target_os = "windows"))]
+    {
+        VOLUME_DIRS.iter().any(|(dir, depth)| {
+            path.strip_prefix(dir).is_ok_and(|rest| {
+                let volume = rest
+                    .components()
+                    .take(*depth)
+                    .fold(std::path::PathBuf::from(dir), |volume, part| volume.join(part));
+                !volume.exists()
+            })
+        })
+    }
+}
+
+/// Check once per launch, off the main thread (paths on network volumes can
+/// be slow to stat), which recent files were deleted or moved, and report
+/// them to the frontend as `recent-files:pruned` so it drops them. Files on
+/// volumes that aren't mounted are kept.
+fn prune_recent_files(app: AppHandle, files: Vec<String>) {
+    std::thread::spawn(move || {
+        let missing: Vec<String> = files
+            .into_iter()
+            .filter(|file| {
+                let path = Path::new(file);
+                !is_volume_unmounted(path) && !path.exists()
+            })
+            .collect();
+        if !missing.is_empty() {
+            let _ = app.emit("recent-files:pruned", &missing);
+        }
+    });
+}
+
 /// Update the Open Recent Workspace submenu with the given list of workspace paths
 pub fn update_recent_workspaces_menu(app: &AppHandle, workspaces: Vec<String>) -> tauri::Result<()> {
     if let Ok(mut snapshot) = RECENT_WORKSPACES_SNAPSHOT.lock() {
         *snapshot = workspaces.clone();
     }
 
-    let Some(menu) = app.menu() else {
-        return Ok(());
-    };
-
-    let mut submenu_opt = None;
-    for item in menu.items()? {
-        if let MenuItemKind::Submenu(sub) = item {
-            if let Some(found) = sub.get(RECENT_WORKSPACES_SUBMENU_ID) {
-                if let MenuItemKind::Submenu(recent) = found {
-                    submenu_opt = Some(recent);
-                    break;
-                }
-            }
-        }
-    }
-
-    let Some(submenu) = submenu_opt else {
+    let Some(submenu) = find_submenu(app, RECENT_WORKSPACES_SUBMENU_ID)? else {
         return Ok(());
     };
 
@@ -656,7 +903,7 @@ pub fn update_recent_workspaces_menu(app: &AppHandle, workspaces: Vec<String>) -
     }
 
     if workspaces.is_empty() {
-        let no_recent = MenuItem::with_id(app, "no-recent-workspace", "No Recent Workspaces", false, None::<&str>)?;
+        let no_recent = MenuItem::with_id(app, "no-recent-workspace", crate::menu_i18n::tr("No Recent Workspaces"), false, None::<&str>)?;
         submenu.append(&no_recent)?;
     } else {
         for (index, path) in workspaces.iter().enumerate() {
@@ -674,7 +921,7 @@ pub fn update_recent_workspaces_menu(app: &AppHandle, workspaces: Vec<String>) -
     let separator = PredefinedMenuItem::separator(app)?;
     submenu.append(&separator)?;
 
-    let clear_item = MenuItem::with_id(app, "clear-recent-workspaces", "Clear Recent Workspaces", !workspaces.is_empty(), None::<&str>)?;
+    let clear_item = MenuItem::with_id(app, "clear-recent-workspaces", crate::menu_i18n::tr("Clear Recent Workspaces"), !workspaces.is_empty(), None::<&str>)?;
     submenu.append(&clear_item)?;
 
     Ok(())
@@ -685,15 +932,219 @@ pub fn update_recent_workspaces(app: AppHandle, workspaces: Vec<String>) -> Resu
     update_recent_workspaces_menu(&app, workspaces).map_err(|e| e.to_string())
 }
 
+/// List the templates of the current workspace (see `refresh_workspace_menus`)
+/// and the global ones in the New from Template submenu.
+pub fn update_templates_menu(app: &AppHandle) -> tauri::Result<()> {
+    let root = MENU_WORKSPACE.lock().ok().and_then(|r| r.clone());
+    let templates = crate::templates::list_templates(root.as_deref().map(std::path::Path::new));
+    if let Ok(mut snapshot) = TEMPLATES_SNAPSHOT.lock() {
+        *snapshot = templates.iter().map(|t| t.path.clone()).collect();
+    }
+
+    let Some(submenu) = find_submenu(app, TEMPLATES_SUBMENU_ID)? else {
+        return Ok(());
+    };
+
+    while let Some(item) = submenu.items()?.first() {
+        submenu.remove(item)?;
+    }
+
+    if templates.is_empty() {
+        let none = MenuItem::with_id(app, "no-templates", crate::menu_i18n::tr("No Templates"), false, None::<&str>)?;
+        submenu.append(&none)?;
+    }
+    for (index, template) in templates.iter().enumerate() {
+        let item_id = format!("{}{}", TEMPLATE_ITEM_PREFIX, index);
+        let item = MenuItem::with_id(app, &item_id, &template.name, true, None::<&str>)?;
+        submenu.append(&item)?;
+    }
+    Ok(())
+}
+
+/// List the pinned files of the current workspace in the Favorites submenu.
+/// Pins whose file is gone are shown disabled.
+pub fn update_favorites_menu(app: &AppHandle) -> tauri::Result<()> {
+    let root = MENU_WORKSPACE.lock().ok().and_then(|r| r.clone());
+    let pinned = root
+        .as_deref()
+        .and_then(|root| crate::workspace::list_pinned_files(root).ok())
+        .unwrap_or_default();
+    if let Ok(mut snapshot) = FAVORITES_SNAPSHOT.lock() {
+        *snapshot = pinned.iter().map(|p| p.absolute_path.clone()).collect();
+    }
+
+    let Some(submenu) = find_submenu(app, FAVORITES_SUBMENU_ID)? else {
+        return Ok(());
+    };
+
+    while let Some(item) = submenu.items()?.first() {
+        submenu.remove(item)?;
+    }
+
+    if pinned.is_empty() {
+        let none = MenuItem::with_id(app, "no-favorites", crate::menu_i18n::tr("No Favorites"), false, None::<&str>)?;
+        submenu.append(&none)?;
+    }
+    for (index, file) in pinned.iter().enumerate() {
+        let filename = std::path::Path::new(&file.path)
+            .file_name()
+            .and_then(|n| n.to_str())
+            .unwrap_or(&file.path);
+
+        let item_id = format!("{}{}", FAVORITE_ITEM_PREFIX, index);
+        let item = MenuItem::with_id(app, &item_id, filename, file.exists, None::<&str>)?;
+        submenu.append(&item)?;
+    }
+    Ok(())
+}
+
+/// Re-read templates and pinned files, following the workspace of the
+/// focused document window (unchanged when none is focused). Failures are
+/// logged in debug builds.
+pub fn refresh_workspace_menus(app: &AppHandle) {
+    let focused = crate::window_registry::document_windows(app)
+        .into_iter()
+        .find(|w| w.focused);
+    if let Some(window) = focused {
+        if let Ok(mut root) = MENU_WORKSPACE.lock() {
+            *root = window.workspace_root;
+        }
+    }
+    if let Err(_e) = update_templates_menu(app).and_then(|_| update_favorites_menu(app)) {
+        #[cfg(debug_assertions)]
+        eprintln!("[menu] Failed to update workspace menus: {}", _e);
+    }
+}
+
+/// List the installed spell-check dictionaries in the Spelling submenu. The
+/// checked language follows the focused window through
+/// `menu_state::sync_view_menu_state` (ids `spelling-lang-<code>`).
+pub fn update_spelling_menu(app: &AppHandle, languages: Vec<SpellingLanguage>) -> tauri::Result<()> {
+    if let Ok(mut snapshot) = SPELLING_SNAPSHOT.lock() {
+        *snapshot = languages.clone();
+    }
+
+    let Some(submenu) = find_submenu(app, SPELLING_SUBMENU_ID)? else {
+        return Ok(());
+    };
+
+    while let Some(item) = submenu.items()?.first() {
+        submenu.remove(item)?;
+    }
+
+    if languages.is_empty() {
+        let none = MenuItem::with_id(app, "no-dictionaries", crate::menu_i18n::tr("No Dictionaries"), false, None::<&str>)?;
+        submenu.append(&none)?;
+    }
+    for language in &languages {
+        let item_id = format!("{}{}", SPELLING_ITEM_PREFIX, language.code);
+        let item = CheckMenuItem::with_id(app, &item_id, &language.name, true, false, None::<&str>)?;
+        submenu.append(&item)?;
+    }
+    Ok(())
+}
+
+/// Report the dictionaries available to the spell checker.
+#[tauri::command]
+pub fn update_spelling_languages(app: AppHandle, languages: Vec<SpellingLanguage>) -> Result<(), String> {
+    update_spelling_menu(&app, languages).map_err(|e| e.to_string())?;
+    crate::menu_state::reapply(&app)
+}
+
+/// List open document windows at the end of the Window menu, checking the
+/// focused one. Called when windows open, close, retitle or change focus.
+///
+/// On macOS AppKit already lists windows in the registered Windows menu (see
+/// `macos_menu::fix_window_menu`), so nothing is added there.
+pub fn update_window_list_menu(app: &AppHandle) -> tauri::Result<()> {
+    if cfg!(target_os = "macos") {
+        return Ok(());
+    }
+    let Some(menu) = app.menu() else {
+        return Ok(());
+    };
+    let Some(MenuItemKind::Submenu(submenu)) = menu.get(WINDOW_MENU_ID) else {
+        return Ok(());
+    };
+
+    // Remove the previous list and the separator in front of it
+    for item in submenu.items()? {
+        if item.id().as_ref().starts_with(WINDOW_ITEM_PREFIX) {
+            submenu.remove(&item)?;
+        }
+    }
+    if let Some(last @ MenuItemKind::Predefined(_)) = submenu.items()?.pop() {
+        submenu.remove(&last)?;
+    }
+
+    let windows = crate::window_registry::document_windows(app);
+    if windows.is_empty() {
+        return Ok(());
+    }
+    submenu.append(&PredefinedMenuItem::separator(app)?)?;
+    for info in windows {
+        let title = if info.title.is_empty() {
+            crate::menu_i18n::tr("Untitled")
+        } else {
+            info.title
+        };
+        let item_id = format!("{}{}", WINDOW_ITEM_PREFIX, info.label);
+        let item = CheckMenuItem::with_id(app, &item_id, title, true, info.focused, None::<&str>)?;
+        submenu.append(&item)?;
+    }
+    Ok(())
+}
+
+/// Check the Window menu entry of `label` and uncheck the others. Clicking a
+/// check item toggles it, so the clicked entry has to be re-checked even when
+/// its window was already in front.
+pub fn check_window_item(app: &AppHandle, label: &str) -> tauri::Result<()> {
+    let Some(menu) = app.menu() else {
+        return Ok(());
+    };
+    let Some(MenuItemKind::Submenu(submenu)) = menu.get(WINDOW_MENU_ID) else {
+        return Ok(());
+    };
+    for item in submenu.items()? {
+        if let MenuItemKind::Check(check) = item {
+            if let Some(item_label) = check.id().as_ref().strip_prefix(WINDOW_ITEM_PREFIX) {
+                check.set_checked(item_label == label)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Refresh the Window menu list, logging failures in debug builds.
+pub fn refresh_window_list(app: &AppHandle) {
+    if let Err(_e) = update_window_list_menu(app) {
+        #[cfg(debug_assertions)]
+        eprintln!("[menu] Failed to update window list: {}", _e);
+    }
+}
+
 /// Rebuild the application menu with custom keyboard shortcuts.
 /// The shortcuts map is: menu_item_id -> accelerator_string (e.g., "bold" -> "CmdOrCtrl+B")
 #[tauri::command]
 pub fn rebuild_menu(app: AppHandle, shortcuts: HashMap<String, String>) -> Result<(), String> {
-    let menu = create_menu_with_shortcuts(&app, &shortcuts).map_err(|e| e.to_string())?;
+    let accelerators = crate::keybindings::apply_keymap(&shortcuts);
+    if let Ok(mut last) = LAST_SHORTCUTS.lock() {
+        *last = Some(shortcuts);
+    }
+    let menu = create_menu_with_shortcuts(&app, &accelerators).map_err(|e| e.to_string())?;
+    crate::menu_i18n::localize_menu(&menu).map_err(|e| e.to_string())?;
     app.set_menu(menu).map_err(|e| e.to_string())?;
+    restore_recent_menus(&app).map_err(|e| e.to_string())?;
+    update_templates_menu(&app).map_err(|e| e.to_string())?;
+    update_favorites_menu(&app).map_err(|e| e.to_string())?;
+    let spelling = SPELLING_SNAPSHOT.lock().map(|l| l.clone()).unwrap_or_default();
+    update_spelling_menu(&app, spelling).map_err(|e| e.to_string())?;
+    update_window_list_menu(&app).map_err(|e| e.to_string())?;
+    crate::plugin_menu::update_plugins_menu(&app).map_err(|e| e.to_string())?;
+    crate::menu_state::reapply(&app)?;
 
     #[cfg(target_os = "macos")]
-    crate::macos_menu::apply_menu_fixes();
+    crate::macos_menu::apply_menu_fixes(&app);
 
     Ok(())
 }
@@ -703,12 +1154,16 @@ fn create_menu_with_shortcuts(
     app: &AppHandle,
     shortcuts: &HashMap<String, String>,
 ) -> tauri::Result<Menu<tauri::Wry>> {
+    // Accelerators actually applied, checked for conflicts once the menu is built
+    let applied: std::cell::RefCell<HashMap<String, String>> = Default::default();
+
     // Helper to get shortcut for a menu item, falling back to default
     let get_accel = |id: &str, default: &str| -> Option<String> {
         let accel = shortcuts.get(id).map(|s| s.as_str()).unwrap_or(default);
         if accel.is_empty() {
             None
         } else {
+            applied.borrow_mut().insert(id.to_string(), accel.to_string());
             Some(accel.to_string())
         }
     };
@@ -723,9 +1178,9 @@ fn create_menu_with_shortcuts(
         true,
         &[
             &PredefinedMenuItem::about(app, Some("About VMark"), None)?,
-            &MenuItem::with_id(app, "check-updates", "Check for Updates...", true, None::<&str>)?,
+            &MenuItem::with_id(app, "check-updates", "Check for Updates…", true, None::<&str>)?,
             &PredefinedMenuItem::separator(app)?,
-            &MenuItem::with_id(app, "preferences", "Settings...", true, get_accel("preferences", "CmdOrCtrl+,"))?,
+            &MenuItem::with_id(app, "preferences", "Settings…", true, get_accel("preferences", "CmdOrCtrl+,"))?,
             &PredefinedMenuItem::separator(app)?,
             &PredefinedMenuItem::services(app, Some("Services"))?,
             &PredefinedMenuItem::separator(app)?,
@@ -741,6 +1196,22 @@ fn create_menu_with_shortcuts(
     // ========================================================================
     // File menu
     // ========================================================================
+    let templates_submenu = Submenu::with_id_and_items(
+        app,
+        TEMPLATES_SUBMENU_ID,
+        "New from Template",
+        true,
+        &[&MenuItem::with_id(app, "no-templates", "No Templates", false, None::<&str>)?],
+    )?;
+
+    let favorites_submenu = Submenu::with_id_and_items(
+        app,
+        FAVORITES_SUBMENU_ID,
+        "Favorites",
+        true,
+        &[&MenuItem::with_id(app, "no-favorites", "No Favorites", false, None::<&str>)?],
+    )?;
+
     let recent_submenu = Submenu::with_id_and_items(
         app,
         RECENT_FILES_SUBMENU_ID,
@@ -765,13 +1236,18 @@ fn create_menu_with_shortcuts(
         ],
     )?;
 
-    let export_submenu = Submenu::with_items(
+    let export_submenu = Submenu::with_id_and_items(
         app,
+        EXPORT_SUBMENU_ID,
         "Export",
         true,
         &[
-            &MenuItem::with_id(app, "export-html", "HTML...", true, get_accel("export-html", "Alt+CmdOrCtrl+E"))?,
-            &MenuItem::with_id(app, "export-pdf", "Print...", true, get_accel("export-pdf", "CmdOrCtrl+P"))?,
+            &MenuItem::with_id(app, "save-pdf", "PDF…", true, None::<&str>)?,
+            &MenuItem::with_id(app, "export-html", "HTML…", true, get_accel("export-html", "Alt+CmdOrCtrl+E"))?,
+            &MenuItem::with_id(app, "export-docx", "Word (DOCX)…", true, None::<&str>)?,
+            &MenuItem::with_id(app, "export-epub", "EPUB…", true, None::<&str>)?,
+            &PredefinedMenuItem::separator(app)?,
+            &MenuItem::with_id(app, "export-pdf", "Print…", true, get_accel("export-pdf", "CmdOrCtrl+P"))?,
             &PredefinedMenuItem::separator(app)?,
             &MenuItem::with_id(app, "copy-html", "Copy as HTML", true, get_accel("copy-html", "CmdOrCtrl+Shift+C"))?,
         ],
@@ -782,9 +1258,9 @@ fn create_menu_with_shortcuts(
         "Document History",
         true,
         &[
-            &MenuItem::with_id(app, "view-history", "View History...", true, get_accel("view-history", "CmdOrCtrl+Shift+H"))?,
+            &MenuItem::with_id(app, "view-history", "View History…", true, get_accel("view-history", "CmdOrCtrl+Shift+H"))?,
             &PredefinedMenuItem::separator(app)?,
-            &MenuItem::with_id(app, "clear-history", "Clear History...", true, None::<&str>)?,
+            &MenuItem::with_id(app, "clear-history", "Clear History…", true, None::<&str>)?,
         ],
     )?;
 
@@ -796,18 +1272,26 @@ fn create_menu_with_shortcuts(
         &[
             &MenuItem::with_id(app, "new", "New", true, get_accel("new", "CmdOrCtrl+N"))?,
             &MenuItem::with_id(app, "new-window", "New Window", true, get_accel("new-window", "CmdOrCtrl+Shift+N"))?,
+            &templates_submenu,
             &PredefinedMenuItem::separator(app)?,
-            &MenuItem::with_id(app, "open", "Open...", true, get_accel("open", "CmdOrCtrl+O"))?,
-            &MenuItem::with_id(app, "open-folder", "Open Folder...", true, get_accel("open-folder", "CmdOrCtrl+Shift+O"))?,
+            &MenuItem::with_id(app, "open", "Open…", true, get_accel("open", "CmdOrCtrl+O"))?,
+            &MenuItem::with_id(app, "open-folder", "Open Folder…", true, get_accel("open-folder", "CmdOrCtrl+Shift+O"))?,
+            &MenuItem::with_id(app, "insert-file-contents", "Insert File Contents…", true, get_accel("insert-file-contents", ""))?,
+            &MenuItem::with_id(app, "insert-table-from-csv", "Insert Table from CSV…", true, get_accel("insert-table-from-csv", ""))?,
             &recent_submenu,
             &recent_workspaces_submenu,
+            &favorites_submenu,
             &PredefinedMenuItem::separator(app)?,
             &MenuItem::with_id(app, "close", "Close", true, get_accel("close", "CmdOrCtrl+W"))?,
             &MenuItem::with_id(app, "close-workspace", "Close Workspace", true, None::<&str>)?,
             &PredefinedMenuItem::separator(app)?,
             &MenuItem::with_id(app, "save", "Save", true, get_accel("save", "CmdOrCtrl+S"))?,
-            &MenuItem::with_id(app, "save-as", "Save As...", true, get_accel("save-as", "CmdOrCtrl+Shift+S"))?,
-            &MenuItem::with_id(app, "move-to", "Move to...", true, get_accel("move-to", ""))?,
+            &MenuItem::with_id(app, "save-as", "Save As…", true, get_accel("save-as", "CmdOrCtrl+Shift+S"))?,
+            &MenuItem::with_id(app, "move-to", "Move to…", true, get_accel("move-to", ""))?,
+            &MenuItem::with_id(app, "toggle-pin-file", "Pin to Workspace", true, get_accel("toggle-pin-file", ""))?,
+            &MenuItem::with_id(app, "reveal-in-file-manager", crate::reveal::REVEAL_LABEL, true, get_accel("reveal-in-file-manager", ""))?,
+            &MenuItem::with_id(app, "open-in-terminal", "Open in Terminal", true, get_accel("open-in-terminal", ""))?,
+            &MenuItem::with_id(app, "share", "Share…", true, get_accel("share", ""))?,
             &PredefinedMenuItem::separator(app)?,
             &export_submenu,
             &PredefinedMenuItem::separator(app)?,
@@ -823,25 +1307,30 @@ fn create_menu_with_shortcuts(
         &[
             &MenuItem::with_id(app, "new", "New", true, get_accel("new", "CmdOrCtrl+N"))?,
             &MenuItem::with_id(app, "new-window", "New Window", true, get_accel("new-window", "CmdOrCtrl+Shift+N"))?,
+            &templates_submenu,
             &PredefinedMenuItem::separator(app)?,
-            &MenuItem::with_id(app, "open", "Open...", true, get_accel("open", "CmdOrCtrl+O"))?,
-            &MenuItem::with_id(app, "open-folder", "Open Folder...", true, get_accel("open-folder", "CmdOrCtrl+Shift+O"))?,
+            &MenuItem::with_id(app, "open", "Open…", true, get_accel("open", "CmdOrCtrl+O"))?,
+            &MenuItem::with_id(app, "open-folder", "Open Folder…", true, get_accel("open-folder", "CmdOrCtrl+Shift+O"))?,
+            &MenuItem::with_id(app, "insert-file-contents", "Insert File Contents…", true, get_accel("insert-file-contents", ""))?,
+            &MenuItem::with_id(app, "insert-table-from-csv", "Insert Table from CSV…", true, get_accel("insert-table-from-csv", ""))?,
             &recent_submenu,
             &recent_workspaces_submenu,
+            &favorites_submenu,
             &PredefinedMenuItem::separator(app)?,
             &MenuItem::with_id(app, "close", "Close", true, get_accel("close", "CmdOrCtrl+W"))?,
             &MenuItem::with_id(app, "close-workspace", "Close Workspace", true, None::<&str>)?,
             &PredefinedMenuItem::separator(app)?,
             &MenuItem::with_id(app, "save", "Save", true, get_accel("save", "CmdOrCtrl+S"))?,
-            &MenuItem::with_id(app, "save-as", "Save As...", true, get_accel("save-as", "CmdOrCtrl+Shift+S"))?,
-            &MenuItem::with_id(app, "move-to", "Move to...", true, get_accel("move-to", ""))?,
+            &MenuItem::with_id(app, "save-as", "Save As…", true, get_accel("save-as", "CmdOrCtrl+Shift+S"))?,
+            &MenuItem::with_id(app, "move-to", "Move to…", true, get_accel("move-to", ""))?,
+            &MenuItem::with_id(app, "toggle-pin-file", "Pin to Workspace", true, get_accel("toggle-pin-file", ""))?,
+            &MenuItem::with_id(app, "reveal-in-file-manager", crate::reveal::REVEAL_LABEL, true, get_accel("reveal-in-file-manager", ""))?,
+            &MenuItem::with_id(app, "open-in-terminal", "Open in Terminal", true, get_accel("open-in-terminal", ""))?,
             &PredefinedMenuItem::separator(app)?,
             &export_submenu,
             &PredefinedMenuItem::separator(app)?,
             &history_submenu,
             &PredefinedMenuItem::separator(app)?,
-            &MenuItem::with_id(app, "preferences", "Settings...", true, get_accel("preferences", "CmdOrCtrl+,"))?,
-            &PredefinedMenuItem::separator(app)?,
             &MenuItem::with_id(app, "save-all-quit", "Save All and Exit", true, get_accel("save-all-quit", "Alt+Shift+CmdOrCtrl+Q"))?,
             &MenuItem::with_id(app, "quit", "Exit", true, get_accel("quit", "CmdOrCtrl+Q"))?,
         ],
@@ -855,7 +1344,7 @@ fn create_menu_with_shortcuts(
         "Find",
         true,
         &[
-            &MenuItem::with_id(app, "find-replace", "Find and Replace...", true, get_accel("find-replace", "CmdOrCtrl+F"))?,
+            &MenuItem::with_id(app, "find-replace", "Find and Replace…", true, get_accel("find-replace", "CmdOrCtrl+F"))?,
             &MenuItem::with_id(app, "find-next", "Find Next", true, get_accel("find-next", "CmdOrCtrl+G"))?,
             &MenuItem::with_id(app, "find-prev", "Find Previous", true, get_accel("find-prev", "CmdOrCtrl+Shift+G"))?,
             &PredefinedMenuItem::separator(app)?,
@@ -894,6 +1383,14 @@ fn create_menu_with_shortcuts(
         ],
     )?;
 
+    let spelling_submenu = Submenu::with_id_and_items(
+        app,
+        SPELLING_SUBMENU_ID,
+        "Spelling",
+        true,
+        &[&MenuItem::with_id(app, "no-dictionaries", "No Dictionaries", false, None::<&str>)?],
+    )?;
+
     let line_endings_submenu = Submenu::with_items(
         app,
         "Line Endings",
@@ -910,7 +1407,7 @@ fn create_menu_with_shortcuts(
         true,
         &[
             &MenuItem::with_id(app, "undo", "Undo", true, get_accel("undo", "CmdOrCtrl+Z"))?,
-            &MenuItem::with_id(app, "redo", "Redo", true, get_accel("redo", "CmdOrCtrl+Shift+Z"))?,
+            &MenuItem::with_id(app, "redo", "Redo", true, get_accel("redo", REDO_ACCEL))?,
             &PredefinedMenuItem::separator(app)?,
             &PredefinedMenuItem::cut(app, Some("Cut"))?,
             &PredefinedMenuItem::copy(app, Some("Copy"))?,
@@ -921,6 +1418,8 @@ fn create_menu_with_shortcuts(
             &selection_submenu,
             &lines_submenu,
             &line_endings_submenu,
+            &PredefinedMenuItem::separator(app)?,
+            &spelling_submenu,
         ],
     )?;
 
@@ -1002,7 +1501,7 @@ fn create_menu_with_shortcuts(
             &MenuItem::with_id(app, "remove-trailing-spaces", "Remove Trailing Spaces", true, None::<&str>)?,
             &MenuItem::with_id(app, "collapse-blank-lines", "Collapse Blank Lines", true, None::<&str>)?,
             &PredefinedMenuItem::separator(app)?,
-            &MenuItem::with_id(app, "cleanup-images", "Clean Up Unused Images...", true, None::<&str>)?,
+            &MenuItem::with_id(app, "cleanup-images", "Clean Up Unused Images…", true, None::<&str>)?,
         ],
     )?;
 
@@ -1029,6 +1528,7 @@ fn create_menu_with_shortcuts(
             &transform_submenu,
             &cjk_submenu,
             &cleanup_submenu,
+            &MenuItem::with_id(app, "format-markdown", "Format Markdown", true, get_accel("format-markdown", ""))?,
             &PredefinedMenuItem::separator(app)?,
             &MenuItem::with_id(app, "clear-format", "Clear Format", true, get_accel("clear-format", "CmdOrCtrl+\\"))?,
         ],
@@ -1095,7 +1595,7 @@ fn create_menu_with_shortcuts(
         true,
         &[
             &links_submenu,
-            &MenuItem::with_id(app, "image", "Image...", true, get_accel("image", "Shift+CmdOrCtrl+I"))?,
+            &MenuItem::with_id(app, "image", "Image…", true, get_accel("image", "Shift+CmdOrCtrl+I"))?,
             &PredefinedMenuItem::separator(app)?,
             &table_submenu,
             &MenuItem::with_id(app, "code-fences", "Code Block", true, get_accel("code-fences", "Alt+CmdOrCtrl+C"))?,
@@ -1117,50 +1617,86 @@ fn create_menu_with_shortcuts(
         "View",
         true,
         &[
-            &MenuItem::with_id(app, "source-mode", "Source Code Mode", true, get_accel("source-mode", "F6"))?,
+            &CheckMenuItem::with_id(app, "source-mode", "Source Code Mode", true, false, get_accel("source-mode", "F6"))?,
             &PredefinedMenuItem::separator(app)?,
-            &MenuItem::with_id(app, "focus-mode", "Focus Mode", true, get_accel("focus-mode", "F8"))?,
-            &MenuItem::with_id(app, "typewriter-mode", "Typewriter Mode", true, get_accel("typewriter-mode", "F9"))?,
+            &CheckMenuItem::with_id(app, "focus-mode", "Focus Mode", true, false, get_accel("focus-mode", "F8"))?,
+            &CheckMenuItem::with_id(app, "typewriter-mode", "Typewriter Mode", true, false, get_accel("typewriter-mode", "F9"))?,
             &PredefinedMenuItem::separator(app)?,
             &MenuItem::with_id(app, "zoom-actual", "Actual Size", true, get_accel("zoom-actual", "CmdOrCtrl+0"))?,
             &MenuItem::with_id(app, "zoom-in", "Zoom In", true, get_accel("zoom-in", "CmdOrCtrl+="))?,
             &MenuItem::with_id(app, "zoom-out", "Zoom Out", true, get_accel("zoom-out", "CmdOrCtrl+-"))?,
+            &MenuItem::with_id(app, "page-zoom-actual", "Actual Page Size", true, get_accel("page-zoom-actual", "Alt+CmdOrCtrl+0"))?,
+            &MenuItem::with_id(app, "page-zoom-in", "Zoom Page In", true, get_accel("page-zoom-in", "Shift+CmdOrCtrl+="))?,
+            &MenuItem::with_id(app, "page-zoom-out", "Zoom Page Out", true, get_accel("page-zoom-out", "Shift+CmdOrCtrl+-"))?,
             &PredefinedMenuItem::separator(app)?,
-            &MenuItem::with_id(app, "word-wrap", "Toggle Word Wrap", true, get_accel("word-wrap", "Alt+Z"))?,
+            &CheckMenuItem::with_id(app, "word-wrap", "Word Wrap", true, false, get_accel("word-wrap", "Alt+Z"))?,
             &MenuItem::with_id(app, "line-numbers", "Toggle Line Numbers", true, get_accel("line-numbers", "Alt+CmdOrCtrl+L"))?,
             &MenuItem::with_id(app, "diagram-preview", "Toggle Diagram Preview", true, get_accel("diagram-preview", "Alt+CmdOrCtrl+P"))?,
             &PredefinedMenuItem::separator(app)?,
-            &MenuItem::with_id(app, "sidebar", "Toggle Sidebar", true, get_accel("sidebar", "CmdOrCtrl+Shift+B"))?,
-            &MenuItem::with_id(app, "outline", "Toggle Outline", true, get_accel("outline", "Alt+CmdOrCtrl+1"))?,
+            &CheckMenuItem::with_id(app, "sidebar", "Sidebar", true, false, get_accel("sidebar", "CmdOrCtrl+Shift+B"))?,
+            &CheckMenuItem::with_id(app, "outline", "Outline", true, false, get_accel("outline", "Alt+CmdOrCtrl+1"))?,
             &PredefinedMenuItem::separator(app)?,
-            &PredefinedMenuItem::fullscreen(app, Some("Enter Full Screen"))?,
+            &CheckMenuItem::with_id(app, "always-on-top", "Float on Top", true, false, get_accel("always-on-top", ""))?,
+            &MenuItem::with_id(app, "toggle-fullscreen", "Toggle Full Screen", true, get_accel("toggle-fullscreen", FULLSCREEN_ACCEL))?,
         ],
     )?;
 
+    // ========================================================================
+    // Tools menu (Windows/Linux; settings live in the app menu on macOS)
+    // ========================================================================
+    #[cfg(not(target_os = "macos"))]
+    let tools_menu = Submenu::with_items(
+        app,
+        "Tools",
+        true,
+        &[&MenuItem::with_id(app, "preferences", "Options…", true, get_accel("preferences", "CmdOrCtrl+,"))?],
+    )?;
+
     // ========================================================================
     // Window menu
     // ========================================================================
+    let arrange_submenu = Submenu::with_items(
+        app,
+        "Arrange",
+        true,
+        &[
+            &MenuItem::with_id(app, "snap-left-half", "Left Half", true, get_accel("snap-left-half", "Ctrl+Alt+Left"))?,
+            &MenuItem::with_id(app, "snap-right-half", "Right Half", true, get_accel("snap-right-half", "Ctrl+Alt+Right"))?,
+            &MenuItem::with_id(app, "snap-top", "Top Half", true, get_accel("snap-top", "Ctrl+Alt+Up"))?,
+            &MenuItem::with_id(app, "snap-bottom", "Bottom Half", true, get_accel("snap-bottom", "Ctrl+Alt+Down"))?,
+            &PredefinedMenuItem::separator(app)?,
+            &MenuItem::with_id(app, "snap-top-left", "Top Left", true, get_accel("snap-top-left", "Ctrl+Alt+Home"))?,
+            &MenuItem::with_id(app, "snap-top-right", "Top Right", true, get_accel("snap-top-right", "Ctrl+Alt+PageUp"))?,
+            &MenuItem::with_id(app, "snap-bottom-left", "Bottom Left", true, get_accel("snap-bottom-left", "Ctrl+Alt+End"))?,
+            &MenuItem::with_id(app, "snap-bottom-right", "Bottom Right", true, get_accel("snap-bottom-right", "Ctrl+Alt+PageDown"))?,
+        ],
+    )?;
+
     #[cfg(target_os = "macos")]
-    let window_menu = Submenu::with_items(
+    let window_menu = Submenu::with_id_and_items(
         app,
+        WINDOW_MENU_ID,
         "Window",
         true,
         &[
             &PredefinedMenuItem::minimize(app, Some("Minimize"))?,
             &PredefinedMenuItem::maximize(app, Some("Zoom"))?,
+            &arrange_submenu,
             &PredefinedMenuItem::separator(app)?,
             &MenuItem::with_id(app, "bring-all-to-front", "Bring All to Front", true, None::<&str>)?,
         ],
     )?;
 
     #[cfg(not(target_os = "macos"))]
-    let window_menu = Submenu::with_items(
+    let window_menu = Submenu::with_id_and_items(
         app,
+        WINDOW_MENU_ID,
         "Window",
         true,
         &[
             &PredefinedMenuItem::minimize(app, Some("Minimize"))?,
             &PredefinedMenuItem::maximize(app, Some("Maximize"))?,
+            &arrange_submenu,
         ],
     )?;
 
@@ -1168,34 +1704,40 @@ fn create_menu_with_shortcuts(
     // Help menu
     // ========================================================================
     #[cfg(target_os = "macos")]
-    let help_menu = Submenu::with_items(
+    let help_menu = Submenu::with_id_and_items(
         app,
+        HELP_MENU_ID,
         "Help",
         true,
         &[
             &MenuItem::with_id(app, "vmark-help", "VMark Help", true, None::<&str>)?,
             &MenuItem::with_id(app, "keyboard-shortcuts", "Keyboard Shortcuts", true, None::<&str>)?,
             &PredefinedMenuItem::separator(app)?,
-            &MenuItem::with_id(app, "report-issue", "Report an Issue...", true, None::<&str>)?,
+            &MenuItem::with_id(app, "report-issue", "Report an Issue…", true, None::<&str>)?,
+            &MenuItem::with_id(app, "open-logs-folder", "Open Logs Folder", true, None::<&str>)?,
         ],
     )?;
 
     #[cfg(not(target_os = "macos"))]
-    let help_menu = Submenu::with_items(
+    let help_menu = Submenu::with_id_and_items(
         app,
+        HELP_MENU_ID,
         "Help",
         true,
         &[
             &MenuItem::with_id(app, "vmark-help", "VMark Help", true, None::<&str>)?,
             &MenuItem::with_id(app, "keyboard-shortcuts", "Keyboard Shortcuts", true, None::<&str>)?,
             &PredefinedMenuItem::separator(app)?,
-            &MenuItem::with_id(app, "report-issue", "Report an Issue...", true, None::<&str>)?,
+            &MenuItem::with_id(app, "report-issue", "Report an Issue…", true, None::<&str>)?,
+            &MenuItem::with_id(app, "open-logs-folder", "Open Logs Folder", true, None::<&str>)?,
             &PredefinedMenuItem::separator(app)?,
-            &MenuItem::with_id(app, "check-updates", "Check for Updates...", true, None::<&str>)?,
+            &MenuItem::with_id(app, "check-updates", "Check for Updates…", true, None::<&str>)?,
             &PredefinedMenuItem::about(app, Some("About VMark"), None)?,
         ],
     )?;
 
+    crate::keybindings::record_diagnostics(&applied.borrow());
+
     // ========================================================================
     // Assemble the menu bar
     // ========================================================================
@@ -1223,6 +1765,7 @@ fn create_menu_with_shortcuts(
             &format_menu,
             &insert_menu,
             &view_menu,
+            &tools_menu,
             &window_menu,
             &help_menu,
         ],
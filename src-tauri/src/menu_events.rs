@@ -202,6 +202,22 @@ fn make_recent_workspace_event(path: &str) -> PendingMenuEvent {
     }
 }
 
+/// Create a PendingMenuEvent for a new-from-template event (payload includes template path)
+fn make_template_event(path: &str) -> PendingMenuEvent {
+    PendingMenuEvent {
+        event_name: "menu:new-from-template".to_string(),
+        recent_file_path: Some(path.to_string()),
+    }
+}
+
+/// Create a PendingMenuEvent for a favorite (pinned file) event (payload includes file path)
+fn make_favorite_event(path: &str) -> PendingMenuEvent {
+    PendingMenuEvent {
+        event_name: "menu:open-favorite-file".to_string(),
+        recent_file_path: Some(path.to_string()),
+    }
+}
+
 /// Create a new document window and queue an event to it.
 /// The event will be emitted when the window becomes ready.
 fn create_window_and_queue(app: &AppHandle, event: PendingMenuEvent) {
@@ -215,27 +231,108 @@ fn create_window_and_queue(app: &AppHandle, event: PendingMenuEvent) {
     }
 }
 
+/// Open the app log directory, creating it first so there is something to show.
+fn open_logs_folder(app: &AppHandle) -> Result<(), String> {
+    let dir = app
+        .path()
+        .app_log_dir()
+        .map_err(|e| format!("Failed to resolve log directory: {e}"))?;
+    std::fs::create_dir_all(&dir).map_err(|e| format!("Failed to create log directory: {e}"))?;
+    tauri_plugin_opener::open_path(&dir, None::<&str>)
+        .map_err(|e| format!("Failed to open log directory: {e}"))
+}
+
+/// Ask for a Markdown or text file and send its content to `window` for
+/// insertion at the cursor as `menu:insert-file-contents` (content, path, label).
+/// The file may use any encoding `text_encoding` detects; a file that can't be
+/// read is reported in a dialog.
+fn insert_file_contents(window: tauri::WebviewWindow) {
+    use tauri_plugin_dialog::{DialogExt, MessageDialogKind};
+
+    let app = window.app_handle().clone();
+    let mut dialog = app
+        .dialog()
+        .file()
+        .set_parent(&window)
+        .set_title(crate::menu_i18n::tr("Insert File Contents"))
+        .add_filter("Markdown", crate::file_tree::MARKDOWN_EXTENSIONS)
+        .add_filter("Text", &["txt", "text"]);
+    if let Some(dir) = crate::window_registry::file_path_of(window.label()).and_then(|path| {
+        std::path::Path::new(&path)
+            .parent()
+            .map(|p| p.to_path_buf())
+    }) {
+        dialog = dialog.set_directory(dir);
+    }
+
+    dialog.pick_file(move |file| {
+        let Some(path) = file.and_then(|f| f.into_path().ok()) else {
+            return;
+        };
+        match std::fs::read(&path) {
+            Ok(bytes) => {
+                let content = crate::text_encoding::decode(&bytes).content;
+                let path = path.to_string_lossy().to_string();
+                let _ = window.emit("menu:insert-file-contents", (content, path, window.label()));
+            }
+            Err(e) => {
+                window
+                    .app_handle()
+                    .dialog()
+                    .message(format!("{}\n\n{e}", path.display()))
+                    .title(crate::menu_i18n::tr("Could Not Insert File"))
+                    .kind(MessageDialogKind::Error)
+                    .parent(&window)
+                    .show(|_| {});
+            }
+        }
+    });
+}
+
+/// Confirm with a native dialog, then send `menu:clear-recent` to `window`
+/// (the frontend owns the recent files list).
+fn confirm_clear_recent(window: tauri::WebviewWindow) {
+    use tauri_plugin_dialog::{DialogExt, MessageDialogButtons, MessageDialogKind};
+
+    let tr = crate::menu_i18n::tr;
+    window
+        .app_handle()
+        .dialog()
+        .message(tr("The list of recently opened files will be cleared."))
+        .title(tr("Clear Recent Files?"))
+        .kind(MessageDialogKind::Warning)
+        .buttons(MessageDialogButtons::OkCancelCustom(
+            tr("Clear"),
+            tr("Cancel"),
+        ))
+        .parent(&window)
+        .show(move |confirmed| {
+            if confirmed {
+                let _ = window.emit("menu:clear-recent", window.label());
+            }
+        });
+}
+
 pub fn handle_menu_event(app: &AppHandle, event: tauri::menu::MenuEvent) {
     let id = event.id().as_ref();
 
+    // Context menu clicks go back to the window that opened the menu
+    if let Some(item_id) = id.strip_prefix(crate::context_menu::CONTEXT_ITEM_PREFIX) {
+        crate::context_menu::handle_click(app, item_id);
+        return;
+    }
+
     // Custom Quit (Cmd+Q) is handled in Rust so we can coordinate unsaved-changes prompts.
     if id == "quit" {
         quit::start_quit(app);
         return;
     }
 
-    // Save All and Quit (Alt+Shift+Cmd+Q) - emit to document window to handle save-all logic
+    // Save All and Quit (Alt+Shift+Cmd+Q) - a document window saves everything, then the coordinated quit runs
     // Uses get_focused_document_window to skip Settings window
     if id == "save-all-quit" {
-        let event = make_menu_event("menu:save-all-quit");
-        if let Some(focused) = get_focused_document_window(app) {
-            emit_or_queue_atomic(&focused, event);
-        } else if let Some(window) = get_any_document_window(app) {
-            emit_or_queue_atomic(&window, event);
-        } else {
-            // No document windows - nothing to save, just quit
-            crate::window_manager::force_quit(app.clone());
-        }
+        let window = get_focused_document_window(app).or_else(|| get_any_document_window(app));
+        quit::start_save_all_quit(app, window);
         return;
     }
 
@@ -281,6 +378,56 @@ pub fn handle_menu_event(app: &AppHandle, event: tauri::menu::MenuEvent) {
         }
     }
 
+    // Handle Favorites clicks - same three cases as recent files
+    if let Some(index_str) = id.strip_prefix(crate::menu::FAVORITE_ITEM_PREFIX) {
+        if let Ok(index) = index_str.parse::<usize>() {
+            if let Some(path) = crate::menu::get_favorite_path(index) {
+                let event = make_favorite_event(&path);
+                if let Some(focused) = get_focused_window(app) {
+                    emit_event(&focused, &event);
+                } else if !has_document_windows(app) {
+                    create_window_and_queue(app, event);
+                } else if let Some(window) = get_any_document_window(app) {
+                    emit_or_queue_atomic(&window, event);
+                }
+            }
+            return;
+        }
+    }
+
+    // Handle New from Template clicks - similar to recent files
+    if let Some(index_str) = id.strip_prefix(crate::menu::TEMPLATE_ITEM_PREFIX) {
+        if let Ok(index) = index_str.parse::<usize>() {
+            if let Some(path) = crate::menu::get_template_path(index) {
+                let event = make_template_event(&path);
+                if let Some(focused) = get_focused_document_window(app) {
+                    emit_event(&focused, &event);
+                } else if !has_document_windows(app) {
+                    create_window_and_queue(app, event);
+                } else if let Some(window) = get_any_document_window(app) {
+                    emit_or_queue_atomic(&window, event);
+                }
+            }
+            return;
+        }
+    }
+
+    // "plugin:<plugin>:<item>" is emitted under its own id for the plugin to handle
+    if id.starts_with(crate::menu::PLUGIN_ITEM_PREFIX) {
+        if let Some(focused) = get_focused_window(app) {
+            let _ = focused.emit(id, focused.label());
+        }
+        return;
+    }
+
+    // "spelling-lang-<code>" switches the focused window's spell-check language
+    if let Some(code) = id.strip_prefix(crate::menu::SPELLING_ITEM_PREFIX) {
+        if let Some(focused) = get_focused_document_window(app) {
+            let _ = focused.emit("menu:spelling-language", (code, focused.label()));
+        }
+        return;
+    }
+
     // Handle clear-recent-workspaces
     if id == "clear-recent-workspaces" {
         if let Some(focused) = get_focused_window(app) {
@@ -295,6 +442,130 @@ pub fn handle_menu_event(app: &AppHandle, event: tauri::menu::MenuEvent) {
         return;
     }
 
+    // "always-on-top" toggles the focused document window directly in Rust
+    if id == "always-on-top" {
+        if let Some(focused) = get_focused_document_window(app) {
+            let on = !focused.is_always_on_top().unwrap_or(false);
+            crate::window_manager::apply_always_on_top(&focused, on);
+        }
+        return;
+    }
+
+    // "reveal-in-file-manager" shows the focused document on disk
+    if id == "reveal-in-file-manager" {
+        let path = get_focused_document_window(app)
+            .and_then(|focused| crate::window_registry::file_path_of(focused.label()));
+        if let Some(path) = path {
+            if let Err(_e) = crate::reveal::reveal(std::path::Path::new(&path)) {
+                #[cfg(debug_assertions)]
+                eprintln!("[menu_events] {}", _e);
+            }
+        }
+        return;
+    }
+
+    // "open-in-terminal" opens a terminal at the focused window's workspace root,
+    // else at the folder of its document
+    if id == "open-in-terminal" {
+        let path = get_focused_document_window(app).and_then(|focused| {
+            crate::window_registry::workspace_root_of(focused.label())
+                .or_else(|| crate::window_registry::file_path_of(focused.label()))
+        });
+        if let Some(path) = path {
+            if let Err(_e) = crate::terminal::open_in_terminal(path, None) {
+                #[cfg(debug_assertions)]
+                eprintln!("[menu_events] {}", _e);
+            }
+        }
+        return;
+    }
+
+    // "share" shows the share sheet for the focused document
+    if id == "share" {
+        if let Some(focused) = get_focused_document_window(app) {
+            let label = focused.label().to_string();
+            if let Err(_e) = crate::share::share_document(app.clone(), label) {
+                #[cfg(debug_assertions)]
+                eprintln!("[menu_events] {}", _e);
+            }
+        }
+        return;
+    }
+
+    // Page zoom items scale the focused document window's webview directly in Rust
+    // (zoom-in/zoom-out/zoom-actual change the editor font size in the frontend)
+    if matches!(id, "page-zoom-in" | "page-zoom-out" | "page-zoom-actual") {
+        if let Some(focused) = get_focused_document_window(app) {
+            let current = crate::session::zoom_of(focused.label()).unwrap_or(1.0);
+            let factor = match id {
+                "page-zoom-in" => crate::window_settings::step_zoom(current, true),
+                "page-zoom-out" => crate::window_settings::step_zoom(current, false),
+                _ => 1.0,
+            };
+            if let Err(_e) = crate::window_manager::apply_zoom(&focused, factor) {
+                #[cfg(debug_assertions)]
+                eprintln!("[menu_events] {}", _e);
+            }
+        }
+        return;
+    }
+
+    // "toggle-fullscreen" enters native fullscreen for the focused document window
+    if id == "toggle-fullscreen" {
+        if let Some(focused) = get_focused_document_window(app) {
+            if let Err(_e) = crate::window_manager::apply_fullscreen(&focused, false) {
+                #[cfg(debug_assertions)]
+                eprintln!("[menu_events] {}", _e);
+            }
+        }
+        return;
+    }
+
+    // "window-item-<label>" brings the chosen document window to the front
+    if let Some(label) = id.strip_prefix(crate::menu::WINDOW_ITEM_PREFIX) {
+        if let Some(window) = app.get_webview_window(label) {
+            let _ = window.unminimize();
+            let _ = window.show();
+            let _ = window.set_focus();
+        }
+        if let Err(_e) = crate::menu::check_window_item(app, label) {
+            #[cfg(debug_assertions)]
+            eprintln!("[menu_events] {}", _e);
+        }
+        return;
+    }
+
+    // "snap-*" arranges the focused document window on its monitor
+    if let Some(position) = id
+        .strip_prefix("snap-")
+        .and_then(crate::window_geometry::SnapPosition::from_menu_id)
+    {
+        if let Some(focused) = get_focused_document_window(app) {
+            if let Err(_e) = crate::window_manager::apply_snap(&focused, position) {
+                #[cfg(debug_assertions)]
+                eprintln!("[menu_events] {}", _e);
+            }
+        }
+        return;
+    }
+
+    // "insert-file-contents" picks a file and inserts it into the focused document
+    if id == "insert-file-contents" {
+        if let Some(focused) = get_focused_document_window(app) {
+            insert_file_contents(focused);
+        }
+        return;
+    }
+
+    // "open-logs-folder" opens the app log directory in the file manager
+    if id == "open-logs-folder" {
+        if let Err(_e) = open_logs_folder(app) {
+            #[cfg(debug_assertions)]
+            eprintln!("[menu_events] {}", _e);
+        }
+        return;
+    }
+
     // "preferences" - always handle in Rust to ensure it works in all scenarios:
     // - Settings already open and focused
     // - Settings open but in background
@@ -333,12 +604,15 @@ pub fn handle_menu_event(app: &AppHandle, event: tauri::menu::MenuEvent) {
         return;
     }
 
-    // File operations that should work without windows
+    // Actions that should work without windows (file operations, Help menu)
     // Three cases to handle:
     // 1. Focused window exists → emit directly
     // 2. No windows at all → create window, queue event for when ready
     // 3. Windows exist but none focused → queue event for when ready
-    if matches!(id, "open" | "open-folder") {
+    if matches!(
+        id,
+        "open" | "open-folder" | "keyboard-shortcuts" | "report-issue" | "check-updates" | "vmark-help"
+    ) {
         let event = make_menu_event(&format!("menu:{id}"));
         if let Some(focused) = get_focused_window(app) {
             // Case 1: Focused window - emit directly (window is ready)
@@ -354,8 +628,13 @@ pub fn handle_menu_event(app: &AppHandle, event: tauri::menu::MenuEvent) {
         return;
     }
 
-    // "clear-recent" can be handled without a window if needed
-    // But for consistency, we still emit to a window (frontend handles storage)
+    // "clear-recent" asks for confirmation before the frontend clears its list
+    if id == "clear-recent" {
+        if let Some(focused) = get_focused_window(app) {
+            confirm_clear_recent(focused);
+        }
+        return;
+    }
 
     // All other menu events are emitted only to the focused window
     // Note: window.emit() broadcasts to all windows, so include target label in payload
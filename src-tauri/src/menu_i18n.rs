@@ -0,0 +1,1120 @@
+//! Localized labels for the native menu.
+//!
+//! Menus are built with English labels; `localize_menu` then swaps each label
+//! for its translation in the selected language, falling back to English for
+//! labels a table doesn't cover. The language is set with `set_menu_language`
+//! and persisted to `~/.vmark/menu-language.json` so the menu built at the
+//! next startup is localized before any webview exists.
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{LazyLock, Mutex};
+use tauri::menu::{Menu, MenuItemKind};
+use tauri::AppHandle;
+
+use crate::menu;
+
+type Table = &'static [(&'static str, &'static str)];
+
+const ZH_CN: Table = &[
+    // Menus
+    ("File", "文件"),
+    ("Edit", "编辑"),
+    ("Format", "格式"),
+    ("Insert", "插入"),
+    ("View", "视图"),
+    ("Plugins", "插件"),
+    ("Window", "窗口"),
+    ("Tools", "工具"),
+    ("Help", "帮助"),
+    // App menu
+    ("About VMark", "关于 VMark"),
+    ("Check for Updates…", "检查更新…"),
+    ("Settings…", "设置…"),
+    ("Options…", "选项…"),
+    ("Services", "服务"),
+    ("Hide VMark", "隐藏 VMark"),
+    ("Hide Others", "隐藏其他"),
+    ("Show All", "全部显示"),
+    ("Save All and Quit", "全部保存并退出"),
+    ("Save All and Exit", "全部保存并退出"),
+    ("Quit VMark", "退出 VMark"),
+    ("Exit", "退出"),
+    // File
+    ("New", "新建"),
+    ("New Window", "新建窗口"),
+    ("New from Template", "从模板新建"),
+    ("No Templates", "无模板"),
+    ("Open…", "打开…"),
+    ("Open Folder…", "打开文件夹…"),
+    ("Insert File Contents…", "插入文件内容…"),
+    ("Insert File Contents", "插入文件内容"),
+    ("Open Recent", "最近打开"),
+    ("Open Recent Workspace", "最近的工作区"),
+    ("No Recent Files", "无最近文件"),
+    ("No Recent Workspaces", "无最近工作区"),
+    ("Clear Recent Files", "清除最近文件"),
+    ("Clear Recent Files?", "清除最近打开的文件？"),
+    ("The list of recently opened files will be cleared.", "最近打开的文件列表将被清除。"),
+    ("Clear", "清除"),
+    ("Could Not Insert File", "无法插入文件"),
+    ("Cancel", "取消"),
+    ("Windows Not Responding", "窗口未响应"),
+    ("Some windows did not respond to the quit request. Unsaved changes in them will be lost if you force quit.", "部分窗口未响应退出请求。如果强制退出，其中未保存的更改将会丢失。"),
+    ("Force Quit", "强制退出"),
+    ("Keep Waiting", "继续等待"),
+    ("Open Files", "打开文件"),
+    ("Open {count} files as tabs in this window, or each in its own window?", "要在此窗口中以标签页打开这 {count} 个文件，还是分别在各自的窗口中打开？"),
+    ("Open as Tabs", "以标签页打开"),
+    ("Open in Windows", "在多个窗口中打开"),
+    ("Closing open documents", "正在关闭打开的文档"),
+    ("Clear Recent Workspaces", "清除最近工作区"),
+    ("Close", "关闭"),
+    ("Close Workspace", "关闭工作区"),
+    ("Save", "保存"),
+    ("Save As…", "另存为…"),
+    ("Move to…", "移动到…"),
+    ("Pin to Workspace", "固定到工作区"),
+    ("Favorites", "收藏"),
+    ("No Favorites", "无收藏"),
+    ("Reveal in Finder", "在访达中显示"),
+    ("Show in Explorer", "在资源管理器中显示"),
+    ("Show in File Manager", "在文件管理器中显示"),
+    ("Open in Terminal", "在终端中打开"),
+    ("Share…", "分享…"),
+    ("Export", "导出"),
+    ("Print…", "打印…"),
+    ("Copy as HTML", "复制为 HTML"),
+    ("Document History", "文档历史"),
+    ("View History…", "查看历史…"),
+    ("Clear History…", "清除历史…"),
+    ("PDF…", "PDF…"),
+    ("HTML…", "HTML…"),
+    ("Word (DOCX)…", "Word (DOCX)…"),
+    ("EPUB…", "EPUB…"),
+    ("Clean Up Unused Images…", "清理未使用的图片…"),
+    // Edit
+    ("Undo", "撤销"),
+    ("Redo", "重做"),
+    ("Cut", "剪切"),
+    ("Copy", "拷贝"),
+    ("Paste", "粘贴"),
+    ("Select All", "全选"),
+    ("Find", "查找"),
+    ("Find and Replace…", "查找和替换…"),
+    ("Find Next", "查找下一个"),
+    ("Find Previous", "查找上一个"),
+    ("Use Selection for Find", "使用所选内容查找"),
+    ("Selection", "选择"),
+    ("Lines", "行"),
+    ("Spelling", "拼写"),
+    ("No Dictionaries", "无词典"),
+    ("Line Endings", "换行符"),
+    ("Select Word", "选择单词"),
+    ("Select Line", "选择行"),
+    ("Select Block", "选择块"),
+    ("Expand Selection", "扩大选择范围"),
+    ("Move Line Up", "上移行"),
+    ("Move Line Down", "下移行"),
+    ("Duplicate Line", "复制行"),
+    ("Delete Line", "删除行"),
+    ("Join Lines", "合并行"),
+    ("Sort Lines Ascending", "按升序排列行"),
+    ("Sort Lines Descending", "按降序排列行"),
+    ("Convert to LF", "转换为 LF"),
+    ("Convert to CRLF", "转换为 CRLF"),
+    // Format
+    ("Bold", "粗体"),
+    ("Italic", "斜体"),
+    ("Underline", "下划线"),
+    ("Strikethrough", "删除线"),
+    ("Inline Code", "行内代码"),
+    ("Highlight", "高亮"),
+    ("Clear Format", "清除格式"),
+    ("Headings", "标题"),
+    ("Paragraph", "段落"),
+    ("Lists", "列表"),
+    ("Table", "表格"),
+    ("Quote", "引用"),
+    ("Links", "链接"),
+    ("Link", "链接"),
+    ("Transform", "转换"),
+    ("Text Cleanup", "文本清理"),
+    ("Subscript", "下标"),
+    ("Superscript", "上标"),
+    ("Heading 1", "标题 1"),
+    ("Heading 2", "标题 2"),
+    ("Heading 3", "标题 3"),
+    ("Heading 4", "标题 4"),
+    ("Heading 5", "标题 5"),
+    ("Heading 6", "标题 6"),
+    ("Increase Heading Level", "提升标题级别"),
+    ("Decrease Heading Level", "降低标题级别"),
+    ("Ordered List", "有序列表"),
+    ("Unordered List", "无序列表"),
+    ("Task List", "任务列表"),
+    ("Indent", "增加缩进"),
+    ("Outdent", "减少缩进"),
+    ("Remove List", "移除列表"),
+    ("Add Row Above", "在上方插入行"),
+    ("Add Row Below", "在下方插入行"),
+    ("Add Column Before", "在左侧插入列"),
+    ("Add Column After", "在右侧插入列"),
+    ("Delete Row", "删除行"),
+    ("Delete Column", "删除列"),
+    ("Delete Table", "删除表格"),
+    ("Align Left", "左对齐"),
+    ("Align Center", "居中对齐"),
+    ("Align Right", "右对齐"),
+    ("Align All Left", "全部左对齐"),
+    ("Align All Center", "全部居中对齐"),
+    ("Align All Right", "全部右对齐"),
+    ("Format Table", "格式化表格"),
+    ("Nest Quote", "增加引用层级"),
+    ("Unnest Quote", "减少引用层级"),
+    ("Wiki Link", "Wiki 链接"),
+    ("Bookmark", "书签"),
+    ("UPPERCASE", "大写"),
+    ("lowercase", "小写"),
+    ("Title Case", "首字母大写"),
+    ("Toggle Case", "切换大小写"),
+    ("Remove Trailing Spaces", "删除行尾空格"),
+    ("Collapse Blank Lines", "合并连续空行"),
+    ("Remove Blank Lines", "删除空行"),
+    ("Format Selection", "格式化所选内容"),
+    ("Format Entire File", "格式化整个文件"),
+    ("Format Markdown", "格式化 Markdown"),
+    // Insert
+    ("Image…", "图片…"),
+    ("Code Block", "代码块"),
+    ("Math Block", "数学公式块"),
+    ("Footnote", "脚注"),
+    ("Horizontal Line", "分隔线"),
+    ("Insert Table", "插入表格"),
+    ("Info Box", "信息框"),
+    ("Diagram", "图表"),
+    ("Collapsible Block", "折叠块"),
+    ("Note", "备注"),
+    ("Tip", "提示"),
+    ("Important", "重要"),
+    ("Warning", "警告"),
+    ("Caution", "注意"),
+    ("Insert Table from CSV…", "从 CSV 插入表格…"),
+    // View
+    ("Source Code Mode", "源代码模式"),
+    ("Focus Mode", "专注模式"),
+    ("Typewriter Mode", "打字机模式"),
+    ("Word Wrap", "自动换行"),
+    ("Sidebar", "侧边栏"),
+    ("Outline", "大纲"),
+    ("Actual Size", "实际大小"),
+    ("Zoom In", "放大"),
+    ("Zoom Out", "缩小"),
+    ("Actual Page Size", "实际页面大小"),
+    ("Zoom Page In", "放大页面"),
+    ("Zoom Page Out", "缩小页面"),
+    ("Float on Top", "置顶"),
+    ("Toggle Full Screen", "切换全屏"),
+    ("Toggle Line Numbers", "切换行号"),
+    ("Toggle Diagram Preview", "切换图表预览"),
+    // Window
+    ("Minimize", "最小化"),
+    ("Maximize", "最大化"),
+    ("Zoom", "缩放"),
+    ("Arrange", "排列"),
+    ("Bring All to Front", "前置全部窗口"),
+    ("Untitled", "未命名"),
+    ("Left Half", "左半边"),
+    ("Right Half", "右半边"),
+    ("Top Half", "上半边"),
+    ("Bottom Half", "下半边"),
+    ("Top Left", "左上角"),
+    ("Top Right", "右上角"),
+    ("Bottom Left", "左下角"),
+    ("Bottom Right", "右下角"),
+    // Help
+    ("VMark Help", "VMark 帮助"),
+    ("Keyboard Shortcuts", "键盘快捷键"),
+    ("Report an Issue…", "报告问题…"),
+    ("Open Logs Folder", "打开日志文件夹"),
+];
+
+const ZH_TW: Table = &[
+    // Menus
+    ("File", "檔案"),
+    ("Edit", "編輯"),
+    ("Format", "格式"),
+    ("Insert", "插入"),
+    ("View", "顯示方式"),
+    ("Plugins", "外掛程式"),
+    ("Window", "視窗"),
+    ("Tools", "工具"),
+    ("Help", "說明"),
+    // App menu
+    ("About VMark", "關於 VMark"),
+    ("Check for Updates…", "檢查更新項目…"),
+    ("Settings…", "設定…"),
+    ("Options…", "選項…"),
+    ("Services", "服務"),
+    ("Hide VMark", "隱藏 VMark"),
+    ("Hide Others", "隱藏其他"),
+    ("Show All", "顯示全部"),
+    ("Save All and Quit", "全部儲存並結束"),
+    ("Save All and Exit", "全部儲存並結束"),
+    ("Quit VMark", "結束 VMark"),
+    ("Exit", "結束"),
+    // File
+    ("New", "新增"),
+    ("New Window", "新增視窗"),
+    ("New from Template", "從範本新增"),
+    ("No Templates", "沒有範本"),
+    ("Open…", "開啟…"),
+    ("Open Folder…", "開啟資料夾…"),
+    ("Insert File Contents…", "插入檔案內容…"),
+    ("Insert File Contents", "插入檔案內容"),
+    ("Open Recent", "打開最近使用過的"),
+    ("Open Recent Workspace", "最近使用的工作區"),
+    ("No Recent Files", "沒有最近使用的檔案"),
+    ("No Recent Workspaces", "沒有最近使用的工作區"),
+    ("Clear Recent Files", "清除最近使用的檔案"),
+    ("Clear Recent Files?", "清除最近使用的檔案？"),
+    ("The list of recently opened files will be cleared.", "最近打開的檔案列表將被清除。"),
+    ("Clear", "清除"),
+    ("Could Not Insert File", "無法插入檔案"),
+    ("Cancel", "取消"),
+    ("Windows Not Responding", "視窗沒有回應"),
+    ("Some windows did not respond to the quit request. Unsaved changes in them will be lost if you force quit.", "部分視窗沒有回應結束要求。若強制結束，其中未儲存的變更將會遺失。"),
+    ("Force Quit", "強制結束"),
+    ("Keep Waiting", "繼續等待"),
+    ("Open Files", "打開檔案"),
+    ("Open {count} files as tabs in this window, or each in its own window?", "要在此視窗中以標籤頁打開這 {count} 個檔案，還是分別在各自的視窗中打開？"),
+    ("Open as Tabs", "以標籤頁打開"),
+    ("Open in Windows", "在多個視窗中打開"),
+    ("Closing open documents", "正在關閉開啟的文件"),
+    ("Clear Recent Workspaces", "清除最近使用的工作區"),
+    ("Close", "關閉"),
+    ("Close Workspace", "關閉工作區"),
+    ("Save", "儲存"),
+    ("Save As…", "另存新檔…"),
+    ("Move to…", "移動到…"),
+    ("Pin to Workspace", "釘選到工作區"),
+    ("Favorites", "我的最愛"),
+    ("No Favorites", "沒有我的最愛"),
+    ("Reveal in Finder", "在 Finder 中顯示"),
+    ("Show in Explorer", "在檔案總管中顯示"),
+    ("Show in File Manager", "在檔案管理員中顯示"),
+    ("Open in Terminal", "在終端機中打開"),
+    ("Share…", "分享…"),
+    ("Export", "輸出"),
+    ("Print…", "列印…"),
+    ("Copy as HTML", "拷貝為 HTML"),
+    ("Document History", "文件歷史記錄"),
+    ("View History…", "檢視歷史記錄…"),
+    ("Clear History…", "清除歷史記錄…"),
+    ("PDF…", "PDF…"),
+    ("HTML…", "HTML…"),
+    ("Word (DOCX)…", "Word (DOCX)…"),
+    ("EPUB…", "EPUB…"),
+    ("Clean Up Unused Images…", "清理未使用的影像…"),
+    // Edit
+    ("Undo", "還原"),
+    ("Redo", "重做"),
+    ("Cut", "剪下"),
+    ("Copy", "拷貝"),
+    ("Paste", "貼上"),
+    ("Select All", "全選"),
+    ("Find", "尋找"),
+    ("Find and Replace…", "尋找與取代…"),
+    ("Find Next", "尋找下一個"),
+    ("Find Previous", "尋找上一個"),
+    ("Use Selection for Find", "使用所選範圍尋找"),
+    ("Selection", "選取"),
+    ("Lines", "行"),
+    ("Spelling", "拼字"),
+    ("No Dictionaries", "沒有字典"),
+    ("Line Endings", "換行字元"),
+    ("Select Word", "選取字詞"),
+    ("Select Line", "選取行"),
+    ("Select Block", "選取區塊"),
+    ("Expand Selection", "擴大選取範圍"),
+    ("Move Line Up", "上移行"),
+    ("Move Line Down", "下移行"),
+    ("Duplicate Line", "複製行"),
+    ("Delete Line", "刪除行"),
+    ("Join Lines", "合併行"),
+    ("Sort Lines Ascending", "遞增排序行"),
+    ("Sort Lines Descending", "遞減排序行"),
+    ("Convert to LF", "轉換為 LF"),
+    ("Convert to CRLF", "轉換為 CRLF"),
+    // Format
+    ("Bold", "粗體"),
+    ("Italic", "斜體"),
+    ("Underline", "底線"),
+    ("Strikethrough", "刪除線"),
+    ("Inline Code", "行內程式碼"),
+    ("Highlight", "螢光標示"),
+    ("Clear Format", "清除格式"),
+    ("Headings", "標題"),
+    ("Paragraph", "段落"),
+    ("Lists", "列表"),
+    ("Table", "表格"),
+    ("Quote", "引言"),
+    ("Links", "連結"),
+    ("Link", "連結"),
+    ("Transform", "轉換"),
+    ("Text Cleanup", "文字清理"),
+    ("Subscript", "下標"),
+    ("Superscript", "上標"),
+    ("Heading 1", "標題 1"),
+    ("Heading 2", "標題 2"),
+    ("Heading 3", "標題 3"),
+    ("Heading 4", "標題 4"),
+    ("Heading 5", "標題 5"),
+    ("Heading 6", "標題 6"),
+    ("Increase Heading Level", "提高標題層級"),
+    ("Decrease Heading Level", "降低標題層級"),
+    ("Ordered List", "編號列表"),
+    ("Unordered List", "項目符號列表"),
+    ("Task List", "工作列表"),
+    ("Indent", "增加縮排"),
+    ("Outdent", "減少縮排"),
+    ("Remove List", "移除列表"),
+    ("Add Row Above", "在上方加入列"),
+    ("Add Row Below", "在下方加入列"),
+    ("Add Column Before", "在左側加入欄"),
+    ("Add Column After", "在右側加入欄"),
+    ("Delete Row", "刪除列"),
+    ("Delete Column", "刪除欄"),
+    ("Delete Table", "刪除表格"),
+    ("Align Left", "靠左對齊"),
+    ("Align Center", "置中對齊"),
+    ("Align Right", "靠右對齊"),
+    ("Align All Left", "全部靠左對齊"),
+    ("Align All Center", "全部置中對齊"),
+    ("Align All Right", "全部靠右對齊"),
+    ("Format Table", "格式化表格"),
+    ("Nest Quote", "增加引言層級"),
+    ("Unnest Quote", "減少引言層級"),
+    ("Wiki Link", "Wiki 連結"),
+    ("Bookmark", "書籤"),
+    ("UPPERCASE", "大寫"),
+    ("lowercase", "小寫"),
+    ("Title Case", "字首大寫"),
+    ("Toggle Case", "切換大小寫"),
+    ("Remove Trailing Spaces", "移除行尾空格"),
+    ("Collapse Blank Lines", "合併連續空白行"),
+    ("Remove Blank Lines", "移除空白行"),
+    ("Format Selection", "格式化所選範圍"),
+    ("Format Entire File", "格式化整個檔案"),
+    ("Format Markdown", "格式化 Markdown"),
+    // Insert
+    ("Image…", "影像…"),
+    ("Code Block", "程式碼區塊"),
+    ("Math Block", "數學公式區塊"),
+    ("Footnote", "註腳"),
+    ("Horizontal Line", "水平線"),
+    ("Insert Table", "插入表格"),
+    ("Info Box", "資訊方塊"),
+    ("Diagram", "圖表"),
+    ("Collapsible Block", "可摺疊區塊"),
+    ("Note", "附註"),
+    ("Tip", "提示"),
+    ("Important", "重要"),
+    ("Warning", "警告"),
+    ("Caution", "注意"),
+    ("Insert Table from CSV…", "從 CSV 插入表格…"),
+    // View
+    ("Source Code Mode", "原始碼模式"),
+    ("Focus Mode", "專注模式"),
+    ("Typewriter Mode", "打字機模式"),
+    ("Word Wrap", "自動換行"),
+    ("Sidebar", "側邊欄"),
+    ("Outline", "大綱"),
+    ("Actual Size", "實際大小"),
+    ("Zoom In", "放大"),
+    ("Zoom Out", "縮小"),
+    ("Actual Page Size", "實際頁面大小"),
+    ("Zoom Page In", "放大頁面"),
+    ("Zoom Page Out", "縮小頁面"),
+    ("Float on Top", "保持在最上層"),
+    ("Toggle Full Screen", "切換全螢幕"),
+    ("Toggle Line Numbers", "切換行號"),
+    ("Toggle Diagram Preview", "切換圖表預覽"),
+    // Window
+    ("Minimize", "縮到最小"),
+    ("Maximize", "放到最大"),
+    ("Zoom", "縮放"),
+    ("Arrange", "排列"),
+    ("Bring All to Front", "將此程式所有視窗移至最前"),
+    ("Untitled", "未命名"),
+    ("Left Half", "左半部"),
+    ("Right Half", "右半部"),
+    ("Top Half", "上半部"),
+    ("Bottom Half", "下半部"),
+    ("Top Left", "左上角"),
+    ("Top Right", "右上角"),
+    ("Bottom Left", "左下角"),
+    ("Bottom Right", "右下角"),
+    // Help
+    ("VMark Help", "VMark 說明"),
+    ("Keyboard Shortcuts", "鍵盤快速鍵"),
+    ("Report an Issue…", "回報問題…"),
+    ("Open Logs Folder", "打開日誌資料夾"),
+];
+
+const JA: Table = &[
+    // Menus
+    ("File", "ファイル"),
+    ("Edit", "編集"),
+    ("Format", "フォーマット"),
+    ("Insert", "挿入"),
+    ("View", "表示"),
+    ("Plugins", "プラグイン"),
+    ("Window", "ウインドウ"),
+    ("Tools", "ツール"),
+    ("Help", "ヘルプ"),
+    // App menu
+    ("About VMark", "VMark について"),
+    ("Check for Updates…", "アップデートを確認…"),
+    ("Settings…", "設定…"),
+    ("Options…", "オプション…"),
+    ("Services", "サービス"),
+    ("Hide VMark", "VMark を隠す"),
+    ("Hide Others", "ほかを隠す"),
+    ("Show All", "すべてを表示"),
+    ("Save All and Quit", "すべて保存して終了"),
+    ("Save All and Exit", "すべて保存して終了"),
+    ("Quit VMark", "VMark を終了"),
+    ("Exit", "終了"),
+    // File
+    ("New", "新規"),
+    ("New Window", "新規ウインドウ"),
+    ("New from Template", "テンプレートから新規作成"),
+    ("No Templates", "テンプレートなし"),
+    ("Open…", "開く…"),
+    ("Open Folder…", "フォルダを開く…"),
+    ("Insert File Contents…", "ファイルの内容を挿入…"),
+    ("Insert File Contents", "ファイルの内容を挿入"),
+    ("Open Recent", "最近使った項目を開く"),
+    ("Open Recent Workspace", "最近使ったワークスペース"),
+    ("No Recent Files", "最近使ったファイルはありません"),
+    (
+        "No Recent Workspaces",
+        "最近使ったワークスペースはありません",
+    ),
+    ("Clear Recent Files", "最近使ったファイルを消去"),
+    ("Clear Recent Files?", "最近使ったファイルを消去しますか？"),
+    ("The list of recently opened files will be cleared.", "最近開いたファイルの一覧が消去されます。"),
+    ("Clear", "消去"),
+    ("Could Not Insert File", "ファイルを挿入できません"),
+    ("Cancel", "キャンセル"),
+    ("Windows Not Responding", "ウインドウが応答していません"),
+    ("Some windows did not respond to the quit request. Unsaved changes in them will be lost if you force quit.", "一部のウインドウが終了要求に応答していません。強制終了すると、保存されていない変更は失われます。"),
+    ("Force Quit", "強制終了"),
+    ("Keep Waiting", "待機を続ける"),
+    ("Open Files", "ファイルを開く"),
+    ("Open {count} files as tabs in this window, or each in its own window?", "{count} 個のファイルをこのウインドウのタブで開きますか？それともそれぞれ別のウインドウで開きますか？"),
+    ("Open as Tabs", "タブで開く"),
+    ("Open in Windows", "別のウインドウで開く"),
+    ("Closing open documents", "開いている書類を閉じています"),
+    ("Clear Recent Workspaces", "最近使ったワークスペースを消去"),
+    ("Close", "閉じる"),
+    ("Close Workspace", "ワークスペースを閉じる"),
+    ("Save", "保存"),
+    ("Save As…", "別名で保存…"),
+    ("Move to…", "移動…"),
+    ("Pin to Workspace", "ワークスペースにピン留め"),
+    ("Favorites", "お気に入り"),
+    ("No Favorites", "お気に入りなし"),
+    ("Reveal in Finder", "Finder に表示"),
+    ("Show in Explorer", "エクスプローラーで表示"),
+    ("Show in File Manager", "ファイルマネージャで表示"),
+    ("Open in Terminal", "ターミナルで開く"),
+    ("Share…", "共有…"),
+    ("Export", "書き出す"),
+    ("Print…", "プリント…"),
+    ("Copy as HTML", "HTML としてコピー"),
+    ("Document History", "書類の履歴"),
+    ("View History…", "履歴を表示…"),
+    ("Clear History…", "履歴を消去…"),
+    ("PDF…", "PDF…"),
+    ("HTML…", "HTML…"),
+    ("Word (DOCX)…", "Word (DOCX)…"),
+    ("EPUB…", "EPUB…"),
+    ("Clean Up Unused Images…", "未使用の画像を整理…"),
+    // Edit
+    ("Undo", "取り消す"),
+    ("Redo", "やり直す"),
+    ("Cut", "カット"),
+    ("Copy", "コピー"),
+    ("Paste", "ペースト"),
+    ("Select All", "すべてを選択"),
+    ("Find", "検索"),
+    ("Find and Replace…", "検索と置換…"),
+    ("Find Next", "次を検索"),
+    ("Find Previous", "前を検索"),
+    ("Use Selection for Find", "選択部分を検索に使用"),
+    ("Selection", "選択"),
+    ("Lines", "行"),
+    ("Spelling", "スペル"),
+    ("No Dictionaries", "辞書なし"),
+    ("Line Endings", "改行コード"),
+    ("Select Word", "単語を選択"),
+    ("Select Line", "行を選択"),
+    ("Select Block", "ブロックを選択"),
+    ("Expand Selection", "選択範囲を拡張"),
+    ("Move Line Up", "行を上に移動"),
+    ("Move Line Down", "行を下に移動"),
+    ("Duplicate Line", "行を複製"),
+    ("Delete Line", "行を削除"),
+    ("Join Lines", "行を結合"),
+    ("Sort Lines Ascending", "行を昇順で並べ替え"),
+    ("Sort Lines Descending", "行を降順で並べ替え"),
+    ("Convert to LF", "LF に変換"),
+    ("Convert to CRLF", "CRLF に変換"),
+    // Format
+    ("Bold", "太字"),
+    ("Italic", "斜体"),
+    ("Underline", "下線"),
+    ("Strikethrough", "取り消し線"),
+    ("Inline Code", "インラインコード"),
+    ("Highlight", "ハイライト"),
+    ("Clear Format", "書式をクリア"),
+    ("Headings", "見出し"),
+    ("Paragraph", "段落"),
+    ("Lists", "リスト"),
+    ("Table", "表"),
+    ("Quote", "引用"),
+    ("Links", "リンク"),
+    ("Link", "リンク"),
+    ("Transform", "変換"),
+    ("Text Cleanup", "テキストの整理"),
+    ("Subscript", "下付き"),
+    ("Superscript", "上付き"),
+    ("Heading 1", "見出し 1"),
+    ("Heading 2", "見出し 2"),
+    ("Heading 3", "見出し 3"),
+    ("Heading 4", "見出し 4"),
+    ("Heading 5", "見出し 5"),
+    ("Heading 6", "見出し 6"),
+    ("Increase Heading Level", "見出しレベルを上げる"),
+    ("Decrease Heading Level", "見出しレベルを下げる"),
+    ("Ordered List", "番号付きリスト"),
+    ("Unordered List", "箇条書き"),
+    ("Task List", "タスクリスト"),
+    ("Indent", "インデント"),
+    ("Outdent", "インデント解除"),
+    ("Remove List", "リストを解除"),
+    ("Add Row Above", "上に行を追加"),
+    ("Add Row Below", "下に行を追加"),
+    ("Add Column Before", "左に列を追加"),
+    ("Add Column After", "右に列を追加"),
+    ("Delete Row", "行を削除"),
+    ("Delete Column", "列を削除"),
+    ("Delete Table", "表を削除"),
+    ("Align Left", "左揃え"),
+    ("Align Center", "中央揃え"),
+    ("Align Right", "右揃え"),
+    ("Align All Left", "すべて左揃え"),
+    ("Align All Center", "すべて中央揃え"),
+    ("Align All Right", "すべて右揃え"),
+    ("Format Table", "表を整形"),
+    ("Nest Quote", "引用のレベルを上げる"),
+    ("Unnest Quote", "引用のレベルを下げる"),
+    ("Wiki Link", "Wiki リンク"),
+    ("Bookmark", "ブックマーク"),
+    ("UPPERCASE", "大文字"),
+    ("lowercase", "小文字"),
+    ("Title Case", "先頭を大文字"),
+    ("Toggle Case", "大文字/小文字を切り替える"),
+    ("Remove Trailing Spaces", "行末の空白を削除"),
+    ("Collapse Blank Lines", "連続する空行をまとめる"),
+    ("Remove Blank Lines", "空行を削除"),
+    ("Format Selection", "選択範囲を整形"),
+    ("Format Entire File", "ファイル全体を整形"),
+    ("Format Markdown", "Markdown を整形"),
+    // Insert
+    ("Image…", "画像…"),
+    ("Code Block", "コードブロック"),
+    ("Math Block", "数式ブロック"),
+    ("Footnote", "脚注"),
+    ("Horizontal Line", "水平線"),
+    ("Insert Table", "表を挿入"),
+    ("Info Box", "情報ボックス"),
+    ("Diagram", "図"),
+    ("Collapsible Block", "折りたたみブロック"),
+    ("Note", "注記"),
+    ("Tip", "ヒント"),
+    ("Important", "重要"),
+    ("Warning", "警告"),
+    ("Caution", "注意"),
+    ("Insert Table from CSV…", "CSV から表を挿入…"),
+    // View
+    ("Source Code Mode", "ソースコードモード"),
+    ("Focus Mode", "フォーカスモード"),
+    ("Typewriter Mode", "タイプライターモード"),
+    ("Word Wrap", "行の折り返し"),
+    ("Sidebar", "サイドバー"),
+    ("Outline", "アウトライン"),
+    ("Actual Size", "実際のサイズ"),
+    ("Zoom In", "拡大"),
+    ("Zoom Out", "縮小"),
+    ("Actual Page Size", "ページを実際のサイズに"),
+    ("Zoom Page In", "ページを拡大"),
+    ("Zoom Page Out", "ページを縮小"),
+    ("Float on Top", "最前面に表示"),
+    ("Toggle Full Screen", "フルスクリーンを切り替える"),
+    ("Toggle Line Numbers", "行番号を切り替える"),
+    ("Toggle Diagram Preview", "図のプレビューを切り替える"),
+    // Window
+    ("Minimize", "しまう"),
+    ("Maximize", "最大化"),
+    ("Zoom", "拡大/縮小"),
+    ("Arrange", "整列"),
+    ("Bring All to Front", "すべてを手前に移動"),
+    ("Untitled", "名称未設定"),
+    ("Left Half", "左半分"),
+    ("Right Half", "右半分"),
+    ("Top Half", "上半分"),
+    ("Bottom Half", "下半分"),
+    ("Top Left", "左上"),
+    ("Top Right", "右上"),
+    ("Bottom Left", "左下"),
+    ("Bottom Right", "右下"),
+    // Help
+    ("VMark Help", "VMark ヘルプ"),
+    ("Keyboard Shortcuts", "キーボードショートカット"),
+    ("Report an Issue…", "問題を報告…"),
+    ("Open Logs Folder", "ログフォルダを開く"),
+];
+
+const DE: Table = &[
+    // Menus
+    ("File", "Ablage"),
+    ("Edit", "Bearbeiten"),
+    ("Format", "Format"),
+    ("Insert", "Einfügen"),
+    ("View", "Darstellung"),
+    ("Plugins", "Plugins"),
+    ("Window", "Fenster"),
+    ("Tools", "Extras"),
+    ("Help", "Hilfe"),
+    // App menu
+    ("About VMark", "Über VMark"),
+    ("Check for Updates…", "Nach Updates suchen …"),
+    ("Settings…", "Einstellungen …"),
+    ("Options…", "Optionen …"),
+    ("Services", "Dienste"),
+    ("Hide VMark", "VMark ausblenden"),
+    ("Hide Others", "Andere ausblenden"),
+    ("Show All", "Alle einblenden"),
+    ("Save All and Quit", "Alle sichern und beenden"),
+    ("Save All and Exit", "Alle speichern und beenden"),
+    ("Quit VMark", "VMark beenden"),
+    ("Exit", "Beenden"),
+    // File
+    ("New", "Neu"),
+    ("New Window", "Neues Fenster"),
+    ("New from Template", "Neu aus Vorlage"),
+    ("No Templates", "Keine Vorlagen"),
+    ("Open…", "Öffnen …"),
+    ("Open Folder…", "Ordner öffnen …"),
+    ("Insert File Contents…", "Dateiinhalt einfügen…"),
+    ("Insert File Contents", "Dateiinhalt einfügen"),
+    ("Open Recent", "Benutzte Dokumente"),
+    ("Open Recent Workspace", "Benutzte Arbeitsbereiche"),
+    ("No Recent Files", "Keine benutzten Dokumente"),
+    ("No Recent Workspaces", "Keine benutzten Arbeitsbereiche"),
+    ("Clear Recent Files", "Liste löschen"),
+    ("Clear Recent Files?", "Zuletzt verwendete Dateien löschen?"),
+    ("The list of recently opened files will be cleared.", "Die Liste der zuletzt geöffneten Dateien wird gelöscht."),
+    ("Clear", "Löschen"),
+    ("Could Not Insert File", "Datei konnte nicht eingefügt werden"),
+    ("Cancel", "Abbrechen"),
+    ("Windows Not Responding", "Fenster reagieren nicht"),
+    ("Some windows did not respond to the quit request. Unsaved changes in them will be lost if you force quit.", "Einige Fenster haben nicht auf die Anfrage zum Beenden reagiert. Nicht gesicherte Änderungen gehen beim sofortigen Beenden verloren."),
+    ("Force Quit", "Sofort beenden"),
+    ("Keep Waiting", "Weiter warten"),
+    ("Open Files", "Dateien öffnen"),
+    ("Open {count} files as tabs in this window, or each in its own window?", "{count} Dateien als Tabs in diesem Fenster oder jeweils in einem eigenen Fenster öffnen?"),
+    ("Open as Tabs", "Als Tabs öffnen"),
+    ("Open in Windows", "In Fenstern öffnen"),
+    ("Closing open documents", "Offene Dokumente werden geschlossen"),
+    ("Clear Recent Workspaces", "Liste löschen"),
+    ("Close", "Schließen"),
+    ("Close Workspace", "Arbeitsbereich schließen"),
+    ("Save", "Sichern"),
+    ("Save As…", "Sichern unter …"),
+    ("Move to…", "Bewegen nach …"),
+    ("Pin to Workspace", "Im Arbeitsbereich anheften"),
+    ("Favorites", "Favoriten"),
+    ("No Favorites", "Keine Favoriten"),
+    ("Reveal in Finder", "Im Finder zeigen"),
+    ("Show in Explorer", "Im Explorer anzeigen"),
+    ("Show in File Manager", "Im Dateimanager anzeigen"),
+    ("Open in Terminal", "Im Terminal öffnen"),
+    ("Share…", "Teilen…"),
+    ("Export", "Exportieren"),
+    ("Print…", "Drucken …"),
+    ("Copy as HTML", "Als HTML kopieren"),
+    ("Document History", "Dokumentverlauf"),
+    ("View History…", "Verlauf anzeigen …"),
+    ("Clear History…", "Verlauf löschen …"),
+    ("PDF…", "PDF …"),
+    ("HTML…", "HTML …"),
+    ("Word (DOCX)…", "Word (DOCX) …"),
+    ("EPUB…", "EPUB …"),
+    ("Clean Up Unused Images…", "Nicht verwendete Bilder bereinigen …"),
+    // Edit
+    ("Undo", "Widerrufen"),
+    ("Redo", "Wiederholen"),
+    ("Cut", "Ausschneiden"),
+    ("Copy", "Kopieren"),
+    ("Paste", "Einsetzen"),
+    ("Select All", "Alles auswählen"),
+    ("Find", "Suchen"),
+    ("Find and Replace…", "Suchen und ersetzen …"),
+    ("Find Next", "Weitersuchen"),
+    ("Find Previous", "Rückwärts suchen"),
+    ("Use Selection for Find", "Auswahl suchen"),
+    ("Selection", "Auswahl"),
+    ("Lines", "Zeilen"),
+    ("Spelling", "Rechtschreibung"),
+    ("No Dictionaries", "Keine Wörterbücher"),
+    ("Line Endings", "Zeilenenden"),
+    ("Select Word", "Wort auswählen"),
+    ("Select Line", "Zeile auswählen"),
+    ("Select Block", "Block auswählen"),
+    ("Expand Selection", "Auswahl erweitern"),
+    ("Move Line Up", "Zeile nach oben verschieben"),
+    ("Move Line Down", "Zeile nach unten verschieben"),
+    ("Duplicate Line", "Zeile duplizieren"),
+    ("Delete Line", "Zeile löschen"),
+    ("Join Lines", "Zeilen verbinden"),
+    ("Sort Lines Ascending", "Zeilen aufsteigend sortieren"),
+    ("Sort Lines Descending", "Zeilen absteigend sortieren"),
+    ("Convert to LF", "In LF umwandeln"),
+    ("Convert to CRLF", "In CRLF umwandeln"),
+    // Format
+    ("Bold", "Fett"),
+    ("Italic", "Kursiv"),
+    ("Underline", "Unterstrichen"),
+    ("Strikethrough", "Durchgestrichen"),
+    ("Inline Code", "Inline-Code"),
+    ("Highlight", "Hervorheben"),
+    ("Clear Format", "Formatierung entfernen"),
+    ("Headings", "Überschriften"),
+    ("Paragraph", "Absatz"),
+    ("Lists", "Listen"),
+    ("Table", "Tabelle"),
+    ("Quote", "Zitat"),
+    ("Links", "Links"),
+    ("Link", "Link"),
+    ("Transform", "Umwandeln"),
+    ("Text Cleanup", "Text bereinigen"),
+    ("Subscript", "Tiefgestellt"),
+    ("Superscript", "Hochgestellt"),
+    ("Heading 1", "Überschrift 1"),
+    ("Heading 2", "Überschrift 2"),
+    ("Heading 3", "Überschrift 3"),
+    ("Heading 4", "Überschrift 4"),
+    ("Heading 5", "Überschrift 5"),
+    ("Heading 6", "Überschrift 6"),
+    ("Increase Heading Level", "Überschriftebene erhöhen"),
+    ("Decrease Heading Level", "Überschriftebene verringern"),
+    ("Ordered List", "Nummerierte Liste"),
+    ("Unordered List", "Aufzählung"),
+    ("Task List", "Aufgabenliste"),
+    ("Indent", "Einrücken"),
+    ("Outdent", "Ausrücken"),
+    ("Remove List", "Liste entfernen"),
+    ("Add Row Above", "Zeile oberhalb einfügen"),
+    ("Add Row Below", "Zeile unterhalb einfügen"),
+    ("Add Column Before", "Spalte davor einfügen"),
+    ("Add Column After", "Spalte danach einfügen"),
+    ("Delete Row", "Zeile löschen"),
+    ("Delete Column", "Spalte löschen"),
+    ("Delete Table", "Tabelle löschen"),
+    ("Align Left", "Linksbündig"),
+    ("Align Center", "Zentriert"),
+    ("Align Right", "Rechtsbündig"),
+    ("Align All Left", "Alle linksbündig"),
+    ("Align All Center", "Alle zentriert"),
+    ("Align All Right", "Alle rechtsbündig"),
+    ("Format Table", "Tabelle formatieren"),
+    ("Nest Quote", "Zitat verschachteln"),
+    ("Unnest Quote", "Zitatebene entfernen"),
+    ("Wiki Link", "Wiki-Link"),
+    ("Bookmark", "Lesezeichen"),
+    ("UPPERCASE", "GROSSBUCHSTABEN"),
+    ("lowercase", "kleinbuchstaben"),
+    ("Title Case", "Wortanfänge groß"),
+    ("Toggle Case", "Groß-/Kleinschreibung umkehren"),
+    ("Remove Trailing Spaces", "Leerzeichen am Zeilenende entfernen"),
+    ("Collapse Blank Lines", "Leerzeilen zusammenfassen"),
+    ("Remove Blank Lines", "Leerzeilen entfernen"),
+    ("Format Selection", "Auswahl formatieren"),
+    ("Format Entire File", "Ganze Datei formatieren"),
+    ("Format Markdown", "Markdown formatieren"),
+    // Insert
+    ("Image…", "Bild …"),
+    ("Code Block", "Codeblock"),
+    ("Math Block", "Formelblock"),
+    ("Footnote", "Fußnote"),
+    ("Horizontal Line", "Horizontale Linie"),
+    ("Insert Table", "Tabelle einfügen"),
+    ("Info Box", "Infobox"),
+    ("Diagram", "Diagramm"),
+    ("Collapsible Block", "Einklappbarer Block"),
+    ("Note", "Hinweis"),
+    ("Tip", "Tipp"),
+    ("Important", "Wichtig"),
+    ("Warning", "Warnung"),
+    ("Caution", "Vorsicht"),
+    ("Insert Table from CSV…", "Tabelle aus CSV einfügen …"),
+    // View
+    ("Source Code Mode", "Quelltextmodus"),
+    ("Focus Mode", "Fokusmodus"),
+    ("Typewriter Mode", "Schreibmaschinenmodus"),
+    ("Word Wrap", "Zeilenumbruch"),
+    ("Sidebar", "Seitenleiste"),
+    ("Outline", "Gliederung"),
+    ("Actual Size", "Originalgröße"),
+    ("Zoom In", "Vergrößern"),
+    ("Zoom Out", "Verkleinern"),
+    ("Actual Page Size", "Tatsächliche Seitengröße"),
+    ("Zoom Page In", "Seite vergrößern"),
+    ("Zoom Page Out", "Seite verkleinern"),
+    ("Float on Top", "Im Vordergrund halten"),
+    ("Toggle Full Screen", "Vollbildmodus ein/aus"),
+    ("Toggle Line Numbers", "Zeilennummern ein/aus"),
+    ("Toggle Diagram Preview", "Diagrammvorschau ein/aus"),
+    // Window
+    ("Minimize", "Im Dock ablegen"),
+    ("Maximize", "Maximieren"),
+    ("Zoom", "Zoomen"),
+    ("Arrange", "Anordnen"),
+    ("Bring All to Front", "Alle nach vorne bringen"),
+    ("Untitled", "Ohne Titel"),
+    ("Left Half", "Linke Hälfte"),
+    ("Right Half", "Rechte Hälfte"),
+    ("Top Half", "Obere Hälfte"),
+    ("Bottom Half", "Untere Hälfte"),
+    ("Top Left", "Oben links"),
+    ("Top Right", "Oben rechts"),
+    ("Bottom Left", "Unten links"),
+    ("Bottom Right", "Unten rechts"),
+    // Help
+    ("VMark Help", "VMark-Hilfe"),
+    ("Keyboard Shortcuts", "Tastaturkurzbefehle"),
+    ("Report an Issue…", "Problem melden …"),
+    ("Open Logs Folder", "Protokollordner öffnen"),
+];
+
+/// Persisted menu language.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct LanguageFile {
+    #[serde(default)]
+    language: Option<String>,
+}
+
+static LANGUAGE: LazyLock<Mutex<Option<String>>> = LazyLock::new(|| {
+    Mutex::new(
+        language_file_path()
+            .and_then(|path| read_language(&path))
+            .filter(|l| table_for(l).is_some()),
+    )
+});
+
+fn language_file_path() -> Option<PathBuf> {
+    dirs::home_dir().map(|home| home.join(".vmark").join("menu-language.json"))
+}
+
+fn read_language(path: &Path) -> Option<String> {
+    let content = fs::read_to_string(path).ok()?;
+    serde_json::from_str::<LanguageFile>(&content)
+        .ok()?
+        .language
+}
+
+fn write_language(path: &Path, language: Option<&str>) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create ~/.vmark directory: {e}"))?;
+    }
+    let content = serde_json::to_string_pretty(&LanguageFile {
+        language: language.map(String::from),
+    })
+    .map_err(|e| format!("Failed to serialize menu language: {e}"))?;
+    fs::write(path, content).map_err(|e| format!("Failed to write menu language: {e}"))
+}
+
+/// Translation table for a locale tag (`zh-CN`, `zh_TW`, `ja-JP`, `de`, ...).
+/// None for English and unsupported languages.
+fn table_for(locale: &str) -> Option<Table> {
+    let tag = locale.trim().to_lowercase().replace('_', "-");
+    let mut parts = tag.split('-');
+    match parts.next()? {
+        "zh" => {
+            let traditional = parts.any(|p| matches!(p, "tw" | "hk" | "mo" | "hant"));
+            Some(if traditional { ZH_TW } else { ZH_CN })
+        }
+        "ja" => Some(JA),
+        "de" => Some(DE),
+        _ => None,
+    }
+}
+
+fn lookup(table: Table, text: &str) -> Option<&'static str> {
+    table.iter().find(|(en, _)| *en == text).map(|(_, t)| *t)
+}
+
+/// Translate an English menu label into the current menu language.
+pub fn tr(text: &str) -> String {
+    let language = LANGUAGE.lock().ok().and_then(|l| l.clone());
+    language
+        .as_deref()
+        .and_then(table_for)
+        .and_then(|table| lookup(table, text))
+        .unwrap_or(text)
+        .to_string()
+}
+
+/// Items whose label is data (file names, window titles), not UI text.
+fn is_dynamic_item(id: &str) -> bool {
+    id.starts_with("recent-file-")
+        || id.starts_with("recent-workspace-")
+        || id.starts_with(menu::WINDOW_ITEM_PREFIX)
+        || id.starts_with(menu::TEMPLATE_ITEM_PREFIX)
+        || id.starts_with(menu::FAVORITE_ITEM_PREFIX)
+        || id.starts_with(menu::SPELLING_ITEM_PREFIX)
+        || id.starts_with(menu::PLUGIN_ITEM_PREFIX)
+}
+
+fn localize_items(items: Vec<MenuItemKind<tauri::Wry>>) -> tauri::Result<()> {
+    for item in items {
+        if is_dynamic_item(item.id().as_ref()) {
+            continue;
+        }
+        match &item {
+            MenuItemKind::MenuItem(i) => i.set_text(tr(&i.text()?))?,
+            MenuItemKind::Check(i) => i.set_text(tr(&i.text()?))?,
+            MenuItemKind::Predefined(i) => {
+                let text = i.text()?;
+                if !text.is_empty() {
+                    i.set_text(tr(&text))?;
+                }
+            }
+            MenuItemKind::Submenu(sub) => {
+                sub.set_text(tr(&sub.text()?))?;
+                localize_items(sub.items()?)?;
+            }
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+/// Translate the labels of a freshly built (English) menu.
+pub fn localize_menu(menu: &Menu<tauri::Wry>) -> tauri::Result<()> {
+    if LANGUAGE.lock().map(|l| l.is_none()).unwrap_or(true) {
+        return Ok(());
+    }
+    localize_items(menu.items()?)
+}
+
+/// Switch the menu language and rebuild the menu. Empty, `en` or an
+/// unsupported locale selects English.
+#[tauri::command]
+pub fn set_menu_language(app: AppHandle, locale: String) -> Result<(), String> {
+    let language = table_for(&locale).map(|_| locale.trim().to_string());
+    if let Some(path) = language_file_path() {
+        write_language(&path, language.as_deref())?;
+    }
+    if let Ok(mut current) = LANGUAGE.lock() {
+        *current = language;
+    }
+    menu::rebuild_menu(app, menu::last_shortcuts())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_table_for() {
+        let view = |locale: &str| table_for(locale).and_then(|t| lookup(t, "View"));
+        assert_eq!(view("zh-CN"), Some("视图"));
+        assert_eq!(view("zh_Hans"), Some("视图"));
+        assert_eq!(view("zh-Hant-TW"), Some("顯示方式"));
+        assert_eq!(view("ja-JP"), Some("表示"));
+        assert_eq!(view("de"), Some("Darstellung"));
+        assert!(table_for("en-US").is_none());
+        assert!(table_for("").is_none());
+    }
+
+    #[test]
+    fn test_tables_cover_top_level_menus() {
+        for table in [ZH_CN, ZH_TW, JA, DE] {
+            for menu in ["File", "Edit", "Format", "Insert", "View", "Window", "Help"] {
+                assert!(lookup(table, menu).is_some(), "missing '{}'", menu);
+            }
+        }
+        assert_eq!(lookup(JA, "Not a label"), None);
+    }
+
+    /// Static labels passed to the item constructors in menu.rs.
+    fn menu_labels() -> Vec<String> {
+        let source = include_str!("menu.rs")
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" ");
+        let patterns = [
+            r#"(?:Check)?MenuItem::with_id\(app, "[^"]*", "([^"]+)""#,
+            r#"Submenu::with_items\(app, "([^"]+)""#,
+            r#"Submenu::with_id_and_items\(app, [^,]+, "([^"]+)""#,
+            r#"PredefinedMenuItem::\w+\(app, Some\("([^"]+)"\)"#,
+        ];
+        let mut labels: Vec<String> = patterns
+            .iter()
+            .flat_map(|p| {
+                regex::Regex::new(p)
+                    .unwrap()
+                    .captures_iter(&source)
+                    .map(|c| c[1].to_string())
+                    .collect::<Vec<_>>()
+            })
+            .filter(|label| label != "VMark")
+            .collect();
+        labels.sort();
+        labels.dedup();
+        labels
+    }
+
+    #[test]
+    fn test_tables_cover_menu_labels() {
+        let labels = menu_labels();
+        assert!(labels.len() > 100);
+        for (name, table) in [("zh-CN", ZH_CN), ("zh-TW", ZH_TW), ("ja", JA), ("de", DE)] {
+            let missing: Vec<_> = labels
+                .iter()
+                .filter(|l| lookup(table, l).is_none())
+                .collect();
+            assert!(missing.is_empty(), "{name} is missing {missing:?}");
+        }
+    }
+
+    #[test]
+    fn test_language_round_trip() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("menu-language.json");
+        assert_eq!(read_language(&path), None);
+
+        write_language(&path, Some("ja")).unwrap();
+        assert_eq!(read_language(&path).as_deref(), Some("ja"));
+        write_language(&path, None).unwrap();
+        assert_eq!(read_language(&path), None);
+    }
+}
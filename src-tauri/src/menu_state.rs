@@ -0,0 +1,416 @@
+//! Context-dependent state of native menu items.
+//!
+//! The frontend calls `set_menu_item_states` on selection and tab changes to
+//! enable, disable or retitle items by id (e.g. table operations only while
+//! the cursor is in a table). Applied states are remembered so they survive
+//! `menu::rebuild_menu`. When the last document window closes, document-only
+//! items are disabled until a document window is focused again.
+//!
+//! View toggles (Source Code Mode, Focus Mode, ...) are check items. Each
+//! window reports its modes with `sync_view_menu_state` (Float on Top is
+//! recorded by `window_manager` itself); the menu shows the modes of the
+//! focused document window, with defaults for the ones it hasn't reported yet.
+
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{LazyLock, Mutex};
+use tauri::menu::MenuItemKind;
+use tauri::AppHandle;
+
+/// Items that need an open document.
+const DOCUMENT_ITEMS: &[&str] = &[
+    "close",
+    "save",
+    "save-as",
+    "insert-file-contents",
+    "insert-table-from-csv",
+    "move-to",
+    "toggle-pin-file",
+    "reveal-in-file-manager",
+    "share",
+    crate::menu::EXPORT_SUBMENU_ID,
+    "save-pdf",
+    "export-html",
+    "export-docx",
+    "export-epub",
+    "export-pdf",
+    "copy-html",
+    "view-history",
+    "find-replace",
+    "find-next",
+    "find-prev",
+    "use-selection-find",
+    crate::menu::SPELLING_SUBMENU_ID,
+    "bold",
+    "italic",
+    "underline",
+    "strikethrough",
+    "code",
+    "highlight",
+    "subscript",
+    "superscript",
+    "link",
+    "wiki-link",
+    "clear-format",
+    "format-cjk",
+    "format-cjk-file",
+    "format-markdown",
+    "insert-table",
+    "add-row-before",
+    "add-row-after",
+    "add-col-before",
+    "add-col-after",
+    "delete-row",
+    "delete-col",
+    "delete-table",
+    "align-left",
+    "align-center",
+    "align-right",
+    "align-all-left",
+    "align-all-center",
+    "align-all-right",
+    "format-table",
+];
+
+/// View toggles mirrored from the focused window, with their state in a
+/// freshly built menu.
+const VIEW_TOGGLES: &[(&str, bool)] = &[
+    ("source-mode", false),
+    ("focus-mode", false),
+    ("typewriter-mode", false),
+    ("word-wrap", false),
+    ("sidebar", false),
+    ("outline", false),
+    ("always-on-top", false),
+];
+
+/// Requested state of one menu item; omitted fields are left as they are.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+pub struct MenuItemState {
+    pub id: String,
+    #[serde(default)]
+    pub enabled: Option<bool>,
+    #[serde(default)]
+    pub text: Option<String>,
+}
+
+/// Last applied state per item id
+static STATES: LazyLock<Mutex<HashMap<String, MenuItemState>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// Set while no document window is open
+static NO_DOCUMENT: AtomicBool = AtomicBool::new(false);
+
+/// View toggle states per window label (item id -> checked)
+static VIEW_STATES: LazyLock<Mutex<HashMap<String, HashMap<String, bool>>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// Label of the document window the menu currently reflects
+static FOCUSED_LABEL: Mutex<Option<String>> = Mutex::new(None);
+
+/// Fold a state update into the stored states.
+fn merge(states: &mut HashMap<String, MenuItemState>, update: &MenuItemState) {
+    let entry = states
+        .entry(update.id.clone())
+        .or_insert_with(|| MenuItemState {
+            id: update.id.clone(),
+            ..Default::default()
+        });
+    if update.enabled.is_some() {
+        entry.enabled = update.enabled;
+    }
+    if update.text.is_some() {
+        entry.text = update.text.clone();
+    }
+}
+
+/// Find an item anywhere in the menu tree.
+fn find_item(items: Vec<MenuItemKind<tauri::Wry>>, id: &str) -> Option<MenuItemKind<tauri::Wry>> {
+    for item in items {
+        if item.id().as_ref() == id {
+            return Some(item);
+        }
+        if let MenuItemKind::Submenu(sub) = &item {
+            if let Some(found) = sub
+                .items()
+                .ok()
+                .and_then(|children| find_item(children, id))
+            {
+                return Some(found);
+            }
+        }
+    }
+    None
+}
+
+/// Apply a state to the item in the current app menu, if it exists.
+fn apply(app: &AppHandle, state: &MenuItemState) -> tauri::Result<()> {
+    let Some(menu) = app.menu() else {
+        return Ok(());
+    };
+    let Some(item) = find_item(menu.items()?, &state.id) else {
+        return Ok(());
+    };
+    match item {
+        MenuItemKind::MenuItem(item) => {
+            if let Some(enabled) = state.enabled {
+                item.set_enabled(enabled)?;
+            }
+            if let Some(text) = &state.text {
+                item.set_text(text)?;
+            }
+        }
+        MenuItemKind::Check(item) => {
+            if let Some(enabled) = state.enabled {
+                item.set_enabled(enabled)?;
+            }
+            if let Some(text) = &state.text {
+                item.set_text(text)?;
+            }
+        }
+        MenuItemKind::Submenu(item) => {
+            if let Some(enabled) = state.enabled {
+                item.set_enabled(enabled)?;
+            }
+            if let Some(text) = &state.text {
+                item.set_text(text)?;
+            }
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+/// Record and apply state updates.
+fn update(app: &AppHandle, updates: &[MenuItemState]) -> Result<(), String> {
+    if let Ok(mut states) = STATES.lock() {
+        for state in updates {
+            merge(&mut states, state);
+        }
+    }
+    for state in updates {
+        apply(app, state).map_err(|e| format!("Failed to update menu item '{}': {e}", state.id))?;
+    }
+    Ok(())
+}
+
+/// Check or uncheck view toggles in the current app menu.
+fn apply_checked(app: &AppHandle, checked: &HashMap<String, bool>) -> Result<(), String> {
+    let Some(menu) = app.menu() else {
+        return Ok(());
+    };
+    for (id, on) in checked {
+        let items = menu.items().map_err(|e| e.to_string())?;
+        if let Some(MenuItemKind::Check(item)) = find_item(items, id) {
+            item.set_checked(*on)
+                .map_err(|e| format!("Failed to update menu item '{}': {e}", id))?;
+        }
+    }
+    Ok(())
+}
+
+/// View toggle states recorded for a window.
+fn view_states_of(label: &str) -> HashMap<String, bool> {
+    VIEW_STATES
+        .lock()
+        .ok()
+        .and_then(|states| states.get(label).cloned())
+        .unwrap_or_default()
+}
+
+/// Every view toggle for a window: its reported states, and the defaults for
+/// toggles it hasn't reported (so another window's checkmarks don't linger).
+fn view_states_with_defaults(label: &str) -> HashMap<String, bool> {
+    let mut checked: HashMap<String, bool> = VIEW_TOGGLES
+        .iter()
+        .map(|(id, on)| (id.to_string(), *on))
+        .collect();
+    checked.extend(view_states_of(label));
+    checked
+}
+
+fn focused_label() -> Option<String> {
+    FOCUSED_LABEL.lock().ok().and_then(|l| l.clone())
+}
+
+/// Apply the remembered states to a freshly built menu.
+pub fn reapply(app: &AppHandle) -> Result<(), String> {
+    let states: Vec<MenuItemState> = STATES
+        .lock()
+        .map(|s| s.values().cloned().collect())
+        .unwrap_or_default();
+    for state in &states {
+        apply(app, state).map_err(|e| format!("Failed to update menu item '{}': {e}", state.id))?;
+    }
+    if let Some(label) = focused_label() {
+        apply_checked(app, &view_states_with_defaults(&label))?;
+    }
+    Ok(())
+}
+
+/// Enable or disable the document-only items.
+fn set_document_items_enabled(app: &AppHandle, enabled: bool) {
+    let updates: Vec<MenuItemState> = DOCUMENT_ITEMS
+        .iter()
+        .map(|id| MenuItemState {
+            id: id.to_string(),
+            enabled: Some(enabled),
+            text: None,
+        })
+        .collect();
+    if let Err(_e) = update(app, &updates) {
+        #[cfg(debug_assertions)]
+        eprintln!("[menu_state] {}", _e);
+    }
+}
+
+/// Disable document-only items once no document window is left.
+pub fn handle_no_document_windows(app: &AppHandle) {
+    if !NO_DOCUMENT.swap(true, Ordering::SeqCst) {
+        set_document_items_enabled(app, false);
+    }
+}
+
+/// A document window gained focus: show its view modes, and re-enable
+/// document-only items if none was open before. The frontend refines item
+/// states with `set_menu_item_states` from there.
+pub fn handle_document_window_focused(app: &AppHandle, label: &str) {
+    if NO_DOCUMENT.swap(false, Ordering::SeqCst) {
+        set_document_items_enabled(app, true);
+    }
+    if let Ok(mut focused) = FOCUSED_LABEL.lock() {
+        *focused = Some(label.to_string());
+    }
+    if let Err(_e) = apply_checked(app, &view_states_with_defaults(label)) {
+        #[cfg(debug_assertions)]
+        eprintln!("[menu_state] {}", _e);
+    }
+}
+
+/// Forget a destroyed window's view states.
+pub fn remove_window(label: &str) {
+    if let Ok(mut states) = VIEW_STATES.lock() {
+        states.remove(label);
+    }
+    if let Ok(mut focused) = FOCUSED_LABEL.lock() {
+        if focused.as_deref() == Some(label) {
+            *focused = None;
+        }
+    }
+}
+
+/// Enable, disable or retitle menu items by id.
+#[tauri::command]
+pub fn set_menu_item_states(app: AppHandle, states: Vec<MenuItemState>) -> Result<(), String> {
+    update(&app, &states)
+}
+
+/// Record view toggles for a window, and mirror them in the menu bar if it
+/// is the focused one.
+fn record_view_states(
+    app: &AppHandle,
+    label: &str,
+    states: HashMap<String, bool>,
+) -> Result<(), String> {
+    if let Ok(mut all) = VIEW_STATES.lock() {
+        all.entry(label.to_string())
+            .or_default()
+            .extend(states.clone());
+    }
+    if focused_label().as_deref() == Some(label) {
+        apply_checked(app, &states)?;
+    }
+    Ok(())
+}
+
+/// Record one view toggle changed on the Rust side (e.g. Float on Top).
+pub fn set_view_state(app: &AppHandle, label: &str, id: &str, on: bool) {
+    let states = HashMap::from([(id.to_string(), on)]);
+    if let Err(_e) = record_view_states(app, label, states) {
+        #[cfg(debug_assertions)]
+        eprintln!("[menu_state] {}", _e);
+    }
+}
+
+/// Report a window's view toggles (item id -> on). Mirrored in the menu bar
+/// while that window is focused.
+#[tauri::command]
+pub fn sync_view_menu_state(
+    app: AppHandle,
+    label: String,
+    states: HashMap<String, bool>,
+) -> Result<(), String> {
+    record_view_states(&app, &label, states)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_merge_keeps_unset_fields() {
+        let mut states = HashMap::new();
+        merge(
+            &mut states,
+            &MenuItemState {
+                id: "save".into(),
+                enabled: Some(false),
+                text: Some("Save Copy".into()),
+            },
+        );
+        merge(
+            &mut states,
+            &MenuItemState {
+                id: "save".into(),
+                enabled: Some(true),
+                text: None,
+            },
+        );
+
+        assert_eq!(states["save"].enabled, Some(true));
+        assert_eq!(states["save"].text.as_deref(), Some("Save Copy"));
+    }
+
+    #[test]
+    fn test_view_states_are_per_window() {
+        let mut states = HashMap::new();
+        states.insert("focus-mode".to_string(), true);
+        VIEW_STATES
+            .lock()
+            .unwrap()
+            .insert("doc-7".to_string(), states);
+
+        assert_eq!(view_states_of("doc-7").get("focus-mode"), Some(&true));
+        remove_window("doc-7");
+        assert!(view_states_of("doc-7").is_empty());
+    }
+
+    #[test]
+    fn test_unreported_toggles_fall_back_to_defaults() {
+        assert_eq!(
+            view_states_with_defaults("doc-8").get("source-mode"),
+            Some(&false)
+        );
+
+        let mut states = HashMap::new();
+        states.insert("source-mode".to_string(), true);
+        VIEW_STATES
+            .lock()
+            .unwrap()
+            .insert("doc-8".to_string(), states);
+
+        let checked = view_states_with_defaults("doc-8");
+        assert_eq!(checked.get("source-mode"), Some(&true));
+        assert_eq!(checked.get("focus-mode"), Some(&false));
+        assert_eq!(checked.len(), VIEW_TOGGLES.len());
+        remove_window("doc-8");
+    }
+
+    #[test]
+    fn test_state_accepts_partial_json() {
+        let state: MenuItemState = serde_json::from_str(r#"{ "id": "delete-table" }"#).unwrap();
+        assert_eq!(state.enabled, None);
+        assert_eq!(state.text, None);
+    }
+}
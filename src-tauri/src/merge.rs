@@ -0,0 +1,245 @@
+//! Three-way merge for documents changed on disk while edited.
+//!
+//! `check_external_change` compares the file on disk ("theirs") with the
+//! content last loaded or saved ("base") and the editor buffer ("ours").
+//! Both sides are diffed against the base; regions changed by only one
+//! side merge cleanly, regions changed differently by both are conflicts.
+
+use serde::Serialize;
+use std::fs;
+
+use crate::compare;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum HunkKind {
+    Unchanged,
+    /// Changed only in the buffer
+    Ours,
+    /// Changed only on disk
+    Theirs,
+    /// Changed identically on both sides
+    Both,
+    Conflict,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct MergeHunk {
+    pub kind: HunkKind,
+    /// 1-based first base line of the hunk
+    #[serde(rename = "baseStart")]
+    pub base_start: usize,
+    pub base: Vec<String>,
+    pub ours: Vec<String>,
+    pub theirs: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ConflictData {
+    /// Content on disk
+    pub theirs: String,
+    pub hunks: Vec<MergeHunk>,
+    #[serde(rename = "hasConflicts")]
+    pub has_conflicts: bool,
+    /// Both sides merged, when nothing conflicts
+    pub merged: Option<String>,
+}
+
+/// Lines `start..end` of the base replaced by `lines`.
+#[derive(Debug, Clone, PartialEq)]
+struct Change {
+    start: usize,
+    end: usize,
+    lines: Vec<String>,
+}
+
+fn changes(base: &str, other: &str) -> Vec<Change> {
+    let other_lines: Vec<&str> = other.lines().collect();
+    compare::changed_line_ranges(base, other)
+        .into_iter()
+        .map(|(old, new)| Change {
+            start: old.start,
+            end: old.end,
+            lines: other_lines[new].iter().map(|l| l.to_string()).collect(),
+        })
+        .collect()
+}
+
+/// Base lines `start..end` with `changes` (all inside that range) applied.
+fn apply(base: &[&str], start: usize, end: usize, changes: &[&Change]) -> Vec<String> {
+    let mut out = Vec::new();
+    let mut pos = start;
+    for change in changes {
+        out.extend(base[pos..change.start].iter().map(|l| l.to_string()));
+        out.extend(change.lines.iter().cloned());
+        pos = change.end;
+    }
+    out.extend(base[pos..end].iter().map(|l| l.to_string()));
+    out
+}
+
+fn merge_hunks(base: &str, ours: &str, theirs: &str) -> Vec<MergeHunk> {
+    let base_lines: Vec<&str> = base.lines().collect();
+    let ours_changes = changes(base, ours);
+    let theirs_changes = changes(base, theirs);
+    let (mut o, mut t) = (0, 0);
+    let mut pos = 0;
+    let mut hunks = Vec::new();
+
+    let unchanged = |from: usize, to: usize| {
+        let lines: Vec<String> = base_lines[from..to].iter().map(|l| l.to_string()).collect();
+        MergeHunk {
+            kind: HunkKind::Unchanged,
+            base_start: from + 1,
+            base: lines.clone(),
+            ours: lines.clone(),
+            theirs: lines,
+        }
+    };
+
+    while o < ours_changes.len() || t < theirs_changes.len() {
+        // Start a group at the earliest change, then pull in every change
+        // from either side that overlaps or touches it
+        let take_ours = t == theirs_changes.len()
+            || (o < ours_changes.len() && ours_changes[o].start <= theirs_changes[t].start);
+        let first = if take_ours {
+            &ours_changes[o]
+        } else {
+            &theirs_changes[t]
+        };
+        let (start, mut end) = (first.start, first.end);
+        let (mut group_ours, mut group_theirs) = (Vec::new(), Vec::new());
+        loop {
+            if o < ours_changes.len() && ours_changes[o].start <= end {
+                end = end.max(ours_changes[o].end);
+                group_ours.push(&ours_changes[o]);
+                o += 1;
+            } else if t < theirs_changes.len() && theirs_changes[t].start <= end {
+                end = end.max(theirs_changes[t].end);
+                group_theirs.push(&theirs_changes[t]);
+                t += 1;
+            } else {
+                break;
+            }
+        }
+
+        if pos < start {
+            hunks.push(unchanged(pos, start));
+        }
+        let ours_lines = apply(&base_lines, start, end, &group_ours);
+        let theirs_lines = apply(&base_lines, start, end, &group_theirs);
+        let kind = if group_theirs.is_empty() {
+            HunkKind::Ours
+        } else if group_ours.is_empty() {
+            HunkKind::Theirs
+        } else if ours_lines == theirs_lines {
+            HunkKind::Both
+        } else {
+            HunkKind::Conflict
+        };
+        hunks.push(MergeHunk {
+            kind,
+            base_start: start + 1,
+            base: base_lines[start..end]
+                .iter()
+                .map(|l| l.to_string())
+                .collect(),
+            ours: ours_lines,
+            theirs: theirs_lines,
+        });
+        pos = end;
+    }
+    if pos < base_lines.len() {
+        hunks.push(unchanged(pos, base_lines.len()));
+    }
+    hunks
+}
+
+fn build_conflict(base: &str, ours: &str, theirs: String) -> ConflictData {
+    let hunks = merge_hunks(base, ours, &theirs);
+    let has_conflicts = hunks.iter().any(|h| h.kind == HunkKind::Conflict);
+    let merged = (!has_conflicts).then(|| {
+        let lines: Vec<&str> = hunks
+            .iter()
+            .flat_map(|h| match h.kind {
+                HunkKind::Theirs => &h.theirs,
+                _ => &h.ours,
+            })
+            .map(String::as_str)
+            .collect();
+        let mut merged = lines.join("\n");
+        if !merged.is_empty() && ours.ends_with('\n') {
+            merged.push('\n');
+        }
+        merged
+    });
+    ConflictData {
+        theirs,
+        hunks,
+        has_conflicts,
+        merged,
+    }
+}
+
+/// Called when a document with unsaved changes changed on disk. Returns
+/// None if the disk content is still `base` (e.g. our own save).
+#[tauri::command]
+pub fn check_external_change(
+    path: String,
+    base: String,
+    ours: String,
+) -> Result<Option<ConflictData>, String> {
+    let theirs = fs::read_to_string(&path).map_err(|e| format!("Failed to read file: {e}"))?;
+    if theirs == base {
+        return Ok(None);
+    }
+    Ok(Some(build_conflict(&base, &ours, theirs)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_clean_merge() {
+        let base = "a\nb\nc\nd\ne\n";
+        let ours = "a\nB\nc\nd\ne\n";
+        let theirs = "a\nb\nc\nd\nE\nf\n".to_string();
+
+        let data = build_conflict(base, ours, theirs);
+        assert!(!data.has_conflicts);
+        assert_eq!(data.merged.as_deref(), Some("a\nB\nc\nd\nE\nf\n"));
+        let kinds: Vec<_> = data.hunks.iter().map(|h| h.kind).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                HunkKind::Unchanged,
+                HunkKind::Ours,
+                HunkKind::Unchanged,
+                HunkKind::Theirs
+            ]
+        );
+        assert_eq!(data.hunks[3].base_start, 5);
+    }
+
+    #[test]
+    fn test_conflict() {
+        let base = "title\nbody\nend\n";
+        let ours = "title\nours\nend\n";
+        let theirs = "title\ntheirs\nend\n".to_string();
+
+        let data = build_conflict(base, ours, theirs);
+        assert!(data.has_conflicts);
+        assert!(data.merged.is_none());
+        let conflict = &data.hunks[1];
+        assert_eq!(conflict.kind, HunkKind::Conflict);
+        assert_eq!(conflict.base, vec!["body"]);
+        assert_eq!(conflict.ours, vec!["ours"]);
+        assert_eq!(conflict.theirs, vec!["theirs"]);
+
+        // The same edit on both sides is not a conflict
+        let same = build_conflict(base, ours, ours.to_string());
+        assert!(!same.has_conflicts);
+        assert_eq!(same.hunks[1].kind, HunkKind::Both);
+    }
+}
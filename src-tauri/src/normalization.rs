@@ -0,0 +1,109 @@
+//! Unicode normalization of documents on save.
+//!
+//! Text typed or pasted on macOS often arrives decomposed: Korean as
+//! conjoining jamo, Vietnamese as base letters followed by stacked
+//! combining marks. Such text looks the same as its composed form but
+//! breaks search and turns into whole-line git diffs, so documents can be
+//! normalized to one form when they are saved.
+
+use serde::{Deserialize, Serialize};
+use unicode_normalization::{is_nfc_quick, is_nfd_quick, IsNormalized, UnicodeNormalization};
+
+const HANGUL_BASE: u32 = 0xAC00;
+const LEAD_BASE: u32 = 0x1100;
+const VOWEL_BASE: u32 = 0x1161;
+const TRAIL_BASE: u32 = 0x11A7;
+const LEAD_COUNT: u32 = 19;
+const VOWEL_COUNT: u32 = 21;
+const TRAIL_COUNT: u32 = 28;
+const SYLLABLE_COUNT: u32 = LEAD_COUNT * VOWEL_COUNT * TRAIL_COUNT;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum NormalizationForm {
+    /// Composed characters (`é` as one code point)
+    Nfc,
+    /// Decomposed characters (`e` + U+0301); Hangul syllables stay whole,
+    /// since jamo sequences show up as loose letters in most other apps
+    Nfd,
+}
+
+/// Join leading consonant + vowel (+ trailing consonant) jamo into
+/// Hangul syllables, leaving everything else as it is.
+fn compose_hangul(text: impl Iterator<Item = char>) -> String {
+    let mut out = String::new();
+    let mut last: Option<char> = None;
+    for c in text {
+        let code = c as u32;
+        if let Some(prev) = last {
+            let prev_code = prev as u32;
+            let lead = prev_code.wrapping_sub(LEAD_BASE);
+            let vowel = code.wrapping_sub(VOWEL_BASE);
+            let syllable = prev_code.wrapping_sub(HANGUL_BASE);
+            let trail = code.wrapping_sub(TRAIL_BASE);
+            let composed = if lead < LEAD_COUNT && vowel < VOWEL_COUNT {
+                char::from_u32(HANGUL_BASE + (lead * VOWEL_COUNT + vowel) * TRAIL_COUNT)
+            } else if syllable < SYLLABLE_COUNT
+                && syllable.is_multiple_of(TRAIL_COUNT)
+                && (1..TRAIL_COUNT).contains(&trail)
+            {
+                char::from_u32(prev_code + trail)
+            } else {
+                None
+            };
+            if let Some(composed) = composed {
+                last = Some(composed);
+                continue;
+            }
+            out.push(prev);
+        }
+        last = Some(c);
+    }
+    out.extend(last);
+    out
+}
+
+/// `content` in normalization `form`.
+pub fn normalize(content: &str, form: NormalizationForm) -> String {
+    match form {
+        NormalizationForm::Nfc => {
+            if is_nfc_quick(content.chars()) == IsNormalized::Yes {
+                content.to_string()
+            } else {
+                content.nfc().collect()
+            }
+        }
+        NormalizationForm::Nfd => {
+            let has_jamo = content.chars().any(|c| matches!(c as u32, 0x1100..=0x11FF));
+            if !has_jamo && is_nfd_quick(content.chars()) == IsNormalized::Yes {
+                return content.to_string();
+            }
+            // Decompose, then put Hangul syllables back together
+            compose_hangul(content.nfd())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_nfc() {
+        // Hangul jamo and Vietnamese with marks in non-canonical order
+        let decomposed =
+            "\u{1112}\u{1161}\u{11AB}\u{1100}\u{1173}\u{11AF} Vi\u{0065}\u{0302}\u{0323}t";
+        assert_eq!(normalize(decomposed, NormalizationForm::Nfc), "한글 Việt");
+        assert_eq!(normalize("e\u{0323}\u{0302}", NormalizationForm::Nfc), "ệ");
+        assert_eq!(normalize("plain", NormalizationForm::Nfc), "plain");
+    }
+
+    #[test]
+    fn test_normalize_nfd_keeps_hangul() {
+        let mixed = "café \u{1112}\u{1161}\u{11AB}글";
+        assert_eq!(
+            normalize(mixed, NormalizationForm::Nfd),
+            "cafe\u{0301} 한글"
+        );
+    }
+}
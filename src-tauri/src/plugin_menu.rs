@@ -0,0 +1,282 @@
+//! Menu items contributed by plugins at runtime.
+//!
+//! A plugin registers its items with `register_menu_items`; they appear in a
+//! Plugins menu (before Window) that exists only while something is
+//! registered. Item `<item>` of plugin `<plugin>` gets the menu id
+//! `plugin:<plugin>:<item>`, and a click emits an event with that same name
+//! to the focused window (payload: window label). Registering again with the
+//! same plugin id replaces its items. Registrations survive menu rebuilds.
+
+use serde::Deserialize;
+use std::collections::{BTreeMap, HashSet};
+use std::sync::Mutex;
+use tauri::menu::{IsMenuItem, MenuItem, MenuItemKind, PredefinedMenuItem, Submenu};
+use tauri::{AppHandle, Wry};
+
+use crate::menu::{PLUGIN_ITEM_PREFIX, WINDOW_MENU_ID};
+
+const PLUGINS_MENU_ID: &str = "plugins-menu";
+
+/// Registered items per plugin id, in plugin id order
+static REGISTRY: Mutex<BTreeMap<String, Vec<PluginMenuItem>>> = Mutex::new(BTreeMap::new());
+
+/// One entry of a plugin's menu: an item, a submenu (when `items` is not
+/// empty) or a separator.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct PluginMenuItem {
+    /// Stable id, unique within the plugin. Unused for separators.
+    #[serde(default)]
+    pub id: String,
+    #[serde(default)]
+    pub label: String,
+    #[serde(default)]
+    pub accelerator: Option<String>,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    #[serde(default)]
+    pub separator: bool,
+    /// Children of a submenu
+    #[serde(default)]
+    pub items: Vec<PluginMenuItem>,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct PluginMenuSpec {
+    #[serde(rename = "pluginId")]
+    pub plugin_id: String,
+    pub items: Vec<PluginMenuItem>,
+}
+
+/// Ids become part of menu ids and event names, which only allow a
+/// restricted character set.
+fn is_valid_id(id: &str) -> bool {
+    !id.is_empty()
+        && id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+fn validate_items(items: &[PluginMenuItem], seen: &mut HashSet<String>) -> Result<(), String> {
+    for item in items {
+        if item.separator {
+            continue;
+        }
+        if !is_valid_id(&item.id) {
+            return Err(format!(
+                "Invalid menu item id '{}': use letters, digits, '-' and '_'",
+                item.id
+            ));
+        }
+        if !seen.insert(item.id.clone()) {
+            return Err(format!("Duplicate menu item id '{}'", item.id));
+        }
+        if item.label.trim().is_empty() {
+            return Err(format!("Menu item '{}' has no label", item.id));
+        }
+        validate_items(&item.items, seen)?;
+    }
+    Ok(())
+}
+
+fn validate(spec: &PluginMenuSpec) -> Result<(), String> {
+    if !is_valid_id(&spec.plugin_id) {
+        return Err(format!(
+            "Invalid plugin id '{}': use letters, digits, '-' and '_'",
+            spec.plugin_id
+        ));
+    }
+    validate_items(&spec.items, &mut HashSet::new())
+}
+
+/// Menu id (and event name) of a plugin item.
+fn item_id(plugin_id: &str, item_id: &str) -> String {
+    format!("{PLUGIN_ITEM_PREFIX}{plugin_id}:{item_id}")
+}
+
+fn build_items(
+    app: &AppHandle,
+    plugin_id: &str,
+    items: &[PluginMenuItem],
+) -> tauri::Result<Vec<MenuItemKind<Wry>>> {
+    let mut built = Vec::new();
+    for item in items {
+        let id = item_id(plugin_id, &item.id);
+        let kind = if item.separator {
+            MenuItemKind::Predefined(PredefinedMenuItem::separator(app)?)
+        } else if item.items.is_empty() {
+            MenuItemKind::MenuItem(MenuItem::with_id(
+                app,
+                id,
+                &item.label,
+                item.enabled,
+                item.accelerator.as_deref().filter(|a| !a.is_empty()),
+            )?)
+        } else {
+            let children = build_items(app, plugin_id, &item.items)?;
+            let children: Vec<&dyn IsMenuItem<Wry>> =
+                children.iter().map(|c| c as &dyn IsMenuItem<Wry>).collect();
+            MenuItemKind::Submenu(Submenu::with_id_and_items(
+                app,
+                id,
+                &item.label,
+                item.enabled,
+                &children,
+            )?)
+        };
+        built.push(kind);
+    }
+    Ok(built)
+}
+
+/// Rebuild the Plugins menu from the registry: removed when nothing is
+/// registered, otherwise inserted before the Window menu.
+pub fn update_plugins_menu(app: &AppHandle) -> tauri::Result<()> {
+    let Some(menu) = app.menu() else {
+        return Ok(());
+    };
+    if let Some(existing) = menu.get(PLUGINS_MENU_ID) {
+        menu.remove(&existing)?;
+    }
+
+    let registry = REGISTRY.lock().map(|r| r.clone()).unwrap_or_default();
+    if registry.is_empty() {
+        return Ok(());
+    }
+
+    let plugins_menu =
+        Submenu::with_id(app, PLUGINS_MENU_ID, crate::menu_i18n::tr("Plugins"), true)?;
+    for (index, (plugin_id, items)) in registry.iter().enumerate() {
+        if index > 0 {
+            plugins_menu.append(&PredefinedMenuItem::separator(app)?)?;
+        }
+        for item in build_items(app, plugin_id, items)? {
+            plugins_menu.append(&item)?;
+        }
+    }
+
+    let position = menu
+        .items()?
+        .iter()
+        .position(|item| item.id() == WINDOW_MENU_ID)
+        .unwrap_or(menu.items()?.len());
+    menu.insert(&plugins_menu, position)
+}
+
+/// Put back the registration a plugin had before a failed update.
+fn restore(
+    registry: &mut BTreeMap<String, Vec<PluginMenuItem>>,
+    plugin_id: &str,
+    previous: Option<Vec<PluginMenuItem>>,
+) {
+    match previous {
+        Some(items) => {
+            registry.insert(plugin_id.to_string(), items);
+        }
+        None => {
+            registry.remove(plugin_id);
+        }
+    }
+}
+
+/// Add or replace the menu items of a plugin. Nothing is registered when the
+/// items are invalid or the menu can't be built with them.
+#[tauri::command]
+pub fn register_menu_items(app: AppHandle, spec: PluginMenuSpec) -> Result<(), String> {
+    validate(&spec)?;
+    // Catches what validation can't, such as an unparsable accelerator
+    build_items(&app, &spec.plugin_id, &spec.items)
+        .map_err(|e| format!("Invalid menu items: {e}"))?;
+
+    let plugin_id = spec.plugin_id.clone();
+    let previous = REGISTRY
+        .lock()
+        .map_err(|e| format!("Failed to lock plugin menu registry: {e}"))?
+        .insert(spec.plugin_id, spec.items);
+    if let Err(e) = update_plugins_menu(&app) {
+        if let Ok(mut registry) = REGISTRY.lock() {
+            restore(&mut registry, &plugin_id, previous);
+        }
+        let _ = update_plugins_menu(&app);
+        return Err(format!("Failed to update Plugins menu: {e}"));
+    }
+    Ok(())
+}
+
+/// Remove all menu items of a plugin.
+#[tauri::command]
+pub fn unregister_menu_items(app: AppHandle, plugin_id: String) -> Result<(), String> {
+    let removed = REGISTRY
+        .lock()
+        .map(|mut registry| registry.remove(&plugin_id).is_some())
+        .unwrap_or(false);
+    if !removed {
+        return Ok(());
+    }
+    update_plugins_menu(&app).map_err(|e| format!("Failed to update Plugins menu: {e}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn item(id: &str, label: &str) -> PluginMenuItem {
+        PluginMenuItem {
+            id: id.into(),
+            label: label.into(),
+            accelerator: None,
+            enabled: true,
+            separator: false,
+            items: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_validate() {
+        let separator = PluginMenuItem {
+            separator: true,
+            ..item("", "")
+        };
+        let mut submenu = item("export", "Export");
+        submenu.items = vec![item("to-blog", "To Blog"), item("to_wiki", "To Wiki")];
+        let spec = PluginMenuSpec {
+            plugin_id: "publisher".into(),
+            items: vec![item("publish", "Publish"), separator, submenu],
+        };
+        assert!(validate(&spec).is_ok());
+
+        let mut duplicate = spec.clone();
+        duplicate.items[2].items.push(item("publish", "Again"));
+        assert!(validate(&duplicate).unwrap_err().contains("Duplicate"));
+
+        let mut bad_id = spec.clone();
+        bad_id.items[0].id = "pub lish".into();
+        assert!(validate(&bad_id).is_err());
+
+        let bad_plugin = PluginMenuSpec {
+            plugin_id: "a:b".into(),
+            ..spec
+        };
+        assert!(validate(&bad_plugin).is_err());
+    }
+
+    #[test]
+    fn test_restore() {
+        let mut registry = BTreeMap::new();
+        registry.insert("publisher".to_string(), vec![item("new", "New")]);
+
+        restore(&mut registry, "publisher", Some(vec![item("old", "Old")]));
+        assert_eq!(registry["publisher"], vec![item("old", "Old")]);
+
+        restore(&mut registry, "publisher", None);
+        assert!(registry.is_empty());
+    }
+
+    #[test]
+    fn test_item_id() {
+        assert_eq!(item_id("publisher", "publish"), "plugin:publisher:publish");
+    }
+}
@@ -0,0 +1,252 @@
+//! Reading positions: where the cursor and scroll were when a file was
+//! last viewed.
+//!
+//! Positions of workspace files are kept in `<root>/.vmark/positions.json`
+//! keyed by workspace-relative path, so they survive moving the folder;
+//! other files use `~/.vmark/positions.json` keyed by absolute path. The
+//! editor reports positions as the user scrolls, so writes are batched and
+//! flushed shortly after the last change (and on quit and sleep).
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{LazyLock, Mutex};
+use std::time::{Duration, Instant};
+
+use crate::document_io::{self, SaveOptions};
+
+/// Delay between the last reported position and writing it to disk
+const FLUSH_DELAY: Duration = Duration::from_secs(2);
+
+/// Positions kept per store; the least recently viewed files are dropped
+const MAX_ENTRIES: usize = 1000;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct Position {
+    /// Cursor offset in the document
+    pub cursor: usize,
+    /// Vertical scroll offset in pixels
+    #[serde(rename = "scrollTop", default)]
+    pub scroll_top: f64,
+    /// Unix timestamp in milliseconds
+    #[serde(rename = "updatedAt", default)]
+    pub updated_at: i64,
+}
+
+#[derive(Default)]
+struct Store {
+    positions: HashMap<String, Position>,
+    dirty: bool,
+}
+
+/// Loaded stores by file path
+static STORES: LazyLock<Mutex<HashMap<PathBuf, Store>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// When the pending positions are due to be written (None: no flush is
+/// scheduled). Every change pushes it back.
+static FLUSH_AT: Mutex<Option<Instant>> = Mutex::new(None);
+
+/// The store file for `path` and the key of `path` in it.
+fn locate(path: &Path, workspace_root: Option<&Path>) -> Option<(PathBuf, String)> {
+    if let Some(root) = workspace_root {
+        if let Ok(relative) = path.strip_prefix(root) {
+            let key = relative.to_string_lossy().replace('\\', "/");
+            return Some((root.join(".vmark").join("positions.json"), key));
+        }
+    }
+    let home = dirs::home_dir()?;
+    Some((
+        home.join(".vmark").join("positions.json"),
+        path.to_string_lossy().to_string(),
+    ))
+}
+
+/// The positions stored in `path`. A file that can't be parsed is moved
+/// aside (`positions.corrupt.json`) instead of being overwritten.
+fn read_store(path: &Path) -> HashMap<String, Position> {
+    let Ok(content) = fs::read(path) else {
+        return HashMap::new();
+    };
+    match serde_json::from_slice(&content) {
+        Ok(positions) => positions,
+        Err(_e) => {
+            #[cfg(debug_assertions)]
+            eprintln!("[positions] {}: {}", path.display(), _e);
+            let _ = fs::rename(path, path.with_extension("corrupt.json"));
+            HashMap::new()
+        }
+    }
+}
+
+fn write_store(path: &Path, positions: &HashMap<String, Position>) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create .vmark directory: {e}"))?;
+    }
+    let content = serde_json::to_string_pretty(positions)
+        .map_err(|e| format!("Failed to serialize positions: {e}"))?;
+    document_io::save_atomic(path, content.as_bytes(), &SaveOptions::default())?;
+    Ok(())
+}
+
+fn store<'a>(stores: &'a mut HashMap<PathBuf, Store>, file: &Path) -> &'a mut Store {
+    stores.entry(file.to_path_buf()).or_insert_with(|| Store {
+        positions: read_store(file),
+        dirty: false,
+    })
+}
+
+/// Write every store with unsaved positions.
+pub fn flush() {
+    let Ok(mut stores) = STORES.lock() else {
+        return;
+    };
+    for (file, store) in stores.iter_mut().filter(|(_, s)| s.dirty) {
+        // Another window or process may have stored positions meanwhile
+        let mut merged = read_store(file);
+        for (key, position) in &store.positions {
+            let newer = merged
+                .get(key)
+                .is_none_or(|p| p.updated_at <= position.updated_at);
+            if newer {
+                merged.insert(key.clone(), *position);
+            }
+        }
+        if merged.len() > MAX_ENTRIES {
+            let mut by_age: Vec<(String, i64)> = merged
+                .iter()
+                .map(|(key, p)| (key.clone(), p.updated_at))
+                .collect();
+            by_age.sort_by_key(|(_, updated_at)| std::cmp::Reverse(*updated_at));
+            for (key, _) in by_age.into_iter().skip(MAX_ENTRIES) {
+                merged.remove(&key);
+            }
+        }
+        match write_store(file, &merged) {
+            Ok(()) => {
+                store.positions = merged;
+                store.dirty = false;
+            }
+            Err(_e) => {
+                #[cfg(debug_assertions)]
+                eprintln!("[positions] {}", _e);
+            }
+        }
+    }
+}
+
+/// Flush once no position was reported for `FLUSH_DELAY`.
+fn schedule_flush() {
+    let Ok(mut flush_at) = FLUSH_AT.lock() else {
+        return;
+    };
+    let scheduled = flush_at.replace(Instant::now() + FLUSH_DELAY).is_some();
+    drop(flush_at);
+    if scheduled {
+        return;
+    }
+    std::thread::spawn(|| loop {
+        let wait = {
+            let Ok(mut flush_at) = FLUSH_AT.lock() else {
+                return;
+            };
+            let wait = flush_at
+                .map(|at| at.saturating_duration_since(Instant::now()))
+                .unwrap_or_default();
+            if wait.is_zero() {
+                *flush_at = None;
+            }
+            wait
+        };
+        if wait.is_zero() {
+            flush();
+            return;
+        }
+        std::thread::sleep(wait);
+    });
+}
+
+/// Remember the cursor and scroll position of `path`.
+#[tauri::command]
+pub fn set_reading_position(
+    path: String,
+    workspace_root: Option<String>,
+    cursor: usize,
+    scroll_top: f64,
+) -> Result<(), String> {
+    let Some((file, key)) = locate(Path::new(&path), workspace_root.as_deref().map(Path::new))
+    else {
+        return Err("Failed to locate the home directory".to_string());
+    };
+    let mut stores = STORES
+        .lock()
+        .map_err(|e| format!("Failed to lock positions: {e}"))?;
+    let store = store(&mut stores, &file);
+    store.positions.insert(
+        key,
+        Position {
+            cursor,
+            scroll_top,
+            updated_at: chrono::Utc::now().timestamp_millis(),
+        },
+    );
+    store.dirty = true;
+    drop(stores);
+    schedule_flush();
+    Ok(())
+}
+
+/// The last stored position of `path`, if any.
+#[tauri::command]
+pub fn get_reading_position(path: String, workspace_root: Option<String>) -> Option<Position> {
+    let (file, key) = locate(Path::new(&path), workspace_root.as_deref().map(Path::new))?;
+    let mut stores = STORES.lock().ok()?;
+    store(&mut stores, &file).positions.get(&key).copied()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_positions_round_trip() {
+        let dir = tempdir().unwrap();
+        let root = dir.path().to_string_lossy().to_string();
+        let doc = dir.path().join("book/ch1.md").to_string_lossy().to_string();
+
+        set_reading_position(doc.clone(), Some(root.clone()), 1200, 840.5).unwrap();
+        let position = get_reading_position(doc.clone(), Some(root.clone())).unwrap();
+        assert_eq!((position.cursor, position.scroll_top), (1200, 840.5));
+
+        flush();
+        let stored = read_store(&dir.path().join(".vmark/positions.json"));
+        assert_eq!(stored["book/ch1.md"].cursor, 1200);
+
+        // Reloaded from disk, as after a restart
+        STORES.lock().unwrap().clear();
+        assert_eq!(
+            get_reading_position(doc, Some(root.clone()))
+                .unwrap()
+                .cursor,
+            1200
+        );
+        assert!(get_reading_position(format!("{root}/other.md"), Some(root)).is_none());
+    }
+
+    #[test]
+    fn test_corrupt_store_is_kept_aside() {
+        let dir = tempdir().unwrap();
+        let file = dir.path().join("positions.json");
+        fs::write(&file, "{ not json").unwrap();
+
+        assert!(read_store(&file).is_empty());
+        assert!(!file.exists());
+        assert_eq!(
+            fs::read_to_string(dir.path().join("positions.corrupt.json")).unwrap(),
+            "{ not json"
+        );
+    }
+}
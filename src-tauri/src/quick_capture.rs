@@ -0,0 +1,244 @@
+//! System-wide quick capture shortcut.
+//!
+//! A global shortcut (default `CmdOrCtrl+Shift+Space`) that works while VMark
+//! is in the background. It either toggles the quick-note window or appends
+//! the clipboard text to the quick-note inbox as a timestamped entry. The
+//! settings UI changes it with `update_quick_capture_settings`; the settings
+//! are persisted to `~/.vmark/quick-capture.json` and registered at startup.
+
+use serde::{Deserialize, Serialize};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::{LazyLock, Mutex};
+use tauri::{AppHandle, Emitter};
+use tauri_plugin_clipboard_manager::ClipboardExt;
+use tauri_plugin_global_shortcut::{GlobalShortcutExt, ShortcutState};
+
+use crate::quick_note;
+
+const DEFAULT_SHORTCUT: &str = "CmdOrCtrl+Shift+Space";
+
+/// What the shortcut does.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum CaptureAction {
+    /// Show or hide the quick-note window
+    #[default]
+    OpenWindow,
+    /// Append the clipboard text to the inbox without showing a window
+    AppendClipboard,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct QuickCaptureSettings {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default = "default_shortcut")]
+    pub shortcut: String,
+    #[serde(default)]
+    pub action: CaptureAction,
+}
+
+fn default_shortcut() -> String {
+    DEFAULT_SHORTCUT.to_string()
+}
+
+impl Default for QuickCaptureSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            shortcut: default_shortcut(),
+            action: CaptureAction::default(),
+        }
+    }
+}
+
+static SETTINGS: LazyLock<Mutex<QuickCaptureSettings>> = LazyLock::new(|| {
+    Mutex::new(
+        settings_file_path()
+            .map(|path| read_settings(&path))
+            .unwrap_or_default(),
+    )
+});
+
+/// Shortcut currently registered with the OS
+static REGISTERED: Mutex<Option<String>> = Mutex::new(None);
+
+fn settings_file_path() -> Option<PathBuf> {
+    dirs::home_dir().map(|home| home.join(".vmark").join("quick-capture.json"))
+}
+
+fn read_settings(path: &Path) -> QuickCaptureSettings {
+    fs::read_to_string(path)
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+fn write_settings(path: &Path, settings: &QuickCaptureSettings) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create ~/.vmark directory: {e}"))?;
+    }
+    let content = serde_json::to_string_pretty(settings)
+        .map_err(|e| format!("Failed to serialize quick capture settings: {e}"))?;
+    fs::write(path, content).map_err(|e| format!("Failed to write quick capture settings: {e}"))
+}
+
+fn current() -> QuickCaptureSettings {
+    SETTINGS.lock().map(|s| s.clone()).unwrap_or_default()
+}
+
+/// Append `text` to the inbox under a timestamp heading.
+fn append_to_inbox(path: &Path, text: &str, timestamp: &str) -> Result<(), String> {
+    let text = text.trim();
+    if text.is_empty() {
+        return Ok(());
+    }
+    let existing = fs::read_to_string(path).unwrap_or_default();
+    let separator = match existing.as_str() {
+        "" => "",
+        s if s.ends_with("\n\n") => "",
+        s if s.ends_with('\n') => "\n",
+        _ => "\n\n",
+    };
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| format!("Failed to create inbox directory: {e}"))?;
+    }
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .map_err(|e| format!("Failed to open inbox: {e}"))?;
+    write!(file, "{separator}## {timestamp}\n\n{text}\n")
+        .map_err(|e| format!("Failed to write inbox: {e}"))
+}
+
+/// Append text to the quick-note inbox and tell windows showing it.
+pub fn append_text(app: &AppHandle, text: &str) -> Result<(), String> {
+    let inbox = quick_note::inbox_path().ok_or("Cannot determine quick note inbox")?;
+    let timestamp = chrono::Local::now().format("%Y-%m-%d %H:%M").to_string();
+    append_to_inbox(&inbox, text, &timestamp)?;
+    let _ = app.emit(
+        "quick-capture:appended",
+        inbox.to_string_lossy().to_string(),
+    );
+    Ok(())
+}
+
+fn append_clipboard(app: &AppHandle) -> Result<(), String> {
+    let text = app
+        .clipboard()
+        .read_text()
+        .map_err(|e| format!("Failed to read clipboard: {e}"))?;
+    append_text(app, &text)
+}
+
+fn run_action(app: &AppHandle) {
+    let result = match current().action {
+        CaptureAction::OpenWindow => quick_note::open_quick_note(app.clone()).map(|_| ()),
+        CaptureAction::AppendClipboard => append_clipboard(app),
+    };
+    if let Err(_e) = result {
+        #[cfg(debug_assertions)]
+        eprintln!("[quick_capture] {}", _e);
+    }
+}
+
+/// Register the shortcut for `settings`, replacing the current one.
+fn register(app: &AppHandle, settings: &QuickCaptureSettings) -> Result<(), String> {
+    let shortcuts = app.global_shortcut();
+    if let Some(previous) = REGISTERED.lock().ok().and_then(|mut r| r.take()) {
+        let _ = shortcuts.unregister(previous.as_str());
+    }
+    if !settings.enabled || settings.shortcut.trim().is_empty() {
+        return Ok(());
+    }
+
+    shortcuts
+        .on_shortcut(settings.shortcut.as_str(), |app, _shortcut, event| {
+            if event.state() == ShortcutState::Pressed {
+                run_action(app);
+            }
+        })
+        .map_err(|e| format!("Failed to register shortcut '{}': {e}", settings.shortcut))?;
+    if let Ok(mut registered) = REGISTERED.lock() {
+        *registered = Some(settings.shortcut.clone());
+    }
+    Ok(())
+}
+
+/// Register the saved shortcut. Called once at startup.
+pub fn init(app: &AppHandle) {
+    if let Err(_e) = register(app, &current()) {
+        #[cfg(debug_assertions)]
+        eprintln!("[quick_capture] {}", _e);
+    }
+}
+
+#[tauri::command]
+pub fn get_quick_capture_settings() -> QuickCaptureSettings {
+    current()
+}
+
+/// Enable, disable or rebind the shortcut. If the new shortcut can't be
+/// registered (invalid or taken by another app), the previous one is kept.
+#[tauri::command]
+pub fn update_quick_capture_settings(
+    app: AppHandle,
+    settings: QuickCaptureSettings,
+) -> Result<(), String> {
+    if let Err(e) = register(&app, &settings) {
+        let _ = register(&app, &current());
+        return Err(e);
+    }
+    if let Some(path) = settings_file_path() {
+        write_settings(&path, &settings)?;
+    }
+    if let Ok(mut current) = SETTINGS.lock() {
+        *current = settings;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_settings_round_trip() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("quick-capture.json");
+        assert_eq!(read_settings(&path), QuickCaptureSettings::default());
+
+        let settings = QuickCaptureSettings {
+            enabled: true,
+            shortcut: "Alt+Space".into(),
+            action: CaptureAction::AppendClipboard,
+        };
+        write_settings(&path, &settings).unwrap();
+        assert_eq!(read_settings(&path), settings);
+        assert!(fs::read_to_string(&path)
+            .unwrap()
+            .contains("\"appendClipboard\""));
+    }
+
+    #[test]
+    fn test_append_to_inbox() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("Quick Notes.md");
+
+        append_to_inbox(&path, "  first idea \n", "2024-05-01 09:00").unwrap();
+        append_to_inbox(&path, "   ", "2024-05-01 09:05").unwrap();
+        fs::write(&path, fs::read_to_string(&path).unwrap() + "typed by hand").unwrap();
+        append_to_inbox(&path, "second", "2024-05-01 10:00").unwrap();
+
+        assert_eq!(
+            fs::read_to_string(&path).unwrap(),
+            "## 2024-05-01 09:00\n\nfirst idea\ntyped by hand\n\n## 2024-05-01 10:00\n\nsecond\n"
+        );
+    }
+}
@@ -0,0 +1,83 @@
+//! Floating quick-note window.
+//!
+//! A small frameless, always-on-top window (label `quick-note`) for jotting
+//! something down without switching to a document window. It edits the
+//! inbox file from the `quickNoteInbox` window setting. It is not a document
+//! window, so it closes without the unsaved-changes round trip and is closed
+//! directly on quit.
+
+use std::fs;
+use std::path::PathBuf;
+use tauri::{AppHandle, Manager, WebviewUrl, WebviewWindowBuilder};
+
+use crate::{window_manager, window_settings};
+
+pub const QUICK_NOTE_LABEL: &str = "quick-note";
+
+const WIDTH: f64 = 360.0;
+const HEIGHT: f64 = 260.0;
+const MIN_WIDTH: f64 = 240.0;
+const MIN_HEIGHT: f64 = 160.0;
+
+/// Distance from the work area's top-right corner
+const MARGIN: f64 = 24.0;
+
+/// Inbox file: the configured one, else `Quick Notes.md` in Documents.
+pub fn inbox_path() -> Option<PathBuf> {
+    if let Some(inbox) = window_settings::current().quick_note_inbox {
+        if !inbox.trim().is_empty() {
+            return Some(PathBuf::from(inbox));
+        }
+    }
+    dirs::document_dir()
+        .or_else(dirs::home_dir)
+        .map(|dir| dir.join("Quick Notes.md"))
+}
+
+/// Show the quick-note window, or hide it when it is already focused.
+/// Returns whether the window is visible afterwards.
+#[tauri::command]
+pub fn open_quick_note(app: AppHandle) -> Result<bool, String> {
+    if let Some(window) = app.get_webview_window(QUICK_NOTE_LABEL) {
+        let visible = window.is_visible().unwrap_or(false);
+        if visible && window.is_focused().unwrap_or(false) {
+            window.hide().map_err(|e| e.to_string())?;
+            return Ok(false);
+        }
+        let _ = window.show();
+        let _ = window.set_focus();
+        return Ok(true);
+    }
+
+    let inbox = inbox_path().ok_or("Cannot determine quick note inbox")?;
+    if !inbox.exists() {
+        if let Some(parent) = inbox.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create inbox directory: {e}"))?;
+        }
+        fs::write(&inbox, "").map_err(|e| format!("Failed to create inbox: {e}"))?;
+    }
+    let url = format!(
+        "/quick-note?file={}",
+        urlencoding::encode(&inbox.to_string_lossy())
+    );
+
+    let mut builder =
+        WebviewWindowBuilder::new(&app, QUICK_NOTE_LABEL, WebviewUrl::App(url.into()))
+            .title("Quick Note")
+            .inner_size(WIDTH, HEIGHT)
+            .min_inner_size(MIN_WIDTH, MIN_HEIGHT)
+            .decorations(false)
+            .always_on_top(true)
+            .skip_taskbar(true)
+            .resizable(true)
+            .focused(true);
+
+    // Top-right corner of the monitor in use
+    if let Some(area) = window_manager::target_work_area(&app) {
+        builder = builder.position(area.x + area.width - WIDTH - MARGIN, area.y + MARGIN);
+    }
+
+    builder.build().map_err(|e| e.to_string())?;
+    Ok(true)
+}
@@ -1,16 +1,38 @@
-use std::collections::HashSet;
-use std::sync::{Mutex, LazyLock, atomic::{AtomicBool, Ordering}};
-use tauri::{AppHandle, Emitter, Manager};
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::sync::{Mutex, LazyLock, atomic::{AtomicBool, AtomicU64, Ordering}};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager, WebviewWindow};
 
-use crate::mcp_server;
+use crate::{mcp_server, positions, session, updater, window_settings};
 
 static QUIT_IN_PROGRESS: AtomicBool = AtomicBool::new(false);
+/// Incremented by every start/cancel so a pending timeout can tell whether
+/// it still belongs to the current quit.
+static QUIT_GENERATION: AtomicU64 = AtomicU64::new(0);
 // IMPORTANT: A coordinated quit can be "in progress" while we still need to
 // block OS quit requests until all windows have handled unsaved changes.
 // This flag is only set to true immediately before calling `app.exit(0)`.
 static EXIT_ALLOWED: AtomicBool = AtomicBool::new(false);
 static QUIT_TARGETS: LazyLock<Mutex<HashSet<String>>> =
     LazyLock::new(|| Mutex::new(HashSet::new()));
+/// Quit targets that answered `app:quit-requested` (`quit_ack`), and
+/// whether they are prompting about unsaved changes
+static QUIT_ACKS: LazyLock<Mutex<HashMap<String, bool>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+/// Incremented whenever the quit timeout restarts, so only the latest
+/// timer acts.
+static TIMEOUT_GENERATION: AtomicU64 = AtomicU64::new(0);
+/// Document windows the current quit started with, sorted
+static QUIT_STARTED_WITH: Mutex<Vec<String>> = Mutex::new(Vec::new());
+/// The document window saving for a Save All and Quit request.
+static SAVE_ALL_WINDOW: Mutex<Option<String>> = Mutex::new(None);
+/// Incremented by every Save All and Quit so a pending timeout can tell
+/// whether it still belongs to the current request.
+static SAVE_ALL_GENERATION: AtomicU64 = AtomicU64::new(0);
+/// Time a Save All and Quit waits for the saves, including any save
+/// dialogs for untitled documents, before giving up.
+const SAVE_ALL_TIMEOUT: Duration = Duration::from_secs(300);
 
 /// Determine whether a window label is a document window.
 pub fn is_document_window_label(label: &str) -> bool {
@@ -23,6 +45,11 @@ pub fn is_exit_allowed() -> bool {
     EXIT_ALLOWED.load(Ordering::SeqCst)
 }
 
+/// Whether a coordinated quit is running.
+pub fn is_quit_in_progress() -> bool {
+    QUIT_IN_PROGRESS.load(Ordering::SeqCst)
+}
+
 fn set_exit_allowed(allowed: bool) {
     EXIT_ALLOWED.store(allowed, Ordering::SeqCst);
 }
@@ -46,36 +73,368 @@ pub fn start_quit(app: &AppHandle) {
     if QUIT_IN_PROGRESS.swap(true, Ordering::SeqCst) {
         return;
     }
+    // A plain quit supersedes a Save All and Quit still waiting for saves
+    reset_save_all();
     set_exit_allowed(false);
+    // Windows are destroyed one by one below; capture them for session restore first
+    session::snapshot_for_quit();
+
+    let targets: HashSet<String> = app
+        .webview_windows()
+        .into_keys()
+        .filter(|label| is_document_window_label(label))
+        .collect();
+
+    if targets.is_empty() {
+        // Keep QUIT_IN_PROGRESS true so ExitRequested handler allows exit
+        finish_quit(app);
+        return;
+    }
 
-    let mut targets = HashSet::new();
+    if let Ok(mut started_with) = QUIT_STARTED_WITH.lock() {
+        let mut labels: Vec<String> = targets.iter().cloned().collect();
+        labels.sort();
+        *started_with = labels;
+    }
+    set_quit_targets(targets);
+    if let Ok(mut acks) = QUIT_ACKS.lock() {
+        acks.clear();
+    }
+    let generation = QUIT_GENERATION.fetch_add(1, Ordering::SeqCst) + 1;
+
+    // Let MCP clients wrap up in-flight edits while the windows are still there
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        mcp_server::announce_shutdown().await;
+        if QUIT_GENERATION.load(Ordering::SeqCst) != generation {
+            return;
+        }
+        request_window_close(&app);
+        schedule_timeout(&app, generation);
+    });
+}
+
+/// Ask the remaining quit targets to close; other windows close immediately.
+fn request_window_close(app: &AppHandle) {
+    let targets = remaining_quit_targets();
     for (label, window) in app.webview_windows() {
-        if is_document_window_label(&label) {
-            targets.insert(label.clone());
+        if targets.contains(&label) {
             let _ = window.emit("app:quit-requested", label);
-        } else {
-            // Close non-document windows immediately
+        } else if !is_document_window_label(&label) {
             let _ = window.close();
         }
     }
+}
 
-    if targets.is_empty() {
-        // Keep QUIT_IN_PROGRESS true so ExitRequested handler allows exit
-        set_exit_allowed(true);
-        mcp_server::cleanup();
-        app.exit(0);
+/// Exit the app at the end of a coordinated quit.
+fn finish_quit(app: &AppHandle) {
+    // Allow the ExitRequested handler through (some platforms trigger it again during quit).
+    // Also makes a second call (force quit racing the last window) a no-op.
+    if EXIT_ALLOWED.swap(true, Ordering::SeqCst) {
         return;
     }
+    mcp_server::cleanup();
+    session::save_on_exit();
+    positions::flush();
+    #[cfg(target_os = "windows")]
+    crate::windows_system_events::release_shutdown_block(app);
+    if updater::install_downloaded() {
+        // Restarting skips RunEvent::Exit
+        crate::crash_reports::mark_clean_exit();
+        app.restart();
+    }
+    app.exit(0);
+}
 
-    set_quit_targets(targets);
+fn remaining_quit_targets() -> Vec<String> {
+    QUIT_TARGETS
+        .lock()
+        .map(|guard| guard.iter().cloned().collect())
+        .unwrap_or_default()
+}
+
+/// Whether a quit target is prompting about unsaved changes.
+fn any_prompt_pending() -> bool {
+    QUIT_ACKS
+        .lock()
+        .map(|acks| acks.values().any(|pending| *pending))
+        .unwrap_or(false)
+}
+
+/// Quit targets that may be closed without losing changes the user is
+/// being asked about: the ones that never answered or have nothing to save.
+fn unresponsive_targets() -> Vec<String> {
+    let acks = QUIT_ACKS.lock().map(|acks| acks.clone()).unwrap_or_default();
+    remaining_quit_targets()
+        .into_iter()
+        .filter(|label| acks.get(label) != Some(&true))
+        .collect()
+}
+
+/// Check on the quit after the configured timeout, unless it finished or
+/// was cancelled by then. A timeout of 0 waits indefinitely. The timeout
+/// only runs while no window is prompting about unsaved changes; it
+/// restarts once the last prompt is answered.
+fn schedule_timeout(app: &AppHandle, generation: u64) {
+    let timeout_generation = TIMEOUT_GENERATION.fetch_add(1, Ordering::SeqCst) + 1;
+    let seconds = window_settings::current().quit_timeout_seconds;
+    if seconds == 0 {
+        return;
+    }
+    let app = app.clone();
+    std::thread::spawn(move || {
+        std::thread::sleep(Duration::from_secs(seconds));
+        if QUIT_IN_PROGRESS.load(Ordering::SeqCst)
+            && QUIT_GENERATION.load(Ordering::SeqCst) == generation
+            && TIMEOUT_GENERATION.load(Ordering::SeqCst) == timeout_generation
+            && !any_prompt_pending()
+        {
+            handle_timeout(&app, generation);
+        }
+    });
+}
+
+/// Some document windows never closed (hung or crashed webview). Ask
+/// whether to force quit or keep waiting; the question is skipped when
+/// `forceQuitOnTimeout` is set. Windows prompting about unsaved changes
+/// are never force quit.
+fn handle_timeout(app: &AppHandle, generation: u64) {
+    use tauri_plugin_dialog::{DialogExt, MessageDialogButtons, MessageDialogKind};
+
+    let remaining = unresponsive_targets();
+    if remaining.is_empty() {
+        return;
+    }
+    #[cfg(debug_assertions)]
+    eprintln!("[Tauri] quit timed out waiting for: {:?}", remaining);
+
+    if window_settings::current().force_quit_on_timeout {
+        force_quit_targets(app, &remaining);
+        return;
+    }
+
+    let tr = crate::menu_i18n::tr;
+    let app_handle = app.clone();
+    app.dialog()
+        .message(tr(
+            "Some windows did not respond to the quit request. Unsaved changes in them will be lost if you force quit.",
+        ))
+        .title(tr("Windows Not Responding"))
+        .kind(MessageDialogKind::Warning)
+        .buttons(MessageDialogButtons::OkCancelCustom(
+            tr("Force Quit"),
+            tr("Keep Waiting"),
+        ))
+        .show(move |force| {
+            // The quit may have completed or been cancelled while the dialog was up.
+            if !QUIT_IN_PROGRESS.load(Ordering::SeqCst)
+                || QUIT_GENERATION.load(Ordering::SeqCst) != generation
+            {
+                return;
+            }
+            if any_prompt_pending() {
+                // A prompt came up meanwhile; its answer restarts the timeout
+                return;
+            }
+            if force {
+                force_quit_targets(&app_handle, &unresponsive_targets());
+            } else {
+                schedule_timeout(&app_handle, generation);
+            }
+        });
+}
+
+/// Destroy the unresponsive windows without waiting for their frontend,
+/// then exit. Destroying also drives `handle_window_destroyed`; exiting
+/// here covers webviews too broken to be destroyed.
+fn force_quit_targets(app: &AppHandle, labels: &[String]) {
+    for label in labels {
+        if let Some(window) = app.get_webview_window(label) {
+            let _ = window.destroy();
+        }
+    }
+    finish_quit(app);
+}
+
+fn reset_save_all() {
+    SAVE_ALL_GENERATION.fetch_add(1, Ordering::SeqCst);
+    if let Ok(mut guard) = SAVE_ALL_WINDOW.lock() {
+        *guard = None;
+    }
+}
+
+/// End the Save All and Quit `label` is saving for; false if it is not.
+fn take_save_all_window(label: &str) -> bool {
+    let Ok(mut guard) = SAVE_ALL_WINDOW.lock() else {
+        return false;
+    };
+    if guard.as_deref() != Some(label) {
+        return false;
+    }
+    *guard = None;
+    SAVE_ALL_GENERATION.fetch_add(1, Ordering::SeqCst);
+    true
+}
+
+/// Start Save All and Quit: `window` is asked to save the documents of
+/// every window (`menu:save-all-quit`) and reports back with
+/// `save_all_result`. The coordinated quit starts once everything is
+/// saved, so no unsaved-changes prompts are left to answer.
+pub fn start_save_all_quit(app: &AppHandle, window: Option<WebviewWindow>) {
+    if QUIT_IN_PROGRESS.load(Ordering::SeqCst) {
+        return;
+    }
+    let Some(window) = window else {
+        // No document windows - nothing to save, just quit
+        start_quit(app);
+        return;
+    };
+    let label = window.label().to_string();
+    {
+        let Ok(mut guard) = SAVE_ALL_WINDOW.lock() else {
+            return;
+        };
+        if guard.is_some() {
+            return;
+        }
+        *guard = Some(label.clone());
+    }
+    let generation = SAVE_ALL_GENERATION.fetch_add(1, Ordering::SeqCst) + 1;
+    let _ = app.emit_to(label.as_str(), "menu:save-all-quit", &label);
+
+    std::thread::spawn(move || {
+        std::thread::sleep(SAVE_ALL_TIMEOUT);
+        if SAVE_ALL_GENERATION.load(Ordering::SeqCst) == generation {
+            #[cfg(debug_assertions)]
+            eprintln!("[Tauri] save all and quit timed out waiting for: {}", label);
+            reset_save_all();
+        }
+    });
+}
+
+/// Report whether a window saved all documents for Save All and Quit.
+/// A failed or cancelled save ends the request and the app keeps running.
+#[tauri::command]
+pub fn save_all_result(app: AppHandle, label: String, saved: bool, error: Option<String>) {
+    if !take_save_all_window(&label) {
+        return;
+    }
+    if saved {
+        start_quit(&app);
+    } else if let Some(_e) = error {
+        #[cfg(debug_assertions)]
+        eprintln!("[Tauri] save all and quit failed: {}", _e);
+    }
+}
+
+/// The OS is shutting down, restarting or logging out. Run the coordinated
+/// quit so unsaved changes are prompted for instead of being lost.
+#[cfg(any(target_os = "macos", target_os = "windows"))]
+pub fn handle_session_end(app: &AppHandle) {
+    #[cfg(debug_assertions)]
+    eprintln!("[Tauri] system session ending, starting quit flow");
+    start_quit(app);
+}
+
+/// The system is about to sleep. Persist the session and let document
+/// windows flush their recovery snapshots (`app:system-sleep`).
+#[cfg(any(target_os = "macos", target_os = "windows"))]
+pub fn handle_system_sleep(app: &AppHandle) {
+    session::save_now();
+    positions::flush();
+    for (label, window) in app.webview_windows() {
+        if is_document_window_label(&label) {
+            let _ = window.emit("app:system-sleep", label);
+        }
+    }
+}
+
+/// Payload of `app:quit-cancelled`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct QuitCancelled {
+    /// Window whose prompt was cancelled
+    #[serde(rename = "cancelledBy")]
+    pub cancelled_by: String,
+    /// Windows that already closed during the quit; they stay closed
+    #[serde(rename = "closedWindows")]
+    pub closed_windows: Vec<String>,
+    /// Document windows still open
+    #[serde(rename = "openWindows")]
+    pub open_windows: Vec<String>,
+}
+
+/// Split the windows a quit started with into closed and still open.
+fn partition_closed(
+    started_with: &[String],
+    remaining: &HashSet<String>,
+) -> (Vec<String>, Vec<String>) {
+    started_with
+        .iter()
+        .cloned()
+        .partition(|label| !remaining.contains(label))
+}
+
+/// A document window received `app:quit-requested`. `prompt_pending` is
+/// true while it asks the user about unsaved changes; the quit timeout
+/// waits for that answer.
+#[tauri::command]
+pub fn quit_ack(app: AppHandle, window: WebviewWindow, prompt_pending: bool) {
+    let label = window.label().to_string();
+    if !QUIT_IN_PROGRESS.load(Ordering::SeqCst) || !remaining_quit_targets().contains(&label) {
+        return;
+    }
+    let was_pending = any_prompt_pending();
+    if let Ok(mut acks) = QUIT_ACKS.lock() {
+        acks.insert(label, prompt_pending);
+    }
+    if was_pending && !any_prompt_pending() {
+        schedule_timeout(&app, QUIT_GENERATION.load(Ordering::SeqCst));
+    }
 }
 
 /// Cancel an in-progress quit (e.g., user cancelled save prompt).
+///
+/// Windows that already closed stay closed; the session keeps tracking the
+/// open ones (closed windows left it when they were destroyed). Every open
+/// document window gets `app:quit-cancelled` so windows still waiting on
+/// the quit can reset, and the same details are returned to the caller.
 #[tauri::command]
-pub fn cancel_quit() {
-    QUIT_IN_PROGRESS.store(false, Ordering::SeqCst);
+pub fn cancel_quit(app: AppHandle, window: WebviewWindow) -> Option<QuitCancelled> {
+    if !QUIT_IN_PROGRESS.swap(false, Ordering::SeqCst) {
+        return None;
+    }
+    QUIT_GENERATION.fetch_add(1, Ordering::SeqCst);
     set_exit_allowed(false);
-    set_quit_targets(HashSet::new());
+    reset_save_all();
+
+    let remaining: HashSet<String> = QUIT_TARGETS
+        .lock()
+        .map(|mut targets| std::mem::take(&mut *targets))
+        .unwrap_or_default();
+    let started_with = QUIT_STARTED_WITH
+        .lock()
+        .map(|mut started| std::mem::take(&mut *started))
+        .unwrap_or_default();
+    let (closed_windows, open_windows) = partition_closed(&started_with, &remaining);
+
+    mcp_server::cancel_shutdown();
+    session::clear_quit_snapshot();
+    #[cfg(target_os = "windows")]
+    crate::windows_system_events::release_shutdown_block(&app);
+
+    let cancelled = QuitCancelled {
+        cancelled_by: window.label().to_string(),
+        closed_windows,
+        open_windows,
+    };
+    #[cfg(debug_assertions)]
+    eprintln!("[Tauri] quit cancelled: {:?}", cancelled);
+    for label in app.webview_windows().into_keys() {
+        if is_document_window_label(&label) {
+            let _ = app.emit_to(label.as_str(), "app:quit-cancelled", &cancelled);
+        }
+    }
+    Some(cancelled)
 }
 
 /// Handle a window being destroyed while quit is in progress.
@@ -84,21 +443,28 @@ pub fn handle_window_destroyed(app: &AppHandle, label: &str) {
     #[cfg(debug_assertions)]
     eprintln!("[Tauri] handle_window_destroyed: label={}, quit_in_progress={}", label, quit_in_progress);
 
-    if !quit_in_progress {
+    if !is_document_window_label(label) {
         return;
     }
 
-    if !is_document_window_label(label) {
+    // The saving window is gone; its saves may not have finished
+    take_save_all_window(label);
+
+    if !quit_in_progress {
         return;
     }
 
+    let was_pending = any_prompt_pending();
+    if let Ok(mut acks) = QUIT_ACKS.lock() {
+        acks.remove(label);
+    }
     if remove_quit_target(label) {
         #[cfg(debug_assertions)]
         eprintln!("[Tauri] handle_window_destroyed: all targets done, calling app.exit(0)");
-        // Allow the ExitRequested handler through (some platforms trigger it again during quit).
-        set_exit_allowed(true);
-        mcp_server::cleanup();
-        app.exit(0);
+        finish_quit(app);
+    } else if was_pending && !any_prompt_pending() {
+        // The last prompt was answered by saving or discarding
+        schedule_timeout(app, QUIT_GENERATION.load(Ordering::SeqCst));
     }
 }
 
@@ -112,5 +478,16 @@ mod tests {
         assert!(is_document_window_label("doc-0"));
         assert!(is_document_window_label("doc-123"));
         assert!(!is_document_window_label("settings"));
+        assert!(!is_document_window_label("quick-note"));
+    }
+
+    #[test]
+    fn test_partition_closed() {
+        let started_with = vec!["doc-1".to_string(), "doc-2".to_string(), "main".to_string()];
+        let remaining: HashSet<String> = ["doc-2".to_string()].into_iter().collect();
+
+        let (closed, open) = partition_closed(&started_with, &remaining);
+        assert_eq!(closed, vec!["doc-1", "main"]);
+        assert_eq!(open, vec!["doc-2"]);
     }
 }
@@ -0,0 +1,309 @@
+//! Crash-recovery journal for unsaved content.
+//!
+//! While a document has unsaved changes the frontend periodically sends a
+//! snapshot of it; each document is journaled as `<hash>.md` plus a
+//! `<hash>.json` metadata file in `~/.vmark/recovery/`, where the hash is
+//! derived from the document id (file path, or a tab id for untitled
+//! documents). The frontend removes the entry once the document is saved or
+//! its changes are discarded, so whatever is left on the next launch was
+//! lost in a crash or force quit and can be offered for restoration.
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use crate::cjk_format::is_cjk;
+use crate::document_io;
+
+/// Serializes journal writes so a snapshot and its metadata stay paired.
+static LOCK: Mutex<()> = Mutex::new(());
+
+/// Unsaved content of one document, as sent by the frontend.
+#[derive(Debug, Clone, Deserialize)]
+pub struct RecoverySnapshot {
+    /// File path for saved documents, a stable tab id for untitled ones
+    #[serde(rename = "documentId")]
+    pub document_id: String,
+    #[serde(rename = "filePath", default)]
+    pub file_path: Option<String>,
+    #[serde(default)]
+    pub title: String,
+    pub content: String,
+}
+
+/// Metadata of a journaled snapshot.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecoveryFile {
+    /// Journal id (hash of the document id)
+    pub id: String,
+    #[serde(rename = "documentId")]
+    pub document_id: String,
+    #[serde(rename = "filePath", default)]
+    pub file_path: Option<String>,
+    #[serde(default)]
+    pub title: String,
+    /// Unix timestamp in milliseconds
+    #[serde(rename = "savedAt")]
+    pub saved_at: i64,
+}
+
+/// What the "Restore unsaved documents?" dialog shows for a snapshot.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RecoverableDocument {
+    pub id: String,
+    #[serde(rename = "documentId")]
+    pub document_id: String,
+    /// Path of the original file; None for untitled documents
+    #[serde(rename = "originalPath")]
+    pub original_path: Option<String>,
+    pub title: String,
+    /// Unix timestamp in milliseconds
+    #[serde(rename = "snapshotAt")]
+    pub snapshot_at: i64,
+    #[serde(rename = "wordCount")]
+    pub word_count: usize,
+    pub preview: String,
+}
+
+/// Characters of content shown in a preview
+const PREVIEW_CHARS: usize = 160;
+
+fn recovery_dir() -> Option<PathBuf> {
+    dirs::home_dir().map(|home| home.join(".vmark").join("recovery"))
+}
+
+/// FNV-1a checksum of the id, so journal names stay the same across builds.
+fn journal_id(document_id: &str) -> String {
+    document_io::checksum(document_id.as_bytes())
+}
+
+/// Journal ids are generated here; reject anything else so ids from the
+/// frontend can't address files outside the journal.
+fn is_journal_id(id: &str) -> bool {
+    id.len() == 16 && id.chars().all(|c| c.is_ascii_hexdigit())
+}
+
+fn write_snapshot(dir: &Path, snapshot: &RecoverySnapshot, saved_at: i64) -> Result<(), String> {
+    fs::create_dir_all(dir).map_err(|e| format!("Failed to create recovery directory: {e}"))?;
+    let id = journal_id(&snapshot.document_id);
+    let meta = RecoveryFile {
+        id: id.clone(),
+        document_id: snapshot.document_id.clone(),
+        file_path: snapshot.file_path.clone(),
+        title: snapshot.title.clone(),
+        saved_at,
+    };
+    let meta = serde_json::to_string_pretty(&meta)
+        .map_err(|e| format!("Failed to serialize recovery metadata: {e}"))?;
+
+    // Write to temp files first so a crash mid-write never leaves a
+    // truncated snapshot behind.
+    let content_path = dir.join(format!("{id}.md"));
+    let meta_path = dir.join(format!("{id}.json"));
+    let content_tmp = dir.join(format!("{id}.md.tmp"));
+    let meta_tmp = dir.join(format!("{id}.json.tmp"));
+    fs::write(&content_tmp, &snapshot.content)
+        .and_then(|_| fs::write(&meta_tmp, meta))
+        .and_then(|_| fs::rename(&content_tmp, &content_path))
+        .and_then(|_| fs::rename(&meta_tmp, &meta_path))
+        .map_err(|e| format!("Failed to write recovery snapshot: {e}"))
+}
+
+fn remove_snapshot(dir: &Path, id: &str) {
+    let _ = fs::remove_file(dir.join(format!("{id}.md")));
+    let _ = fs::remove_file(dir.join(format!("{id}.json")));
+}
+
+/// Journaled snapshots, newest first. Metadata without content is skipped.
+fn list_snapshots(dir: &Path) -> Vec<RecoveryFile> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut files: Vec<RecoveryFile> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+        .filter_map(|path| {
+            let meta: RecoveryFile = serde_json::from_str(&fs::read_to_string(&path).ok()?).ok()?;
+            (is_journal_id(&meta.id) && dir.join(format!("{}.md", meta.id)).is_file())
+                .then_some(meta)
+        })
+        .collect();
+    files.sort_by_key(|file| std::cmp::Reverse(file.saved_at));
+    files
+}
+
+/// Words in `text`; each CJK character counts as one word.
+fn word_count(text: &str) -> usize {
+    text.split_whitespace()
+        .map(|token| {
+            let cjk = token.chars().filter(|c| is_cjk(*c)).count();
+            let has_other = token.chars().any(|c| c.is_alphanumeric() && !is_cjk(c));
+            cjk + usize::from(has_other)
+        })
+        .sum()
+}
+
+/// Start of the content on one line, without frontmatter.
+fn preview(text: &str) -> String {
+    let body = text
+        .strip_prefix("---\n")
+        .and_then(|rest| rest.split_once("\n---\n").map(|(_, body)| body))
+        .unwrap_or(text);
+    let joined = body
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ");
+    match joined.char_indices().nth(PREVIEW_CHARS) {
+        Some((end, _)) => format!("{}…", &joined[..end]),
+        None => joined,
+    }
+}
+
+fn recoverable_documents(dir: &Path) -> Vec<RecoverableDocument> {
+    list_snapshots(dir)
+        .into_iter()
+        .map(|file| {
+            let content =
+                fs::read_to_string(dir.join(format!("{}.md", file.id))).unwrap_or_default();
+            RecoverableDocument {
+                word_count: word_count(&content),
+                preview: preview(&content),
+                id: file.id,
+                document_id: file.document_id,
+                original_path: file.file_path,
+                title: file.title,
+                snapshot_at: file.saved_at,
+            }
+        })
+        .collect()
+}
+
+/// Journal the unsaved content of a document, replacing its previous
+/// snapshot.
+#[tauri::command]
+pub fn save_recovery_snapshot(snapshot: RecoverySnapshot) -> Result<(), String> {
+    let dir = recovery_dir().ok_or("Cannot find home directory")?;
+    let _guard = LOCK.lock();
+    write_snapshot(&dir, &snapshot, chrono::Utc::now().timestamp_millis())
+}
+
+/// Drop the snapshot of a document that was saved or whose changes were
+/// discarded.
+#[tauri::command]
+pub fn remove_recovery_snapshot(document_id: String) {
+    let Some(dir) = recovery_dir() else {
+        return;
+    };
+    let _guard = LOCK.lock();
+    remove_snapshot(&dir, &journal_id(&document_id));
+}
+
+/// Snapshots left over from a previous run.
+#[tauri::command]
+pub fn list_recovery_files() -> Vec<RecoveryFile> {
+    recovery_dir()
+        .map(|dir| list_snapshots(&dir))
+        .unwrap_or_default()
+}
+
+/// Drafts left over from a previous run, newest first, with what a
+/// restore dialog needs to describe them.
+#[tauri::command]
+pub fn list_recoverable_documents() -> Vec<RecoverableDocument> {
+    recovery_dir()
+        .map(|dir| recoverable_documents(&dir))
+        .unwrap_or_default()
+}
+
+/// Delete a draft the user chose not to restore.
+#[tauri::command]
+pub fn discard_recovery(id: String) -> Result<(), String> {
+    if !is_journal_id(&id) {
+        return Err(format!("Invalid recovery id: {id}"));
+    }
+    let dir = recovery_dir().ok_or("Cannot find home directory")?;
+    let _guard = LOCK.lock();
+    remove_snapshot(&dir, &id);
+    Ok(())
+}
+
+/// Content of a journaled snapshot.
+#[tauri::command]
+pub fn read_recovery_file(id: String) -> Result<String, String> {
+    if !is_journal_id(&id) {
+        return Err(format!("Invalid recovery id: {id}"));
+    }
+    let dir = recovery_dir().ok_or("Cannot find home directory")?;
+    fs::read_to_string(dir.join(format!("{id}.md")))
+        .map_err(|e| format!("Failed to read recovery file: {e}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_snapshot_round_trip() {
+        let dir = tempdir().unwrap();
+        let snapshot = |content: &str| RecoverySnapshot {
+            document_id: "/notes/todo.md".into(),
+            file_path: Some("/notes/todo.md".into()),
+            title: "todo.md".into(),
+            content: content.into(),
+        };
+
+        write_snapshot(dir.path(), &snapshot("draft"), 1).unwrap();
+        write_snapshot(dir.path(), &snapshot("draft 2"), 2).unwrap();
+        let untitled = RecoverySnapshot {
+            document_id: "tab-7".into(),
+            file_path: None,
+            title: "Untitled".into(),
+            content: "new".into(),
+        };
+        write_snapshot(dir.path(), &untitled, 3).unwrap();
+
+        let files = list_snapshots(dir.path());
+        assert_eq!(files.len(), 2);
+        assert_eq!(files[0].document_id, "tab-7");
+        assert_eq!(files[1].saved_at, 2);
+        let content = fs::read_to_string(dir.path().join(format!("{}.md", files[1].id))).unwrap();
+        assert_eq!(content, "draft 2");
+
+        remove_snapshot(dir.path(), &journal_id("tab-7"));
+        assert_eq!(list_snapshots(dir.path()).len(), 1);
+    }
+
+    #[test]
+    fn test_recoverable_documents() {
+        let dir = tempdir().unwrap();
+        let snapshot = RecoverySnapshot {
+            document_id: "/notes/todo.md".into(),
+            file_path: Some("/notes/todo.md".into()),
+            title: "todo.md".into(),
+            content: "---\ntags: [a]\n---\n# Plan\n\nShip it 今天\n".into(),
+        };
+        write_snapshot(dir.path(), &snapshot, 5).unwrap();
+
+        let docs = recoverable_documents(dir.path());
+        assert_eq!(docs.len(), 1);
+        assert_eq!(docs[0].original_path.as_deref(), Some("/notes/todo.md"));
+        assert_eq!(docs[0].snapshot_at, 5);
+        assert_eq!(docs[0].preview, "# Plan Ship it 今天");
+        assert_eq!(word_count("# Plan\n\nShip it 今天"), 5);
+        assert_eq!(preview(&"x".repeat(200)).chars().count(), PREVIEW_CHARS + 1);
+    }
+
+    #[test]
+    fn test_journal_id() {
+        let id = journal_id("/notes/todo.md");
+        assert!(is_journal_id(&id));
+        assert_eq!(id, journal_id("/notes/todo.md"));
+        assert_ne!(id, journal_id("/notes/done.md"));
+        assert!(!is_journal_id("../session.json"));
+    }
+}
@@ -0,0 +1,30 @@
+//! Reveal files in the platform file manager (Finder, Explorer, ...).
+//!
+//! Used by the File menu item for the focused document, by the file tree
+//! through `reveal_in_file_manager`, and by context menus that include a
+//! `revealInFileManager` entry.
+
+use std::path::Path;
+
+/// Menu label of the reveal action on this platform.
+#[cfg(target_os = "macos")]
+pub const REVEAL_LABEL: &str = "Reveal in Finder";
+#[cfg(target_os = "windows")]
+pub const REVEAL_LABEL: &str = "Show in Explorer";
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+pub const REVEAL_LABEL: &str = "Show in File Manager";
+
+/// Open the containing folder of `path` with the item selected.
+pub fn reveal(path: &Path) -> Result<(), String> {
+    if !path.exists() {
+        return Err(format!("Path does not exist: {}", path.display()));
+    }
+    tauri_plugin_opener::reveal_item_in_dir(path)
+        .map_err(|e| format!("Failed to reveal {}: {e}", path.display()))
+}
+
+/// Show a file or folder in Finder/Explorer/the file manager.
+#[tauri::command]
+pub fn reveal_in_file_manager(path: String) -> Result<(), String> {
+    reveal(Path::new(&path))
+}
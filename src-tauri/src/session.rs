@@ -0,0 +1,355 @@
+//! Session restore.
+//!
+//! Each document window reports its workspace and open files. When a
+//! coordinated quit starts, the set of windows is snapshotted (windows are
+//! destroyed one by one during quit, so the registry empties out), and the
+//! snapshot is written to `~/.vmark/session.json` right before `app.exit(0)`.
+//! On the next launch the frontend can call `restore_session` to reopen them.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{LazyLock, Mutex};
+use tauri::{AppHandle, Manager, WebviewWindow};
+
+use crate::{quit, window_geometry, window_manager, window_registry};
+
+/// What a document window had open.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct WindowSession {
+    #[serde(
+        rename = "workspaceRoot",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub workspace_root: Option<String>,
+    #[serde(default)]
+    pub files: Vec<String>,
+    #[serde(
+        rename = "activeFile",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub active_file: Option<String>,
+    /// Window floats above other apps
+    #[serde(
+        rename = "alwaysOnTop",
+        default,
+        skip_serializing_if = "std::ops::Not::not"
+    )]
+    pub always_on_top: bool,
+    /// Webview zoom factor
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub zoom: Option<f64>,
+    /// Window title (live only, see `window_registry`)
+    #[serde(skip)]
+    pub title: String,
+    /// Unsaved changes (live only, see `window_registry`)
+    #[serde(skip)]
+    pub dirty: bool,
+}
+
+impl WindowSession {
+    fn is_empty(&self) -> bool {
+        self.workspace_root.is_none() && self.files.is_empty()
+    }
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct SavedSession {
+    pub windows: Vec<WindowSession>,
+    /// Unix timestamp in milliseconds
+    #[serde(rename = "savedAt", default)]
+    pub saved_at: i64,
+}
+
+/// Live window sessions keyed by label (BTreeMap keeps restore order stable)
+static WINDOWS: LazyLock<Mutex<BTreeMap<String, WindowSession>>> =
+    LazyLock::new(|| Mutex::new(BTreeMap::new()));
+
+/// Snapshot taken when quit starts, written on exit
+static QUIT_SNAPSHOT: Mutex<Option<Vec<WindowSession>>> = Mutex::new(None);
+
+fn session_file_path() -> Option<PathBuf> {
+    dirs::home_dir().map(|home| home.join(".vmark").join("session.json"))
+}
+
+fn read_session(path: &Path) -> Option<SavedSession> {
+    let content = fs::read_to_string(path).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+fn write_session(path: &Path, session: &SavedSession) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create ~/.vmark directory: {e}"))?;
+    }
+    let content = serde_json::to_string_pretty(session)
+        .map_err(|e| format!("Failed to serialize session: {e}"))?;
+    fs::write(path, content).map_err(|e| format!("Failed to write session: {e}"))
+}
+
+/// Drop files that no longer exist; drop windows left with nothing to show.
+fn prune_missing(windows: Vec<WindowSession>) -> Vec<WindowSession> {
+    windows
+        .into_iter()
+        .map(|mut w| {
+            w.files.retain(|f| Path::new(f).is_file());
+            if w.workspace_root
+                .as_deref()
+                .is_some_and(|r| !Path::new(r).is_dir())
+            {
+                w.workspace_root = None;
+            }
+            if w.active_file.as_ref().is_some_and(|f| !w.files.contains(f)) {
+                w.active_file = None;
+            }
+            w
+        })
+        .filter(|w| !w.is_empty())
+        .collect()
+}
+
+/// Record a window's always-on-top state.
+pub fn set_always_on_top(label: &str, on: bool) {
+    if let Ok(mut windows) = WINDOWS.lock() {
+        windows.entry(label.to_string()).or_default().always_on_top = on;
+    }
+}
+
+/// Record a window's zoom factor.
+pub fn set_zoom(label: &str, zoom: f64) {
+    if let Ok(mut windows) = WINDOWS.lock() {
+        windows.entry(label.to_string()).or_default().zoom = Some(zoom);
+    }
+}
+
+/// Zoom factor recorded for a window, if any.
+pub fn zoom_of(label: &str) -> Option<f64> {
+    WINDOWS.lock().ok()?.get(label)?.zoom
+}
+
+/// Forget a window (called when it is destroyed).
+pub fn remove_window(label: &str) {
+    if let Ok(mut windows) = WINDOWS.lock() {
+        windows.remove(label);
+    }
+}
+
+/// Live state of every window that reported any, keyed by label.
+pub fn live_windows() -> BTreeMap<String, WindowSession> {
+    WINDOWS.lock().map(|w| w.clone()).unwrap_or_default()
+}
+
+/// Update a window's live state in place, returning `f`'s result.
+pub fn update_window<R>(label: &str, f: impl FnOnce(&mut WindowSession) -> R) -> Option<R> {
+    let mut windows = WINDOWS.lock().ok()?;
+    Some(f(windows.entry(label.to_string()).or_default()))
+}
+
+/// Sessions of the document windows that are currently open, in window
+/// creation order.
+fn open_windows() -> Vec<WindowSession> {
+    let mut windows: Vec<(String, WindowSession)> = live_windows()
+        .into_iter()
+        .filter(|(label, s)| quit::is_document_window_label(label) && !s.is_empty())
+        .collect();
+    windows.sort_by(|(a, _), (b, _)| {
+        window_registry::label_order(a).cmp(&window_registry::label_order(b))
+    });
+    windows.into_iter().map(|(_, s)| s).collect()
+}
+
+/// Snapshot open document windows at the start of a coordinated quit.
+pub fn snapshot_for_quit() {
+    let windows = open_windows();
+    if let Ok(mut snapshot) = QUIT_SNAPSHOT.lock() {
+        *snapshot = Some(windows);
+    }
+}
+
+/// Discard the quit snapshot (quit was cancelled).
+pub fn clear_quit_snapshot() {
+    if let Ok(mut snapshot) = QUIT_SNAPSHOT.lock() {
+        *snapshot = None;
+    }
+}
+
+/// Persist the quit snapshot (or the open windows, when exiting without a
+/// coordinated quit). Called right before `app.exit(0)`.
+pub fn save_on_exit() {
+    let windows = QUIT_SNAPSHOT
+        .lock()
+        .ok()
+        .and_then(|mut s| s.take())
+        .unwrap_or_else(open_windows);
+    save_windows(windows);
+}
+
+/// Persist the open windows without quitting (e.g. before the system
+/// sleeps, in case it never wakes up cleanly).
+#[cfg(any(target_os = "macos", target_os = "windows"))]
+pub fn save_now() {
+    save_windows(open_windows());
+}
+
+fn save_windows(windows: Vec<WindowSession>) {
+    let Some(path) = session_file_path() else {
+        return;
+    };
+    let session = SavedSession {
+        windows,
+        saved_at: chrono::Utc::now().timestamp_millis(),
+    };
+    if let Err(_e) = write_session(&path, &session) {
+        #[cfg(debug_assertions)]
+        eprintln!("[session] {}", _e);
+    }
+}
+
+/// Report what a window has open. Also updates the window's geometry key.
+#[tauri::command]
+pub fn update_window_session(
+    window: WebviewWindow,
+    workspace_root: Option<String>,
+    files: Vec<String>,
+    active_file: Option<String>,
+) {
+    let label = window.label().to_string();
+    window_geometry::register_window(
+        &label,
+        window_geometry::context_key(active_file.as_deref(), workspace_root.as_deref()),
+    );
+
+    let rerooted = update_window(&label, |entry| {
+        let rerooted = entry.workspace_root != workspace_root;
+        entry.workspace_root = workspace_root;
+        entry.files = files;
+        entry.active_file = active_file;
+        rerooted
+    });
+    // Shared with `window_registry`, which may not see the change itself
+    if rerooted == Some(true) {
+        crate::menu::refresh_workspace_menus(window.app_handle());
+    }
+}
+
+/// Get the session saved at last quit, without consuming it.
+#[tauri::command]
+pub fn get_last_session() -> Option<SavedSession> {
+    let path = session_file_path()?;
+    read_session(&path).map(|mut s| {
+        s.windows = prune_missing(s.windows);
+        s
+    })
+}
+
+/// Reopen the windows saved at last quit.
+///
+/// The first saved window is returned for the calling window to load itself
+/// (selecting the tab of its `activeFile`); a new window is
+/// created for each remaining one, with its active file's tab selected. The saved session is
+/// consumed so it is restored only once.
+#[tauri::command]
+pub fn restore_session(
+    app: AppHandle,
+    window: WebviewWindow,
+) -> Result<Option<WindowSession>, String> {
+    let Some(path) = session_file_path() else {
+        return Ok(None);
+    };
+    let Some(session) = read_session(&path) else {
+        return Ok(None);
+    };
+    let _ = fs::remove_file(&path);
+
+    let mut windows = prune_missing(session.windows).into_iter();
+    let first = windows.next();
+    if let Some(first) = &first {
+        restore_window_state(&window, first);
+    }
+    for w in windows {
+        let label = window_manager::create_document_window_with_active_file(
+            &app,
+            &w.files,
+            w.workspace_root.as_deref(),
+            w.active_file.as_deref(),
+        )
+        .map_err(|e| format!("Failed to restore window: {e}"))?;
+        if let Some(restored) = app.get_webview_window(&label) {
+            restore_window_state(&restored, &w);
+        }
+    }
+    // New windows take focus as they are created; hand it back to the
+    // window that restores the first (and most recent) session entry
+    let _ = window.set_focus();
+
+    Ok(first)
+}
+
+/// Reapply the per-window state saved with a session.
+fn restore_window_state(window: &WebviewWindow, saved: &WindowSession) {
+    if saved.always_on_top {
+        window_manager::apply_always_on_top(window, true);
+    }
+    if let Some(zoom) = saved.zoom {
+        let _ = window_manager::apply_zoom(window, zoom);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_prune_missing() {
+        let dir = tempdir().unwrap();
+        let kept = dir.path().join("a.md");
+        fs::write(&kept, "").unwrap();
+        let kept = kept.to_string_lossy().to_string();
+        let gone = dir.path().join("gone.md").to_string_lossy().to_string();
+
+        let windows = vec![
+            WindowSession {
+                workspace_root: Some(dir.path().to_string_lossy().to_string()),
+                files: vec![kept.clone(), gone.clone()],
+                active_file: Some(gone.clone()),
+                ..Default::default()
+            },
+            WindowSession {
+                workspace_root: Some("/definitely/not/here".to_string()),
+                files: vec![gone],
+                active_file: None,
+                ..Default::default()
+            },
+        ];
+
+        let pruned = prune_missing(windows);
+        assert_eq!(pruned.len(), 1);
+        assert_eq!(pruned[0].files, vec![kept]);
+        assert_eq!(pruned[0].active_file, None);
+    }
+
+    #[test]
+    fn test_session_round_trip() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("session.json");
+        let session = SavedSession {
+            windows: vec![WindowSession {
+                workspace_root: Some("/ws".to_string()),
+                files: vec!["/ws/a.md".to_string()],
+                active_file: Some("/ws/a.md".to_string()),
+                always_on_top: true,
+                zoom: Some(1.25),
+                ..Default::default()
+            }],
+            saved_at: 1,
+        };
+
+        write_session(&path, &session).unwrap();
+        let read = read_session(&path).unwrap();
+        assert_eq!(read.windows, session.windows);
+    }
+}
@@ -0,0 +1,98 @@
+//! Share the current document with the macOS share sheet (Mail, Messages,
+//! AirDrop, Notes, ...).
+//!
+//! The picker is shown from the top of the window's webview. Only saved
+//! documents can be shared since the file itself is handed to the service.
+
+use tauri::AppHandle;
+#[cfg(target_os = "macos")]
+use tauri::Manager;
+
+use crate::window_registry;
+
+#[cfg(target_os = "macos")]
+mod picker {
+    use std::cell::RefCell;
+    use std::path::Path;
+
+    use objc2::rc::Retained;
+    use objc2::runtime::AnyObject;
+    use objc2::AllocAnyThread;
+    use objc2_app_kit::{NSSharingServicePicker, NSView};
+    use objc2_foundation::{NSArray, NSPoint, NSRect, NSRectEdge, NSSize, NSURL};
+
+    thread_local! {
+        /// The picker on screen; kept alive until the next share.
+        static PICKER: RefCell<Option<Retained<NSSharingServicePicker>>> = const { RefCell::new(None) };
+    }
+
+    /// Show the share picker for `path` anchored to `view`.
+    pub fn show(view: &NSView, path: &Path) -> Result<(), String> {
+        let url = NSURL::from_file_path(path)
+            .ok_or_else(|| format!("Invalid file path: {}", path.display()))?;
+        let item: &AnyObject = &url;
+        let items = NSArray::from_slice(&[item]);
+        let picker = unsafe {
+            NSSharingServicePicker::initWithItems(NSSharingServicePicker::alloc(), &items)
+        };
+
+        // Anchor to a point at the top center of the view.
+        let bounds = view.bounds();
+        let top = if view.isFlipped() {
+            bounds.origin.y
+        } else {
+            bounds.origin.y + bounds.size.height
+        };
+        let anchor = NSRect::new(
+            NSPoint::new(bounds.origin.x + bounds.size.width / 2.0, top),
+            NSSize::new(1.0, 1.0),
+        );
+        picker.showRelativeToRect_ofView_preferredEdge(anchor, view, NSRectEdge::MinY);
+        PICKER.with(|current| *current.borrow_mut() = Some(picker));
+        Ok(())
+    }
+}
+
+/// Path of the saved document in window `label`.
+fn shared_file(label: &str) -> Result<String, String> {
+    let path = window_registry::file_path_of(label).ok_or("Save the document before sharing it")?;
+    if !std::path::Path::new(&path).is_file() {
+        return Err(format!("File does not exist: {path}"));
+    }
+    Ok(path)
+}
+
+/// Show the share sheet for the document in window `label`.
+#[cfg(target_os = "macos")]
+#[tauri::command]
+pub fn share_document(app: AppHandle, label: String) -> Result<(), String> {
+    let path = shared_file(&label)?;
+    let window = app
+        .get_webview_window(&label)
+        .ok_or_else(|| format!("Window not found: {label}"))?;
+    let view = window
+        .ns_view()
+        .map_err(|e| format!("Failed to get window view: {e}"))? as usize;
+
+    window
+        .run_on_main_thread(move || {
+            if objc2::MainThreadMarker::new().is_none() {
+                return;
+            }
+            // SAFETY: `ns_view` is the window's live NSView, used on the
+            // main thread.
+            let view = unsafe { &*(view as *const objc2_app_kit::NSView) };
+            if let Err(_e) = picker::show(view, std::path::Path::new(&path)) {
+                #[cfg(debug_assertions)]
+                eprintln!("[share] {}", _e);
+            }
+        })
+        .map_err(|e| format!("Failed to show share sheet: {e}"))
+}
+
+#[cfg(not(target_os = "macos"))]
+#[tauri::command]
+pub fn share_document(_app: AppHandle, label: String) -> Result<(), String> {
+    shared_file(&label)?;
+    Err("Sharing is only available on macOS".to_string())
+}
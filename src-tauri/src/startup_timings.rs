@@ -0,0 +1,151 @@
+//! Startup performance timings.
+//!
+//! Startup phases (plugin init, setup, menu build, first window ready, MCP
+//! bridge start) are timed relative to the start of `run`. Each phase is
+//! logged once as a `tracing` event with target `startup`, which ends up in
+//! `vmark.log` in the app log directory, and kept for `get_startup_timings`.
+
+use serde::Serialize;
+use std::fs::{self, OpenOptions};
+use std::path::Path;
+use std::sync::{LazyLock, Mutex};
+use std::time::{Duration, Instant};
+
+/// Name of the log file in the app log directory
+pub const LOG_FILE_NAME: &str = "vmark.log";
+
+/// The log file is rotated to `vmark.log.1` once it grows past this
+const MAX_LOG_SIZE: u64 = 5 * 1024 * 1024;
+
+static PROCESS_START: LazyLock<Instant> = LazyLock::new(Instant::now);
+
+static PHASES: Mutex<Vec<PhaseTiming>> = Mutex::new(Vec::new());
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PhaseTiming {
+    pub name: String,
+    /// Milliseconds from the start of `run` to the start of the phase
+    #[serde(rename = "startMs")]
+    pub start_ms: u64,
+    #[serde(rename = "durationMs")]
+    pub duration_ms: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct StartupTimings {
+    pub phases: Vec<PhaseTiming>,
+    /// Milliseconds until the first window was ready, once it is
+    #[serde(rename = "totalMs")]
+    pub total_ms: Option<u64>,
+}
+
+/// A phase being timed; recorded when dropped.
+pub struct Phase {
+    name: &'static str,
+    start: Instant,
+}
+
+impl Drop for Phase {
+    fn drop(&mut self) {
+        record(self.name, self.start, self.start.elapsed());
+    }
+}
+
+/// Anchor the timings. Called first thing in `run`.
+pub fn mark_process_start() {
+    LazyLock::force(&PROCESS_START);
+}
+
+/// Time a phase until the returned guard is dropped.
+pub fn phase(name: &'static str) -> Phase {
+    Phase {
+        name,
+        start: Instant::now(),
+    }
+}
+
+/// Record a phase that started at `start`. Only the first occurrence of
+/// each phase counts; later ones (e.g. restarting the bridge) are not
+/// part of startup.
+pub fn record(name: &str, start: Instant, duration: Duration) {
+    let Ok(mut phases) = PHASES.lock() else {
+        return;
+    };
+    if phases.iter().any(|phase| phase.name == name) {
+        return;
+    }
+    let timing = PhaseTiming {
+        name: name.to_string(),
+        start_ms: start.saturating_duration_since(*PROCESS_START).as_millis() as u64,
+        duration_ms: duration.as_millis() as u64,
+    };
+    tracing::info!(
+        target: "startup",
+        phase = %timing.name,
+        start_ms = timing.start_ms,
+        duration_ms = timing.duration_ms,
+        "startup phase finished"
+    );
+    phases.push(timing);
+}
+
+/// Record a phase that lasted from the start of `run` until now.
+pub fn record_since_start(name: &str) {
+    record(name, *PROCESS_START, PROCESS_START.elapsed());
+}
+
+fn rotate_log(path: &Path) {
+    if fs::metadata(path).is_ok_and(|meta| meta.len() > MAX_LOG_SIZE) {
+        let _ = fs::rename(path, path.with_extension("log.1"));
+    }
+}
+
+/// Send `tracing` output to `vmark.log` in `log_dir`.
+pub fn init_logging(log_dir: &Path) -> Result<(), String> {
+    fs::create_dir_all(log_dir).map_err(|e| format!("Failed to create log directory: {e}"))?;
+    let path = log_dir.join(LOG_FILE_NAME);
+    rotate_log(&path);
+    let file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .map_err(|e| format!("Failed to open log file: {e}"))?;
+    tracing_subscriber::fmt()
+        .with_writer(Mutex::new(file))
+        .with_ansi(false)
+        .with_max_level(tracing::Level::INFO)
+        .try_init()
+        .map_err(|e| format!("Failed to initialize logging: {e}"))
+}
+
+#[tauri::command]
+pub fn get_startup_timings() -> StartupTimings {
+    let phases = PHASES.lock().map(|p| p.clone()).unwrap_or_default();
+    let total_ms = phases
+        .iter()
+        .find(|phase| phase.name == "first_window")
+        .map(|phase| phase.start_ms + phase.duration_ms);
+    StartupTimings { phases, total_ms }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_record_keeps_first_occurrence() {
+        mark_process_start();
+        let start = Instant::now();
+        record("test_phase", start, Duration::from_millis(12));
+        record("test_phase", start, Duration::from_millis(99));
+
+        let timings = get_startup_timings();
+        let recorded: Vec<_> = timings
+            .phases
+            .iter()
+            .filter(|phase| phase.name == "test_phase")
+            .collect();
+        assert_eq!(recorded.len(), 1);
+        assert_eq!(recorded[0].duration_ms, 12);
+    }
+}
@@ -0,0 +1,362 @@
+//! Moving tabs between windows.
+//!
+//! Windows register the files they have open as tabs, so the backend knows
+//! which window owns each file. A transfer is two-phase so unsaved content is
+//! never lost in flight:
+//!
+//! 1. The source window calls `transfer_tab` with the tab's state (content,
+//!    dirty flag, cursor, ...). The target (or a new window when no target is
+//!    given) receives `tab:transfer-incoming` with the transfer id.
+//! 2. The target calls `claim_tab_transfer` to take the state and ownership;
+//!    the source then receives `tab:transfer-complete` and closes its tab.
+//!
+//! If the target goes away before claiming, the source receives
+//! `tab:transfer-cancelled` and keeps the tab; if the source is gone as
+//! well, the tab is handed to a new window instead. A source closing after
+//! starting a transfer leaves it for the target to claim. New windows that
+//! were not listening yet pick up their transfers with
+//! `pending_tab_transfers`.
+
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::{LazyLock, Mutex};
+use tauri::{AppHandle, Emitter, Manager, WebviewWindow};
+
+use crate::{file_locks, window_manager};
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TabTransfer {
+    pub id: String,
+    #[serde(rename = "fromLabel")]
+    pub from_label: String,
+    #[serde(rename = "toLabel")]
+    pub to_label: String,
+    /// None for untitled tabs
+    #[serde(rename = "filePath")]
+    pub file_path: Option<String>,
+    /// Opaque tab state from the source window
+    pub state: Value,
+}
+
+/// Event payload for transfer notifications (state is not included).
+#[derive(Debug, Clone, Serialize)]
+struct TransferNotice {
+    #[serde(rename = "transferId")]
+    transfer_id: String,
+    #[serde(rename = "fromLabel")]
+    from_label: String,
+    #[serde(rename = "toLabel")]
+    to_label: String,
+    #[serde(rename = "filePath")]
+    file_path: Option<String>,
+}
+
+impl From<&TabTransfer> for TransferNotice {
+    fn from(t: &TabTransfer) -> Self {
+        Self {
+            transfer_id: t.id.clone(),
+            from_label: t.from_label.clone(),
+            to_label: t.to_label.clone(),
+            file_path: t.file_path.clone(),
+        }
+    }
+}
+
+/// File ownership and in-flight transfers.
+#[derive(Debug, Default)]
+struct TransferState {
+    /// File path -> label of the window that has it open
+    owners: HashMap<String, String>,
+    /// Transfer id -> transfer awaiting a claim
+    pending: HashMap<String, TabTransfer>,
+}
+
+impl TransferState {
+    /// Validate and record a new transfer.
+    fn begin(&mut self, transfer: TabTransfer) -> Result<(), String> {
+        if transfer.from_label == transfer.to_label {
+            return Err("Cannot transfer a tab to the same window".to_string());
+        }
+        if let Some(path) = &transfer.file_path {
+            if self
+                .pending
+                .values()
+                .any(|t| t.file_path.as_ref() == Some(path))
+            {
+                return Err(format!("A transfer of '{}' is already in progress", path));
+            }
+            match self.owners.get(path) {
+                Some(owner) if owner == &transfer.to_label => {
+                    return Err(format!("'{}' is already open in the target window", path));
+                }
+                Some(owner) if owner != &transfer.from_label => {
+                    return Err(format!("'{}' is open in another window", path));
+                }
+                _ => {}
+            }
+        }
+        self.pending.insert(transfer.id.clone(), transfer);
+        Ok(())
+    }
+
+    /// Complete a transfer for the window that claims it, moving ownership.
+    fn claim(&mut self, id: &str, label: &str) -> Result<TabTransfer, String> {
+        let transfer = self
+            .pending
+            .remove(id)
+            .ok_or_else(|| format!("Transfer '{}' not found", id))?;
+        if transfer.to_label != label {
+            self.pending.insert(id.to_string(), transfer);
+            return Err("Transfer is addressed to another window".to_string());
+        }
+        if let Some(path) = &transfer.file_path {
+            self.owners.insert(path.clone(), label.to_string());
+        }
+        Ok(transfer)
+    }
+
+    /// Drop a window's ownership and return transfers that can no longer
+    /// complete because it was their target. Transfers it started stay
+    /// pending: their state is kept here, so the target can still claim them.
+    fn remove_window(&mut self, label: &str) -> Vec<TabTransfer> {
+        self.owners.retain(|_, owner| owner != label);
+        let ids: Vec<String> = self
+            .pending
+            .values()
+            .filter(|t| t.to_label == label)
+            .map(|t| t.id.clone())
+            .collect();
+        ids.iter().filter_map(|id| self.pending.remove(id)).collect()
+    }
+}
+
+static STATE: LazyLock<Mutex<TransferState>> =
+    LazyLock::new(|| Mutex::new(TransferState::default()));
+
+fn lock_state() -> Result<std::sync::MutexGuard<'static, TransferState>, String> {
+    STATE
+        .lock()
+        .map_err(|_| "Tab transfer lock poisoned".to_string())
+}
+
+/// Forget a destroyed window. Sources of transfers it never claimed are told
+/// to keep their tabs; transfers whose source is gone too move to a new
+/// window so their content is not lost.
+pub fn handle_window_destroyed(app: &AppHandle, label: &str) {
+    let orphaned = match lock_state() {
+        Ok(mut state) => state.remove_window(label),
+        Err(_) => return,
+    };
+    for mut transfer in orphaned {
+        if app.get_webview_window(&transfer.from_label).is_some() {
+            let _ = app.emit_to(
+                transfer.from_label.as_str(),
+                "tab:transfer-cancelled",
+                TransferNotice::from(&transfer),
+            );
+            continue;
+        }
+        // Windows are closing on purpose during quit
+        if crate::quit::is_quit_in_progress() {
+            continue;
+        }
+        match window_manager::create_document_window(app, None, None) {
+            Ok(new_label) => {
+                transfer.to_label = new_label;
+                let notice = TransferNotice::from(&transfer);
+                if let Ok(mut state) = lock_state() {
+                    state.pending.insert(transfer.id.clone(), transfer);
+                }
+                let _ = app.emit_to(notice.to_label.as_str(), "tab:transfer-incoming", &notice);
+            }
+            Err(_e) => {
+                #[cfg(debug_assertions)]
+                eprintln!("[tab_transfer] Failed to create window for transfer: {}", _e);
+            }
+        }
+    }
+}
+
+/// Record that a window has a file open as a tab.
+#[tauri::command]
+pub fn register_tab(window: WebviewWindow, file_path: String) {
+    if let Ok(mut state) = lock_state() {
+        state.owners.insert(file_path, window.label().to_string());
+    }
+}
+
+/// Record that a window closed a file's tab.
+#[tauri::command]
+pub fn unregister_tab(window: WebviewWindow, file_path: String) {
+    if let Ok(mut state) = lock_state() {
+        if state.owners.get(&file_path).map(String::as_str) == Some(window.label()) {
+            state.owners.remove(&file_path);
+        }
+    }
+}
+
+/// Label of the window that has a file open, if any.
+#[tauri::command]
+pub fn file_owner(file_path: String) -> Option<String> {
+    lock_state().ok()?.owners.get(&file_path).cloned()
+}
+
+/// Start moving a tab from `from_label` to `to_label`, or to a new window
+/// when `to_label` is omitted. Returns the transfer id.
+#[tauri::command]
+pub fn transfer_tab(
+    app: AppHandle,
+    from_label: String,
+    to_label: Option<String>,
+    file_path: Option<String>,
+    state: Value,
+) -> Result<String, String> {
+    let new_window = to_label.is_none();
+    let to_label = match to_label {
+        Some(label) => {
+            if app.get_webview_window(&label).is_none() {
+                return Err(format!("Window '{}' not found", label));
+            }
+            label
+        }
+        None => window_manager::create_document_window(&app, None, None)
+            .map_err(|e| format!("Failed to create window: {e}"))?,
+    };
+
+    let transfer = TabTransfer {
+        id: uuid::Uuid::new_v4().to_string(),
+        from_label,
+        to_label,
+        file_path,
+        state,
+    };
+    let notice = TransferNotice::from(&transfer);
+    let id = transfer.id.clone();
+    if let Err(e) = lock_state().and_then(|mut state| state.begin(transfer)) {
+        // Don't leave an empty window behind for a rejected transfer
+        if new_window {
+            if let Some(window) = app.get_webview_window(&notice.to_label) {
+                let _ = window.destroy();
+            }
+        }
+        return Err(e);
+    }
+
+    let _ = app.emit_to(notice.to_label.as_str(), "tab:transfer-incoming", &notice);
+    Ok(id)
+}
+
+/// Transfers waiting for this window to claim them.
+#[tauri::command]
+pub fn pending_tab_transfers(window: WebviewWindow) -> Vec<TabTransfer> {
+    lock_state()
+        .map(|state| {
+            state
+                .pending
+                .values()
+                .filter(|t| t.to_label == window.label())
+                .cloned()
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Take a transfer's tab state. The source window is told it can close
+/// its tab.
+#[tauri::command]
+pub fn claim_tab_transfer(
+    app: AppHandle,
+    window: WebviewWindow,
+    transfer_id: String,
+) -> Result<TabTransfer, String> {
+    let transfer = lock_state()?.claim(&transfer_id, window.label())?;
+    if let Some(path) = &transfer.file_path {
+        file_locks::transfer(path, &transfer.from_label, window.label());
+    }
+    let _ = app.emit_to(
+        transfer.from_label.as_str(),
+        "tab:transfer-complete",
+        TransferNotice::from(&transfer),
+    );
+    Ok(transfer)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn transfer(id: &str, from: &str, to: &str, path: Option<&str>) -> TabTransfer {
+        TabTransfer {
+            id: id.to_string(),
+            from_label: from.to_string(),
+            to_label: to.to_string(),
+            file_path: path.map(String::from),
+            state: json!({ "content": "# draft", "dirty": true }),
+        }
+    }
+
+    #[test]
+    fn test_transfer_moves_ownership() {
+        let mut state = TransferState::default();
+        state.owners.insert("/a.md".into(), "main".into());
+
+        state
+            .begin(transfer("t1", "main", "doc-1", Some("/a.md")))
+            .unwrap();
+        assert!(state.claim("t1", "doc-2").is_err());
+
+        let claimed = state.claim("t1", "doc-1").unwrap();
+        assert_eq!(claimed.state["content"], "# draft");
+        assert_eq!(state.owners["/a.md"], "doc-1");
+        assert!(state.claim("t1", "doc-1").is_err());
+    }
+
+    #[test]
+    fn test_begin_rejects_conflicts() {
+        let mut state = TransferState::default();
+        state.owners.insert("/a.md".into(), "doc-3".into());
+        state.owners.insert("/b.md".into(), "doc-1".into());
+
+        assert!(state.begin(transfer("t0", "main", "main", None)).is_err());
+        // Owned by a third window
+        assert!(state
+            .begin(transfer("t1", "main", "doc-1", Some("/a.md")))
+            .is_err());
+        // Already open in the target
+        assert!(state
+            .begin(transfer("t2", "main", "doc-1", Some("/b.md")))
+            .is_err());
+
+        state
+            .begin(transfer("t3", "main", "doc-1", Some("/c.md")))
+            .unwrap();
+        assert!(state
+            .begin(transfer("t4", "main", "doc-2", Some("/c.md")))
+            .is_err());
+        // Untitled tabs never conflict
+        state.begin(transfer("t5", "main", "doc-2", None)).unwrap();
+    }
+
+    #[test]
+    fn test_remove_window_returns_orphaned_transfers() {
+        let mut state = TransferState::default();
+        state
+            .begin(transfer("t1", "main", "doc-1", Some("/a.md")))
+            .unwrap();
+        state
+            .begin(transfer("t2", "doc-1", "doc-2", Some("/b.md")))
+            .unwrap();
+        state.owners.insert("/c.md".into(), "doc-1".into());
+
+        let cancelled = state.remove_window("doc-1");
+
+        assert_eq!(cancelled.len(), 1);
+        assert_eq!(cancelled[0].id, "t1");
+        // The transfer doc-1 started can still be claimed by doc-2
+        assert!(state.pending.contains_key("t2"));
+        assert!(state.owners.is_empty());
+        assert_eq!(state.claim("t2", "doc-2").unwrap().id, "t2");
+    }
+}
@@ -0,0 +1,226 @@
+//! CSV/TSV to markdown table conversion ("Insert Table from CSV…").
+//!
+//! Data comes from a file, decoded in its detected encoding, or from the
+//! clipboard (cells copied from a spreadsheet arrive tab-separated). The
+//! delimiter is detected unless given. Columns whose cells are all numbers
+//! are right-aligned.
+
+use serde::Deserialize;
+use std::fs;
+use tauri::AppHandle;
+use tauri_plugin_clipboard_manager::ClipboardExt;
+
+use crate::tables::{self, Align, Table};
+use crate::text_encoding;
+
+/// Delimiters tried when none is given, in order of preference
+const DELIMITERS: [u8; 4] = [b'\t', b',', b';', b'|'];
+
+/// Records looked at to detect the delimiter
+const SAMPLE_RECORDS: usize = 20;
+
+fn enabled() -> bool {
+    true
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ImportOptions {
+    /// Field delimiter; detected from the data when absent
+    #[serde(default)]
+    pub delimiter: Option<char>,
+    /// Use the first row as the table header; otherwise the header is empty
+    #[serde(rename = "hasHeader", default = "enabled")]
+    pub has_header: bool,
+}
+
+impl Default for ImportOptions {
+    fn default() -> Self {
+        ImportOptions {
+            delimiter: None,
+            has_header: enabled(),
+        }
+    }
+}
+
+/// Number of `delimiter`s outside quoted fields in each of the first
+/// records of `text` (quoted fields may span lines).
+fn delimiter_counts(text: &str, delimiter: u8) -> Vec<usize> {
+    let mut counts = Vec::new();
+    let mut quoted = false;
+    let mut count = 0;
+    let mut empty = true;
+    for byte in text.bytes().chain(std::iter::once(b'\n')) {
+        match byte {
+            b'"' => quoted = !quoted,
+            b'\n' if !quoted => {
+                if !empty {
+                    counts.push(count);
+                    if counts.len() == SAMPLE_RECORDS {
+                        break;
+                    }
+                }
+                count = 0;
+                empty = true;
+                continue;
+            }
+            b if b == delimiter && !quoted => count += 1,
+            _ => {}
+        }
+        empty &= byte.is_ascii_whitespace();
+    }
+    counts
+}
+
+/// The delimiter that splits the first records into the same, largest
+/// number of fields; a comma if none does.
+fn detect_delimiter(text: &str) -> u8 {
+    DELIMITERS
+        .iter()
+        .filter_map(|&delimiter| {
+            let counts = delimiter_counts(text, delimiter);
+            let first = *counts.first()?;
+            (first > 0 && counts.iter().all(|c| *c == first)).then_some((delimiter, first))
+        })
+        // Ties go to the delimiter listed first
+        .min_by_key(|&(delimiter, count)| {
+            let position = DELIMITERS.iter().position(|d| *d == delimiter);
+            (std::cmp::Reverse(count), position)
+        })
+        .map_or(b',', |(delimiter, _)| delimiter)
+}
+
+/// A field as a table cell: pipes escaped (unless they already are), line
+/// breaks as `<br>`.
+fn to_cell(field: &str) -> String {
+    let mut cell = String::with_capacity(field.len());
+    let mut backslashes = 0;
+    for c in field.trim().chars() {
+        if c == '|' && backslashes % 2 == 0 {
+            cell.push('\\');
+        }
+        backslashes = if c == '\\' { backslashes + 1 } else { 0 };
+        cell.push(c);
+    }
+    cell.replace("\r\n", "<br>").replace('\n', "<br>")
+}
+
+fn parse_csv(text: &str, options: &ImportOptions) -> Result<Table, String> {
+    let delimiter = match options.delimiter {
+        Some(c) if c.is_ascii() => c as u8,
+        Some(c) => return Err(format!("Unsupported delimiter: {c}")),
+        None => detect_delimiter(text),
+    };
+    let mut reader = csv::ReaderBuilder::new()
+        .delimiter(delimiter)
+        .has_headers(false)
+        .flexible(true)
+        .from_reader(text.as_bytes());
+    let mut records: Vec<Vec<String>> = Vec::new();
+    for record in reader.records() {
+        let record = record.map_err(|e| format!("Failed to parse CSV: {e}"))?;
+        if record.iter().all(|field| field.trim().is_empty()) {
+            continue;
+        }
+        records.push(record.iter().map(to_cell).collect());
+    }
+    let columns = records.iter().map(Vec::len).max().unwrap_or(0);
+    if columns == 0 {
+        return Err("No table data found".to_string());
+    }
+    for record in &mut records {
+        record.resize(columns, String::new());
+    }
+
+    let header = if options.has_header {
+        records.remove(0)
+    } else {
+        vec![String::new(); columns]
+    };
+    let align = (0..columns)
+        .map(|column| {
+            let numeric = !records.is_empty()
+                && records.iter().all(|row| {
+                    let cell = &row[column];
+                    cell.is_empty() || cell.replace(',', "").parse::<f64>().is_ok()
+                });
+            if numeric {
+                Align::Right
+            } else {
+                Align::None
+            }
+        })
+        .collect();
+    Ok(Table {
+        header,
+        align,
+        rows: records,
+    })
+}
+
+/// Convert CSV/TSV from `path`, or from the clipboard when no path is
+/// given, into a markdown table.
+#[tauri::command]
+pub fn import_table(
+    app: AppHandle,
+    path: Option<String>,
+    options: Option<ImportOptions>,
+) -> Result<String, String> {
+    let text = match path {
+        Some(path) => {
+            let bytes = fs::read(&path).map_err(|e| format!("Failed to read file: {e}"))?;
+            text_encoding::decode(&bytes).content
+        }
+        None => app
+            .clipboard()
+            .read_text()
+            .map_err(|e| format!("Failed to read clipboard: {e}"))?,
+    };
+    let table = parse_csv(&text, &options.unwrap_or_default())?;
+    let mut markdown = tables::format(&table).join("\n");
+    markdown.push('\n');
+    Ok(markdown)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_delimiter() {
+        assert_eq!(detect_delimiter("a\tb\tc\n1\t2\t3\n"), b'\t');
+        assert_eq!(detect_delimiter("name;note\n\"x\";\"a, b\"\n"), b';');
+        assert_eq!(detect_delimiter("one column\nvalue\n"), b',');
+        assert_eq!(detect_delimiter("a;b\n\"multi\nline\";2\n"), b';');
+    }
+
+    #[test]
+    fn test_parse_csv() {
+        let text = "Item,Price,Note\r\nPen,\"1,200\",\"red | blue\"\r\nBook,15,\"two\nlines\"\r\n\r\nCup\r\n";
+        let table = parse_csv(text, &ImportOptions::default()).unwrap();
+        assert_eq!(
+            tables::format(&table),
+            vec![
+                "| Item | Price | Note         |",
+                "| ---- | ----: | ------------ |",
+                "| Pen  | 1,200 | red \\| blue  |",
+                "| Book |    15 | two<br>lines |",
+                "| Cup  |       |              |",
+            ]
+        );
+
+        let options = ImportOptions {
+            delimiter: Some('\t'),
+            has_header: false,
+        };
+        let table = parse_csv("a\tb\n", &options).unwrap();
+        assert_eq!(table.header, vec!["", ""]);
+        assert_eq!(table.rows, vec![vec!["a", "b"]]);
+    }
+
+    #[test]
+    fn test_to_cell_escapes_pipes_once() {
+        assert_eq!(to_cell("a | b"), "a \\| b");
+        assert_eq!(to_cell("a \\| b"), "a \\| b");
+        assert_eq!(to_cell("a \\\\| b"), "a \\\\\\| b");
+    }
+}
@@ -0,0 +1,412 @@
+//! GFM pipe tables: finding, parsing, editing and writing them.
+//!
+//! Cells are split on unescaped pipes. Column widths are measured in
+//! display columns, so tables with CJK text still line up in a monospace
+//! font. `edit_table` backs the Table menu and the MCP table handlers: it
+//! takes a table's markdown and returns the edited, re-aligned table.
+
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::ops::Range;
+use unicode_width::UnicodeWidthStr;
+
+use crate::line_endings::{self, LineEnding};
+use crate::links;
+
+/// Narrowest column written, so delimiters keep at least `---`
+const MIN_WIDTH: usize = 3;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Align {
+    #[default]
+    None,
+    Left,
+    Center,
+    Right,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Table {
+    pub header: Vec<String>,
+    pub align: Vec<Align>,
+    pub rows: Vec<Vec<String>>,
+}
+
+/// Cells of a table row, trimmed, with the outer pipes removed.
+pub fn split_row(line: &str) -> Vec<String> {
+    let trimmed = line.trim();
+    let inner = trimmed.strip_prefix('|').unwrap_or(trimmed);
+    let mut cells = Vec::new();
+    let mut cell = String::new();
+    let mut ended_with_pipe = false;
+    let mut chars = inner.chars();
+    while let Some(c) = chars.next() {
+        ended_with_pipe = c == '|';
+        match c {
+            '|' => cells.push(std::mem::take(&mut cell).trim().to_string()),
+            '\\' => {
+                cell.push(c);
+                cell.extend(chars.next());
+            }
+            _ => cell.push(c),
+        }
+    }
+    if !ended_with_pipe {
+        cells.push(cell.trim().to_string());
+    }
+    cells
+}
+
+fn has_pipe(line: &str) -> bool {
+    let indent = line.len() - line.trim_start_matches(' ').len();
+    indent < 4 && line.contains('|')
+}
+
+/// Alignments of a delimiter row (`| :--- | ---: |`).
+fn parse_delimiter(line: &str) -> Option<Vec<Align>> {
+    if !line.contains('-') {
+        return None;
+    }
+    split_row(line)
+        .iter()
+        .map(|cell| {
+            let left = cell.starts_with(':');
+            let right = cell.ends_with(':');
+            let dashes = cell.trim_start_matches(':').trim_end_matches(':');
+            if dashes.is_empty() || !dashes.bytes().all(|b| b == b'-') {
+                return None;
+            }
+            Some(match (left, right) {
+                (true, true) => Align::Center,
+                (true, false) => Align::Left,
+                (false, true) => Align::Right,
+                (false, false) => Align::None,
+            })
+        })
+        .collect()
+}
+
+/// Parse a table from its lines (header, delimiter, body rows). Short rows
+/// are padded; long rows widen the table rather than losing cells.
+pub fn parse(lines: &[&str]) -> Option<Table> {
+    let [header, delimiter, body @ ..] = lines else {
+        return None;
+    };
+    let mut header = split_row(header);
+    let mut align = parse_delimiter(delimiter)?;
+    if !has_pipe(lines[0]) || header.len() != align.len() {
+        return None;
+    }
+    let mut rows: Vec<Vec<String>> = body.iter().map(|line| split_row(line)).collect();
+    let columns = rows
+        .iter()
+        .map(Vec::len)
+        .max()
+        .unwrap_or(0)
+        .max(header.len());
+    header.resize(columns, String::new());
+    align.resize(columns, Align::None);
+    for row in &mut rows {
+        row.resize(columns, String::new());
+    }
+    Some(Table {
+        header,
+        align,
+        rows,
+    })
+}
+
+/// Line ranges of the tables in `lines`, skipping fenced code.
+pub fn find_tables(lines: &[&str]) -> Vec<Range<usize>> {
+    let mut found = Vec::new();
+    let mut fence = None;
+    let mut i = 0;
+    while i < lines.len() {
+        if links::update_fence(&mut fence, lines[i]) || fence.is_some() {
+            i += 1;
+            continue;
+        }
+        let is_start =
+            i + 1 < lines.len() && has_pipe(lines[i]) && parse(&lines[i..i + 2]).is_some();
+        if !is_start {
+            i += 1;
+            continue;
+        }
+        let mut end = i + 2;
+        while end < lines.len() && !lines[end].trim().is_empty() && has_pipe(lines[end]) {
+            end += 1;
+        }
+        found.push(i..end);
+        i = end;
+    }
+    found
+}
+
+fn pad(cell: &str, width: usize, align: Align) -> String {
+    let space = width.saturating_sub(cell.width());
+    let (before, after) = match align {
+        Align::Right => (space, 0),
+        Align::Center => (space / 2, space - space / 2),
+        Align::None | Align::Left => (0, space),
+    };
+    format!("{}{cell}{}", " ".repeat(before), " ".repeat(after))
+}
+
+fn delimiter_cell(width: usize, align: Align) -> String {
+    match align {
+        Align::None => "-".repeat(width),
+        Align::Left => format!(":{}", "-".repeat(width - 1)),
+        Align::Right => format!("{}:", "-".repeat(width - 1)),
+        Align::Center => format!(":{}:", "-".repeat(width - 2)),
+    }
+}
+
+/// The table as aligned markdown lines, without line endings.
+pub fn format(table: &Table) -> Vec<String> {
+    let widths: Vec<usize> = (0..table.header.len())
+        .map(|column| {
+            std::iter::once(&table.header)
+                .chain(&table.rows)
+                .filter_map(|row| row.get(column))
+                .map(|cell| cell.width())
+                .max()
+                .unwrap_or(0)
+                .max(MIN_WIDTH)
+        })
+        .collect();
+    let write_row = |cells: Vec<String>| format!("| {} |", cells.join(" | "));
+    let cells = |row: &[String]| {
+        row.iter()
+            .zip(&widths)
+            .zip(&table.align)
+            .map(|((cell, width), align)| pad(cell, *width, *align))
+            .collect()
+    };
+
+    let mut lines = vec![write_row(cells(&table.header))];
+    lines.push(write_row(
+        widths
+            .iter()
+            .zip(&table.align)
+            .map(|(width, align)| delimiter_cell(*width, *align))
+            .collect(),
+    ));
+    lines.extend(table.rows.iter().map(|row| write_row(cells(row))));
+    lines
+}
+
+/// An edit to a table. Row indexes count body rows (the header is not a
+/// row); insert indexes may equal the count to append.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "op", rename_all = "camelCase")]
+pub enum TableOp {
+    /// Only re-align the pipes
+    Format,
+    InsertRow {
+        index: usize,
+    },
+    DeleteRow {
+        index: usize,
+    },
+    InsertColumn {
+        index: usize,
+        #[serde(default)]
+        align: Align,
+    },
+    DeleteColumn {
+        index: usize,
+    },
+    /// Align one column, or all columns when `column` is absent
+    SetAlignment {
+        #[serde(default)]
+        column: Option<usize>,
+        align: Align,
+    },
+    /// Swap rows and columns; the first column becomes the header
+    Transpose,
+    /// Sort body rows by a column, numerically if all its cells are numbers
+    Sort {
+        column: usize,
+        #[serde(default)]
+        descending: bool,
+    },
+}
+
+fn check_index(index: usize, len: usize, what: &str) -> Result<(), String> {
+    if index < len {
+        Ok(())
+    } else {
+        Err(format!("{what} {index} out of range (table has {len})"))
+    }
+}
+
+fn compare_cells(a: &str, b: &str, numeric: bool) -> Ordering {
+    if numeric {
+        let a: f64 = a.parse().unwrap_or(f64::NAN);
+        let b: f64 = b.parse().unwrap_or(f64::NAN);
+        a.total_cmp(&b)
+    } else {
+        a.to_lowercase().cmp(&b.to_lowercase())
+    }
+}
+
+/// Apply `op` to `table`.
+pub fn apply(table: &mut Table, op: &TableOp) -> Result<(), String> {
+    let columns = table.header.len();
+    match *op {
+        TableOp::Format => {}
+        TableOp::InsertRow { index } => {
+            check_index(index, table.rows.len() + 1, "Row")?;
+            table.rows.insert(index, vec![String::new(); columns]);
+        }
+        TableOp::DeleteRow { index } => {
+            check_index(index, table.rows.len(), "Row")?;
+            table.rows.remove(index);
+        }
+        TableOp::InsertColumn { index, align } => {
+            check_index(index, columns + 1, "Column")?;
+            table.header.insert(index, String::new());
+            table.align.insert(index, align);
+            for row in &mut table.rows {
+                row.insert(index, String::new());
+            }
+        }
+        TableOp::DeleteColumn { index } => {
+            check_index(index, columns, "Column")?;
+            if columns == 1 {
+                return Err("Cannot delete the only column".to_string());
+            }
+            table.header.remove(index);
+            table.align.remove(index);
+            for row in &mut table.rows {
+                row.remove(index);
+            }
+        }
+        TableOp::SetAlignment { column, align } => match column {
+            Some(column) => {
+                check_index(column, columns, "Column")?;
+                table.align[column] = align;
+            }
+            None => table.align.fill(align),
+        },
+        TableOp::Transpose => {
+            let source: Vec<&Vec<String>> =
+                std::iter::once(&table.header).chain(&table.rows).collect();
+            let mut transposed = (0..columns).map(|column| {
+                source
+                    .iter()
+                    .map(|row| row[column].clone())
+                    .collect::<Vec<_>>()
+            });
+            let header = transposed.next().unwrap_or_default();
+            let rows: Vec<Vec<String>> = transposed.collect();
+            table.align = vec![Align::None; header.len()];
+            table.header = header;
+            table.rows = rows;
+        }
+        TableOp::Sort { column, descending } => {
+            check_index(column, columns, "Column")?;
+            let numeric = table
+                .rows
+                .iter()
+                .all(|row| row[column].is_empty() || row[column].parse::<f64>().is_ok());
+            table.rows.sort_by(|a, b| {
+                let order = compare_cells(&a[column], &b[column], numeric);
+                if descending {
+                    order.reverse()
+                } else {
+                    order
+                }
+            });
+        }
+    }
+    Ok(())
+}
+
+/// Edit a markdown table and return it re-aligned, with the input's line
+/// endings.
+#[tauri::command]
+pub fn edit_table(table: String, operation: TableOp) -> Result<String, String> {
+    let lines: Vec<&str> = table.lines().collect();
+    let mut parsed = parse(&lines).ok_or_else(|| "Not a markdown table".to_string())?;
+    apply(&mut parsed, &operation)?;
+    let mut result = format(&parsed).join("\n");
+    if table.ends_with('\n') {
+        result.push('\n');
+    }
+    Ok(match line_endings::detect(&table) {
+        Some(LineEnding::Crlf) => line_endings::apply(&result, LineEnding::Crlf),
+        _ => result,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_and_format() {
+        let lines = [
+            "Name|Qty|Note",
+            ":--|--:|:-:",
+            "苹果|3|a \\| b",
+            "pear",
+            "fig|12|x|extra",
+        ];
+        let table = parse(&lines).unwrap();
+        assert_eq!(table.header, vec!["Name", "Qty", "Note", ""]);
+        assert_eq!(table.rows[0], vec!["苹果", "3", "a \\| b", ""]);
+        assert_eq!(
+            format(&table),
+            vec![
+                "| Name | Qty |  Note  |       |",
+                "| :--- | --: | :----: | ----- |",
+                "| 苹果 |   3 | a \\| b |       |",
+                "| pear |     |        |       |",
+                "| fig  |  12 |   x    | extra |",
+            ]
+        );
+
+        let text = [
+            "```", "a|b", "-|-", "```", "", "a | b", "--|--", "1 | 2", "", "x|y",
+        ];
+        assert_eq!(find_tables(&text), vec![5..8]);
+    }
+
+    #[test]
+    fn test_edit_table() {
+        let table = "| Name | Qty |\r\n| --- | --: |\r\n| b | 10 |\r\n| A | 9 |\r\n".to_string();
+        let edit = |op: serde_json::Value| {
+            edit_table(table.clone(), serde_json::from_value(op).unwrap()).unwrap()
+        };
+
+        assert_eq!(
+            edit(serde_json::json!({"op": "sort", "column": 1})),
+            "| Name | Qty |\r\n| ---- | --: |\r\n| A    |   9 |\r\n| b    |  10 |\r\n"
+        );
+        assert_eq!(
+            edit(serde_json::json!({"op": "transpose"})).replace('\r', ""),
+            "| Name | b   | A   |\n| ---- | --- | --- |\n| Qty  | 10  | 9   |\n"
+        );
+        assert_eq!(
+            edit(serde_json::json!({"op": "insertColumn", "index": 1, "align": "center"}))
+                .replace('\r', ""),
+            "| Name |     | Qty |\n| ---- | :-: | --: |\n| b    |     |  10 |\n| A    |     |   9 |\n"
+        );
+
+        let mut parsed = parse(&table.lines().collect::<Vec<_>>()).unwrap();
+        apply(&mut parsed, &TableOp::DeleteRow { index: 0 }).unwrap();
+        apply(
+            &mut parsed,
+            &TableOp::SetAlignment {
+                column: None,
+                align: Align::Left,
+            },
+        )
+        .unwrap();
+        assert_eq!(parsed.rows, vec![vec!["A", "9"]]);
+        assert_eq!(parsed.align, vec![Align::Left; 2]);
+        assert!(apply(&mut parsed, &TableOp::DeleteRow { index: 1 }).is_err());
+    }
+}
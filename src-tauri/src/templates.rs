@@ -0,0 +1,293 @@
+//! Document templates for File > New from Template.
+//!
+//! Templates are Markdown files in the workspace's `.vmark/templates/` and
+//! in the global `~/.vmark/templates/`. Workspace templates are listed first;
+//! a global template with the same file name is hidden by the workspace one.
+//!
+//! Creating a document from a template substitutes `{{variables}}`: values
+//! passed by the caller, then the built-ins `date`, `time`, `datetime`
+//! (each with an optional strftime format, `{{date:%d.%m.%Y}}`), `title`
+//! (the new file's name) and `author`, then fields of the template's own
+//! frontmatter. Unknown variables are left as written.
+
+use chrono::format::{Item, StrftimeItems};
+use chrono::{DateTime, Local};
+use serde_json::Value;
+use std::collections::{HashMap, HashSet};
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+use crate::{file_tree, frontmatter, text_encoding};
+
+/// A template file offered in the menu.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Template {
+    /// Display name (file name without extension)
+    pub name: String,
+    pub path: String,
+}
+
+/// Template folder of a workspace.
+pub fn workspace_templates_dir(root: &Path) -> PathBuf {
+    root.join(".vmark").join("templates")
+}
+
+fn global_templates_dir() -> Option<PathBuf> {
+    dirs::home_dir().map(|home| home.join(".vmark").join("templates"))
+}
+
+/// Markdown files directly inside `dir`, sorted by name.
+fn templates_in(dir: &Path) -> Vec<Template> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut templates: Vec<Template> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.is_file() && file_tree::is_markdown_path(path))
+        .filter_map(|path| {
+            let name = path.file_stem()?.to_str()?.to_string();
+            Some(Template {
+                name,
+                path: path.to_string_lossy().to_string(),
+            })
+        })
+        .collect();
+    templates.sort_by_key(|t| t.name.to_lowercase());
+    templates
+}
+
+/// Templates from the workspace folder followed by the global ones.
+fn collect_templates(workspace_dir: Option<&Path>, global_dir: Option<&Path>) -> Vec<Template> {
+    let mut templates = workspace_dir.map(templates_in).unwrap_or_default();
+    let names: HashSet<String> = templates.iter().map(|t| t.name.clone()).collect();
+    if let Some(dir) = global_dir {
+        templates.extend(
+            templates_in(dir)
+                .into_iter()
+                .filter(|t| !names.contains(&t.name)),
+        );
+    }
+    templates
+}
+
+/// Templates available for a workspace (or only the global ones).
+pub fn list_templates(workspace_root: Option<&Path>) -> Vec<Template> {
+    let workspace_dir = workspace_root.map(workspace_templates_dir);
+    collect_templates(workspace_dir.as_deref(), global_templates_dir().as_deref())
+}
+
+/// Whether a changed path affects the templates of the workspace at `root`.
+pub fn is_template_change(root: &Path, path: &Path) -> bool {
+    path.starts_with(workspace_templates_dir(root))
+}
+
+/// Top-level scalar fields of the document's frontmatter.
+fn frontmatter_fields(content: &str) -> HashMap<String, String> {
+    let Ok(Some(matter)) = frontmatter::parse(content) else {
+        return HashMap::new();
+    };
+    let Value::Object(data) = matter.data else {
+        return HashMap::new();
+    };
+    data.into_iter()
+        .filter_map(|(key, value)| match value {
+            Value::String(s) if !s.is_empty() => Some((key, s)),
+            Value::Number(n) => Some((key, n.to_string())),
+            Value::Bool(b) => Some((key, b.to_string())),
+            _ => None,
+        })
+        .collect()
+}
+
+/// `time` in a strftime `format`, or None if the format is invalid.
+pub fn format_time(time: &DateTime<Local>, format: &str) -> Option<String> {
+    if StrftimeItems::new(format).any(|item| matches!(item, Item::Error)) {
+        return None;
+    }
+    Some(time.format(format).to_string())
+}
+
+/// Replace every `{{name}}` / `{{name:format}}` that `lookup` knows.
+pub fn substitute<F>(content: &str, mut lookup: F) -> String
+where
+    F: FnMut(&str, Option<&str>) -> Option<String>,
+{
+    let mut out = String::with_capacity(content.len());
+    let mut rest = content;
+    while let Some(open) = rest.find("{{") {
+        out.push_str(&rest[..open]);
+        let Some(close) = rest[open..].find("}}").map(|i| open + i) else {
+            break;
+        };
+        let inner = rest[open + 2..close].trim();
+        let (name, format) = match inner.split_once(':') {
+            Some((name, format)) => (name.trim(), Some(format)),
+            None => (inner, None),
+        };
+        match lookup(name, format) {
+            Some(value) => out.push_str(&value),
+            None => out.push_str(&rest[open..close + 2]),
+        }
+        rest = &rest[close + 2..];
+    }
+    out.push_str(rest);
+    out
+}
+
+/// Fill in a template's variables (see the module docs).
+fn render_template(
+    content: &str,
+    vars: &HashMap<String, String>,
+    title: &str,
+    now: &DateTime<Local>,
+) -> String {
+    let author = std::env::var("USER")
+        .or_else(|_| std::env::var("USERNAME"))
+        .unwrap_or_default();
+    let rendered = substitute(content, |name, format| {
+        if let Some(value) = vars.get(name) {
+            return Some(value.clone());
+        }
+        let default_format = match name {
+            "date" => "%Y-%m-%d",
+            "time" => "%H:%M",
+            "datetime" => "%Y-%m-%d %H:%M",
+            "title" => return Some(title.to_string()),
+            "author" => return (!author.is_empty()).then(|| author.clone()),
+            _ => return None,
+        };
+        format_time(now, format.unwrap_or(default_format))
+    });
+
+    let fields = frontmatter_fields(&rendered);
+    if fields.is_empty() {
+        return rendered;
+    }
+    substitute(&rendered, |name, _| fields.get(name).cloned())
+}
+
+/// Create `dest_path` from a template and return its path.
+///
+/// # Arguments
+/// * `template_path` - Template file
+/// * `dest_path` - New document (must not exist)
+/// * `vars` - Variable values; these win over built-ins and frontmatter
+#[tauri::command]
+pub fn create_from_template(
+    template_path: String,
+    dest_path: String,
+    vars: Option<HashMap<String, String>>,
+) -> Result<String, String> {
+    let dest = Path::new(&dest_path);
+    let bytes = fs::read(&template_path).map_err(|e| format!("Failed to read template: {e}"))?;
+    let template = text_encoding::decode(&bytes).content;
+    let title = dest
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or_default();
+    let content = render_template(&template, &vars.unwrap_or_default(), title, &Local::now());
+
+    if let Some(parent) = dest.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create directory {}: {e}", parent.display()))?;
+    }
+    // `create_new` so a file that appeared meanwhile is never replaced
+    let mut file = OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(dest)
+        .map_err(|e| match e.kind() {
+            io::ErrorKind::AlreadyExists => format!("Destination already exists: {dest_path}"),
+            _ => format!("Failed to create document: {e}"),
+        })?;
+    if let Err(e) = file.write_all(content.as_bytes()) {
+        let _ = fs::remove_file(dest);
+        return Err(format!("Failed to create document: {e}"));
+    }
+    Ok(dest_path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_collect_templates() {
+        let dir = tempdir().unwrap();
+        let workspace = dir.path().join("ws");
+        let global = dir.path().join("global");
+        let ws_templates = workspace_templates_dir(&workspace);
+        fs::create_dir_all(&ws_templates).unwrap();
+        fs::create_dir_all(&global).unwrap();
+
+        fs::write(ws_templates.join("Meeting.md"), "# Meeting").unwrap();
+        fs::write(ws_templates.join("notes.txt"), "ignored").unwrap();
+        fs::write(global.join("Weekly.md"), "# Weekly").unwrap();
+        fs::write(global.join("Meeting.md"), "# Shadowed").unwrap();
+        fs::write(global.join("Daily.md"), "# Daily").unwrap();
+
+        let templates = collect_templates(Some(&ws_templates), Some(&global));
+        let names: Vec<&str> = templates.iter().map(|t| t.name.as_str()).collect();
+        assert_eq!(names, vec!["Meeting", "Daily", "Weekly"]);
+        assert!(templates[0]
+            .path
+            .starts_with(&*ws_templates.to_string_lossy()));
+
+        assert!(collect_templates(Some(&dir.path().join("missing")), None).is_empty());
+    }
+
+    #[test]
+    fn test_is_template_change() {
+        let root = Path::new("/ws");
+        assert!(is_template_change(
+            root,
+            Path::new("/ws/.vmark/templates/a.md")
+        ));
+        assert!(!is_template_change(
+            root,
+            Path::new("/ws/.vmark/vmark.code-workspace")
+        ));
+    }
+
+    #[test]
+    fn test_render_template() {
+        use chrono::TimeZone;
+
+        let now = Local.with_ymd_and_hms(2024, 3, 9, 14, 5, 0).unwrap();
+        let template = "---\nproject: Apollo\ntags: [{{kind}}]\n---\n\
+            # {{ title }} ({{project}})\n{{date}} / {{date:%d.%m.%Y}} {{time}}\n{{unknown}}\n";
+        let vars = HashMap::from([("kind".to_string(), "meeting".to_string())]);
+
+        let rendered = render_template(template, &vars, "Kickoff", &now);
+        assert_eq!(
+            rendered,
+            "---\nproject: Apollo\ntags: [meeting]\n---\n\
+            # Kickoff (Apollo)\n2024-03-09 / 09.03.2024 14:05\n{{unknown}}\n"
+        );
+    }
+
+    #[test]
+    fn test_create_from_template_refuses_existing_file() {
+        let dir = tempdir().unwrap();
+        let template = dir.path().join("t.md");
+        let dest = dir.path().join("notes/new.md");
+        fs::write(&template, "# {{title}}").unwrap();
+
+        let path = |p: &Path| p.to_string_lossy().to_string();
+        create_from_template(path(&template), path(&dest), None).unwrap();
+        assert_eq!(fs::read_to_string(&dest).unwrap(), "# new");
+        fs::write(&template, "# {{title}} again").unwrap();
+        assert!(create_from_template(path(&template), path(&dest), None).is_err());
+        assert_eq!(fs::read_to_string(&dest).unwrap(), "# new");
+
+        // Templates in other encodings are decoded
+        let utf16 = text_encoding::encode("# {{title}} – 記録", "UTF-16LE", true).unwrap();
+        fs::write(&template, utf16).unwrap();
+        let dest = dir.path().join("notes/log.md");
+        create_from_template(path(&template), path(&dest), None).unwrap();
+        assert_eq!(fs::read_to_string(&dest).unwrap(), "# log – 記録");
+    }
+}
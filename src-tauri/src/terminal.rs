@@ -0,0 +1,162 @@
+//! Open a system terminal at a folder.
+//!
+//! The frontend passes the terminal chosen in settings; without one the
+//! platform default is detected: iTerm if installed, else Terminal on macOS;
+//! Windows Terminal, else cmd on Windows; `$TERMINAL` or the first known
+//! emulator on `PATH` on Linux.
+
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Linux terminal emulators tried in order when none is configured
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+const LINUX_TERMINALS: &[&str] = &[
+    "x-terminal-emulator",
+    "gnome-terminal",
+    "konsole",
+    "xfce4-terminal",
+    "kitty",
+    "alacritty",
+    "wezterm",
+    "xterm",
+];
+
+/// Find an executable on `PATH`.
+#[cfg(not(target_os = "macos"))]
+fn find_in_path(program: &str) -> Option<PathBuf> {
+    let paths = std::env::var_os("PATH")?;
+    std::env::split_paths(&paths)
+        .map(|dir| dir.join(program))
+        .find(|candidate| candidate.is_file())
+}
+
+#[cfg(target_os = "macos")]
+fn default_terminal() -> Option<String> {
+    let iterm = Path::new("/Applications/iTerm.app").exists()
+        || dirs::home_dir().is_some_and(|home| home.join("Applications/iTerm.app").exists());
+    Some(if iterm { "iTerm" } else { "Terminal" }.to_string())
+}
+
+#[cfg(target_os = "windows")]
+fn default_terminal() -> Option<String> {
+    Some(
+        if find_in_path("wt.exe").is_some() {
+            "wt"
+        } else {
+            "cmd"
+        }
+        .to_string(),
+    )
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+fn default_terminal() -> Option<String> {
+    std::env::var("TERMINAL")
+        .ok()
+        .filter(|t| !t.trim().is_empty())
+        .or_else(|| {
+            LINUX_TERMINALS
+                .iter()
+                .find(|t| find_in_path(t).is_some())
+                .map(|t| t.to_string())
+        })
+}
+
+/// Arguments that make a terminal emulator start in `dir`. Terminals not
+/// listed here inherit the working directory of the spawned process.
+#[cfg(not(target_os = "macos"))]
+fn working_dir_args(terminal: &str, dir: &Path) -> Vec<String> {
+    let name = Path::new(terminal)
+        .file_stem()
+        .and_then(|n| n.to_str())
+        .unwrap_or(terminal)
+        .to_lowercase();
+    let dir = dir.to_string_lossy().to_string();
+    match name.as_str() {
+        "gnome-terminal" | "xfce4-terminal" | "mate-terminal" | "tilix" => {
+            vec![format!("--working-directory={dir}")]
+        }
+        "konsole" => vec!["--workdir".into(), dir],
+        "alacritty" => vec!["--working-directory".into(), dir],
+        "kitty" => vec!["--directory".into(), dir],
+        "wezterm" => vec!["start".into(), "--cwd".into(), dir],
+        "wt" => vec!["-d".into(), dir],
+        _ => Vec::new(),
+    }
+}
+
+/// Command that opens `terminal` at `dir`.
+#[cfg(target_os = "macos")]
+fn launch_command(terminal: &str, dir: &Path) -> Command {
+    let mut command = Command::new("open");
+    command.arg("-a").arg(terminal).arg(dir);
+    command
+}
+
+#[cfg(target_os = "windows")]
+fn launch_command(terminal: &str, dir: &Path) -> Command {
+    let mut command = if terminal.eq_ignore_ascii_case("cmd") {
+        let mut command = Command::new("cmd");
+        command.args(["/C", "start", "", "cmd"]);
+        command
+    } else {
+        let mut command = Command::new(terminal);
+        command.args(working_dir_args(terminal, dir));
+        command
+    };
+    command.current_dir(dir);
+    command
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+fn launch_command(terminal: &str, dir: &Path) -> Command {
+    let mut command = Command::new(terminal);
+    command
+        .args(working_dir_args(terminal, dir))
+        .current_dir(dir);
+    command
+}
+
+/// Open a terminal at `path` (its parent folder for a file). `terminal` is
+/// the app or program from settings; empty or missing uses the default.
+#[tauri::command]
+pub fn open_in_terminal(path: String, terminal: Option<String>) -> Result<(), String> {
+    let path = PathBuf::from(&path);
+    let dir = if path.is_dir() {
+        path
+    } else {
+        path.parent()
+            .filter(|p| p.is_dir())
+            .map(Path::to_path_buf)
+            .ok_or_else(|| format!("Folder does not exist: {}", path.display()))?
+    };
+    let terminal = terminal
+        .filter(|t| !t.trim().is_empty())
+        .or_else(default_terminal)
+        .ok_or("No terminal application found")?;
+
+    launch_command(&terminal, &dir)
+        .spawn()
+        .map(|_| ())
+        .map_err(|e| format!("Failed to open {terminal}: {e}"))
+}
+
+#[cfg(all(test, not(target_os = "macos")))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_working_dir_args() {
+        let dir = Path::new("/work/notes");
+        assert_eq!(
+            working_dir_args("/usr/bin/gnome-terminal", dir),
+            vec!["--working-directory=/work/notes"]
+        );
+        assert_eq!(
+            working_dir_args("kitty", dir),
+            vec!["--directory", "/work/notes"]
+        );
+        assert_eq!(working_dir_args("wt.exe", dir), vec!["-d", "/work/notes"]);
+        assert!(working_dir_args("xterm", dir).is_empty());
+    }
+}
@@ -0,0 +1,139 @@
+//! Text encoding detection and conversion.
+//!
+//! A byte order mark decides the encoding when present. Otherwise valid
+//! UTF-8 is taken as UTF-8, and anything else is guessed with `chardetng`
+//! (GBK, Big5, Shift_JIS, ...). Saving converts back to the encoding the
+//! file was read with, so legacy files keep their encoding.
+
+use encoding_rs::{Encoding, UTF_16BE, UTF_16LE, UTF_8};
+use serde::Serialize;
+use std::fs;
+use std::path::Path;
+
+use crate::line_endings::{self, LineEnding};
+use crate::write_access;
+
+/// A decoded text file.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TextFile {
+    pub content: String,
+    /// Encoding name, e.g. "UTF-8", "GBK", "Shift_JIS"
+    pub encoding: String,
+    /// Whether the file starts with a byte order mark
+    pub bom: bool,
+    /// Whether some bytes were invalid in the detected encoding
+    #[serde(rename = "hadErrors")]
+    pub had_errors: bool,
+    /// Line endings of the content; None if it has no line breaks
+    #[serde(rename = "lineEnding")]
+    pub line_ending: Option<LineEnding>,
+    /// Whether the file can be saved in place (checked by `read_text_smart`)
+    pub writable: bool,
+}
+
+fn detect(bytes: &[u8]) -> (&'static Encoding, usize) {
+    if let Some(found) = Encoding::for_bom(bytes) {
+        return found;
+    }
+    if std::str::from_utf8(bytes).is_ok() {
+        return (UTF_8, 0);
+    }
+    let mut detector = chardetng::EncodingDetector::new();
+    detector.feed(bytes, true);
+    (detector.guess(None, true), 0)
+}
+
+/// Decode file bytes in their detected encoding.
+pub fn decode(bytes: &[u8]) -> TextFile {
+    let (encoding, bom_len) = detect(bytes);
+    let (content, had_errors) = encoding.decode_without_bom_handling(&bytes[bom_len..]);
+    TextFile {
+        line_ending: line_endings::detect(&content),
+        content: content.into_owned(),
+        encoding: encoding.name().to_string(),
+        bom: bom_len > 0,
+        had_errors,
+        writable: true,
+    }
+}
+
+/// Encode `content` in the encoding named `label`, optionally with a BOM.
+/// Fails rather than writing characters the encoding can't represent.
+pub fn encode(content: &str, label: &str, bom: bool) -> Result<Vec<u8>, String> {
+    let encoding = Encoding::for_label(label.trim().as_bytes())
+        .ok_or_else(|| format!("Unknown encoding: {label}"))?;
+
+    // encoding_rs only decodes UTF-16, so encode it here
+    if encoding == UTF_16LE || encoding == UTF_16BE {
+        let little_endian = encoding == UTF_16LE;
+        let mut bytes = Vec::with_capacity(content.len() * 2 + 2);
+        let units = bom
+            .then_some(0xFEFF)
+            .into_iter()
+            .chain(content.encode_utf16());
+        for unit in units {
+            if little_endian {
+                bytes.extend(unit.to_le_bytes());
+            } else {
+                bytes.extend(unit.to_be_bytes());
+            }
+        }
+        return Ok(bytes);
+    }
+
+    let (encoded, _, had_errors) = encoding.encode(content);
+    if had_errors {
+        return Err(format!(
+            "The document contains characters that can't be saved as {}",
+            encoding.name()
+        ));
+    }
+    let mut bytes = Vec::with_capacity(encoded.len() + 3);
+    if bom && encoding == UTF_8 {
+        bytes.extend([0xEF, 0xBB, 0xBF]);
+    }
+    bytes.extend_from_slice(&encoded);
+    Ok(bytes)
+}
+
+/// Read a text file in whatever encoding it uses.
+#[tauri::command]
+pub fn read_text_smart(path: String) -> Result<TextFile, String> {
+    let bytes = fs::read(&path).map_err(|e| format!("Failed to read file: {e}"))?;
+    Ok(TextFile {
+        writable: write_access::is_writable(Path::new(&path)),
+        ..decode(&bytes)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode_utf8_and_bom() {
+        let plain = decode("# 标题".as_bytes());
+        assert_eq!(plain.content, "# 标题");
+        assert_eq!(plain.encoding, "UTF-8");
+        assert!(!plain.bom);
+        assert_eq!(plain.line_ending, None);
+
+        let with_bom = decode(b"\xEF\xBB\xBF# Title");
+        assert_eq!(with_bom.content, "# Title");
+        assert!(with_bom.bom);
+    }
+
+    #[test]
+    fn test_utf16_round_trip() {
+        let bytes = encode("# 見出し\n", "UTF-16LE", true).unwrap();
+        assert_eq!(&bytes[..2], &[0xFF, 0xFE]);
+
+        let decoded = decode(&bytes);
+        assert_eq!(decoded.content, "# 見出し\n");
+        assert_eq!(decoded.encoding, "UTF-16LE");
+        assert_eq!(decoded.line_ending, Some(LineEnding::Lf));
+        assert!(decoded.bom);
+
+        assert!(encode("text", "no-such-encoding", false).is_err());
+    }
+}
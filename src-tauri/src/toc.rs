@@ -0,0 +1,338 @@
+//! Heading scan and table-of-contents generation.
+//!
+//! Headings are found in ATX (`## Title`) and setext (`Title` over `===`
+//! or `---`) form, skipping frontmatter and fenced code. Anchors follow
+//! GitHub's or kramdown's rules, including the `-1`, `-2` suffixes for
+//! repeated headings, so TOC links match what those renderers generate.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+
+use crate::{frontmatter, links};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum AnchorStyle {
+    #[default]
+    Github,
+    Kramdown,
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct TocOptions {
+    /// Shallowest heading level to include (default 1)
+    #[serde(rename = "minLevel", default)]
+    pub min_level: Option<u8>,
+    /// Deepest heading level to include (default 6)
+    #[serde(rename = "maxLevel", default)]
+    pub max_level: Option<u8>,
+    #[serde(rename = "anchorStyle", default)]
+    pub anchor_style: AnchorStyle,
+    /// Numbered list instead of bullets
+    #[serde(default)]
+    pub ordered: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Heading {
+    pub level: u8,
+    /// Heading text with inline markup removed
+    pub text: String,
+    /// 1-based line of the heading (of its text, for setext headings)
+    pub line: usize,
+    pub anchor: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TocEntry {
+    #[serde(flatten)]
+    pub heading: Heading,
+    pub children: Vec<TocEntry>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Toc {
+    pub entries: Vec<TocEntry>,
+    /// The TOC as a markdown list, ready to insert
+    pub markdown: String,
+}
+
+/// Level and raw text of an ATX heading line.
+pub fn atx_heading(line: &str) -> Option<(u8, &str)> {
+    let trimmed = line.trim_start_matches(' ');
+    if line.len() - trimmed.len() > 3 {
+        return None;
+    }
+    let level = trimmed.bytes().take_while(|b| *b == b'#').count();
+    let rest = &trimmed[level..];
+    if !(1..=6).contains(&level) || !(rest.is_empty() || rest.starts_with([' ', '\t'])) {
+        return None;
+    }
+    // Optional closing sequence: ` ##`
+    let rest = rest.trim();
+    let without_closing = rest.trim_end_matches('#');
+    let text = if without_closing.is_empty() || without_closing.ends_with([' ', '\t']) {
+        without_closing.trim_end()
+    } else {
+        rest
+    };
+    Some((level as u8, text))
+}
+
+/// Setext underline level: 1 for `===`, 2 for `---`.
+fn setext_level(line: &str) -> Option<u8> {
+    let trimmed = line.trim();
+    if line.len() - line.trim_start_matches(' ').len() > 3 || trimmed.is_empty() {
+        return None;
+    }
+    if trimmed.bytes().all(|b| b == b'=') {
+        Some(1)
+    } else if trimmed.bytes().all(|b| b == b'-') {
+        Some(2)
+    } else {
+        None
+    }
+}
+
+/// Whether a line can be the text of a setext heading (plain paragraph text).
+fn is_paragraph_line(line: &str) -> bool {
+    let trimmed = line.trim_start_matches(' ');
+    if trimmed.trim().is_empty() || line.len() - trimmed.len() > 3 {
+        return false;
+    }
+    let list_item = trimmed
+        .strip_prefix(['-', '*', '+'])
+        .or_else(|| {
+            let digits = trimmed.bytes().take_while(u8::is_ascii_digit).count();
+            (digits > 0)
+                .then(|| trimmed[digits..].strip_prefix(['.', ')']))
+                .flatten()
+        })
+        .is_some_and(|rest| rest.is_empty() || rest.starts_with([' ', '\t']));
+    !list_item
+        && !trimmed.starts_with(['>', '|', '<'])
+        && atx_heading(line).is_none()
+        && setext_level(line).is_none()
+        && links::fence_marker(line).is_none()
+}
+
+/// Heading text as rendered: links and images reduced to their text,
+/// emphasis, code and HTML markers removed.
+fn plain_text(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let chars: Vec<char> = text.chars().collect();
+    let mut i = 0;
+    while i < chars.len() {
+        match chars[i] {
+            '\\' if i + 1 < chars.len() => {
+                out.push(chars[i + 1]);
+                i += 2;
+                continue;
+            }
+            '!' if chars.get(i + 1) == Some(&'[') => {}
+            '[' | ']' | '*' | '`' | '~' => {}
+            '(' if i > 0 && chars[i - 1] == ']' => {
+                // Skip the link destination
+                while i < chars.len() && chars[i] != ')' {
+                    i += 1;
+                }
+            }
+            '<' => {
+                while i < chars.len() && chars[i] != '>' {
+                    i += 1;
+                }
+            }
+            '_' => {
+                // Only emphasis underscores, not ones inside words
+                let before = i > 0 && chars[i - 1].is_alphanumeric();
+                let after = chars.get(i + 1).is_some_and(|c| c.is_alphanumeric());
+                if before && after {
+                    out.push('_');
+                }
+            }
+            c => out.push(c),
+        }
+        i += 1;
+    }
+    out.trim().to_string()
+}
+
+fn slug(text: &str, style: AnchorStyle) -> String {
+    match style {
+        AnchorStyle::Github => text
+            .to_lowercase()
+            .chars()
+            .filter(|c| c.is_alphanumeric() || matches!(c, ' ' | '-' | '_'))
+            .map(|c| if c == ' ' { '-' } else { c })
+            .collect(),
+        AnchorStyle::Kramdown => {
+            let kept: String = text
+                .chars()
+                .filter(|c| c.is_ascii_alphanumeric() || matches!(c, ' ' | '-'))
+                .collect();
+            let from_letter = kept.trim_start_matches(|c: char| !c.is_ascii_alphabetic());
+            let slug: String = from_letter
+                .to_ascii_lowercase()
+                .chars()
+                .map(|c| if c == ' ' { '-' } else { c })
+                .collect();
+            if slug.is_empty() {
+                "section".to_string()
+            } else {
+                slug
+            }
+        }
+    }
+}
+
+/// All headings of a document in order, with unique anchors.
+pub fn scan_headings(content: &str, style: AnchorStyle) -> Vec<Heading> {
+    let body_start = frontmatter::body_start(content);
+    let first_line = content[..body_start].matches('\n').count();
+    let lines: Vec<&str> = content[body_start..].lines().collect();
+
+    let mut found = Vec::new();
+    let mut fence = None;
+    for (index, line) in lines.iter().enumerate() {
+        if links::update_fence(&mut fence, line) || fence.is_some() {
+            continue;
+        }
+        if let Some((level, text)) = atx_heading(line) {
+            found.push((level, text.to_string(), index));
+            continue;
+        }
+        let previous = index.checked_sub(1).map(|i| lines[i]);
+        let starts_paragraph = index < 2 || lines[index - 2].trim().is_empty();
+        if let (Some(level), Some(previous)) = (setext_level(line), previous) {
+            // Only single-line paragraphs; a `---` under anything else is a rule
+            if starts_paragraph && is_paragraph_line(previous) {
+                found.push((level, previous.trim().to_string(), index - 1));
+            }
+        }
+    }
+
+    let mut seen: HashMap<String, usize> = HashMap::new();
+    found
+        .into_iter()
+        .map(|(level, raw, index)| {
+            let text = plain_text(&raw);
+            let base = slug(&text, style);
+            let count = seen.entry(base.clone()).or_insert(0);
+            let anchor = if *count == 0 {
+                base
+            } else {
+                format!("{base}-{count}")
+            };
+            *count += 1;
+            Heading {
+                level,
+                text,
+                line: first_line + index + 1,
+                anchor,
+            }
+        })
+        .collect()
+}
+
+/// Add a heading under the last entry it is deeper than.
+fn insert(entries: &mut Vec<TocEntry>, heading: Heading) {
+    match entries.last_mut() {
+        Some(last) if last.heading.level < heading.level => insert(&mut last.children, heading),
+        _ => entries.push(TocEntry {
+            heading,
+            children: Vec::new(),
+        }),
+    }
+}
+
+fn write_markdown(entries: &[TocEntry], depth: usize, ordered: bool, out: &mut String) {
+    let indent = if ordered { 3 } else { 2 };
+    for (index, entry) in entries.iter().enumerate() {
+        let marker = if ordered {
+            format!("{}.", index + 1)
+        } else {
+            "-".to_string()
+        };
+        let text = entry.heading.text.replace('[', "\\[").replace(']', "\\]");
+        out.push_str(&format!(
+            "{:width$}{marker} [{text}](#{})\n",
+            "",
+            entry.heading.anchor,
+            width = depth * indent
+        ));
+        write_markdown(&entry.children, depth + 1, ordered, out);
+    }
+}
+
+fn build_toc(content: &str, options: &TocOptions) -> Toc {
+    let min = options.min_level.unwrap_or(1);
+    let max = options.max_level.unwrap_or(6);
+    let headings = scan_headings(content, options.anchor_style)
+        .into_iter()
+        .filter(|h| (min..=max).contains(&h.level));
+    let mut entries = Vec::new();
+    for heading in headings {
+        insert(&mut entries, heading);
+    }
+    let mut markdown = String::new();
+    write_markdown(&entries, 0, options.ordered, &mut markdown);
+    Toc { entries, markdown }
+}
+
+/// Build a table of contents for a document, given by path or content.
+#[tauri::command]
+pub fn generate_toc(
+    path: Option<String>,
+    content: Option<String>,
+    options: Option<TocOptions>,
+) -> Result<Toc, String> {
+    let content = match (content, path) {
+        (Some(content), _) => content,
+        (None, Some(path)) => {
+            fs::read_to_string(&path).map_err(|e| format!("Failed to read file: {e}"))?
+        }
+        (None, None) => return Err("Either path or content is required".to_string()),
+    };
+    Ok(build_toc(&content, &options.unwrap_or_default()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scan_headings() {
+        let content = "---\ntitle: T\n---\n# Intro ##\n\nSetext *Title*\n=====\n\n```\n# not a heading\n```\n\n- item\n---\n\n## Intro\n### [API](api.md) `v2`\n";
+        let headings = scan_headings(content, AnchorStyle::Github);
+        let summary: Vec<_> = headings
+            .iter()
+            .map(|h| (h.level, h.text.as_str(), h.line, h.anchor.as_str()))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (1, "Intro", 4, "intro"),
+                (1, "Setext Title", 6, "setext-title"),
+                (2, "Intro", 16, "intro-1"),
+                (3, "API v2", 17, "api-v2"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_toc_markdown() {
+        let content = "# Guide\n## 1. Install\n### macOS\n## Use\n# FAQ\n";
+        let options = TocOptions {
+            min_level: Some(2),
+            anchor_style: AnchorStyle::Kramdown,
+            ..Default::default()
+        };
+        let toc = build_toc(content, &options);
+        assert_eq!(toc.entries.len(), 2);
+        assert_eq!(
+            toc.markdown,
+            "- [1. Install](#install)\n  - [macOS](#macos)\n- [Use](#use)\n"
+        );
+    }
+}
@@ -0,0 +1,256 @@
+//! Smart typography: curly quotes, dashes and ellipses.
+//!
+//! `"` and `'` become the quotation marks of the document's locale (「」
+//! for Japanese and traditional Chinese), `--` and `---` become en and em
+//! dashes, and `...` an ellipsis. Code, math, HTML, link destinations,
+//! frontmatter and markdown syntax made of dashes (rules, table delimiter
+//! rows) are left alone.
+
+use std::ops::Range;
+
+use crate::formatter::{self, VerbatimBlocks};
+use crate::frontmatter;
+
+/// Opening and closing marks for double and single quotes.
+struct QuoteStyle {
+    double: (&'static str, &'static str),
+    single: (&'static str, &'static str),
+}
+
+const ENGLISH: QuoteStyle = QuoteStyle {
+    double: ("“", "”"),
+    single: ("‘", "’"),
+};
+
+const GERMAN: QuoteStyle = QuoteStyle {
+    double: ("„", "“"),
+    single: ("‚", "‘"),
+};
+
+/// French quotes with narrow no-break spaces inside
+const FRENCH: QuoteStyle = QuoteStyle {
+    double: ("«\u{202F}", "\u{202F}»"),
+    single: ("‹\u{202F}", "\u{202F}›"),
+};
+
+const GUILLEMETS: QuoteStyle = QuoteStyle {
+    double: ("«", "»"),
+    single: ("“", "”"),
+};
+
+const CORNER_BRACKETS: QuoteStyle = QuoteStyle {
+    double: ("「", "」"),
+    single: ("『", "』"),
+};
+
+fn quote_style(locale: &str) -> &'static QuoteStyle {
+    let locale = locale.to_ascii_lowercase().replace('_', "-");
+    let mut parts = locale.split('-');
+    let language = parts.next().unwrap_or_default();
+    let traditional = parts.any(|part| matches!(part, "hant" | "tw" | "hk" | "mo"));
+    match language {
+        "ja" => &CORNER_BRACKETS,
+        "zh" if traditional => &CORNER_BRACKETS,
+        "de" | "cs" | "sk" => &GERMAN,
+        "fr" => &FRENCH,
+        "es" | "it" | "ru" | "uk" => &GUILLEMETS,
+        _ => &ENGLISH,
+    }
+}
+
+/// Letters and digits of alphabetic scripts, where `'` is an apostrophe.
+fn is_word_char(c: Option<char>) -> bool {
+    c.is_some_and(|c| c.is_alphanumeric() && (c as u32) < 0x2E80)
+}
+
+/// Whether a quote next to `c` sits at a word boundary on that side.
+fn is_boundary(c: Option<char>, opening_side: bool) -> bool {
+    let Some(c) = c else {
+        return true;
+    };
+    let punctuation = if opening_side {
+        "([{<“‘「『—–-/"
+    } else {
+        ".,;:!?)]}>…—–-/。，；：！？、"
+    };
+    c.is_whitespace() || punctuation.contains(c)
+}
+
+/// Byte ranges of a line that stay as written: code spans, HTML tags and
+/// comments, link destinations, URLs and inline math.
+pub fn protected(line: &str) -> Vec<Range<usize>> {
+    let mut ranges = formatter::protected_ranges(line);
+    let mut from = 0;
+    while let Some(start) = line[from..].find("<!--").map(|i| from + i) {
+        let end = line[start..]
+            .find("-->")
+            .map_or(line.len(), |i| start + i + 3);
+        ranges.push(start..end);
+        from = end;
+    }
+    let dollars: Vec<usize> = line
+        .match_indices('$')
+        .map(|(i, _)| i)
+        .filter(|&i| !line[..i].ends_with('\\'))
+        .collect();
+    for pair in dollars.chunks_exact(2) {
+        ranges.push(pair[0]..pair[1] + 1);
+    }
+    ranges
+}
+
+/// Quotes left open by earlier lines of the paragraph.
+#[derive(Default)]
+struct OpenQuotes {
+    double: bool,
+    single: bool,
+}
+
+fn smarten_line(line: &str, style: &QuoteStyle, open: &mut OpenQuotes) -> String {
+    let protected = protected(line);
+    let chars: Vec<(usize, char)> = line.char_indices().collect();
+    let mut out = String::with_capacity(line.len() + 8);
+    let mut i = 0;
+    while i < chars.len() {
+        let (at, c) = chars[i];
+        let prev = i.checked_sub(1).map(|p| chars[p].1);
+        let next = chars.get(i + 1).map(|n| n.1);
+        if protected.iter().any(|r| r.contains(&at)) {
+            out.push(c);
+            i += 1;
+            continue;
+        }
+        let run = chars[i..].iter().take_while(|(_, r)| *r == c).count();
+        match c {
+            '\\' => {
+                out.push(c);
+                if let Some(next) = next {
+                    out.push(next);
+                }
+                i += 2;
+                continue;
+            }
+            '.' if run == 3 => out.push('…'),
+            '-' if run == 2 => out.push('–'),
+            '-' if run == 3 => out.push('—'),
+            '.' | '-' => {
+                out.extend(std::iter::repeat_n(c, run));
+            }
+            '\'' if (is_word_char(prev) && is_word_char(next))
+                || (is_boundary(prev, true) && next.is_some_and(|n| n.is_ascii_digit())) =>
+            {
+                // don't, l'homme, '90s
+                out.push('’');
+            }
+            '"' | '\'' => {
+                let (marks, is_open) = if c == '"' {
+                    (style.double, &mut open.double)
+                } else {
+                    (style.single, &mut open.single)
+                };
+                let opens = match (is_boundary(prev, true), is_boundary(next, false)) {
+                    (true, false) => Some(true),
+                    (false, true) => Some(false),
+                    // Between two words (common in CJK text): alternate
+                    (false, false) => Some(!*is_open),
+                    (true, true) => None,
+                };
+                match opens {
+                    Some(true) => {
+                        out.push_str(marks.0);
+                        *is_open = true;
+                    }
+                    Some(false) if c == '\'' && !*is_open => out.push('’'),
+                    Some(false) => {
+                        out.push_str(marks.1);
+                        *is_open = false;
+                    }
+                    None => out.push(c),
+                }
+            }
+            _ => out.push(c),
+        }
+        i += if matches!(c, '.' | '-') { run } else { 1 };
+    }
+    out
+}
+
+/// Markdown syntax made of dashes: rules, setext underlines and table
+/// delimiter rows.
+pub fn is_syntax_line(line: &str) -> bool {
+    let trimmed = line.trim();
+    formatter::is_thematic_break(trimmed)
+        || (!trimmed.is_empty() && trimmed.bytes().all(|b| matches!(b, b'=' | b'-' | b' ')))
+        || (trimmed.contains('|')
+            && trimmed
+                .bytes()
+                .all(|b| matches!(b, b'|' | b':' | b'-' | b' ')))
+}
+
+/// Apply smart typography to markdown `content` for `locale` (a BCP 47
+/// tag such as "en-US", "de" or "zh-Hant").
+pub fn smarten(content: &str, locale: &str) -> String {
+    let style = quote_style(locale);
+    let body_start = frontmatter::body_start(content);
+    let mut out = String::with_capacity(content.len());
+    out.push_str(&content[..body_start]);
+    let mut verbatim = VerbatimBlocks::default();
+    let mut open = OpenQuotes::default();
+    for line in content[body_start..].split_inclusive('\n') {
+        let text = line.trim_end_matches(['\n', '\r']);
+        let indented = text.starts_with("    ") || text.starts_with('\t');
+        if verbatim.update(text)
+            || indented
+            || is_syntax_line(text)
+            || formatter::is_reference_definition(text.trim_start())
+        {
+            out.push_str(line);
+            continue;
+        }
+        if text.trim().is_empty() {
+            open = OpenQuotes::default();
+        }
+        out.push_str(&smarten_line(text, style, &mut open));
+        out.push_str(&line[text.len()..]);
+    }
+    out
+}
+
+/// Convert straight quotes, `--`, `---` and `...` in `content`.
+#[tauri::command]
+pub fn smarten_typography(content: String, locale: Option<String>) -> String {
+    smarten(&content, locale.as_deref().unwrap_or("en"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_smarten_english() {
+        let content = "---\ntitle: \"x\"\n---\n\"Don't,\" she said -- it's 'ok'... in the '90s --- `\"code\"` [a](b--c.md \"t\")\n\n| a | b |\n|---|---|\n\n```\n\"fenced\"\n```\n";
+        assert_eq!(
+            smarten(content, "en-US"),
+            "---\ntitle: \"x\"\n---\n“Don’t,” she said – it’s ‘ok’… in the ’90s — `\"code\"` [a](b--c.md \"t\")\n\n| a | b |\n|---|---|\n\n```\n\"fenced\"\n```\n"
+        );
+    }
+
+    #[test]
+    fn test_smarten_locales() {
+        assert_eq!(smarten("他说\"你好\"。", "zh-Hant"), "他说「你好」。");
+        assert_eq!(
+            smarten("Er sagt \"Hallo\".\n", "de-DE"),
+            "Er sagt „Hallo“.\n"
+        );
+        assert_eq!(smarten("\"Salut\"", "fr"), "«\u{202F}Salut\u{202F}»");
+    }
+
+    #[test]
+    fn test_smarten_skips_math_and_reference_definitions() {
+        let content = "$$\n\"x\" -- y\n$$\n\n[id]: http://a--b.com \"Title\"\n  [^1]: \"note\"\n";
+        assert_eq!(
+            smarten(content, "en-US"),
+            "$$\n\"x\" -- y\n$$\n\n[id]: http://a--b.com \"Title\"\n  [^1]: “note”\n"
+        );
+    }
+}
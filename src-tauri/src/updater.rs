@@ -0,0 +1,170 @@
+//! App updates through the Tauri updater plugin.
+//!
+//! Updates are checked on demand (`check_for_updates`, from the Updates
+//! section of Settings that Check for Updates… opens) and on a schedule
+//! set by the frontend (`set_update_check_interval`).
+//! `download_and_install` downloads the update with `updater:progress`
+//! events, then starts the coordinated quit: the update is installed and
+//! the app restarted only after every window has handled its unsaved
+//! changes. If that quit is cancelled, the update is installed on the next
+//! quit instead.
+
+use serde::Serialize;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter};
+use tauri_plugin_updater::{Update, UpdaterExt};
+
+use crate::quit;
+
+/// Delay before the first scheduled check, to stay out of the way of startup
+const FIRST_CHECK_DELAY: Duration = Duration::from_secs(30);
+
+/// Update found by the last check
+static AVAILABLE: Mutex<Option<Update>> = Mutex::new(None);
+/// Update downloaded and waiting for the quit to install it
+static DOWNLOADED: Mutex<Option<(Update, Vec<u8>)>> = Mutex::new(None);
+static DOWNLOADING: AtomicBool = AtomicBool::new(false);
+/// Incremented whenever the schedule changes so older check loops stop.
+static SCHEDULE_GENERATION: AtomicU64 = AtomicU64::new(0);
+
+#[derive(Debug, Clone, Serialize)]
+pub struct UpdateInfo {
+    pub version: String,
+    #[serde(rename = "currentVersion")]
+    pub current_version: String,
+    /// Release notes
+    pub notes: Option<String>,
+    /// Release date
+    pub date: Option<String>,
+}
+
+impl From<&Update> for UpdateInfo {
+    fn from(update: &Update) -> Self {
+        Self {
+            version: update.version.clone(),
+            current_version: update.current_version.clone(),
+            notes: update.body.clone(),
+            date: update.date.map(|date| date.to_string()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct DownloadProgress {
+    downloaded: u64,
+    total: Option<u64>,
+}
+
+async fn check(app: &AppHandle) -> Result<Option<UpdateInfo>, String> {
+    let update = app
+        .updater()
+        .map_err(|e| format!("Failed to initialize updater: {e}"))?
+        .check()
+        .await
+        .map_err(|e| format!("Failed to check for updates: {e}"))?;
+    let info = update.as_ref().map(UpdateInfo::from);
+    if let Ok(mut available) = AVAILABLE.lock() {
+        *available = update;
+    }
+    if let Some(info) = &info {
+        let _ = app.emit("updater:available", info);
+    }
+    Ok(info)
+}
+
+/// Check for a newer version. Also emits `updater:available` when found.
+#[tauri::command]
+pub async fn check_for_updates(app: AppHandle) -> Result<Option<UpdateInfo>, String> {
+    check(&app).await
+}
+
+/// Download the available update, then quit to install it and restart.
+#[tauri::command]
+pub async fn download_and_install(app: AppHandle) -> Result<(), String> {
+    let update = AVAILABLE
+        .lock()
+        .ok()
+        .and_then(|available| available.clone());
+    let update = match update {
+        Some(update) => update,
+        None => {
+            check(&app).await?;
+            AVAILABLE
+                .lock()
+                .ok()
+                .and_then(|available| available.clone())
+                .ok_or("No update available")?
+        }
+    };
+
+    if DOWNLOADING.swap(true, Ordering::SeqCst) {
+        return Err("An update is already downloading".to_string());
+    }
+    let mut downloaded = 0u64;
+    let progress_app = app.clone();
+    let result = update
+        .download(
+            move |chunk, total| {
+                downloaded += chunk as u64;
+                let _ =
+                    progress_app.emit("updater:progress", DownloadProgress { downloaded, total });
+            },
+            || {},
+        )
+        .await;
+    DOWNLOADING.store(false, Ordering::SeqCst);
+    let bytes = result.map_err(|e| format!("Failed to download update: {e}"))?;
+
+    let _ = app.emit("updater:downloaded", UpdateInfo::from(&update));
+    if let Ok(mut pending) = DOWNLOADED.lock() {
+        *pending = Some((update, bytes));
+    }
+    quit::start_quit(&app);
+    Ok(())
+}
+
+/// Install a downloaded update. Called by the coordinated quit once all
+/// windows are closed; returns true if the app should restart.
+pub fn install_downloaded() -> bool {
+    let Some((update, bytes)) = DOWNLOADED
+        .lock()
+        .ok()
+        .and_then(|mut pending| pending.take())
+    else {
+        return false;
+    };
+    match update.install(bytes) {
+        Ok(()) => true,
+        Err(_e) => {
+            #[cfg(debug_assertions)]
+            eprintln!("[updater] Failed to install update: {}", _e);
+            false
+        }
+    }
+}
+
+/// Check for updates every `hours` hours, starting shortly after the call;
+/// 0 turns scheduled checks off.
+#[tauri::command]
+pub fn set_update_check_interval(app: AppHandle, hours: u64) {
+    let generation = SCHEDULE_GENERATION.fetch_add(1, Ordering::SeqCst) + 1;
+    if hours == 0 {
+        return;
+    }
+    tauri::async_runtime::spawn(async move {
+        let mut delay = FIRST_CHECK_DELAY;
+        loop {
+            tokio::time::sleep(delay).await;
+            if SCHEDULE_GENERATION.load(Ordering::SeqCst) != generation {
+                return;
+            }
+            if let Err(_e) = check(&app).await {
+                #[cfg(debug_assertions)]
+                eprintln!("[updater] {}", _e);
+            }
+            delay = Duration::from_secs(hours * 60 * 60);
+        }
+    });
+}
@@ -1,13 +1,41 @@
 use notify::{Config, Event, RecommendedWatcher, RecursiveMode, Watcher};
 use serde::Serialize;
 use std::collections::HashMap;
-use std::path::Path;
-use std::sync::Mutex;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{LazyLock, Mutex};
+use std::time::{Duration, Instant, SystemTime};
 use tauri::{AppHandle, Emitter};
 
+use crate::exclude::ExcludeMatcher;
+use crate::{document_io, file_tree, menu, templates, wikilinks, workspace};
+
 /// Watchers keyed by watch_id (typically window label or unique identifier)
 static WATCHERS: Mutex<Option<HashMap<String, WatcherEntry>>> = Mutex::new(None);
 
+/// Files the app is about to write, so their events aren't reported as
+/// external changes
+static SELF_WRITES: LazyLock<Mutex<HashMap<PathBuf, SelfWrite>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// How long events for a registered write are swallowed
+const SELF_WRITE_TTL: Duration = Duration::from_secs(2);
+
+struct SelfWrite {
+    /// What the file must hold on disk; None for our temp files
+    expected: Option<Expected>,
+    expires: Instant,
+}
+
+#[derive(Clone)]
+struct Expected {
+    len: u64,
+    checksum: String,
+    /// Modification time at which the checksum last matched, so further
+    /// events for the same write aren't hashed again
+    verified: Option<SystemTime>,
+}
+
 struct WatcherEntry {
     /// Stored to keep the watcher alive; dropping stops watching
     _watcher: RecommendedWatcher,
@@ -46,12 +74,158 @@ fn event_kind_to_string(kind: &notify::EventKind) -> Option<&'static str> {
     }
 }
 
+/// Check whether every path of an event is excluded by workspace rules.
+/// Events are kept whole so rename pairs stay aligned.
+fn is_event_excluded(excludes: &ExcludeMatcher, event: &Event) -> bool {
+    !event.paths.is_empty() && event.paths.iter().all(|p| excludes.is_excluded_path(p))
+}
+
+/// Register a save about to replace `path` (`target` once symlinks are
+/// resolved) with `content` via the temp file `temp`.
+pub fn expect_write(path: &Path, target: &Path, temp: &Path, content: &[u8]) {
+    register_write(&SELF_WRITES, path, target, temp, content);
+}
+
+fn register_write(
+    writes: &Mutex<HashMap<PathBuf, SelfWrite>>,
+    path: &Path,
+    target: &Path,
+    temp: &Path,
+    content: &[u8],
+) {
+    let expected = Expected {
+        len: content.len() as u64,
+        checksum: document_io::checksum(content),
+        verified: None,
+    };
+    let expires = Instant::now() + SELF_WRITE_TTL;
+    // Events may name the file as opened or by its canonical path
+    let canonical = fs::canonicalize(path).unwrap_or_else(|_| target.to_path_buf());
+    if let Ok(mut writes) = writes.lock() {
+        for key in [path, target, canonical.as_path()] {
+            writes.insert(
+                key.to_path_buf(),
+                SelfWrite {
+                    expected: Some(expected.clone()),
+                    expires,
+                },
+            );
+        }
+        writes.insert(
+            temp.to_path_buf(),
+            SelfWrite {
+                expected: None,
+                expires,
+            },
+        );
+    }
+}
+
+/// Whether `path` on disk holds what we wrote, with its modification time.
+/// The size is compared first; a file already verified at the same
+/// modification time isn't read again.
+fn matches_disk(path: &Path, expected: &Expected) -> (bool, Option<SystemTime>) {
+    let Ok(meta) = fs::metadata(path) else {
+        return (false, None);
+    };
+    if meta.len() != expected.len {
+        return (false, None);
+    }
+    let modified = meta.modified().ok();
+    if modified.is_some() && modified == expected.verified {
+        return (true, modified);
+    }
+    let matches =
+        fs::read(path).is_ok_and(|bytes| document_io::checksum(&bytes) == expected.checksum);
+    (matches, modified)
+}
+
+/// Whether every one of `paths` was written by the app. A file that was
+/// changed again after our write no longer matches and is reported. Files
+/// are hashed without holding the lock.
+fn is_self_write<P: AsRef<Path>>(writes: &Mutex<HashMap<PathBuf, SelfWrite>>, paths: &[P]) -> bool {
+    let pending: Vec<(&Path, Option<Expected>)> = {
+        let Ok(mut writes) = writes.lock() else {
+            return false;
+        };
+        let now = Instant::now();
+        writes.retain(|_, write| write.expires > now);
+        let mut pending = Vec::with_capacity(paths.len());
+        for path in paths.iter().map(AsRef::as_ref) {
+            match writes.get(path) {
+                Some(write) => pending.push((path, write.expected.clone())),
+                None => return false,
+            }
+        }
+        pending
+    };
+
+    let mut verified = Vec::new();
+    for (path, expected) in pending {
+        let Some(expected) = expected else {
+            continue;
+        };
+        match matches_disk(path, &expected) {
+            (true, Some(modified)) => verified.push((path, modified)),
+            (true, None) => {}
+            (false, _) => return false,
+        }
+    }
+    if let Ok(mut writes) = writes.lock() {
+        for (path, modified) in verified {
+            if let Some(expected) = writes.get_mut(path).and_then(|w| w.expected.as_mut()) {
+                expected.verified = Some(modified);
+            }
+        }
+    }
+    true
+}
+
+/// Check whether every path of an event comes from our own writes.
+fn is_own_event(event: &Event) -> bool {
+    !event.paths.is_empty() && is_self_write(&SELF_WRITES, &event.paths)
+}
+
 /// Handle a notify event and emit it to the frontend.
-fn handle_event(app: &AppHandle, watch_id: &str, root_path: &str, event: Event) {
+///
+/// `root` is the canonical workspace root the exclude rules, templates and
+/// note index belong to; `root_path` is the watched path as requested.
+fn handle_event(
+    app: &AppHandle,
+    watch_id: &str,
+    root_path: &str,
+    root: &Path,
+    excludes: &Mutex<ExcludeMatcher>,
+    event: Event,
+) {
     let Some(kind_str) = event_kind_to_string(&event.kind) else {
         return;
     };
 
+    if let Ok(mut excludes) = excludes.lock() {
+        // Pick up edited exclude rules without restarting the watcher
+        let config_path = workspace::get_workspace_file_path(root);
+        let config_changed = event.paths.iter().any(|p| p == &config_path);
+        if config_changed {
+            *excludes = ExcludeMatcher::for_root(root);
+        }
+        // `.vmark/` is usually excluded, so refresh the templates and
+        // favorites (pinned files live in the config) before filtering
+        if config_changed || event.paths.iter().any(|p| templates::is_template_change(root, p)) {
+            menu::refresh_workspace_menus(app);
+        }
+        if is_event_excluded(&excludes, &event) {
+            return;
+        }
+    }
+    // Our own saves can change aliases too, so drop the note index first
+    if event.paths.iter().any(|p| file_tree::is_markdown_path(p)) {
+        wikilinks::invalidate(root);
+    }
+    if is_own_event(&event) {
+        return;
+    }
+
     // Collect all paths from the event
     let paths: Vec<String> = event
         .paths
@@ -79,8 +253,14 @@ fn handle_event(app: &AppHandle, watch_id: &str, root_path: &str, event: Event)
 /// * `app` - Tauri app handle for emitting events
 /// * `watch_id` - Unique identifier for this watcher (typically window label)
 /// * `path` - Directory path to watch recursively
+/// * `workspace_root` - Workspace whose exclude rules apply (default: `path`)
 #[tauri::command]
-pub fn start_watching(app: AppHandle, watch_id: String, path: String) -> Result<(), String> {
+pub fn start_watching(
+    app: AppHandle,
+    watch_id: String,
+    path: String,
+    workspace_root: Option<String>,
+) -> Result<(), String> {
     let watch_path = Path::new(&path);
     if !watch_path.exists() {
         return Err(format!("Path does not exist: {path}"));
@@ -92,11 +272,22 @@ pub fn start_watching(app: AppHandle, watch_id: String, path: String) -> Result<
     let app_handle = app.clone();
     let watch_id_clone = watch_id.clone();
     let root_path_clone = path.clone();
+    // Canonical, as notify reports canonical paths (e.g. /private/var on macOS)
+    let workspace_root = workspace_root.map_or_else(|| watch_path.to_path_buf(), PathBuf::from);
+    let workspace_root = fs::canonicalize(&workspace_root).unwrap_or(workspace_root);
+    let excludes = Mutex::new(ExcludeMatcher::for_root(&workspace_root));
 
     let mut watcher = RecommendedWatcher::new(
         move |res: Result<Event, notify::Error>| {
             if let Ok(event) = res {
-                handle_event(&app_handle, &watch_id_clone, &root_path_clone, event);
+                handle_event(
+                    &app_handle,
+                    &watch_id_clone,
+                    &root_path_clone,
+                    &workspace_root,
+                    &excludes,
+                    event,
+                );
             }
         },
         Config::default(),
@@ -179,6 +370,63 @@ mod tests {
         assert_eq!(event_kind_to_string(&kind), None);
     }
 
+    #[test]
+    fn test_event_excluded_only_when_all_paths_match() {
+        let excludes = ExcludeMatcher::new(
+            Path::new("/ws"),
+            vec!["node_modules".to_string()],
+            &["*.tmp.md".to_string()],
+        );
+        let event = |paths: &[&str]| {
+            paths.iter().fold(
+                Event::new(EventKind::Create(notify::event::CreateKind::File)),
+                |event, path| event.add_path(path.into()),
+            )
+        };
+
+        assert!(is_event_excluded(&excludes, &event(&["/ws/node_modules/a.md"])));
+        assert!(is_event_excluded(&excludes, &event(&["/ws/a.tmp.md"])));
+        assert!(!is_event_excluded(&excludes, &event(&["/ws/a.tmp.md", "/ws/a.md"])));
+        assert!(!is_event_excluded(&excludes, &event(&["/ws/notes/a.md"])));
+    }
+
+    #[test]
+    fn test_self_write_matches_checksum() {
+        let dir = tempfile::tempdir().unwrap();
+        let real = dir.path().join("real");
+        fs::create_dir_all(&real).unwrap();
+        let path = real.join("a.md");
+        let temp = real.join(".a.md.1-0.tmp");
+        fs::write(&path, "saved").unwrap();
+        let link = dir.path().join("link");
+        #[cfg(unix)]
+        std::os::unix::fs::symlink(&real, &link).unwrap();
+
+        let writes = Mutex::new(HashMap::new());
+        let opened = link.join("a.md");
+        register_write(&writes, &opened, &path, &temp, b"saved");
+        assert!(is_self_write(&writes, &[&path, &temp]));
+        assert!(is_self_write(&writes, &[&path]));
+        #[cfg(unix)]
+        assert!(is_self_write(&writes, &[&opened]));
+        assert!(!is_self_write(&writes, &[path.clone(), real.join("b.md")]));
+
+        // Edited by someone else after our save, at the same size and not
+        let verified = writes.lock().unwrap()[&path]
+            .expected
+            .as_ref()
+            .unwrap()
+            .verified;
+        assert!(verified.is_some());
+        fs::write(&path, "SAVED").unwrap();
+        let modified = fs::metadata(&path).unwrap().modified().ok();
+        if modified != verified {
+            assert!(!is_self_write(&writes, &[&path]));
+        }
+        fs::write(&path, "changed").unwrap();
+        assert!(!is_self_write(&writes, &[path]));
+    }
+
     #[test]
     fn test_fs_change_event_serialization() {
         let event = FsChangeEvent {
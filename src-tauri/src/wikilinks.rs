@@ -0,0 +1,443 @@
+//! Wiki link resolution: which note `[[Name]]` points to.
+//!
+//! A link names a note by file name, by the end of its workspace-relative
+//! path (`[[projects/Plan]]`) or by one of the `aliases` in its
+//! frontmatter. When several notes match, the one in the linking
+//! document's folder wins, then the one closest to the workspace root.
+//! Notes are looked up in a per-workspace index that is built on first use
+//! and rebuilt on the next lookup after the file watcher invalidates it.
+
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fs::{self, OpenOptions};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, LazyLock, Mutex};
+
+use crate::exclude::ExcludeMatcher;
+use crate::toc::{self, AnchorStyle};
+use crate::{document_io, file_tree, frontmatter};
+
+/// Note indexes by canonical workspace root
+static INDEXES: LazyLock<Mutex<HashMap<PathBuf, Index>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+#[derive(Default)]
+struct Index {
+    /// None until built and after an invalidation
+    notes: Option<Arc<Vec<Note>>>,
+    /// Bumped by every invalidation, so a build that raced one is dropped
+    generation: u64,
+}
+
+#[derive(Debug, Clone)]
+struct Note {
+    path: PathBuf,
+    /// Workspace-relative path without the markdown extension, `/`-separated
+    name: String,
+    /// Lowercased frontmatter aliases
+    aliases: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct WikiLinkTarget {
+    /// The note or attachment the link points to
+    pub path: Option<String>,
+    /// Heading or `^block` after `#`
+    pub fragment: Option<String>,
+    /// 1-based line of the fragment in the note
+    #[serde(rename = "fragmentLine")]
+    pub fragment_line: Option<usize>,
+    /// Display text after `|`
+    pub alias: Option<String>,
+    /// Every note the name matched, best first
+    pub candidates: Vec<String>,
+    /// Shortest link text that names the note unambiguously
+    #[serde(rename = "linkText")]
+    pub link_text: Option<String>,
+    /// The note did not exist and was created
+    pub created: bool,
+}
+
+fn note_name(root: &Path, path: &Path) -> String {
+    let relative = path.strip_prefix(root).unwrap_or(path).with_extension("");
+    relative.to_string_lossy().replace('\\', "/")
+}
+
+fn aliases(content: &str) -> Vec<String> {
+    let Ok(Some(matter)) = frontmatter::parse(content) else {
+        return Vec::new();
+    };
+    ["aliases", "alias"]
+        .iter()
+        .filter_map(|key| matter.data.get(key))
+        .flat_map(|value| match value {
+            Value::String(alias) => vec![alias.clone()],
+            Value::Array(items) => items
+                .iter()
+                .filter_map(|item| item.as_str().map(str::to_string))
+                .collect(),
+            _ => Vec::new(),
+        })
+        .map(|alias| alias.trim().to_lowercase())
+        .collect()
+}
+
+fn build_index(root: &Path) -> Vec<Note> {
+    file_tree::collect_markdown_files(root, &ExcludeMatcher::for_root(root))
+        .into_iter()
+        .map(|path| Note {
+            name: note_name(root, &path),
+            aliases: fs::read_to_string(&path)
+                .map(|content| aliases(&content))
+                .unwrap_or_default(),
+            path,
+        })
+        .collect()
+}
+
+/// Mark the note index of `root` stale after notes changed on disk.
+pub fn invalidate(root: &Path) {
+    if let Ok(mut indexes) = INDEXES.lock() {
+        let index = indexes
+            .entry(document_io::canonical_path(root))
+            .or_default();
+        index.notes = None;
+        index.generation += 1;
+    }
+}
+
+/// The note index of the canonical `root`, built outside the lock when it
+/// is missing or stale.
+fn index(root: &Path) -> Option<Arc<Vec<Note>>> {
+    let generation = {
+        let mut indexes = INDEXES.lock().ok()?;
+        let index = indexes.entry(root.to_path_buf()).or_default();
+        if let Some(notes) = &index.notes {
+            return Some(Arc::clone(notes));
+        }
+        index.generation
+    };
+    let notes = Arc::new(build_index(root));
+    let mut indexes = INDEXES.lock().ok()?;
+    let index = indexes.entry(root.to_path_buf()).or_default();
+    if index.generation == generation {
+        index.notes = Some(Arc::clone(&notes));
+    }
+    Some(notes)
+}
+
+/// Whether note `name` ends with the path components of `target`.
+fn ends_with(name: &str, target: &str) -> bool {
+    let name = name.to_lowercase();
+    name == target || name.ends_with(&format!("/{target}"))
+}
+
+/// Notes matching `target` (a name or path, without extension), best
+/// first: same folder as `from_dir`, then fewest folders, then by path.
+fn matches<'a>(notes: &'a [Note], target: &str, from_dir: &Path) -> Vec<&'a Note> {
+    let target = target.trim().to_lowercase();
+    let mut found: Vec<&Note> = match target.strip_prefix('/') {
+        Some(exact) => notes
+            .iter()
+            .filter(|n| n.name.to_lowercase() == exact)
+            .collect(),
+        None => notes
+            .iter()
+            .filter(|n| ends_with(&n.name, &target))
+            .collect(),
+    };
+    if found.is_empty() {
+        found = notes
+            .iter()
+            .filter(|n| n.aliases.contains(&target))
+            .collect();
+    }
+    found.sort_by_key(|n| {
+        (
+            n.path.parent() != Some(from_dir),
+            n.name.matches('/').count(),
+            n.name.clone(),
+        )
+    });
+    found
+}
+
+/// The note among `paths` that a link to `target` (a name or path without
+/// extension) written in `from_dir` points to, by the rules of `resolve`
+/// minus aliases. Used to follow links across renames, when the index no
+/// longer reflects the disk.
+pub fn resolve_among(
+    root: &Path,
+    paths: &[PathBuf],
+    target: &str,
+    from_dir: &Path,
+) -> Option<PathBuf> {
+    let notes: Vec<Note> = paths
+        .iter()
+        .map(|path| Note {
+            path: path.clone(),
+            name: note_name(root, path),
+            aliases: Vec::new(),
+        })
+        .collect();
+    matches(&notes, target, from_dir)
+        .first()
+        .map(|note| note.path.clone())
+}
+
+/// The shortest trailing part of the note's path that no other note
+/// shares.
+fn shortest_link(notes: &[Note], note: &Note) -> String {
+    let parts: Vec<&str> = note.name.split('/').collect();
+    (1..=parts.len())
+        .map(|n| parts[parts.len() - n..].join("/"))
+        .find(|suffix| {
+            let suffix = suffix.to_lowercase();
+            notes
+                .iter()
+                .filter(|other| ends_with(&other.name, &suffix))
+                .count()
+                == 1
+        })
+        .unwrap_or_else(|| note.name.clone())
+}
+
+/// 1-based line of a heading (by text or anchor) or `^block` id.
+fn fragment_line(path: &Path, fragment: &str) -> Option<usize> {
+    let content = fs::read_to_string(path).ok()?;
+    if let Some(block) = fragment.strip_prefix('^') {
+        let marker = format!(" ^{block}");
+        return content
+            .lines()
+            .position(|line| line.trim_end().ends_with(&marker))
+            .map(|index| index + 1);
+    }
+    toc::scan_headings(&content, AnchorStyle::Github)
+        .into_iter()
+        .find(|h| h.text.eq_ignore_ascii_case(fragment) || h.anchor == fragment.to_lowercase())
+        .map(|h| h.line)
+}
+
+/// Split `[[name#fragment|alias]]` (brackets and `!` optional).
+fn parse_target(target: &str) -> (&str, Option<&str>, Option<&str>) {
+    let inner = target.trim().trim_start_matches('!');
+    let inner = inner
+        .strip_prefix("[[")
+        .and_then(|s| s.strip_suffix("]]"))
+        .unwrap_or(inner);
+    let (link, alias) = match inner.split_once('|') {
+        Some((link, alias)) => (link, Some(alias.trim())),
+        None => (inner, None),
+    };
+    let (name, fragment) = match link.split_once('#') {
+        Some((name, fragment)) => (name.trim(), Some(fragment.trim())),
+        None => (link.trim(), None),
+    };
+    (name, fragment.filter(|f| !f.is_empty()), alias)
+}
+
+/// Resolve a wiki link `target` written in `current_file`.
+pub fn resolve(root: &Path, current_file: &Path, target: &str) -> WikiLinkTarget {
+    let (name, fragment, alias) = parse_target(target);
+    let from_dir = current_file.parent().unwrap_or(root);
+    let mut result = WikiLinkTarget {
+        fragment: fragment.map(str::to_string),
+        alias: alias.map(str::to_string),
+        ..WikiLinkTarget::default()
+    };
+
+    let path = if name.is_empty() {
+        // [[#Heading]] points into the current document
+        Some(current_file.to_path_buf())
+    } else if name.starts_with("./") || name.starts_with("../") {
+        let path = crate::links::normalize_path(&from_dir.join(name));
+        let with_extension = path.with_extension("md");
+        [path, with_extension].into_iter().find(|p| p.is_file())
+    } else {
+        let is_note = file_tree::is_markdown_path(Path::new(name));
+        let stem = if is_note {
+            &name[..name.rfind('.').unwrap_or(name.len())]
+        } else {
+            name
+        };
+        // Embedded attachments sit next to the document or at the root;
+        // anything else with a dot ("v1.2 notes") is a note name
+        let attachment = (!is_note)
+            .then(|| {
+                [from_dir, root]
+                    .iter()
+                    .map(|base| base.join(name))
+                    .find(|p| p.is_file())
+            })
+            .flatten();
+        attachment.or_else(|| lookup(root, stem, from_dir, &mut result))
+    };
+
+    if let Some(path) = path {
+        if let Some(fragment) = fragment {
+            result.fragment_line = fragment_line(&path, fragment);
+        }
+        result.path = Some(path.to_string_lossy().to_string());
+    }
+    result
+}
+
+/// Find `name` in the index of `root`. Paths come back under `root` as
+/// given, even though the index is built from its canonical form.
+fn lookup(
+    root: &Path,
+    name: &str,
+    from_dir: &Path,
+    result: &mut WikiLinkTarget,
+) -> Option<PathBuf> {
+    let canonical_root = document_io::canonical_path(root);
+    let notes = index(&canonical_root)?;
+    let found = matches(&notes, name, &document_io::canonical_path(from_dir));
+    let best = found.first().filter(|n| n.path.is_file())?;
+    let under_root = |note: &Note| {
+        note.path
+            .strip_prefix(&canonical_root)
+            .map(|relative| root.join(relative))
+            .unwrap_or_else(|_| note.path.clone())
+    };
+    result.candidates = found
+        .iter()
+        .map(|n| under_root(n).to_string_lossy().to_string())
+        .collect();
+    result.link_text = Some(shortest_link(&notes, best));
+    Some(under_root(best))
+}
+
+/// Resolve a wiki link written in `current_file`. With `create_if_missing`
+/// a missing note is created: next to the current file for a bare name,
+/// under the workspace root for a path.
+#[tauri::command]
+pub fn resolve_wikilink(
+    current_file: String,
+    target: String,
+    workspace_root: Option<String>,
+    create_if_missing: Option<bool>,
+) -> Result<WikiLinkTarget, String> {
+    let current_file = PathBuf::from(current_file);
+    let root = workspace_root
+        .map(PathBuf::from)
+        .or_else(|| current_file.parent().map(Path::to_path_buf))
+        .unwrap_or_default();
+    let mut result = resolve(&root, &current_file, &target);
+    if result.path.is_some() || !create_if_missing.unwrap_or(false) {
+        return Ok(result);
+    }
+
+    let (name, _, _) = parse_target(&target);
+    if name.is_empty() {
+        return Ok(result);
+    }
+    let name = name.trim_start_matches('/');
+    let base = if name.contains('/') {
+        root.clone()
+    } else {
+        current_file.parent().unwrap_or(&root).to_path_buf()
+    };
+    let mut path = crate::links::normalize_path(&base.join(name));
+    if !file_tree::is_markdown_path(&path) {
+        path.set_extension("md");
+    }
+    if !path.starts_with(&root) {
+        return Err(format!(
+            "Refusing to create a note outside the workspace: {name}"
+        ));
+    }
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| format!("Failed to create folder: {e}"))?;
+    }
+    OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(&path)
+        .map_err(|e| format!("Failed to create note: {e}"))?;
+    invalidate(&root);
+    result.path = Some(path.to_string_lossy().to_string());
+    result.link_text = Some(note_name(&root, &path));
+    result.created = true;
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_resolve_wikilink() {
+        let dir = tempdir().unwrap();
+        let root = dir.path();
+        fs::create_dir_all(root.join("a")).unwrap();
+        fs::create_dir_all(root.join("b/deep")).unwrap();
+        fs::write(
+            root.join("a/Plan.md"),
+            "---\naliases: [Roadmap]\n---\n# Goals\n",
+        )
+        .unwrap();
+        fs::write(root.join("b/deep/Plan.md"), "text ^anchor\n").unwrap();
+        fs::write(root.join("b/Other.md"), "").unwrap();
+        let from = root.join("b/Other.md");
+
+        let result = resolve(root, &from, "[[Plan#goals|the plan]]");
+        assert_eq!(
+            result.path.as_deref(),
+            Some(&*root.join("a/Plan.md").to_string_lossy())
+        );
+        assert_eq!(result.fragment_line, Some(4));
+        assert_eq!(result.alias.as_deref(), Some("the plan"));
+        assert_eq!(result.candidates.len(), 2);
+        assert_eq!(result.link_text.as_deref(), Some("a/Plan"));
+
+        let result = resolve(root, &from, "deep/plan#^anchor");
+        assert_eq!(result.link_text.as_deref(), Some("deep/Plan"));
+        assert_eq!(result.fragment_line, Some(1));
+        let result = resolve(root, &from, "roadmap");
+        assert!(result.path.unwrap().ends_with("Plan.md"));
+
+        // Created after the index was built: found once the watcher
+        // invalidates, through any spelling of the root
+        fs::write(root.join("New.md"), "").unwrap();
+        assert!(resolve(root, &from, "New").path.is_none());
+        invalidate(&root.join("a/.."));
+        let result = resolve(root, &from, "New");
+        assert_eq!(
+            result.path.as_deref(),
+            Some(&*root.join("New.md").to_string_lossy())
+        );
+        assert!(resolve(root, &from, "Missing").path.is_none());
+    }
+
+    #[test]
+    fn test_create_if_missing() {
+        let dir = tempdir().unwrap();
+        let root = dir.path().to_string_lossy().to_string();
+        let current = dir
+            .path()
+            .join("notes/Today.md")
+            .to_string_lossy()
+            .to_string();
+        let result = resolve_wikilink(
+            current.clone(),
+            "Idea".into(),
+            Some(root.clone()),
+            Some(true),
+        )
+        .unwrap();
+        assert!(result.created);
+        assert!(dir.path().join("notes/Idea.md").is_file());
+        let again = resolve_wikilink(
+            current.clone(),
+            "Idea".into(),
+            Some(root.clone()),
+            Some(true),
+        )
+        .unwrap();
+        assert!(!again.created);
+        assert!(resolve_wikilink(current, "../../x".into(), Some(root), Some(true)).is_err());
+    }
+}
@@ -0,0 +1,524 @@
+//! Per-document window geometry.
+//!
+//! When a document window closes, its size and position (plus the monitor it
+//! was on) are saved to `~/.vmark/window-geometry.json`, keyed by the
+//! workspace root or, for loose files, the file path. Reopening the same
+//! workspace or file restores that geometry; new contexts use the cascade.
+//! Maximized and fullscreen windows keep their last normal bounds and are
+//! flagged so they are maximized or made fullscreen again on restore.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{LazyLock, Mutex};
+use tauri::{Monitor, Window};
+
+/// Maximum number of remembered contexts; oldest are dropped first
+const MAX_ENTRIES: usize = 200;
+
+/// How much of the title bar must be on a monitor for a position to be usable
+const MIN_VISIBLE: f64 = 100.0;
+
+/// Saved outer bounds (window frame included) in logical pixels.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct WindowGeometry {
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+    /// Name of the monitor the window was on
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub monitor: Option<String>,
+    /// Unix timestamp in milliseconds
+    #[serde(rename = "savedAt", default)]
+    pub saved_at: i64,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub maximized: bool,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub fullscreen: bool,
+}
+
+impl WindowGeometry {
+    /// Bounds are of a maximized or fullscreen window, not the normal ones.
+    fn is_zoomed(&self) -> bool {
+        self.maximized || self.fullscreen
+    }
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct GeometryStore {
+    #[serde(default)]
+    entries: HashMap<String, WindowGeometry>,
+    /// Inner size of the most recently closed document window
+    #[serde(rename = "lastSize", default, skip_serializing_if = "Option::is_none")]
+    last_size: Option<(f64, f64)>,
+}
+
+/// Context key per window label, used to save geometry on close
+static WINDOW_CONTEXTS: LazyLock<Mutex<HashMap<String, String>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// Serializes read-modify-write of the geometry file
+static STORE_LOCK: Mutex<()> = Mutex::new(());
+
+/// Geometry key for a window context. Workspaces take precedence over files.
+pub fn context_key(file_path: Option<&str>, workspace_root: Option<&str>) -> Option<String> {
+    match (workspace_root, file_path) {
+        (Some(root), _) if !root.is_empty() => Some(format!("workspace:{root}")),
+        (_, Some(file)) if !file.is_empty() => Some(format!("file:{file}")),
+        _ => None,
+    }
+}
+
+/// Remember which context a window shows (or clear it with `None`).
+pub fn register_window(label: &str, key: Option<String>) {
+    if let Ok(mut contexts) = WINDOW_CONTEXTS.lock() {
+        match key {
+            Some(key) => {
+                contexts.insert(label.to_string(), key);
+            }
+            None => {
+                contexts.remove(label);
+            }
+        }
+    }
+}
+
+fn window_context(label: &str) -> Option<String> {
+    WINDOW_CONTEXTS
+        .lock()
+        .ok()
+        .and_then(|contexts| contexts.get(label).cloned())
+}
+
+fn store_path() -> Option<PathBuf> {
+    dirs::home_dir().map(|home| home.join(".vmark").join("window-geometry.json"))
+}
+
+fn read_store(path: &Path) -> GeometryStore {
+    fs::read_to_string(path)
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+fn write_store(path: &Path, store: &GeometryStore) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create ~/.vmark directory: {e}"))?;
+    }
+    let content = serde_json::to_string_pretty(store)
+        .map_err(|e| format!("Failed to serialize window geometry: {e}"))?;
+    fs::write(path, content).map_err(|e| format!("Failed to write window geometry: {e}"))
+}
+
+/// Insert an entry, dropping the oldest entries beyond `MAX_ENTRIES`.
+fn insert_entry(store: &mut GeometryStore, key: String, geometry: WindowGeometry) {
+    store.entries.insert(key, geometry);
+    if store.entries.len() > MAX_ENTRIES {
+        let mut by_age: Vec<(String, i64)> = store
+            .entries
+            .iter()
+            .map(|(k, g)| (k.clone(), g.saved_at))
+            .collect();
+        by_age.sort_by_key(|(_, saved_at)| *saved_at);
+        let excess = store.entries.len() - MAX_ENTRIES;
+        for (key, _) in by_age.into_iter().take(excess) {
+            store.entries.remove(&key);
+        }
+    }
+}
+
+/// Size of the most recently closed document window.
+pub fn last_size() -> Option<(f64, f64)> {
+    read_store(&store_path()?).last_size
+}
+
+/// Look up saved geometry for a context key.
+pub fn saved_geometry(key: &str) -> Option<WindowGeometry> {
+    let path = store_path()?;
+    read_store(&path).entries.remove(key)
+}
+
+/// Capture a window's current geometry. Returns None for minimized
+/// windows, whose bounds aren't worth restoring.
+fn capture(window: &Window) -> Option<WindowGeometry> {
+    if window.is_minimized().unwrap_or(false) {
+        return None;
+    }
+
+    let scale = window.scale_factor().ok()?;
+    let position = window.outer_position().ok()?.to_logical::<f64>(scale);
+    let size = window.outer_size().ok()?.to_logical::<f64>(scale);
+    let monitor = window
+        .current_monitor()
+        .ok()
+        .flatten()
+        .and_then(|m| m.name().cloned());
+
+    Some(WindowGeometry {
+        x: position.x,
+        y: position.y,
+        width: size.width,
+        height: size.height,
+        monitor,
+        saved_at: chrono::Utc::now().timestamp_millis(),
+        maximized: window.is_maximized().unwrap_or(false),
+        fullscreen: window.is_fullscreen().unwrap_or(false),
+    })
+}
+
+/// Inner size of a window in logical pixels, the size new windows are
+/// created with.
+fn inner_size(window: &Window) -> Option<(f64, f64)> {
+    let scale = window.scale_factor().ok()?;
+    let size = window.inner_size().ok()?.to_logical::<f64>(scale);
+    Some((size.width, size.height))
+}
+
+/// For a maximized or fullscreen window, keep the previously saved normal
+/// bounds and only take the state flags.
+fn merge_zoomed(previous: Option<&WindowGeometry>, current: WindowGeometry) -> WindowGeometry {
+    match previous {
+        Some(prev) if current.is_zoomed() => WindowGeometry {
+            maximized: current.maximized,
+            fullscreen: current.fullscreen,
+            saved_at: current.saved_at,
+            ..prev.clone()
+        },
+        _ => current,
+    }
+}
+
+/// Save the geometry of a document window under its context key, and its
+/// size as the last closed window size.
+pub fn save_window_geometry(window: &Window) {
+    let (Some(geometry), Some(path)) = (capture(window), store_path()) else {
+        return;
+    };
+
+    let Ok(_guard) = STORE_LOCK.lock() else {
+        return;
+    };
+    let mut store = read_store(&path);
+    if !geometry.is_zoomed() {
+        store.last_size = inner_size(window).or(store.last_size);
+    }
+    if let Some(key) = window_context(window.label()) {
+        let geometry = merge_zoomed(store.entries.get(&key), geometry);
+        insert_entry(&mut store, key, geometry);
+    }
+    if let Err(_e) = write_store(&path, &store) {
+        #[cfg(debug_assertions)]
+        eprintln!("[window_geometry] {}", _e);
+    }
+}
+
+/// Region of a monitor's work area to snap a window to.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SnapPosition {
+    LeftHalf,
+    RightHalf,
+    Top,
+    Bottom,
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    BottomRight,
+}
+
+impl SnapPosition {
+    /// Parse the suffix of a `snap-*` menu item id.
+    pub fn from_menu_id(id: &str) -> Option<Self> {
+        Some(match id {
+            "left-half" => Self::LeftHalf,
+            "right-half" => Self::RightHalf,
+            "top" => Self::Top,
+            "bottom" => Self::Bottom,
+            "top-left" => Self::TopLeft,
+            "top-right" => Self::TopRight,
+            "bottom-left" => Self::BottomLeft,
+            "bottom-right" => Self::BottomRight,
+            _ => return None,
+        })
+    }
+}
+
+/// A monitor's bounds in logical pixels.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MonitorBounds {
+    pub name: Option<String>,
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+}
+
+impl MonitorBounds {
+    pub fn from_monitor(monitor: &Monitor) -> Self {
+        let scale = monitor.scale_factor();
+        let position = monitor.position().to_logical::<f64>(scale);
+        let size = monitor.size().to_logical::<f64>(scale);
+        Self {
+            name: monitor.name().cloned(),
+            x: position.x,
+            y: position.y,
+            width: size.width,
+            height: size.height,
+        }
+    }
+
+    /// Bounds of a monitor's work area (without menu bar, dock or taskbar).
+    pub fn work_area_of(monitor: &Monitor) -> Self {
+        let scale = monitor.scale_factor();
+        let area = monitor.work_area();
+        let position = area.position.to_logical::<f64>(scale);
+        let size = area.size.to_logical::<f64>(scale);
+        Self {
+            name: monitor.name().cloned(),
+            x: position.x,
+            y: position.y,
+            width: size.width,
+            height: size.height,
+        }
+    }
+
+    /// Place a window of `size` at cascade `step` inside these bounds,
+    /// starting at `base` and moving by `offset` per step. The window is
+    /// shrunk (but not below `min_size`) and shifted so it stays visible.
+    /// Returns `(x, y, width, height)`.
+    pub fn place_cascaded(
+        &self,
+        step: u32,
+        size: (f64, f64),
+        min_size: (f64, f64),
+        base: (f64, f64),
+        offset: f64,
+    ) -> (f64, f64, f64, f64) {
+        let width = size.0.min(self.width).max(min_size.0);
+        let height = size.1.min(self.height).max(min_size.1);
+        let shift = step as f64 * offset;
+        let x = (self.x + base.0 + shift)
+            .min(self.x + self.width - width)
+            .max(self.x);
+        let y = (self.y + base.1 + shift)
+            .min(self.y + self.height - height)
+            .max(self.y);
+        (x, y, width, height)
+    }
+
+    /// The half of these bounds not containing `center_x`, as
+    /// `(x, y, width, height)`.
+    pub fn opposite_half(&self, center_x: f64) -> (f64, f64, f64, f64) {
+        let half = self.width / 2.0;
+        let x = if center_x < self.x + half {
+            self.x + half
+        } else {
+            self.x
+        };
+        (x, self.y, half, self.height)
+    }
+
+    /// Region of these bounds for a snap position, as `(x, y, width, height)`.
+    pub fn snap_rect(&self, position: SnapPosition) -> (f64, f64, f64, f64) {
+        let (half_w, half_h) = (self.width / 2.0, self.height / 2.0);
+        let (mid_x, mid_y) = (self.x + half_w, self.y + half_h);
+        match position {
+            SnapPosition::LeftHalf => (self.x, self.y, half_w, self.height),
+            SnapPosition::RightHalf => (mid_x, self.y, half_w, self.height),
+            SnapPosition::Top => (self.x, self.y, self.width, half_h),
+            SnapPosition::Bottom => (self.x, mid_y, self.width, half_h),
+            SnapPosition::TopLeft => (self.x, self.y, half_w, half_h),
+            SnapPosition::TopRight => (mid_x, self.y, half_w, half_h),
+            SnapPosition::BottomLeft => (self.x, mid_y, half_w, half_h),
+            SnapPosition::BottomRight => (mid_x, mid_y, half_w, half_h),
+        }
+    }
+
+    /// Whether enough of the window's title bar lies on this monitor.
+    fn shows_title_bar(&self, geometry: &WindowGeometry) -> bool {
+        let left = geometry.x.max(self.x);
+        let right = (geometry.x + geometry.width).min(self.x + self.width);
+        right - left >= MIN_VISIBLE.min(geometry.width)
+            && geometry.y >= self.y
+            && geometry.y < self.y + self.height - MIN_VISIBLE.min(self.height)
+    }
+}
+
+/// Check whether saved geometry is still usable with the connected monitors.
+/// The original monitor is preferred; any monitor that shows the title bar
+/// is accepted when that one is gone.
+pub fn is_restorable(geometry: &WindowGeometry, monitors: &[MonitorBounds]) -> bool {
+    if let Some(name) = &geometry.monitor {
+        if let Some(monitor) = monitors.iter().find(|m| m.name.as_ref() == Some(name)) {
+            return monitor.shows_title_bar(geometry);
+        }
+    }
+    monitors.iter().any(|m| m.shows_title_bar(geometry))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn geometry(x: f64, y: f64, monitor: Option<&str>) -> WindowGeometry {
+        WindowGeometry {
+            x,
+            y,
+            width: 900.0,
+            height: 700.0,
+            monitor: monitor.map(String::from),
+            saved_at: 0,
+            maximized: false,
+            fullscreen: false,
+        }
+    }
+
+    fn monitor(name: &str, x: f64) -> MonitorBounds {
+        MonitorBounds {
+            name: Some(name.to_string()),
+            x,
+            y: 0.0,
+            width: 1440.0,
+            height: 900.0,
+        }
+    }
+
+    #[test]
+    fn test_context_key_prefers_workspace() {
+        assert_eq!(
+            context_key(Some("/ws/a.md"), Some("/ws")),
+            Some("workspace:/ws".to_string())
+        );
+        assert_eq!(
+            context_key(Some("/a.md"), None),
+            Some("file:/a.md".to_string())
+        );
+        assert_eq!(context_key(None, None), None);
+    }
+
+    #[test]
+    fn test_is_restorable() {
+        let monitors = vec![monitor("Built-in", 0.0), monitor("External", 1440.0)];
+
+        assert!(is_restorable(
+            &geometry(1600.0, 50.0, Some("External")),
+            &monitors
+        ));
+        // Monitor disconnected, but position still lands on the built-in one
+        assert!(is_restorable(
+            &geometry(100.0, 50.0, Some("Gone")),
+            &monitors
+        ));
+        // Off every screen
+        assert!(!is_restorable(
+            &geometry(4000.0, 50.0, Some("Gone")),
+            &monitors
+        ));
+        assert!(!is_restorable(&geometry(100.0, 880.0, None), &monitors));
+    }
+
+    #[test]
+    fn test_place_cascaded_clamps_to_area() {
+        let area = MonitorBounds {
+            name: None,
+            x: 1440.0,
+            y: 25.0,
+            width: 1280.0,
+            height: 775.0,
+        };
+
+        let min = (400.0, 300.0);
+        assert_eq!(
+            area.place_cascaded(0, (800.0, 600.0), min, (100.0, 50.0), 25.0),
+            (1540.0, 75.0, 800.0, 600.0)
+        );
+        // Deep cascade is pulled back so the window stays inside
+        assert_eq!(
+            area.place_cascaded(9, (800.0, 600.0), min, (100.0, 50.0), 25.0),
+            (1765.0, 200.0, 800.0, 600.0)
+        );
+        // Window larger than a small screen is shrunk to fit
+        assert_eq!(
+            area.place_cascaded(0, (1600.0, 1000.0), min, (100.0, 50.0), 25.0),
+            (1440.0, 25.0, 1280.0, 775.0)
+        );
+        // ...but not below the minimum size
+        assert_eq!(
+            area.place_cascaded(0, (1600.0, 1000.0), (1400.0, 800.0), (100.0, 50.0), 25.0),
+            (1440.0, 25.0, 1400.0, 800.0)
+        );
+    }
+
+    #[test]
+    fn test_merge_zoomed_keeps_normal_bounds() {
+        let normal = geometry(100.0, 50.0, None);
+        let mut maximized = geometry(0.0, 0.0, None);
+        maximized.width = 1440.0;
+        maximized.maximized = true;
+
+        let merged = merge_zoomed(Some(&normal), maximized.clone());
+        assert!(merged.maximized);
+        assert_eq!((merged.x, merged.width), (100.0, 900.0));
+
+        // Nothing saved before: keep the zoomed bounds with the flag
+        assert_eq!(merge_zoomed(None, maximized.clone()), maximized);
+        // Normal window replaces previous bounds
+        assert_eq!(merge_zoomed(Some(&maximized), normal.clone()), normal);
+    }
+
+    #[test]
+    fn test_snap_rect() {
+        let area = monitor("External", 1440.0);
+
+        assert_eq!(
+            area.snap_rect(SnapPosition::RightHalf),
+            (2160.0, 0.0, 720.0, 900.0)
+        );
+        assert_eq!(
+            area.snap_rect(SnapPosition::Bottom),
+            (1440.0, 450.0, 1440.0, 450.0)
+        );
+        assert_eq!(
+            area.snap_rect(SnapPosition::TopLeft),
+            (1440.0, 0.0, 720.0, 450.0)
+        );
+        assert_eq!(
+            SnapPosition::from_menu_id("bottom-right"),
+            Some(SnapPosition::BottomRight)
+        );
+        assert_eq!(SnapPosition::from_menu_id("middle"), None);
+    }
+
+    #[test]
+    fn test_opposite_half() {
+        let area = monitor("External", 1440.0);
+
+        assert_eq!(area.opposite_half(1600.0), (2160.0, 0.0, 720.0, 900.0));
+        assert_eq!(area.opposite_half(2500.0), (1440.0, 0.0, 720.0, 900.0));
+    }
+
+    #[test]
+    fn test_store_round_trip_and_trim() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("window-geometry.json");
+
+        let mut store = GeometryStore::default();
+        for i in 0..(MAX_ENTRIES + 5) {
+            let mut g = geometry(0.0, 0.0, None);
+            g.saved_at = i as i64;
+            insert_entry(&mut store, format!("file:/{i}.md"), g);
+        }
+        write_store(&path, &store).unwrap();
+
+        let store = read_store(&path);
+        assert_eq!(store.entries.len(), MAX_ENTRIES);
+        assert!(!store.entries.contains_key("file:/0.md"));
+        assert!(store
+            .entries
+            .contains_key(&format!("file:/{}.md", MAX_ENTRIES + 4)));
+    }
+}
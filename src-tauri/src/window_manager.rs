@@ -1,9 +1,27 @@
+use std::collections::HashMap;
 use std::path::Path;
 use std::sync::atomic::{AtomicU32, Ordering};
-use tauri::{AppHandle, Manager, WebviewUrl, WebviewWindowBuilder};
+use std::sync::{LazyLock, Mutex};
+use tauri::{AppHandle, Emitter, Manager, WebviewUrl, WebviewWindow, WebviewWindowBuilder};
+
+use crate::window_geometry::{self, MonitorBounds, SnapPosition, WindowGeometry};
+use crate::window_settings::{self, OpenPolicy, WindowSettings};
 
 static WINDOW_COUNTER: AtomicU32 = AtomicU32::new(0);
 
+/// Initial content for windows created by `new_window_with_content`, keyed by
+/// the handoff id passed in the window URL. Taken once by the new window.
+static CONTENT_HANDOFFS: LazyLock<Mutex<HashMap<String, InitialContent>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// Cascade step per monitor name, so each monitor cascades independently
+static CASCADE_STEPS: LazyLock<Mutex<HashMap<String, u32>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// Last fullscreen state reported to the frontend, per window label
+static FULLSCREEN_STATES: LazyLock<Mutex<HashMap<String, bool>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
 /// Compute workspace root from a file path (parent directory).
 /// Returns None if the file is at root level or path is invalid.
 ///
@@ -25,9 +43,6 @@ const BASE_X: f64 = 100.0;
 const BASE_Y: f64 = 100.0;
 /// Max cascade steps before wrapping
 const MAX_CASCADE: u32 = 10;
-/// Minimum window size (also used as default)
-const MIN_WIDTH: f64 = 800.0;
-const MIN_HEIGHT: f64 = 600.0;
 
 /// Get cascaded position based on window counter
 fn get_cascaded_position(count: u32) -> (f64, f64) {
@@ -39,6 +54,218 @@ fn get_cascaded_position(count: u32) -> (f64, f64) {
     )
 }
 
+/// Saved geometry for a window context, if it still fits the connected monitors.
+fn restorable_geometry(app: &AppHandle, context: Option<&str>) -> Option<WindowGeometry> {
+    let geometry = window_geometry::saved_geometry(context?)?;
+    let monitors: Vec<MonitorBounds> = app
+        .available_monitors()
+        .unwrap_or_default()
+        .iter()
+        .map(MonitorBounds::from_monitor)
+        .collect();
+    window_geometry::is_restorable(&geometry, &monitors).then_some(geometry)
+}
+
+/// Work area of the monitor new windows should open on: the one holding
+/// the focused window, else the primary monitor.
+pub(crate) fn target_work_area(app: &AppHandle) -> Option<MonitorBounds> {
+    let focused_monitor = app
+        .webview_windows()
+        .values()
+        .find(|w| w.is_focused().unwrap_or(false))
+        .and_then(|w| w.current_monitor().ok().flatten());
+    let monitor = match focused_monitor {
+        Some(monitor) => monitor,
+        None => app.primary_monitor().ok().flatten()?,
+    };
+    Some(MonitorBounds::work_area_of(&monitor))
+}
+
+/// Rectangle in logical pixels.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct DisplayRect {
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+}
+
+impl From<&MonitorBounds> for DisplayRect {
+    fn from(b: &MonitorBounds) -> Self {
+        Self {
+            x: b.x,
+            y: b.y,
+            width: b.width,
+            height: b.height,
+        }
+    }
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct DisplayInfo {
+    pub name: Option<String>,
+    pub bounds: DisplayRect,
+    #[serde(rename = "workArea")]
+    pub work_area: DisplayRect,
+    #[serde(rename = "scaleFactor")]
+    pub scale_factor: f64,
+    pub primary: bool,
+    /// Holds the focused window
+    pub focused: bool,
+}
+
+/// Connected monitors with bounds, work area and scale factor, flagging the
+/// primary one and the one holding the focused window.
+#[tauri::command]
+pub fn get_display_info(app: AppHandle) -> Result<Vec<DisplayInfo>, String> {
+    let monitors = app
+        .available_monitors()
+        .map_err(|e| format!("Failed to list monitors: {e}"))?;
+    let primary = app
+        .primary_monitor()
+        .ok()
+        .flatten()
+        .map(|m| MonitorBounds::from_monitor(&m));
+    let focused = app
+        .webview_windows()
+        .values()
+        .find(|w| w.is_focused().unwrap_or(false))
+        .and_then(|w| w.current_monitor().ok().flatten())
+        .map(|m| MonitorBounds::from_monitor(&m));
+
+    Ok(monitors
+        .iter()
+        .map(|monitor| {
+            let bounds = MonitorBounds::from_monitor(monitor);
+            let work_area = MonitorBounds::work_area_of(monitor);
+            DisplayInfo {
+                name: bounds.name.clone(),
+                primary: primary.as_ref() == Some(&bounds),
+                focused: focused.as_ref() == Some(&bounds),
+                work_area: DisplayRect::from(&work_area),
+                bounds: DisplayRect::from(&bounds),
+                scale_factor: monitor.scale_factor(),
+            }
+        })
+        .collect())
+}
+
+/// Cascade position on the target monitor, clamped to its work area.
+/// Falls back to the global cascade when monitor info is unavailable.
+fn cascaded_bounds(
+    app: &AppHandle,
+    count: u32,
+    size: (f64, f64),
+    min_size: (f64, f64),
+) -> (f64, f64, f64, f64) {
+    let (width, height) = size;
+    let Some(area) = target_work_area(app) else {
+        let (x, y) = get_cascaded_position(count);
+        return (x, y, width, height);
+    };
+
+    let step = CASCADE_STEPS
+        .lock()
+        .map(|mut steps| {
+            let step = steps
+                .entry(area.name.clone().unwrap_or_default())
+                .or_insert(0);
+            let current = *step;
+            *step = (current + 1) % MAX_CASCADE;
+            current
+        })
+        .unwrap_or(count % MAX_CASCADE);
+
+    area.place_cascaded(step, size, min_size, (BASE_X, BASE_Y), CASCADE_OFFSET)
+}
+
+/// Initial (x, y, width, height) for a new window: saved geometry for its
+/// context, or the cascade at the configured size.
+fn initial_bounds(
+    app: &AppHandle,
+    count: u32,
+    saved: Option<&WindowGeometry>,
+    settings: &WindowSettings,
+) -> (f64, f64, f64, f64) {
+    let (min_width, min_height) = settings.min_size();
+    match saved {
+        Some(g) => (g.x, g.y, g.width.max(min_width), g.height.max(min_height)),
+        None => {
+            let size = settings.initial_size(window_geometry::last_size());
+            cascaded_bounds(app, count, size, (min_width, min_height))
+        }
+    }
+}
+
+/// Apply the configured default zoom to a new window.
+fn apply_default_zoom(window: &WebviewWindow, settings: &WindowSettings) {
+    if let Some(zoom) = settings.zoom() {
+        if window.set_zoom(zoom).is_ok() {
+            crate::session::set_zoom(window.label(), zoom);
+        }
+    }
+}
+
+/// Width and height of a window's frame (outer minus inner size).
+fn frame_size(window: &WebviewWindow) -> Option<(f64, f64)> {
+    let scale = window.scale_factor().ok()?;
+    let outer = window.outer_size().ok()?.to_logical::<f64>(scale);
+    let inner = window.inner_size().ok()?.to_logical::<f64>(scale);
+    Some((
+        (outer.width - inner.width).max(0.0),
+        (outer.height - inner.height).max(0.0),
+    ))
+}
+
+/// Move and resize a window to outer bounds (frame included), as saved by
+/// `window_geometry` and computed for snapping. Also applied after build so
+/// restored geometry wins over the window-state plugin, which restores by
+/// label.
+fn reapply_geometry(window: &WebviewWindow, bounds: (f64, f64, f64, f64)) {
+    let (x, y, width, height) = bounds;
+    // set_size takes the inner size
+    let (frame_width, frame_height) = frame_size(window).unwrap_or((0.0, 0.0));
+    let _ = window.set_size(tauri::Size::Logical(tauri::LogicalSize {
+        width: (width - frame_width).max(1.0),
+        height: (height - frame_height).max(1.0),
+    }));
+    let _ = window.set_position(tauri::Position::Logical(tauri::LogicalPosition { x, y }));
+}
+
+/// Maximize or enter fullscreen again if the window was when last closed.
+fn reapply_window_state(window: &WebviewWindow, saved: &WindowGeometry) {
+    if saved.fullscreen {
+        let _ = window.set_fullscreen(true);
+    } else if saved.maximized {
+        let _ = window.maximize();
+    }
+}
+
+/// Set up a freshly built document window: restore its saved geometry and
+/// state, apply the default zoom and install the per-window platform hooks.
+fn finish_document_window(
+    app: &AppHandle,
+    window: &WebviewWindow,
+    label: &str,
+    saved: Option<WindowGeometry>,
+    bounds: (f64, f64, f64, f64),
+    settings: &WindowSettings,
+    context: Option<String>,
+) {
+    if let Some(saved) = &saved {
+        reapply_geometry(window, bounds);
+        reapply_window_state(window, saved);
+    }
+    apply_default_zoom(window, settings);
+    #[cfg(target_os = "macos")]
+    crate::macos_touch_bar::install(app, window);
+    #[cfg(target_os = "windows")]
+    crate::windows_system_events::install(app, window);
+    #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+    let _ = app;
+    window_geometry::register_window(label, context);
+}
+
 /// Build window URL with optional query params
 fn build_window_url(file_path: Option<&str>, workspace_root: Option<&str>) -> String {
     let mut params = Vec::new();
@@ -58,8 +285,13 @@ fn build_window_url(file_path: Option<&str>, workspace_root: Option<&str>) -> St
     }
 }
 
-/// Build window URL with workspace root and multiple file paths.
-fn build_window_url_with_files(file_paths: &[String], workspace_root: Option<&str>) -> String {
+/// Build window URL with workspace root, multiple file paths, and the file
+/// whose tab should be active (the last one when omitted).
+fn build_window_url_with_files(
+    file_paths: &[String],
+    workspace_root: Option<&str>,
+    active_file: Option<&str>,
+) -> String {
     let mut params = Vec::new();
 
     if let Some(root) = workspace_root {
@@ -71,6 +303,10 @@ fn build_window_url_with_files(file_paths: &[String], workspace_root: Option<&st
         params.push(format!("files={}", urlencoding::encode(&serialized)));
     }
 
+    if let Some(active) = active_file {
+        params.push(format!("activeFile={}", urlencoding::encode(active)));
+    }
+
     if params.is_empty() {
         "/".to_string()
     } else {
@@ -79,21 +315,27 @@ fn build_window_url_with_files(file_paths: &[String], workspace_root: Option<&st
 }
 
 /// Create a new document window from a pre-built URL.
+/// `context` is the window geometry key (see `window_geometry::context_key`).
 fn create_document_window_with_url(
     app: &AppHandle,
     url: String,
+    context: Option<String>,
 ) -> Result<String, tauri::Error> {
     let count = WINDOW_COUNTER.fetch_add(1, Ordering::SeqCst);
     let label = format!("doc-{}", count);
 
     let title = String::new();
-    let (x, y) = get_cascaded_position(count);
+    let saved = restorable_geometry(app, context.as_deref());
+    let settings = window_settings::current();
+    let bounds = initial_bounds(app, count, saved.as_ref(), &settings);
+    let (x, y, width, height) = bounds;
+    let (min_width, min_height) = settings.min_size();
 
     // Start hidden to avoid flash - window will be shown when frontend emits "ready"
     let mut builder = WebviewWindowBuilder::new(app, &label, WebviewUrl::App(url.into()))
         .title(&title)
-        .inner_size(MIN_WIDTH, MIN_HEIGHT)
-        .min_inner_size(800.0, 600.0)
+        .inner_size(width, height)
+        .min_inner_size(min_width, min_height)
         .position(x, y)
         .resizable(true)
         .fullscreen(false)
@@ -108,7 +350,13 @@ fn create_document_window_with_url(
             .accept_first_mouse(true);
     }
 
-    builder.build()?;
+    // Translucent background from the appearance settings
+    if let Some(effects) = settings.effects() {
+        builder = builder.transparent(true).effects(effects);
+    }
+
+    let window = builder.build()?;
+    finish_document_window(app, &window, &label, saved, bounds, &settings, context);
 
     Ok(label)
 }
@@ -134,15 +382,20 @@ pub fn create_document_window(
     // Empty initial title - React will update based on settings
     let title = String::new();
 
-    // Get cascaded position (always use minimum size for new windows)
-    let (x, y) = get_cascaded_position(count);
+    // Restore geometry saved for this workspace/file, else cascade on the focused monitor
+    let context = window_geometry::context_key(file_path, workspace_root);
+    let saved = restorable_geometry(app, context.as_deref());
+    let settings = window_settings::current();
+    let bounds = initial_bounds(app, count, saved.as_ref(), &settings);
+    let (x, y, width, height) = bounds;
+    let (min_width, min_height) = settings.min_size();
 
     // CRITICAL: Full window configuration for proper behavior
     // Start hidden to avoid flash - window will be shown when frontend emits "ready"
     let mut builder = WebviewWindowBuilder::new(app, &label, WebviewUrl::App(url.into()))
         .title(&title)
-        .inner_size(MIN_WIDTH, MIN_HEIGHT)
-        .min_inner_size(800.0, 600.0)
+        .inner_size(width, height)
+        .min_inner_size(min_width, min_height)
         .position(x, y)
         .resizable(true)
         .fullscreen(false)
@@ -158,11 +411,71 @@ pub fn create_document_window(
             .accept_first_mouse(true);
     }
 
-    builder.build()?;
+    // Translucent background from the appearance settings
+    if let Some(effects) = settings.effects() {
+        builder = builder.transparent(true).effects(effects);
+    }
+
+    let window = builder.build()?;
+    finish_document_window(app, &window, &label, saved, bounds, &settings, context);
 
     Ok(label)
 }
 
+/// Unsaved markdown for a new window, with an optional suggested filename.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct InitialContent {
+    pub content: String,
+    #[serde(rename = "suggestedName")]
+    pub suggested_name: Option<String>,
+}
+
+/// Create a document window that starts with an untitled tab holding
+/// `content`. The window finds its content via the `handoff` URL param.
+pub fn create_document_window_with_content(
+    app: &AppHandle,
+    content: InitialContent,
+) -> Result<String, tauri::Error> {
+    let handoff = uuid::Uuid::new_v4().to_string();
+    if let Ok(mut handoffs) = CONTENT_HANDOFFS.lock() {
+        handoffs.insert(handoff.clone(), content);
+    }
+
+    let url = format!("/?handoff={}", urlencoding::encode(&handoff));
+    let result = create_document_window_with_url(app, url, None);
+    if result.is_err() {
+        if let Ok(mut handoffs) = CONTENT_HANDOFFS.lock() {
+            handoffs.remove(&handoff);
+        }
+    }
+    result
+}
+
+/// Open a new window with in-memory content instead of a file
+/// ("open selection in new window", clipper output, MCP drafts).
+#[tauri::command]
+pub fn new_window_with_content(
+    app: AppHandle,
+    content: String,
+    suggested_name: Option<String>,
+) -> Result<String, String> {
+    create_document_window_with_content(
+        &app,
+        InitialContent {
+            content,
+            suggested_name,
+        },
+    )
+    .map_err(|e| e.to_string())
+}
+
+/// Take the initial content for a handoff id. Returns None if it was
+/// already taken.
+#[tauri::command]
+pub fn take_initial_content(handoff: String) -> Option<InitialContent> {
+    CONTENT_HANDOFFS.lock().ok()?.remove(&handoff)
+}
+
 /// Create a new empty window (Tauri command)
 #[tauri::command]
 pub fn new_window(app: AppHandle) -> Result<String, String> {
@@ -193,6 +506,142 @@ pub fn open_workspace_in_new_window(
     .map_err(|e| e.to_string())
 }
 
+/// Create a new document window with several files and an optional workspace root.
+/// Returns the window label on success.
+pub fn create_document_window_with_files(
+    app: &AppHandle,
+    file_paths: &[String],
+    workspace_root: Option<&str>,
+) -> Result<String, tauri::Error> {
+    create_document_window_with_active_file(app, file_paths, workspace_root, None)
+}
+
+/// Create a new document window with several files, activating the tab of
+/// `active_file` once they are loaded. Returns the window label on success.
+pub fn create_document_window_with_active_file(
+    app: &AppHandle,
+    file_paths: &[String],
+    workspace_root: Option<&str>,
+    active_file: Option<&str>,
+) -> Result<String, tauri::Error> {
+    let url = build_window_url_with_files(file_paths, workspace_root, active_file);
+    let context = window_geometry::context_key(
+        active_file.or(file_paths.first().map(String::as_str)),
+        workspace_root,
+    );
+    create_document_window_with_url(app, url, context)
+}
+
+/// The focused document window, else any document window.
+fn target_document_window(app: &AppHandle) -> Option<WebviewWindow> {
+    let mut windows: Vec<WebviewWindow> = app
+        .webview_windows()
+        .into_values()
+        .filter(|w| crate::quit::is_document_window_label(w.label()))
+        .collect();
+    windows.sort_by_key(|w| !w.is_focused().unwrap_or(false));
+    windows.into_iter().next()
+}
+
+/// Open several files and folders at once (multi-file drop, CLI arguments).
+///
+/// Folders always open as workspaces in their own window. Files follow
+/// `mode`, or the `openPolicy` window setting when omitted:
+/// - `tabs`: sent to the focused document window one `open-file` event per
+///   file, as the file explorer does (a new window is created when none is
+///   open)
+/// - `windows`: one new window per file
+/// - `prompt`: a dialog asks which of the two, then `open_paths` runs again
+///   with the chosen mode
+///
+/// Returns the labels of the windows the paths went to.
+#[tauri::command]
+pub fn open_paths(
+    app: AppHandle,
+    paths: Vec<String>,
+    mode: Option<OpenPolicy>,
+) -> Result<Vec<String>, String> {
+    let (folders, files): (Vec<String>, Vec<String>) =
+        paths.into_iter().partition(|p| Path::new(p).is_dir());
+    let mut labels = Vec::new();
+
+    for folder in &folders {
+        let label = create_document_window(&app, None, Some(folder)).map_err(|e| e.to_string())?;
+        labels.push(label);
+    }
+    if files.is_empty() {
+        return Ok(labels);
+    }
+
+    let target = target_document_window(&app);
+    let mode = mode.unwrap_or_else(|| window_settings::current().open_policy);
+    match (mode, target) {
+        (OpenPolicy::Prompt, Some(window)) if files.len() > 1 => {
+            prompt_open_mode(&app, files);
+            labels.push(window.label().to_string());
+        }
+        (OpenPolicy::Tabs | OpenPolicy::Prompt, Some(window)) => {
+            for path in &files {
+                app.emit_to(
+                    window.label(),
+                    "open-file",
+                    serde_json::json!({ "path": path }),
+                )
+                .map_err(|e| e.to_string())?;
+            }
+            labels.push(window.label().to_string());
+        }
+        (OpenPolicy::Tabs | OpenPolicy::Prompt, None) => {
+            let workspace_root = get_workspace_root_for_file(&files[0]);
+            labels.push(
+                create_document_window_with_files(&app, &files, workspace_root.as_deref())
+                    .map_err(|e| e.to_string())?,
+            );
+        }
+        (OpenPolicy::Windows, _) => {
+            for path in &files {
+                let workspace_root = get_workspace_root_for_file(path);
+                labels.push(
+                    create_document_window(&app, Some(path), workspace_root.as_deref())
+                        .map_err(|e| e.to_string())?,
+                );
+            }
+        }
+    }
+
+    Ok(labels)
+}
+
+/// Ask whether several files open as tabs or in their own windows.
+fn prompt_open_mode(app: &AppHandle, files: Vec<String>) {
+    use tauri_plugin_dialog::{DialogExt, MessageDialogButtons, MessageDialogKind};
+
+    let tr = crate::menu_i18n::tr;
+    let app_handle = app.clone();
+    app.dialog()
+        .message(
+            tr("Open {count} files as tabs in this window, or each in its own window?")
+                .replace("{count}", &files.len().to_string()),
+        )
+        .title(tr("Open Files"))
+        .kind(MessageDialogKind::Info)
+        .buttons(MessageDialogButtons::OkCancelCustom(
+            tr("Open as Tabs"),
+            tr("Open in Windows"),
+        ))
+        .show(move |as_tabs| {
+            let mode = if as_tabs {
+                OpenPolicy::Tabs
+            } else {
+                OpenPolicy::Windows
+            };
+            if let Err(_e) = open_paths(app_handle, files, Some(mode)) {
+                #[cfg(debug_assertions)]
+                eprintln!("[window_manager] {}", _e);
+            }
+        });
+}
+
 /// Open a workspace in a new window with multiple files.
 #[tauri::command]
 pub fn open_workspace_with_files_in_new_window(
@@ -200,8 +649,292 @@ pub fn open_workspace_with_files_in_new_window(
     workspace_root: String,
     file_paths: Vec<String>,
 ) -> Result<String, String> {
-    let url = build_window_url_with_files(&file_paths, Some(&workspace_root));
-    create_document_window_with_url(&app, url).map_err(|e| e.to_string())
+    create_document_window_with_files(&app, &file_paths, Some(&workspace_root))
+        .map_err(|e| e.to_string())
+}
+
+/// Open a file in a new window filling the half of the source window's
+/// monitor that the source window is not on.
+#[tauri::command]
+pub fn open_side_by_side(
+    app: AppHandle,
+    source_label: String,
+    path: String,
+) -> Result<String, String> {
+    let source = app
+        .get_webview_window(&source_label)
+        .ok_or_else(|| format!("Window '{}' not found", source_label))?;
+    let monitor = source
+        .current_monitor()
+        .ok()
+        .flatten()
+        .ok_or("Failed to determine monitor of source window")?;
+    let scale = monitor.scale_factor();
+    let position = source
+        .outer_position()
+        .map_err(|e| format!("Failed to get window position: {e}"))?
+        .to_logical::<f64>(scale);
+    let size = source
+        .outer_size()
+        .map_err(|e| format!("Failed to get window size: {e}"))?
+        .to_logical::<f64>(scale);
+
+    let area = MonitorBounds::work_area_of(&monitor);
+    let bounds = area.opposite_half(position.x + size.width / 2.0);
+
+    let label = create_document_window(&app, Some(&path), None).map_err(|e| e.to_string())?;
+    if let Some(window) = app.get_webview_window(&label) {
+        reapply_geometry(&window, bounds);
+    }
+    Ok(label)
+}
+
+/// Move and resize a window to a region of its monitor's work area.
+pub fn apply_snap(window: &WebviewWindow, position: SnapPosition) -> Result<(), String> {
+    let monitor = window
+        .current_monitor()
+        .ok()
+        .flatten()
+        .ok_or("Failed to determine monitor of window")?;
+    if window.is_fullscreen().unwrap_or(false) {
+        let _ = window.set_fullscreen(false);
+    }
+    if window.is_maximized().unwrap_or(false) {
+        let _ = window.unmaximize();
+    }
+    reapply_geometry(
+        window,
+        MonitorBounds::work_area_of(&monitor).snap_rect(position),
+    );
+    Ok(())
+}
+
+/// Snap a window to a half or quarter of its monitor.
+#[tauri::command]
+pub fn snap_window(app: AppHandle, label: String, position: SnapPosition) -> Result<(), String> {
+    let window = app
+        .get_webview_window(&label)
+        .ok_or_else(|| format!("Window '{}' not found", label))?;
+    apply_snap(&window, position)
+}
+
+/// Set a window's always-on-top state, remember it for session restore and
+/// check the Float on Top menu item while the window is focused.
+pub fn apply_always_on_top(window: &WebviewWindow, on: bool) {
+    if let Err(_e) = window.set_always_on_top(on) {
+        #[cfg(debug_assertions)]
+        eprintln!("[Tauri] set_always_on_top failed: {}", _e);
+        return;
+    }
+    let label = window.label();
+    crate::session::set_always_on_top(label, on);
+    crate::menu_state::set_view_state(window.app_handle(), label, "always-on-top", on);
+    let _ = window.emit_to(label, "window:always-on-top-changed", (label, on));
+}
+
+/// Keep a window floating above other apps (or stop doing so).
+#[tauri::command]
+pub fn set_always_on_top(app: AppHandle, label: String, on: bool) -> Result<(), String> {
+    let window = app
+        .get_webview_window(&label)
+        .ok_or_else(|| format!("Window '{}' not found", label))?;
+    apply_always_on_top(&window, on);
+    Ok(())
+}
+
+/// Set a window's webview zoom and remember it for session restore.
+/// Returns the factor actually applied.
+pub fn apply_zoom(window: &WebviewWindow, factor: f64) -> Result<f64, String> {
+    let zoom = window_settings::clamp_zoom(factor);
+    window
+        .set_zoom(zoom)
+        .map_err(|e| format!("Failed to set zoom: {e}"))?;
+    let label = window.label();
+    crate::session::set_zoom(label, zoom);
+    let _ = window.emit_to(label, "window:zoom-changed", (label, zoom));
+    Ok(zoom)
+}
+
+/// Scale a window's content. Returns the factor actually applied.
+#[tauri::command]
+pub fn set_zoom(app: AppHandle, label: String, factor: f64) -> Result<f64, String> {
+    let window = app
+        .get_webview_window(&label)
+        .ok_or_else(|| format!("Window '{}' not found", label))?;
+    apply_zoom(&window, factor)
+}
+
+/// Current zoom factor of a window.
+#[tauri::command]
+pub fn get_zoom(app: AppHandle, label: String) -> Result<f64, String> {
+    if app.get_webview_window(&label).is_none() {
+        return Err(format!("Window '{}' not found", label));
+    }
+    Ok(crate::session::zoom_of(&label).unwrap_or(1.0))
+}
+
+/// Enter or leave native fullscreen and tell the frontend, which hides its
+/// chrome while fullscreen. Returns the new state.
+///
+/// With `hide_title_bar`, macOS windows switch from the overlay title bar to
+/// the standard one while fullscreen, so the traffic lights slide in above the
+/// editor on hover instead of covering it.
+pub fn apply_fullscreen(window: &WebviewWindow, hide_title_bar: bool) -> Result<bool, String> {
+    let fullscreen = !window
+        .is_fullscreen()
+        .map_err(|e| format!("Failed to get fullscreen state: {e}"))?;
+
+    #[cfg(target_os = "macos")]
+    if hide_title_bar {
+        let style = if fullscreen {
+            tauri::TitleBarStyle::Visible
+        } else {
+            tauri::TitleBarStyle::Overlay
+        };
+        let _ = window.set_title_bar_style(style);
+    }
+    #[cfg(not(target_os = "macos"))]
+    let _ = hide_title_bar;
+
+    window
+        .set_fullscreen(fullscreen)
+        .map_err(|e| format!("Failed to set fullscreen: {e}"))?;
+    let label = window.label();
+    record_fullscreen(label, fullscreen);
+    let _ = window.emit_to(label, "window:fullscreen-changed", (label, fullscreen));
+    Ok(fullscreen)
+}
+
+/// Remember the fullscreen state last reported for a window. Returns true if
+/// it changed.
+fn record_fullscreen(label: &str, fullscreen: bool) -> bool {
+    let Ok(mut states) = FULLSCREEN_STATES.lock() else {
+        return false;
+    };
+    let previous = states
+        .insert(label.to_string(), fullscreen)
+        .unwrap_or(false);
+    previous != fullscreen
+}
+
+/// A window was resized: if that was it entering or leaving fullscreen by
+/// other means than `apply_fullscreen` (the green title bar button, the
+/// system shortcut), tell the frontend too.
+pub fn handle_window_resized(app: &AppHandle, label: &str) {
+    let Some(window) = app.get_webview_window(label) else {
+        return;
+    };
+    let Ok(fullscreen) = window.is_fullscreen() else {
+        return;
+    };
+    if !record_fullscreen(label, fullscreen) {
+        return;
+    }
+
+    // Undo the standard title bar `apply_fullscreen` may have switched to
+    #[cfg(target_os = "macos")]
+    if !fullscreen {
+        let _ = window.set_title_bar_style(tauri::TitleBarStyle::Overlay);
+    }
+    let _ = window.emit_to(label, "window:fullscreen-changed", (label, fullscreen));
+}
+
+/// Forget a destroyed window's fullscreen state.
+pub fn remove_window(label: &str) {
+    if let Ok(mut states) = FULLSCREEN_STATES.lock() {
+        states.remove(label);
+    }
+}
+
+/// Toggle native fullscreen for a window. Returns the new state.
+#[tauri::command]
+pub fn toggle_fullscreen(
+    app: AppHandle,
+    label: String,
+    hide_title_bar: Option<bool>,
+) -> Result<bool, String> {
+    let window = app
+        .get_webview_window(&label)
+        .ok_or_else(|| format!("Window '{}' not found", label))?;
+    apply_fullscreen(&window, hide_title_bar.unwrap_or(false))
+}
+
+/// Run `f` with a window's `NSWindow` on the main thread.
+#[cfg(target_os = "macos")]
+fn with_ns_window<F>(window: &WebviewWindow, f: F) -> Result<(), String>
+where
+    F: FnOnce(&objc2_app_kit::NSWindow) + Send + 'static,
+{
+    let target = window.clone();
+    window
+        .run_on_main_thread(move || {
+            let Ok(ptr) = target.ns_window() else {
+                return;
+            };
+            // SAFETY: tao hands out the window's live NSWindow, and we are on
+            // the main thread.
+            let ns_window = unsafe { &*(ptr as *const objc2_app_kit::NSWindow) };
+            f(ns_window);
+        })
+        .map_err(|e| format!("Failed to run on main thread: {e}"))
+}
+
+/// Set the file a window represents, so the macOS title bar shows its proxy
+/// icon (drag it out, Cmd-click for the path popup). `None` clears it.
+/// No-op on other platforms.
+#[tauri::command]
+pub fn set_represented_file(
+    app: AppHandle,
+    label: String,
+    path: Option<String>,
+) -> Result<(), String> {
+    let window = app
+        .get_webview_window(&label)
+        .ok_or_else(|| format!("Window '{}' not found", label))?;
+
+    #[cfg(target_os = "macos")]
+    {
+        with_ns_window(&window, move |ns_window| {
+            let filename = objc2_foundation::NSString::from_str(path.as_deref().unwrap_or(""));
+            ns_window.setRepresentedFilename(&filename);
+        })
+    }
+    #[cfg(not(target_os = "macos"))]
+    {
+        let _ = (window, path);
+        Ok(())
+    }
+}
+
+/// Show or clear the unsaved-changes dot in the macOS close button.
+/// No-op on other platforms.
+#[tauri::command]
+pub fn set_document_edited(app: AppHandle, label: String, dirty: bool) -> Result<(), String> {
+    let window = app
+        .get_webview_window(&label)
+        .ok_or_else(|| format!("Window '{}' not found", label))?;
+
+    #[cfg(target_os = "macos")]
+    {
+        with_ns_window(&window, move |ns_window| ns_window.setDocumentEdited(dirty))
+    }
+    #[cfg(not(target_os = "macos"))]
+    {
+        let _ = (window, dirty);
+        Ok(())
+    }
+}
+
+/// Update the file/workspace a window shows, so its geometry is saved under
+/// the right key when it closes.
+#[tauri::command]
+pub fn set_window_context(
+    window: WebviewWindow,
+    file_path: Option<String>,
+    workspace_root: Option<String>,
+) {
+    let context = window_geometry::context_key(file_path.as_deref(), workspace_root.as_deref());
+    window_geometry::register_window(window.label(), context);
 }
 
 /// Close a specific window by label
@@ -211,6 +944,14 @@ pub fn close_window(app: AppHandle, label: String) -> Result<(), String> {
     eprintln!("[Tauri] close_window called for '{}'", label);
 
     if let Some(window) = app.get_webview_window(&label) {
+        window_geometry::save_window_geometry(&window.as_ref().window());
+        if crate::quit::is_quit_in_progress() {
+            // Let the window's exit hooks finish before it goes away
+            crate::exit_hooks::run(&app, &label, move || {
+                let _ = window.destroy();
+            });
+            return Ok(());
+        }
         #[cfg(debug_assertions)]
         eprintln!("[Tauri] destroying window '{}'", label);
         let result = window.destroy().map_err(|e| e.to_string());
@@ -286,9 +1027,137 @@ pub fn show_settings_window(app: &AppHandle) -> Result<String, tauri::Error> {
     Ok(SETTINGS_LABEL.to_string())
 }
 
+/// Paper sizes offered by print preview.
+#[derive(Debug, Clone, Copy, Default, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum PaperSize {
+    #[default]
+    A4,
+    A5,
+    Letter,
+    Legal,
+}
+
+impl PaperSize {
+    fn as_str(self) -> &'static str {
+        match self {
+            PaperSize::A4 => "a4",
+            PaperSize::A5 => "a5",
+            PaperSize::Letter => "letter",
+            PaperSize::Legal => "legal",
+        }
+    }
+
+    /// Page height divided by width (portrait).
+    fn aspect_ratio(self) -> f64 {
+        match self {
+            PaperSize::A4 | PaperSize::A5 => 297.0 / 210.0,
+            PaperSize::Letter => 11.0 / 8.5,
+            PaperSize::Legal => 14.0 / 8.5,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+pub struct PrintPreviewOptions {
+    #[serde(rename = "paperSize", default)]
+    pub paper_size: PaperSize,
+    #[serde(default)]
+    pub landscape: bool,
+}
+
+/// Label of the print preview window (not a document window, so quit
+/// coordination closes it directly).
+pub const PRINT_PREVIEW_LABEL: &str = "print-preview";
+
+/// Open (or refresh) the print preview for a document window.
+///
+/// The preview is a child of its parent window, sized to one page of the
+/// chosen paper plus the preview toolbar. An existing preview is re-targeted
+/// via `print-preview:update` instead of opening a second one.
+#[tauri::command]
+pub fn open_print_preview(
+    app: AppHandle,
+    parent_label: String,
+    options: PrintPreviewOptions,
+) -> Result<String, String> {
+    /// Page width in the preview (logical pixels)
+    const PAGE_WIDTH: f64 = 600.0;
+    /// Toolbar and margins around the page
+    const CHROME_HEIGHT: f64 = 80.0;
+    const CHROME_WIDTH: f64 = 40.0;
+
+    let parent = app
+        .get_webview_window(&parent_label)
+        .ok_or_else(|| format!("Window '{}' not found", parent_label))?;
+
+    if let Some(window) = app.get_webview_window(PRINT_PREVIEW_LABEL) {
+        let _ = window.emit_to(
+            PRINT_PREVIEW_LABEL,
+            "print-preview:update",
+            (&parent_label, &options),
+        );
+        let _ = window.show();
+        let _ = window.set_focus();
+        return Ok(PRINT_PREVIEW_LABEL.to_string());
+    }
+
+    let ratio = options.paper_size.aspect_ratio();
+    let (page_width, page_height) = if options.landscape {
+        (PAGE_WIDTH * ratio, PAGE_WIDTH)
+    } else {
+        (PAGE_WIDTH, PAGE_WIDTH * ratio)
+    };
+    let mut width = page_width + CHROME_WIDTH;
+    let mut height = page_height + CHROME_HEIGHT;
+
+    // Keep the preview within the parent's monitor
+    if let Some(monitor) = parent.current_monitor().ok().flatten() {
+        let area = MonitorBounds::work_area_of(&monitor);
+        width = width.min(area.width * 0.9);
+        height = height.min(area.height * 0.9);
+    }
+
+    let url = format!(
+        "/print-preview?parent={}&paper={}&landscape={}",
+        urlencoding::encode(&parent_label),
+        options.paper_size.as_str(),
+        options.landscape
+    );
+
+    let mut builder = WebviewWindowBuilder::new(
+        &app,
+        PRINT_PREVIEW_LABEL,
+        WebviewUrl::App(url.into()),
+    )
+    .title("Print Preview")
+    .inner_size(width, height)
+    .min_inner_size(400.0, 400.0)
+    .resizable(true)
+    .skip_taskbar(true)
+    .visible(false)
+    .focused(true)
+    .parent(&parent)
+    .map_err(|e| e.to_string())?;
+
+    #[cfg(target_os = "macos")]
+    {
+        builder = builder
+            .title_bar_style(tauri::TitleBarStyle::Overlay)
+            .hidden_title(true);
+    }
+
+    let window = builder.build().map_err(|e| e.to_string())?;
+    let _ = window.center();
+    let _ = window.show();
+
+    Ok(PRINT_PREVIEW_LABEL.to_string())
+}
+
 /// Force quit the entire application
 #[tauri::command]
 pub fn force_quit(app: AppHandle) {
+    crate::session::save_on_exit();
     app.exit(0);
 }
 
@@ -0,0 +1,162 @@
+//! Registry of document windows and what they show.
+//!
+//! The frontend registers each window's title, file/workspace and dirty
+//! state; `list_windows` joins that with live focus state. Used by the MCP
+//! `windows.list` tool and the Window menu (`menu::update_window_list_menu`).
+//! The metadata lives with each window's session entry (see `session`), so
+//! it is forgotten together with it when the window is destroyed.
+
+use serde::Serialize;
+use std::collections::BTreeMap;
+use tauri::{AppHandle, Manager, WebviewWindow};
+
+use crate::quit;
+use crate::session::{self, WindowSession};
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct WindowInfo {
+    pub label: String,
+    pub title: String,
+    #[serde(rename = "filePath")]
+    pub file_path: Option<String>,
+    #[serde(rename = "workspaceRoot")]
+    pub workspace_root: Option<String>,
+    pub dirty: bool,
+    pub focused: bool,
+}
+
+/// Sort key that orders labels by their numeric suffix, so `doc-2` comes
+/// before `doc-10` (window creation order).
+pub fn label_order(label: &str) -> (&str, u64) {
+    let prefix = label.trim_end_matches(|c: char| c.is_ascii_digit());
+    (prefix, label[prefix.len()..].parse().unwrap_or(0))
+}
+
+/// File shown in a window, if it registered one.
+pub fn file_path_of(label: &str) -> Option<String> {
+    session::live_windows().remove(label)?.active_file
+}
+
+/// Workspace open in a window, if it registered one.
+pub fn workspace_root_of(label: &str) -> Option<String> {
+    session::live_windows().remove(label)?.workspace_root
+}
+
+/// Document windows in creation order. Windows that are open but have not
+/// registered yet are listed with empty metadata.
+fn collect_infos(
+    registered: &BTreeMap<String, WindowSession>,
+    open_labels: &[String],
+    focused: Option<&str>,
+) -> Vec<WindowInfo> {
+    let mut labels: Vec<&String> = open_labels
+        .iter()
+        .filter(|label| quit::is_document_window_label(label))
+        .collect();
+    labels.sort_by(|a, b| label_order(a).cmp(&label_order(b)));
+    labels.dedup();
+
+    labels
+        .into_iter()
+        .map(|label| {
+            let meta = registered.get(label).cloned().unwrap_or_default();
+            WindowInfo {
+                label: label.clone(),
+                title: meta.title,
+                file_path: meta.active_file,
+                workspace_root: meta.workspace_root,
+                dirty: meta.dirty,
+                focused: focused == Some(label.as_str()),
+            }
+        })
+        .collect()
+}
+
+/// Report a window's title, document and dirty state.
+#[tauri::command]
+pub fn register_window_info(
+    window: WebviewWindow,
+    title: String,
+    file_path: Option<String>,
+    workspace_root: Option<String>,
+    dirty: bool,
+) {
+    let (retitled, rerooted) = session::update_window(window.label(), |entry| {
+        let changed = (entry.title != title, entry.workspace_root != workspace_root);
+        entry.title = title;
+        entry.active_file = file_path;
+        entry.workspace_root = workspace_root;
+        entry.dirty = dirty;
+        changed
+    })
+    .unwrap_or((false, false));
+    if retitled {
+        crate::menu::refresh_window_list(window.app_handle());
+    }
+    if rerooted {
+        crate::menu::refresh_workspace_menus(window.app_handle());
+    }
+}
+
+/// Open document windows with their metadata and focus state.
+pub fn document_windows(app: &AppHandle) -> Vec<WindowInfo> {
+    let open = app.webview_windows();
+    let labels: Vec<String> = open.keys().cloned().collect();
+    let focused = open
+        .iter()
+        .find(|(_, w)| w.is_focused().unwrap_or(false))
+        .map(|(label, _)| label.clone());
+
+    collect_infos(&session::live_windows(), &labels, focused.as_deref())
+}
+
+/// List open document windows with their metadata and focus state.
+#[tauri::command]
+pub fn list_windows(app: AppHandle) -> Vec<WindowInfo> {
+    document_windows(&app)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_collect_infos() {
+        let mut registered = BTreeMap::new();
+        registered.insert(
+            "doc-1".to_string(),
+            WindowSession {
+                title: "notes.md".into(),
+                active_file: Some("/ws/notes.md".into()),
+                workspace_root: Some("/ws".into()),
+                dirty: true,
+                ..Default::default()
+            },
+        );
+        // Registered but already closed
+        registered.insert("doc-9".to_string(), WindowSession::default());
+
+        let open = vec![
+            "main".to_string(),
+            "settings".to_string(),
+            "doc-10".to_string(),
+            "doc-1".to_string(),
+            "doc-2".to_string(),
+        ];
+        let infos = collect_infos(&registered, &open, Some("main"));
+
+        let labels: Vec<&str> = infos.iter().map(|i| i.label.as_str()).collect();
+        assert_eq!(labels, ["doc-1", "doc-2", "doc-10", "main"]);
+        assert!(infos[0].dirty);
+        assert_eq!(infos[0].file_path.as_deref(), Some("/ws/notes.md"));
+        assert!(!infos[0].focused);
+        assert_eq!(infos[3].title, "");
+        assert!(infos[3].focused);
+    }
+
+    #[test]
+    fn test_label_order() {
+        assert!(label_order("doc-2") < label_order("doc-10"));
+        assert_eq!(label_order("main"), ("main", 0));
+    }
+}
@@ -0,0 +1,344 @@
+//! Settings that affect how native windows are created and opened.
+//!
+//! The frontend settings store is the source of truth; it pushes the values
+//! the backend needs before a webview exists via `update_window_settings`.
+//! They are persisted to `~/.vmark/window-settings.json` so the first window
+//! of the next launch is created with them too.
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{LazyLock, Mutex};
+use tauri::utils::config::WindowEffectsConfig;
+use tauri::window::{Effect, EffectsBuilder};
+
+/// Default document window size (logical pixels)
+const DEFAULT_WIDTH: f64 = 800.0;
+const DEFAULT_HEIGHT: f64 = 600.0;
+
+/// Smallest minimum size accepted from settings
+const MIN_FLOOR_WIDTH: f64 = 320.0;
+const MIN_FLOOR_HEIGHT: f64 = 240.0;
+
+/// Accepted range for the default zoom factor
+const ZOOM_RANGE: std::ops::RangeInclusive<f64> = 0.5..=3.0;
+
+/// Seconds to wait for document windows to answer a quit request
+const DEFAULT_QUIT_TIMEOUT_SECONDS: u64 = 10;
+
+/// Levels stepped through by View > Zoom In / Zoom Out
+const ZOOM_LEVELS: &[f64] = &[
+    0.5, 0.67, 0.75, 0.8, 0.9, 1.0, 1.1, 1.25, 1.5, 1.75, 2.0, 2.5, 3.0,
+];
+
+/// Translucent background material for document windows.
+///
+/// `sidebar`/`underWindow` are macOS vibrancy materials and `mica`/`acrylic`
+/// Windows backdrops; each maps to the closest material on the other
+/// platform. Linux has no support and stays opaque.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Vibrancy {
+    #[default]
+    None,
+    Sidebar,
+    UnderWindow,
+    Mica,
+    Acrylic,
+}
+
+impl Vibrancy {
+    /// Platform effect for this material, if any.
+    #[cfg(target_os = "macos")]
+    fn effect(self) -> Option<Effect> {
+        match self {
+            Vibrancy::None => None,
+            Vibrancy::Sidebar | Vibrancy::Mica => Some(Effect::Sidebar),
+            Vibrancy::UnderWindow | Vibrancy::Acrylic => Some(Effect::UnderWindowBackground),
+        }
+    }
+
+    #[cfg(target_os = "windows")]
+    fn effect(self) -> Option<Effect> {
+        match self {
+            Vibrancy::None => None,
+            Vibrancy::Sidebar | Vibrancy::Mica => Some(Effect::Mica),
+            Vibrancy::UnderWindow | Vibrancy::Acrylic => Some(Effect::Acrylic),
+        }
+    }
+
+    #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+    fn effect(self) -> Option<Effect> {
+        None
+    }
+}
+
+/// Where files go when several are opened at once.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum OpenPolicy {
+    /// All as tabs in the focused window
+    #[default]
+    Tabs,
+    /// One window per file
+    Windows,
+    /// Ask the user
+    Prompt,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WindowSettings {
+    #[serde(default)]
+    pub vibrancy: Vibrancy,
+    #[serde(rename = "defaultWidth", default = "default_width")]
+    pub default_width: f64,
+    #[serde(rename = "defaultHeight", default = "default_height")]
+    pub default_height: f64,
+    #[serde(rename = "minWidth", default = "default_width")]
+    pub min_width: f64,
+    #[serde(rename = "minHeight", default = "default_height")]
+    pub min_height: f64,
+    /// Open new windows at the size of the last closed one
+    #[serde(rename = "rememberLastSize", default)]
+    pub remember_last_size: bool,
+    /// Webview zoom factor for new windows
+    #[serde(rename = "defaultZoom", default = "default_zoom")]
+    pub default_zoom: f64,
+    #[serde(rename = "openPolicy", default)]
+    pub open_policy: OpenPolicy,
+    /// File the quick-note window writes to
+    #[serde(
+        rename = "quickNoteInbox",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub quick_note_inbox: Option<String>,
+    /// How long quitting waits for unresponsive windows before offering to
+    /// force quit; 0 waits indefinitely
+    #[serde(rename = "quitTimeoutSeconds", default = "default_quit_timeout")]
+    pub quit_timeout_seconds: u64,
+    /// Force quit on timeout without asking
+    #[serde(rename = "forceQuitOnTimeout", default)]
+    pub force_quit_on_timeout: bool,
+}
+
+fn default_width() -> f64 {
+    DEFAULT_WIDTH
+}
+
+fn default_height() -> f64 {
+    DEFAULT_HEIGHT
+}
+
+fn default_zoom() -> f64 {
+    1.0
+}
+
+fn default_quit_timeout() -> u64 {
+    DEFAULT_QUIT_TIMEOUT_SECONDS
+}
+
+impl Default for WindowSettings {
+    fn default() -> Self {
+        Self {
+            vibrancy: Vibrancy::default(),
+            default_width: DEFAULT_WIDTH,
+            default_height: DEFAULT_HEIGHT,
+            min_width: DEFAULT_WIDTH,
+            min_height: DEFAULT_HEIGHT,
+            remember_last_size: false,
+            default_zoom: default_zoom(),
+            open_policy: OpenPolicy::default(),
+            quick_note_inbox: None,
+            quit_timeout_seconds: DEFAULT_QUIT_TIMEOUT_SECONDS,
+            force_quit_on_timeout: false,
+        }
+    }
+}
+
+impl WindowSettings {
+    /// Minimum document window size, never below a usable floor.
+    pub fn min_size(&self) -> (f64, f64) {
+        (
+            self.min_width.max(MIN_FLOOR_WIDTH),
+            self.min_height.max(MIN_FLOOR_HEIGHT),
+        )
+    }
+
+    /// Size for a new window without saved geometry: the last closed
+    /// window's size when remembered, else the default, never below the
+    /// minimum.
+    pub fn initial_size(&self, last_size: Option<(f64, f64)>) -> (f64, f64) {
+        let (width, height) = last_size
+            .filter(|_| self.remember_last_size)
+            .unwrap_or((self.default_width, self.default_height));
+        let (min_width, min_height) = self.min_size();
+        (width.max(min_width), height.max(min_height))
+    }
+
+    /// Window effects for new document windows, or None for an opaque window.
+    pub fn effects(&self) -> Option<WindowEffectsConfig> {
+        let effect = self.vibrancy.effect()?;
+        Some(EffectsBuilder::new().effect(effect).build())
+    }
+
+    /// Zoom factor to apply to new windows, if not the default.
+    pub fn zoom(&self) -> Option<f64> {
+        let zoom = clamp_zoom(self.default_zoom);
+        ((zoom - 1.0).abs() > f64::EPSILON).then_some(zoom)
+    }
+}
+
+/// Limit a webview zoom factor to the supported range.
+pub fn clamp_zoom(factor: f64) -> f64 {
+    if factor.is_nan() {
+        return 1.0;
+    }
+    factor.clamp(*ZOOM_RANGE.start(), *ZOOM_RANGE.end())
+}
+
+/// The zoom level after `current` when zooming in, or before it when
+/// zooming out. Stays at the ends of the range.
+pub fn step_zoom(current: f64, zoom_in: bool) -> f64 {
+    const TOLERANCE: f64 = 0.001;
+    let current = clamp_zoom(current);
+    let next = if zoom_in {
+        ZOOM_LEVELS
+            .iter()
+            .find(|&&level| level > current + TOLERANCE)
+    } else {
+        ZOOM_LEVELS
+            .iter()
+            .rev()
+            .find(|&&level| level < current - TOLERANCE)
+    };
+    next.copied().unwrap_or(current)
+}
+
+static SETTINGS: LazyLock<Mutex<WindowSettings>> = LazyLock::new(|| {
+    Mutex::new(
+        settings_file_path()
+            .map(|path| read_settings(&path))
+            .unwrap_or_default(),
+    )
+});
+
+fn settings_file_path() -> Option<PathBuf> {
+    dirs::home_dir().map(|home| home.join(".vmark").join("window-settings.json"))
+}
+
+fn read_settings(path: &Path) -> WindowSettings {
+    fs::read_to_string(path)
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+fn write_settings(path: &Path, settings: &WindowSettings) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create ~/.vmark directory: {e}"))?;
+    }
+    let content = serde_json::to_string_pretty(settings)
+        .map_err(|e| format!("Failed to serialize window settings: {e}"))?;
+    fs::write(path, content).map_err(|e| format!("Failed to write window settings: {e}"))
+}
+
+/// Current window settings.
+pub fn current() -> WindowSettings {
+    SETTINGS.lock().map(|s| s.clone()).unwrap_or_default()
+}
+
+/// Store window settings pushed from the frontend. Applies to windows
+/// created afterwards.
+#[tauri::command]
+pub fn update_window_settings(settings: WindowSettings) -> Result<(), String> {
+    if let Some(path) = settings_file_path() {
+        write_settings(&path, &settings)?;
+    }
+    if let Ok(mut current) = SETTINGS.lock() {
+        *current = settings;
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_window_settings() -> WindowSettings {
+    current()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_settings_round_trip() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("window-settings.json");
+
+        assert_eq!(read_settings(&path), WindowSettings::default());
+
+        let settings = WindowSettings {
+            vibrancy: Vibrancy::UnderWindow,
+            ..Default::default()
+        };
+        write_settings(&path, &settings).unwrap();
+        assert_eq!(read_settings(&path), settings);
+        assert!(fs::read_to_string(&path)
+            .unwrap()
+            .contains("\"underWindow\""));
+    }
+
+    #[test]
+    fn test_partial_settings_use_defaults() {
+        let settings: WindowSettings =
+            serde_json::from_str(r#"{ "defaultWidth": 1200, "minWidth": 100 }"#).unwrap();
+
+        assert_eq!(settings.default_height, DEFAULT_HEIGHT);
+        assert_eq!(settings.min_size(), (MIN_FLOOR_WIDTH, DEFAULT_HEIGHT));
+        assert_eq!(settings.zoom(), None);
+        assert_eq!(settings.open_policy, OpenPolicy::Tabs);
+        assert_eq!(settings.quit_timeout_seconds, DEFAULT_QUIT_TIMEOUT_SECONDS);
+    }
+
+    #[test]
+    fn test_initial_size() {
+        let mut settings = WindowSettings {
+            default_width: 1200.0,
+            default_height: 900.0,
+            ..Default::default()
+        };
+
+        assert_eq!(
+            settings.initial_size(Some((1000.0, 700.0))),
+            (1200.0, 900.0)
+        );
+
+        settings.remember_last_size = true;
+        assert_eq!(
+            settings.initial_size(Some((1000.0, 700.0))),
+            (1000.0, 700.0)
+        );
+        assert_eq!(settings.initial_size(Some((300.0, 200.0))), (800.0, 600.0));
+        assert_eq!(settings.initial_size(None), (1200.0, 900.0));
+    }
+
+    #[test]
+    fn test_clamp_zoom() {
+        assert_eq!(clamp_zoom(1.25), 1.25);
+        assert_eq!(clamp_zoom(10.0), 3.0);
+        assert_eq!(clamp_zoom(0.1), 0.5);
+        assert_eq!(clamp_zoom(f64::NAN), 1.0);
+    }
+
+    #[test]
+    fn test_step_zoom() {
+        assert_eq!(step_zoom(1.0, true), 1.1);
+        assert_eq!(step_zoom(1.0, false), 0.9);
+        assert_eq!(step_zoom(1.2, true), 1.25);
+        assert_eq!(step_zoom(1.2, false), 1.1);
+        assert_eq!(step_zoom(3.0, true), 3.0);
+        assert_eq!(step_zoom(0.5, false), 0.5);
+    }
+}
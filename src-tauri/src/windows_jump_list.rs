@@ -0,0 +1,114 @@
+//! Windows taskbar Jump List.
+//!
+//! Right-clicking the taskbar icon shows a "New Window" task and a Recent
+//! category mirroring the Open Recent menu. Entries relaunch the executable
+//! with `--new-window` or the file path as arguments. The list is rebuilt
+//! whenever `menu::update_recent_files_menu` stores a new snapshot.
+
+use std::sync::Mutex;
+
+use windows::core::{Interface, Result, HSTRING, PROPVARIANT};
+use windows::Win32::Storage::EnhancedStorage::PKEY_Title;
+use windows::Win32::System::Com::{
+    CoCreateInstance, CoInitializeEx, CoUninitialize, CLSCTX_INPROC_SERVER,
+    COINIT_APARTMENTTHREADED,
+};
+use windows::Win32::UI::Shell::Common::{IObjectArray, IObjectCollection};
+use windows::Win32::UI::Shell::PropertiesSystem::IPropertyStore;
+use windows::Win32::UI::Shell::{
+    DestinationList, EnumerableObjectCollection, ICustomDestinationList, IShellLinkW, ShellLink,
+};
+
+use crate::launch_args::NEW_WINDOW_ARG;
+use crate::menu_i18n;
+
+/// Serializes rebuilds; the shell rejects overlapping BeginList calls.
+static LOCK: Mutex<()> = Mutex::new(());
+
+fn shell_link(exe: &HSTRING, arguments: &str, title: &str) -> Result<IShellLinkW> {
+    unsafe {
+        let link: IShellLinkW = CoCreateInstance(&ShellLink, None, CLSCTX_INPROC_SERVER)?;
+        link.SetPath(exe)?;
+        link.SetArguments(&HSTRING::from(arguments))?;
+        link.SetIconLocation(exe, 0)?;
+        link.SetDescription(&HSTRING::from(title))?;
+        let store: IPropertyStore = link.cast()?;
+        store.SetValue(&PKEY_Title, &PROPVARIANT::from(title))?;
+        store.Commit()?;
+        Ok(link)
+    }
+}
+
+/// Arguments of the links the user removed from the list. Removed items
+/// must not be added again in the same session.
+fn removed_arguments(removed: &IObjectArray) -> Vec<String> {
+    let count = unsafe { removed.GetCount() }.unwrap_or(0);
+    (0..count)
+        .filter_map(|index| {
+            let link: IShellLinkW = unsafe { removed.GetAt(index) }.ok()?;
+            let mut buffer = [0u16; 1024];
+            unsafe { link.GetArguments(&mut buffer) }.ok()?;
+            let len = buffer.iter().position(|&c| c == 0).unwrap_or(buffer.len());
+            Some(String::from_utf16_lossy(&buffer[..len]))
+        })
+        .collect()
+}
+
+fn build(files: &[String]) -> Result<()> {
+    let exe = std::env::current_exe().map_err(|_| windows::core::Error::empty())?;
+    let exe = HSTRING::from(exe.as_os_str());
+
+    unsafe {
+        let list: ICustomDestinationList =
+            CoCreateInstance(&DestinationList, None, CLSCTX_INPROC_SERVER)?;
+        let mut max_slots = 0u32;
+        let removed: IObjectArray = list.BeginList(&mut max_slots)?;
+        let removed = removed_arguments(&removed);
+
+        let recent: IObjectCollection =
+            CoCreateInstance(&EnumerableObjectCollection, None, CLSCTX_INPROC_SERVER)?;
+        for path in files.iter().take(max_slots as usize) {
+            let arguments = format!("\"{path}\"");
+            if removed.contains(&arguments) {
+                continue;
+            }
+            let title = std::path::Path::new(path)
+                .file_name()
+                .and_then(|n| n.to_str())
+                .unwrap_or(path);
+            recent.AddObject(&shell_link(&exe, &arguments, title)?)?;
+        }
+        if recent.GetCount()? > 0 {
+            list.AppendCategory(
+                &HSTRING::from(menu_i18n::tr("Open Recent")),
+                &recent.cast::<IObjectArray>()?,
+            )?;
+        }
+
+        let tasks: IObjectCollection =
+            CoCreateInstance(&EnumerableObjectCollection, None, CLSCTX_INPROC_SERVER)?;
+        let new_window = menu_i18n::tr("New Window");
+        tasks.AddObject(&shell_link(&exe, NEW_WINDOW_ARG, &new_window)?)?;
+        list.AddUserTasks(&tasks.cast::<IObjectArray>()?)?;
+
+        list.CommitList()
+    }
+}
+
+/// Rebuild the Jump List from the recent files snapshot. Runs on its own
+/// COM apartment thread so it never blocks the caller.
+pub fn update(files: Vec<String>) {
+    std::thread::spawn(move || {
+        let _guard = LOCK.lock();
+        unsafe {
+            if CoInitializeEx(None, COINIT_APARTMENTTHREADED).is_err() {
+                return;
+            }
+        }
+        if let Err(_e) = build(&files) {
+            #[cfg(debug_assertions)]
+            eprintln!("[windows_jump_list] Failed to update Jump List: {}", _e);
+        }
+        unsafe { CoUninitialize() };
+    });
+}
@@ -0,0 +1,116 @@
+//! Windows session-end and sleep messages.
+//!
+//! Every top-level window receives `WM_QUERYENDSESSION` before a logout,
+//! restart or shutdown and `WM_POWERBROADCAST`/`PBT_APMSUSPEND` before the
+//! PC sleeps. Document windows are subclassed to catch them: session end
+//! is blocked (with a reason shown by Windows) while the coordinated quit
+//! runs, until it is cancelled or done saving, and sleep is handed to
+//! `quit::handle_system_sleep`.
+
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+use tauri::{AppHandle, WebviewWindow};
+use windows::core::HSTRING;
+use windows::Win32::Foundation::{HWND, LPARAM, LRESULT, WPARAM};
+use windows::Win32::System::Shutdown::{ShutdownBlockReasonCreate, ShutdownBlockReasonDestroy};
+use windows::Win32::UI::Shell::{DefSubclassProc, SetWindowSubclass};
+use windows::Win32::UI::WindowsAndMessaging::{
+    PBT_APMSUSPEND, WM_ENDSESSION, WM_POWERBROADCAST, WM_QUERYENDSESSION,
+};
+
+use crate::{menu_i18n, quit, session};
+
+/// Identifies our subclass procedure on a window
+const SUBCLASS_ID: usize = 0x766d_6b;
+
+static APP: OnceLock<AppHandle> = OnceLock::new();
+
+/// Windows whose session end is blocked with a reason (as `HWND` values)
+static BLOCKED: Mutex<Vec<usize>> = Mutex::new(Vec::new());
+
+/// Sleep is broadcast to every window; handle it once.
+static LAST_SLEEP: Mutex<Option<Instant>> = Mutex::new(None);
+
+fn handle_sleep_once(app: &AppHandle) {
+    if let Ok(mut last) = LAST_SLEEP.lock() {
+        if last.is_some_and(|at| at.elapsed() < Duration::from_secs(5)) {
+            return;
+        }
+        *last = Some(Instant::now());
+    }
+    quit::handle_system_sleep(app);
+}
+
+unsafe extern "system" fn subclass_proc(
+    hwnd: HWND,
+    msg: u32,
+    wparam: WPARAM,
+    lparam: LPARAM,
+    _id: usize,
+    _data: usize,
+) -> LRESULT {
+    let Some(app) = APP.get() else {
+        return DefSubclassProc(hwnd, msg, wparam, lparam);
+    };
+    match msg {
+        // Nothing left to protect once the coordinated quit allowed exit
+        WM_QUERYENDSESSION if !quit::is_exit_allowed() => {
+            let reason = HSTRING::from(menu_i18n::tr("Closing open documents"));
+            if ShutdownBlockReasonCreate(hwnd, &reason).is_ok() {
+                if let Ok(mut blocked) = BLOCKED.lock() {
+                    blocked.push(hwnd.0 as usize);
+                }
+            }
+            quit::handle_session_end(app);
+            LRESULT(0)
+        }
+        // The session ends now whether or not the quit finished
+        WM_ENDSESSION if wparam.0 != 0 => {
+            session::save_on_exit();
+            LRESULT(0)
+        }
+        WM_POWERBROADCAST if wparam.0 as u32 == PBT_APMSUSPEND => {
+            handle_sleep_once(app);
+            DefSubclassProc(hwnd, msg, wparam, lparam)
+        }
+        _ => DefSubclassProc(hwnd, msg, wparam, lparam),
+    }
+}
+
+/// Drop the block reasons once the quit was cancelled or everything is
+/// saved, so Windows stops listing the app as preventing shutdown.
+pub fn release_shutdown_block(app: &AppHandle) {
+    let hwnds = BLOCKED
+        .lock()
+        .map(|mut blocked| std::mem::take(&mut *blocked))
+        .unwrap_or_default();
+    if hwnds.is_empty() {
+        return;
+    }
+    // Only the thread that owns a window may destroy its reason
+    let _ = app.run_on_main_thread(move || {
+        for hwnd in hwnds {
+            let hwnd = HWND(hwnd as *mut core::ffi::c_void);
+            let _ = unsafe { ShutdownBlockReasonDestroy(hwnd) };
+        }
+    });
+}
+
+/// Subclass a document window to receive the session messages.
+pub fn install(app: &AppHandle, window: &WebviewWindow) {
+    let _ = APP.set(app.clone());
+    let Ok(hwnd) = window.hwnd() else {
+        return;
+    };
+    let hwnd = hwnd.0 as usize;
+
+    // Subclassing must happen on the thread that owns the window.
+    let _ = window.run_on_main_thread(move || {
+        let hwnd = HWND(hwnd as *mut core::ffi::c_void);
+        if !unsafe { SetWindowSubclass(hwnd, Some(subclass_proc), SUBCLASS_ID, 0) }.as_bool() {
+            #[cfg(debug_assertions)]
+            eprintln!("[windows_system_events] Failed to subclass window");
+        }
+    });
+}
@@ -1,8 +1,11 @@
 use serde::{Deserialize, Serialize};
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Emitter};
 use tauri_plugin_dialog::{DialogExt, FilePath};
 
+use crate::workspace_migration::{self, MigrationReport, CURRENT_WORKSPACE_VERSION};
+
 /// VS Code-compatible workspace file with VMark namespace extensions.
 /// Stored in `.vmark/vmark.code-workspace`.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -13,11 +16,29 @@ pub struct WorkspaceFile {
     /// Settings namespace (VS Code compatible)
     #[serde(default)]
     pub settings: WorkspaceSettings,
+    /// Schema version (VMark extension, see `workspace_migration`)
+    #[serde(rename = "vmark.version", default = "default_file_version")]
+    pub version: u32,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+/// A workspace folder entry. `path` may be relative to the workspace root
+/// (`.`, `docs`, `../shared-notes`) or absolute, and is preserved as written.
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
 pub struct WorkspaceFolder {
     pub path: String,
+    /// Optional display name (VS Code compatible)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+}
+
+impl WorkspaceFolder {
+    /// The workspace root itself (`.`)
+    pub fn root() -> Self {
+        Self {
+            path: ".".to_string(),
+            name: None,
+        }
+    }
 }
 
 /// Workspace identity and trust information
@@ -42,6 +63,9 @@ pub struct WorkspaceSettings {
     /// Folders to exclude from file tree (VMark extension)
     #[serde(rename = "vmark.excludeFolders", default)]
     pub exclude_folders: Vec<String>,
+    /// Gitignore-style glob patterns to exclude (VMark extension)
+    #[serde(rename = "vmark.excludeGlobs", default, skip_serializing_if = "Vec::is_empty")]
+    pub exclude_globs: Vec<String>,
     /// Show hidden files in file explorer (VMark extension)
     #[serde(rename = "vmark.showHiddenFiles", default)]
     pub show_hidden_files: bool,
@@ -54,29 +78,38 @@ pub struct WorkspaceSettings {
     /// Workspace identity and trust info (VMark extension)
     #[serde(rename = "vmark.identity", default, skip_serializing_if = "Option::is_none")]
     pub identity: Option<WorkspaceIdentity>,
+    /// Pinned files, workspace-relative (VMark extension)
+    #[serde(rename = "vmark.pinnedFiles", default, skip_serializing_if = "Vec::is_empty")]
+    pub pinned_files: Vec<String>,
 }
 
 impl Default for WorkspaceFile {
     fn default() -> Self {
         Self {
-            folders: vec![WorkspaceFolder {
-                path: ".".to_string(),
-            }],
+            folders: vec![WorkspaceFolder::root()],
             settings: WorkspaceSettings {
                 exclude_folders: vec![
                     ".git".to_string(),
                     "node_modules".to_string(),
                     ".vmark".to_string(),
                 ],
+                exclude_globs: vec![],
                 show_hidden_files: false,
                 last_open_tabs: vec![],
                 ai: None,
                 identity: None,
+                pinned_files: vec![],
             },
+            version: CURRENT_WORKSPACE_VERSION,
         }
     }
 }
 
+/// Workspace files without a version predate versioning.
+fn default_file_version() -> u32 {
+    workspace_migration::UNVERSIONED
+}
+
 /// Legacy workspace configuration (stored in root/.vmark file)
 /// Kept for migration purposes only.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -98,6 +131,8 @@ pub struct WorkspaceConfig {
     pub version: u32,
     #[serde(rename = "excludeFolders")]
     pub exclude_folders: Vec<String>,
+    #[serde(rename = "excludeGlobs", default)]
+    pub exclude_globs: Vec<String>,
     #[serde(rename = "showHiddenFiles", default)]
     pub show_hidden_files: bool,
     #[serde(rename = "lastOpenTabs")]
@@ -106,6 +141,14 @@ pub struct WorkspaceConfig {
     pub ai: Option<serde_json::Value>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub identity: Option<WorkspaceIdentity>,
+    /// Workspace folders as written in the workspace file.
+    /// Empty means "keep whatever the file already has" when writing.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub folders: Vec<WorkspaceFolder>,
+    /// Pinned files, workspace-relative. Ignored by `write_workspace_config`;
+    /// change them with `pin_file`/`unpin_file`.
+    #[serde(rename = "pinnedFiles", default)]
+    pub pinned_files: Vec<String>,
 }
 
 impl Default for WorkspaceConfig {
@@ -117,10 +160,13 @@ impl Default for WorkspaceConfig {
                 "node_modules".to_string(),
                 ".vmark".to_string(),
             ],
+            exclude_globs: vec![],
             show_hidden_files: false,
             last_open_tabs: vec![],
             ai: None,
             identity: None,
+            folders: vec![],
+            pinned_files: vec![],
         }
     }
 }
@@ -130,27 +176,51 @@ impl From<WorkspaceFile> for WorkspaceConfig {
         Self {
             version: 1,
             exclude_folders: file.settings.exclude_folders,
+            exclude_globs: file.settings.exclude_globs,
             show_hidden_files: file.settings.show_hidden_files,
             last_open_tabs: file.settings.last_open_tabs,
             ai: file.settings.ai,
             identity: file.settings.identity,
+            pinned_files: file.settings.pinned_files,
+            folders: file
+                .folders
+                .into_iter()
+                .map(|f| WorkspaceFolder {
+                    path: normalize_folder_entry(&f.path),
+                    name: f.name,
+                })
+                .collect(),
         }
     }
 }
 
 impl From<WorkspaceConfig> for WorkspaceFile {
     fn from(config: WorkspaceConfig) -> Self {
+        let folders = if config.folders.is_empty() {
+            vec![WorkspaceFolder::root()]
+        } else {
+            config
+                .folders
+                .into_iter()
+                .map(|f| WorkspaceFolder {
+                    path: normalize_folder_entry(&f.path),
+                    name: f.name,
+                })
+                .collect()
+        };
+
         Self {
-            folders: vec![WorkspaceFolder {
-                path: ".".to_string(),
-            }],
+            folders,
             settings: WorkspaceSettings {
                 exclude_folders: config.exclude_folders,
+                exclude_globs: config.exclude_globs,
                 show_hidden_files: config.show_hidden_files,
                 last_open_tabs: config.last_open_tabs,
                 ai: config.ai,
                 identity: config.identity,
+                pinned_files: config.pinned_files,
             },
+            version: CURRENT_WORKSPACE_VERSION,
         }
     }
 }
@@ -160,16 +230,222 @@ impl From<LegacyWorkspaceConfig> for WorkspaceConfig {
         Self {
             version: legacy.version,
             exclude_folders: legacy.exclude_folders,
+            exclude_globs: vec![],
             show_hidden_files: false,
             last_open_tabs: legacy.last_open_tabs,
             ai: legacy.ai,
             identity: None, // Legacy configs don't have identity
+            folders: vec![],
+            pinned_files: vec![],
+        }
+    }
+}
+
+/// Normalize a folder entry as written in the workspace file.
+/// Relative paths use `/` separators with `.`/`..` collapsed where possible;
+/// absolute paths are kept absolute. Empty paths mean the root (`.`).
+fn normalize_folder_entry(path: &str) -> String {
+    let trimmed = path.trim();
+    if trimmed.is_empty() {
+        return ".".to_string();
+    }
+    if Path::new(trimmed).is_absolute() {
+        return crate::links::normalize_path(Path::new(trimmed))
+            .to_string_lossy()
+            .to_string();
+    }
+
+    let unified = trimmed.replace('\\', "/");
+    let normalized = crate::links::normalize_path(Path::new(&unified));
+    let parts: Vec<String> = normalized
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy().to_string())
+        .collect();
+    if parts.is_empty() {
+        ".".to_string()
+    } else {
+        parts.join("/")
+    }
+}
+
+/// Resolve a workspace-relative (or absolute) path against the workspace root.
+pub fn resolve_workspace_path(root_path: &Path, path: &str) -> PathBuf {
+    let candidate = Path::new(path.trim());
+    if candidate.is_absolute() {
+        crate::links::normalize_path(candidate)
+    } else {
+        crate::links::normalize_path(&root_path.join(candidate))
+    }
+}
+
+/// Express a path relative to the workspace root with `/` separators.
+/// Paths outside the root become `../`-relative, or stay absolute when they
+/// share no common prefix with the root (e.g. another drive on Windows).
+pub fn to_workspace_relative(root_path: &Path, path: &Path) -> String {
+    let root = crate::links::normalize_path(root_path);
+    let target = crate::links::normalize_path(path);
+    match (root.components().next(), target.components().next()) {
+        (Some(a), Some(b)) if a == b => crate::links::relative_link(&root, &target),
+        _ => target.to_string_lossy().to_string(),
+    }
+}
+
+/// A workspace folder resolved against the workspace root.
+#[derive(Debug, Clone, Serialize)]
+pub struct ResolvedWorkspaceFolder {
+    /// Path as written in the workspace file
+    pub path: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// Absolute path on disk
+    #[serde(rename = "absolutePath")]
+    pub absolute_path: String,
+    pub exists: bool,
+    /// Folder lives outside the workspace root (e.g. `../shared-notes`)
+    #[serde(rename = "outsideRoot")]
+    pub outside_root: bool,
+}
+
+/// Strip JSONC extensions (comments and trailing commas) so the content can be
+/// parsed with serde_json. VS Code writes `.code-workspace` files in this dialect.
+/// String literals are preserved verbatim; comments are replaced by whitespace
+/// so error positions still point at the right line.
+pub(crate) fn strip_jsonc(content: &str) -> String {
+    let chars: Vec<char> = content.chars().collect();
+    let mut out = String::with_capacity(content.len());
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        match c {
+            '"' => {
+                // Copy string literal, honoring escapes
+                out.push(c);
+                i += 1;
+                while i < chars.len() {
+                    let sc = chars[i];
+                    out.push(sc);
+                    i += 1;
+                    if sc == '\\' {
+                        if let Some(&escaped) = chars.get(i) {
+                            out.push(escaped);
+                            i += 1;
+                        }
+                    } else if sc == '"' {
+                        break;
+                    }
+                }
+            }
+            '/' if chars.get(i + 1) == Some(&'/') => {
+                while i < chars.len() && chars[i] != '\n' {
+                    i += 1;
+                }
+            }
+            '/' if chars.get(i + 1) == Some(&'*') => {
+                i += 2;
+                while i < chars.len() && !(chars[i] == '*' && chars.get(i + 1) == Some(&'/')) {
+                    if chars[i] == '\n' {
+                        out.push('\n');
+                    }
+                    i += 1;
+                }
+                i += 2;
+            }
+            ',' => {
+                // Drop trailing commas: next significant char closes an object/array
+                let mut j = i + 1;
+                loop {
+                    while j < chars.len() && chars[j].is_whitespace() {
+                        j += 1;
+                    }
+                    if chars.get(j) == Some(&'/') && chars.get(j + 1) == Some(&'/') {
+                        while j < chars.len() && chars[j] != '\n' {
+                            j += 1;
+                        }
+                    } else if chars.get(j) == Some(&'/') && chars.get(j + 1) == Some(&'*') {
+                        j += 2;
+                        while j < chars.len() && !(chars[j] == '*' && chars.get(j + 1) == Some(&'/')) {
+                            j += 1;
+                        }
+                        j += 2;
+                    } else {
+                        break;
+                    }
+                }
+                if !matches!(chars.get(j), Some('}') | Some(']')) {
+                    out.push(c);
+                }
+                i += 1;
+            }
+            _ => {
+                out.push(c);
+                i += 1;
+            }
         }
     }
+
+    out
+}
+
+/// Parse a workspace file into raw JSON, accepting JSONC (comments and trailing commas).
+fn parse_workspace_value(content: &str) -> Result<serde_json::Value, serde_json::Error> {
+    serde_json::from_str(&strip_jsonc(content))
+}
+
+/// Parse a workspace file, applying pending migrations in memory only.
+fn parse_workspace_file(content: &str) -> Result<WorkspaceFile, serde_json::Error> {
+    let mut value = parse_workspace_value(content)?;
+    workspace_migration::migrate(&mut value);
+    serde_json::from_value(value)
+}
+
+/// Whether a workspace file uses JSONC comments or trailing commas, which
+/// rewriting it would lose.
+fn is_jsonc(content: &str) -> bool {
+    strip_jsonc(content) != content
+}
+
+/// Migrate an outdated workspace file on disk: back up the original and
+/// write the upgraded content. JSONC files are left alone so their comments
+/// survive; reads still migrate them in memory.
+fn migrate_workspace_file(
+    root_path: &Path,
+    workspace_path: &Path,
+) -> Result<Option<MigrationReport>, String> {
+    let content = fs::read_to_string(workspace_path)
+        .map_err(|e| format!("Failed to read workspace file: {e}"))?;
+    let mut value = parse_workspace_value(&content)
+        .map_err(|e| format!("Failed to parse workspace file: {e}"))?;
+    let (from_version, changes) = workspace_migration::migrate(&mut value);
+    if changes.is_empty() {
+        return Ok(None);
+    }
+    if is_jsonc(&content) {
+        return Err(
+            "Workspace file contains comments; not rewriting it with the migrated layout"
+                .to_string(),
+        );
+    }
+
+    let backup = workspace_migration::backup_path(workspace_path, from_version);
+    fs::write(&backup, &content).map_err(|e| format!("Failed to back up workspace file: {e}"))?;
+
+    let migrated = serde_json::to_string_pretty(&value)
+        .map_err(|e| format!("Failed to serialize workspace: {e}"))?;
+    fs::write(workspace_path, migrated)
+        .map_err(|e| format!("Failed to write workspace file: {e}"))?;
+
+    Ok(Some(MigrationReport {
+        root_path: root_path.to_string_lossy().to_string(),
+        from_version,
+        to_version: workspace_migration::file_version(&value),
+        changes,
+        backup_path: Some(backup.to_string_lossy().to_string()),
+    }))
 }
 
 /// Get the path to the new workspace file (.vmark/vmark.code-workspace)
-fn get_workspace_file_path(root_path: &Path) -> std::path::PathBuf {
+pub(crate) fn get_workspace_file_path(root_path: &Path) -> std::path::PathBuf {
     root_path.join(".vmark").join("vmark.code-workspace")
 }
 
@@ -201,49 +477,49 @@ fn read_legacy_config(root_path: &Path) -> Result<Option<LegacyWorkspaceConfig>,
     Ok(Some(config))
 }
 
-/// Migrate legacy .vmark file to new .vmark/vmark.code-workspace format.
-/// Returns true if migration occurred, false if no migration needed.
-fn migrate_legacy_config(root_path: &Path) -> Result<bool, String> {
+/// Migrate legacy .vmark file to new .vmark/vmark.code-workspace format
+/// through the migration registry. The legacy content is kept as the
+/// version 0 backup. Returns None if no migration was needed.
+fn migrate_legacy_config(root_path: &Path) -> Result<Option<MigrationReport>, String> {
     if !is_legacy_config(root_path) {
-        return Ok(false);
+        return Ok(None);
     }
 
-    // Read legacy config
-    let legacy = match read_legacy_config(root_path)? {
-        Some(c) => c,
-        None => return Ok(false),
-    };
-
-    // Convert to new format
-    let config: WorkspaceConfig = legacy.into();
-    let workspace_file: WorkspaceFile = config.into();
-
-    // Ensure .vmark directory exists
-    let vmark_dir = root_path.join(".vmark");
-
-    // Rename legacy file to backup before creating directory
     let legacy_path = get_legacy_config_path(root_path);
-    let backup_path = root_path.join(".vmark.backup");
+    let content = fs::read_to_string(&legacy_path)
+        .map_err(|e| format!("Failed to read legacy .vmark: {e}"))?;
+    let mut value: serde_json::Value = serde_json::from_str(&content)
+        .map_err(|e| format!("Failed to parse legacy .vmark: {e}"))?;
+    workspace_migration::mark_legacy(&mut value);
+    let (from_version, changes) = workspace_migration::migrate(&mut value);
+    let migrated = serde_json::to_string_pretty(&value)
+        .map_err(|e| format!("Failed to serialize workspace: {e}"))?;
 
-    fs::rename(&legacy_path, &backup_path)
+    // The .vmark file has to make way for the .vmark directory
+    let temp_backup_path = root_path.join(".vmark.backup");
+    fs::rename(&legacy_path, &temp_backup_path)
         .map_err(|e| format!("Failed to backup legacy .vmark: {e}"))?;
 
-    // Create .vmark directory
+    let vmark_dir = root_path.join(".vmark");
     fs::create_dir_all(&vmark_dir)
         .map_err(|e| format!("Failed to create .vmark directory: {e}"))?;
 
-    // Write new workspace file
     let workspace_path = get_workspace_file_path(root_path);
-    let content = serde_json::to_string_pretty(&workspace_file)
-        .map_err(|e| format!("Failed to serialize workspace: {e}"))?;
-
-    fs::write(&workspace_path, content)
+    let backup = workspace_migration::backup_path(&workspace_path, from_version);
+    fs::write(&backup, &content).map_err(|e| format!("Failed to back up legacy .vmark: {e}"))?;
+    fs::write(&workspace_path, migrated)
         .map_err(|e| format!("Failed to write workspace file: {e}"))?;
 
-    // Remove backup after successful migration
-    let _ = fs::remove_file(&backup_path);
+    // Remove temporary backup after successful migration
+    let _ = fs::remove_file(&temp_backup_path);
 
-    Ok(true)
+    Ok(Some(MigrationReport {
+        root_path: root_path.to_string_lossy().to_string(),
+        from_version,
+        to_version: workspace_migration::file_version(&value),
+        changes,
+        backup_path: Some(backup.to_string_lossy().to_string()),
+    }))
 }
 
 /// Open folder dialog and return selected path
@@ -265,13 +541,30 @@ pub async fn open_folder_dialog(app: tauri::AppHandle) -> Result<Option<String>,
     }
 }
 
-/// Read workspace config, with automatic migration from legacy format.
+/// Bring the workspace file on disk up to date: move a legacy .vmark file
+/// to the new location and apply pending schema migrations. Announces
+/// changes with `workspace:migrated` and returns what changed.
 #[tauri::command]
-pub fn read_workspace_config(root_path: &str) -> Result<Option<WorkspaceConfig>, String> {
+pub fn migrate_workspace_config(root_path: &str) -> Result<Option<MigrationReport>, String> {
     let root = Path::new(root_path);
+    let report = match migrate_legacy_config(root)? {
+        Some(report) => Some(report),
+        None if get_workspace_file_path(root).exists() => {
+            migrate_workspace_file(root, &get_workspace_file_path(root))?
+        }
+        None => None,
+    };
+    if let Some(report) = &report {
+        workspace_migration::notify_migrated(report);
+    }
+    Ok(report)
+}
 
-    // Try to migrate legacy config first
-    let _ = migrate_legacy_config(root);
+/// Read workspace config. Legacy and outdated files are migrated in memory
+/// only; `migrate_workspace_config` updates them on disk.
+#[tauri::command]
+pub fn read_workspace_config(root_path: &str) -> Result<Option<WorkspaceConfig>, String> {
+    let root = Path::new(root_path);
 
     // Read from new location
     let workspace_path = get_workspace_file_path(root);
@@ -287,7 +580,7 @@ pub fn read_workspace_config(root_path: &str) -> Result<Option<WorkspaceConfig>,
     let content = fs::read_to_string(&workspace_path)
         .map_err(|e| format!("Failed to read workspace file: {e}"))?;
 
-    let workspace_file: WorkspaceFile = serde_json::from_str(&content)
+    let workspace_file = parse_workspace_file(&content)
         .map_err(|e| format!("Failed to parse workspace file: {e}"))?;
 
     Ok(Some(workspace_file.into()))
@@ -296,6 +589,30 @@ pub fn read_workspace_config(root_path: &str) -> Result<Option<WorkspaceConfig>,
 /// Write workspace config to .vmark/vmark.code-workspace
 #[tauri::command]
 pub fn write_workspace_config(root_path: &str, config: WorkspaceConfig) -> Result<(), String> {
+    let workspace_path = get_workspace_file_path(Path::new(root_path));
+
+    // Pinned files are owned by pin_file/unpin_file; a window writing its
+    // settings may never have loaded them, so always keep the file's list.
+    // Folders are kept too when the caller didn't send any.
+    let mut config = config;
+    let existing = fs::read_to_string(&workspace_path)
+        .ok()
+        .and_then(|content| parse_workspace_file(&content).ok());
+    config.pinned_files = existing
+        .as_ref()
+        .map(|file| file.settings.pinned_files.clone())
+        .unwrap_or_default();
+    if config.folders.is_empty() {
+        if let Some(existing) = existing {
+            config.folders = existing.folders;
+        }
+    }
+
+    save_workspace_config(root_path, config)
+}
+
+/// Write the workspace file as given, creating `.vmark` if needed.
+fn save_workspace_config(root_path: &str, config: WorkspaceConfig) -> Result<(), String> {
     let root = Path::new(root_path);
     let vmark_dir = root.join(".vmark");
 
@@ -305,8 +622,8 @@ pub fn write_workspace_config(root_path: &str, config: WorkspaceConfig) -> Resul
             .map_err(|e| format!("Failed to create .vmark directory: {e}"))?;
     }
 
-    let workspace_file: WorkspaceFile = config.into();
     let workspace_path = get_workspace_file_path(root);
+    let workspace_file: WorkspaceFile = config.into();
 
     let content = serde_json::to_string_pretty(&workspace_file)
         .map_err(|e| format!("Failed to serialize workspace: {e}"))?;
@@ -324,6 +641,167 @@ pub fn has_workspace_config(root_path: &str) -> bool {
     get_workspace_file_path(root).exists() || is_legacy_config(root)
 }
 
+/// List workspace folders resolved to absolute paths.
+#[tauri::command]
+pub fn resolve_workspace_folders(root_path: &str) -> Result<Vec<ResolvedWorkspaceFolder>, String> {
+    let root = Path::new(root_path);
+    let config = read_workspace_config(root_path)?.unwrap_or_default();
+    let folders = if config.folders.is_empty() {
+        vec![WorkspaceFolder::root()]
+    } else {
+        config.folders
+    };
+    let normalized_root = crate::links::normalize_path(root);
+
+    Ok(folders
+        .into_iter()
+        .map(|folder| {
+            let absolute = resolve_workspace_path(root, &folder.path);
+            ResolvedWorkspaceFolder {
+                exists: absolute.is_dir(),
+                outside_root: !absolute.starts_with(&normalized_root),
+                absolute_path: absolute.to_string_lossy().to_string(),
+                path: folder.path,
+                name: folder.name,
+            }
+        })
+        .collect())
+}
+
+/// Convert an absolute path to a workspace-relative path (for storing in settings).
+#[tauri::command]
+pub fn workspace_relative_path(root_path: &str, path: &str) -> String {
+    to_workspace_relative(Path::new(root_path), Path::new(path))
+}
+
+/// A pinned file resolved against the workspace root.
+#[derive(Debug, Clone, Serialize)]
+pub struct PinnedFile {
+    /// Workspace-relative path as stored in settings
+    pub path: String,
+    #[serde(rename = "absolutePath")]
+    pub absolute_path: String,
+    pub exists: bool,
+}
+
+/// Payload for `workspace:pinned-files-changed`
+#[derive(Clone, Serialize)]
+struct PinnedFilesChanged {
+    #[serde(rename = "rootPath")]
+    root_path: String,
+    #[serde(rename = "pinnedFiles")]
+    pinned_files: Vec<PinnedFile>,
+}
+
+/// Resolve stored pinned entries to absolute paths.
+fn resolve_pinned_files(root: &Path, pinned: &[String]) -> Vec<PinnedFile> {
+    pinned
+        .iter()
+        .map(|entry| {
+            let absolute = resolve_workspace_path(root, entry);
+            PinnedFile {
+                path: entry.clone(),
+                exists: absolute.is_file(),
+                absolute_path: absolute.to_string_lossy().to_string(),
+            }
+        })
+        .collect()
+}
+
+/// Normalize an absolute or relative path to a stored pinned entry.
+fn pinned_entry(root: &Path, path: &str) -> String {
+    to_workspace_relative(root, &resolve_workspace_path(root, path))
+}
+
+/// Read, modify and write the pinned files list. Returns the resolved list.
+fn update_pinned_files<F>(root_path: &str, update: F) -> Result<Vec<PinnedFile>, String>
+where
+    F: FnOnce(&mut Vec<String>),
+{
+    let root = Path::new(root_path);
+    let mut config = read_workspace_config(root_path)?.unwrap_or_default();
+    let before = config.pinned_files.clone();
+    update(&mut config.pinned_files);
+
+    if config.pinned_files != before {
+        let pinned = config.pinned_files.clone();
+        save_workspace_config(root_path, config)?;
+        return Ok(resolve_pinned_files(root, &pinned));
+    }
+
+    Ok(resolve_pinned_files(root, &config.pinned_files))
+}
+
+/// Rewrite pinned entries after files were moved. `map_path` maps an old
+/// absolute path to its new location. Returns true if any entry changed.
+pub fn remap_pinned_files<F>(root_path: &Path, map_path: F) -> Result<bool, String>
+where
+    F: Fn(&Path) -> PathBuf,
+{
+    let Some(root_str) = root_path.to_str() else {
+        return Ok(false);
+    };
+    if !get_workspace_file_path(root_path).exists() {
+        return Ok(false);
+    }
+
+    let mut changed = false;
+    update_pinned_files(root_str, |pinned| {
+        for entry in pinned.iter_mut() {
+            let absolute = resolve_workspace_path(root_path, entry);
+            let mapped = map_path(&absolute);
+            if mapped != absolute {
+                *entry = to_workspace_relative(root_path, &mapped);
+                changed = true;
+            }
+        }
+    })?;
+
+    Ok(changed)
+}
+
+fn emit_pinned_files_changed(app: &AppHandle, root_path: &str, pinned_files: &[PinnedFile]) {
+    let _ = app.emit(
+        "workspace:pinned-files-changed",
+        PinnedFilesChanged {
+            root_path: root_path.to_string(),
+            pinned_files: pinned_files.to_vec(),
+        },
+    );
+    crate::menu::refresh_workspace_menus(app);
+}
+
+/// List pinned files for a workspace.
+#[tauri::command]
+pub fn list_pinned_files(root_path: &str) -> Result<Vec<PinnedFile>, String> {
+    let config = read_workspace_config(root_path)?.unwrap_or_default();
+    Ok(resolve_pinned_files(Path::new(root_path), &config.pinned_files))
+}
+
+/// Pin a file (absolute or workspace-relative path). Pinning twice is a no-op.
+/// Emits `workspace:pinned-files-changed` to all windows.
+#[tauri::command]
+pub fn pin_file(app: AppHandle, root_path: &str, path: &str) -> Result<Vec<PinnedFile>, String> {
+    let entry = pinned_entry(Path::new(root_path), path);
+    let pinned = update_pinned_files(root_path, |pinned| {
+        if !pinned.contains(&entry) {
+            pinned.push(entry);
+        }
+    })?;
+    emit_pinned_files_changed(&app, root_path, &pinned);
+    Ok(pinned)
+}
+
+/// Unpin a file (absolute or workspace-relative path).
+/// Emits `workspace:pinned-files-changed` to all windows.
+#[tauri::command]
+pub fn unpin_file(app: AppHandle, root_path: &str, path: &str) -> Result<Vec<PinnedFile>, String> {
+    let entry = pinned_entry(Path::new(root_path), path);
+    let pinned = update_pinned_files(root_path, |pinned| pinned.retain(|p| p != &entry))?;
+    emit_pinned_files_changed(&app, root_path, &pinned);
+    Ok(pinned)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -344,10 +822,13 @@ mod tests {
         let config = WorkspaceConfig {
             version: 1,
             exclude_folders: vec!["test".to_string()],
+            exclude_globs: vec![],
             show_hidden_files: true,
             last_open_tabs: vec!["file.md".to_string()],
             ai: None,
             identity: None,
+            folders: vec![],
+            pinned_files: vec![],
         };
 
         let file: WorkspaceFile = config.clone().into();
@@ -374,10 +855,13 @@ mod tests {
         let config = WorkspaceConfig {
             version: 1,
             exclude_folders: vec!["custom".to_string()],
+            exclude_globs: vec![],
             show_hidden_files: false,
             last_open_tabs: vec!["doc.md".to_string()],
             ai: None,
             identity: None,
+            folders: vec![],
+            pinned_files: vec![],
         };
 
         write_workspace_config(root, config.clone()).unwrap();
@@ -406,7 +890,14 @@ mod tests {
         // Verify it's detected as legacy
         assert!(is_legacy_config(root));
 
-        // Read should trigger migration
+        // Reading leaves the legacy file alone
+        let config = read_workspace_config(root.to_str().unwrap()).unwrap().unwrap();
+        assert!(is_legacy_config(root));
+        assert!(config.exclude_folders.contains(&"legacy_folder".to_string()));
+
+        let report = migrate_workspace_config(root.to_str().unwrap()).unwrap().unwrap();
+        assert_eq!(report.from_version, 0);
+        assert_eq!(report.to_version, CURRENT_WORKSPACE_VERSION);
         let config = read_workspace_config(root.to_str().unwrap()).unwrap().unwrap();
 
         // Verify migration occurred
@@ -433,6 +924,44 @@ mod tests {
         assert!(has_workspace_config(root.to_str().unwrap()));
     }
 
+    #[test]
+    fn test_migrate_outdated_workspace_file() {
+        let dir = tempdir().unwrap();
+        let root = dir.path();
+        let root_str = root.to_str().unwrap();
+        fs::create_dir_all(root.join(".vmark")).unwrap();
+
+        let commented = r#"{
+            // written before versioning
+            "folders": [{ "path": "docs/" }],
+            "settings": { "vmark.excludeFolders": [".git", ".git"] }
+        }"#;
+        let workspace_path = get_workspace_file_path(root);
+        fs::write(&workspace_path, commented).unwrap();
+
+        // Reads migrate in memory only; comments block rewriting the file
+        let config = read_workspace_config(root_str).unwrap().unwrap();
+        assert_eq!(config.exclude_folders, vec![".git".to_string()]);
+        assert_eq!(config.folders[0].path, "docs");
+        assert!(migrate_workspace_config(root_str).is_err());
+        assert_eq!(fs::read_to_string(&workspace_path).unwrap(), commented);
+
+        let original = r#"{
+            "folders": [{ "path": "docs/" }],
+            "settings": { "vmark.excludeFolders": [".git", ".git"] }
+        }"#;
+        fs::write(&workspace_path, original).unwrap();
+        assert!(migrate_workspace_config(root_str).unwrap().is_some());
+        assert!(migrate_workspace_config(root_str).unwrap().is_none());
+
+        let backup = workspace_migration::backup_path(&workspace_path, 1);
+        assert_eq!(fs::read_to_string(backup).unwrap(), original);
+
+        let migrated = fs::read_to_string(&workspace_path).unwrap();
+        let ws = parse_workspace_file(&migrated).unwrap();
+        assert_eq!(ws.version, CURRENT_WORKSPACE_VERSION);
+    }
+
     #[test]
     fn test_has_workspace_config_legacy_format() {
         let dir = tempdir().unwrap();
@@ -455,4 +984,182 @@ mod tests {
         let result = read_workspace_config(root.to_str().unwrap());
         assert!(result.is_err());
     }
+
+    #[test]
+    fn test_strip_jsonc_comments_and_trailing_commas() {
+        let content = r#"{
+            // Line comment
+            "folders": [{ "path": "." },],
+            /* Block
+               comment */
+            "settings": {
+                "vmark.excludeFolders": ["a//b", "c/*d*/",], // trailing
+            },
+        }"#;
+
+        let ws = parse_workspace_file(content).unwrap();
+        assert_eq!(ws.folders.len(), 1);
+        assert_eq!(
+            ws.settings.exclude_folders,
+            vec!["a//b".to_string(), "c/*d*/".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_strip_jsonc_preserves_escaped_quotes() {
+        let stripped = strip_jsonc(r#"{"a": "quote \" // not a comment",}"#);
+        let value: serde_json::Value = serde_json::from_str(&stripped).unwrap();
+        assert_eq!(value["a"], "quote \" // not a comment");
+    }
+
+    #[test]
+    fn test_read_jsonc_workspace_file() {
+        let dir = tempdir().unwrap();
+        let root = dir.path();
+        fs::create_dir_all(root.join(".vmark")).unwrap();
+        fs::write(
+            root.join(".vmark").join("vmark.code-workspace"),
+            "{\n  // edited in VS Code\n  \"settings\": { \"vmark.showHiddenFiles\": true, },\n}\n",
+        )
+        .unwrap();
+
+        let config = read_workspace_config(root.to_str().unwrap()).unwrap().unwrap();
+        assert!(config.show_hidden_files);
+    }
+
+    #[test]
+    fn test_normalize_folder_entry() {
+        assert_eq!(normalize_folder_entry(""), ".");
+        assert_eq!(normalize_folder_entry("./"), ".");
+        assert_eq!(normalize_folder_entry("./docs/"), "docs");
+        assert_eq!(normalize_folder_entry("../shared-notes"), "../shared-notes");
+        assert_eq!(normalize_folder_entry("docs/../notes"), "notes");
+    }
+
+    #[test]
+    fn test_folders_roundtrip_preserved() {
+        let dir = tempdir().unwrap();
+        let root = dir.path();
+        fs::create_dir_all(root.join(".vmark")).unwrap();
+        fs::write(
+            get_workspace_file_path(root),
+            r#"{ "folders": [{ "path": "." }, { "path": "../shared-notes", "name": "Shared" }, { "path": "/abs/notes" }] }"#,
+        )
+        .unwrap();
+
+        // Frontend sends a config without folders: existing folders must survive
+        let mut config = read_workspace_config(root.to_str().unwrap()).unwrap().unwrap();
+        config.folders.clear();
+        write_workspace_config(root.to_str().unwrap(), config).unwrap();
+
+        let reread = read_workspace_config(root.to_str().unwrap()).unwrap().unwrap();
+        let paths: Vec<&str> = reread.folders.iter().map(|f| f.path.as_str()).collect();
+        assert_eq!(paths, vec![".", "../shared-notes", "/abs/notes"]);
+        assert_eq!(reread.folders[1].name.as_deref(), Some("Shared"));
+    }
+
+    #[test]
+    fn test_resolve_workspace_folders_outside_root() {
+        let dir = tempdir().unwrap();
+        let root = dir.path().join("ws");
+        fs::create_dir_all(dir.path().join("shared-notes")).unwrap();
+        let config = WorkspaceConfig {
+            folders: vec![
+                WorkspaceFolder::root(),
+                WorkspaceFolder {
+                    path: "../shared-notes".to_string(),
+                    name: None,
+                },
+            ],
+            ..WorkspaceConfig::default()
+        };
+        write_workspace_config(root.to_str().unwrap(), config).unwrap();
+
+        let resolved = resolve_workspace_folders(root.to_str().unwrap()).unwrap();
+        assert_eq!(resolved.len(), 2);
+        assert!(!resolved[0].outside_root);
+        assert!(resolved[1].outside_root);
+        assert!(resolved[1].exists);
+        assert_eq!(
+            PathBuf::from(&resolved[1].absolute_path),
+            dir.path().join("shared-notes")
+        );
+    }
+
+    #[test]
+    fn test_to_workspace_relative() {
+        let root = Path::new("/ws/project");
+        assert_eq!(to_workspace_relative(root, Path::new("/ws/project/docs/a.md")), "docs/a.md");
+        assert_eq!(to_workspace_relative(root, Path::new("/ws/shared")), "../shared");
+        assert_eq!(resolve_workspace_path(root, "../shared"), PathBuf::from("/ws/shared"));
+    }
+
+    #[test]
+    fn test_pin_and_unpin_files() {
+        let dir = tempdir().unwrap();
+        let root = dir.path().to_str().unwrap();
+        fs::write(dir.path().join("todo.md"), "").unwrap();
+
+        let abs = dir.path().join("todo.md");
+        assert_eq!(pinned_entry(dir.path(), abs.to_str().unwrap()), "todo.md");
+
+        for _ in 0..2 {
+            update_pinned_files(root, |pinned| {
+                let entry = pinned_entry(dir.path(), "todo.md");
+                if !pinned.contains(&entry) {
+                    pinned.push(entry);
+                }
+            })
+            .unwrap();
+        }
+
+        let pinned = list_pinned_files(root).unwrap();
+        assert_eq!(pinned.len(), 1);
+        assert_eq!(pinned[0].path, "todo.md");
+        assert!(pinned[0].exists);
+
+        let pinned = update_pinned_files(root, |pinned| pinned.clear()).unwrap();
+        assert!(pinned.is_empty());
+    }
+
+    #[test]
+    fn test_config_write_keeps_pinned_files() {
+        let dir = tempdir().unwrap();
+        let root = dir.path().to_str().unwrap();
+
+        update_pinned_files(root, |pinned| pinned.push("todo.md".to_string())).unwrap();
+
+        // A window that never loaded the pins writes its settings
+        let config = WorkspaceConfig {
+            show_hidden_files: true,
+            ..Default::default()
+        };
+        write_workspace_config(root, config).unwrap();
+
+        let config = read_workspace_config(root).unwrap().unwrap();
+        assert!(config.show_hidden_files);
+        assert_eq!(config.pinned_files, vec!["todo.md".to_string()]);
+    }
+
+    #[test]
+    fn test_remap_pinned_files() {
+        let dir = tempdir().unwrap();
+        let root = dir.path();
+        let config = WorkspaceConfig {
+            pinned_files: vec!["notes/a.md".to_string(), "b.md".to_string()],
+            ..WorkspaceConfig::default()
+        };
+        save_workspace_config(root.to_str().unwrap(), config).unwrap();
+
+        let (from, to) = (root.join("notes"), root.join("archive"));
+        let changed = remap_pinned_files(root, |p| match p.strip_prefix(&from) {
+            Ok(rest) => to.join(rest),
+            Err(_) => p.to_path_buf(),
+        })
+        .unwrap();
+        assert!(changed);
+
+        let config = read_workspace_config(root.to_str().unwrap()).unwrap().unwrap();
+        assert_eq!(config.pinned_files, vec!["archive/a.md".to_string(), "b.md".to_string()]);
+    }
 }
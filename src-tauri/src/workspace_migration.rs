@@ -0,0 +1,275 @@
+//! Versioned migrations for `.vmark/vmark.code-workspace`.
+//!
+//! The file carries a `vmark.version` field. Steps registered in
+//! `MIGRATIONS` are applied in order to the raw JSON until the file reaches
+//! `CURRENT_WORKSPACE_VERSION`. Reads apply them in memory only; the file
+//! is rewritten by `migrate_workspace_config` when a workspace is opened,
+//! which backs up the original next to it and tells the frontend what
+//! changed with a `workspace:migrated` event.
+//!
+//! The legacy `.vmark` file is version 0. Workspace files without a version
+//! are version 1 (the first `.code-workspace` layout). Files from a newer
+//! VMark are read as-is.
+
+use serde::Serialize;
+use serde_json::Value;
+use std::path::Path;
+use std::sync::OnceLock;
+use tauri::{AppHandle, Emitter};
+
+/// Schema version written by this build
+pub const CURRENT_WORKSPACE_VERSION: u32 = 2;
+
+/// Version of the legacy `.vmark` file
+pub const LEGACY_VERSION: u32 = 0;
+
+/// Version assumed for workspace files that predate the version field
+pub const UNVERSIONED: u32 = 1;
+
+/// Key of the version field in the workspace file
+pub const VERSION_KEY: &str = "vmark.version";
+
+/// A single schema upgrade from `from` to `from + 1`.
+struct Migration {
+    from: u32,
+    description: &'static str,
+    apply: fn(&mut Value),
+}
+
+/// Registered migrations, in order. Add new steps at the end and bump
+/// `CURRENT_WORKSPACE_VERSION`.
+const MIGRATIONS: &[Migration] = &[
+    Migration {
+        from: 0,
+        description: "Moved legacy .vmark file to .vmark/vmark.code-workspace",
+        apply: migrate_v0_to_v1,
+    },
+    Migration {
+        from: 1,
+        description: "Normalized folder paths and removed duplicate exclude folders",
+        apply: migrate_v1_to_v2,
+    },
+];
+
+/// v0 -> v1: the flat legacy `.vmark` config becomes a `.code-workspace`
+/// file with the workspace root as its only folder.
+fn migrate_v0_to_v1(file: &mut Value) {
+    let mut settings = serde_json::Map::new();
+    for (legacy_key, key) in [
+        ("excludeFolders", "vmark.excludeFolders"),
+        ("lastOpenTabs", "vmark.lastOpenTabs"),
+        ("ai", "vmark.ai"),
+    ] {
+        if let Some(value) = file.get(legacy_key).filter(|v| !v.is_null()) {
+            settings.insert(key.to_string(), value.clone());
+        }
+    }
+    let mut migrated = serde_json::Map::new();
+    migrated.insert("folders".into(), serde_json::json!([{ "path": "." }]));
+    migrated.insert("settings".into(), Value::Object(settings));
+    migrated.insert(VERSION_KEY.into(), Value::from(LEGACY_VERSION));
+    *file = Value::Object(migrated);
+}
+
+/// v1 -> v2: folder paths use `/` without trailing slashes, and
+/// `vmark.excludeFolders` has no duplicates.
+fn migrate_v1_to_v2(file: &mut Value) {
+    if let Some(folders) = file.get_mut("folders").and_then(Value::as_array_mut) {
+        for folder in folders {
+            if let Some(path) = folder.get_mut("path") {
+                if let Some(s) = path.as_str() {
+                    let unified = s.replace('\\', "/");
+                    let trimmed = unified.trim_end_matches('/');
+                    *path = Value::String(if trimmed.is_empty() {
+                        ".".into()
+                    } else {
+                        trimmed.into()
+                    });
+                }
+            }
+        }
+    }
+
+    if let Some(excludes) = file
+        .get_mut("settings")
+        .and_then(|s| s.get_mut("vmark.excludeFolders"))
+        .and_then(Value::as_array_mut)
+    {
+        let mut seen = Vec::new();
+        excludes.retain(|v| {
+            if seen.contains(v) {
+                false
+            } else {
+                seen.push(v.clone());
+                true
+            }
+        });
+    }
+}
+
+/// What a migration run changed. Payload of `workspace:migrated`.
+#[derive(Debug, Clone, Serialize)]
+pub struct MigrationReport {
+    #[serde(rename = "rootPath")]
+    pub root_path: String,
+    #[serde(rename = "fromVersion")]
+    pub from_version: u32,
+    #[serde(rename = "toVersion")]
+    pub to_version: u32,
+    /// Human-readable description of each applied step
+    pub changes: Vec<String>,
+    /// Copy of the file before migration
+    #[serde(rename = "backupPath", skip_serializing_if = "Option::is_none")]
+    pub backup_path: Option<String>,
+}
+
+/// Read the schema version of a raw workspace file.
+pub fn file_version(file: &Value) -> u32 {
+    file.get(VERSION_KEY)
+        .and_then(Value::as_u64)
+        .map(|v| v as u32)
+        .unwrap_or(UNVERSIONED)
+}
+
+/// Mark raw legacy `.vmark` content so `migrate` starts at version 0.
+pub fn mark_legacy(file: &mut Value) {
+    if let Some(obj) = file.as_object_mut() {
+        obj.insert(VERSION_KEY.to_string(), Value::from(LEGACY_VERSION));
+    }
+}
+
+/// Apply pending migrations in place. Returns `(from_version, changes)`,
+/// with no changes when the file is current (or newer than this build).
+pub fn migrate(file: &mut Value) -> (u32, Vec<String>) {
+    let from = file_version(file);
+    let mut version = from;
+    let mut changes = Vec::new();
+
+    for step in MIGRATIONS {
+        if step.from == version && version < CURRENT_WORKSPACE_VERSION {
+            (step.apply)(file);
+            changes.push(step.description.to_string());
+            version += 1;
+        }
+    }
+
+    if version != from {
+        if let Some(obj) = file.as_object_mut() {
+            obj.insert(VERSION_KEY.to_string(), Value::from(version));
+        }
+    }
+
+    (from, changes)
+}
+
+/// Backup path for a workspace file migrated from `version`.
+pub fn backup_path(workspace_file: &Path, version: u32) -> std::path::PathBuf {
+    let name = workspace_file
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+    workspace_file.with_file_name(format!("{name}.v{version}.bak"))
+}
+
+/// App handle used to announce migrations that happen during config reads.
+static APP_HANDLE: OnceLock<AppHandle> = OnceLock::new();
+
+/// Register the app handle so migrations can emit `workspace:migrated`.
+pub fn init(app: AppHandle) {
+    let _ = APP_HANDLE.set(app);
+}
+
+/// Emit `workspace:migrated` to all windows.
+pub fn notify_migrated(report: &MigrationReport) {
+    #[cfg(debug_assertions)]
+    eprintln!(
+        "[workspace] Migrated {} from v{} to v{}",
+        report.root_path, report.from_version, report.to_version
+    );
+
+    if let Some(app) = APP_HANDLE.get() {
+        let _ = app.emit("workspace:migrated", report.clone());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_migrations_are_contiguous() {
+        for (i, step) in MIGRATIONS.iter().enumerate() {
+            assert_eq!(step.from, LEGACY_VERSION + i as u32);
+        }
+        assert_eq!(
+            LEGACY_VERSION + MIGRATIONS.len() as u32,
+            CURRENT_WORKSPACE_VERSION
+        );
+    }
+
+    #[test]
+    fn test_migrate_unversioned_file() {
+        let mut file = json!({
+            "folders": [{ "path": "docs\\notes/" }, { "path": "./" }],
+            "settings": { "vmark.excludeFolders": [".git", "node_modules", ".git"] }
+        });
+
+        let (from, changes) = migrate(&mut file);
+
+        assert_eq!(from, 1);
+        assert_eq!(changes.len(), 1);
+        assert_eq!(file[VERSION_KEY], CURRENT_WORKSPACE_VERSION);
+        assert_eq!(file["folders"][0]["path"], "docs/notes");
+        assert_eq!(file["folders"][1]["path"], ".");
+        assert_eq!(
+            file["settings"]["vmark.excludeFolders"],
+            json!([".git", "node_modules"])
+        );
+    }
+
+    #[test]
+    fn test_migrate_legacy_file() {
+        let mut file = json!({
+            "version": 1,
+            "excludeFolders": ["dist", "dist"],
+            "lastOpenTabs": ["/ws/a.md"],
+            "ai": null
+        });
+        mark_legacy(&mut file);
+
+        let (from, changes) = migrate(&mut file);
+
+        assert_eq!(from, LEGACY_VERSION);
+        assert_eq!(changes.len(), 2);
+        assert_eq!(
+            file,
+            json!({
+                "folders": [{ "path": "." }],
+                "settings": {
+                    "vmark.excludeFolders": ["dist"],
+                    "vmark.lastOpenTabs": ["/ws/a.md"]
+                },
+                "vmark.version": CURRENT_WORKSPACE_VERSION
+            })
+        );
+    }
+
+    #[test]
+    fn test_current_and_newer_files_untouched() {
+        let mut current = json!({ "vmark.version": CURRENT_WORKSPACE_VERSION });
+        assert!(migrate(&mut current).1.is_empty());
+
+        let mut newer = json!({ "vmark.version": CURRENT_WORKSPACE_VERSION + 1 });
+        assert!(migrate(&mut newer).1.is_empty());
+        assert_eq!(newer[VERSION_KEY], CURRENT_WORKSPACE_VERSION + 1);
+    }
+
+    #[test]
+    fn test_backup_path() {
+        assert_eq!(
+            backup_path(Path::new("/ws/.vmark/vmark.code-workspace"), 1),
+            Path::new("/ws/.vmark/vmark.code-workspace.v1.bak")
+        );
+    }
+}
@@ -0,0 +1,218 @@
+//! Why a document can't be written, and what the user can do about it.
+//!
+//! `read_text_smart` reports whether a file is writable when it is opened.
+//! A failed save returns a `SaveError` naming the cause (a read-only file
+//! or volume, missing permissions, a cloud file that isn't downloaded)
+//! together with the remedies the frontend can offer.
+
+use serde::Serialize;
+use std::fs::{self, OpenOptions};
+use std::io;
+use std::path::Path;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SaveErrorKind {
+    /// The file is marked read-only
+    ReadOnly,
+    /// The user may not write the file or its folder
+    PermissionDenied,
+    /// The volume is mounted read-only
+    ReadOnlyVolume,
+    /// A cloud file (iCloud Drive, OneDrive) that isn't available locally
+    CloudPlaceholder,
+    /// Another window has the file open for editing
+    OwnedByWindow,
+    Other,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Remedy {
+    SaveAs,
+    /// Clear the read-only flag with `make_writable`, then save again
+    MakeWritable,
+    /// Save again, e.g. once the cloud file has downloaded
+    Retry,
+    RevealInFileManager,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SaveError {
+    pub kind: SaveErrorKind,
+    pub message: String,
+    pub path: Option<String>,
+    /// What the frontend can offer, most useful first
+    pub remedies: Vec<Remedy>,
+}
+
+impl SaveError {
+    /// Error for an I/O failure while saving `path`.
+    pub fn io(path: &Path, context: &str, error: &io::Error) -> Self {
+        let kind = classify(path, error);
+        SaveError {
+            kind,
+            message: format!("{context}: {error}"),
+            path: Some(path.to_string_lossy().to_string()),
+            remedies: remedies(kind),
+        }
+    }
+
+    /// Error for saving over a file marked read-only.
+    pub fn read_only(path: &Path) -> Self {
+        SaveError {
+            kind: SaveErrorKind::ReadOnly,
+            message: format!("{} is read-only", path.display()),
+            path: Some(path.to_string_lossy().to_string()),
+            remedies: remedies(SaveErrorKind::ReadOnly),
+        }
+    }
+
+    /// Error for saving a file another window has open for editing.
+    pub fn owned_by_window(path: &Path, label: &str) -> Self {
+        SaveError {
+            kind: SaveErrorKind::OwnedByWindow,
+            message: format!("{} is open for editing in window {label}", path.display()),
+            path: Some(path.to_string_lossy().to_string()),
+            remedies: remedies(SaveErrorKind::OwnedByWindow),
+        }
+    }
+}
+
+impl From<String> for SaveError {
+    fn from(message: String) -> Self {
+        SaveError {
+            kind: SaveErrorKind::Other,
+            message,
+            path: None,
+            remedies: Vec::new(),
+        }
+    }
+}
+
+impl From<SaveError> for String {
+    fn from(error: SaveError) -> Self {
+        error.message
+    }
+}
+
+fn remedies(kind: SaveErrorKind) -> Vec<Remedy> {
+    match kind {
+        SaveErrorKind::ReadOnly => vec![
+            Remedy::MakeWritable,
+            Remedy::SaveAs,
+            Remedy::RevealInFileManager,
+        ],
+        SaveErrorKind::PermissionDenied | SaveErrorKind::ReadOnlyVolume => {
+            vec![Remedy::SaveAs, Remedy::RevealInFileManager]
+        }
+        SaveErrorKind::CloudPlaceholder => {
+            vec![Remedy::Retry, Remedy::SaveAs, Remedy::RevealInFileManager]
+        }
+        SaveErrorKind::OwnedByWindow => vec![Remedy::SaveAs],
+        SaveErrorKind::Other => Vec::new(),
+    }
+}
+
+/// iCloud Drive replaces files that aren't downloaded with `.<name>.icloud`.
+fn is_icloud_placeholder(path: &Path) -> bool {
+    let Some(name) = path.file_name() else {
+        return false;
+    };
+    path.with_file_name(format!(".{}.icloud", name.to_string_lossy()))
+        .exists()
+}
+
+fn is_cloud_error(error: &io::Error) -> bool {
+    // ERROR_CLOUD_FILE_* and the other cloud filter codes
+    cfg!(windows) && matches!(error.raw_os_error(), Some(358..=398 | 426))
+}
+
+fn is_read_only_volume(error: &io::Error) -> bool {
+    // EROFS on Linux and macOS, ERROR_WRITE_PROTECT on Windows
+    let code = if cfg!(windows) { 19 } else { 30 };
+    error.raw_os_error() == Some(code)
+}
+
+fn classify(path: &Path, error: &io::Error) -> SaveErrorKind {
+    if is_cloud_error(error) || is_icloud_placeholder(path) {
+        SaveErrorKind::CloudPlaceholder
+    } else if is_read_only_volume(error) {
+        SaveErrorKind::ReadOnlyVolume
+    } else if error.kind() == io::ErrorKind::PermissionDenied {
+        let read_only = fs::metadata(path).is_ok_and(|m| m.permissions().readonly());
+        if read_only {
+            SaveErrorKind::ReadOnly
+        } else {
+            SaveErrorKind::PermissionDenied
+        }
+    } else {
+        SaveErrorKind::Other
+    }
+}
+
+/// Whether a file can be created in `dir`. Permission bits don't tell
+/// (ACLs, read-only mounts, sandboxing), so this creates and removes a
+/// probe file.
+fn can_create_in(dir: &Path) -> bool {
+    let probe = dir.join(format!(".vmark-write-probe-{}", std::process::id()));
+    match OpenOptions::new().write(true).create_new(true).open(&probe) {
+        Ok(_) => {
+            let _ = fs::remove_file(&probe);
+            true
+        }
+        Err(_) => false,
+    }
+}
+
+/// Whether `path` can be saved in place: saving writes a temp file next
+/// to it, so its folder must be writable too.
+pub fn is_writable(path: &Path) -> bool {
+    let dir_writable = path.parent().is_some_and(can_create_in);
+    dir_writable && OpenOptions::new().append(true).open(path).is_ok()
+}
+
+/// Clear the read-only flag of a file (the `makeWritable` remedy).
+#[tauri::command]
+pub fn make_writable(path: String) -> Result<(), String> {
+    let mut permissions = fs::metadata(&path)
+        .map_err(|e| format!("Failed to read metadata: {e}"))?
+        .permissions();
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        permissions.set_mode(permissions.mode() | 0o200);
+    }
+    #[cfg(not(unix))]
+    permissions.set_readonly(false);
+    fs::set_permissions(&path, permissions)
+        .map_err(|e| format!("Failed to change permissions: {e}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_classify() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("a.md");
+        fs::write(&path, "text").unwrap();
+        let denied = io::Error::from(io::ErrorKind::PermissionDenied);
+
+        assert_eq!(classify(&path, &denied), SaveErrorKind::PermissionDenied);
+        let mut permissions = fs::metadata(&path).unwrap().permissions();
+        permissions.set_readonly(true);
+        fs::set_permissions(&path, permissions).unwrap();
+        assert_eq!(classify(&path, &denied), SaveErrorKind::ReadOnly);
+
+        make_writable(path.to_string_lossy().to_string()).unwrap();
+        assert!(is_writable(&path));
+
+        fs::write(dir.path().join(".b.md.icloud"), "").unwrap();
+        let error = SaveError::io(&dir.path().join("b.md"), "Failed to save", &denied);
+        assert_eq!(error.kind, SaveErrorKind::CloudPlaceholder);
+        assert_eq!(error.remedies[0], Remedy::Retry);
+    }
+}
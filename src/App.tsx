@@ -58,6 +58,7 @@ import { useMenuEvents } from "@/hooks/useMenuEvents";
 import { useViewMenuEvents } from "@/hooks/useViewMenuEvents";
 import { useRecentFilesMenuEvents } from "@/hooks/useRecentFilesMenuEvents";
 import { useExportMenuEvents } from "@/hooks/useExportMenuEvents";
+import { useDocumentToolsMenuEvents } from "@/hooks/useDocumentToolsMenuEvents";
 import { useWorkspaceMenuEvents } from "@/hooks/useWorkspaceMenuEvents";
 import { useWorkspaceBootstrap } from "@/hooks/useWorkspaceBootstrap";
 import { useFileOperations } from "@/hooks/useFileOperations";
@@ -167,6 +168,7 @@ function MainLayout() {
   useViewMenuEvents();
   useRecentFilesMenuEvents();
   useExportMenuEvents();
+  useDocumentToolsMenuEvents();
   useWorkspaceMenuEvents();
   useFileOperations();
   useSearchCommands();
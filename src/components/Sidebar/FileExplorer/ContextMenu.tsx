@@ -8,6 +8,7 @@ import {
   Copy,
   FolderOpen,
   FolderInput,
+  SquareTerminal,
 } from "lucide-react";
 import { isImeKeyEvent } from "@/utils/imeGuard";
 import { getRevealInFileManagerLabel } from "@/utils/pathUtils";
@@ -49,12 +50,14 @@ function buildFolderMenuItems(revealLabel: string): MenuItem[] {
     { id: "delete", label: "Delete", icon: <Trash2 size={14} />, separator: true },
     { id: "copyPath", label: "Copy Path", icon: <Copy size={14} /> },
     { id: "revealInFinder", label: revealLabel, icon: <FolderOpen size={14} /> },
+    { id: "openInTerminal", label: "Open in Terminal", icon: <SquareTerminal size={14} /> },
   ];
 }
 
 const EMPTY_MENU_ITEMS: MenuItem[] = [
   { id: "newFile", label: "New File", icon: <FilePlus size={14} /> },
-  { id: "newFolder", label: "New Folder", icon: <FolderPlus size={14} /> },
+  { id: "newFolder", label: "New Folder", icon: <FolderPlus size={14} />, separator: true },
+  { id: "openInTerminal", label: "Open in Terminal", icon: <SquareTerminal size={14} /> },
 ];
 
 function getMenuItems(type: ContextMenuType, revealLabel: string): MenuItem[] {
@@ -73,6 +73,7 @@ export const FileExplorer = forwardRef<FileExplorerHandle, FileExplorerProps>(
     duplicateFile,
     copyPath,
     revealInFinder,
+    openInTerminal,
   } = useExplorerOperations();
 
   // Close context menu
@@ -176,6 +177,15 @@ export const FileExplorer = forwardRef<FileExplorerHandle, FileExplorerProps>(
           }
           break;
 
+        case "openInTerminal": {
+          // Empty area opens the workspace root
+          const folder = targetPath ?? rootPath;
+          if (folder) {
+            await openInTerminal(folder);
+          }
+          break;
+        }
+
         case "newFile":
           await handleNewFile(targetPath);
           break;
@@ -186,7 +196,7 @@ export const FileExplorer = forwardRef<FileExplorerHandle, FileExplorerProps>(
       }
     },
     // eslint-disable-next-line react-hooks/exhaustive-deps -- handleNewFile/handleNewFolder use getState() pattern
-    [contextMenu, openFile, duplicateFile, moveItem, deleteItem, copyPath, revealInFinder]
+    [contextMenu, rootPath, openFile, duplicateFile, moveItem, deleteItem, copyPath, revealInFinder, openInTerminal]
   );
 
   // Handle file activation (double-click or Enter)
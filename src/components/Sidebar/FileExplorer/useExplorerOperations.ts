@@ -10,6 +10,7 @@ import {
 import { ask } from "@tauri-apps/plugin-dialog";
 import { join, basename } from "@tauri-apps/api/path";
 import { emit } from "@tauri-apps/api/event";
+import { invoke } from "@tauri-apps/api/core";
 import { writeText } from "@tauri-apps/plugin-clipboard-manager";
 import { revealItemInDir } from "@tauri-apps/plugin-opener";
 import { toast } from "sonner";
@@ -286,6 +287,15 @@ export function useExplorerOperations() {
     }
   }, []);
 
+  const openInTerminal = useCallback(async (path: string): Promise<void> => {
+    try {
+      await invoke("open_in_terminal", { path });
+    } catch (error) {
+      console.error("[Explorer] Failed to open terminal:", error);
+      toast.error("Failed to open terminal");
+    }
+  }, []);
+
   return {
     createFile,
     createFolder,
@@ -296,5 +306,6 @@ export function useExplorerOperations() {
     duplicateFile,
     copyPath,
     revealInFinder,
+    openInTerminal,
   };
 }
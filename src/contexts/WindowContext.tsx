@@ -81,6 +81,7 @@ export function WindowProvider({ children }: WindowProviderProps) {
             const filePath = urlParams.get("file");
             const workspaceRootParam = urlParams.get("workspaceRoot");
             const filesParam = urlParams.get("files");
+            const activeFileParam = urlParams.get("activeFile");
             let filePaths: string[] | null = null;
             if (filesParam) {
               try {
@@ -141,6 +142,13 @@ export function WindowProvider({ children }: WindowProviderProps) {
                   toast.error(`Failed to open ${filename}`);
                 }
               }
+              // Tabs open in order, leaving the last one active
+              const activeTab = activeFileParam
+                ? useTabStore.getState().findTabByPath(label, activeFileParam)
+                : null;
+              if (activeTab) {
+                useTabStore.getState().setActiveTab(label, activeTab.id);
+              }
             } else {
               // Create the initial tab
               const tabId = useTabStore.getState().createTab(label, filePath);
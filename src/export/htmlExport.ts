@@ -527,3 +527,44 @@ export async function copyHtmlToClipboard(
     await writeText(html);
   }
 }
+
+export interface EmbeddedExport {
+  /** Rendered content wrapped in the export surface, as XHTML */
+  body: string;
+  /** Theme and content styles for the body */
+  css: string;
+  /** Number of local resources that could not be embedded */
+  missingCount: number;
+}
+
+/**
+ * Prepare rendered HTML for formats that carry their own copy of the
+ * document (PDF, DOCX, EPUB): local images are embedded as data URIs and the
+ * markup is serialized as well-formed XHTML.
+ *
+ * @param html - The rendered HTML content
+ * @param sourceFilePath - Source file path (for resource resolution)
+ */
+export async function prepareEmbeddedExport(
+  html: string,
+  sourceFilePath?: string | null
+): Promise<EmbeddedExport> {
+  const baseDir = await getDocumentBaseDir(sourceFilePath ?? null);
+  const { html: processedHtml, report } = await resolveResources(html, {
+    baseDir,
+    mode: "single",
+  });
+
+  const parsed = new DOMParser().parseFromString(
+    `<div class="export-surface"><div class="export-surface-editor">${processedHtml}</div></div>`,
+    "text/html"
+  );
+  const wrapper = parsed.body.firstElementChild;
+  const body = wrapper ? new XMLSerializer().serializeToString(wrapper) : "";
+
+  return {
+    body,
+    css: `${captureThemeCSS()}\n\n${getEditorContentCSS()}`,
+    missingCount: report.missing.length,
+  };
+}
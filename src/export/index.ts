@@ -54,14 +54,19 @@ export {
 export type { FontConfig, FontEmbedResult } from "./fontEmbedder";
 
 // HTML Export
-export { exportHtml, copyHtmlToClipboard } from "./htmlExport";
-export type { HtmlExportOptions, HtmlExportResult } from "./htmlExport";
+export { exportHtml, copyHtmlToClipboard, prepareEmbeddedExport } from "./htmlExport";
+export type { HtmlExportOptions, HtmlExportResult, EmbeddedExport } from "./htmlExport";
 
 // Export Operations
 export {
   exportToHtml,
+  exportToFile,
   exportToPdf,
   copyAsHtml,
   getRenderedHtml,
 } from "./useExportOperations";
-export type { ExportToHtmlOptions } from "./useExportOperations";
+export type {
+  ExportToHtmlOptions,
+  ExportToFileOptions,
+  ExportFileFormat,
+} from "./useExportOperations";
@@ -3,9 +3,11 @@
  *
  * Print: Uses Typora-style direct printing (window.print() with @media print CSS).
  * HTML Export: Uses ExportSurface for visual-parity rendering.
+ * PDF/DOCX/EPUB Export: Same rendering, with resources embedded in the file.
  */
 
 import { save } from "@tauri-apps/plugin-dialog";
+import { writeFile } from "@tauri-apps/plugin-fs";
 import { writeText } from "@tauri-apps/plugin-clipboard-manager";
 import { invoke } from "@tauri-apps/api/core";
 import { toast } from "sonner";
@@ -13,7 +15,7 @@ import { createRoot } from "react-dom/client";
 import React from "react";
 
 import { ExportSurface, type ExportSurfaceRef } from "./ExportSurface";
-import { exportHtml } from "./htmlExport";
+import { exportHtml, prepareEmbeddedExport } from "./htmlExport";
 import { waitForAssets } from "./waitForAssets";
 import { captureThemeCSS } from "./themeSnapshot";
 import { useSettingsStore } from "@/stores/settingsStore";
@@ -208,6 +210,115 @@ export async function exportToHtml(
   }
 }
 
+export interface ExportToFileOptions {
+  /** Markdown content */
+  markdown: string;
+  /** Default filename without extension */
+  defaultName?: string;
+  /** Default directory */
+  defaultDirectory?: string;
+  /** Source file path for resource resolution */
+  sourceFilePath?: string | null;
+}
+
+/** Formats written by exportToFile */
+const FILE_FORMATS = {
+  pdf: { label: "PDF", extension: "pdf" },
+  docx: { label: "Word", extension: "docx" },
+  epub: { label: "EPUB", extension: "epub" },
+} as const;
+
+export type ExportFileFormat = keyof typeof FILE_FORMATS;
+
+/**
+ * Lay out the export markup on A4 pages and return the PDF bytes.
+ */
+async function renderPdf(body: string, css: string, title: string): Promise<Uint8Array> {
+  const { default: html2pdf } = await import("html2pdf.js");
+
+  // A4 width at 96 dpi so the layout matches the page
+  const container = document.createElement("div");
+  container.style.cssText = "position: absolute; left: -9999px; top: -9999px; width: 794px;";
+  container.innerHTML = `<style>${css}</style>${body}`;
+  document.body.appendChild(container);
+
+  try {
+    const buffer = await html2pdf()
+      .set({
+        margin: 12,
+        filename: `${title}.pdf`,
+        image: { type: "jpeg", quality: 0.95 },
+        html2canvas: { scale: 2, useCORS: true, logging: false },
+        jsPDF: { unit: "mm", format: "a4", orientation: "portrait" },
+      })
+      .from(container)
+      .outputPdf("arraybuffer");
+    return new Uint8Array(buffer);
+  } finally {
+    document.body.removeChild(container);
+  }
+}
+
+/**
+ * Export markdown to a self-contained PDF, Word or EPUB file.
+ * PDF is laid out in the webview; DOCX and EPUB are packaged by the backend.
+ */
+export async function exportToFile(
+  format: ExportFileFormat,
+  options: ExportToFileOptions
+): Promise<boolean> {
+  const { markdown, defaultName = "document", defaultDirectory, sourceFilePath } = options;
+  const { label, extension } = FILE_FORMATS[format];
+
+  // Check for empty content
+  if (!markdown.trim()) {
+    toast.error("No content to export!");
+    return false;
+  }
+
+  try {
+    // Get save path
+    const filename = `${defaultName}.${extension}`;
+    const defaultPath = defaultDirectory ? joinPath(defaultDirectory, filename) : filename;
+    const path = await save({
+      defaultPath,
+      filters: [{ name: label, extensions: [extension] }],
+    });
+
+    if (!path) return false;
+
+    const html = await renderMarkdownToHtml(markdown, true);
+    const { body, css, missingCount } = await prepareEmbeddedExport(html, sourceFilePath);
+
+    if (format === "pdf") {
+      await writeFile(path, await renderPdf(body, css, defaultName));
+    } else {
+      await invoke("export_document", {
+        body,
+        css,
+        title: defaultName,
+        language: document.documentElement.lang || null,
+        format,
+        path,
+      });
+    }
+
+    if (missingCount > 0) {
+      toast.warning(
+        missingCount === 1
+          ? "1 resource could not be included"
+          : `${missingCount} resources could not be included`
+      );
+    }
+
+    return true;
+  } catch (error) {
+    console.error(`[Export] Failed to export ${label}:`, error);
+    await showError(FileErrors.exportFailed(label));
+    return false;
+  }
+}
+
 /**
  * Print document via native print dialog.
  * Uses Typora-style direct printing - no re-rendering needed.
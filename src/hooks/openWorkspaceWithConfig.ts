@@ -8,6 +8,11 @@ import { useWorkspaceStore, type WorkspaceConfig } from "@/stores/workspaceStore
 export async function openWorkspaceWithConfig(
   rootPath: string
 ): Promise<WorkspaceConfig | null> {
+  // Bring an outdated workspace file up to date on disk; reads work either way
+  await invoke("migrate_workspace_config", { rootPath }).catch((error) => {
+    console.warn("[Workspace] Failed to migrate config:", error);
+  });
+
   try {
     const config = await invoke<WorkspaceConfig | null>("read_workspace_config", {
       rootPath,
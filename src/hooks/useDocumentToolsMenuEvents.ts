@@ -0,0 +1,121 @@
+/**
+ * Hook for menu events that change the active document through backend
+ * commands.
+ *
+ * Handles menu:format-markdown and menu:insert-table-from-csv.
+ *
+ * @module hooks/useDocumentToolsMenuEvents
+ */
+import { useEffect, useRef } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import { type UnlistenFn } from "@tauri-apps/api/event";
+import { getCurrentWebviewWindow } from "@tauri-apps/api/webviewWindow";
+import { open } from "@tauri-apps/plugin-dialog";
+import { toast } from "sonner";
+import { createMarkdownPasteTransaction } from "@/plugins/markdownPaste/tiptap";
+import { useActiveEditorStore } from "@/stores/activeEditorStore";
+import { useDocumentStore } from "@/stores/documentStore";
+import { useEditorStore } from "@/stores/editorStore";
+import { flushActiveWysiwygNow } from "@/utils/wysiwygFlush";
+import { withReentryGuard } from "@/utils/reentryGuard";
+import { getActiveDocument, getActiveTabId } from "@/utils/activeDocument";
+
+/**
+ * Insert markdown at the cursor of the active editor, on its own lines.
+ * Returns false if no editor is available.
+ */
+function insertMarkdownAtCursor(markdown: string): boolean {
+  const { activeWysiwygEditor, activeSourceView } = useActiveEditorStore.getState();
+  if (useEditorStore.getState().sourceMode) {
+    if (!activeSourceView) return false;
+    const { state } = activeSourceView;
+    const line = state.doc.lineAt(state.selection.main.from);
+    const lead = line.text.trim() ? "\n\n" : "";
+    activeSourceView.dispatch(state.replaceSelection(`${lead}${markdown}`));
+    activeSourceView.focus();
+    return true;
+  }
+  const view = activeWysiwygEditor?.view;
+  if (!view) return false;
+  const tr = createMarkdownPasteTransaction(view.state, markdown);
+  if (!tr) return false;
+  view.dispatch(tr);
+  view.focus();
+  return true;
+}
+
+export function useDocumentToolsMenuEvents(): void {
+  const unlistenRefs = useRef<UnlistenFn[]>([]);
+
+  useEffect(() => {
+    let cancelled = false;
+
+    const setupListeners = async () => {
+      // Clean up any existing listeners first
+      unlistenRefs.current.forEach((fn) => fn());
+      unlistenRefs.current = [];
+
+      if (cancelled) return;
+
+      const currentWindow = getCurrentWebviewWindow();
+      const windowLabel = currentWindow.label;
+
+      const unlistenFormatMarkdown = await currentWindow.listen<string>("menu:format-markdown", async (event) => {
+        if (event.payload !== windowLabel) return;
+        flushActiveWysiwygNow();
+
+        await withReentryGuard(windowLabel, "format-markdown", async () => {
+          const tabId = getActiveTabId(windowLabel);
+          const doc = getActiveDocument(windowLabel);
+          if (!tabId || !doc) return;
+          try {
+            const formatted = await invoke<string>("format_markdown", { content: doc.content });
+            // Drop the result if the document was edited meanwhile
+            const current = useDocumentStore.getState().getDocument(tabId);
+            if (formatted !== doc.content && current?.content === doc.content) {
+              useDocumentStore.getState().setContent(tabId, formatted);
+            }
+          } catch (error) {
+            console.error("[Menu] Failed to format markdown:", error);
+            toast.error("Failed to format document");
+          }
+        });
+      });
+      if (cancelled) { unlistenFormatMarkdown(); return; }
+      unlistenRefs.current.push(unlistenFormatMarkdown);
+
+      const unlistenInsertTable = await currentWindow.listen<string>("menu:insert-table-from-csv", async (event) => {
+        if (event.payload !== windowLabel) return;
+
+        await withReentryGuard(windowLabel, "insert-table", async () => {
+          const path = await open({
+            filters: [
+              { name: "CSV / TSV", extensions: ["csv", "tsv", "tab", "txt"] },
+            ],
+          });
+          if (typeof path !== "string") return;
+          try {
+            const table = await invoke<string>("import_table", { path });
+            if (!insertMarkdownAtCursor(table)) {
+              toast.error("Open a document to insert the table into");
+            }
+          } catch (error) {
+            console.error("[Menu] Failed to import table:", error);
+            toast.error(`Failed to import table: ${error}`);
+          }
+        });
+      });
+      if (cancelled) { unlistenInsertTable(); return; }
+      unlistenRefs.current.push(unlistenInsertTable);
+    };
+
+    setupListeners();
+
+    return () => {
+      cancelled = true;
+      const fns = unlistenRefs.current;
+      unlistenRefs.current = [];
+      fns.forEach((fn) => fn());
+    };
+  }, []);
+}
@@ -1,7 +1,8 @@
 /**
  * Hook for export menu event handling.
  *
- * Handles menu:export-html, menu:save-pdf, menu:export-pdf, and menu:copy-html events.
+ * Handles menu:export-html, menu:save-pdf, menu:export-docx, menu:export-epub,
+ * menu:export-pdf, and menu:copy-html events.
  * Extracted from useMenuEvents to keep file sizes under 300 lines.
  *
  * Uses ExportSurface for visual parity.
@@ -12,12 +13,19 @@ import { useEffect, useRef } from "react";
 import { type UnlistenFn } from "@tauri-apps/api/event";
 import { getCurrentWebviewWindow } from "@tauri-apps/api/webviewWindow";
 import { toast } from "sonner";
-import { exportToHtml, exportToPdf, copyAsHtml } from "@/export";
+import { exportToHtml, exportToFile, exportToPdf, copyAsHtml, type ExportFileFormat } from "@/export";
 import { getFileNameWithoutExtension, getDirectory } from "@/utils/pathUtils";
 import { flushActiveWysiwygNow } from "@/utils/wysiwygFlush";
 import { withReentryGuard } from "@/utils/reentryGuard";
 import { getActiveDocument } from "@/utils/activeDocument";
 
+/** Menu events that export to a self-contained file */
+const FILE_EXPORT_EVENTS: [string, ExportFileFormat, string][] = [
+  ["menu:save-pdf", "pdf", "PDF"],
+  ["menu:export-docx", "docx", "Word"],
+  ["menu:export-epub", "epub", "EPUB"],
+];
+
 export function useExportMenuEvents(): void {
   const unlistenRefs = useRef<UnlistenFn[]>([]);
 
@@ -67,6 +75,35 @@ export function useExportMenuEvents(): void {
       if (cancelled) { unlistenExportHtml(); return; }
       unlistenRefs.current.push(unlistenExportHtml);
 
+      for (const [eventName, format, label] of FILE_EXPORT_EVENTS) {
+        const unlisten = await currentWindow.listen<string>(eventName, async (event) => {
+          if (event.payload !== windowLabel) return;
+          flushActiveWysiwygNow();
+
+          await withReentryGuard(windowLabel, "export", async () => {
+            const doc = getActiveDocument(windowLabel);
+            if (!doc) return;
+            try {
+              const success = await exportToFile(format, {
+                markdown: doc.content,
+                defaultName: doc.filePath
+                  ? getFileNameWithoutExtension(doc.filePath) || "document"
+                  : "document",
+                defaultDirectory: doc.filePath ? getDirectory(doc.filePath) : undefined,
+                sourceFilePath: doc.filePath,
+              });
+              if (success) {
+                toast.success(`Exported to ${label}`);
+              }
+            } catch (error) {
+              console.error(`[Menu] Failed to export ${label}:`, error);
+            }
+          });
+        });
+        if (cancelled) { unlisten(); return; }
+        unlistenRefs.current.push(unlisten);
+      }
+
       // Print/PDF via print dialog
       const unlistenExportPdf = await currentWindow.listen<string>("menu:export-pdf", async (event) => {
         if (event.payload !== windowLabel) return;
@@ -447,7 +447,7 @@ export function useFileOperations() {
               const dirtyTabIds = useDocumentStore.getState().getAllDirtyDocuments();
               if (dirtyTabIds.length === 0) {
                 // No dirty docs, quit immediately
-                await invoke("force_quit");
+                await invoke("save_all_result", { label: windowLabel, saved: true });
                 return;
               }
 
@@ -480,18 +480,25 @@ export function useFileOperations() {
               }
 
               if (contexts.length === 0) {
-                await invoke("force_quit");
+                await invoke("save_all_result", { label: windowLabel, saved: true });
                 return;
               }
 
               // Save all documents (will prompt for folder if multiple untitled)
+              // Rust starts the coordinated quit once everything is saved;
+              // if cancelled, the app stays open
               const result = await saveAllDocuments(contexts);
-              if (result.action === "saved-all") {
-                await invoke("force_quit");
-              }
-              // If cancelled, do nothing (stay in app)
+              await invoke("save_all_result", {
+                label: windowLabel,
+                saved: result.action === "saved-all",
+              });
             } catch (error) {
               console.error("[SaveAllQuit] Failed:", error);
+              await invoke("save_all_result", {
+                label: windowLabel,
+                saved: false,
+                error: String(error),
+              }).catch(() => {});
               toast.error("Failed to save documents");
             }
           });
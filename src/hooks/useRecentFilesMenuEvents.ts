@@ -16,7 +16,79 @@ import { detectLinebreaks } from "@/utils/linebreakDetection";
 import { openWorkspaceWithConfig } from "@/hooks/openWorkspaceWithConfig";
 
 /**
- * Handles recent files menu events: open-recent-file, clear-recent.
+ * Open a file picked from a menu in the given window, following the
+ * workspace boundary policy. Returns false if the file could not be read.
+ */
+async function openMenuFile(windowLabel: string, filePath: string): Promise<boolean> {
+  const { isWorkspaceMode, rootPath } = useWorkspaceStore.getState();
+  const existingTab = useTabStore.getState().findTabByPath(windowLabel, filePath);
+  const replaceableTab = getReplaceableTab(windowLabel);
+
+  const result = resolveOpenAction({
+    filePath,
+    workspaceRoot: rootPath,
+    isWorkspaceMode,
+    existingTabId: existingTab?.id ?? null,
+    replaceableTab,
+  });
+
+  switch (result.action) {
+    case "activate_tab":
+      useTabStore.getState().setActiveTab(windowLabel, result.tabId);
+      return true;
+
+    case "create_tab":
+      try {
+        const content = await readTextFile(filePath);
+        const tabId = useTabStore.getState().createTab(windowLabel, filePath);
+        useDocumentStore.getState().initDocument(tabId, content, filePath);
+        useDocumentStore.getState().setLineMetadata(tabId, detectLinebreaks(content));
+        useRecentFilesStore.getState().addFile(filePath);
+      } catch (error) {
+        console.error("[Menu] Failed to open file:", error);
+        return false;
+      }
+      return true;
+
+    case "replace_tab":
+      try {
+        const content = await readTextFile(filePath);
+        useTabStore.getState().updateTabPath(result.tabId, result.filePath);
+        useDocumentStore.getState().loadContent(
+          result.tabId,
+          content,
+          result.filePath,
+          detectLinebreaks(content)
+        );
+        await openWorkspaceWithConfig(result.workspaceRoot);
+        useRecentFilesStore.getState().addFile(filePath);
+      } catch (error) {
+        console.error("[Menu] Failed to replace tab with file:", error);
+        return false;
+      }
+      return true;
+
+    case "open_workspace_in_new_window":
+      try {
+        await invoke("open_workspace_in_new_window", {
+          workspaceRoot: result.workspaceRoot,
+          filePath: result.filePath,
+        });
+      } catch (error) {
+        console.error("[Menu] Failed to open workspace in new window:", error);
+        const filename = filePath.split("/").pop() ?? filePath;
+        toast.error(`Failed to open ${filename}`);
+      }
+      return true;
+
+    case "no_op":
+      return true;
+  }
+}
+
+/**
+ * Handles recent files menu events: open-recent-file, clear-recent, and
+ * open-favorite-file for the workspace's pinned files.
  */
 export function useRecentFilesMenuEvents(): void {
   const unlistenRefs = useRef<UnlistenFn[]>([]);
@@ -33,25 +105,10 @@ export function useRecentFilesMenuEvents(): void {
       const currentWindow = getCurrentWebviewWindow();
       const windowLabel = currentWindow.label;
 
-      // Clear Recent Files
-      const unlistenClearRecent = await currentWindow.listen<string>("menu:clear-recent", async (event) => {
+      // Clear Recent Files (already confirmed by the native dialog)
+      const unlistenClearRecent = await currentWindow.listen<string>("menu:clear-recent", (event) => {
         if (event.payload !== windowLabel) return;
-
-        const { files } = useRecentFilesStore.getState();
-        if (files.length === 0) return;
-
-        await withReentryGuard(windowLabel, "clear-recent", async () => {
-          const confirmed = await ask(
-            "Clear the list of recently opened files?",
-            {
-              title: "Clear Recent Files",
-              kind: "warning",
-            }
-          );
-          if (confirmed) {
-            useRecentFilesStore.getState().clearAll();
-          }
-        });
+        useRecentFilesStore.getState().clearAll();
       });
       if (cancelled) { unlistenClearRecent(); return; }
       unlistenRefs.current.push(unlistenClearRecent);
@@ -62,90 +119,34 @@ export function useRecentFilesMenuEvents(): void {
         const [filePath, targetLabel] = event.payload;
         if (targetLabel !== windowLabel) return;
 
-        // Find file in store by path (or create minimal file object)
-        const { files } = useRecentFilesStore.getState();
-        const file = files.find(f => f.path === filePath) ?? { path: filePath };
-        const { isWorkspaceMode, rootPath } = useWorkspaceStore.getState();
-        const existingTab = useTabStore.getState().findTabByPath(windowLabel, file.path);
-        const replaceableTab = getReplaceableTab(windowLabel);
-
-        const result = resolveOpenAction({
-          filePath: file.path,
-          workspaceRoot: rootPath,
-          isWorkspaceMode,
-          existingTabId: existingTab?.id ?? null,
-          replaceableTab,
-        });
-
         await withReentryGuard(windowLabel, "open-recent", async () => {
-          switch (result.action) {
-            case "activate_tab":
-              useTabStore.getState().setActiveTab(windowLabel, result.tabId);
-              break;
-
-            case "create_tab":
-              try {
-                const content = await readTextFile(file.path);
-                const tabId = useTabStore.getState().createTab(windowLabel, file.path);
-                useDocumentStore.getState().initDocument(tabId, content, file.path);
-                useDocumentStore.getState().setLineMetadata(tabId, detectLinebreaks(content));
-                useRecentFilesStore.getState().addFile(file.path);
-              } catch (error) {
-                console.error("[Menu] Failed to open recent file:", error);
-                const remove = await ask(
-                  "This file could not be opened. It may have been moved or deleted.\n\nRemove from recent files?",
-                  { title: "File Not Found", kind: "warning" }
-                );
-                if (remove) {
-                  useRecentFilesStore.getState().removeFile(file.path);
-                }
-              }
-              break;
-
-            case "replace_tab":
-              try {
-                const content = await readTextFile(file.path);
-                useTabStore.getState().updateTabPath(result.tabId, result.filePath);
-                useDocumentStore.getState().loadContent(
-                  result.tabId,
-                  content,
-                  result.filePath,
-                  detectLinebreaks(content)
-                );
-                await openWorkspaceWithConfig(result.workspaceRoot);
-                useRecentFilesStore.getState().addFile(file.path);
-              } catch (error) {
-                console.error("[Menu] Failed to replace tab with recent file:", error);
-                const remove = await ask(
-                  "This file could not be opened. It may have been moved or deleted.\n\nRemove from recent files?",
-                  { title: "File Not Found", kind: "warning" }
-                );
-                if (remove) {
-                  useRecentFilesStore.getState().removeFile(file.path);
-                }
-              }
-              break;
-
-            case "open_workspace_in_new_window":
-              try {
-                await invoke("open_workspace_in_new_window", {
-                  workspaceRoot: result.workspaceRoot,
-                  filePath: result.filePath,
-                });
-              } catch (error) {
-                console.error("[Menu] Failed to open workspace in new window:", error);
-                const filename = file.path.split("/").pop() ?? file.path;
-                toast.error(`Failed to open ${filename}`);
-              }
-              break;
-
-            case "no_op":
-              break;
+          if (await openMenuFile(windowLabel, filePath)) return;
+          const remove = await ask(
+            "This file could not be opened. It may have been moved or deleted.\n\nRemove from recent files?",
+            { title: "File Not Found", kind: "warning" }
+          );
+          if (remove) {
+            useRecentFilesStore.getState().removeFile(filePath);
           }
         });
       });
       if (cancelled) { unlistenOpenRecent(); return; }
       unlistenRefs.current.push(unlistenOpenRecent);
+
+      // Open Favorite (pinned file) - same payload as recent files
+      const unlistenOpenFavorite = await currentWindow.listen<[string, string]>("menu:open-favorite-file", async (event) => {
+        const [filePath, targetLabel] = event.payload;
+        if (targetLabel !== windowLabel) return;
+
+        await withReentryGuard(windowLabel, "open-favorite", async () => {
+          if (!(await openMenuFile(windowLabel, filePath))) {
+            const filename = filePath.split("/").pop() ?? filePath;
+            toast.error(`Failed to open ${filename}. It may have been moved or deleted.`);
+          }
+        });
+      });
+      if (cancelled) { unlistenOpenFavorite(); return; }
+      unlistenRefs.current.push(unlistenOpenFavorite);
     };
 
     setupListeners();
@@ -2,15 +2,36 @@
  * Hook to sync recent files to native menu on app startup.
  *
  * The persist middleware's onRehydrateStorage runs before Tauri APIs are ready,
- * so we need this hook to sync after the app is fully loaded.
+ * so we need this hook to sync after the app is fully loaded. The first sync
+ * also has the backend check for deleted files, which come back as
+ * `recent-files:pruned` and are dropped from the list.
  */
 
 import { useEffect } from "react";
+import { listen } from "@tauri-apps/api/event";
 import { useRecentFilesStore } from "@/stores/recentFilesStore";
 
 export function useRecentFilesSync() {
   useEffect(() => {
+    let cancelled = false;
+    let unlisten: (() => void) | undefined;
+
+    listen<string[]>("recent-files:pruned", (event) => {
+      const { files, removeFile } = useRecentFilesStore.getState();
+      for (const path of event.payload) {
+        if (files.some((f) => f.path === path)) removeFile(path);
+      }
+    }).then((fn) => {
+      if (cancelled) fn();
+      else unlisten = fn;
+    });
+
     // Sync recent files to native menu on mount
     useRecentFilesStore.getState().syncToNativeMenu();
+
+    return () => {
+      cancelled = true;
+      unlisten?.();
+    };
   }, []);
 }
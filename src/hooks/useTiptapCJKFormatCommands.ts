@@ -5,7 +5,7 @@ import type { Editor as TiptapEditor } from "@tiptap/core";
 import { useDocumentStore } from "@/stores/documentStore";
 import { useSettingsStore } from "@/stores/settingsStore";
 import { useTabStore } from "@/stores/tabStore";
-import { collapseNewlines, formatMarkdown, formatSelection, removeTrailingSpaces } from "@/lib/cjkFormatter";
+import { collapseNewlines, formatDocument, formatSelection, removeTrailingSpaces } from "@/lib/cjkFormatter";
 import { resolveHardBreakStyle } from "@/utils/linebreaks";
 import { FEATURE_FLAGS } from "@/stores/featureFlagsStore";
 
@@ -36,6 +36,23 @@ function shouldPreserveTwoSpaceBreaks(windowLabel: string): boolean {
   return resolveHardBreakStyle(doc?.hardBreakStyle ?? "unknown", hardBreakStyleOnSave) === "twoSpaces";
 }
 
+/** Format the whole active document in the backend. */
+function formatActiveDocument(windowLabel: string) {
+  const tabId = getActiveTabIdForWindow(windowLabel);
+  if (!tabId) return;
+  const config = useSettingsStore.getState().cjkFormatting;
+  const preserveTwoSpaceHardBreaks = shouldPreserveTwoSpaceBreaks(windowLabel);
+  const content = getActiveMarkdown(windowLabel);
+  formatDocument(content, config, { preserveTwoSpaceHardBreaks })
+    .then((formatted) => {
+      // Drop the result if the document was edited meanwhile
+      const current = useDocumentStore.getState().getDocument(tabId);
+      if (formatted === null || formatted === content || current?.content !== content) return;
+      useDocumentStore.getState().setContent(tabId, formatted);
+    })
+    .catch((error) => console.error("[CJK] Failed to format document:", error));
+}
+
 export function useTiptapCJKFormatCommands(editor: TiptapEditor | null) {
   const editorRef = useRef<TiptapEditor | null>(null);
   editorRef.current = editor;
@@ -79,11 +96,7 @@ export function useTiptapCJKFormatCommands(editor: TiptapEditor | null) {
           return;
         }
 
-        const content = getActiveMarkdown(windowLabel);
-        const formatted = formatMarkdown(content, config, { preserveTwoSpaceHardBreaks });
-        if (formatted !== content) {
-          setActiveMarkdown(windowLabel, formatted);
-        }
+        formatActiveDocument(windowLabel);
       });
       if (cancelled) {
         unlistenFormatCJK();
@@ -93,13 +106,7 @@ export function useTiptapCJKFormatCommands(editor: TiptapEditor | null) {
 
       const unlistenFormatFile = await currentWindow.listen<string>("menu:format-cjk-file", (event) => {
         if (event.payload !== windowLabel) return;
-        const config = useSettingsStore.getState().cjkFormatting;
-        const preserveTwoSpaceHardBreaks = shouldPreserveTwoSpaceBreaks(windowLabel);
-        const content = getActiveMarkdown(windowLabel);
-        const formatted = formatMarkdown(content, config, { preserveTwoSpaceHardBreaks });
-        if (formatted !== content) {
-          setActiveMarkdown(windowLabel, formatted);
-        }
+        formatActiveDocument(windowLabel);
       });
       if (cancelled) {
         unlistenFormatFile();
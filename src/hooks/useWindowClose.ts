@@ -165,6 +165,17 @@ export function useWindowClose() {
             return;
           }
 
+          // Tell Rust we are responsive; the quit timeout waits while we prompt
+          const tabs = useTabStore.getState().tabs[windowLabel] ?? [];
+          const promptPending = tabs.some(
+            (tab) => useDocumentStore.getState().getDocument(tab.id)?.isDirty
+          );
+          invoke("quit_ack", { promptPending }).catch((e) => {
+            if (import.meta.env.DEV) {
+              console.warn("[WindowClose] quit_ack failed:", e);
+            }
+          });
+
           const closed = await handleCloseRequest();
           if (!closed) {
             invoke("cancel_quit").catch((e) => {
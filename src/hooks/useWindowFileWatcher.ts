@@ -39,7 +39,9 @@ export function useWindowFileWatcher(): void {
       return;
     }
 
-    invoke("start_watching", { watchId: windowLabel, path: watchPath }).catch(
+    // Exclude rules come from the workspace even when watching a folder in it
+    const workspaceRoot = isWorkspaceMode ? rootPath : null;
+    invoke("start_watching", { watchId: windowLabel, path: watchPath, workspaceRoot }).catch(
       (err) => {
         console.warn("[Watcher] Failed to start watcher:", err);
       }
@@ -48,5 +50,5 @@ export function useWindowFileWatcher(): void {
     return () => {
       invoke("stop_watching", { watchId: windowLabel }).catch(() => {});
     };
-  }, [windowLabel, watchPath]);
+  }, [windowLabel, watchPath, isWorkspaceMode, rootPath]);
 }
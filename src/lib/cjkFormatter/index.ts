@@ -6,5 +6,6 @@
  */
 
 export { formatMarkdown, formatSelection, formatFile } from "./formatter";
+export { formatDocument, cancelDocumentFormat } from "./native";
 export { containsCJK, removeTrailingSpaces, collapseNewlines } from "./rules";
 export type { CJKFormattingSettings } from "@/stores/settingsStore";
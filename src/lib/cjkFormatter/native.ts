@@ -0,0 +1,82 @@
+/**
+ * Whole-document CJK formatting in the backend (`format_cjk_stream`).
+ *
+ * The backend formats in the background and sends the result in pieces,
+ * so long documents never block the editor. Starting a new run cancels
+ * the one still in progress.
+ */
+import { invoke } from "@tauri-apps/api/core";
+import { type UnlistenFn } from "@tauri-apps/api/event";
+import { getCurrentWebviewWindow } from "@tauri-apps/api/webviewWindow";
+import type { CJKFormattingSettings } from "@/stores/settingsStore";
+
+interface FormatChunk {
+  text: string;
+  progress: number;
+  done: boolean;
+}
+
+let cancelCurrent: (() => void) | null = null;
+
+/** Cancel the formatting run in progress, if any. */
+export function cancelDocumentFormat(): void {
+  cancelCurrent?.();
+}
+
+/**
+ * Format a markdown document with the CJK rules.
+ * Resolves to null if the run was cancelled.
+ */
+export async function formatDocument(
+  content: string,
+  config: CJKFormattingSettings,
+  options: { preserveTwoSpaceHardBreaks?: boolean } = {}
+): Promise<string | null> {
+  cancelDocumentFormat();
+
+  const id = crypto.randomUUID();
+  const parts: string[] = [];
+  let unlisten: UnlistenFn | null = null;
+  let settled = false;
+  let settle: (result: string | null) => void = () => {};
+  const result = new Promise<string | null>((resolve) => {
+    settle = resolve;
+  });
+
+  const finish = (formatted: string | null) => {
+    if (settled) return;
+    settled = true;
+    unlisten?.();
+    if (cancelCurrent === cancel) cancelCurrent = null;
+    settle(formatted);
+  };
+  const cancel = () => {
+    void invoke("cancel_cjk_format", { id });
+    finish(null);
+  };
+  cancelCurrent = cancel;
+
+  unlisten = await getCurrentWebviewWindow().listen<[string, FormatChunk]>("cjk-format:chunk", (event) => {
+    const [chunkId, chunk] = event.payload;
+    if (chunkId !== id) return;
+    parts.push(chunk.text);
+    if (chunk.done) finish(parts.join(""));
+  });
+  // Cancelled while the listener was being set up
+  if (settled) {
+    unlisten();
+    return result;
+  }
+
+  try {
+    await invoke("format_cjk_stream", {
+      id,
+      content,
+      options: { ...config, preserveTwoSpaceHardBreaks: options.preserveTwoSpaceHardBreaks ?? false },
+    });
+  } catch (error) {
+    finish(null);
+    throw error;
+  }
+  return result;
+}
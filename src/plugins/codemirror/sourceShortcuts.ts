@@ -25,7 +25,7 @@ import { getSourceMultiSelectionContext } from "@/plugins/toolbarActions/multiSe
 import { performSourceToolbarAction, setSourceHeadingLevel } from "@/plugins/toolbarActions/sourceAdapter";
 import { guardCodeMirrorKeyBinding } from "@/utils/imeGuard";
 import { copyAsHtml } from "@/export";
-import { formatMarkdown, formatSelection } from "@/lib/cjkFormatter";
+import { formatDocument, formatSelection } from "@/lib/cjkFormatter";
 import { resolveHardBreakStyle } from "@/utils/linebreaks";
 import { getWindowLabel } from "@/hooks/useWindowFocus";
 import { getHeadingInfo, setHeadingLevel, convertToHeading } from "@/plugins/sourceContextDetection/headingDetection";
@@ -198,14 +198,17 @@ function formatCJKFile(view: EditorView): boolean {
   const config = useSettingsStore.getState().cjkFormatting;
   const preserveTwoSpaceHardBreaks = shouldPreserveTwoSpaceBreaks();
   const content = view.state.doc.toString();
-  const formatted = formatMarkdown(content, config, { preserveTwoSpaceHardBreaks });
+  const active = getActiveDocument();
+  if (!active) return true;
 
-  if (formatted !== content) {
-    const active = getActiveDocument();
-    if (active) {
+  formatDocument(content, config, { preserveTwoSpaceHardBreaks })
+    .then((formatted) => {
+      // Drop the result if the document was edited meanwhile
+      const current = useDocumentStore.getState().getDocument(active.tabId);
+      if (formatted === null || formatted === content || current?.content !== content) return;
       useDocumentStore.getState().setContent(active.tabId, formatted);
-    }
-  }
+    })
+    .catch((error) => console.error("[CJK] Failed to format document:", error));
   return true;
 }
 
@@ -30,7 +30,7 @@ import { useImagePopupStore } from "@/stores/imagePopupStore";
 import { useSettingsStore } from "@/stores/settingsStore";
 import { useTabStore } from "@/stores/tabStore";
 import { getWindowLabel } from "@/hooks/useWindowFocus";
-import { collapseNewlines, formatMarkdown, formatSelection, removeTrailingSpaces } from "@/lib/cjkFormatter";
+import { collapseNewlines, formatDocument, formatSelection, removeTrailingSpaces } from "@/lib/cjkFormatter";
 import { normalizeLineEndings, resolveHardBreakStyle } from "@/utils/linebreaks";
 import {
   toUpperCase,
@@ -792,17 +792,20 @@ function handleFormatCJKFile(view: EditorView): boolean {
   const config = useSettingsStore.getState().cjkFormatting;
   const preserveTwoSpaceHardBreaks = shouldPreserveTwoSpaceBreaks();
   const content = view.state.doc.toString();
-  const formatted = formatMarkdown(content, config, { preserveTwoSpaceHardBreaks });
 
-  if (formatted !== content) {
-    // Preserve cursor position as best as possible
-    const cursorPos = view.state.selection.main.head;
-    const newCursorPos = Math.min(cursorPos, formatted.length);
-    view.dispatch({
-      changes: { from: 0, to: content.length, insert: formatted },
-      selection: { anchor: newCursorPos },
-    });
-  }
+  formatDocument(content, config, { preserveTwoSpaceHardBreaks })
+    .then((formatted) => {
+      // Drop the result if the document was edited meanwhile
+      if (formatted === null || formatted === content || view.state.doc.toString() !== content) return;
+      // Preserve cursor position as best as possible
+      const cursorPos = view.state.selection.main.head;
+      const newCursorPos = Math.min(cursorPos, formatted.length);
+      view.dispatch({
+        changes: { from: 0, to: content.length, insert: formatted },
+        selection: { anchor: newCursorPos },
+      });
+    })
+    .catch((error) => console.error("[CJK] Failed to format document:", error));
   return true;
 }
 
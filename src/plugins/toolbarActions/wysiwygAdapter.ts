@@ -16,7 +16,7 @@ import { useLinkPopupStore } from "@/stores/linkPopupStore";
 import { useSettingsStore } from "@/stores/settingsStore";
 import { useWikiLinkPopupStore } from "@/stores/wikiLinkPopupStore";
 import { useTabStore } from "@/stores/tabStore";
-import { collapseNewlines, formatMarkdown, formatSelection, removeTrailingSpaces } from "@/lib/cjkFormatter";
+import { collapseNewlines, formatDocument, formatSelection, removeTrailingSpaces } from "@/lib/cjkFormatter";
 import { normalizeLineEndings, resolveHardBreakStyle } from "@/utils/linebreaks";
 import { parseMarkdown, serializeMarkdown } from "@/utils/markdownPipeline";
 import { toUpperCase, toLowerCase, toTitleCase, toggleCase } from "@/utils/textTransformations";
@@ -941,12 +941,20 @@ function handleFormatCJK(context: WysiwygToolbarContext): boolean {
 }
 
 function handleFormatCJKFile(context: WysiwygToolbarContext): boolean {
+  const { editor } = context;
+  if (!editor) return false;
   const config = useSettingsStore.getState().cjkFormatting;
   const preserveTwoSpaceHardBreaks = shouldPreserveTwoSpaceBreaks();
+  const markdown = serializeMarkdown(editor.schema, editor.state.doc, getSerializeOptions());
 
-  return applyFullDocumentTransform(context, (markdown) =>
-    formatMarkdown(markdown, config, { preserveTwoSpaceHardBreaks })
-  );
+  formatDocument(markdown, config, { preserveTwoSpaceHardBreaks })
+    .then((formatted) => {
+      if (formatted === null || editor.isDestroyed) return;
+      // Drop the result if the document was edited meanwhile
+      applyFullDocumentTransform(context, (current) => (current === markdown ? formatted : current));
+    })
+    .catch((error) => console.error("[CJK] Failed to format document:", error));
+  return true;
 }
 
 function handleRemoveTrailingSpaces(context: WysiwygToolbarContext): boolean {
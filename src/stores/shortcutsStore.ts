@@ -131,6 +131,9 @@ export const DEFAULT_SHORTCUTS: ShortcutDefinition[] = [
   { id: "zoomActual", label: "Actual Size", category: "view", defaultKey: "Mod-0", menuId: "zoom-actual", scope: "global", description: "Reset font size to default" },
   { id: "zoomIn", label: "Zoom In", category: "view", defaultKey: "Mod-=", menuId: "zoom-in", scope: "global", description: "Increase font size" },
   { id: "zoomOut", label: "Zoom Out", category: "view", defaultKey: "Mod--", menuId: "zoom-out", scope: "global", description: "Decrease font size" },
+  { id: "pageZoomActual", label: "Actual Page Size", category: "view", defaultKey: "Alt-Mod-0", menuId: "page-zoom-actual", scope: "global", description: "Reset window zoom to 100%" },
+  { id: "pageZoomIn", label: "Zoom Page In", category: "view", defaultKey: "Mod-Shift-=", menuId: "page-zoom-in", scope: "global", description: "Scale the whole window up" },
+  { id: "pageZoomOut", label: "Zoom Page Out", category: "view", defaultKey: "Mod-Shift--", menuId: "page-zoom-out", scope: "global", description: "Scale the whole window down" },
 
   // === File ===
   { id: "newTab", label: "New Tab", category: "file", defaultKey: "Mod-t", description: "Create a new tab", scope: "global" },